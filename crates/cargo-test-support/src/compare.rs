@@ -189,7 +189,10 @@ fn substitute_macros(input: &str) -> String {
     let macros = [
         ("[RUNNING]", "     Running"),
         ("[COMPILING]", "   Compiling"),
+        ("[BUMPING]", "     Bumping"),
+        ("[COMPARING]", "   Comparing"),
         ("[CHECKING]", "    Checking"),
+        ("[CHECKED]", "     Checked"),
         ("[COMPLETED]", "   Completed"),
         ("[CREATED]", "     Created"),
         ("[CREDENTIAL]", "  Credential"),
@@ -214,6 +217,7 @@ fn substitute_macros(input: &str) -> String {
         ("[UPLOADING]", "   Uploading"),
         ("[UPLOADED]", "    Uploaded"),
         ("[VERIFYING]", "   Verifying"),
+        ("[VERIFIED]", "    Verified"),
         ("[ARCHIVING]", "   Archiving"),
         ("[INSTALLING]", "  Installing"),
         ("[REPLACING]", "   Replacing"),
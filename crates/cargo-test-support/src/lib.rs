@@ -1270,6 +1270,8 @@ pub trait TestEnv: Sized {
             // Don't read the system git config which is out of our control.
             .env("GIT_CONFIG_NOSYSTEM", "1")
             .env_remove("__CARGO_DEFAULT_LIB_METADATA")
+            .env_remove("ACTIONS_ID_TOKEN_REQUEST_TOKEN")
+            .env_remove("ACTIONS_ID_TOKEN_REQUEST_URL")
             .env_remove("ALL_PROXY")
             .env_remove("EMAIL")
             .env_remove("GIT_AUTHOR_EMAIL")
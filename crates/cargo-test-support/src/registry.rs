@@ -106,6 +106,12 @@ pub struct RegistryBuilder {
     delayed_index_update: usize,
     /// Credential provider in configuration
     credential_provider: Option<String>,
+    /// If set, config.json includes 'signature-key'
+    signature_key: Option<String>,
+    /// If set, config.json includes 'device-authorization'
+    device_authorization: bool,
+    /// If set, config.json includes 'bin-dl'
+    bin_dl: bool,
 }
 
 pub struct TestRegistry {
@@ -175,6 +181,9 @@ impl RegistryBuilder {
             not_found_handler: Box::new(not_found),
             delayed_index_update: 0,
             credential_provider: None,
+            signature_key: None,
+            device_authorization: false,
+            bin_dl: false,
         }
     }
 
@@ -248,6 +257,31 @@ impl RegistryBuilder {
         self
     }
 
+    /// Advertises the PASERK public key `key` for verifying the signatures of
+    /// packages, see [`Package::sign`] and [`signature_public_key`].
+    #[must_use]
+    pub fn signature_key(mut self, key: &str) -> Self {
+        self.signature_key = Some(key.to_string());
+        self
+    }
+
+    /// Advertises the device authorization grant for `cargo login`, with the
+    /// `/oauth/device/code` and `/oauth/token` endpoints of the API, which
+    /// need responders.
+    #[must_use]
+    pub fn device_authorization(mut self) -> Self {
+        self.device_authorization = true;
+        self
+    }
+
+    /// Advertises a `bin-dl` URL for the prebuilt binaries of the packages,
+    /// see [`Package::prebuilt_bin`].
+    #[must_use]
+    pub fn bin_dl(mut self) -> Self {
+        self.bin_dl = true;
+        self
+    }
+
     /// Operate the index over http
     #[must_use]
     pub fn http_index(mut self) -> Self {
@@ -446,11 +480,35 @@ impl RegistryBuilder {
         } else {
             String::new()
         };
+        let signature_key = if let Some(key) = &self.signature_key {
+            format!(r#","signature-key":"{key}""#)
+        } else {
+            String::new()
+        };
+        let device_authorization = if self.device_authorization {
+            format!(
+                r#","device-authorization":{{"url":"{api}oauth/device/code","token-url":"{api}oauth/token","client-id":"cargo"}}"#,
+                api = registry.api_url
+            )
+        } else {
+            String::new()
+        };
+        let bin_dl = if self.bin_dl {
+            format!(
+                r#","bin-dl":"{}/{{crate}}/{{version}}/bin/{{target}}/{{bin}}""#,
+                registry.dl_url.as_str().trim_end_matches('/')
+            )
+        } else {
+            String::new()
+        };
         // Initialize a new registry.
         repo(&registry.path)
             .file(
                 "config.json",
-                &format!(r#"{{"dl":"{}"{api}{auth}}}"#, registry.dl_url),
+                &format!(
+                    r#"{{"dl":"{}"{api}{auth}{signature_key}{device_authorization}{bin_dl}}}"#,
+                    registry.dl_url
+                ),
             )
             .build();
         fs::create_dir_all(api_path.join("api/v1/crates")).unwrap();
@@ -540,6 +598,8 @@ pub struct Package {
     rust_version: Option<String>,
     cargo_features: Vec<String>,
     v: Option<u32>,
+    signing_key: Option<String>,
+    prebuilt_bins: Vec<(String, String, Vec<u8>)>,
 }
 
 pub(crate) type FeatureMap = BTreeMap<String, Vec<String>>;
@@ -1212,6 +1272,8 @@ impl Package {
             rust_version: None,
             cargo_features: Vec::new(),
             v: None,
+            signing_key: None,
+            prebuilt_bins: Vec::new(),
         }
     }
 
@@ -1353,6 +1415,23 @@ impl Package {
         self
     }
 
+    /// Signs the package with the PASERK secret key `secret_key`, adding a
+    /// `sig` field to the index entry, and a `bins_sig` field if it has
+    /// prebuilt binaries.
+    pub fn sign(&mut self, secret_key: &str) -> &mut Package {
+        self.signing_key = Some(secret_key.to_string());
+        self
+    }
+
+    /// Adds a prebuilt binary `name` for `target`, listed in the `bins`
+    /// field of the index entry and served from the `bin-dl` URL of the
+    /// registry, see [`RegistryBuilder::bin_dl`].
+    pub fn prebuilt_bin(&mut self, target: &str, name: &str, contents: &[u8]) -> &mut Package {
+        self.prebuilt_bins
+            .push((target.to_string(), name.to_string(), contents.to_vec()));
+        self
+    }
+
     /// Specify a minimal Rust version.
     pub fn rust_version(&mut self, rust_version: &str) -> &mut Package {
         self.rust_version = Some(rust_version.into());
@@ -1432,7 +1511,7 @@ impl Package {
         } else {
             serde_json::json!(self.name)
         };
-        let line = create_index_line(
+        let mut line = create_index_line(
             name,
             &self.vers,
             deps,
@@ -1443,6 +1522,62 @@ impl Package {
             self.rust_version.as_deref(),
             self.v,
         );
+        if let Some(secret_key) = &self.signing_key {
+            let secret: AsymmetricSecretKey<pasetors::version3::V3> =
+                secret_key.as_str().try_into().unwrap();
+            let message = serde_json::json!({
+                "name": self.name,
+                "vers": self.vers,
+                "cksum": cksum,
+            });
+            let sig = pasetors::version3::PublicToken::sign(
+                &secret,
+                message.to_string().as_bytes(),
+                None,
+                None,
+            )
+            .unwrap();
+            let mut json: serde_json::Value = serde_json::from_str(&line).unwrap();
+            json["sig"] = serde_json::json!(sig);
+            line = json.to_string();
+        }
+        if !self.prebuilt_bins.is_empty() {
+            let mut bins = BTreeMap::<&str, BTreeMap<&str, String>>::new();
+            for (target, name, contents) in &self.prebuilt_bins {
+                let path = self
+                    .archive_dst()
+                    .parent()
+                    .unwrap()
+                    .join("bin")
+                    .join(target)
+                    .join(name);
+                t!(fs::create_dir_all(path.parent().unwrap()));
+                t!(fs::write(&path, contents));
+                bins.entry(target)
+                    .or_default()
+                    .insert(name, self::cksum(contents));
+            }
+            let mut json: serde_json::Value = serde_json::from_str(&line).unwrap();
+            if let Some(secret_key) = &self.signing_key {
+                let secret: AsymmetricSecretKey<pasetors::version3::V3> =
+                    secret_key.as_str().try_into().unwrap();
+                let message = serde_json::json!({
+                    "name": self.name,
+                    "vers": self.vers,
+                    "bins": bins,
+                });
+                let sig = pasetors::version3::PublicToken::sign(
+                    &secret,
+                    message.to_string().as_bytes(),
+                    None,
+                    None,
+                )
+                .unwrap();
+                json["bins_sig"] = serde_json::json!(sig);
+            }
+            json["bins"] = serde_json::json!(bins);
+            line = json.to_string();
+        }
 
         let registry_path = if self.alternative {
             alt_registry_path()
@@ -1606,6 +1741,15 @@ impl Package {
     }
 }
 
+/// Returns the PASERK public key of the PASERK secret key `secret_key`.
+pub fn signature_public_key(secret_key: &str) -> String {
+    let secret: AsymmetricSecretKey<pasetors::version3::V3> = secret_key.try_into().unwrap();
+    let public: AsymmetricPublicKey<pasetors::version3::V3> = (&secret).try_into().unwrap();
+    let mut key = String::new();
+    FormatAsPaserk::fmt(&public, &mut key).unwrap();
+    key
+}
+
 pub fn cksum(s: &[u8]) -> String {
     Sha256::new().update(s).finish_hex()
 }
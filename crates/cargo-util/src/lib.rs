@@ -1,7 +1,7 @@
 //! Miscellaneous support code used by Cargo.
 
 pub use self::read2::read2;
-pub use process_builder::ProcessBuilder;
+pub use process_builder::{kill_process_trees, ProcessBuilder};
 pub use process_error::{exit_status_to_string, is_simple_exit_code, ProcessError};
 pub use sha256::Sha256;
 
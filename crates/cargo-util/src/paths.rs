@@ -108,6 +108,171 @@ pub fn normalize_path(path: &Path) -> PathBuf {
     ret
 }
 
+/// Converts a Windows extended-length path, like `\\?\C:\foo` or
+/// `\\?\UNC\server\share\foo`, to the usual form, like `C:\foo` or
+/// `\\server\share\foo`, when both forms name the same file. Other paths,
+/// and all paths on other platforms, are returned unchanged.
+///
+/// [`std::fs::canonicalize`] returns extended-length paths on Windows, while
+/// rustc, build scripts and users write paths in the usual form, so paths have
+/// to be converted before being compared or recorded. Long paths don't need
+/// the `\\?\` prefix to be accessed, as the standard library adds it to
+/// paths longer than `MAX_PATH`.
+pub fn normalize_verbatim(path: &Path) -> PathBuf {
+    #[cfg(windows)]
+    if let Some(normalized) = path.to_str().and_then(normalize_verbatim_str) {
+        return PathBuf::from(normalized);
+    }
+    path.to_path_buf()
+}
+
+/// Implementation of [`normalize_verbatim`] on the text of a Windows path,
+/// `None` if it isn't an extended-length path or its usual form names a
+/// different file.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn normalize_verbatim_str(path: &str) -> Option<String> {
+    let (prefix, rest) = if let Some(rest) = path.strip_prefix(r"\\?\UNC\") {
+        // `server\share\...`
+        (r"\\", rest)
+    } else if let Some(rest) = path.strip_prefix(r"\\?\") {
+        // Only drive paths, not volume GUIDs or other devices.
+        let drive = rest.as_bytes();
+        if drive.len() < 2 || !drive[0].is_ascii_alphabetic() || drive[1] != b':' {
+            return None;
+        }
+        if drive.len() > 2 && drive[2] != b'\\' {
+            return None;
+        }
+        ("", rest)
+    } else {
+        return None;
+    };
+
+    // Components the usual form would change: `/` is a separator, `.`, `..`
+    // and empty components are collapsed, trailing dots and spaces are
+    // dropped, and reserved names are devices.
+    let mut components: Vec<_> = rest.split('\\').collect();
+    if prefix.is_empty() {
+        // The drive, like `C:`.
+        components.remove(0);
+    }
+    if components.last() == Some(&"") {
+        // A trailing separator.
+        components.pop();
+    }
+    let changed = components.iter().any(|component| {
+        component.is_empty()
+            || component.contains('/')
+            || component.ends_with('.')
+            || component.ends_with(' ')
+            || is_reserved_name(component)
+    });
+    if changed {
+        return None;
+    }
+    Some(format!("{prefix}{rest}"))
+}
+
+/// Whether `component` is a DOS device name, like `NUL` or `com1.txt`.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn is_reserved_name(component: &str) -> bool {
+    let stem = component.split('.').next().unwrap_or(component).trim_end();
+    let stem = stem.to_ascii_uppercase();
+    match stem.as_str() {
+        "CON" | "PRN" | "AUX" | "NUL" | "CONIN$" | "CONOUT$" => true,
+        _ => {
+            let (name, digit) = stem.split_at(stem.len().saturating_sub(1));
+            (name == "COM" || name == "LPT") && matches!(digit.as_bytes(), [b'1'..=b'9'])
+        }
+    }
+}
+
+/// Whether a filesystem tells apart paths that only differ by case.
+///
+/// The filesystems used by default on macOS and Windows are case-insensitive
+/// but case-preserving, so the same file can be named with different cases,
+/// for example by a working directory typed in another case than the one on
+/// disk. Paths have to be compared with the sensitivity of the filesystem
+/// holding them, which may differ between directories of the same system.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CaseSensitivity {
+    Sensitive,
+    Insensitive,
+}
+
+impl CaseSensitivity {
+    /// The usual sensitivity of the filesystems of the platform, used when
+    /// the filesystem can't be probed.
+    pub fn platform_default() -> CaseSensitivity {
+        if cfg!(any(windows, target_os = "macos", target_os = "ios")) {
+            CaseSensitivity::Insensitive
+        } else {
+            CaseSensitivity::Sensitive
+        }
+    }
+
+    /// Probes the filesystem holding the existing directory `dir`, by
+    /// creating a temporary file in it and looking it up with another case.
+    pub fn probe(dir: &Path) -> CaseSensitivity {
+        let probe = || -> io::Result<CaseSensitivity> {
+            let file = TempFileBuilder::new()
+                .prefix(".cargo-case-Probe-")
+                .tempfile_in(dir)?;
+            let name = file.path().file_name().and_then(|name| name.to_str());
+            let Some(name) = name else {
+                return Ok(CaseSensitivity::platform_default());
+            };
+            let other_case = dir.join(name.to_lowercase());
+            Ok(if fs::symlink_metadata(other_case).is_ok() {
+                CaseSensitivity::Insensitive
+            } else {
+                CaseSensitivity::Sensitive
+            })
+        };
+        probe().unwrap_or_else(|e| {
+            tracing::debug!(
+                "failed to probe the case sensitivity of {}: {e}",
+                dir.display()
+            );
+            CaseSensitivity::platform_default()
+        })
+    }
+
+    /// Like [`Path::strip_prefix`], but a prefix in another case is stripped
+    /// too on case-insensitive filesystems.
+    pub fn strip_prefix<'a>(self, path: &'a Path, base: &Path) -> Option<&'a Path> {
+        if let Ok(rest) = path.strip_prefix(base) {
+            return Some(rest);
+        }
+        if self == CaseSensitivity::Sensitive {
+            return None;
+        }
+        let mut components = path.components();
+        for base_component in base.components() {
+            let component = components.next()?;
+            if !eq_ignore_case(component.as_os_str(), base_component.as_os_str()) {
+                return None;
+            }
+        }
+        Some(components.as_path())
+    }
+
+    /// Like [`Path::starts_with`], but a prefix in another case matches too
+    /// on case-insensitive filesystems.
+    pub fn starts_with(self, path: &Path, base: &Path) -> bool {
+        self.strip_prefix(path, base).is_some()
+    }
+}
+
+/// Compares path components like case-insensitive filesystems. Components
+/// that aren't UTF-8 are compared as is.
+fn eq_ignore_case(a: &OsStr, b: &OsStr) -> bool {
+    match (a.to_str(), b.to_str()) {
+        (Some(a), Some(b)) => a.eq_ignore_ascii_case(b) || a.to_lowercase() == b.to_lowercase(),
+        _ => a == b,
+    }
+}
+
 /// Returns the absolute path of where the given executable is located based
 /// on searching the `PATH` environment variable.
 ///
@@ -185,6 +350,85 @@ pub fn write_if_changed<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) ->
     Ok(())
 }
 
+/// Equivalent to [`write()`], but the file is replaced at once, so that it
+/// never has partial contents, even if the process is killed or the system
+/// loses power while writing.
+///
+/// The contents are written to a temporary file next to `path`, which is
+/// synced to disk and then renamed to `path`. The parent directory is synced
+/// too on Unix, so that the rename itself is durable.
+///
+/// When `path` is a symlink, the file it points to is replaced, like
+/// [`write()`] writes through it, and the symlink is kept.
+///
+/// As `path` becomes a new file, a lock held on the old one doesn't cover
+/// it. Processes waiting on that lock have to check that they locked the
+/// file which is still at `path` once they got it.
+pub fn write_atomic<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> Result<()> {
+    write_atomic_with(path.as_ref(), contents.as_ref(), |_| Ok(()))?;
+    Ok(())
+}
+
+/// Like [`write_atomic`], but calls `before_rename` with the new file before
+/// it replaces `path`, and returns it.
+pub fn write_atomic_with(
+    path: &Path,
+    contents: &[u8],
+    before_rename: impl FnOnce(&File) -> io::Result<()>,
+) -> Result<File> {
+    (|| -> Result<File> {
+        let path = resolve_symlinks(path);
+        let path = path.as_path();
+        let parent = path.parent().unwrap();
+        let name = path.file_name().unwrap();
+        let mut prefix = OsString::from(".");
+        prefix.push(name);
+        prefix.push(".");
+        // Created like `write` creates files, not with the restricted
+        // permissions of temporary files.
+        let mut tmp = TempFileBuilder::new()
+            .prefix(&prefix)
+            .make_in(parent, |tmp| {
+                OpenOptions::new().write(true).create_new(true).open(tmp)
+            })?;
+        if let Ok(meta) = fs::metadata(path) {
+            tmp.as_file().set_permissions(meta.permissions())?;
+        }
+        tmp.write_all(contents)?;
+        tmp.as_file().sync_all()?;
+        before_rename(tmp.as_file())?;
+        let file = tmp.persist(path)?;
+        sync_dir(parent);
+        Ok(file)
+    })()
+    .with_context(|| format!("failed to write `{}`", path.display()))
+}
+
+/// The file `path` points to, following its symlinks, which may not exist.
+fn resolve_symlinks(path: &Path) -> PathBuf {
+    let mut path = path.to_path_buf();
+    // Bounded like the nesting of symlinks the OS follows, to stop on loops.
+    for _ in 0..40 {
+        match fs::read_link(&path) {
+            // A relative target is relative to the directory of the symlink.
+            Ok(target) => path = path.parent().unwrap().join(target),
+            Err(_) => break,
+        }
+    }
+    path
+}
+
+/// Syncs the entries of a directory to disk. Errors are ignored, as some
+/// file systems don't support it.
+fn sync_dir(path: &Path) {
+    #[cfg(unix)]
+    if let Ok(dir) = File::open(path) {
+        let _ = dir.sync_all();
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
+
 /// Equivalent to [`write()`], but appends to the end instead of replacing the
 /// contents.
 pub fn append(path: &Path, contents: &[u8]) -> Result<()> {
@@ -237,7 +481,7 @@ pub fn mtime_recursive(path: &Path) -> Result<FileTime> {
             Err(e) => {
                 // Ignore errors while walking. If Cargo can't access it, the
                 // build script probably can't access it, either.
-                log::debug!("failed to determine mtime while walking directory: {}", e);
+                tracing::debug!("failed to determine mtime while walking directory: {}", e);
                 None
             }
         })
@@ -252,7 +496,7 @@ pub fn mtime_recursive(path: &Path) -> Result<FileTime> {
                         // I'm not sure when this is really possible (maybe a
                         // race with unlinking?). Regardless, if Cargo can't
                         // read it, the build script probably can't either.
-                        log::debug!(
+                        tracing::debug!(
                             "failed to determine mtime while fetching symlink metadata of {}: {}",
                             e.path().display(),
                             err
@@ -271,7 +515,7 @@ pub fn mtime_recursive(path: &Path) -> Result<FileTime> {
                         // Can't access the symlink target. If Cargo can't
                         // access it, the build script probably can't access
                         // it either.
-                        log::debug!(
+                        tracing::debug!(
                             "failed to determine mtime of symlink target for {}: {}",
                             e.path().display(),
                             err
@@ -286,7 +530,7 @@ pub fn mtime_recursive(path: &Path) -> Result<FileTime> {
                         // I'm not sure when this is really possible (maybe a
                         // race with unlinking?). Regardless, if Cargo can't
                         // read it, the build script probably can't either.
-                        log::debug!(
+                        tracing::debug!(
                             "failed to determine mtime while fetching metadata of {}: {}",
                             e.path().display(),
                             err
@@ -314,7 +558,7 @@ pub fn set_invocation_time(path: &Path) -> Result<FileTime> {
         "This file has an mtime of when this was started.",
     )?;
     let ft = mtime(&timestamp)?;
-    log::debug!("invocation time for {:?} is {}", path, ft);
+    tracing::debug!("invocation time for {:?} is {}", path, ft);
     Ok(ft)
 }
 
@@ -502,26 +746,100 @@ fn set_not_readonly(p: &Path) -> io::Result<bool> {
 ///
 /// If the destination already exists, it is removed before linking.
 pub fn link_or_copy(src: impl AsRef<Path>, dst: impl AsRef<Path>) -> Result<()> {
-    let src = src.as_ref();
-    let dst = dst.as_ref();
-    _link_or_copy(src, dst)
+    link_with(src, dst, LinkStrategy::platform_default())
 }
 
-fn _link_or_copy(src: &Path, dst: &Path) -> Result<()> {
-    log::debug!("linking {} to {}", src.display(), dst.display());
-    if same_file::is_same_file(src, dst).unwrap_or(false) {
-        return Ok(());
+/// How [`link_with`] places a file at its destination.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LinkStrategy {
+    /// A hard link, falling back to a copy.
+    Hardlink,
+    /// A symbolic link, relative if the source is in the directory of the
+    /// destination, falling back to a copy.
+    Symlink,
+    /// A copy.
+    Copy,
+}
+
+impl LinkStrategy {
+    /// Hard links, except on macOS where files are copied.
+    ///
+    /// There seems to be a race condition with APFS when hard-linking
+    /// binaries. Gatekeeper does not have signing or hash information stored
+    /// in kernel when running the process, therefore killing it. This problem
+    /// does not appear when copying files as kernel has time to process it.
+    /// Note that `fs::copy` on macOS is using CopyOnWrite (syscall
+    /// `fclonefileat`) which should be as fast as hardlinking.
+    /// See <https://github.com/rust-lang/cargo/issues/10060> for the details.
+    pub fn platform_default() -> LinkStrategy {
+        if cfg!(target_os = "macos") {
+            LinkStrategy::Copy
+        } else {
+            LinkStrategy::Hardlink
+        }
     }
+}
 
+/// Links or copies the file `src` to `dst` with `strategy`, copying it when
+/// the filesystem doesn't support the link, like a hard link to another
+/// device. Directories are always symlinked.
+///
+/// If the destination already exists, it is removed before linking, unless
+/// it already is a link of the requested kind to `src`.
+pub fn link_with(
+    src: impl AsRef<Path>,
+    dst: impl AsRef<Path>,
+    strategy: LinkStrategy,
+) -> Result<()> {
+    let src = src.as_ref();
+    let dst = dst.as_ref();
+    _link_with(src, dst, strategy)
+}
+
+fn _link_with(src: &Path, dst: &Path, strategy: LinkStrategy) -> Result<()> {
+    tracing::debug!(
+        "linking {} to {} ({:?})",
+        src.display(),
+        dst.display(),
+        strategy
+    );
+    let is_dir = src.is_dir();
     // NB: we can't use dst.exists(), as if dst is a broken symlink,
     // dst.exists() will return false. This is problematic, as we still need to
     // unlink dst in this case. symlink_metadata(dst).is_ok() will tell us
     // whether dst exists *without* following symlinks, which is what we want.
-    if fs::symlink_metadata(dst).is_ok() {
+    if let Ok(dst_meta) = fs::symlink_metadata(dst) {
+        // A link of another kind, like a hard link left by a previous build
+        // when symlinks are requested, is replaced.
+        let is_symlink = dst_meta.file_type().is_symlink();
+        let up_to_date = match strategy {
+            _ if is_dir => true,
+            LinkStrategy::Symlink => is_symlink,
+            LinkStrategy::Hardlink => !is_symlink,
+            LinkStrategy::Copy => false,
+        };
+        if up_to_date && same_file::is_same_file(src, dst).unwrap_or(false) {
+            return Ok(());
+        }
         remove_file(&dst)?;
     }
 
-    let link_result = if src.is_dir() {
+    let strategy = if env::var_os("__CARGO_COPY_DONT_LINK_DO_NOT_USE_THIS").is_some() {
+        // This is a work-around for a bug in macOS 10.15. When running on
+        // APFS, there seems to be a strange race condition with
+        // Gatekeeper where it will forcefully kill a process launched via
+        // `cargo run` with SIGKILL. Copying seems to avoid the problem.
+        // This shouldn't affect anyone except Cargo's test suite because
+        // it is very rare, and only seems to happen under heavy load and
+        // rapidly creating lots of executables and running them.
+        // See https://github.com/rust-lang/cargo/issues/7821 for the
+        // gory details.
+        LinkStrategy::Copy
+    } else {
+        strategy
+    };
+
+    let link_result = if is_dir {
         #[cfg(target_os = "redox")]
         use std::os::redox::fs::symlink;
         #[cfg(unix)]
@@ -533,41 +851,28 @@ fn _link_or_copy(src: &Path, dst: &Path) -> Result<()> {
         // accidentally relied upon.
         use std::os::windows::fs::symlink_dir as symlink;
 
-        let dst_dir = dst.parent().unwrap();
-        let src = if src.starts_with(dst_dir) {
-            src.strip_prefix(dst_dir).unwrap()
-        } else {
-            src
-        };
-        symlink(src, dst)
-    } else if env::var_os("__CARGO_COPY_DONT_LINK_DO_NOT_USE_THIS").is_some() {
-        // This is a work-around for a bug in macOS 10.15. When running on
-        // APFS, there seems to be a strange race condition with
-        // Gatekeeper where it will forcefully kill a process launched via
-        // `cargo run` with SIGKILL. Copying seems to avoid the problem.
-        // This shouldn't affect anyone except Cargo's test suite because
-        // it is very rare, and only seems to happen under heavy load and
-        // rapidly creating lots of executables and running them.
-        // See https://github.com/rust-lang/cargo/issues/7821 for the
-        // gory details.
-        fs::copy(src, dst).map(|_| ())
+        symlink(relative_to_parent(src, dst), dst)
     } else {
-        if cfg!(target_os = "macos") {
-            // This is a work-around for a bug on macos. There seems to be a race condition
-            // with APFS when hard-linking binaries. Gatekeeper does not have signing or
-            // hash information stored in kernel when running the process. Therefore killing it.
-            // This problem does not appear when copying files as kernel has time to process it.
-            // Note that: fs::copy on macos is using CopyOnWrite (syscall fclonefileat) which should be
-            // as fast as hardlinking.
-            // See https://github.com/rust-lang/cargo/issues/10060 for the details
-            fs::copy(src, dst).map(|_| ())
-        } else {
-            fs::hard_link(src, dst)
+        match strategy {
+            LinkStrategy::Hardlink => fs::hard_link(src, dst),
+            LinkStrategy::Symlink => {
+                #[cfg(target_os = "redox")]
+                use std::os::redox::fs::symlink;
+                #[cfg(unix)]
+                use std::os::unix::fs::symlink;
+                // Creating symlinks needs a privilege or the developer mode
+                // on Windows, otherwise the file is copied.
+                #[cfg(windows)]
+                use std::os::windows::fs::symlink_file as symlink;
+
+                symlink(relative_to_parent(src, dst), dst)
+            }
+            LinkStrategy::Copy => fs::copy(src, dst).map(|_| ()),
         }
     };
     link_result
         .or_else(|err| {
-            log::debug!("link failed {}. falling back to fs::copy", err);
+            tracing::debug!("link failed {}. falling back to fs::copy", err);
             fs::copy(src, dst).map(|_| ())
         })
         .with_context(|| {
@@ -580,6 +885,13 @@ fn _link_or_copy(src: &Path, dst: &Path) -> Result<()> {
     Ok(())
 }
 
+/// The path of `src` relative to the directory of `dst` if it is inside it,
+/// so that symlinks keep working when the directory is moved.
+fn relative_to_parent<'a>(src: &'a Path, dst: &Path) -> &'a Path {
+    let dst_dir = dst.parent().unwrap();
+    src.strip_prefix(dst_dir).unwrap_or(src)
+}
+
 /// Copies a file from one location to another.
 ///
 /// Equivalent to [`std::fs::copy`] with better error messages.
@@ -598,8 +910,8 @@ pub fn copy<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> Result<u64> {
 pub fn set_file_time_no_err<P: AsRef<Path>>(path: P, time: FileTime) {
     let path = path.as_ref();
     match filetime::set_file_times(path, time, time) {
-        Ok(()) => log::debug!("set file mtime {} to {}", path.display(), time),
-        Err(e) => log::warn!(
+        Ok(()) => tracing::debug!("set file mtime {} to {}", path.display(), time),
+        Err(e) => tracing::warn!(
             "could not set mtime of {} to {}: {:?}",
             path.display(),
             time,
@@ -621,7 +933,7 @@ pub fn strip_prefix_canonical<P: AsRef<Path>>(
     let safe_canonicalize = |path: &Path| match path.canonicalize() {
         Ok(p) => p,
         Err(e) => {
-            log::warn!("cannot canonicalize {:?}: {:?}", path, e);
+            tracing::warn!("cannot canonicalize {:?}: {:?}", path, e);
             path.to_path_buf()
         }
     };
@@ -762,6 +1074,142 @@ fn exclude_from_time_machine(path: &Path) {
 #[cfg(test)]
 mod tests {
     use super::join_paths;
+    use super::normalize_verbatim_str;
+    use super::write_atomic;
+    use super::CaseSensitivity;
+    use super::{link_with, LinkStrategy};
+    use std::path::Path;
+
+    #[test]
+    fn write_atomic_replaces_contents() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fingerprint");
+        write_atomic(&path, "old contents").unwrap();
+        write_atomic(&path, "new").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+        // The temporary file was renamed.
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_atomic_keeps_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("real")).unwrap();
+        let target = dir.path().join("real/Cargo.lock");
+        let link = dir.path().join("Cargo.lock");
+        write_atomic(&target, "old contents").unwrap();
+        std::os::unix::fs::symlink("real/Cargo.lock", &link).unwrap();
+        write_atomic(&link, "new").unwrap();
+        assert!(std::fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "new");
+        // The temporary file was written next to the target.
+        let real = std::fs::read_dir(dir.path().join("real")).unwrap();
+        assert_eq!(real.count(), 1);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn link_strategies() {
+        use std::os::unix::fs::MetadataExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("deps/foo-1234");
+        let dst = dir.path().join("foo");
+        std::fs::create_dir(src.parent().unwrap()).unwrap();
+        std::fs::write(&src, "foo").unwrap();
+        let ino = |path: &Path| std::fs::metadata(path).unwrap().ino();
+        let is_symlink = |path: &Path| std::fs::symlink_metadata(path).unwrap().is_symlink();
+
+        link_with(&src, &dst, LinkStrategy::Hardlink).unwrap();
+        assert!(!is_symlink(&dst));
+        assert_eq!(ino(&src), ino(&dst));
+
+        // A link of another kind is replaced.
+        link_with(&src, &dst, LinkStrategy::Symlink).unwrap();
+        assert!(is_symlink(&dst));
+        assert_eq!(
+            std::fs::read_link(&dst).unwrap(),
+            Path::new("deps/foo-1234")
+        );
+
+        link_with(&src, &dst, LinkStrategy::Copy).unwrap();
+        assert!(!is_symlink(&dst));
+        assert_ne!(ino(&src), ino(&dst));
+        assert_eq!(std::fs::read_to_string(&dst).unwrap(), "foo");
+    }
+
+    #[test]
+    fn case_insensitive_strip_prefix() {
+        let path = Path::new("/Users/Foo/Project/src/lib.rs");
+        let base = Path::new("/users/foo/project");
+        let insensitive = CaseSensitivity::Insensitive;
+        assert_eq!(
+            insensitive.strip_prefix(path, base),
+            Some(Path::new("src/lib.rs"))
+        );
+        assert_eq!(CaseSensitivity::Sensitive.strip_prefix(path, base), None);
+        assert_eq!(
+            insensitive.strip_prefix(path, Path::new("/users/foo/pro")),
+            None
+        );
+        assert!(insensitive.starts_with(Path::new("/ÉTÉ/a"), Path::new("/été")));
+        assert!(!insensitive.starts_with(Path::new("/users"), base));
+    }
+
+    #[test]
+    fn probe_case_sensitivity() {
+        let dir = tempfile::tempdir().unwrap();
+        let sensitivity = CaseSensitivity::probe(dir.path());
+        // The probe doesn't leave anything behind.
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+        if cfg!(target_os = "linux") {
+            assert_eq!(sensitivity, CaseSensitivity::Sensitive);
+        }
+    }
+
+    #[test]
+    fn normalize_verbatim_paths() {
+        let normalized = |path: &str| normalize_verbatim_str(path);
+        assert_eq!(normalized(r"\\?\C:\foo\bar.rs").unwrap(), r"C:\foo\bar.rs");
+        assert_eq!(normalized(r"\\?\C:\").unwrap(), r"C:\");
+        assert_eq!(normalized(r"\\?\C:").unwrap(), r"C:");
+        assert_eq!(
+            normalized(r"\\?\UNC\server\share\foo").unwrap(),
+            r"\\server\share\foo"
+        );
+        let long = format!(r"\\?\C:\{}\lib.rs", vec!["a".repeat(50); 6].join(r"\"));
+        assert!(long.len() > 260);
+        assert_eq!(normalized(&long).unwrap(), &long[4..]);
+
+        // Not extended-length paths.
+        assert_eq!(normalized(r"C:\foo"), None);
+        assert_eq!(normalized(r"\\server\share\foo"), None);
+        assert_eq!(normalized(r"\\.\C:\foo"), None);
+        // Devices other than drives.
+        assert_eq!(normalized(r"\\?\Volume{1234}\foo"), None);
+        assert_eq!(normalized(r"\\?\GLOBALROOT\Device"), None);
+        // Paths the usual form would change.
+        assert_eq!(normalized(r"\\?\C:\foo/bar"), None);
+        assert_eq!(normalized(r"\\?\C:\foo\..\bar"), None);
+        assert_eq!(normalized(r"\\?\C:\foo\.\bar"), None);
+        assert_eq!(normalized(r"\\?\C:\foo\\bar"), None);
+        assert_eq!(normalized(r"\\?\C:\foo.\bar"), None);
+        assert_eq!(normalized(r"\\?\C:\foo \bar"), None);
+        assert_eq!(normalized(r"\\?\C:\foo\NUL"), None);
+        assert_eq!(normalized(r"\\?\C:\foo\com1.txt"), None);
+        assert_eq!(
+            normalized(r"\\?\C:\foo\com10.txt").unwrap(),
+            r"C:\foo\com10.txt"
+        );
+        assert_eq!(
+            normalized(r"\\?\C:\foo\console").unwrap(),
+            r"C:\foo\console"
+        );
+    }
 
     #[test]
     fn join_paths_lists_paths_on_error() {
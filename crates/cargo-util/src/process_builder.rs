@@ -13,7 +13,12 @@ use std::fmt;
 use std::io::{self, Write};
 use std::iter::once;
 use std::path::Path;
-use std::process::{Command, ExitStatus, Output, Stdio};
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicIsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
 
 /// A builder object for an external process, similar to [`std::process::Command`].
 #[derive(Clone, Debug)]
@@ -41,6 +46,11 @@ pub struct ProcessBuilder {
     retry_with_argfile: bool,
     /// Data to write to stdin.
     stdin: Option<Vec<u8>>,
+    /// Kills the process once set, see [`ProcessBuilder::cancel_on`].
+    cancel: Option<Arc<AtomicBool>>,
+    /// `true` to also kill the processes it spawned.
+    /// See [`ProcessBuilder::kill_process_tree`] for more information.
+    kill_process_tree: bool,
 }
 
 impl fmt::Display for ProcessBuilder {
@@ -83,6 +93,8 @@ impl ProcessBuilder {
             display_env_vars: false,
             retry_with_argfile: false,
             stdin: None,
+            cancel: None,
+            kill_process_tree: false,
         }
     }
 
@@ -216,6 +228,29 @@ impl ProcessBuilder {
         self
     }
 
+    /// Kills the process once `cancelled` is set, while
+    /// [`ProcessBuilder::exec_with_streaming`] waits for it.
+    ///
+    /// Only the process itself is killed, not the processes it spawned,
+    /// unless [`ProcessBuilder::kill_process_tree`] is enabled.
+    pub fn cancel_on(&mut self, cancelled: Arc<AtomicBool>) -> &mut Self {
+        self.cancel = Some(cancelled);
+        self
+    }
+
+    /// Enables killing the processes spawned by the process along with it,
+    /// when it is cancelled with [`ProcessBuilder::cancel_on`] or by
+    /// [`kill_process_trees`].
+    ///
+    /// [`ProcessBuilder::exec_with_streaming`] then runs it in a new process
+    /// group on Unix, and in a new job object on Windows. On Unix, this means
+    /// that the Ctrl-C of the terminal doesn't reach the process anymore, so
+    /// the caller has to handle it.
+    pub fn kill_process_tree(&mut self, enabled: bool) -> &mut Self {
+        self.kill_process_tree = enabled;
+        self
+    }
+
     fn should_retry_with_argfile(&self, err: &io::Error) -> bool {
         self.retry_with_argfile && imp::command_line_too_big(err)
     }
@@ -335,6 +370,31 @@ impl ProcessBuilder {
         on_stderr_line: &mut dyn FnMut(&str) -> Result<()>,
         capture_output: bool,
     ) -> Result<Output> {
+        self._exec_with_streaming(on_stdout_line, on_stderr_line, capture_output, false)
+            .map(|(output, _)| output)
+    }
+
+    /// Like [`ProcessBuilder::exec_with_streaming`], also returning the CPU
+    /// time used by the process and by the processes it spawned and waited
+    /// for.
+    ///
+    /// The CPU time is only measured on Unix.
+    pub fn exec_with_streaming_and_cpu_time(
+        &self,
+        on_stdout_line: &mut dyn FnMut(&str) -> Result<()>,
+        on_stderr_line: &mut dyn FnMut(&str) -> Result<()>,
+        capture_output: bool,
+    ) -> Result<(Output, Option<Duration>)> {
+        self._exec_with_streaming(on_stdout_line, on_stderr_line, capture_output, true)
+    }
+
+    fn _exec_with_streaming(
+        &self,
+        on_stdout_line: &mut dyn FnMut(&str) -> Result<()>,
+        on_stderr_line: &mut dyn FnMut(&str) -> Result<()>,
+        capture_output: bool,
+        measure_cpu_time: bool,
+    ) -> Result<(Output, Option<Duration>)> {
         let mut stdout = Vec::new();
         let mut stderr = Vec::new();
 
@@ -342,7 +402,10 @@ impl ProcessBuilder {
         let mut stdout_pos = 0;
         let mut stderr_pos = 0;
 
-        let spawn = |mut cmd| {
+        let spawn = |mut cmd: Command| {
+            if self.kill_process_tree {
+                imp::new_process_group(&mut cmd);
+            }
             if !debug_force_argfile(self.retry_with_argfile) {
                 match piped(&mut cmd, false).spawn() {
                     Err(ref e) if self.should_retry_with_argfile(e) => {}
@@ -351,64 +414,87 @@ impl ProcessBuilder {
                 }
             }
             let (mut cmd, argfile) = self.build_command_with_argfile()?;
+            if self.kill_process_tree {
+                imp::new_process_group(&mut cmd);
+            }
             Ok((piped(&mut cmd, false).spawn()?, Some(argfile)))
         };
 
         let status = (|| {
             let cmd = self.build_command();
             let (mut child, argfile) = spawn(cmd)?;
+            let tree = if self.kill_process_tree {
+                ProcessTree::new(&child)
+            } else {
+                None
+            };
             let out = child.stdout.take().unwrap();
             let err = child.stderr.take().unwrap();
-            read2(out, err, &mut |is_out, data, eof| {
-                let pos = if is_out {
-                    &mut stdout_pos
-                } else {
-                    &mut stderr_pos
-                };
-                let idx = if eof {
-                    data.len()
-                } else {
-                    match data[*pos..].iter().rposition(|b| *b == b'\n') {
-                        Some(i) => *pos + i + 1,
-                        None => {
-                            *pos = data.len();
-                            return;
+            let child = Mutex::new(Some(child));
+            let status = thread::scope(|s| {
+                // Dropped once the process exited, to stop the thread killing it.
+                let (exited, wait_exited) = mpsc::channel::<()>();
+                if let Some(cancel) = &self.cancel {
+                    let child = &child;
+                    let tree = tree.as_ref();
+                    s.spawn(move || kill_on_cancel(child, tree, cancel, wait_exited));
+                }
+                read2(out, err, &mut |is_out, data, eof| {
+                    let pos = if is_out {
+                        &mut stdout_pos
+                    } else {
+                        &mut stderr_pos
+                    };
+                    let idx = if eof {
+                        data.len()
+                    } else {
+                        match data[*pos..].iter().rposition(|b| *b == b'\n') {
+                            Some(i) => *pos + i + 1,
+                            None => {
+                                *pos = data.len();
+                                return;
+                            }
                         }
-                    }
-                };
+                    };
 
-                let new_lines = &data[..idx];
+                    let new_lines = &data[..idx];
 
-                for line in String::from_utf8_lossy(new_lines).lines() {
-                    if callback_error.is_some() {
-                        break;
-                    }
-                    let callback_result = if is_out {
-                        on_stdout_line(line)
-                    } else {
-                        on_stderr_line(line)
-                    };
-                    if let Err(e) = callback_result {
-                        callback_error = Some(e);
-                        break;
+                    for line in String::from_utf8_lossy(new_lines).lines() {
+                        if callback_error.is_some() {
+                            break;
+                        }
+                        let callback_result = if is_out {
+                            on_stdout_line(line)
+                        } else {
+                            on_stderr_line(line)
+                        };
+                        if let Err(e) = callback_result {
+                            callback_error = Some(e);
+                            break;
+                        }
                     }
-                }
 
-                if capture_output {
-                    let dst = if is_out { &mut stdout } else { &mut stderr };
-                    dst.extend(new_lines);
-                }
+                    if capture_output {
+                        let dst = if is_out { &mut stdout } else { &mut stderr };
+                        dst.extend(new_lines);
+                    }
 
-                data.drain(..idx);
-                *pos = 0;
-            })?;
-            let status = child.wait();
+                    data.drain(..idx);
+                    *pos = 0;
+                })?;
+                let status = wait(&child, self.cancel.is_some(), measure_cpu_time);
+                drop(exited);
+                status
+            });
+            // No longer killed by `kill_process_trees` once it exited.
+            drop(tree);
             if let Some(argfile) = argfile {
                 close_tempfile_and_log_error(argfile);
             }
             status
         })()
         .with_context(|| ProcessError::could_not_execute(self))?;
+        let (status, cpu_time) = status;
         let output = Output {
             status,
             stdout,
@@ -433,7 +519,7 @@ impl ProcessBuilder {
             }
         }
 
-        Ok(output)
+        Ok((output, cpu_time))
     }
 
     /// Builds the command with an `@<path>` argfile that contains all the
@@ -449,7 +535,7 @@ impl ProcessBuilder {
         arg.push(tmp.path());
         let mut cmd = self.build_command_without_args();
         cmd.arg(arg);
-        log::debug!("created argfile at {} for {self}", tmp.path().display());
+        tracing::debug!("created argfile at {} for {self}", tmp.path().display());
 
         let cap = self.get_args().map(|arg| arg.len() + 1).sum::<usize>();
         let mut buf = Vec::with_capacity(cap);
@@ -545,6 +631,128 @@ fn debug_force_argfile(retry_enabled: bool) -> bool {
     cfg!(debug_assertions) && env::var("__CARGO_TEST_FORCE_ARGFILE").is_ok() && retry_enabled
 }
 
+/// Kills `child`, or its whole `tree`, once `cancel` is set, until it exits.
+fn kill_on_cancel(
+    child: &Mutex<Option<Child>>,
+    tree: Option<&ProcessTree>,
+    cancel: &AtomicBool,
+    exited: mpsc::Receiver<()>,
+) {
+    while let Err(RecvTimeoutError::Timeout) = exited.recv_timeout(Duration::from_millis(50)) {
+        if cancel.load(Ordering::SeqCst) {
+            let mut child = child.lock().unwrap();
+            // Reaped by `wait` without `Child` knowing it, its pid may be
+            // reused.
+            let Some(child) = child.as_mut() else {
+                return;
+            };
+            match tree {
+                // The process group of a reaped process may be reused.
+                Some(tree) if matches!(child.try_wait(), Ok(None)) => tree.kill(),
+                Some(_) => {}
+                None => {
+                    let _ = child.kill();
+                }
+            }
+            return;
+        }
+    }
+}
+
+/// Waits for `child` to exit. When it is `killable`, its lock isn't held
+/// while waiting for it to exit, which leaves it unreaped so that
+/// [`kill_on_cancel`] can still kill it without its pid being reused.
+///
+/// Returns the CPU time of `child` when `measure_cpu_time` is set and the
+/// platform supports it. `child` is then reaped without [`Child`] knowing it,
+/// so it is taken out of the mutex.
+fn wait(
+    child: &Mutex<Option<Child>>,
+    killable: bool,
+    measure_cpu_time: bool,
+) -> io::Result<(ExitStatus, Option<Duration>)> {
+    let try_wait = |block: bool| {
+        let mut child = child.lock().unwrap();
+        let running = child.as_mut().expect("only reaped once");
+        if measure_cpu_time {
+            let exited = imp::wait_with_cpu_time(running, block)?;
+            if exited.is_some() {
+                *child = None;
+            }
+            Ok(exited)
+        } else if block {
+            Ok(Some((running.wait()?, None)))
+        } else {
+            Ok(running.try_wait()?.map(|status| (status, None)))
+        }
+    };
+    if killable {
+        let id = imp::exit_wait_id(child.lock().unwrap().as_ref().expect("not reaped yet"));
+        imp::wait_until_exited(id)?;
+    }
+    try_wait(true).map(|exited| exited.expect("blocked until it exited"))
+}
+
+/// The process groups, or job objects on Windows, of the running processes
+/// started with [`ProcessBuilder::kill_process_tree`], for
+/// [`kill_process_trees`]. Free slots are zero.
+///
+/// This is a fixed array of atomics so that it can be read from a signal
+/// handler. Processes past its capacity are still killed when cancelled,
+/// just not by [`kill_process_trees`].
+static RUNNING_TREES: [AtomicIsize; 256] = {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const FREE: AtomicIsize = AtomicIsize::new(0);
+    [FREE; 256]
+};
+
+/// Kills the processes started with [`ProcessBuilder::kill_process_tree`]
+/// which are still running, along with the processes they spawned.
+///
+/// On Unix, this is async-signal-safe, so it can be called from a signal
+/// handler.
+pub fn kill_process_trees() {
+    for slot in &RUNNING_TREES {
+        let id = slot.load(Ordering::SeqCst);
+        if id != 0 {
+            imp::kill_process_tree(id);
+        }
+    }
+}
+
+/// A process started with [`ProcessBuilder::kill_process_tree`], with the
+/// processes it spawned.
+struct ProcessTree {
+    /// The process group on Unix, the job object on Windows.
+    id: isize,
+    /// The index in [`RUNNING_TREES`], if there was room.
+    slot: Option<usize>,
+}
+
+impl ProcessTree {
+    fn new(child: &Child) -> Option<ProcessTree> {
+        let id = imp::process_tree_of(child)?;
+        let slot = RUNNING_TREES.iter().position(|slot| {
+            slot.compare_exchange(0, id, Ordering::SeqCst, Ordering::SeqCst)
+                .is_ok()
+        });
+        Some(ProcessTree { id, slot })
+    }
+
+    fn kill(&self) {
+        imp::kill_process_tree(self.id);
+    }
+}
+
+impl Drop for ProcessTree {
+    fn drop(&mut self) {
+        if let Some(slot) = self.slot {
+            RUNNING_TREES[slot].store(0, Ordering::SeqCst);
+        }
+        imp::close_process_tree(self.id);
+    }
+}
+
 /// Creates new pipes for stderr, stdout, and optionally stdin.
 fn piped(cmd: &mut Command, pipe_stdin: bool) -> &mut Command {
     cmd.stdout(Stdio::piped())
@@ -558,7 +766,7 @@ fn piped(cmd: &mut Command, pipe_stdin: bool) -> &mut Command {
 
 fn close_tempfile_and_log_error(file: NamedTempFile) {
     file.close().unwrap_or_else(|e| {
-        log::warn!("failed to close temporary file: {e}");
+        tracing::warn!("failed to close temporary file: {e}");
     });
 }
 
@@ -567,7 +775,10 @@ mod imp {
     use super::{close_tempfile_and_log_error, debug_force_argfile, ProcessBuilder, ProcessError};
     use anyhow::Result;
     use std::io;
-    use std::os::unix::process::CommandExt;
+    use std::mem::MaybeUninit;
+    use std::os::unix::process::{CommandExt, ExitStatusExt};
+    use std::process::{Child, Command, ExitStatus};
+    use std::time::Duration;
 
     pub fn exec_replace(process_builder: &ProcessBuilder) -> Result<()> {
         let mut error;
@@ -599,6 +810,93 @@ mod imp {
     pub fn command_line_too_big(err: &io::Error) -> bool {
         err.raw_os_error() == Some(libc::E2BIG)
     }
+
+    pub fn new_process_group(cmd: &mut Command) {
+        cmd.process_group(0);
+    }
+
+    /// The process group of `child`, which leads it.
+    pub fn process_tree_of(child: &Child) -> Option<isize> {
+        Some(child.id() as isize)
+    }
+
+    pub fn kill_process_tree(pgid: isize) {
+        unsafe {
+            libc::killpg(pgid as libc::pid_t, libc::SIGKILL);
+        }
+    }
+
+    pub fn close_process_tree(_pgid: isize) {}
+
+    /// What [`wait_until_exited`] waits for, the pid of `child`.
+    pub fn exit_wait_id(child: &Child) -> isize {
+        child.id() as isize
+    }
+
+    /// Blocks until the process `pid` exits, without reaping it.
+    pub fn wait_until_exited(pid: isize) -> io::Result<()> {
+        loop {
+            let mut info = MaybeUninit::<libc::siginfo_t>::zeroed();
+            let result = unsafe {
+                libc::waitid(
+                    libc::P_PID,
+                    pid as libc::id_t,
+                    info.as_mut_ptr(),
+                    libc::WEXITED | libc::WNOWAIT,
+                )
+            };
+            if result == 0 {
+                return Ok(());
+            }
+            let err = io::Error::last_os_error();
+            if err.kind() != io::ErrorKind::Interrupted {
+                return Err(err);
+            }
+        }
+    }
+
+    /// Waits for `child` to exit with `wait4`, which also returns the CPU
+    /// time it used, including the one of the processes it waited for.
+    /// Returns `None` if it is still running and `block` isn't set.
+    ///
+    /// `child` is reaped without [`Child`] knowing it, so it must not be
+    /// waited for or killed afterwards.
+    pub fn wait_with_cpu_time(
+        child: &mut Child,
+        block: bool,
+    ) -> io::Result<Option<(ExitStatus, Option<Duration>)>> {
+        let mut status = 0;
+        let mut usage = MaybeUninit::<libc::rusage>::zeroed();
+        let options = if block { 0 } else { libc::WNOHANG };
+        loop {
+            let pid = unsafe {
+                libc::wait4(
+                    child.id() as libc::pid_t,
+                    &mut status,
+                    options,
+                    usage.as_mut_ptr(),
+                )
+            };
+            match pid {
+                0 => return Ok(None),
+                -1 => {
+                    let err = io::Error::last_os_error();
+                    if err.kind() != io::ErrorKind::Interrupted {
+                        return Err(err);
+                    }
+                }
+                _ => break,
+            }
+        }
+        let usage = unsafe { usage.assume_init() };
+        let time = |tv: libc::timeval| {
+            Duration::from_secs(tv.tv_sec as u64) + Duration::from_micros(tv.tv_usec as u64)
+        };
+        Ok(Some((
+            ExitStatus::from_raw(status),
+            Some(time(usage.ru_utime) + time(usage.ru_stime)),
+        )))
+    }
 }
 
 #[cfg(windows)]
@@ -606,8 +904,16 @@ mod imp {
     use super::{ProcessBuilder, ProcessError};
     use anyhow::Result;
     use std::io;
-    use windows_sys::Win32::Foundation::{BOOL, FALSE, TRUE};
+    use std::os::windows::io::AsRawHandle;
+    use std::process::{Child, Command, ExitStatus};
+    use std::ptr;
+    use std::time::Duration;
+    use windows_sys::Win32::Foundation::{CloseHandle, BOOL, FALSE, HANDLE, TRUE, WAIT_FAILED};
     use windows_sys::Win32::System::Console::SetConsoleCtrlHandler;
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, TerminateJobObject,
+    };
+    use windows_sys::Win32::System::Threading::{WaitForSingleObject, INFINITE};
 
     unsafe extern "system" fn ctrlc_handler(_: u32) -> BOOL {
         // Do nothing; let the child process handle it.
@@ -629,6 +935,63 @@ mod imp {
         use windows_sys::Win32::Foundation::ERROR_FILENAME_EXCED_RANGE;
         err.raw_os_error() == Some(ERROR_FILENAME_EXCED_RANGE as i32)
     }
+
+    /// Job objects are created after spawning, see [`process_tree_of`].
+    pub fn new_process_group(_cmd: &mut Command) {}
+
+    /// A new job object containing `child`, and the processes it spawns
+    /// from now on.
+    pub fn process_tree_of(child: &Child) -> Option<isize> {
+        unsafe {
+            let job = CreateJobObjectW(ptr::null(), ptr::null());
+            if job == 0 {
+                return None;
+            }
+            if AssignProcessToJobObject(job, child.as_raw_handle() as HANDLE) == FALSE {
+                CloseHandle(job);
+                return None;
+            }
+            Some(job)
+        }
+    }
+
+    pub fn kill_process_tree(job: isize) {
+        unsafe {
+            TerminateJobObject(job, 1);
+        }
+    }
+
+    pub fn close_process_tree(job: isize) {
+        unsafe {
+            CloseHandle(job);
+        }
+    }
+
+    /// What [`wait_until_exited`] waits for, the handle of `child`, which is
+    /// valid as long as `child` isn't dropped.
+    pub fn exit_wait_id(child: &Child) -> isize {
+        child.as_raw_handle() as HANDLE
+    }
+
+    /// Blocks until the process of `handle` exits.
+    pub fn wait_until_exited(handle: isize) -> io::Result<()> {
+        if unsafe { WaitForSingleObject(handle, INFINITE) } == WAIT_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// The CPU time of the processes spawned by `child` isn't measured.
+    pub fn wait_with_cpu_time(
+        child: &mut Child,
+        block: bool,
+    ) -> io::Result<Option<(ExitStatus, Option<Duration>)>> {
+        if block {
+            Ok(Some((child.wait()?, None)))
+        } else {
+            Ok(child.try_wait()?.map(|status| (status, None)))
+        }
+    }
 }
 
 #[cfg(test)]
@@ -36,6 +36,12 @@ pub struct Crate {
     pub name: String,
     pub description: Option<String>,
     pub max_version: String,
+    /// Total number of downloads, if the registry provides it.
+    pub downloads: Option<u64>,
+    /// Number of downloads in the last 90 days, if the registry provides it.
+    pub recent_downloads: Option<u64>,
+    /// The `package.rust-version` of `max_version`, if the registry provides it.
+    pub rust_version: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -111,6 +117,14 @@ struct ApiError {
 struct OwnersReq<'a> {
     users: &'a [&'a str],
 }
+#[derive(Serialize)]
+struct TrustedPublishingReq<'a> {
+    jwt: &'a str,
+}
+#[derive(Deserialize)]
+struct TrustedPublishingToken {
+    token: String,
+}
 #[derive(Deserialize)]
 struct Users {
     users: Vec<User>,
@@ -336,11 +350,21 @@ impl Registry {
 
     pub fn search(&mut self, query: &str, limit: u32) -> Result<(Vec<Crate>, u32)> {
         let formatted_query = percent_encode(query.as_bytes(), NON_ALPHANUMERIC);
-        let body = self.req(
-            &format!("/crates?q={}&per_page={}", formatted_query, limit),
-            None,
-            Auth::Unauthorized,
-        )?;
+        self.search_req(&format!("/crates?q={}&per_page={}", formatted_query, limit))
+    }
+
+    /// Like [`Registry::search`], but gets the `page`th page of `limit`
+    /// results, starting at 1.
+    pub fn search_page(&mut self, query: &str, limit: u32, page: u32) -> Result<(Vec<Crate>, u32)> {
+        let formatted_query = percent_encode(query.as_bytes(), NON_ALPHANUMERIC);
+        self.search_req(&format!(
+            "/crates?q={}&per_page={}&page={}",
+            formatted_query, limit, page
+        ))
+    }
+
+    fn search_req(&mut self, path: &str) -> Result<(Vec<Crate>, u32)> {
+        let body = self.req(path, None, Auth::Unauthorized)?;
 
         let crates = serde_json::from_str::<Crates>(&body)?;
         Ok((crates.crates, crates.meta.total))
@@ -358,6 +382,28 @@ impl Registry {
         Ok(())
     }
 
+    /// Exchanges the OIDC token `jwt` of a CI provider for a short-lived
+    /// token of a trusted publisher.
+    pub fn trusted_publishing_token(&mut self, jwt: &str) -> Result<String> {
+        let body = serde_json::to_string(&TrustedPublishingReq { jwt })?;
+        self.handle.custom_request("POST")?;
+        let body = self.req(
+            "/trusted_publishing/tokens",
+            Some(body.as_bytes()),
+            Auth::Unauthorized,
+        )?;
+        Ok(serde_json::from_str::<TrustedPublishingToken>(&body)?.token)
+    }
+
+    /// Revokes the short-lived token of a trusted publisher this registry
+    /// uses.
+    pub fn revoke_trusted_publishing_token(&mut self) -> Result<()> {
+        // The handle may still be set up to upload the body of `publish`.
+        self.handle.upload(false)?;
+        self.delete("/trusted_publishing/tokens", None)?;
+        Ok(())
+    }
+
     fn put(&mut self, path: &str, b: &[u8]) -> Result<String> {
         self.handle.put(true)?;
         self.req(path, Some(b), Auth::Authorized)
@@ -1,6 +1,7 @@
 use anyhow::{anyhow, Context as _};
 use cargo::core::shell::Shell;
 use cargo::core::{features, CliUnstable};
+use cargo::util::config::schema;
 use cargo::{self, drop_print, drop_println, CargoResult, CliResult, Config};
 use clap::{Arg, ArgMatches};
 use itertools::Itertools;
@@ -11,6 +12,7 @@ use std::fmt::Write;
 
 use super::commands;
 use super::list_commands;
+use super::plugin_manifest;
 use crate::command_prelude::*;
 use cargo::core::features::HIDDEN;
 
@@ -117,6 +119,51 @@ Run with 'cargo -Z [FLAG] [COMMAND]'",
         return Ok(());
     }
 
+    let explain_config = expanded_args.get_one::<String>("explain-config");
+    if explain_config.is_some() || expanded_args.flag("schema-json") {
+        let unstable_options = config.nightly_features_allowed
+            && expanded_args
+                .get_many::<String>("unstable-features")
+                .map_or(false, |mut z| z.any(|value| value == "unstable-options"));
+        if !unstable_options {
+            let flag = if explain_config.is_some() {
+                "--explain-config"
+            } else {
+                "--schema-json"
+            };
+            return Err(anyhow::format_err!(
+                "the `{flag}` flag is unstable, pass `-Z unstable-options` to enable it"
+            )
+            .into());
+        }
+        explain_config_key(config, explain_config, expanded_args.flag("schema-json"))?;
+        return Ok(());
+    }
+
+    // `-Z` flags aren't parsed into the config until a command runs, so check
+    // for `-Zplugin-manifest` directly, like `-C` does.
+    let plugin_manifest_enabled = config.nightly_features_allowed
+        && expanded_args
+            .get_many::<String>("unstable-features")
+            .map_or(false, |mut z| z.any(|value| value == "plugin-manifest"));
+
+    if let Some(name) = expanded_args.get_one::<String>("subcommand-completions") {
+        if !plugin_manifest_enabled {
+            return Err(anyhow::format_err!(
+                "the `--subcommand-completions` flag is unstable, \
+                 pass `-Z plugin-manifest` to enable it"
+            )
+            .into());
+        }
+        let manifest = plugin_manifest(config);
+        if let Some(info) = manifest.subcommands.get(name) {
+            for completion in &info.completions {
+                drop_println!(config, "{}", completion);
+            }
+        }
+        return Ok(());
+    }
+
     if expanded_args.flag("list") {
         // Maps from commonly known external commands (not builtin to cargo)
         // to their description, for the help page. Reserved for external
@@ -132,6 +179,11 @@ Run with 'cargo -Z [FLAG] [COMMAND]'",
                 "Formats all bin and lib files of the current crate using rustfmt.",
             ),
         ]);
+        let manifest = if plugin_manifest_enabled {
+            plugin_manifest(config)
+        } else {
+            Default::default()
+        };
         drop_println!(config, "Installed Commands:");
         for (name, command) in list_commands(config) {
             let known_external_desc = known_external_command_descriptions.get(name.as_str());
@@ -146,7 +198,25 @@ Run with 'cargo -Z [FLAG] [COMMAND]'",
                     drop_println!(config, "    {:<20} {}", name, summary);
                 }
                 CommandInfo::External { path } => {
-                    if let Some(desc) = known_external_desc {
+                    let info = manifest.subcommands.get(&name);
+                    let plugin_desc = info.and_then(|info| {
+                        let mut desc = info
+                            .description
+                            .as_deref()
+                            .and_then(|desc| desc.lines().next())
+                            .unwrap_or_default()
+                            .to_string();
+                        if let Some(min) = info.unmet_min_cargo_version() {
+                            if !desc.is_empty() {
+                                desc.push(' ');
+                            }
+                            desc.push_str(&format!("(requires cargo {min})"));
+                        }
+                        (!desc.is_empty()).then_some(desc)
+                    });
+                    if let Some(desc) = plugin_desc {
+                        drop_println!(config, "    {:<20} {}", name, desc);
+                    } else if let Some(desc) = known_external_desc {
                         drop_println!(config, "    {:<20} {}", name, desc);
                     } else if is_verbose {
                         drop_println!(config, "    {:<20} {}", name, path.display());
@@ -177,9 +247,27 @@ Run with 'cargo -Z [FLAG] [COMMAND]'",
     };
     let exec = Exec::infer(cmd)?;
     config_configure(config, &expanded_args, subcommand_args, global_args, &exec)?;
+    config.set_subcommand(cmd);
     super::init_git(config);
+    if config.cli_unstable().cancellation {
+        config.cancellation().cancel_on_ctrl_c();
+    }
 
-    exec.exec(config, subcommand_args)
+    if expanded_args.flag("network-report") {
+        if !config.cli_unstable().unstable_options {
+            return Err(anyhow::format_err!(
+                "the `--network-report` flag is unstable, pass `-Z unstable-options` to enable it"
+            )
+            .into());
+        }
+        config.enable_network_report();
+    }
+
+    let result = exec.exec(config, subcommand_args);
+    if let Some(report) = config.network_report() {
+        report.print(&mut config.shell())?;
+    }
+    result
 }
 
 pub fn get_version_string(is_verbose: bool) -> String {
@@ -249,6 +337,42 @@ fn add_ssl(version_string: &mut String) {
     }
 }
 
+/// Prints the schema of the config key `key` for `--explain-config`, or of
+/// the whole config for `--schema-json`, from [`schema::known_keys`].
+fn explain_config_key(config: &Config, key: Option<&String>, json: bool) -> CargoResult<()> {
+    let Some(key) = key else {
+        let schema = serde_json::to_string(&schema::json_schema())?;
+        drop_println!(config, "{}", schema);
+        return Ok(());
+    };
+    let Some(key_schema) = schema::find(key) else {
+        anyhow::bail!(
+            "unknown config key `{key}`\n\n\
+             See https://doc.rust-lang.org/nightly/cargo/reference/config.html \
+             for the known keys."
+        );
+    };
+    if json {
+        let schema = serde_json::to_string(&key_schema.json_schema())?;
+        drop_println!(config, "{}", schema);
+        return Ok(());
+    }
+    drop_println!(config, "{}", key_schema.key);
+    if key_schema.deprecated {
+        drop_println!(config, "deprecated, this key is no longer used");
+    } else {
+        drop_println!(config, "type: {}", key_schema.ty);
+    }
+    if let Some(default) = &key_schema.default {
+        drop_println!(config, "default: {}", default);
+    }
+    if !key_schema.env.is_empty() {
+        drop_println!(config, "environment: {}", key_schema.env.join(", "));
+    }
+    drop_println!(config, "\n{}", key_schema.doc);
+    Ok(())
+}
+
 /// Expands aliases recursively to collect all the command line arguments.
 ///
 /// [`GlobalArgs`] need to be extracted before expanding aliases because the
@@ -411,6 +535,19 @@ fn config_configure(
         &unstable_flags,
         &config_args,
     )?;
+    let output_width = args
+        .get_one::<u32>("output-width")
+        .copied()
+        .or(global_args.output_width);
+    if let Some(width) = output_width {
+        if !config.cli_unstable().output_width {
+            return Err(anyhow::format_err!(
+                "the `--output-width` flag is unstable, pass `-Z output-width` to enable it"
+            )
+            .into());
+        }
+        config.shell().set_output_width(Some(width as usize));
+    }
     Ok(())
 }
 
@@ -480,6 +617,7 @@ struct GlobalArgs {
     verbose: u32,
     quiet: bool,
     color: Option<String>,
+    output_width: Option<u32>,
     frozen: bool,
     locked: bool,
     offline: bool,
@@ -493,6 +631,7 @@ impl GlobalArgs {
             verbose: args.verbose(),
             quiet: args.flag("quiet"),
             color: args.get_one::<String>("color").cloned(),
+            output_width: args.get_one::<u32>("output-width").copied(),
             frozen: args.flag("frozen"),
             locked: args.flag("locked"),
             offline: args.flag("offline"),
@@ -563,6 +702,33 @@ See 'cargo help <command>' for more information on a specific command.\n",
         .arg(flag("version", "Print version info and exit").short('V'))
         .arg(flag("list", "List installed commands"))
         .arg(opt("explain", "Run `rustc --explain CODE`").value_name("CODE"))
+        .arg(
+            opt(
+                "explain-config",
+                "Print the type, default and documentation of a config key (unstable)",
+            )
+            .value_name("KEY")
+            .hide(true),
+        )
+        .arg(
+            flag(
+                "schema-json",
+                "Print the JSON schema of the config (unstable)",
+            )
+            .hide(true),
+        )
+        .arg(
+            opt(
+                "subcommand-completions",
+                "Print the completions of an external subcommand from `plugins.toml`",
+            )
+            .value_name("COMMAND")
+            .hide(true),
+        )
+        .arg(flag(
+            "network-report",
+            "Print statistics about the network requests of the command (unstable)",
+        ))
         .arg(
             opt(
                 "verbose",
@@ -578,6 +744,14 @@ See 'cargo help <command>' for more information on a specific command.\n",
                 .value_name("WHEN")
                 .global(true),
         )
+        .arg(
+            opt(
+                "output-width",
+                "Width to wrap diagnostics to, instead of the width of the terminal (unstable)",
+            )
+            .value_name("WIDTH")
+            .value_parser(clap::value_parser!(u32).range(1..)),
+        )
         .arg(
             Arg::new("directory")
                 .help("Change to DIRECTORY before doing anything (nightly-only)")
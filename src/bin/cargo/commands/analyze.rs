@@ -0,0 +1,107 @@
+use crate::command_prelude::*;
+
+use cargo::ops::{self, CompileFilter, UnusedDepsOptions};
+
+pub fn cli() -> Command {
+    subcommand("analyze")
+        .about("Analyze the packages of the workspace (unstable)")
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .subcommand(
+            subcommand("msrv")
+                .about(
+                    "Verify that the dependencies of the package support its `rust-version` (unstable)",
+                )
+                .arg_quiet()
+                .arg_package_spec(
+                    "Package(s) to verify",
+                    "Verify all packages in the workspace",
+                    "Exclude packages from the verification",
+                )
+                .arg_features()
+                .arg_target_triple("Verify for the target triple")
+                .arg_target_dir()
+                .arg_manifest_path(),
+        )
+        .subcommand(
+            subcommand("unused-deps")
+                .about("Find dependencies that no target of the package uses (unstable)")
+                .arg_quiet()
+                .arg_package_spec(
+                    "Package(s) to analyze",
+                    "Analyze all packages in the workspace",
+                    "Exclude packages from the analysis",
+                )
+                .arg(flag(
+                    "remove",
+                    "Remove the unused dependencies from the manifests",
+                ))
+                .arg_features()
+                .arg_target_triple("Analyze for the target triple")
+                .arg_target_dir()
+                .arg_manifest_path(),
+        )
+}
+
+pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
+    match args.subcommand() {
+        Some(("msrv", args)) => msrv(config, args),
+        Some(("unused-deps", args)) => unused_deps(config, args),
+        Some((cmd, _)) => {
+            unreachable!("unexpected command {}", cmd)
+        }
+        None => {
+            unreachable!("unexpected command")
+        }
+    }
+}
+
+fn msrv(config: &Config, args: &ArgMatches) -> CliResult {
+    if !config.cli_unstable().unstable_options {
+        return Err(anyhow::format_err!(
+            "the `cargo analyze msrv` command is unstable, pass `-Z unstable-options` to enable it"
+        )
+        .into());
+    }
+    let ws = args.workspace(config)?;
+    let compile_opts = args.compile_options(
+        config,
+        CompileMode::Check { test: false },
+        Some(&ws),
+        ProfileChecking::Custom,
+    )?;
+    ops::verify_msrv(&ws, &compile_opts)?;
+    Ok(())
+}
+
+fn unused_deps(config: &Config, args: &ArgMatches) -> CliResult {
+    if !config.cli_unstable().unstable_options {
+        return Err(anyhow::format_err!(
+            "the `cargo analyze unused-deps` command is unstable, pass `-Z unstable-options` to enable it"
+        )
+        .into());
+    }
+    let ws = args.workspace(config)?;
+    let mut compile_opts = args.compile_options(
+        config,
+        CompileMode::Check { test: false },
+        Some(&ws),
+        ProfileChecking::Custom,
+    )?;
+    compile_opts.filter = CompileFilter::new_all_targets();
+    let remove = args.flag("remove");
+    ops::unused_deps(
+        &ws,
+        &UnusedDepsOptions {
+            compile_opts,
+            remove,
+        },
+    )?;
+
+    if remove {
+        // Reload the workspace since we've changed dependencies
+        let ws = args.workspace(config)?;
+        ops::resolve_ws(&ws)?;
+    }
+    Ok(())
+}
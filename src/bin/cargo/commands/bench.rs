@@ -1,4 +1,5 @@
 use crate::command_prelude::*;
+use cargo::core::compiler::Workdir;
 use cargo::ops::{self, TestOptions};
 
 pub fn cli() -> Command {
@@ -47,7 +48,10 @@ pub fn cli() -> Command {
             "Run all benchmarks regardless of failure",
         ))
         .arg_unit_graph()
+        .arg_print_flags()
+        .arg_emit_env()
         .arg_timings()
+        .arg_workdir("Directory to run the benchmarks in (unstable)")
         .after_help("Run `cargo help bench` for more detailed information.\n")
 }
 
@@ -67,6 +71,8 @@ pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
         no_run: args.flag("no-run"),
         no_fail_fast: args.flag("no-fail-fast"),
         compile_opts,
+        workdir: args.workdir(config)?.unwrap_or(Workdir::PackageRoot),
+        list_binaries: false,
     };
 
     let bench_args = args.get_one::<String>("BENCHNAME").into_iter();
@@ -1,5 +1,6 @@
 use crate::command_prelude::*;
 
+use cargo::core::compiler::ExportDirs;
 use cargo::ops;
 
 pub fn cli() -> Command {
@@ -31,6 +32,7 @@ pub fn cli() -> Command {
         .arg_features()
         .arg_target_triple("Build for the target triple")
         .arg_target_dir()
+        .arg_dep_info_basedir()
         .arg(
             opt(
                 "out-dir",
@@ -38,11 +40,17 @@ pub fn cli() -> Command {
             )
             .value_name("PATH"),
         )
+        .arg(flag(
+            "report-sizes",
+            "Print the sizes of the built binaries and what is linked into them (unstable)",
+        ))
         .arg_manifest_path()
         .arg_ignore_rust_version()
         .arg_message_format()
         .arg_build_plan()
         .arg_unit_graph()
+        .arg_print_flags()
+        .arg_emit_env()
         .arg_future_incompat_report()
         .arg_timings()
         .after_help("Run `cargo help build` for more detailed information.\n")
@@ -58,16 +66,25 @@ pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
     )?;
 
     if let Some(out_dir) = args.value_of_path("out-dir", config) {
-        compile_opts.build_config.export_dir = Some(out_dir);
+        compile_opts.build_config.export_dir = Some(ExportDirs::single(out_dir));
     } else if let Some(out_dir) = config.build_config()?.out_dir.as_ref() {
-        let out_dir = out_dir.resolve_path(config);
-        compile_opts.build_config.export_dir = Some(out_dir);
+        compile_opts.build_config.export_dir = Some(out_dir.resolve(config)?);
     }
     if compile_opts.build_config.export_dir.is_some() {
         config
             .cli_unstable()
             .fail_if_stable_opt("--out-dir", 6790)?;
     }
-    ops::compile(&ws, &compile_opts)?;
+    compile_opts.build_config.report_sizes = args.flag("report-sizes");
+    if compile_opts.build_config.report_sizes && !config.cli_unstable().unstable_options {
+        return Err(anyhow::format_err!(
+            "the `--report-sizes` flag is unstable, pass `-Z unstable-options` to enable it"
+        )
+        .into());
+    }
+    let compilation = ops::compile(&ws, &compile_opts)?;
+    if compile_opts.build_config.report_sizes {
+        compilation.report_sizes()?;
+    }
     Ok(())
 }
@@ -0,0 +1,75 @@
+use crate::command_prelude::*;
+
+use cargo::ops;
+
+pub fn cli() -> Command {
+    subcommand("bundle")
+        .about("Move the dependencies of a workspace to an offline machine (unstable)")
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .subcommand(
+            subcommand("export")
+                .about("Export the locked dependencies of the workspace into a bundle (unstable)")
+                .arg_quiet()
+                .arg(
+                    opt(
+                        "output",
+                        "Path of the bundle (`cargo-bundle.tar` by default)",
+                    )
+                    .short('o')
+                    .value_name("FILE"),
+                )
+                .arg_manifest_path(),
+        )
+        .subcommand(
+            subcommand("import")
+                .about("Import a bundle into the Cargo home directory (unstable)")
+                .arg_quiet()
+                .arg(
+                    Arg::new("file")
+                        .required(true)
+                        .value_name("FILE")
+                        .help("Path of the bundle"),
+                ),
+        )
+}
+
+pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
+    match args.subcommand() {
+        Some(("export", args)) => export(config, args),
+        Some(("import", args)) => import(config, args),
+        Some((cmd, _)) => {
+            unreachable!("unexpected command {}", cmd)
+        }
+        None => {
+            unreachable!("unexpected command")
+        }
+    }
+}
+
+fn export(config: &Config, args: &ArgMatches) -> CliResult {
+    if !config.cli_unstable().unstable_options {
+        return Err(anyhow::format_err!(
+            "the `cargo bundle export` command is unstable, pass `-Z unstable-options` to enable it"
+        )
+        .into());
+    }
+    let ws = args.workspace(config)?;
+    let output = args
+        .value_of_path("output", config)
+        .unwrap_or_else(|| config.cwd().join("cargo-bundle.tar"));
+    ops::bundle_export(&ws, &output)?;
+    Ok(())
+}
+
+fn import(config: &Config, args: &ArgMatches) -> CliResult {
+    if !config.cli_unstable().unstable_options {
+        return Err(anyhow::format_err!(
+            "the `cargo bundle import` command is unstable, pass `-Z unstable-options` to enable it"
+        )
+        .into());
+    }
+    let path = args.value_of_path("file", config).unwrap();
+    ops::bundle_import(config, &path)?;
+    Ok(())
+}
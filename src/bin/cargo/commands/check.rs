@@ -29,12 +29,30 @@ pub fn cli() -> Command {
         .arg_release("Check artifacts in release mode, with optimizations")
         .arg_profile("Check artifacts with the specified profile")
         .arg_features()
+        .arg(
+            flag(
+                "all-features-matrix",
+                "Check every combination of features of the selected packages (unstable)",
+            )
+            .conflicts_with_all(&["features", "all-features", "no-default-features"]),
+        )
+        .arg(
+            opt(
+                "matrix-depth",
+                "Only check combinations of at most N features with --all-features-matrix (unstable)",
+            )
+            .value_name("N")
+            .value_parser(clap::value_parser!(u32).range(1..))
+            .requires("all-features-matrix"),
+        )
         .arg_target_triple("Check for the target triple")
         .arg_target_dir()
         .arg_manifest_path()
         .arg_ignore_rust_version()
         .arg_message_format()
         .arg_unit_graph()
+        .arg_print_flags()
+        .arg_emit_env()
         .arg_future_incompat_report()
         .arg_timings()
         .after_help("Run `cargo help check` for more detailed information.\n")
@@ -51,6 +69,17 @@ pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
     let compile_opts =
         args.compile_options(config, mode, Some(&ws), ProfileChecking::LegacyTestOnly)?;
 
-    ops::compile(&ws, &compile_opts)?;
+    if args.flag("all-features-matrix") {
+        if !config.cli_unstable().unstable_options {
+            return Err(anyhow::format_err!(
+                "the `--all-features-matrix` flag is unstable, pass `-Z unstable-options` to enable it"
+            )
+            .into());
+        }
+        let max_depth = args.get_one::<u32>("matrix-depth").map(|&d| d as usize);
+        ops::compile_feature_matrix(&ws, &compile_opts, max_depth)?;
+    } else {
+        ops::compile(&ws, &compile_opts)?;
+    }
     Ok(())
 }
@@ -14,6 +14,13 @@ pub fn cli() -> Command {
         .arg_release("Whether or not to clean release artifacts")
         .arg_profile("Clean artifacts of the specified profile")
         .arg_doc("Whether or not to clean just the documentation directory")
+        .arg(
+            flag(
+                "incremental-only",
+                "Whether or not to clean just the incremental compilation data (unstable)",
+            )
+            .conflicts_with_all(["doc", "package"]),
+        )
         .after_help("Run `cargo help clean` for more detailed information.\n")
 }
 
@@ -24,6 +31,13 @@ pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
         print_available_packages(&ws)?;
     }
 
+    if args.flag("incremental-only") && !config.cli_unstable().unstable_options {
+        return Err(anyhow::format_err!(
+            "the `--incremental-only` flag is unstable, pass `-Z unstable-options` to enable it"
+        )
+        .into());
+    }
+
     let opts = CleanOptions {
         config,
         spec: values(args, "package"),
@@ -31,6 +45,7 @@ pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
         requested_profile: args.get_profile_name(config, "dev", ProfileChecking::Custom)?,
         profile_specified: args.contains_id("profile") || args.flag("release"),
         doc: args.flag("doc"),
+        incremental_only: args.flag("incremental-only"),
     };
     ops::clean(&ws, &opts)?;
     Ok(())
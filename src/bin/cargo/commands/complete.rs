@@ -0,0 +1,83 @@
+use crate::command_prelude::*;
+
+use std::collections::BTreeSet;
+
+use cargo::core::Package;
+use cargo::drop_println;
+
+pub fn cli() -> Command {
+    subcommand("complete")
+        .about("Print values for the shell completions of `cargo completions`")
+        .hide(true)
+        .arg(flag("packages", "List the packages of the workspace"))
+        .arg(
+            opt("targets", "List the targets of the given kind")
+                .value_name("KIND")
+                .value_parser(["bin", "example", "test", "bench"]),
+        )
+        .arg(flag("features", "List the features of the packages"))
+        .arg(
+            opt(
+                "package",
+                "Only list the targets or features of this package",
+            )
+            .short('p')
+            .value_name("SPEC"),
+        )
+        .group(
+            clap::ArgGroup::new("values")
+                .args(["packages", "targets", "features"])
+                .required(true),
+        )
+        .arg_manifest_path()
+}
+
+pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
+    if !config.cli_unstable().unstable_options {
+        return Err(anyhow::format_err!(
+            "the `cargo complete` command is unstable, pass `-Z unstable-options` to enable it"
+        )
+        .into());
+    }
+    let ws = args.workspace(config)?;
+
+    // Targets and features of the package given with `-p`, the current one,
+    // or else all of the workspace.
+    let packages: Vec<&Package> = match args.get_one::<String>("package") {
+        Some(name) => ws
+            .members()
+            .filter(|pkg| pkg.name() == name.as_str())
+            .collect(),
+        None => match ws.current_opt() {
+            Some(pkg) => vec![pkg],
+            None => ws.members().collect(),
+        },
+    };
+
+    let values: BTreeSet<String> = if args.flag("packages") {
+        ws.members().map(|pkg| pkg.name().to_string()).collect()
+    } else if let Some(kind) = args.get_one::<String>("targets") {
+        packages
+            .iter()
+            .flat_map(|pkg| pkg.targets())
+            .filter(|target| match kind.as_str() {
+                "bin" => target.is_bin(),
+                "example" => target.is_example(),
+                "test" => target.is_test(),
+                "bench" => target.is_bench(),
+                _ => unreachable!("unexpected kind `{kind}`"),
+            })
+            .map(|target| target.name().to_string())
+            .collect()
+    } else {
+        packages
+            .iter()
+            .flat_map(|pkg| pkg.summary().features().keys())
+            .map(|feature| feature.to_string())
+            .collect()
+    };
+    for value in values {
+        drop_println!(config, "{}", value);
+    }
+    Ok(())
+}
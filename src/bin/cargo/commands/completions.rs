@@ -0,0 +1,84 @@
+use crate::command_prelude::*;
+
+use clap_complete::Shell;
+
+pub fn cli() -> Command {
+    subcommand("completions")
+        .about("Generate shell completions for Cargo (unstable)")
+        .arg(
+            Arg::new("shell")
+                .value_name("SHELL")
+                .required(true)
+                .value_parser(["bash", "zsh", "fish", "powershell"])
+                .help("The shell to generate the completions for"),
+        )
+        .arg_quiet()
+}
+
+pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
+    if !config.cli_unstable().unstable_options {
+        return Err(anyhow::format_err!(
+            "the `cargo completions` command is unstable, pass `-Z unstable-options` to enable it"
+        )
+        .into());
+    }
+    let (shell, dynamic) = match args.get_one::<String>("shell").unwrap().as_str() {
+        "bash" => (Shell::Bash, BASH_DYNAMIC),
+        "zsh" => (Shell::Zsh, ZSH_DYNAMIC),
+        "fish" => (Shell::Fish, FISH_DYNAMIC),
+        "powershell" => (Shell::PowerShell, ""),
+        shell => unreachable!("unexpected shell `{shell}`"),
+    };
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut crate::cli::cli(), "cargo", &mut script);
+    script.extend_from_slice(dynamic.as_bytes());
+    config.shell().out().write_all(&script)?;
+    Ok(())
+}
+
+// The scripts generated from the definitions of the commands only know the
+// options, these complete their values from the workspace with `cargo
+// complete`.
+
+const BASH_DYNAMIC: &str = r#"
+_cargo_dynamic() {
+    local cur="${COMP_WORDS[COMP_CWORD]}" prev="${COMP_WORDS[COMP_CWORD-1]}" values
+    case "$prev" in
+        -p|--package) values="$(cargo -Zunstable-options complete --packages 2>/dev/null)" ;;
+        --bin) values="$(cargo -Zunstable-options complete --targets bin 2>/dev/null)" ;;
+        --example) values="$(cargo -Zunstable-options complete --targets example 2>/dev/null)" ;;
+        --test) values="$(cargo -Zunstable-options complete --targets test 2>/dev/null)" ;;
+        --bench) values="$(cargo -Zunstable-options complete --targets bench 2>/dev/null)" ;;
+        -F|--features) values="$(cargo -Zunstable-options complete --features 2>/dev/null)" ;;
+        *) _cargo "$@"; return ;;
+    esac
+    COMPREPLY=( $(compgen -W "$values" -- "$cur") )
+}
+complete -F _cargo_dynamic -o bashdefault -o default cargo
+"#;
+
+const ZSH_DYNAMIC: &str = r#"
+_cargo_dynamic() {
+    local -a values
+    case "${words[CURRENT-1]}" in
+        -p|--package) values=( ${(f)"$(cargo -Zunstable-options complete --packages 2>/dev/null)"} ) ;;
+        --bin) values=( ${(f)"$(cargo -Zunstable-options complete --targets bin 2>/dev/null)"} ) ;;
+        --example) values=( ${(f)"$(cargo -Zunstable-options complete --targets example 2>/dev/null)"} ) ;;
+        --test) values=( ${(f)"$(cargo -Zunstable-options complete --targets test 2>/dev/null)"} ) ;;
+        --bench) values=( ${(f)"$(cargo -Zunstable-options complete --targets bench 2>/dev/null)"} ) ;;
+        -F|--features) values=( ${(f)"$(cargo -Zunstable-options complete --features 2>/dev/null)"} ) ;;
+        *) _cargo "$@"; return ;;
+    esac
+    compadd -a values
+}
+compdef _cargo_dynamic cargo
+"#;
+
+const FISH_DYNAMIC: &str = r#"
+complete -c cargo -s p -l package -x -a '(cargo -Zunstable-options complete --packages 2>/dev/null)'
+complete -c cargo -l bin -x -a '(cargo -Zunstable-options complete --targets bin 2>/dev/null)'
+complete -c cargo -l example -x -a '(cargo -Zunstable-options complete --targets example 2>/dev/null)'
+complete -c cargo -l test -x -a '(cargo -Zunstable-options complete --targets test 2>/dev/null)'
+complete -c cargo -l bench -x -a '(cargo -Zunstable-options complete --targets bench 2>/dev/null)'
+complete -c cargo -s F -l features -x -a '(cargo -Zunstable-options complete --features 2>/dev/null)'
+"#;
@@ -22,6 +22,14 @@ pub fn cli() -> Command {
             "Don't build documentation for dependencies",
         ))
         .arg(flag("document-private-items", "Document private items"))
+        .arg(flag(
+            "deny-warnings",
+            "Fail if rustdoc emits warnings for the documented packages (unstable)",
+        ))
+        .arg(flag(
+            "show-coverage",
+            "Report how many items of the packages are documented instead of documenting them (unstable)",
+        ))
         .arg_jobs()
         .arg_targets_lib_bin_example(
             "Document only this package's library",
@@ -39,18 +47,33 @@ pub fn cli() -> Command {
         .arg_message_format()
         .arg_ignore_rust_version()
         .arg_unit_graph()
+        .arg_print_flags()
+        .arg_emit_env()
         .arg_timings()
         .after_help("Run `cargo help doc` for more detailed information.\n")
 }
 
 pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
     let ws = args.workspace(config)?;
+    for flag in ["deny-warnings", "show-coverage"] {
+        if args.flag(flag) && !config.cli_unstable().unstable_options {
+            return Err(anyhow::format_err!(
+                "the `--{flag}` flag is unstable, pass `-Z unstable-options` to enable it"
+            )
+            .into());
+        }
+    }
+    if args.flag("show-coverage") && args.flag("open") {
+        return Err(anyhow::format_err!("`--open` can't be used with `--show-coverage`").into());
+    }
     let mode = CompileMode::Doc {
         deps: !args.flag("no-deps"),
     };
     let mut compile_opts =
         args.compile_options(config, mode, Some(&ws), ProfileChecking::Custom)?;
     compile_opts.rustdoc_document_private_items = args.flag("document-private-items");
+    compile_opts.build_config.rustdoc_deny_warnings = args.flag("deny-warnings");
+    compile_opts.build_config.rustdoc_coverage = args.flag("show-coverage");
 
     let doc_opts = DocOptions {
         open_result: args.flag("open"),
@@ -9,15 +9,30 @@ pub fn cli() -> Command {
         .arg_quiet()
         .arg_manifest_path()
         .arg_target_triple("Fetch dependencies for the target triple")
+        .arg_dry_run("Report what would be downloaded without downloading it (unstable)")
+        .arg(flag(
+            "json",
+            "Print a JSON line for each needed package (unstable)",
+        ))
         .after_help("Run `cargo help fetch` for more detailed information.\n")
 }
 
 pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
     let ws = args.workspace(config)?;
 
+    for flag in ["dry-run", "json"] {
+        if args.flag(flag) && !config.cli_unstable().unstable_options {
+            return Err(anyhow::format_err!(
+                "the `--{flag}` flag is unstable, pass `-Z unstable-options` to enable it"
+            )
+            .into());
+        }
+    }
     let opts = FetchOptions {
         config,
         targets: args.targets(),
+        dry_run: args.dry_run(),
+        json: args.flag("json"),
     };
     let _ = ops::fetch(&ws, &opts)?;
     Ok(())
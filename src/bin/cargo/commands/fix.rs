@@ -1,5 +1,6 @@
 use crate::command_prelude::*;
 
+use cargo::core::Edition;
 use cargo::ops;
 
 pub fn cli() -> Command {
@@ -36,6 +37,21 @@ pub fn cli() -> Command {
             "Fix code even if it already has compiler errors",
         ))
         .arg(flag("edition", "Fix in preparation for the next edition"))
+        .arg(
+            opt(
+                "to-edition",
+                "Migrate through the editions up to this edition, updating the manifests (unstable)",
+            )
+            .value_name("YEAR")
+            .value_parser(Edition::CLI_VALUES),
+        )
+        .arg(
+            flag(
+                "replace-to-patch",
+                "Rewrite the `[replace]` table of the workspace into `[patch]` tables (unstable)",
+            )
+            .conflicts_with_all(["edition", "to-edition", "edition-idioms", "broken-code"]),
+        )
         .arg(flag(
             "edition-idioms",
             "Fix warnings to migrate to the idioms of an edition",
@@ -76,10 +92,31 @@ pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
         opts.filter = ops::CompileFilter::new_all_targets();
     }
 
+    let to_edition = args
+        .get_one::<String>("to-edition")
+        .map(|edition| edition.parse::<Edition>())
+        .transpose()?;
+    if to_edition.is_some() && !config.cli_unstable().unstable_options {
+        return Err(anyhow::format_err!(
+            "the `--to-edition` flag is unstable, pass `-Z unstable-options` to enable it"
+        )
+        .into());
+    }
+
+    let replace_to_patch = args.flag("replace-to-patch");
+    if replace_to_patch && !config.cli_unstable().unstable_options {
+        return Err(anyhow::format_err!(
+            "the `--replace-to-patch` flag is unstable, pass `-Z unstable-options` to enable it"
+        )
+        .into());
+    }
+
     ops::fix(
         &ws,
         &mut ops::FixOptions {
-            edition: args.flag("edition"),
+            edition: args.flag("edition") || to_edition.is_some(),
+            to_edition,
+            replace_to_patch,
             idioms: args.flag("edition-idioms"),
             compile_opts: opts,
             allow_dirty: args.flag("allow-dirty"),
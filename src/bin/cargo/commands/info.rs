@@ -0,0 +1,36 @@
+use crate::command_prelude::*;
+
+use cargo::ops;
+
+pub fn cli() -> Command {
+    subcommand("info")
+        .about("Display information about a package (unstable)")
+        .arg_quiet()
+        .arg(
+            Arg::new("package")
+                .required(true)
+                .value_name("SPEC")
+                .help("Package to inspect, as `name` or `name@version`"),
+        )
+        .arg_index()
+        .arg(opt("registry", "Registry to use").value_name("REGISTRY"))
+}
+
+pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
+    if !config.cli_unstable().unstable_options {
+        return Err(anyhow::format_err!(
+            "the `cargo info` command is unstable, pass `-Z unstable-options` to enable it"
+        )
+        .into());
+    }
+    let registry = args.registry(config)?;
+    let index = args.index()?;
+    // Outside of a workspace, every package is looked up in the registry.
+    let ws = match args.root_manifest(config) {
+        Ok(_) => Some(args.workspace(config)?),
+        Err(_) => None,
+    };
+    let spec = args.get_one::<String>("package").unwrap();
+    ops::info(spec, config, ws.as_ref(), index, registry)?;
+    Ok(())
+}
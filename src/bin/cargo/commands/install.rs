@@ -51,6 +51,22 @@ pub fn cli() -> Command {
             "list",
             "list all installed packages and their versions",
         ))
+        .arg(
+            flag(
+                "repair",
+                "Reinstall the binaries deleted or modified outside of Cargo (unstable)",
+            )
+            .conflicts_with_all(&[
+                "crate", "list", "git", "path", "index", "registry", "force", "no-track",
+            ]),
+        )
+        .arg(
+            flag(
+                "from-binary-registry",
+                "Install the prebuilt binaries of the registry when available (unstable)",
+            )
+            .conflicts_with_all(&["git", "path"]),
+        )
         .arg_jobs()
         .arg(flag("force", "Force overwriting existing crates or binaries").short('f'))
         .arg(flag("no-track", "Do not save tracking information"))
@@ -175,7 +191,23 @@ pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
 
     if args.flag("list") {
         ops::install_list(root, config)?;
+    } else if args.flag("repair") {
+        if !config.cli_unstable().unstable_options {
+            return Err(anyhow!(
+                "the `--repair` flag is unstable, pass `-Z unstable-options` to enable it"
+            )
+            .into());
+        }
+        ops::install_repair(root, config, &compile_opts)?;
     } else {
+        let from_binary_registry = args.flag("from-binary-registry");
+        if from_binary_registry && !config.cli_unstable().unstable_options {
+            return Err(anyhow!(
+                "the `--from-binary-registry` flag is unstable, \
+                 pass `-Z unstable-options` to enable it"
+            )
+            .into());
+        }
         ops::install(
             config,
             root,
@@ -185,6 +217,7 @@ pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
             &compile_opts,
             args.flag("force"),
             args.flag("no-track"),
+            from_binary_registry,
         )?;
     }
     Ok(())
@@ -0,0 +1,50 @@
+use crate::command_prelude::*;
+
+use cargo::ops::{self, DedupeOptions};
+
+pub fn cli() -> Command {
+    subcommand("lockfile")
+        .about("Maintain the Cargo.lock file of the workspace (unstable)")
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .subcommand(
+            subcommand("dedupe")
+                .about("Remove duplicate versions of packages from Cargo.lock (unstable)")
+                .arg_quiet()
+                .arg(flag(
+                    "write",
+                    "Write the changes to Cargo.lock instead of only printing them",
+                ))
+                .arg_manifest_path(),
+        )
+}
+
+pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
+    match args.subcommand() {
+        Some(("dedupe", args)) => dedupe(config, args),
+        Some((cmd, _)) => {
+            unreachable!("unexpected command {}", cmd)
+        }
+        None => {
+            unreachable!("unexpected command")
+        }
+    }
+}
+
+fn dedupe(config: &Config, args: &ArgMatches) -> CliResult {
+    if !config.cli_unstable().unstable_options {
+        return Err(anyhow::format_err!(
+            "the `cargo lockfile dedupe` command is unstable, pass `-Z unstable-options` to enable it"
+        )
+        .into());
+    }
+    let ws = args.workspace(config)?;
+    ops::dedupe_lockfile(
+        &ws,
+        &DedupeOptions {
+            config,
+            write: args.flag("write"),
+        },
+    )?;
+    Ok(())
+}
@@ -15,6 +15,17 @@ pub fn cli() -> Command {
             "TRIPLE",
             "Only include resolve dependencies matching the given target-triple",
         ))
+        .arg(
+            opt(
+                "resolve-for",
+                "Report the packages, features and dependencies activated \
+                 when building for the given target-triple or `host`, \
+                 followed by comma-separated `cfg` values (unstable)",
+            )
+            .value_name("TARGET")
+            .conflicts_with_all(&["filter-platform", "no-deps"]),
+        )
+        .arg_target_cfg()
         .arg(flag(
             "no-deps",
             "Output information only about the workspace members \
@@ -43,10 +54,29 @@ pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
         Some(version) => version.parse().unwrap(),
     };
 
+    let resolve_for = args.get_one::<String>("resolve-for").cloned();
+    if resolve_for.is_some() && !config.cli_unstable().unstable_options {
+        return Err(anyhow::format_err!(
+            "the `--resolve-for` flag is unstable, pass `-Z unstable-options` to enable it"
+        )
+        .into());
+    }
+
+    let filter_platforms = args._values_of("filter-platform");
+    let cfgs = args.target_cfgs(config)?;
+    if !cfgs.is_empty() && filter_platforms.is_empty() && resolve_for.is_none() {
+        return Err(anyhow::format_err!(
+            "`--cfg` requires a target, pass `--filter-platform` or `--resolve-for`"
+        )
+        .into());
+    }
+
     let options = OutputMetadataOptions {
         cli_features: args.cli_features()?,
         no_deps: args.flag("no-deps"),
-        filter_platforms: args._values_of("filter-platform"),
+        filter_platforms,
+        resolve_for,
+        cfgs,
         version,
     };
 
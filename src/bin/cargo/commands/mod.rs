@@ -3,10 +3,14 @@ use crate::command_prelude::*;
 pub fn builtin() -> Vec<Command> {
     vec![
         add::cli(),
+        analyze::cli(),
         bench::cli(),
         build::cli(),
+        bundle::cli(),
         check::cli(),
         clean::cli(),
+        complete::cli(),
+        completions::cli(),
         config::cli(),
         doc::cli(),
         fetch::cli(),
@@ -14,9 +18,11 @@ pub fn builtin() -> Vec<Command> {
         generate_lockfile::cli(),
         git_checkout::cli(),
         help::cli(),
+        info::cli(),
         init::cli(),
         install::cli(),
         locate_project::cli(),
+        lockfile::cli(),
         login::cli(),
         logout::cli(),
         metadata::cli(),
@@ -39,6 +45,7 @@ pub fn builtin() -> Vec<Command> {
         vendor::cli(),
         verify_project::cli(),
         version::cli(),
+        why_version::cli(),
         yank::cli(),
     ]
 }
@@ -48,10 +55,14 @@ pub type Exec = fn(&mut Config, &ArgMatches) -> CliResult;
 pub fn builtin_exec(cmd: &str) -> Option<Exec> {
     let f = match cmd {
         "add" => add::exec,
+        "analyze" => analyze::exec,
         "bench" => bench::exec,
         "build" => build::exec,
+        "bundle" => bundle::exec,
         "check" => check::exec,
         "clean" => clean::exec,
+        "complete" => complete::exec,
+        "completions" => completions::exec,
         "config" => config::exec,
         "doc" => doc::exec,
         "fetch" => fetch::exec,
@@ -59,9 +70,11 @@ pub fn builtin_exec(cmd: &str) -> Option<Exec> {
         "generate-lockfile" => generate_lockfile::exec,
         "git-checkout" => git_checkout::exec,
         "help" => help::exec,
+        "info" => info::exec,
         "init" => init::exec,
         "install" => install::exec,
         "locate-project" => locate_project::exec,
+        "lockfile" => lockfile::exec,
         "login" => login::exec,
         "logout" => logout::exec,
         "metadata" => metadata::exec,
@@ -84,6 +97,7 @@ pub fn builtin_exec(cmd: &str) -> Option<Exec> {
         "vendor" => vendor::exec,
         "verify-project" => verify_project::exec,
         "version" => version::exec,
+        "why-version" => why_version::exec,
         "yank" => yank::exec,
         _ => return None,
     };
@@ -91,10 +105,14 @@ pub fn builtin_exec(cmd: &str) -> Option<Exec> {
 }
 
 pub mod add;
+pub mod analyze;
 pub mod bench;
 pub mod build;
+pub mod bundle;
 pub mod check;
 pub mod clean;
+pub mod complete;
+pub mod completions;
 pub mod config;
 pub mod doc;
 pub mod fetch;
@@ -102,9 +120,11 @@ pub mod fix;
 pub mod generate_lockfile;
 pub mod git_checkout;
 pub mod help;
+pub mod info;
 pub mod init;
 pub mod install;
 pub mod locate_project;
+pub mod lockfile;
 pub mod login;
 pub mod logout;
 pub mod metadata;
@@ -127,4 +147,5 @@ pub mod update;
 pub mod vendor;
 pub mod verify_project;
 pub mod version;
+pub mod why_version;
 pub mod yank;
@@ -25,6 +25,13 @@ pub fn cli() -> Command {
             "allow-dirty",
             "Allow dirty working directories to be packaged",
         ))
+        .arg(
+            flag(
+                "require-clean-vcs",
+                "Require the package to be committed into git without changes (unstable)",
+            )
+            .conflicts_with("allow-dirty"),
+        )
         .arg_target_triple("Build for the target triple")
         .arg_target_dir()
         .arg_features()
@@ -49,6 +56,13 @@ pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
     }
     let specs = args.packages_from_flags()?;
 
+    if args.flag("require-clean-vcs") && !config.cli_unstable().unstable_options {
+        return Err(anyhow::format_err!(
+            "the `--require-clean-vcs` flag is unstable, pass `-Z unstable-options` to enable it"
+        )
+        .into());
+    }
+
     ops::package(
         &ws,
         &PackageOpts {
@@ -57,6 +71,7 @@ pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
             list: args.flag("list"),
             check_metadata: !args.flag("no-metadata"),
             allow_dirty: args.flag("allow-dirty"),
+            require_clean_vcs: args.flag("require-clean-vcs"),
             to_package: specs,
             targets: args.targets(),
             jobs: args.jobs()?,
@@ -16,6 +16,17 @@ pub fn cli() -> Command {
             "allow-dirty",
             "Allow dirty working directories to be packaged",
         ))
+        .arg(
+            flag(
+                "require-clean-vcs",
+                "Require the package to be committed into git without changes (unstable)",
+            )
+            .conflicts_with("allow-dirty"),
+        )
+        .arg(flag(
+            "semver-check",
+            "Fail if the public API has breaking changes without a major version bump (unstable)",
+        ))
         .arg_target_triple("Build for the target triple")
         .arg_target_dir()
         .arg_package("Package to publish")
@@ -39,6 +50,20 @@ pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
     }
     let index = args.index()?;
 
+    if args.flag("require-clean-vcs") && !config.cli_unstable().unstable_options {
+        return Err(anyhow::format_err!(
+            "the `--require-clean-vcs` flag is unstable, pass `-Z unstable-options` to enable it"
+        )
+        .into());
+    }
+
+    if args.flag("semver-check") && !config.cli_unstable().unstable_options {
+        return Err(anyhow::format_err!(
+            "the `--semver-check` flag is unstable, pass `-Z unstable-options` to enable it"
+        )
+        .into());
+    }
+
     ops::publish(
         &ws,
         &PublishOpts {
@@ -49,6 +74,8 @@ pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
             index,
             verify: !args.flag("no-verify"),
             allow_dirty: args.flag("allow-dirty"),
+            require_clean_vcs: args.flag("require-clean-vcs"),
+            semver_check: args.flag("semver-check"),
             to_publish: args.packages_from_flags()?,
             targets: args.targets(),
             jobs: args.jobs()?,
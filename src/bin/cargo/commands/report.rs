@@ -1,6 +1,7 @@
 use crate::command_prelude::*;
 use cargo::core::compiler::future_incompat::{OnDiskReports, REPORT_PREAMBLE};
 use cargo::drop_println;
+use cargo::ops::{self, NoticesOptions};
 
 pub fn cli() -> Command {
     subcommand("report")
@@ -21,11 +22,61 @@ pub fn cli() -> Command {
                 )
                 .arg_package("Package to display a report for"),
         )
+        .subcommand(
+            subcommand("auto-tune")
+                .about("Explains the opt-level chosen by `auto-tune` for each package (unstable)")
+                .arg_quiet()
+                .arg_package_spec(
+                    "Package(s) to report on",
+                    "Report on all packages in the workspace",
+                    "Exclude packages from the report",
+                )
+                .arg_release("Report on the release profile")
+                .arg_profile("Report on the specified profile")
+                .arg_features()
+                .arg_target_triple("Report for the target triple")
+                .arg_target_dir()
+                .arg_manifest_path(),
+        )
+        .subcommand(
+            subcommand("native-deps")
+                .about("Reports the native libraries linked by build scripts (unstable)")
+                .arg_quiet()
+                .arg_package_spec(
+                    "Package(s) to report on",
+                    "Report on all packages in the workspace",
+                    "Exclude packages from the report",
+                )
+                .arg_release("Report on artifacts built in release mode")
+                .arg_profile("Report on artifacts built with the specified profile")
+                .arg_features()
+                .arg_target_triple("Report for the target triple")
+                .arg_target_dir()
+                .arg_manifest_path(),
+        )
+        .subcommand(
+            subcommand("notices")
+                .about("Reports the license files of the third-party dependencies (unstable)")
+                .arg_quiet()
+                .arg_package_spec(
+                    "Package(s) to report on",
+                    "Report on all packages in the workspace",
+                    "Exclude packages from the report",
+                )
+                .arg(flag("json", "Print the notices as JSON"))
+                .arg_features()
+                .arg_target_triple("Report for the target triple")
+                .arg_target_dir()
+                .arg_manifest_path(),
+        )
 }
 
 pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
     match args.subcommand() {
         Some(("future-incompatibilities", args)) => report_future_incompatibilities(config, args),
+        Some(("auto-tune", args)) => report_auto_tune(config, args),
+        Some(("native-deps", args)) => report_native_deps(config, args),
+        Some(("notices", args)) => report_notices(config, args),
         Some((cmd, _)) => {
             unreachable!("unexpected command {}", cmd)
         }
@@ -47,3 +98,63 @@ fn report_future_incompatibilities(config: &Config, args: &ArgMatches) -> CliRes
     drop(config.shell().print_ansi_stdout(report.as_bytes()));
     Ok(())
 }
+
+fn report_auto_tune(config: &Config, args: &ArgMatches) -> CliResult {
+    if !config.cli_unstable().unstable_options {
+        return Err(anyhow::format_err!(
+            "the `cargo report auto-tune` command is unstable, pass `-Z unstable-options` to enable it"
+        )
+        .into());
+    }
+    let ws = args.workspace(config)?;
+    let compile_opts = args.compile_options(
+        config,
+        CompileMode::Build,
+        Some(&ws),
+        ProfileChecking::Custom,
+    )?;
+    ops::report_auto_tune(&ws, &compile_opts)?;
+    Ok(())
+}
+
+fn report_native_deps(config: &Config, args: &ArgMatches) -> CliResult {
+    if !config.cli_unstable().unstable_options {
+        return Err(anyhow::format_err!(
+            "the `cargo report native-deps` command is unstable, pass `-Z unstable-options` to enable it"
+        )
+        .into());
+    }
+    let ws = args.workspace(config)?;
+    let compile_opts = args.compile_options(
+        config,
+        CompileMode::Build,
+        Some(&ws),
+        ProfileChecking::Custom,
+    )?;
+    ops::report_native_deps(&ws, &compile_opts)?;
+    Ok(())
+}
+
+fn report_notices(config: &Config, args: &ArgMatches) -> CliResult {
+    if !config.cli_unstable().unstable_options {
+        return Err(anyhow::format_err!(
+            "the `cargo report notices` command is unstable, pass `-Z unstable-options` to enable it"
+        )
+        .into());
+    }
+    let ws = args.workspace(config)?;
+    let compile_opts = args.compile_options(
+        config,
+        CompileMode::Build,
+        Some(&ws),
+        ProfileChecking::Custom,
+    )?;
+    ops::report_notices(
+        &ws,
+        &NoticesOptions {
+            compile_opts,
+            json: args.flag("json"),
+        },
+    )?;
+    Ok(())
+}
@@ -4,6 +4,7 @@ use std::path::Path;
 
 use crate::command_prelude::*;
 use crate::util::restricted_names::is_glob_pattern;
+use cargo::core::compiler::Workdir;
 use cargo::core::Verbosity;
 use cargo::core::Workspace;
 use cargo::ops::{self, CompileFilter, Packages};
@@ -36,8 +37,11 @@ pub fn cli() -> Command {
         .arg_manifest_path()
         .arg_message_format()
         .arg_unit_graph()
+        .arg_print_flags()
+        .arg_emit_env()
         .arg_ignore_rust_version()
         .arg_timings()
+        .arg_workdir("Directory to run the binary in (unstable)")
         .after_help("Run `cargo help run` for more detailed information.\n")
 }
 
@@ -50,6 +54,7 @@ pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
         Some(&ws),
         ProfileChecking::Custom,
     )?;
+    let workdir = args.workdir(config)?.unwrap_or(Workdir::Cwd);
 
     // Disallow `spec` to be an glob pattern
     if let Packages::Packages(opt_in) = &compile_opts.spec {
@@ -82,7 +87,8 @@ pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
         }
     };
 
-    ops::run(&ws, &compile_opts, &values_os(args, "args")).map_err(|err| to_run_error(config, err))
+    ops::run(&ws, &compile_opts, &values_os(args, "args"), workdir)
+        .map_err(|err| to_run_error(config, err))
 }
 
 /// See also `util/toml/mod.rs`s `is_embedded`
@@ -117,7 +123,8 @@ pub fn exec_manifest_command(config: &mut Config, cmd: &str, args: &[OsString])
         cargo::ops::CompileOptions::new(config, cargo::core::compiler::CompileMode::Build)?;
     compile_opts.spec = cargo::ops::Packages::Default;
 
-    cargo::ops::run(&ws, &compile_opts, args).map_err(|err| to_run_error(config, err))
+    let workdir = Workdir::from_config(config)?.unwrap_or(Workdir::Cwd);
+    cargo::ops::run(&ws, &compile_opts, args, workdir).map_err(|err| to_run_error(config, err))
 }
 
 fn to_run_error(config: &cargo::util::Config, err: anyhow::Error) -> CliError {
@@ -46,9 +46,12 @@ pub fn cli() -> Command {
             "Comma separated list of types of crates for the compiler to emit",
         ))
         .arg_target_dir()
+        .arg_dep_info_basedir()
         .arg_manifest_path()
         .arg_message_format()
         .arg_unit_graph()
+        .arg_print_flags()
+        .arg_emit_env()
         .arg_ignore_rust_version()
         .arg_future_incompat_report()
         .arg_timings()
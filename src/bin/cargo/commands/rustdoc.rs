@@ -17,6 +17,26 @@ pub fn cli() -> Command {
             "Opens the docs in a browser after the operation",
         ))
         .arg_package("Package to document")
+        .arg(flag(
+            "workspace",
+            "Document all packages in the workspace, with `--output-format json` (unstable)",
+        ))
+        .arg(
+            multi_opt(
+                "exclude",
+                "SPEC",
+                "Exclude packages from the documentation, with `--output-format json` (unstable)",
+            )
+            .requires("workspace"),
+        )
+        .arg(
+            opt(
+                "output-format",
+                "The output format of the documentation (unstable)",
+            )
+            .value_name("FMT")
+            .value_parser(["html", "json"]),
+        )
         .arg_jobs()
         .arg_targets_all(
             "Build only this package's library",
@@ -38,6 +58,8 @@ pub fn cli() -> Command {
         .arg_manifest_path()
         .arg_message_format()
         .arg_unit_graph()
+        .arg_print_flags()
+        .arg_emit_env()
         .arg_ignore_rust_version()
         .arg_timings()
         .after_help("Run `cargo help rustdoc` for more detailed information.\n")
@@ -45,13 +67,49 @@ pub fn cli() -> Command {
 
 pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
     let ws = args.workspace(config)?;
-    let mut compile_opts = args.compile_options_for_single_package(
-        config,
-        CompileMode::Doc { deps: false },
-        Some(&ws),
-        ProfileChecking::Custom,
-    )?;
+    if args.contains_id("output-format") && !config.cli_unstable().unstable_options {
+        return Err(anyhow::format_err!(
+            "the `--output-format` flag is unstable, pass `-Z unstable-options` to enable it"
+        )
+        .into());
+    }
+    let json = args.get_one::<String>("output-format").map(String::as_str) == Some("json");
     let target_args = values(args, "args");
+    let several_packages = args.flag("workspace");
+    if several_packages && !json {
+        return Err(anyhow::format_err!(
+            "`--workspace` can only be used with `--output-format json`"
+        )
+        .into());
+    }
+    if several_packages && !target_args.is_empty() {
+        return Err(anyhow::format_err!(
+            "extra rustdoc flags can only be passed when documenting a single package"
+        )
+        .into());
+    }
+    if json && args.flag("open") {
+        return Err(
+            anyhow::format_err!("`--open` can't be used with `--output-format json`").into(),
+        );
+    }
+
+    let mut compile_opts = if json && target_args.is_empty() {
+        args.compile_options(
+            config,
+            CompileMode::Doc { deps: false },
+            Some(&ws),
+            ProfileChecking::Custom,
+        )?
+    } else {
+        args.compile_options_for_single_package(
+            config,
+            CompileMode::Doc { deps: false },
+            Some(&ws),
+            ProfileChecking::Custom,
+        )?
+    };
+    compile_opts.build_config.rustdoc_json = json;
     compile_opts.target_rustdoc_args = if target_args.is_empty() {
         None
     } else {
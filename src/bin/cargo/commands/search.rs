@@ -17,6 +17,8 @@ pub fn cli() -> Command {
             )
             .value_name("LIMIT"),
         )
+        .arg(opt("page", "Page of results to show, starting at 1 (unstable)").value_name("PAGE"))
+        .arg(flag("json", "Print the results as JSON (unstable)"))
         .arg(opt("registry", "Registry to use").value_name("REGISTRY"))
         .after_help("Run `cargo help search` for more detailed information.\n")
 }
@@ -26,12 +28,26 @@ pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
     let index = args.index()?;
     let limit = args.value_of_u32("limit")?;
     let limit = min(100, limit.unwrap_or(10));
+    let page = args.value_of_u32("page")?;
+    let json = args.flag("json");
+    for (flag, used) in [("page", page.is_some()), ("json", json)] {
+        if used && !config.cli_unstable().unstable_options {
+            return Err(anyhow::format_err!(
+                "the `--{flag}` flag is unstable, pass `-Z unstable-options` to enable it"
+            )
+            .into());
+        }
+    }
+    let page = page.unwrap_or(1);
+    if page == 0 {
+        return Err(anyhow::format_err!("`--page` starts at 1").into());
+    }
     let query: Vec<&str> = args
         .get_many::<String>("query")
         .unwrap_or_default()
         .map(String::as_str)
         .collect();
     let query: String = query.join("+");
-    ops::search(&query, config, index, limit, registry)?;
+    ops::search(&query, config, index, limit, page, json, registry)?;
     Ok(())
 }
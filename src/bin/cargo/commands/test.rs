@@ -1,4 +1,5 @@
 use crate::command_prelude::*;
+use cargo::core::compiler::Workdir;
 use cargo::ops;
 
 pub fn cli() -> Command {
@@ -38,6 +39,10 @@ pub fn cli() -> Command {
         )
         .arg(flag("doc", "Test only this library's documentation"))
         .arg(flag("no-run", "Compile, but don't run tests"))
+        .arg(flag(
+            "list-binaries",
+            "Compile, but print a JSON message for each test binary instead of running them (unstable)",
+        ))
         .arg(flag("no-fail-fast", "Run all tests regardless of failure"))
         .arg_package_spec(
             "Package to run tests for",
@@ -54,8 +59,11 @@ pub fn cli() -> Command {
         .arg_ignore_rust_version()
         .arg_message_format()
         .arg_unit_graph()
+        .arg_print_flags()
+        .arg_emit_env()
         .arg_future_incompat_report()
         .arg_timings()
+        .arg_workdir("Directory to run the tests in (unstable)")
         .after_help(
             "Run `cargo help test` for more detailed information.\n\
              Run `cargo test -- --help` for test binary options.\n",
@@ -82,16 +90,33 @@ pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
     let test_args = test_args.chain(args.get_many::<String>("args").unwrap_or_default());
     let test_args = test_args.map(String::as_str).collect::<Vec<_>>();
 
-    let no_run = args.flag("no-run");
+    let list_binaries = args.flag("list-binaries");
+    if list_binaries && !config.cli_unstable().unstable_options {
+        return Err(anyhow::format_err!(
+            "the `--list-binaries` flag is unstable, pass `-Z unstable-options` to enable it"
+        )
+        .into());
+    }
+    let no_run = args.flag("no-run") || list_binaries;
     let doc = args.flag("doc");
     if doc {
+        if list_binaries {
+            return Err(anyhow::format_err!(
+                "Can't mix --doc with --list-binaries, doctests aren't compiled into test binaries"
+            )
+            .into());
+        }
         if compile_opts.filter.is_specific() {
             return Err(
                 anyhow::format_err!("Can't mix --doc with other target selecting options").into(),
             );
         }
-        if no_run {
-            return Err(anyhow::format_err!("Can't skip running doc tests with --no-run").into());
+        if no_run && !config.cli_unstable().unstable_options {
+            return Err(anyhow::format_err!(
+                "the `--no-run` flag with `--doc` is unstable, \
+                 pass `-Z unstable-options` to enable it"
+            )
+            .into());
         }
         compile_opts.build_config.mode = CompileMode::Doctest;
         compile_opts.filter = ops::CompileFilter::lib_only();
@@ -107,6 +132,8 @@ pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
         no_run,
         no_fail_fast: args.flag("no-fail-fast"),
         compile_opts,
+        workdir: args.workdir(config)?.unwrap_or(Workdir::PackageRoot),
+        list_binaries,
     };
 
     ops::run_tests(&ws, &ops, &test_args)
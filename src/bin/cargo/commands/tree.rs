@@ -30,6 +30,7 @@ pub fn cli() -> Command {
             "Filter dependencies matching the given target-triple (default host platform). \
             Pass `all` to include all targets.",
         )
+        .arg_target_cfg()
         .arg(flag("no-dev-dependencies", "Deprecated, use -e=no-dev instead").hide(true))
         .arg(
             multi_opt(
@@ -139,6 +140,10 @@ pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
         args._values_of("target")
     };
     let target = tree::Target::from_cli(targets);
+    let cfgs = args.target_cfgs(config)?;
+    if !cfgs.is_empty() && !matches!(target, tree::Target::Specific(_)) {
+        return Err(format_err!("`--cfg` requires a target, pass `--target <TRIPLE>`").into());
+    }
 
     let (edge_kinds, no_proc_macro) = parse_edge_kinds(config, args)?;
     let graph_features = edge_kinds.contains(&EdgeKind::Feature);
@@ -188,6 +193,7 @@ subtree of the package given to -p.\n\
         cli_features: args.cli_features()?,
         packages,
         target,
+        cfgs,
         edge_kinds,
         invert,
         pkgs_to_prune,
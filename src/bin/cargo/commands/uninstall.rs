@@ -1,6 +1,7 @@
 use crate::command_prelude::*;
 
 use cargo::ops;
+use cargo::util::IntoUrl;
 
 pub fn cli() -> Command {
     subcommand("uninstall")
@@ -10,6 +11,27 @@ pub fn cli() -> Command {
         .arg_package_spec_simple("Package to uninstall")
         .arg(multi_opt("bin", "NAME", "Only uninstall the binary NAME"))
         .arg(opt("root", "Directory to uninstall packages from").value_name("DIR"))
+        .arg(
+            flag("all", "Uninstall all installed packages (unstable)")
+                .conflicts_with_all(&["spec", "package", "bin", "git", "path"]),
+        )
+        .arg(
+            opt(
+                "git",
+                "Uninstall the packages installed from this git repository (unstable)",
+            )
+            .value_name("URL")
+            .conflicts_with_all(&["spec", "package", "bin", "path"]),
+        )
+        .arg(
+            opt(
+                "path",
+                "Uninstall the packages installed from this directory (unstable)",
+            )
+            .value_name("PATH")
+            .conflicts_with_all(&["spec", "package", "bin"]),
+        )
+        .arg_dry_run("List the binaries to uninstall without removing them (unstable)")
         .after_help("Run `cargo help uninstall` for more detailed information.\n")
 }
 
@@ -24,11 +46,37 @@ pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
         .into());
     }
 
+    let dry_run = args.dry_run();
+    let packages = if args.flag("all") {
+        Some(ops::InstalledPackages::All)
+    } else if let Some(url) = args.get_one::<String>("git") {
+        Some(ops::InstalledPackages::Git(url.into_url()?))
+    } else {
+        args.value_of_path("path", config)
+            .map(ops::InstalledPackages::Path)
+    };
+    if (packages.is_some() || dry_run) && !config.cli_unstable().unstable_options {
+        let flag = match packages {
+            Some(ops::InstalledPackages::All) => "all",
+            Some(ops::InstalledPackages::Git(_)) => "git",
+            Some(ops::InstalledPackages::Path(_)) => "path",
+            None => "dry-run",
+        };
+        return Err(anyhow::format_err!(
+            "the `--{flag}` flag is unstable, pass `-Z unstable-options` to enable it"
+        )
+        .into());
+    }
+    if let Some(packages) = packages {
+        ops::uninstall_installed(root, &packages, dry_run, config)?;
+        return Ok(());
+    }
+
     let specs = args
         .get_many::<String>("spec")
         .unwrap_or_else(|| args.get_many::<String>("package").unwrap_or_default())
         .map(String::as_str)
         .collect();
-    ops::uninstall(root, specs, &values(args, "bin"), config)?;
+    ops::uninstall(root, specs, &values(args, "bin"), dry_run, config)?;
     Ok(())
 }
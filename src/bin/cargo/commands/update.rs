@@ -22,6 +22,28 @@ pub fn cli() -> Command {
             .value_name("PRECISE")
             .requires("package"),
         )
+        .arg(flag(
+            "summary",
+            "Print a summary of the changes grouped by kind (unstable)",
+        ))
+        .arg(flag(
+            "json",
+            "Print the summary of the changes as JSON (unstable)",
+        ))
+        .arg(
+            flag(
+                "interactive",
+                "Choose which updates of direct dependencies to apply (unstable)",
+            )
+            .short('i')
+            .conflicts_with_all([
+                "package",
+                "aggressive",
+                "precise",
+                "dry-run",
+                "workspace",
+            ]),
+        )
         .arg_manifest_path()
         .after_help("Run `cargo help update` for more detailed information.\n")
 }
@@ -33,14 +55,29 @@ pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
         print_available_packages(&ws)?;
     }
 
+    for flag in ["summary", "json", "interactive"] {
+        if args.flag(flag) && !config.cli_unstable().unstable_options {
+            return Err(anyhow::format_err!(
+                "the `--{flag}` flag is unstable, pass `-Z unstable-options` to enable it"
+            )
+            .into());
+        }
+    }
+
     let update_opts = UpdateOptions {
         aggressive: args.flag("aggressive"),
         precise: args.get_one::<String>("precise").map(String::as_str),
         to_update: values(args, "package"),
         dry_run: args.dry_run(),
         workspace: args.flag("workspace"),
+        summary: args.flag("summary"),
+        json: args.flag("json"),
         config,
     };
-    ops::update_lockfile(&ws, &update_opts)?;
+    if args.flag("interactive") {
+        ops::update_interactive(&ws, &update_opts)?;
+    } else {
+        ops::update_lockfile(&ws, &update_opts)?;
+    }
     Ok(())
 }
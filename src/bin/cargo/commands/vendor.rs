@@ -34,6 +34,10 @@ pub fn cli() -> Command {
             "versioned-dirs",
             "Always include version in subdir name",
         ))
+        .arg(flag(
+            "verify",
+            "Check that the vendor directory matches Cargo.lock, without changing it (unstable)",
+        ))
         .arg(flag("no-merge-sources", "Not supported").hide(true))
         .arg(flag("relative-path", "Not supported").hide(true))
         .arg(flag("only-git-deps", "Not supported").hide(true))
@@ -78,6 +82,14 @@ https://github.com/rust-lang/cargo/issues/new
         .into());
     }
 
+    let verify = args.flag("verify");
+    if verify && !config.cli_unstable().unstable_options {
+        return Err(anyhow::format_err!(
+            "the `--verify` flag is unstable, pass `-Z unstable-options` to enable it"
+        )
+        .into());
+    }
+
     let ws = args.workspace(config)?;
     let path = args
         .get_one::<PathBuf>("path")
@@ -94,6 +106,7 @@ https://github.com/rust-lang/cargo/issues/new
                 .unwrap_or_default()
                 .cloned()
                 .collect(),
+            verify,
         },
     )?;
     Ok(())
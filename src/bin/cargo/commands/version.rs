@@ -1,16 +1,78 @@
 use crate::cli;
 use crate::command_prelude::*;
 
+use cargo::ops::{self, BumpLevel, BumpVersionOptions};
+
 pub fn cli() -> Command {
     subcommand("version")
         .about("Show version information")
         .arg_quiet()
+        .subcommand(
+            subcommand("bump")
+                .about("Bump the version of packages of the workspace (unstable)")
+                .arg(
+                    Arg::new("level")
+                        .value_name("LEVEL")
+                        .help("The part of the version to increment")
+                        .value_parser(["major", "minor", "patch"])
+                        .required(true),
+                )
+                .arg_quiet()
+                .arg_package_spec_no_all(
+                    "Package(s) to bump",
+                    "Bump all packages in the workspace",
+                    "Exclude packages from being bumped",
+                )
+                .arg(flag(
+                    "no-lockfile-update",
+                    "Don't update the versions of the packages in Cargo.lock",
+                ))
+                .arg_dry_run("Don't actually write the manifests")
+                .arg_manifest_path(),
+        )
         .after_help("Run `cargo help version` for more detailed information.\n")
 }
 
 pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
+    if let Some(("bump", args)) = args.subcommand() {
+        return bump(config, args);
+    }
     let verbose = args.verbose() > 0;
     let version = cli::get_version_string(verbose);
     cargo::drop_print!(config, "{}", version);
     Ok(())
 }
+
+fn bump(config: &Config, args: &ArgMatches) -> CliResult {
+    if !config.cli_unstable().unstable_options {
+        return Err(anyhow::format_err!(
+            "the `cargo version bump` command is unstable, pass `-Z unstable-options` to enable it"
+        )
+        .into());
+    }
+    let level = match args.get_one::<String>("level").map(String::as_str) {
+        Some("major") => BumpLevel::Major,
+        Some("minor") => BumpLevel::Minor,
+        Some("patch") => BumpLevel::Patch,
+        _ => unreachable!("`level` is validated by clap"),
+    };
+    let dry_run = args.dry_run();
+    let ws = args.workspace(config)?;
+    let packages = args.packages_from_flags()?.get_packages(&ws)?;
+    ops::bump_version(
+        &ws,
+        &BumpVersionOptions {
+            packages,
+            level,
+            dry_run,
+        },
+    )?;
+
+    let lockfile = ws.root().join("Cargo.lock");
+    if !dry_run && !args.flag("no-lockfile-update") && lockfile.exists() {
+        // Reload the workspace since the versions have changed.
+        let ws = args.workspace(config)?;
+        ops::resolve_ws(&ws)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use crate::command_prelude::*;
+
+use cargo::ops;
+
+pub fn cli() -> Command {
+    subcommand("why-version")
+        .about("Explain which requirements keep a package at its locked version (unstable)")
+        .arg_quiet()
+        .arg(
+            Arg::new("package")
+                .required(true)
+                .value_name("SPEC")
+                .help("Package to explain, as `name` or `name@version`"),
+        )
+        .arg_manifest_path()
+}
+
+pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
+    if !config.cli_unstable().unstable_options {
+        return Err(anyhow::format_err!(
+            "the `cargo why-version` command is unstable, pass `-Z unstable-options` to enable it"
+        )
+        .into());
+    }
+    let ws = args.workspace(config)?;
+    let spec = args.get_one::<String>("package").unwrap();
+    ops::why_version(&ws, spec)?;
+    Ok(())
+}
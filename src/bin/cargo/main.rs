@@ -2,6 +2,7 @@
 #![allow(clippy::all)]
 #![warn(clippy::disallowed_methods)]
 
+use cargo::util::config::PluginManifest;
 use cargo::util::network::http::http_handle;
 use cargo::util::network::http::needs_custom_http_transport;
 use cargo::util::toml::StringOrVec;
@@ -13,6 +14,7 @@ use std::env;
 use std::ffi::OsStr;
 use std::fs;
 use std::path::{Path, PathBuf};
+use tracing_subscriber::fmt::format::FmtSpan;
 
 mod cli;
 mod commands;
@@ -20,10 +22,7 @@ mod commands;
 use crate::command_prelude::*;
 
 fn main() {
-    #[cfg(feature = "pretty-env-logger")]
-    pretty_env_logger::init_custom_env("CARGO_LOG");
-    #[cfg(not(feature = "pretty-env-logger"))]
-    env_logger::init_from_env("CARGO_LOG");
+    setup_logger();
 
     let mut config = cli::LazyConfig::new();
 
@@ -40,6 +39,43 @@ fn main() {
     }
 }
 
+/// Displays the traces enabled by `CARGO_LOG` on stderr, as text or, with
+/// `CARGO_LOG_FORMAT=json`, as one JSON object per line. JSON traces include
+/// the spans an event happened in, and the time spent in each closed span.
+///
+/// With the `pretty-env-logger` feature, text traces are displayed by
+/// `pretty_env_logger`, which receives them as `log` records.
+fn setup_logger() {
+    #![allow(clippy::disallowed_methods)]
+
+    let format = env::var("CARGO_LOG_FORMAT");
+    if format.as_deref() == Ok("json") {
+        tracing_subscriber::fmt()
+            .with_writer(std::io::stderr)
+            .with_env_filter(tracing_subscriber::EnvFilter::from_env("CARGO_LOG"))
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+        return;
+    }
+
+    #[cfg(feature = "pretty-env-logger")]
+    pretty_env_logger::init_custom_env("CARGO_LOG");
+    #[cfg(not(feature = "pretty-env-logger"))]
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_env_filter(tracing_subscriber::EnvFilter::from_env("CARGO_LOG"))
+        .with_ansi(std::io::IsTerminal::is_terminal(&std::io::stderr()))
+        .init();
+    if let Ok(format) = format {
+        if format != "text" {
+            tracing::warn!("unknown CARGO_LOG_FORMAT `{format}`, expected `text` or `json`");
+        }
+    }
+}
+
 /// Table for defining the aliases which come builtin in `Cargo`.
 /// The contents are structured as: `(alias, aliased_command, description)`.
 const BUILTIN_ALIASES: [(&str, &str, &str); 6] = [
@@ -192,9 +228,38 @@ fn execute_external_subcommand(config: &Config, cmd: &str, args: &[&OsStr]) -> C
             return Err(CliError::new(err, 101));
         }
     };
+    if config.cli_unstable().plugin_manifest {
+        let manifest = plugin_manifest(config);
+        if let Some(min) = manifest
+            .subcommands
+            .get(cmd)
+            .and_then(|info| info.unmet_min_cargo_version())
+        {
+            let err = anyhow::format_err!(
+                "`cargo {cmd}` requires cargo {min} or newer, but this is cargo {}",
+                cargo::version().version
+            );
+            return Err(CliError::new(err, 101));
+        }
+    }
     execute_subcommand(config, Some(&command), args)
 }
 
+/// Loads `$CARGO_HOME/plugins.toml`, see [`Config::plugin_manifest`].
+///
+/// A broken manifest only loses the metadata of external subcommands, so this
+/// warns instead of failing the command.
+fn plugin_manifest(config: &Config) -> PluginManifest {
+    config.plugin_manifest().unwrap_or_else(|e| {
+        cargo::display_warning_with_error(
+            "failed to read the metadata of external subcommands",
+            &e,
+            &mut config.shell(),
+        );
+        PluginManifest::default()
+    })
+}
+
 fn execute_internal_subcommand(config: &Config, args: &[&OsStr]) -> CliResult {
     execute_subcommand(config, None, args)
 }
@@ -0,0 +1,287 @@
+//! A user-wide cache of compiled dependencies, see `-Zartifact-cache`.
+//!
+//! When a dependency from a registry has to be rebuilt, Cargo first looks
+//! for an entry keyed by the unit's [`Metadata`] and [`Fingerprint`] hash in
+//! `$CARGO_HOME/artifact-cache`. Since the fingerprint covers the compiler
+//! version, features, profile, flags and the fingerprints of all
+//! dependencies, an entry with the same key is interchangeable with what
+//! rustc would produce, so the files are copied into the target directory
+//! instead of invoking the compiler. On a miss, the outputs are stored in the
+//! cache once rustc finishes.
+//!
+//! The cache looks like this:
+//!
+//! ```text
+//! $CARGO_HOME/artifact-cache/
+//!     # Timestamp of the last garbage collection.
+//!     .last-gc
+//!     # One directory per cached unit.
+//!     $pkgname-$META-$FINGERPRINT/
+//!         # Names, sizes and checksums of the cached files.
+//!         manifest.json
+//!         # Touched every time the entry is restored.
+//!         last-use
+//!         # The rlib/rmeta files, placed in the unit's output directory.
+//!         out/
+//!         # The dep-info file, placed in the unit's fingerprint directory.
+//!         fingerprint/
+//! ```
+//!
+//! Entries are written to a temporary directory and renamed into place, so
+//! concurrent Cargo processes never observe partially written entries. The
+//! checksums are verified on every restore, and entries that fail
+//! verification are removed.
+//!
+//! [`Metadata`]: super::Metadata
+//! [`Fingerprint`]: super::fingerprint::Fingerprint
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::Context as _;
+use cargo_util::{paths, Sha256};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use super::job_queue::Work;
+use super::{fingerprint, CompileMode, Context, Unit};
+use crate::util::{CargoResult, Config};
+
+/// Entries that have not been restored for this long are removed by [`gc`].
+const MAX_UNUSED: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// How often [`gc`] actually scans the cache.
+const GC_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+const MANIFEST: &str = "manifest.json";
+const LAST_USE: &str = "last-use";
+const LAST_GC: &str = ".last-gc";
+
+/// The list of files in a cache entry.
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    files: Vec<CachedFile>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedFile {
+    /// Which directory of the unit the file belongs to.
+    dir: CachedDir,
+    /// The file name within that directory.
+    name: String,
+    size: u64,
+    sha256: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+enum CachedDir {
+    Out,
+    Fingerprint,
+}
+
+impl CachedDir {
+    fn as_str(self) -> &'static str {
+        match self {
+            CachedDir::Out => "out",
+            CachedDir::Fingerprint => "fingerprint",
+        }
+    }
+}
+
+/// Returns the root directory of the artifact cache.
+pub fn cache_dir(config: &Config) -> PathBuf {
+    config.home().join("artifact-cache").into_path_unlocked()
+}
+
+/// Returns whether the outputs of `unit` may be shared through the cache.
+///
+/// This is deliberately conservative: only libraries from registries are
+/// cached, since their sources are immutable and live outside of any
+/// workspace. Units with build scripts are skipped since their outputs may
+/// depend on paths in the target directory.
+fn is_cacheable(cx: &Context<'_, '_>, unit: &Unit) -> bool {
+    cx.bcx.config.cli_unstable().artifact_cache
+        && !cx.bcx.build_config.build_plan
+        && unit.pkg.package_id().source_id().is_registry()
+        && matches!(unit.mode, CompileMode::Build | CompileMode::Check { .. })
+        && unit.target.is_lib()
+        && !unit.target.kind().requires_upstream_objects()
+        && !unit.target.proc_macro()
+        && !cx
+            .unit_deps(unit)
+            .iter()
+            .any(|dep| dep.unit.mode.is_run_custom_build())
+}
+
+/// Wraps the `work` compiling `unit` so that it is skipped when the outputs
+/// can be restored from the cache, and stores the outputs otherwise.
+pub fn wrap(cx: &mut Context<'_, '_>, unit: &Unit, work: Work) -> CargoResult<Work> {
+    if !is_cacheable(cx, unit) {
+        return Ok(work);
+    }
+    let fingerprint = cx.fingerprints[unit].clone();
+    let prefix = format!("{}-{}", unit.pkg.name(), cx.files().metadata(unit));
+    let cache_dir = cache_dir(cx.bcx.config);
+    let out_dir = cx.files().out_dir(unit);
+    let fingerprint_dir = cx.files().fingerprint_dir(unit);
+    let dep_info = fingerprint::dep_info_loc(cx, unit);
+    let mut outputs = Vec::new();
+    for output in cx.outputs(unit)?.iter() {
+        outputs.push(output.path.clone());
+        // Pipelined builds also leave an rmeta next to the rlib, which
+        // dependents are compiled against.
+        let rmeta = output.path.with_extension("rmeta");
+        if !outputs.contains(&rmeta) {
+            outputs.push(rmeta);
+        }
+    }
+    outputs.push(dep_info);
+
+    Ok(Work::new(move |state| {
+        // The fingerprint is only final once all dependencies are built.
+        let key = format!("{prefix}-{:016x}", fingerprint.hash_u64());
+        let entry = cache_dir.join(&key);
+        match restore(&entry, &out_dir, &fingerprint_dir) {
+            Ok(true) => {
+                debug!("restored {key} from the artifact cache");
+                return Ok(());
+            }
+            Ok(false) => {}
+            Err(e) => {
+                debug!("failed to restore {key} from the artifact cache: {e:?}");
+                let _ = paths::remove_dir_all(&entry);
+            }
+        }
+        work.call(state)?;
+        if let Err(e) = store(&cache_dir, &key, &outputs, &out_dir, &fingerprint_dir) {
+            debug!("failed to store {key} in the artifact cache: {e:?}");
+        }
+        Ok(())
+    }))
+}
+
+/// Copies the files of the cache `entry` into place.
+///
+/// Returns `false` if there is no such entry. Fails without touching the
+/// destination if any file does not match its checksum.
+fn restore(entry: &Path, out_dir: &Path, fingerprint_dir: &Path) -> CargoResult<bool> {
+    let manifest = match paths::read(&entry.join(MANIFEST)) {
+        Ok(manifest) => manifest,
+        Err(_) => return Ok(false),
+    };
+    let manifest: Manifest = serde_json::from_str(&manifest)?;
+    for file in &manifest.files {
+        let src = entry.join(file.dir.as_str()).join(&file.name);
+        let sha256 = Sha256::new().update_path(&src)?.finish_hex();
+        if sha256 != file.sha256 || std::fs::metadata(&src)?.len() != file.size {
+            anyhow::bail!("checksum mismatch for `{}`", src.display());
+        }
+    }
+    for file in &manifest.files {
+        let src = entry.join(file.dir.as_str()).join(&file.name);
+        let dst_dir = match file.dir {
+            CachedDir::Out => out_dir,
+            CachedDir::Fingerprint => fingerprint_dir,
+        };
+        paths::create_dir_all(dst_dir)?;
+        paths::copy(&src, dst_dir.join(&file.name))?;
+    }
+    paths::write(entry.join(LAST_USE), b"")?;
+    Ok(true)
+}
+
+/// Stores the existing files among `outputs` as the cache entry `key`.
+fn store(
+    cache_dir: &Path,
+    key: &str,
+    outputs: &[PathBuf],
+    out_dir: &Path,
+    fingerprint_dir: &Path,
+) -> CargoResult<()> {
+    let entry = cache_dir.join(key);
+    if entry.exists() {
+        return Ok(());
+    }
+    let tmp = cache_dir.join(format!(".tmp-{key}-{}", std::process::id()));
+    let _ = paths::remove_dir_all(&tmp);
+    let mut files = Vec::new();
+    for path in outputs.iter().filter(|path| path.exists()) {
+        let (dir, parent) = if path.starts_with(fingerprint_dir) {
+            (CachedDir::Fingerprint, fingerprint_dir)
+        } else if path.starts_with(out_dir) {
+            (CachedDir::Out, out_dir)
+        } else {
+            continue;
+        };
+        // The manifest records names as strings, and an entry missing a file
+        // can't be restored, so the unit isn't cached at all then.
+        let Some(name) = path.strip_prefix(parent)?.to_str() else {
+            debug!(
+                "not storing {key} in the artifact cache, `{}` isn't UTF-8",
+                path.display()
+            );
+            let _ = paths::remove_dir_all(&tmp);
+            return Ok(());
+        };
+        let name = name.to_string();
+        let dst = tmp.join(dir.as_str()).join(&name);
+        paths::create_dir_all(dst.parent().unwrap())?;
+        let size = paths::copy(path, &dst)?;
+        let sha256 = Sha256::new().update_path(&dst)?.finish_hex();
+        files.push(CachedFile {
+            dir,
+            name,
+            size,
+            sha256,
+        });
+    }
+    let manifest = serde_json::to_string(&Manifest { files })?;
+    paths::write(tmp.join(MANIFEST), manifest)?;
+    paths::write(tmp.join(LAST_USE), b"")?;
+    // Another process may have stored the same entry in the meantime, in
+    // which case its copy is as good as ours.
+    if std::fs::rename(&tmp, &entry).is_err() {
+        paths::remove_dir_all(&tmp)?;
+    }
+    Ok(())
+}
+
+/// Removes cache entries that have not been used for a while.
+///
+/// This is cheap to call often: the cache is only scanned if the last
+/// collection happened more than a day ago.
+pub fn gc(config: &Config) -> CargoResult<()> {
+    let cache_dir = cache_dir(config);
+    let last_gc = cache_dir.join(LAST_GC);
+    let now = SystemTime::now();
+    let elapsed = |path: &Path| {
+        std::fs::metadata(path)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| now.duration_since(t).ok())
+    };
+    if !cache_dir.exists() || elapsed(&last_gc).map_or(false, |e| e < GC_INTERVAL) {
+        return Ok(());
+    }
+    for entry in std::fs::read_dir(&cache_dir)
+        .with_context(|| format!("failed to read `{}`", cache_dir.display()))?
+    {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        // Leftover temporary directories are judged by their own age.
+        let stamp = if path.join(LAST_USE).exists() {
+            path.join(LAST_USE)
+        } else {
+            path.clone()
+        };
+        if elapsed(&stamp).map_or(true, |e| e > MAX_UNUSED) {
+            debug!("removing unused artifact cache entry {}", path.display());
+            paths::remove_dir_all(&path)?;
+        }
+    }
+    paths::write(&last_gc, b"")?;
+    Ok(())
+}
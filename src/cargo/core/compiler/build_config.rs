@@ -1,14 +1,16 @@
-use crate::core::compiler::CompileKind;
+use crate::core::compiler::{CompileKind, CrateType, FileType};
 use crate::util::config::JobsConfig;
 use crate::util::interning::InternedString;
 use crate::util::{CargoResult, Config, RustfixDiagnosticServer};
 use anyhow::{bail, Context as _};
+use cargo_util::paths::LinkStrategy;
 use cargo_util::ProcessBuilder;
 use serde::ser;
 use std::cell::RefCell;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::thread::available_parallelism;
+use std::time::Duration;
 
 /// Configuration information for a rustc build.
 #[derive(Debug, Clone)]
@@ -17,6 +19,9 @@ pub struct BuildConfig {
     pub requested_kinds: Vec<CompileKind>,
     /// Number of rustc jobs to run in parallel.
     pub jobs: u32,
+    /// With `build.jobs = "auto"`, the thresholds of system load under which
+    /// fewer than `jobs` jobs run in parallel.
+    pub adaptive_jobs: Option<AdaptiveJobs>,
     /// Do not abort the build as soon as there is an error.
     pub keep_going: bool,
     /// Build profile
@@ -31,6 +36,26 @@ pub struct BuildConfig {
     pub build_plan: bool,
     /// Output the unit graph to stdout instead of actually compiling.
     pub unit_graph: bool,
+    /// Print the flags passed to the compiler for each unit instead of
+    /// actually compiling.
+    pub print_flags: bool,
+    /// Write the environment and command line of the processes run for each
+    /// unit, see
+    /// [`CompilationFiles::env_capture_path`](crate::core::compiler::CompilationFiles::env_capture_path).
+    pub emit_env: bool,
+    /// Document the root units with rustdoc's JSON output instead of HTML,
+    /// for `cargo rustdoc --output-format json`.
+    pub rustdoc_json: bool,
+    /// Report the documentation coverage of the root units with rustdoc's
+    /// `--show-coverage` instead of documenting them, for
+    /// `cargo doc --show-coverage`.
+    pub rustdoc_coverage: bool,
+    /// Deny the warnings of rustdoc for the root units, for
+    /// `cargo doc --deny-warnings`.
+    pub rustdoc_deny_warnings: bool,
+    /// Print the sizes of the final artifacts after the build, see
+    /// [`Compilation::report_sizes`](crate::core::compiler::Compilation::report_sizes).
+    pub report_sizes: bool,
     /// An optional override of the rustc process for primary units
     pub primary_unit_rustc: Option<ProcessBuilder>,
     /// A thread used by `cargo fix` to receive messages on a socket regarding
@@ -41,11 +66,23 @@ pub struct BuildConfig {
     /// as usual.
     // Note that, although the cmd-line flag name is `out-dir`, in code we use
     // `export_dir`, to avoid confusion with out dir at `target/debug/deps`.
-    pub export_dir: Option<PathBuf>,
+    pub export_dir: Option<ExportDirs>,
+    /// The directory to make the paths of dep-info files relative to, from
+    /// `--dep-info-basedir`, overriding `build.dep-info-basedir`.
+    pub dep_info_basedir: Option<String>,
     /// `true` to output a future incompatibility report at the end of the build
     pub future_incompat_report: bool,
     /// Which kinds of build timings to output (empty if none).
     pub timing_outputs: Vec<TimingOutput>,
+    /// How final artifacts are placed in the target directory, from
+    /// `build.artifact-link`.
+    pub artifact_link: LinkStrategy,
+    /// How long the build may run before it is cancelled, from
+    /// `build.timeout`.
+    pub timeout: Option<Duration>,
+    /// Link with mold or lld when they are found, from
+    /// `build.prefer-fast-linker`.
+    pub prefer_fast_linker: bool,
 }
 
 fn default_parallelism() -> CargoResult<u32> {
@@ -79,6 +116,7 @@ impl BuildConfig {
                  its environment, ignoring the `-j` parameter",
             )?;
         }
+        let mut adaptive_jobs = None;
         let jobs = match jobs.or(cfg.jobs.clone()) {
             None => default_parallelism()?,
             Some(value) => match value {
@@ -89,14 +127,46 @@ impl BuildConfig {
                 },
                 JobsConfig::String(j) => match j.as_str() {
                     "default" => default_parallelism()?,
+                    "auto" => {
+                        if !config.cli_unstable().adaptive_jobs {
+                            bail!("`jobs = \"auto\"` requires `-Zadaptive-jobs`");
+                        }
+                        let thresholds = cfg.adaptive_jobs.as_ref();
+                        adaptive_jobs = Some(AdaptiveJobs {
+                            max_load: thresholds
+                                .and_then(|t| t.max_load)
+                                .unwrap_or(AdaptiveJobs::DEFAULT_MAX_LOAD),
+                            min_free_memory: thresholds
+                                .and_then(|t| t.min_free_memory)
+                                .map_or(AdaptiveJobs::DEFAULT_MIN_FREE_MEMORY, |mib| {
+                                    mib * 1024 * 1024
+                                }),
+                        });
+                        default_parallelism()?
+                    }
                     _ => {
                         anyhow::bail!(
-			    format!("could not parse `{j}`. Number of parallel jobs should be `default` or a number."))
+			    format!("could not parse `{j}`. Number of parallel jobs should be `default`, `auto` or a number."))
                     }
                 },
             },
         };
 
+        let artifact_link = match cfg.artifact_link {
+            Some(link) if config.cli_unstable().artifact_link => link.strategy(),
+            _ => LinkStrategy::platform_default(),
+        };
+
+        let timeout = match cfg.timeout {
+            Some(secs) if config.cli_unstable().cancellation => Some(Duration::from_secs(secs)),
+            _ => None,
+        };
+
+        let prefer_fast_linker = match cfg.prefer_fast_linker {
+            Some(prefer) if config.cli_unstable().prefer_fast_linker => prefer,
+            _ => false,
+        };
+
         if config.cli_unstable().build_std.is_some() && requested_kinds[0].is_host() {
             // TODO: This should eventually be fixed.
             anyhow::bail!("-Zbuild-std requires --target");
@@ -105,6 +175,7 @@ impl BuildConfig {
         Ok(BuildConfig {
             requested_kinds,
             jobs,
+            adaptive_jobs,
             keep_going,
             requested_profile: InternedString::new("dev"),
             mode,
@@ -112,11 +183,21 @@ impl BuildConfig {
             force_rebuild: false,
             build_plan: false,
             unit_graph: false,
+            print_flags: false,
+            emit_env: false,
+            rustdoc_json: false,
+            rustdoc_coverage: false,
+            rustdoc_deny_warnings: false,
+            report_sizes: false,
             primary_unit_rustc: None,
             rustfix_diagnostic_server: Arc::new(RefCell::new(None)),
             export_dir: None,
+            dep_info_basedir: None,
             future_incompat_report: false,
             timing_outputs: Vec::new(),
+            artifact_link,
+            timeout,
+            prefer_fast_linker,
         })
     }
 
@@ -138,6 +219,71 @@ impl BuildConfig {
     }
 }
 
+/// The thresholds of `build.jobs = "auto"`, from `build.adaptive-jobs`.
+///
+/// While the system is over one of them, the job queue starts fewer jobs, see
+/// `LoadMonitor` in the job queue.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaptiveJobs {
+    /// The load average per CPU above which fewer jobs run.
+    pub max_load: f64,
+    /// The available memory, in bytes, below which fewer jobs run.
+    pub min_free_memory: u64,
+}
+
+impl AdaptiveJobs {
+    pub const DEFAULT_MAX_LOAD: f64 = 1.5;
+    pub const DEFAULT_MIN_FREE_MEMORY: u64 = 1024 * 1024 * 1024;
+}
+
+/// The directories final artifacts are copied to with `--out-dir` or
+/// `build.out-dir`.
+///
+/// An artifact goes to the most specific directory configured for it: `wasm`
+/// for anything built for a WebAssembly target, then the directory for its
+/// crate type, then `all`. Artifacts without any directory are not copied.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExportDirs {
+    /// Directory for every artifact without a more specific directory.
+    pub all: Option<PathBuf>,
+    pub bins: Option<PathBuf>,
+    pub cdylibs: Option<PathBuf>,
+    pub dylibs: Option<PathBuf>,
+    pub staticlibs: Option<PathBuf>,
+    pub rlibs: Option<PathBuf>,
+    /// Directory for artifacts of `wasm32-*` and `wasm64-*` targets.
+    ///
+    /// This takes precedence over the crate type so that emscripten's `.js`
+    /// and `.wasm` outputs stay next to each other.
+    pub wasm: Option<PathBuf>,
+}
+
+impl ExportDirs {
+    /// Copies every artifact to the single directory `path`.
+    pub fn single(path: PathBuf) -> ExportDirs {
+        ExportDirs {
+            all: Some(path),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the directory a file of the given type, built for the target
+    /// `triple`, should be copied to.
+    pub fn dir_for(&self, file_type: &FileType, triple: &str) -> Option<&Path> {
+        let is_wasm = triple.starts_with("wasm32-") || triple.starts_with("wasm64-");
+        let by_kind = match file_type.crate_type {
+            _ if is_wasm && self.wasm.is_some() => &self.wasm,
+            Some(CrateType::Bin) => &self.bins,
+            Some(CrateType::Cdylib) => &self.cdylibs,
+            Some(CrateType::Dylib) => &self.dylibs,
+            Some(CrateType::Staticlib) => &self.staticlibs,
+            Some(CrateType::Lib | CrateType::Rlib) => &self.rlibs,
+            Some(CrateType::ProcMacro | CrateType::Other(_)) | None => &None,
+        };
+        by_kind.as_deref().or(self.all.as_deref())
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum MessageFormat {
     Human,
@@ -0,0 +1,166 @@
+//! See [`FastLinker`].
+
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use cargo_platform::Cfg;
+use cargo_util::paths;
+
+use crate::core::compiler::{CompileKind, RustcTargetData};
+use crate::util::config::Config;
+use crate::util::errors::CargoResult;
+use crate::util::StableHasher;
+
+/// The name of the program linked to check that a linker works.
+const PROBE_CRATE_NAME: &str = "fast_linker_probe";
+
+/// A linker faster than the default one of a target, used instead of it with
+/// `build.prefer-fast-linker`.
+///
+/// Only linkers the compiler driver finds on its own are looked for:
+///
+/// * `ld.mold` (on Linux), then `ld.lld`, for ELF targets, selected with
+///   `-C link-arg=-fuse-ld=`.
+/// * `lld-link` for MSVC targets, replacing `link.exe` with `-C linker=` and
+///   `-C linker-flavor=lld-link`.
+///
+/// Apple and WebAssembly targets already have a linker of their own, and
+/// aren't supported.
+#[derive(Clone, Debug, Hash)]
+pub struct FastLinker {
+    /// The name of the linker, like `mold`.
+    pub name: &'static str,
+    /// Where the linker was found on `PATH`.
+    pub path: PathBuf,
+    /// The flags passed to rustc to link with it.
+    pub args: Vec<String>,
+}
+
+impl FastLinker {
+    /// Looks for a fast linker for `kind`.
+    ///
+    /// Each linker found is checked by linking an empty program with it, and
+    /// a warning is displayed for those which fail, so falling back to the
+    /// next one, or to the default linker, doesn't go unnoticed.
+    pub fn probe(
+        config: &Config,
+        target_data: &RustcTargetData<'_>,
+        kind: CompileKind,
+    ) -> CargoResult<Option<FastLinker>> {
+        let info = target_data.info(kind);
+        if picks_linker(&info.rustflags) {
+            return Ok(None);
+        }
+        let cfg = info.cfg();
+        let key_is = |key: &str, value: &str| {
+            cfg.iter()
+                .any(|c| matches!(c, Cfg::KeyPair(k, v) if k == key && v == value))
+        };
+
+        let mut candidates = Vec::new();
+        if key_is("target_env", "msvc") {
+            candidates.push(("lld-link", "lld-link"));
+        } else if !key_is("target_vendor", "apple")
+            && !key_is("target_family", "wasm")
+            && key_is("target_family", "unix")
+        {
+            if key_is("target_os", "linux") {
+                candidates.push(("mold", "ld.mold"));
+            }
+            candidates.push(("lld", "ld.lld"));
+        }
+
+        for (name, program) in candidates {
+            let Some(path) = find_program(config, program) else {
+                continue;
+            };
+            let args = if name == "lld-link" {
+                vec![
+                    "-C".to_string(),
+                    format!("linker={}", path.display()),
+                    "-C".to_string(),
+                    "linker-flavor=lld-link".to_string(),
+                ]
+            } else {
+                vec!["-C".to_string(), format!("link-arg=-fuse-ld={name}")]
+            };
+            let linker = FastLinker { name, path, args };
+            match linker.check(target_data, kind) {
+                Ok(()) => return Ok(Some(linker)),
+                Err(e) => {
+                    config.shell().warn(format!(
+                        "`build.prefer-fast-linker` could not link with `{name}` for `{}`, \
+                         it will not be used\n{e:#}",
+                        target_data.short_name(&kind)
+                    ))?;
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Links an empty program for `kind` with this linker.
+    ///
+    /// The result is kept in the cache of the rustc outputs of the target
+    /// directory, along with the path and modification time of the linker,
+    /// so a linker is only checked again once it or rustc changes.
+    fn check(&self, target_data: &RustcTargetData<'_>, kind: CompileKind) -> CargoResult<()> {
+        let tmp = tempfile::Builder::new()
+            .prefix("cargo-fast-linker")
+            .tempdir()?;
+        let mut cmd = target_data.rustc.process_no_wrapper();
+        // The program is written to the working directory, which isn't part
+        // of the cache key, unlike an `--out-dir`.
+        cmd.cwd(tmp.path())
+            .arg("-")
+            .arg("--crate-name")
+            .arg(PROBE_CRATE_NAME)
+            .arg("--crate-type")
+            .arg("bin")
+            .args(&target_data.info(kind).rustflags)
+            .args(&self.args)
+            .stdin("fn main() {}");
+        if let CompileKind::Target(target) = kind {
+            cmd.arg("--target").arg(target.rustc_target());
+        }
+        let mut hasher = StableHasher::new();
+        self.path.hash(&mut hasher);
+        paths::mtime(&self.path)?.hash(&mut hasher);
+        target_data
+            .rustc
+            .cached_output(&cmd, hasher.finish())
+            .map(drop)
+    }
+}
+
+/// Whether `rustflags` already pick a linker, with `-C linker=`,
+/// `-C linker-flavor=` or `-fuse-ld=` in `-C link-arg=` or `-C link-args=`.
+fn picks_linker(rustflags: &[String]) -> bool {
+    rustflags.iter().any(|flag| {
+        let codegen_opt = flag.strip_prefix("-C").unwrap_or(flag);
+        codegen_opt.starts_with("linker=")
+            || codegen_opt.starts_with("linker-flavor=")
+            || (codegen_opt.starts_with("link-arg") && codegen_opt.contains("-fuse-ld="))
+    })
+}
+
+/// Finds `program` on `PATH`.
+fn find_program(config: &Config, program: &str) -> Option<PathBuf> {
+    let path = config.get_env_os("PATH")?;
+    std::env::split_paths(&path)
+        .map(|dir| dir.join(format!("{program}{}", std::env::consts::EXE_SUFFIX)))
+        .find(|candidate| is_executable(candidate))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::prelude::*;
+    std::fs::metadata(path)
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(windows)]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
@@ -1,7 +1,7 @@
 //! [`BuildContext`] is a (mostly) static information about a build task.
 
 use crate::core::compiler::unit_graph::UnitGraph;
-use crate::core::compiler::{BuildConfig, CompileKind, Unit};
+use crate::core::compiler::{BuildConfig, CompileKind, CompileMode, Unit};
 use crate::core::profiles::Profiles;
 use crate::core::PackageSet;
 use crate::core::Workspace;
@@ -12,9 +12,11 @@ use crate::util::Rustc;
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
+mod fast_linker;
 mod target_info;
+pub use self::fast_linker::FastLinker;
 pub use self::target_info::{
-    FileFlavor, FileType, RustDocFingerprint, RustcTargetData, TargetInfo,
+    FileFlavor, FileType, RustDocFingerprint, RustcTargetData, RustdocRuntool, TargetInfo,
 };
 
 /// The build context, containing complete information needed for a build task
@@ -79,6 +81,10 @@ pub struct BuildContext<'a, 'cfg> {
 
     /// The list of all kinds that are involved in this build
     pub all_kinds: HashSet<CompileKind>,
+
+    /// The linkers used instead of the default ones with
+    /// `build.prefer-fast-linker`, for the kinds where one was found.
+    pub fast_linkers: HashMap<CompileKind, FastLinker>,
 }
 
 impl<'a, 'cfg> BuildContext<'a, 'cfg> {
@@ -98,7 +104,19 @@ impl<'a, 'cfg> BuildContext<'a, 'cfg> {
             .map(|u| u.kind)
             .chain(build_config.requested_kinds.iter().copied())
             .chain(std::iter::once(CompileKind::Host))
-            .collect();
+            .collect::<HashSet<_>>();
+
+        let mut fast_linkers = HashMap::new();
+        if build_config.prefer_fast_linker {
+            for &kind in &all_kinds {
+                if target_data.target_config(kind).linker.is_some() {
+                    continue;
+                }
+                if let Some(linker) = FastLinker::probe(ws.config(), &target_data, kind)? {
+                    fast_linkers.insert(kind, linker);
+                }
+            }
+        }
 
         Ok(BuildContext {
             ws,
@@ -112,6 +130,7 @@ impl<'a, 'cfg> BuildContext<'a, 'cfg> {
             unit_graph,
             scrape_units,
             all_kinds,
+            fast_linkers,
         })
     }
 
@@ -129,6 +148,19 @@ impl<'a, 'cfg> BuildContext<'a, 'cfg> {
             .map(|l| l.val.clone().resolve_program(self.config))
     }
 
+    /// Gets the linker picked by `build.prefer-fast-linker` for `unit`, if
+    /// one was found and the unit is linked.
+    pub fn fast_linker(&self, unit: &Unit) -> Option<&FastLinker> {
+        let links = matches!(
+            unit.mode,
+            CompileMode::Build | CompileMode::Test | CompileMode::Bench
+        ) && unit.requires_upstream_objects();
+        if !links {
+            return None;
+        }
+        self.fast_linkers.get(&unit.kind)
+    }
+
     /// Gets the host architecture triple.
     ///
     /// For example, x86_64-unknown-linux-gnu, would be
@@ -177,4 +209,16 @@ impl<'a, 'cfg> BuildContext<'a, 'cfg> {
     pub fn extra_args_for(&self, unit: &Unit) -> Option<&Vec<String>> {
         self.extra_compiler_args.get(unit)
     }
+
+    /// Whether rustdoc outputs JSON for this unit, which is the case for the
+    /// root units of `cargo rustdoc --output-format json`.
+    pub fn is_rustdoc_json(&self, unit: &Unit) -> bool {
+        self.build_config.rustdoc_json && unit.mode.is_doc() && self.roots.contains(unit)
+    }
+
+    /// Whether rustdoc reports the documentation coverage of this unit, which
+    /// is the case for the root units of `cargo doc --show-coverage`.
+    pub fn is_rustdoc_coverage(&self, unit: &Unit) -> bool {
+        self.build_config.rustdoc_coverage && unit.mode.is_doc() && self.roots.contains(unit)
+    }
 }
@@ -14,13 +14,15 @@ use crate::core::compiler::{
 use crate::core::{Dependency, Package, Target, TargetKind, Workspace};
 use crate::util::config::{Config, StringList, TargetConfig};
 use crate::util::interning::InternedString;
-use crate::util::{CargoResult, Rustc};
+use crate::util::{CargoResult, Rustc, StableHasher};
 use anyhow::Context as _;
 use cargo_platform::{Cfg, CfgExpr};
 use cargo_util::{paths, ProcessBuilder};
 use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
 use std::collections::hash_map::{Entry, HashMap};
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
 use std::str::{self, FromStr};
 
@@ -695,6 +697,15 @@ impl Flags {
 ///
 ///  - `build.rustflags` from the config
 ///
+/// The flags for `rustdoc` come from the `rustdocflags` counterparts of
+/// these locations, except for `[host]`, and for `target.cfg(..)` without
+/// `-Ztarget-cfg-rustdocflags`. They are passed after the
+/// `profile.*.rustdocflags` of the unit.
+///
+/// With `-Zrustflags-append`, those are then composed with the
+/// `build.rustflags-prepend` and `build.rustflags-append` config values, see
+/// [`compose_rustflags`].
+///
 /// The behavior differs slightly when cross-compiling (or, specifically, when `--target` is
 /// provided) for artifacts that are always built for the host (plugins, build scripts, ...).
 /// For those artifacts, _only_ `host.*.rustflags` is respected, and no other configuration
@@ -732,16 +743,117 @@ fn extra_args(
     // NOTE: It is impossible to have a [host] section and reach this logic with kind.is_host(),
     // since [host] implies `target-applies-to-host = false`, which always early-returns above.
 
-    if let Some(rustflags) = rustflags_from_env(config, flags) {
-        Ok(rustflags)
+    let rustflags = if let Some(rustflags) = rustflags_from_env(config, flags) {
+        rustflags
     } else if let Some(rustflags) =
         rustflags_from_target(config, host_triple, target_cfg, kind, flags)?
     {
-        Ok(rustflags)
+        rustflags
     } else if let Some(rustflags) = rustflags_from_build(config, flags)? {
-        Ok(rustflags)
+        rustflags
     } else {
-        Ok(Vec::new())
+        Vec::new()
+    };
+
+    if config.cli_unstable().rustflags_append {
+        compose_rustflags(config, flags, rustflags)
+    } else {
+        Ok(rustflags)
+    }
+}
+
+/// Surrounds the flags picked by [`extra_args`] with the ones of
+/// `build.rustflags-prepend` and `build.rustflags-append`, or their
+/// `rustdocflags` counterparts, which apply whichever location the other
+/// flags came from, and then removes duplicated flags with
+/// [`dedup_rustflags`].
+fn compose_rustflags(
+    config: &Config,
+    flags: Flags,
+    rustflags: Vec<String>,
+) -> CargoResult<Vec<String>> {
+    let build = config.build_config()?;
+    let (prepend, append) = match flags {
+        Flags::Rust => (&build.rustflags_prepend, &build.rustflags_append),
+        Flags::Rustdoc => (&build.rustdocflags_prepend, &build.rustdocflags_append),
+    };
+    let list = |l: &Option<StringList>| l.as_ref().map(|l| l.as_slice().to_vec());
+    let mut composed = list(prepend).unwrap_or_default();
+    composed.extend(rustflags);
+    composed.extend(list(append).unwrap_or_default());
+    Ok(dedup_rustflags(composed))
+}
+
+/// Options of rustc and rustdoc which take their value as a separate
+/// argument, like `--cfg foo`.
+const FLAGS_WITH_VALUE: &[&str] = &[
+    "-A",
+    "-C",
+    "-D",
+    "-F",
+    "-L",
+    "-W",
+    "-Z",
+    "-l",
+    "--cap-lints",
+    "--cfg",
+    "--check-cfg",
+    "--crate-type",
+    "--edition",
+    "--emit",
+    "--extern",
+    "--force-warn",
+    "--remap-path-prefix",
+];
+
+/// Removes repeated flags, keeping the last occurrence of each, so that the
+/// flags which take precedence when given several times still do.
+///
+/// A flag is an option along with its value, so `--cfg a --cfg b` isn't a
+/// duplicate, but `--cfg a --cfg a` is. Only the flags for which repeating
+/// the same value makes no difference are removed, see [`is_dedupable`], as
+/// the order and repetition of others like `-l` or `-C link-arg` matter.
+fn dedup_rustflags(rustflags: Vec<String>) -> Vec<String> {
+    let mut groups: Vec<Vec<String>> = Vec::new();
+    let mut args = rustflags.into_iter();
+    while let Some(arg) = args.next() {
+        let mut group = vec![arg];
+        if FLAGS_WITH_VALUE.contains(&group[0].as_str()) {
+            group.extend(args.next());
+        }
+        groups.push(group);
+    }
+    let mut seen = HashSet::new();
+    let mut deduped: Vec<_> = groups
+        .into_iter()
+        .rev()
+        .filter(|group| !is_dedupable(group) || seen.insert(group.clone()))
+        .collect();
+    deduped.reverse();
+    deduped.into_iter().flatten().collect()
+}
+
+/// Whether the flag `group` can be removed when given again later: `--cfg`,
+/// `--check-cfg`, and the `-C` codegen options for which the last value wins.
+fn is_dedupable(group: &[String]) -> bool {
+    let (opt, value) = match group {
+        [opt, value] => (opt.as_str(), value.as_str()),
+        [arg] => {
+            if let Some(value) = arg.strip_prefix("-C") {
+                ("-C", value)
+            } else if let Some((opt, value)) = arg.split_once('=') {
+                (opt, value)
+            } else {
+                return false;
+            }
+        }
+        _ => return false,
+    };
+    match opt {
+        "--cfg" | "--check-cfg" => true,
+        // These add to the values given before instead of replacing them.
+        "-C" => !value.starts_with("link-arg") && !value.starts_with("llvm-args"),
+        _ => false,
     }
 }
 
@@ -797,15 +909,15 @@ fn rustflags_from_target(
             .target_cfgs()?
             .iter()
             .filter_map(|(key, cfg)| {
-                match flag {
-                    Flags::Rust => cfg
-                        .rustflags
-                        .as_ref()
-                        .map(|rustflags| (key, &rustflags.val)),
-                    // `target.cfg(…).rustdocflags` is currently not supported.
-                    // In fact, neither is `target.<triple>.rustdocflags`.
-                    Flags::Rustdoc => None,
-                }
+                let rustflags = match flag {
+                    Flags::Rust => &cfg.rustflags,
+                    // `target.cfg(…).rustdocflags` is unstable.
+                    Flags::Rustdoc if config.cli_unstable().target_cfg_rustdocflags => {
+                        &cfg.rustdocflags
+                    }
+                    Flags::Rustdoc => return None,
+                };
+                rustflags.as_ref().map(|rustflags| (key, &rustflags.val))
             })
             .filter(|(key, _rustflags)| CfgExpr::matches_key(key, target_cfg))
             .for_each(|(_key, cfg_rustflags)| {
@@ -870,12 +982,39 @@ pub struct RustcTargetData<'cfg> {
     target_config: HashMap<CompileTarget, TargetConfig>,
     /// Information about the target platform that we're building for.
     target_info: HashMap<CompileTarget, TargetInfo>,
+    /// The `cfg` values to match the requested targets against, instead of
+    /// the ones printed by rustc, set with [`RustcTargetData::with_cfgs`].
+    synthetic_cfgs: Option<Vec<Cfg>>,
 }
 
 impl<'cfg> RustcTargetData<'cfg> {
     pub fn new(
         ws: &Workspace<'cfg>,
         requested_kinds: &[CompileKind],
+    ) -> CargoResult<RustcTargetData<'cfg>> {
+        Self::new_inner(ws, requested_kinds, None)
+    }
+
+    /// Like [`RustcTargetData::new`], but the requested targets are assumed
+    /// to have exactly the given `cfg` values, without querying rustc for
+    /// them.
+    ///
+    /// This is only meant to evaluate platform-specific dependencies, as for
+    /// `cargo metadata --filter-platform` or `cargo tree --target`, and not
+    /// to compile anything: the rest of the information about those targets
+    /// is the one of the host.
+    pub fn with_cfgs(
+        ws: &Workspace<'cfg>,
+        requested_kinds: &[CompileKind],
+        cfgs: Vec<Cfg>,
+    ) -> CargoResult<RustcTargetData<'cfg>> {
+        Self::new_inner(ws, requested_kinds, Some(cfgs))
+    }
+
+    fn new_inner(
+        ws: &Workspace<'cfg>,
+        requested_kinds: &[CompileKind],
+        synthetic_cfgs: Option<Vec<Cfg>>,
     ) -> CargoResult<RustcTargetData<'cfg>> {
         let config = ws.config();
         let rustc = config.load_global_rustc(Some(ws))?;
@@ -908,6 +1047,7 @@ impl<'cfg> RustcTargetData<'cfg> {
             host_info,
             target_config,
             target_info,
+            synthetic_cfgs,
         };
 
         // Get all kinds we currently know about.
@@ -949,10 +1089,14 @@ impl<'cfg> RustcTargetData<'cfg> {
                     .insert(target, self.config.target_cfg_triple(target.short_name())?);
             }
             if !self.target_info.contains_key(&target) {
-                self.target_info.insert(
-                    target,
-                    TargetInfo::new(self.config, &self.requested_kinds, &self.rustc, kind)?,
-                );
+                let info = match &self.synthetic_cfgs {
+                    Some(cfgs) if self.requested_kinds.contains(&kind) => TargetInfo {
+                        cfg: cfgs.clone(),
+                        ..self.host_info.clone()
+                    },
+                    _ => TargetInfo::new(self.config, &self.requested_kinds, &self.rustc, kind)?,
+                };
+                self.target_info.insert(target, info);
             }
         }
         Ok(())
@@ -1020,6 +1164,55 @@ impl<'cfg> RustcTargetData<'cfg> {
     pub fn script_override(&self, lib_name: &str, kind: CompileKind) -> Option<&BuildOutput> {
         self.target_config(kind).links_overrides.get(lib_name)
     }
+
+    /// How `rustdoc --test` takes the runner of cross-compiled doctests,
+    /// learned from its `--help`, which is cached like the queries of rustc.
+    pub fn rustdoc_runtool(&self) -> CargoResult<RustdocRuntool> {
+        let rustdoc = self.config.rustdoc()?;
+        let mut process = ProcessBuilder::new(rustdoc);
+        process.arg("--help");
+        // The cache only keys on the arguments.
+        let mut hasher = StableHasher::new();
+        rustdoc.hash(&mut hasher);
+        let (output, _) = self
+            .rustc
+            .cached_output(&process, hasher.finish())
+            .with_context(|| "failed to query the options of rustdoc")?;
+        Ok(if output.contains("--test-runtool") {
+            RustdocRuntool::Stable
+        } else if output.contains("--runtool") {
+            RustdocRuntool::Unstable
+        } else {
+            RustdocRuntool::Unsupported
+        })
+    }
+}
+
+/// How `rustdoc --test` takes the runner of cross-compiled doctests.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RustdocRuntool {
+    /// With `--test-runtool` and `--test-runtool-arg`.
+    Stable,
+    /// With `-Zunstable-options --runtool` and `--runtool-arg`, which only
+    /// work on nightly.
+    Unstable,
+    /// Rustdoc can't run doctests with a runner.
+    Unsupported,
+}
+
+impl RustdocRuntool {
+    /// Adds `runner` with its `args` to the `rustdoc --test` invocation `p`.
+    pub fn add_args(self, p: &mut ProcessBuilder, runner: &Path, args: &[String]) {
+        let (tool, tool_arg) = match self {
+            RustdocRuntool::Stable => ("--test-runtool", "--test-runtool-arg"),
+            RustdocRuntool::Unstable => ("--runtool", "--runtool-arg"),
+            RustdocRuntool::Unsupported => return,
+        };
+        p.arg(tool).arg(runner);
+        for arg in args {
+            p.arg(tool_arg).arg(arg);
+        }
+    }
 }
 
 /// Structure used to deal with Rustdoc fingerprinting
@@ -1065,7 +1258,7 @@ impl RustDocFingerprint {
                 if fingerprint.rustc_vv == actual_rustdoc_target_data.rustc_vv {
                     return Ok(());
                 } else {
-                    log::debug!(
+                    tracing::debug!(
                         "doc fingerprint changed:\noriginal:\n{}\nnew:\n{}",
                         fingerprint.rustc_vv,
                         actual_rustdoc_target_data.rustc_vv
@@ -1073,11 +1266,11 @@ impl RustDocFingerprint {
                 }
             }
             Err(e) => {
-                log::debug!("could not deserialize {:?}: {}", fingerprint_path, e);
+                tracing::debug!("could not deserialize {:?}: {}", fingerprint_path, e);
             }
         };
         // Fingerprint does not match, delete the doc directories and write a new fingerprint.
-        log::debug!(
+        tracing::debug!(
             "fingerprint {:?} mismatch, clearing doc directories",
             fingerprint_path
         );
@@ -1116,3 +1309,41 @@ impl RustDocFingerprint {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::dedup_rustflags;
+
+    fn dedup(flags: &str) -> String {
+        let flags = flags.split(' ').map(str::to_string).collect();
+        dedup_rustflags(flags).join(" ")
+    }
+
+    #[test]
+    fn dedup_keeps_the_last_value() {
+        assert_eq!(
+            dedup("--cfg a -C debug-assertions=off --cfg b --cfg a -C debug-assertions=off"),
+            "--cfg b --cfg a -C debug-assertions=off"
+        );
+        assert_eq!(
+            dedup("-Copt-level=3 --check-cfg=cfg(a) -Copt-level=3 --check-cfg=cfg(a)"),
+            "-Copt-level=3 --check-cfg=cfg(a)"
+        );
+        assert_eq!(
+            dedup("-C opt-level=3 -C opt-level=1 -C opt-level=3"),
+            "-C opt-level=1 -C opt-level=3"
+        );
+    }
+
+    #[test]
+    fn dedup_keeps_repeated_link_flags() {
+        let flags = "-C link-arg=-Xlinker -C link-arg=--foo -C link-arg=-Xlinker -C link-arg=--bar";
+        assert_eq!(dedup(flags), flags);
+        let flags = "-Clink-args=-a -Clink-args=-a -C llvm-args=-b -C llvm-args=-b";
+        assert_eq!(dedup(flags), flags);
+        let flags = "-l static=x -l static=y -l static=x -lz -lz";
+        assert_eq!(dedup(flags), flags);
+        let flags = "-L native=a -L native=a --extern b=b.rlib --extern b=b.rlib";
+        assert_eq!(dedup(flags), flags);
+    }
+}
@@ -1,16 +1,18 @@
 //! Type definitions for the result of a compilation.
 
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::ffi::{OsStr, OsString};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use bytesize::ByteSize;
 use cargo_platform::CfgExpr;
 use cargo_util::{paths, ProcessBuilder};
 
 use crate::core::compiler::apply_env_config;
 use crate::core::compiler::BuildContext;
-use crate::core::compiler::{CompileKind, Metadata, Unit};
-use crate::core::Package;
+use crate::core::compiler::{CompileKind, CompileMode, Metadata, RustdocRuntool, Unit};
+use crate::core::{Package, PackageId, Workspace};
+use crate::drop_println;
 use crate::util::{config, CargoResult, Config};
 
 /// Structure with enough information to run `rustdoc --test`.
@@ -45,6 +47,59 @@ pub struct UnitOutput {
     pub script_meta: Option<Metadata>,
 }
 
+/// The working directory of the executables started by `cargo run`,
+/// `cargo test` and `cargo bench`, set with `--workdir` or the `run.workdir`
+/// config.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Workdir {
+    /// The directory Cargo was started in, the default of `cargo run`.
+    Cwd,
+    /// The root of the package of the executable, the default of
+    /// `cargo test` and `cargo bench`.
+    PackageRoot,
+    /// The root of the workspace.
+    WorkspaceRoot,
+    Path(PathBuf),
+}
+
+impl Workdir {
+    /// Parses `cwd`, `package-root`, `workspace-root`, or a path relative to
+    /// `base`.
+    pub fn parse(value: &str, base: &Path) -> Workdir {
+        match value {
+            "cwd" => Workdir::Cwd,
+            "package-root" => Workdir::PackageRoot,
+            "workspace-root" => Workdir::WorkspaceRoot,
+            path => Workdir::Path(base.join(path)),
+        }
+    }
+
+    /// Gets the `run.workdir` config, which needs `-Zrun-workdir`.
+    pub fn from_config(config: &Config) -> CargoResult<Option<Workdir>> {
+        if !config.cli_unstable().run_workdir {
+            return Ok(None);
+        }
+        let value = config.get::<Option<config::Value<String>>>("run.workdir")?;
+        Ok(value.map(|value| Workdir::parse(&value.val, &value.definition.root(config))))
+    }
+
+    /// The directory to run the executables of `pkg` in.
+    pub fn resolve(&self, ws: &Workspace<'_>, pkg: &Package) -> CargoResult<PathBuf> {
+        let dir = match self {
+            Workdir::Cwd => ws.config().cwd(),
+            Workdir::PackageRoot => pkg.root(),
+            Workdir::WorkspaceRoot => ws.root(),
+            Workdir::Path(path) => {
+                if !path.is_dir() {
+                    anyhow::bail!("the working directory `{}` does not exist", path.display());
+                }
+                path
+            }
+        };
+        Ok(dir.to_path_buf())
+    }
+}
+
 /// A structure returning the result of a compilation.
 pub struct Compilation<'cfg> {
     /// An array of all tests created during this compilation.
@@ -56,6 +111,19 @@ pub struct Compilation<'cfg> {
     /// An array of all cdylibs created.
     pub cdylibs: Vec<UnitOutput>,
 
+    /// The rlibs linked into each of the [`Compilation::binaries`] and
+    /// [`Compilation::cdylibs`], along with the packages they were built
+    /// from. Only collected for `--report-sizes`.
+    pub linked_rlibs: HashMap<Unit, Vec<(PackageId, PathBuf)>>,
+
+    /// The JSON documentation of the root units, with
+    /// `cargo rustdoc --output-format json`.
+    pub rustdoc_json: Vec<UnitOutput>,
+
+    /// The documentation coverage reports of the root units, in rustdoc's
+    /// JSON format, with `cargo doc --show-coverage`.
+    pub rustdoc_coverage: Vec<UnitOutput>,
+
     /// The crate names of the root units specified on the command-line.
     pub root_crate_names: Vec<String>,
 
@@ -104,6 +172,10 @@ pub struct Compilation<'cfg> {
     primary_rustc_process: Option<ProcessBuilder>,
 
     target_runners: HashMap<CompileKind, Option<(PathBuf, Vec<String>)>>,
+
+    /// How rustdoc takes the runner of cross-compiled doctests, only queried
+    /// when testing for a `--target`.
+    pub rustdoc_runtool: RustdocRuntool,
 }
 
 impl<'cfg> Compilation<'cfg> {
@@ -121,6 +193,20 @@ impl<'cfg> Compilation<'cfg> {
             }
         }
 
+        let rustdoc_runtool = if matches!(
+            bcx.build_config.mode,
+            CompileMode::Test | CompileMode::Doctest
+        ) && bcx
+            .build_config
+            .requested_kinds
+            .iter()
+            .any(|kind| !kind.is_host())
+        {
+            bcx.target_data.rustdoc_runtool()?
+        } else {
+            RustdocRuntool::Unsupported
+        };
+
         Ok(Compilation {
             // TODO: deprecated; remove.
             native_dirs: BTreeSet::new(),
@@ -135,6 +221,9 @@ impl<'cfg> Compilation<'cfg> {
             tests: Vec::new(),
             binaries: Vec::new(),
             cdylibs: Vec::new(),
+            linked_rlibs: HashMap::new(),
+            rustdoc_json: Vec::new(),
+            rustdoc_coverage: Vec::new(),
             root_crate_names: Vec::new(),
             extra_env: HashMap::new(),
             to_doc_test: Vec::new(),
@@ -150,6 +239,7 @@ impl<'cfg> Compilation<'cfg> {
                 .chain(Some(&CompileKind::Host))
                 .map(|kind| Ok((*kind, target_runner(bcx, *kind)?)))
                 .collect::<CargoResult<HashMap<_, _>>>()?,
+            rustdoc_runtool,
         })
     }
 
@@ -246,12 +336,55 @@ impl<'cfg> Compilation<'cfg> {
         self.fill_env(builder, pkg, script_meta, kind, false)
     }
 
+    /// Returns the environment variables Cargo itself sets in
+    /// [`Compilation::target_process`], leaving out the ones from the `[env]`
+    /// config table and from build scripts, whose values may be secrets.
+    pub fn target_cargo_env(
+        &self,
+        kind: CompileKind,
+        pkg: &Package,
+    ) -> CargoResult<BTreeMap<String, String>> {
+        let cmd = self.fill_cargo_env(ProcessBuilder::new(""), pkg, None, kind, false)?;
+        Ok(cmd
+            .get_envs()
+            .iter()
+            .filter_map(|(key, value)| {
+                Some((key.clone(), value.as_ref()?.to_string_lossy().into_owned()))
+            })
+            .collect())
+    }
+
     /// Prepares a new process with an appropriate environment to run against
     /// the artifacts produced by the build process.
     ///
     /// The package argument is also used to configure environment variables as
     /// well as the working directory of the child process.
     fn fill_env(
+        &self,
+        cmd: ProcessBuilder,
+        pkg: &Package,
+        script_meta: Option<Metadata>,
+        kind: CompileKind,
+        is_rustc_tool: bool,
+    ) -> CargoResult<ProcessBuilder> {
+        let mut cmd = self.fill_cargo_env(cmd, pkg, script_meta, kind, is_rustc_tool)?;
+        if self.config.cli_unstable().cancellation {
+            // Cancelling kills the processes spawned by rustc and build
+            // scripts too. They no longer get Ctrl-C from the terminal then,
+            // so this needs the handler of `-Zcancellation`.
+            cmd.cancel_on(self.config.cancellation().flag())
+                .kill_process_tree(true);
+        }
+
+        apply_env_config(self.config, &mut cmd)?;
+
+        Ok(cmd)
+    }
+
+    /// Sets the environment variables Cargo itself sets for `pkg`, along with
+    /// the ones from the build script of `script_meta`, and the working
+    /// directory of the process, see [`Compilation::fill_env`].
+    fn fill_cargo_env(
         &self,
         mut cmd: ProcessBuilder,
         pkg: &Package,
@@ -349,13 +482,89 @@ impl<'cfg> Compilation<'cfg> {
                 metadata.readme.as_ref().unwrap_or(&String::new()),
             )
             .cwd(pkg.root());
+        Ok(cmd)
+    }
 
-        apply_env_config(self.config, &mut cmd)?;
+    /// Prints the size of each of the built binaries and cdylibs, followed by
+    /// its largest sections when `size` is installed, and the sizes of the
+    /// rlibs linked into it, largest first.
+    ///
+    /// The size of an rlib is only an upper bound of what it adds to the
+    /// artifact, as the linker drops the code that isn't used.
+    pub fn report_sizes(&self) -> CargoResult<()> {
+        let cwd = self.config.cwd();
+        let mut artifacts: Vec<_> = self.binaries.iter().chain(&self.cdylibs).collect();
+        artifacts.sort_by(|a, b| a.path.cmp(&b.path));
+        artifacts.dedup_by(|a, b| a.path == b.path);
+        for output in artifacts {
+            let size = std::fs::metadata(&output.path)?.len();
+            let path = output.path.strip_prefix(cwd).unwrap_or(&output.path);
+            drop_println!(
+                self.config,
+                "{:>10}  {}",
+                ByteSize(size).to_string(),
+                path.display()
+            );
+
+            let sections = section_sizes(&output.path);
+            if !sections.is_empty() {
+                let sections: Vec<_> = sections
+                    .iter()
+                    .take(SECTIONS_TO_REPORT)
+                    .map(|(name, size)| format!("{name} {}", ByteSize(*size)))
+                    .collect();
+                drop_println!(self.config, "{:10}  sections: {}", "", sections.join(", "));
+            }
 
-        Ok(cmd)
+            let mut rlibs = Vec::new();
+            for (pkg_id, path) in self.linked_rlibs.get(&output.unit).into_iter().flatten() {
+                rlibs.push((std::fs::metadata(path)?.len(), pkg_id));
+            }
+            rlibs.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(b.1)));
+            if !rlibs.is_empty() {
+                let rlibs: Vec<_> = rlibs
+                    .iter()
+                    .map(|(size, pkg_id)| {
+                        format!(
+                            "{} v{} {}",
+                            pkg_id.name(),
+                            pkg_id.version(),
+                            ByteSize(*size)
+                        )
+                    })
+                    .collect();
+                drop_println!(self.config, "{:10}  rlibs: {}", "", rlibs.join(", "));
+            }
+        }
+        Ok(())
     }
 }
 
+/// The number of the largest sections of an artifact to report.
+const SECTIONS_TO_REPORT: usize = 5;
+
+/// Gets the sizes of the sections of the artifact at `path`, largest first,
+/// from the output of `size -A`, or nothing if `size` isn't installed.
+fn section_sizes(path: &Path) -> Vec<(String, u64)> {
+    let output = match ProcessBuilder::new("size").arg("-A").arg(path).output() {
+        Ok(output) if output.status.success() => output,
+        _ => return Vec::new(),
+    };
+    // Lines look like `.text   123456   4096`, after a header.
+    let mut sections: Vec<_> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let name = parts.next()?;
+            let size: u64 = parts.next()?.parse().ok()?;
+            (name.starts_with('.') || name.starts_with("__")).then(|| (name.to_string(), size))
+        })
+        .filter(|(_, size)| *size > 0)
+        .collect();
+    sections.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    sections
+}
+
 /// Prepares a rustc_tool process with additional environment variables
 /// that are only relevant in a context that has a unit
 fn fill_rustc_tool_env(mut cmd: ProcessBuilder, unit: &Unit) -> ProcessBuilder {
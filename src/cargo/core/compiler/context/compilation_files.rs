@@ -7,10 +7,10 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use lazycell::LazyCell;
-use log::debug;
+use tracing::debug;
 
 use super::{BuildContext, CompileKind, Context, FileFlavor, Layout};
-use crate::core::compiler::{CompileMode, CompileTarget, CrateType, FileType, Unit};
+use crate::core::compiler::{CompileMode, CompileTarget, CrateType, ExportDirs, FileType, Unit};
 use crate::core::{Target, TargetKind, Workspace};
 use crate::util::{self, CargoResult, StableHasher};
 
@@ -101,8 +101,8 @@ pub struct CompilationFiles<'a, 'cfg> {
     pub(super) host: Layout,
     /// The target directory layout for the target (if different from then host).
     pub(super) target: HashMap<CompileTarget, Layout>,
-    /// Additional directory to include a copy of the outputs.
-    export_dir: Option<PathBuf>,
+    /// Additional directories to include a copy of the outputs.
+    export_dir: Option<ExportDirs>,
     /// The root targets requested by the user on the command line (does not
     /// include dependencies).
     roots: Vec<Unit>,
@@ -213,11 +213,6 @@ impl<'a, 'cfg: 'a> CompilationFiles<'a, 'cfg> {
         }
     }
 
-    /// Additional export directory from `--out-dir`.
-    pub fn export_dir(&self) -> Option<PathBuf> {
-        self.export_dir.clone()
-    }
-
     /// Directory name to use for a package in the form `NAME-HASH`.
     ///
     /// Note that some units may share the same directory, so care should be
@@ -284,6 +279,12 @@ impl<'a, 'cfg: 'a> CompilationFiles<'a, 'cfg> {
         self.fingerprint_file_path(unit, "output-")
     }
 
+    /// Path where the environment and command line of the last process run
+    /// for the unit are written with `--emit-env`.
+    pub fn env_capture_path(&self, unit: &Unit) -> PathBuf {
+        self.fingerprint_file_path(unit, "env-")
+    }
+
     /// Returns the directory where a compiled build script is stored.
     /// `/path/to/target/{debug,release}/build/PKG-HASH`
     pub fn build_script_dir(&self, unit: &Unit) -> PathBuf {
@@ -436,10 +437,23 @@ impl<'a, 'cfg: 'a> CompilationFiles<'a, 'cfg> {
     ) -> CargoResult<Arc<Vec<OutputFile>>> {
         let ret = match unit.mode {
             CompileMode::Doc { .. } => {
-                let path = self
-                    .out_dir(unit)
-                    .join(unit.target.crate_name())
-                    .join("index.html");
+                let path = if bcx.is_rustdoc_json(unit) {
+                    self.out_dir(unit).join(format!(
+                        "{}-{}.json",
+                        unit.target.crate_name(),
+                        self.metadata(unit)
+                    ))
+                } else if bcx.is_rustdoc_coverage(unit) {
+                    self.out_dir(unit).join(format!(
+                        "{}-{}.coverage.json",
+                        unit.target.crate_name(),
+                        self.metadata(unit)
+                    ))
+                } else {
+                    self.out_dir(unit)
+                        .join(unit.target.crate_name())
+                        .join("index.html")
+                };
                 vec![OutputFile {
                     path,
                     hardlink: None,
@@ -529,9 +543,10 @@ impl<'a, 'cfg: 'a> CompilationFiles<'a, 'cfg> {
                 None
             } else {
                 self.export_dir.as_ref().and_then(|export_dir| {
+                    let dir = export_dir.dir_for(&file_type, triple)?;
                     hardlink
                         .as_ref()
-                        .map(|hardlink| export_dir.join(hardlink.file_name().unwrap()))
+                        .map(|hardlink| dir.join(hardlink.file_name().unwrap()))
                 })
             };
             outputs.push(OutputFile {
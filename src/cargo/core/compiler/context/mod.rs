@@ -131,6 +131,7 @@ impl<'a, 'cfg> Context<'a, 'cfg> {
     /// See [`ops::cargo_compile`] for a higher-level view of the compile process.
     ///
     /// [`ops::cargo_compile`]: ../../../ops/cargo_compile/index.html
+    #[tracing::instrument(skip_all)]
     pub fn compile(mut self, exec: &Arc<dyn Executor>) -> CargoResult<Compilation<'cfg>> {
         let mut queue = JobQueue::new(self.bcx);
         let mut plan = BuildPlan::new();
@@ -175,6 +176,17 @@ impl<'a, 'cfg> Context<'a, 'cfg> {
         if build_plan {
             plan.set_inputs(self.build_plan_inputs()?);
             plan.output_plan(self.bcx.config);
+        } else if self.bcx.config.cli_unstable().layout_index {
+            super::layout::write_index(&self)?;
+        }
+
+        if self.bcx.config.cli_unstable().artifact_cache {
+            if let Err(e) = super::artifact_cache::gc(self.bcx.config) {
+                self.bcx
+                    .config
+                    .shell()
+                    .warn(format!("failed to clean up the artifact cache: {e:?}"))?;
+            }
         }
 
         // Collect the result of the build into `self.compilation`.
@@ -192,6 +204,14 @@ impl<'a, 'cfg> Context<'a, 'cfg> {
                     self.compilation
                         .tests
                         .push(self.unit_output(unit, &output.path));
+                } else if self.bcx.is_rustdoc_json(unit) {
+                    self.compilation
+                        .rustdoc_json
+                        .push(self.unit_output(unit, &output.path));
+                } else if self.bcx.is_rustdoc_coverage(unit) {
+                    self.compilation
+                        .rustdoc_coverage
+                        .push(self.unit_output(unit, &output.path));
                 } else if unit.target.is_executable() {
                     self.compilation
                         .binaries
@@ -205,6 +225,14 @@ impl<'a, 'cfg> Context<'a, 'cfg> {
                 }
             }
 
+            if self.bcx.build_config.report_sizes
+                && !unit.mode.is_any_test()
+                && (unit.target.is_executable() || unit.target.is_cdylib())
+            {
+                let rlibs = self.linked_rlibs(unit)?;
+                self.compilation.linked_rlibs.insert(unit.clone(), rlibs);
+            }
+
             // If the unit has a build script, add `OUT_DIR` to the
             // environment variables.
             if unit.target.is_lib() {
@@ -257,6 +285,7 @@ impl<'a, 'cfg> Context<'a, 'cfg> {
                         }
                     }
                 }
+                args.extend(unit.profile.rustdocflags.iter().map(Into::into));
                 args.extend(self.bcx.rustdocflags_args(unit).iter().map(Into::into));
 
                 use super::MessageFormat;
@@ -295,6 +324,14 @@ impl<'a, 'cfg> Context<'a, 'cfg> {
         Ok(self.compilation)
     }
 
+    /// Prepares the units without compiling them, so the files they produced
+    /// in previous builds can be inspected, like the outputs of build scripts
+    /// with [`custom_build::previous_output`].
+    pub fn prepare_without_compiling(&mut self) -> CargoResult<()> {
+        self.lto = super::lto::generate(self.bcx)?;
+        self.prepare_units()
+    }
+
     /// Returns the executable for the specified unit (if any).
     pub fn get_executable(&mut self, unit: &Unit) -> CargoResult<Option<PathBuf>> {
         let is_binary = unit.target.is_executable();
@@ -311,11 +348,12 @@ impl<'a, 'cfg> Context<'a, 'cfg> {
 
     pub fn prepare_units(&mut self) -> CargoResult<()> {
         let dest = self.bcx.profiles.get_dir_name();
-        let host_layout = Layout::new(self.bcx.ws, None, &dest)?;
+        let incremental_dir = self.bcx.profiles.incremental_dir();
+        let host_layout = Layout::new(self.bcx.ws, None, &dest, incremental_dir)?;
         let mut targets = HashMap::new();
         for kind in self.bcx.all_kinds.iter() {
             if let CompileKind::Target(target) = *kind {
-                let layout = Layout::new(self.bcx.ws, Some(target), &dest)?;
+                let layout = Layout::new(self.bcx.ws, Some(target), &dest, incremental_dir)?;
                 targets.insert(target, layout);
             }
         }
@@ -434,6 +472,34 @@ impl<'a, 'cfg> Context<'a, 'cfg> {
         }
     }
 
+    /// Returns the rlibs of the dependencies linked into `unit`, along with
+    /// the packages they were built from.
+    fn linked_rlibs(&self, unit: &Unit) -> CargoResult<Vec<(PackageId, PathBuf)>> {
+        let mut rlibs = Vec::new();
+        let mut visited = HashSet::new();
+        let mut pending = vec![unit];
+        while let Some(unit) = pending.pop() {
+            for dep in self.unit_deps(unit) {
+                // Build scripts and proc-macros run on the host and aren't
+                // linked into the artifact.
+                if !dep.unit.target.is_lib()
+                    || dep.unit.mode.is_run_custom_build()
+                    || dep.unit.target.proc_macro()
+                    || !visited.insert(&dep.unit)
+                {
+                    continue;
+                }
+                for output in self.outputs(&dep.unit)?.iter() {
+                    if output.flavor == FileFlavor::Linkable {
+                        rlibs.push((dep.unit.pkg.package_id(), output.path.clone()));
+                    }
+                }
+                pending.push(&dep.unit);
+            }
+        }
+        Ok(rlibs)
+    }
+
     /// Check if any output file name collision happens.
     /// See <https://github.com/rust-lang/cargo/issues/6313> for more.
     fn check_collisions(&self) -> CargoResult<()> {
@@ -529,7 +595,13 @@ impl<'a, 'cfg> Context<'a, 'cfg> {
         let mut doc_libs = HashMap::new();
         let mut doc_bins = HashMap::new();
         for unit in keys {
-            if unit.mode.is_doc() && self.is_primary_package(unit) {
+            // The JSON documentation and coverage reports have the metadata
+            // hash in their file names, so only their outputs are checked.
+            if unit.mode.is_doc()
+                && self.is_primary_package(unit)
+                && !self.bcx.is_rustdoc_json(unit)
+                && !self.bcx.is_rustdoc_coverage(unit)
+            {
                 // These situations have been an error since before 1.0, so it
                 // is not a warning like the other situations.
                 if unit.target.is_lib() {
@@ -593,9 +665,9 @@ impl<'a, 'cfg> Context<'a, 'cfg> {
     /// metadata file from `dep`.
     pub fn only_requires_rmeta(&self, parent: &Unit, dep: &Unit) -> bool {
         // We're only a candidate for requiring an `rmeta` file if we
-        // ourselves are building an rlib,
-        !parent.requires_upstream_objects()
-            && parent.mode == CompileMode::Build
+        // ourselves are building an rlib, or only checking,
+        (parent.mode.is_check()
+            || !parent.requires_upstream_objects() && parent.mode == CompileMode::Build)
             // Our dependency must also be built as an rlib, otherwise the
             // object code must be useful in some fashion
             && !dep.requires_upstream_objects()
@@ -36,7 +36,7 @@ use crate::core::compiler::artifact;
 use crate::core::compiler::context::Metadata;
 use crate::core::compiler::job_queue::JobState;
 use crate::core::{profiles::ProfileRoot, PackageId, Target};
-use crate::util::errors::CargoResult;
+use crate::util::errors::{CargoErrorKind, CargoResult, KindError};
 use crate::util::machine_message::{self, Message};
 use crate::util::{internal, profile};
 use anyhow::{bail, Context as _};
@@ -47,6 +47,7 @@ use std::collections::{BTreeSet, HashSet};
 use std::path::{Path, PathBuf};
 use std::str;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 /// A build script instruction that tells Cargo to display a warning after the
 /// build script has finished running. Read [the doc] for more.
@@ -410,6 +411,15 @@ fn build_work(cx: &mut Context<'_, '_>, unit: &Unit) -> CargoResult<Job> {
     // Need a separate copy for the fresh closure.
     let targets_fresh = targets.clone();
 
+    // With `-Zjobserver-accounting`, `NUM_JOBS` is set when the build script
+    // runs, and its CPU time is checked against `-j` afterwards.
+    let jobserver_accounting = cx
+        .bcx
+        .config
+        .cli_unstable()
+        .jobserver_accounting
+        .then(|| (cx.jobserver.clone(), cx.bcx.jobs()));
+
     let env_profile_name = unit.profile.name.to_uppercase();
     let built_with_debuginfo = cx
         .bcx
@@ -468,13 +478,18 @@ fn build_work(cx: &mut Context<'_, '_>, unit: &Unit) -> CargoResult<Job> {
             return Ok(());
         }
 
+        if let Some((jobserver, jobs)) = &jobserver_accounting {
+            cmd.env("NUM_JOBS", num_jobs(jobserver, *jobs).to_string());
+        }
+
         // And now finally, run the build command itself!
         state.running(&cmd);
         let timestamp = paths::set_invocation_time(&script_run_dir)?;
         let prefix = format!("[{} {}] ", id.name(), id.version());
         let mut warnings_in_case_of_panic = Vec::new();
+        let start = Instant::now();
         let output = cmd
-            .exec_with_streaming(
+            .exec_with_streaming_and_cpu_time(
                 &mut |stdout| {
                     if let Some(warning) = stdout.strip_prefix(CARGO_WARNING) {
                         warnings_in_case_of_panic.push(warning.to_owned());
@@ -518,6 +533,7 @@ fn build_work(cx: &mut Context<'_, '_>, unit: &Unit) -> CargoResult<Job> {
             });
 
         if let Err(error) = output {
+            let error = KindError::new(CargoErrorKind::Compile, error).into();
             insert_warnings_in_build_outputs(
                 build_script_outputs,
                 id,
@@ -527,7 +543,14 @@ fn build_work(cx: &mut Context<'_, '_>, unit: &Unit) -> CargoResult<Job> {
             return Err(error);
         }
 
-        let output = output.unwrap();
+        let (output, cpu_time) = output.unwrap();
+        if let (Some((_, jobs)), Some(cpu_time)) = (&jobserver_accounting, cpu_time) {
+            if let Some(warning) =
+                oversubscription_warning(&pkg_descr, cpu_time, start.elapsed(), *jobs)
+            {
+                state.warning(warning)?;
+            }
+        }
 
         // After the build command has finished running, we need to be sure to
         // remember all of its output so we can later discover precisely what it
@@ -624,6 +647,48 @@ fn insert_warnings_in_build_outputs(
         .insert(id, metadata_hash, build_output_with_only_warnings);
 }
 
+/// The `NUM_JOBS` of a build script about to run with `-Zjobserver-accounting`:
+/// the token of its own unit, plus the tokens of the jobserver nobody holds.
+fn num_jobs(jobserver: &jobserver::Client, jobs: u32) -> u32 {
+    match jobserver.available() {
+        Ok(available) => (available as u32).saturating_add(1).min(jobs),
+        Err(e) => {
+            tracing::debug!("failed to read the available jobserver tokens: {e}");
+            jobs
+        }
+    }
+}
+
+/// Warns about a build script which used more CPUs on average than the `jobs`
+/// allowed by `-j`, like when it runs a `make` or `cmake` sub-build that
+/// ignores the jobserver and `NUM_JOBS`.
+///
+/// Short runs are ignored, as their average is too noisy.
+fn oversubscription_warning(
+    pkg_descr: &str,
+    cpu_time: Duration,
+    elapsed: Duration,
+    jobs: u32,
+) -> Option<String> {
+    if elapsed < Duration::from_secs(1) {
+        return None;
+    }
+    let cpus = cpu_time.as_secs_f64() / elapsed.as_secs_f64();
+    if cpus <= f64::from(jobs) + 0.5 {
+        return None;
+    }
+    Some(format!(
+        "the build script of `{}` used {:.1} CPUs on average for {:.1}s, \
+         more than `-j{}` allows\n\
+         note: the processes it spawns should take jobserver tokens, \
+         or run at most `NUM_JOBS` jobs",
+        pkg_descr,
+        cpus,
+        elapsed.as_secs_f64(),
+        jobs
+    ))
+}
+
 impl BuildOutput {
     /// Like [`BuildOutput::parse`] but from a file path.
     pub fn parse_file(
@@ -1103,6 +1168,12 @@ pub fn build_map(cx: &mut Context<'_, '_>) -> CargoResult<()> {
     }
 }
 
+/// Gets the output of the previous run of the build script of `unit`, if it
+/// ran before, without running it.
+pub fn previous_output(cx: &mut Context<'_, '_>, unit: &Unit) -> Option<BuildOutput> {
+    prev_build_output(cx, unit).0
+}
+
 /// Returns the previous parsed `BuildOutput`, if any, from a previous
 /// execution.
 ///
@@ -149,6 +149,9 @@
 //! * dep-info for external build system integration.
 //! * Unstable `-Zbinary-dep-depinfo`.
 //!
+//! Their formats are read and written by [`util::dep_info`], which is public
+//! for external build tools.
+//!
 //! #### `rustc` dep-info files
 //!
 //! Cargo passes the `--emit=dep-info` flag to `rustc` so that `rustc` will
@@ -347,6 +350,7 @@
 //! [`JobQueue`]: super::job_queue::JobQueue
 //! [`output_depinfo`]: super::output_depinfo()
 //! [`CheckDepInfo`]: LocalFingerprint::CheckDepInfo
+//! [`util::dep_info`]: crate::util::dep_info
 //! [`RerunIfChanged`]: LocalFingerprint::RerunIfChanged
 //! [`CompileMode::RunCustomBuild`]: crate::core::compiler::CompileMode::RunCustomBuild
 //! [`A-rebuild-detection`]: https://github.com/rust-lang/cargo/issues?q=is%3Aissue+is%3Aopen+label%3AA-rebuild-detection
@@ -363,16 +367,18 @@ use std::str;
 use std::sync::{Arc, Mutex};
 use std::time::SystemTime;
 
-use anyhow::{bail, format_err, Context as _};
-use cargo_util::{paths, ProcessBuilder};
+use anyhow::{format_err, Context as _};
+use cargo_util::paths::{self, CaseSensitivity};
+use cargo_util::ProcessBuilder;
 use filetime::FileTime;
-use log::{debug, info};
 use serde::de;
 use serde::ser;
 use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
 
 use crate::core::compiler::unit_graph::UnitDep;
 use crate::core::Package;
+use crate::util::dep_info::{DepInfoPathType, EncodedDepInfo};
 use crate::util::errors::CargoResult;
 use crate::util::interning::InternedString;
 use crate::util::{self, try_canonicalize};
@@ -382,6 +388,11 @@ use crate::{Config, CARGO_ENV};
 use super::custom_build::BuildDeps;
 use super::{BuildContext, Context, FileFlavor, Job, Unit, Work};
 
+// The parsing of dep-info files moved to `util::dep_info`, and is still
+// available from here.
+#[allow(unused_imports)]
+pub use crate::util::dep_info::{parse_dep_info, parse_rustc_dep_info, RustcDepInfo};
+
 pub use dirty_reason::DirtyReason;
 
 /// Determines if a [`Unit`] is up-to-date, and if not prepares necessary work to
@@ -398,6 +409,10 @@ pub use dirty_reason::DirtyReason;
 /// transitively propagate throughout the dependency graph, it only forces this
 /// one unit which is very unlikely to be what you want unless you're
 /// exclusively talking about top-level units.
+#[tracing::instrument(
+    skip_all,
+    fields(package_id = %unit.pkg.package_id(), target = unit.target.name(), mode = ?unit.mode),
+)]
 pub fn prepare_target(cx: &mut Context<'_, '_>, unit: &Unit, force: bool) -> CargoResult<Job> {
     let _p = profile::start(format!(
         "fingerprint: {} / {}",
@@ -899,7 +914,8 @@ impl Fingerprint {
         *self.memoized_hash.lock().unwrap() = None;
     }
 
-    fn hash_u64(&self) -> u64 {
+    /// Returns the hash of this fingerprint, memoizing it.
+    pub fn hash_u64(&self) -> u64 {
         if let Some(s) = *self.memoized_hash.lock().unwrap() {
             return s;
         }
@@ -1408,12 +1424,18 @@ fn calculate_normal(cx: &mut Context<'_, '_>, unit: &Unit) -> CargoResult<Finger
     // Fill out a bunch more information that we'll be tracking typically
     // hashed to take up less space on disk as we just need to know when things
     // change.
+    // The `rustdocflags` of the profile aren't part of its hash, as only
+    // rustdoc sees them, so they are tracked here instead.
     let extra_flags = if unit.mode.is_doc() || unit.mode.is_doc_scrape() {
-        cx.bcx.rustdocflags_args(unit)
+        unit.profile
+            .rustdocflags
+            .iter()
+            .map(|flag| flag.to_string())
+            .chain(cx.bcx.rustdocflags_args(unit).iter().cloned())
+            .collect()
     } else {
-        cx.bcx.rustflags_args(unit)
-    }
-    .to_vec();
+        cx.bcx.rustflags_args(unit).to_vec()
+    };
 
     let profile_hash = util::hash_u64((
         &unit.profile,
@@ -1429,6 +1451,9 @@ fn calculate_normal(cx: &mut Context<'_, '_>, unit: &Unit) -> CargoResult<Finger
     if let Some(linker) = cx.bcx.linker(unit.kind) {
         linker.hash(&mut config);
     }
+    if let Some(linker) = cx.bcx.fast_linker(unit) {
+        linker.hash(&mut config);
+    }
     if unit.mode.is_doc() && cx.bcx.config.cli_unstable().rustdoc_map {
         if let Ok(map) = cx.bcx.config.doc_extern_map() {
             map.hash(&mut config);
@@ -1696,14 +1721,17 @@ fn write_fingerprint(loc: &Path, fingerprint: &Fingerprint) -> CargoResult<()> {
     // as we can use the full hash.
     let hash = fingerprint.hash_u64();
     debug!("write fingerprint ({:x}) : {}", hash, loc.display());
-    paths::write(loc, util::to_hex(hash).as_bytes())?;
 
+    // Both files are replaced atomically, and the hash last, so that a build
+    // interrupted while writing them leaves either the old or the new
+    // fingerprint, never a partial one.
     let json = serde_json::to_string(fingerprint).unwrap();
     if cfg!(debug_assertions) {
         let f: Fingerprint = serde_json::from_str(&json).unwrap();
         assert_eq!(f.hash_u64(), hash);
     }
-    paths::write(&loc.with_extension("json"), json.as_bytes())?;
+    paths::write_atomic(&loc.with_extension("json"), json.as_bytes())?;
+    paths::write_atomic(loc, util::to_hex(hash).as_bytes())?;
     Ok(())
 }
 
@@ -1792,45 +1820,6 @@ fn log_compare(unit: &Unit, compare: &CargoResult<Option<DirtyReason>>) {
     }
 }
 
-/// Parses Cargo's internal [`EncodedDepInfo`] structure that was previously
-/// serialized to disk.
-///
-/// Note that this is not rustc's `*.d` files.
-///
-/// Also note that rustc's `*.d` files are translated to Cargo-specific
-/// `EncodedDepInfo` files after compilations have finished in
-/// [`translate_dep_info`].
-///
-/// Returns `None` if the file is corrupt or couldn't be read from disk. This
-/// indicates that the crate should likely be rebuilt.
-pub fn parse_dep_info(
-    pkg_root: &Path,
-    target_root: &Path,
-    dep_info: &Path,
-) -> CargoResult<Option<RustcDepInfo>> {
-    let data = match paths::read_bytes(dep_info) {
-        Ok(data) => data,
-        Err(_) => return Ok(None),
-    };
-    let info = match EncodedDepInfo::parse(&data) {
-        Some(info) => info,
-        None => {
-            log::warn!("failed to parse cargo's dep-info at {:?}", dep_info);
-            return Ok(None);
-        }
-    };
-    let mut ret = RustcDepInfo::default();
-    ret.env = info.env;
-    ret.files.extend(info.files.into_iter().map(|(ty, path)| {
-        match ty {
-            DepInfoPathType::PackageRootRelative => pkg_root.join(path),
-            // N.B. path might be absolute here in which case the join will have no effect
-            DepInfoPathType::TargetRootRelative => target_root.join(path),
-        }
-    }));
-    Ok(Some(ret))
-}
-
 /// Calcuates the fingerprint of a unit thats contains no dep-info files.
 fn pkg_fingerprint(bcx: &BuildContext<'_, '_>, pkg: &Package) -> CargoResult<String> {
     let source_id = pkg.package_id().source_id();
@@ -1927,16 +1916,6 @@ where
     None
 }
 
-/// Tells the associated path in [`EncodedDepInfo::files`] is relative to package root,
-/// target root, or absolute.
-enum DepInfoPathType {
-    /// src/, e.g. src/lib.rs
-    PackageRootRelative,
-    /// target/debug/deps/lib...
-    /// or an absolute path /.../sysroot/...
-    TargetRootRelative,
-}
-
 /// Parses the dep-info file coming out of rustc into a Cargo-specific format.
 ///
 /// This function will parse `rustc_dep_info` as a makefile-style dep info to
@@ -1960,6 +1939,10 @@ enum DepInfoPathType {
 ///
 /// The serialized Cargo format will contain a list of files, all of which are
 /// relative if they're under `root`. or absolute if they're elsewhere.
+///
+/// Paths are matched against the roots with the `case_sensitivity` of the
+/// filesystem of the target directory, as rustc may name files in another
+/// case than their canonical path on case-insensitive filesystems.
 pub fn translate_dep_info(
     rustc_dep_info: &Path,
     cargo_dep_info: &Path,
@@ -1968,11 +1951,28 @@ pub fn translate_dep_info(
     target_root: &Path,
     rustc_cmd: &ProcessBuilder,
     allow_package: bool,
+    case_sensitivity: CaseSensitivity,
 ) -> CargoResult<()> {
     let depinfo = parse_rustc_dep_info(rustc_dep_info)?;
 
-    let target_root = try_canonicalize(target_root)?;
-    let pkg_root = try_canonicalize(pkg_root)?;
+    // Canonical paths are extended-length paths on Windows, while rustc may
+    // write either form, so both are normalized before being compared.
+    let target_root = paths::normalize_verbatim(&try_canonicalize(target_root)?);
+    let pkg_root = match try_canonicalize(pkg_root) {
+        Ok(pkg_root) => pkg_root,
+        // The root of a package built from a manifest held in memory may not
+        // exist, when its targets are elsewhere.
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            debug!(
+                "failed to canonicalize package root {}, using it as is: {}",
+                pkg_root.display(),
+                e
+            );
+            pkg_root.to_path_buf()
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let pkg_root = paths::normalize_verbatim(&pkg_root);
     let mut on_disk_info = EncodedDepInfo::default();
     on_disk_info.env = depinfo.env;
 
@@ -2016,196 +2016,24 @@ pub fn translate_dep_info(
         // a bug where --remap-path-prefix is affecting .d files, causing them
         // to point to non-existent paths.
         let canon_file = try_canonicalize(&abs_file).unwrap_or_else(|_| abs_file.clone());
-
-        let (ty, path) = if let Ok(stripped) = canon_file.strip_prefix(&target_root) {
-            (DepInfoPathType::TargetRootRelative, stripped)
-        } else if let Ok(stripped) = canon_file.strip_prefix(&pkg_root) {
-            if !allow_package {
-                continue;
-            }
-            (DepInfoPathType::PackageRootRelative, stripped)
-        } else {
-            // It's definitely not target root relative, but this is an absolute path (since it was
-            // joined to rustc_cwd) and as such re-joining it later to the target root will have no
-            // effect.
-            (DepInfoPathType::TargetRootRelative, &*abs_file)
-        };
-        on_disk_info.files.push((ty, path.to_owned()));
-    }
-    paths::write(cargo_dep_info, on_disk_info.serialize()?)?;
-    Ok(())
-}
-
-/// The representation of the `.d` dep-info file generated by rustc
-#[derive(Default)]
-pub struct RustcDepInfo {
-    /// The list of files that the main target in the dep-info file depends on.
-    pub files: Vec<PathBuf>,
-    /// The list of environment variables we found that the rustc compilation
-    /// depends on.
-    ///
-    /// The first element of the pair is the name of the env var and the second
-    /// item is the value. `Some` means that the env var was set, and `None`
-    /// means that the env var wasn't actually set and the compilation depends
-    /// on it not being set.
-    pub env: Vec<(String, Option<String>)>,
-}
-
-/// Same as [`RustcDepInfo`] except avoids absolute paths as much as possible to
-/// allow moving around the target directory.
-///
-/// This is also stored in an optimized format to make parsing it fast because
-/// Cargo will read it for crates on all future compilations.
-#[derive(Default)]
-struct EncodedDepInfo {
-    files: Vec<(DepInfoPathType, PathBuf)>,
-    env: Vec<(String, Option<String>)>,
-}
-
-impl EncodedDepInfo {
-    fn parse(mut bytes: &[u8]) -> Option<EncodedDepInfo> {
-        let bytes = &mut bytes;
-        let nfiles = read_usize(bytes)?;
-        let mut files = Vec::with_capacity(nfiles as usize);
-        for _ in 0..nfiles {
-            let ty = match read_u8(bytes)? {
-                0 => DepInfoPathType::PackageRootRelative,
-                1 => DepInfoPathType::TargetRootRelative,
-                _ => return None,
-            };
-            let bytes = read_bytes(bytes)?;
-            files.push((ty, paths::bytes2path(bytes).ok()?));
-        }
-
-        let nenv = read_usize(bytes)?;
-        let mut env = Vec::with_capacity(nenv as usize);
-        for _ in 0..nenv {
-            let key = str::from_utf8(read_bytes(bytes)?).ok()?.to_string();
-            let val = match read_u8(bytes)? {
-                0 => None,
-                1 => Some(str::from_utf8(read_bytes(bytes)?).ok()?.to_string()),
-                _ => return None,
-            };
-            env.push((key, val));
-        }
-        return Some(EncodedDepInfo { files, env });
-
-        fn read_usize(bytes: &mut &[u8]) -> Option<usize> {
-            let ret = bytes.get(..4)?;
-            *bytes = &bytes[4..];
-            Some(u32::from_le_bytes(ret.try_into().unwrap()) as usize)
-        }
-
-        fn read_u8(bytes: &mut &[u8]) -> Option<u8> {
-            let ret = *bytes.get(0)?;
-            *bytes = &bytes[1..];
-            Some(ret)
-        }
-
-        fn read_bytes<'a>(bytes: &mut &'a [u8]) -> Option<&'a [u8]> {
-            let n = read_usize(bytes)? as usize;
-            let ret = bytes.get(..n)?;
-            *bytes = &bytes[n..];
-            Some(ret)
-        }
-    }
-
-    fn serialize(&self) -> CargoResult<Vec<u8>> {
-        let mut ret = Vec::new();
-        let dst = &mut ret;
-        write_usize(dst, self.files.len());
-        for (ty, file) in self.files.iter() {
-            match ty {
-                DepInfoPathType::PackageRootRelative => dst.push(0),
-                DepInfoPathType::TargetRootRelative => dst.push(1),
-            }
-            write_bytes(dst, paths::path2bytes(file)?);
-        }
-
-        write_usize(dst, self.env.len());
-        for (key, val) in self.env.iter() {
-            write_bytes(dst, key);
-            match val {
-                None => dst.push(0),
-                Some(val) => {
-                    dst.push(1);
-                    write_bytes(dst, val);
+        let canon_file = paths::normalize_verbatim(&canon_file);
+
+        let (ty, path) =
+            if let Some(stripped) = case_sensitivity.strip_prefix(&canon_file, &target_root) {
+                (DepInfoPathType::TargetRootRelative, stripped)
+            } else if let Some(stripped) = case_sensitivity.strip_prefix(&canon_file, &pkg_root) {
+                if !allow_package {
+                    continue;
                 }
-            }
-        }
-        return Ok(ret);
-
-        fn write_bytes(dst: &mut Vec<u8>, val: impl AsRef<[u8]>) {
-            let val = val.as_ref();
-            write_usize(dst, val.len());
-            dst.extend_from_slice(val);
-        }
-
-        fn write_usize(dst: &mut Vec<u8>, val: usize) {
-            dst.extend(&u32::to_le_bytes(val as u32));
-        }
-    }
-}
-
-/// Parse the `.d` dep-info file generated by rustc.
-pub fn parse_rustc_dep_info(rustc_dep_info: &Path) -> CargoResult<RustcDepInfo> {
-    let contents = paths::read(rustc_dep_info)?;
-    let mut ret = RustcDepInfo::default();
-    let mut found_deps = false;
-
-    for line in contents.lines() {
-        if let Some(rest) = line.strip_prefix("# env-dep:") {
-            let mut parts = rest.splitn(2, '=');
-            let env_var = match parts.next() {
-                Some(s) => s,
-                None => continue,
-            };
-            let env_val = match parts.next() {
-                Some(s) => Some(unescape_env(s)?),
-                None => None,
+                (DepInfoPathType::PackageRootRelative, stripped)
+            } else {
+                // It's definitely not target root relative, but this is an absolute path (since it was
+                // joined to rustc_cwd) and as such re-joining it later to the target root will have no
+                // effect.
+                (DepInfoPathType::TargetRootRelative, &*abs_file)
             };
-            ret.env.push((unescape_env(env_var)?, env_val));
-        } else if let Some(pos) = line.find(": ") {
-            if found_deps {
-                continue;
-            }
-            found_deps = true;
-            let mut deps = line[pos + 2..].split_whitespace();
-
-            while let Some(s) = deps.next() {
-                let mut file = s.to_string();
-                while file.ends_with('\\') {
-                    file.pop();
-                    file.push(' ');
-                    file.push_str(deps.next().ok_or_else(|| {
-                        internal("malformed dep-info format, trailing \\".to_string())
-                    })?);
-                }
-                ret.files.push(file.into());
-            }
-        }
-    }
-    return Ok(ret);
-
-    // rustc tries to fit env var names and values all on a single line, which
-    // means it needs to escape `\r` and `\n`. The escape syntax used is "\n"
-    // which means that `\` also needs to be escaped.
-    fn unescape_env(s: &str) -> CargoResult<String> {
-        let mut ret = String::with_capacity(s.len());
-        let mut chars = s.chars();
-        while let Some(c) = chars.next() {
-            if c != '\\' {
-                ret.push(c);
-                continue;
-            }
-            match chars.next() {
-                Some('\\') => ret.push('\\'),
-                Some('n') => ret.push('\n'),
-                Some('r') => ret.push('\r'),
-                Some(c) => bail!("unknown escape character `{}`", c),
-                None => bail!("unterminated escape character"),
-            }
-        }
-        Ok(ret)
+        on_disk_info.files.push((ty, path.to_owned()));
     }
+    paths::write_atomic(cargo_dep_info, on_disk_info.serialize()?)?;
+    Ok(())
 }
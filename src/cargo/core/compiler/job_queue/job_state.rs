@@ -9,7 +9,7 @@ use crate::core::compiler::future_incompat::FutureBreakageItem;
 use crate::util::Queue;
 use crate::CargoResult;
 
-use super::{Artifact, DiagDedupe, Job, JobId, Message};
+use super::{Artifact, CommandEnv, DiagDedupe, Job, JobId, Message};
 
 /// A `JobState` is constructed by `JobQueue::run` and passed to `Job::run`. It includes everything
 /// necessary to communicate between the main thread and the execution of the job.
@@ -44,6 +44,10 @@ pub struct JobState<'a, 'cfg> {
     /// sending a double message later on.
     rmeta_required: Cell<bool>,
 
+    /// Whether the environment and command line of the processes run by the
+    /// job are sent to the main thread, for `--emit-env`.
+    emit_env: bool,
+
     // Historical versions of Cargo made use of the `'a` argument here, so to
     // leave the door open to future refactorings keep it here.
     _marker: marker::PhantomData<&'a ()>,
@@ -55,18 +59,22 @@ impl<'a, 'cfg> JobState<'a, 'cfg> {
         messages: Arc<Queue<Message>>,
         output: Option<&'a DiagDedupe<'cfg>>,
         rmeta_required: bool,
+        emit_env: bool,
     ) -> Self {
         Self {
             id,
             messages,
             output,
             rmeta_required: Cell::new(rmeta_required),
+            emit_env,
             _marker: marker::PhantomData,
         }
     }
 
     pub fn running(&self, cmd: &ProcessBuilder) {
-        self.messages.push(Message::Run(self.id, cmd.to_string()));
+        let env = self.emit_env.then(|| CommandEnv::new(cmd));
+        self.messages
+            .push(Message::Run(self.id, cmd.to_string(), env));
     }
 
     pub fn build_plan(
@@ -0,0 +1,154 @@
+//! See [`LoadMonitor`].
+
+use std::time::{Duration, Instant};
+
+use crate::core::compiler::AdaptiveJobs;
+use crate::util::{self, Config};
+
+/// How often the system is sampled.
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Limits the number of jobs running at once with `build.jobs = "auto"`.
+///
+/// The limit starts at `-j`. Each time the system is sampled over one of the
+/// [`AdaptiveJobs`] thresholds, it is lowered, down to a single job: halved
+/// for memory, as running out of it fails the build, and by one job for the
+/// load average. Each time it is back under them, it grows by one job, up to
+/// `-j` again.
+///
+/// Running jobs are never stopped, the job queue only waits for some of them
+/// to finish before starting others. The limit isn't lowered again until
+/// then, since the previous lowering didn't have any effect yet.
+pub struct LoadMonitor {
+    thresholds: AdaptiveJobs,
+    /// The number of CPUs the load average is compared to.
+    cpus: u32,
+    jobs: u32,
+    limit: u32,
+    last_sample: Option<Instant>,
+    /// The load average and free memory to use instead of the ones of the
+    /// system, for tests.
+    test_sample: Option<(f64, u64)>,
+}
+
+/// What the system was over when the limit was lowered.
+enum Pressure {
+    Load(f64),
+    Memory(u64),
+}
+
+impl LoadMonitor {
+    pub fn new(config: &Config, thresholds: AdaptiveJobs, jobs: u32) -> LoadMonitor {
+        let cpus = std::thread::available_parallelism().map_or(1, |n| n.get() as u32);
+        let test_sample = config
+            .get_env("__CARGO_TEST_SYSTEM_LOAD")
+            .ok()
+            .and_then(|s| {
+                let (load, free_mib) = s.split_once(',')?;
+                Some((
+                    load.parse().ok()?,
+                    free_mib.parse::<u64>().ok()? * 1024 * 1024,
+                ))
+            });
+        LoadMonitor {
+            thresholds,
+            cpus,
+            jobs,
+            limit: jobs,
+            last_sample: None,
+            test_sample,
+        }
+    }
+
+    /// The number of jobs which may run at once.
+    pub fn limit(&self) -> u32 {
+        self.limit
+    }
+
+    /// Samples the system if it wasn't sampled recently, and updates the
+    /// limit, with `active` jobs running.
+    ///
+    /// Returns a message describing the new limit when it changed.
+    pub fn update(&mut self, active: usize) -> Option<String> {
+        let now = Instant::now();
+        if self
+            .last_sample
+            .map_or(false, |last| now - last < SAMPLE_INTERVAL)
+        {
+            return None;
+        }
+        self.last_sample = Some(now);
+
+        let (load, free_memory) = match self.test_sample {
+            Some((load, free_memory)) => (Some(load), Some(free_memory)),
+            None => (load_average(), free_memory()),
+        };
+        let pressure = match (load, free_memory) {
+            (_, Some(free)) if free < self.thresholds.min_free_memory => {
+                Some(Pressure::Memory(free))
+            }
+            (Some(load), _) if load > self.thresholds.max_load * f64::from(self.cpus) => {
+                Some(Pressure::Load(load))
+            }
+            _ => None,
+        };
+
+        let previous = self.limit;
+        self.limit = match &pressure {
+            Some(_) if active > self.limit as usize => self.limit,
+            Some(Pressure::Memory(_)) => (self.limit / 2).max(1),
+            Some(Pressure::Load(_)) => (self.limit - 1).max(1),
+            None => (self.limit + 1).min(self.jobs),
+        };
+        if self.limit == previous {
+            return None;
+        }
+        let running = format!(
+            "running at most {} {} at once",
+            self.limit,
+            if self.limit == 1 { "job" } else { "jobs" }
+        );
+        Some(match pressure {
+            Some(Pressure::Load(load)) => format!(
+                "{running}, the load average is {load:.1} on {} CPUs",
+                self.cpus
+            ),
+            Some(Pressure::Memory(free)) => {
+                let (size, unit) = util::human_readable_bytes(free);
+                format!("{running}, only {size:.1} {unit} of memory is available")
+            }
+            None => running,
+        })
+    }
+}
+
+/// The load average of the last minute.
+#[cfg(unix)]
+fn load_average() -> Option<f64> {
+    let mut loads = [0.0; 3];
+    let n = unsafe { libc::getloadavg(loads.as_mut_ptr(), 1) };
+    (n == 1).then(|| loads[0])
+}
+
+#[cfg(not(unix))]
+fn load_average() -> Option<f64> {
+    None
+}
+
+/// The memory available to start new processes, in bytes.
+#[cfg(target_os = "linux")]
+fn free_memory() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    meminfo.lines().find_map(|line| {
+        let kib = line
+            .strip_prefix("MemAvailable:")?
+            .trim()
+            .strip_suffix("kB")?;
+        Some(kib.trim().parse::<u64>().ok()? * 1024)
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn free_memory() -> Option<u64> {
+    None
+}
@@ -112,25 +112,27 @@
 
 mod job;
 mod job_state;
+mod load_monitor;
 
 use std::cell::RefCell;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Write as _;
-use std::io;
+use std::io::{self, Write as _};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::thread::{self, Scope};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::{format_err, Context as _};
-use cargo_util::ProcessBuilder;
+use cargo_util::{paths, ProcessBuilder};
 use jobserver::{Acquired, HelperThread};
-use log::{debug, trace};
 use semver::Version;
+use tracing::{debug, trace};
 
 pub use self::job::Freshness::{self, Dirty, Fresh};
 pub use self::job::{Job, Work};
 pub use self::job_state::JobState;
+use self::load_monitor::LoadMonitor;
 use super::context::OutputFile;
 use super::timings::Timings;
 use super::{BuildContext, BuildPlan, CompileMode, Context, Unit};
@@ -141,10 +143,11 @@ use crate::core::compiler::future_incompat::{
 use crate::core::resolver::ResolveBehavior;
 use crate::core::{PackageId, Shell, TargetKind};
 use crate::util::diagnostic_server::{self, DiagnosticPrinter};
-use crate::util::errors::AlreadyPrintedError;
+use crate::util::errors::{AlreadyPrintedError, CargoErrorKind, KindError};
 use crate::util::machine_message::{self, Message as _};
 use crate::util::CargoResult;
 use crate::util::{self, internal, profile};
+use crate::util::{CancellationToken, Cancelled};
 use crate::util::{Config, DependencyQueue, Progress, ProgressStyle, Queue};
 
 /// This structure is backed by the `DependencyQueue` type and manages the
@@ -200,6 +203,29 @@ struct DrainState<'cfg> {
     /// How many jobs we've finished
     finished: usize,
     per_package_future_incompat_reports: Vec<FutureIncompatReportPackage>,
+
+    /// Cancels the build, see [`DrainState::check_cancelled`].
+    cancellation: CancellationToken,
+    /// When the build is cancelled, from `build.timeout`.
+    deadline: Option<Instant>,
+    /// Whether the build was cancelled, after which no more work is started.
+    cancelled: bool,
+    /// The units which were running when the build was cancelled, whose
+    /// processes were killed.
+    killed: Vec<Unit>,
+    /// Limits the jobs running at once with `build.jobs = "auto"`.
+    load_monitor: Option<LoadMonitor>,
+}
+
+/// Whether `name` is that of a temporary directory of rustc, which it
+/// creates in its output directory with a random suffix, like `rmetaAbc123`
+/// for metadata and `rustcAbc123` for linking.
+fn is_rustc_temp_dir(name: &str) -> bool {
+    ["rmeta", "rustc"].iter().any(|prefix| {
+        name.strip_prefix(prefix).map_or(false, |rest| {
+            rest.len() == 6 && rest.bytes().all(|b| b.is_ascii_alphanumeric())
+        })
+    })
 }
 
 /// Count of warnings, used to print a summary after the job succeeds
@@ -248,6 +274,9 @@ pub enum FixableWarnings {
 
 pub struct ErrorsDuringDrain {
     pub count: usize,
+    /// The kind of the first error, given to the error returned once the
+    /// queue is drained.
+    pub kind: CargoErrorKind,
 }
 
 struct ErrorToHandle {
@@ -341,7 +370,7 @@ enum Artifact {
 }
 
 enum Message {
-    Run(JobId, String),
+    Run(JobId, String, Option<CommandEnv>),
     BuildPlanMsg(String, ProcessBuilder, Arc<Vec<OutputFile>>),
     Stdout(String),
     Stderr(String),
@@ -462,6 +491,7 @@ impl<'cfg> JobQueue<'cfg> {
     /// This function will spawn off `config.jobs()` workers to build all of the
     /// necessary dependencies, in order. Freshness is propagated as far as
     /// possible along each dependency chain.
+    #[tracing::instrument(skip_all)]
     pub fn execute(mut self, cx: &mut Context<'_, '_>, plan: &mut BuildPlan) -> CargoResult<()> {
         let _p = profile::start("executing the job graph");
         self.queue.queue_finished();
@@ -490,6 +520,13 @@ impl<'cfg> JobQueue<'cfg> {
             print: DiagnosticPrinter::new(cx.bcx.config, &cx.bcx.rustc().workspace_wrapper),
             finished: 0,
             per_package_future_incompat_reports: Vec::new(),
+            cancellation: cx.bcx.config.cancellation().clone(),
+            deadline: cx.bcx.build_config.timeout.map(|t| Instant::now() + t),
+            cancelled: false,
+            killed: Vec::new(),
+            load_monitor: cx.bcx.build_config.adaptive_jobs.map(|thresholds| {
+                LoadMonitor::new(cx.bcx.config, thresholds, cx.bcx.build_config.jobs)
+            }),
         };
 
         // Create a helper thread for acquiring jobserver tokens
@@ -557,7 +594,12 @@ impl<'cfg> DrainState<'cfg> {
         // The `pending_queue` is sorted in ascending priority order, and we
         // remove items from its end to schedule the highest priority items
         // sooner.
-        while self.has_extra_tokens() && !self.pending_queue.is_empty() {
+        if let Some(monitor) = &mut self.load_monitor {
+            if let Some(msg) = monitor.update(self.active.len()) {
+                cx.bcx.config.shell().verbose(|s| s.note(&msg))?;
+            }
+        }
+        while self.has_extra_tokens() && !self.over_load_limit() && !self.pending_queue.is_empty() {
             let (unit, job, _) = self.pending_queue.pop().unwrap();
             *self.counts.get_mut(&unit.pkg.package_id()).unwrap() -= 1;
             if !cx.bcx.build_config.build_plan {
@@ -577,6 +619,13 @@ impl<'cfg> DrainState<'cfg> {
         self.active.len() < self.tokens.len() + 1
     }
 
+    /// Whether as many jobs as the [`LoadMonitor`] allows are running.
+    fn over_load_limit(&self) -> bool {
+        self.load_monitor
+            .as_ref()
+            .map_or(false, |m| self.active.len() >= m.limit() as usize)
+    }
+
     fn handle_event(
         &mut self,
         cx: &mut Context<'_, '_>,
@@ -584,11 +633,14 @@ impl<'cfg> DrainState<'cfg> {
         event: Message,
     ) -> Result<(), ErrorToHandle> {
         match event {
-            Message::Run(id, cmd) => {
+            Message::Run(id, cmd, env) => {
                 cx.bcx
                     .config
                     .shell()
                     .verbose(|c| c.status("Running", &cmd))?;
+                if let Some(env) = env {
+                    emit_env(cx, &self.active[&id], env)?;
+                }
                 self.timings.unit_start(id, self.active[&id].clone());
             }
             Message::BuildPlanMsg(module_name, cmd, filenames) => {
@@ -706,6 +758,10 @@ impl<'cfg> DrainState<'cfg> {
                         events.push(message);
                         break;
                     }
+                    None if !self.cancelled && self.cancellation_requested() => break,
+                    // Sample the system again, to start the waiting jobs once
+                    // the pressure is gone.
+                    None if self.over_load_limit() && !self.pending_queue.is_empty() => break,
                     None => continue,
                 }
             }
@@ -738,12 +794,18 @@ impl<'cfg> DrainState<'cfg> {
         // successful and otherwise wait for pending work to finish if it failed
         // and then immediately return (or keep going, if requested by the build
         // config).
-        let mut errors = ErrorsDuringDrain { count: 0 };
+        let mut errors = ErrorsDuringDrain {
+            count: 0,
+            kind: CargoErrorKind::Other,
+        };
         // CAUTION! Do not use `?` or break out of the loop early. Every error
         // must be handled in such a way that the loop is still allowed to
         // drain event messages.
         loop {
-            if errors.count == 0 || cx.bcx.build_config.keep_going {
+            if let Some(e) = self.check_cancelled(cx) {
+                self.handle_error(&mut cx.bcx.config.shell(), &mut errors, e);
+            }
+            if (errors.count == 0 || cx.bcx.build_config.keep_going) && !self.cancelled {
                 if let Err(e) = self.spawn_work_if_possible(cx, jobserver_helper, scope) {
                     self.handle_error(&mut cx.bcx.config.shell(), &mut errors, e);
                 }
@@ -760,13 +822,25 @@ impl<'cfg> DrainState<'cfg> {
             // jobserver interface is architected we may acquire a token that we
             // don't actually use, and if this happens just relinquish it back
             // to the jobserver itself.
-            for event in self.wait_for_events() {
-                if let Err(event_err) = self.handle_event(cx, plan, event) {
+            let events = self.wait_for_events();
+            // Checked before handling the events, which may be the failures
+            // of the jobs killed by a cancellation.
+            if let Some(e) = self.check_cancelled(cx) {
+                self.handle_error(&mut cx.bcx.config.shell(), &mut errors, e);
+            }
+            for event in events {
+                if let Err(mut event_err) = self.handle_event(cx, plan, event) {
+                    // The jobs killed by the cancellation fail, which is
+                    // expected.
+                    event_err.print_always &= !self.cancelled;
                     self.handle_error(&mut cx.bcx.config.shell(), &mut errors, event_err);
                 }
             }
         }
         self.progress.clear();
+        if self.cancelled {
+            self.remove_temp_files(cx);
+        }
 
         let profile_name = cx.bcx.build_config.requested_profile;
         // NOTE: this may be a bit inaccurate, since this may not display the
@@ -826,6 +900,79 @@ impl<'cfg> DrainState<'cfg> {
         }
     }
 
+    /// Whether the token of the build was cancelled, or `build.timeout` has
+    /// passed.
+    fn cancellation_requested(&self) -> bool {
+        self.cancellation.is_cancelled() || self.deadline.map_or(false, |d| Instant::now() >= d)
+    }
+
+    /// Cancels the build when requested, the first time, by cancelling its
+    /// token to kill the running processes, and returns the error to report.
+    fn check_cancelled(&mut self, cx: &Context<'_, '_>) -> Option<anyhow::Error> {
+        if self.cancelled || !self.cancellation_requested() {
+            return None;
+        }
+        self.cancelled = true;
+        let error = if self.cancellation.is_cancelled() {
+            Cancelled.into()
+        } else {
+            let timeout = cx.bcx.build_config.timeout.unwrap();
+            KindError::new(
+                CargoErrorKind::Cancelled,
+                format_err!(
+                    "build timed out after {}, as set by `build.timeout`",
+                    util::elapsed(timeout)
+                ),
+            )
+            .into()
+        };
+        self.cancellation.cancel();
+        self.killed = self.active.values().cloned().collect();
+        Some(error)
+    }
+
+    /// Removes the temporary files of the rustc processes killed by a
+    /// cancellation, which would otherwise stay in the output directories
+    /// until `cargo clean`. The fingerprints of their units were removed
+    /// before they started, so the next build builds them again.
+    fn remove_temp_files(&self, cx: &Context<'_, '_>) {
+        for unit in &self.killed {
+            if unit.mode.is_run_custom_build()
+                || unit.mode.is_doc()
+                || unit.mode.is_doc_test()
+                || unit.mode.is_doc_scrape()
+            {
+                continue;
+            }
+            // The object files of the codegen units are named after the
+            // output, like `foo-<metadata>.<cgu>.rcgu.o`.
+            let mut stem = unit.target.crate_name();
+            if cx.files().use_extra_filename(unit) {
+                write!(stem, "-{}", cx.files().metadata(unit)).unwrap();
+            }
+            stem.push('.');
+            let Ok(entries) = std::fs::read_dir(cx.files().out_dir(unit)) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                    continue;
+                };
+                let result = if is_rustc_temp_dir(name) && path.is_dir() {
+                    paths::remove_dir_all(&path)
+                } else if name.starts_with(&stem) && name.contains(".rcgu.") {
+                    paths::remove_file(&path)
+                } else {
+                    continue;
+                };
+                if let Err(e) = result {
+                    debug!("failed to remove `{}`: {e}", path.display());
+                }
+            }
+        }
+    }
+
     fn handle_error(
         &self,
         shell: &mut Shell,
@@ -835,12 +982,15 @@ impl<'cfg> DrainState<'cfg> {
         let new_err = new_err.into();
         if new_err.print_always || err_state.count == 0 {
             crate::display_error(&new_err.error, shell);
+            if err_state.count == 0 {
+                err_state.kind = CargoErrorKind::of(&new_err.error);
+            }
             if err_state.count == 0 && !self.active.is_empty() {
                 let _ = shell.warn("build failed, waiting for other jobs to finish...");
             }
             err_state.count += 1;
         } else {
-            log::warn!("{:?}", new_err.error);
+            tracing::warn!("{:?}", new_err.error);
         }
     }
 
@@ -914,9 +1064,21 @@ impl<'cfg> DrainState<'cfg> {
         let messages = self.messages.clone();
         let is_fresh = job.freshness().is_fresh();
         let rmeta_required = cx.rmeta_required(unit);
+        let emit_env = cx.bcx.build_config.emit_env;
+        // Entered on the thread running the job, so that the traces of the
+        // job are attributed to its unit.
+        let span = tracing::debug_span!(
+            "unit",
+            %id,
+            package_id = %unit.pkg.package_id(),
+            target = unit.target.name(),
+            mode = ?unit.mode,
+            fresh = is_fresh,
+        );
 
         let doit = move |diag_dedupe| {
-            let state = JobState::new(id, messages, diag_dedupe, rmeta_required);
+            let _span = span.entered();
+            let state = JobState::new(id, messages, diag_dedupe, rmeta_required, emit_env);
             state.run_to_finish(job);
         };
 
@@ -1116,18 +1278,18 @@ impl<'cfg> DrainState<'cfg> {
 
                 if unit.mode.is_doc() {
                     self.documented.insert(unit.pkg.package_id());
-                    config.shell().status("Documenting", &unit.pkg)?;
+                    package_status(config, "Documenting", unit)?;
                 } else if unit.mode.is_doc_test() {
                     // Skip doc test.
                 } else if unit.mode.is_doc_scrape() {
                     self.scraped.insert(unit.pkg.package_id());
-                    config.shell().status("Scraping", &unit.pkg)?;
+                    package_status(config, "Scraping", unit)?;
                 } else {
                     self.compiled.insert(unit.pkg.package_id());
                     if unit.mode.is_check() {
-                        config.shell().status("Checking", &unit.pkg)?;
+                        package_status(config, "Checking", unit)?;
                     } else {
-                        config.shell().status("Compiling", &unit.pkg)?;
+                        package_status(config, "Compiling", unit)?;
                     }
                 }
             }
@@ -1137,7 +1299,10 @@ impl<'cfg> DrainState<'cfg> {
                     && !(unit.mode.is_doc_test() && self.compiled.contains(&unit.pkg.package_id()))
                 {
                     self.compiled.insert(unit.pkg.package_id());
-                    config.shell().verbose(|c| c.status("Fresh", &unit.pkg))?;
+                    config.shell().verbose(|shell| {
+                        let pkg = shell.package_hyperlink(unit.pkg.package_id());
+                        shell.status("Fresh", pkg)
+                    })?;
                 }
             }
         }
@@ -1173,10 +1338,101 @@ feature resolver. Try updating to diesel 1.4.8 to fix this error.
 
 impl ErrorsDuringDrain {
     fn to_error(&self) -> Option<anyhow::Error> {
-        match self.count {
-            0 => None,
-            1 => Some(format_err!("1 job failed")),
-            n => Some(format_err!("{} jobs failed", n)),
+        let error = match self.count {
+            0 => return None,
+            1 => format_err!("1 job failed"),
+            n => format_err!("{} jobs failed", n),
+        };
+        Some(KindError::new(self.kind, error).into())
+    }
+}
+
+/// Prints a status about the package of `unit`, which is displayed as a
+/// hyperlink with `term.hyperlinks`.
+fn package_status(config: &Config, status: &str, unit: &Unit) -> CargoResult<()> {
+    let mut shell = config.shell();
+    let pkg = shell.package_hyperlink(unit.pkg.package_id());
+    shell.status(status, pkg)
+}
+
+/// The parts of a process run by a job that `--emit-env` writes, sent to the
+/// main thread instead of the whole [`ProcessBuilder`].
+struct CommandEnv {
+    program: String,
+    args: Vec<String>,
+    cwd: Option<PathBuf>,
+    /// The variables set, or removed with `None`, for the process.
+    envs: Vec<(String, Option<String>)>,
+}
+
+impl CommandEnv {
+    fn new(cmd: &ProcessBuilder) -> CommandEnv {
+        CommandEnv {
+            program: cmd.get_program().to_string_lossy().into_owned(),
+            args: cmd
+                .get_args()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect(),
+            cwd: cmd.get_cwd().map(Path::to_path_buf),
+            envs: cmd
+                .get_envs()
+                .iter()
+                .map(|(key, value)| {
+                    let value = value.as_ref().map(|v| v.to_string_lossy().into_owned());
+                    (key.clone(), value)
+                })
+                .collect(),
         }
     }
 }
+
+/// Writes the environment and command line of `cmd`, run for `unit`, to the
+/// file of [`CompilationFiles::env_capture_path`], and to the JSON messages,
+/// for `--emit-env`.
+///
+/// The environment may contain secrets, so on unix the file is only readable
+/// by its owner.
+///
+/// [`CompilationFiles::env_capture_path`]: super::CompilationFiles::env_capture_path
+fn emit_env(cx: &Context<'_, '_>, unit: &Unit, cmd: CommandEnv) -> CargoResult<()> {
+    let mut env: BTreeMap<String, String> = cx
+        .bcx
+        .config
+        .env()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    for (key, value) in cmd.envs {
+        match value {
+            Some(value) => env.insert(key, value),
+            None => env.remove(&key),
+        };
+    }
+    let msg = machine_message::UnitEnv {
+        package_id: unit.pkg.package_id(),
+        target: &unit.target,
+        mode: unit.mode,
+        program: cmd.program,
+        args: cmd.args,
+        cwd: cmd.cwd.as_deref(),
+        env,
+    }
+    .to_json_string();
+
+    let path = cx.files().env_capture_path(unit);
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(&path)
+        .with_context(|| format!("failed to create `{}`", path.display()))?;
+    writeln!(file, "{msg}").with_context(|| format!("failed to write `{}`", path.display()))?;
+
+    if cx.bcx.build_config.emit_json() {
+        writeln!(cx.bcx.config.shell().out(), "{msg}")?;
+    }
+    Ok(())
+}
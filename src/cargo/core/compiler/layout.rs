@@ -11,6 +11,10 @@
 //!     # Cache of `rustc -Vv` output for performance.
 //!     .rustc-info.json
 //!
+//!     # Whether the filesystem tells apart paths that only differ by case,
+//!     # probed the first time the directory is used.
+//!     .case-sensitivity
+//!
 //!     # All final artifacts are linked into this directory from `deps`.
 //!     # Note that named profiles will soon be included as separate directories
 //!     # here. They have a restricted format, similar to Rust identifiers, so
@@ -22,6 +26,10 @@
 //!         # from using it at the same time.
 //!         .cargo-lock
 //!
+//!         # Machine-readable index mapping each unit's metadata hash to the
+//!         # files it produced. Only written with `-Zlayout-index`.
+//!         .layout-index.json
+//!
 //!         # Hidden directory that holds all of the fingerprint files for all
 //!         # packages
 //!         .fingerprint/
@@ -101,12 +109,28 @@
 //! When cross-compiling, the layout is the same, except it appears in
 //! `target/$TRIPLE`.
 
-use crate::core::compiler::CompileTarget;
-use crate::core::Workspace;
+use crate::core::compiler::{CompileKind, CompileMode, CompileTarget, Context, Unit};
+use crate::core::{PackageId, Workspace};
 use crate::util::{CargoResult, FileLock};
-use cargo_util::paths;
+use anyhow::Context as _;
+use cargo_util::paths::{self, CaseSensitivity};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
 use std::path::{Path, PathBuf};
 
+/// Name of the unit index file placed in the destination directory.
+const INDEX_FILE: &str = ".layout-index.json";
+
+/// Name of the file in the target directory recording the case sensitivity
+/// of its filesystem.
+const CASE_SENSITIVITY_FILE: &str = ".case-sensitivity";
+
+/// Version of the unit index format.
+///
+/// Bump this whenever an incompatible change is made to [`IndexEntry`].
+/// Indexes with a different version are discarded rather than merged.
+const INDEX_VERSION: u32 = 1;
+
 /// Contains the paths of all target output locations.
 ///
 /// See module docs for more information.
@@ -122,7 +146,8 @@ pub struct Layout {
     build: PathBuf,
     /// The directory for artifacts, i.e. binaries, cdylibs, staticlibs: `$dest/deps/artifact`
     artifact: PathBuf,
-    /// The directory for incremental files: `$dest/incremental`
+    /// The directory for incremental files: `$dest/incremental`, or
+    /// `$incremental_dir/[$TRIPLE/]$dest_name` with `-Zincremental-dir`.
     incremental: PathBuf,
     /// The directory for fingerprints: `$dest/.fingerprint`
     fingerprint: PathBuf,
@@ -132,6 +157,9 @@ pub struct Layout {
     doc: PathBuf,
     /// The directory for temporary data of integration tests and benches: `$dest/tmp`
     tmp: PathBuf,
+    /// How the filesystem of the target directory compares paths that
+    /// only differ by case.
+    case_sensitivity: CaseSensitivity,
     /// The lockfile for a build (`.cargo-lock`). Will be unlocked when this
     /// struct is `drop`ped.
     _lock: FileLock,
@@ -144,15 +172,25 @@ impl Layout {
     ///
     /// `dest` should be the final artifact directory name. Currently either
     /// "debug" or "release".
+    ///
+    /// `incremental_dir` relocates the incremental files out of the target
+    /// directory, keeping the same structure below it.
     pub fn new(
         ws: &Workspace<'_>,
         target: Option<CompileTarget>,
         dest: &str,
+        incremental_dir: Option<&Path>,
     ) -> CargoResult<Layout> {
-        let mut root = ws.target_dir();
+        let target_dir = ws.target_dir();
+        let mut root = target_dir.clone();
+        let mut incremental_root = incremental_dir.map(Path::to_path_buf);
         if let Some(target) = target {
             root.push(target.short_name());
+            if let Some(incremental_root) = &mut incremental_root {
+                incremental_root.push(target.short_name());
+            }
         }
+        let incremental_dir = incremental_root.map(|root| root.join(dest));
         let dest = root.join(dest);
         // If the root directory doesn't already exist go ahead and create it
         // here. Use this opportunity to exclude it from backups as well if the
@@ -167,20 +205,25 @@ impl Layout {
         // directory, so just lock the entire thing for the duration of this
         // compile.
         let lock = dest.open_rw(".cargo-lock", ws.config(), "build directory")?;
-        let root = root.into_path_unlocked();
-        let dest = dest.into_path_unlocked();
+        // The paths are passed to rustc and recorded in fingerprints, so a
+        // target directory given as an extended-length path on Windows is
+        // used in its usual form, like the paths rustc writes.
+        let root = paths::normalize_verbatim(root.as_path_unlocked());
+        let dest = paths::normalize_verbatim(dest.as_path_unlocked());
         let deps = dest.join("deps");
         let artifact = deps.join("artifact");
+        let case_sensitivity = case_sensitivity(target_dir.as_path_unlocked());
 
         Ok(Layout {
             deps,
             build: dest.join("build"),
             artifact,
-            incremental: dest.join("incremental"),
+            incremental: incremental_dir.unwrap_or_else(|| dest.join("incremental")),
             fingerprint: dest.join(".fingerprint"),
             examples: dest.join("examples"),
             doc: root.join("doc"),
             tmp: root.join("tmp"),
+            case_sensitivity,
             root,
             dest,
             _lock: lock,
@@ -218,6 +261,11 @@ impl Layout {
     pub fn root(&self) -> &Path {
         &self.root
     }
+    /// Whether the filesystem of the build directory tells apart paths that
+    /// only differ by case.
+    pub fn case_sensitivity(&self) -> CaseSensitivity {
+        self.case_sensitivity
+    }
     /// Fetch the incremental path.
     pub fn incremental(&self) -> &Path {
         &self.incremental
@@ -239,4 +287,177 @@ impl Layout {
         paths::create_dir_all(&self.tmp)?;
         Ok(&self.tmp)
     }
+
+    /// Fetch the path of the unit index file (`$dest/.layout-index.json`).
+    pub fn index_file(&self) -> PathBuf {
+        self.dest.join(INDEX_FILE)
+    }
+}
+
+/// The case sensitivity of the filesystem of the target directory, which is
+/// probed the first time, and then read from [`CASE_SENSITIVITY_FILE`] to not
+/// create a probe file every time a layout is opened.
+fn case_sensitivity(target_dir: &Path) -> CaseSensitivity {
+    let path = target_dir.join(CASE_SENSITIVITY_FILE);
+    match paths::read(&path).as_deref().map(str::trim) {
+        Ok("sensitive") => return CaseSensitivity::Sensitive,
+        Ok("insensitive") => return CaseSensitivity::Insensitive,
+        _ => {}
+    }
+    let case_sensitivity = CaseSensitivity::probe(target_dir);
+    let contents = match case_sensitivity {
+        CaseSensitivity::Sensitive => "sensitive",
+        CaseSensitivity::Insensitive => "insensitive",
+    };
+    if let Err(e) = paths::write(&path, contents) {
+        tracing::debug!("failed to cache the case sensitivity of the target directory: {e:?}");
+    }
+    case_sensitivity
+}
+
+/// The on-disk unit index of a [`Layout`], see `-Zlayout-index`.
+///
+/// Every unit is keyed by its [`Metadata`] hash, which already distinguishes
+/// crate versions, features, profiles and compile modes. Entries from
+/// earlier builds are kept, except those whose output files have been
+/// claimed by a unit of the current build.
+///
+/// [`Metadata`]: super::Metadata
+#[derive(Serialize, Deserialize)]
+struct LayoutIndex {
+    version: u32,
+    units: BTreeMap<String, IndexEntry>,
+}
+
+/// A single unit in the [`LayoutIndex`].
+#[derive(Serialize, Deserialize)]
+struct IndexEntry {
+    pkg_id: PackageId,
+    target_name: String,
+    /// A short description of the target kind, such as `lib` or `bin`.
+    target_kind: String,
+    /// The compile mode, serialized the same way as in `--unit-graph`.
+    mode: String,
+    /// The target triple, or `null` for the host.
+    platform: Option<String>,
+    /// Directory the compiler wrote its outputs to.
+    out_dir: PathBuf,
+    outputs: Vec<IndexOutput>,
+}
+
+/// A single file produced by a unit.
+#[derive(Serialize, Deserialize)]
+struct IndexOutput {
+    path: PathBuf,
+    /// Where the file is uplifted to, if anywhere.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    uplifted: Option<PathBuf>,
+}
+
+impl LayoutIndex {
+    /// Loads the index of the given layout.
+    ///
+    /// A missing, unreadable or outdated index is treated as empty, since it
+    /// will be rewritten from scratch anyway.
+    fn load(layout: &Layout) -> LayoutIndex {
+        let empty = LayoutIndex {
+            version: INDEX_VERSION,
+            units: BTreeMap::new(),
+        };
+        let Ok(contents) = paths::read(&layout.index_file()) else {
+            return empty;
+        };
+        match serde_json::from_str::<LayoutIndex>(&contents) {
+            Ok(index) if index.version == INDEX_VERSION => index,
+            Ok(_) => empty,
+            Err(e) => {
+                tracing::debug!("discarding corrupt layout index: {e}");
+                empty
+            }
+        }
+    }
+
+    /// Adds the given entries, replacing any stale entries that previously
+    /// claimed the same output files.
+    fn merge(&mut self, entries: Vec<(String, IndexEntry)>) {
+        let claimed: HashSet<&Path> = entries
+            .iter()
+            .flat_map(|(_, entry)| entry.outputs.iter())
+            .flat_map(|output| std::iter::once(&output.path).chain(output.uplifted.as_ref()))
+            .map(PathBuf::as_path)
+            .collect();
+        self.units.retain(|_, entry| {
+            !entry.outputs.iter().any(|output| {
+                claimed.contains(output.path.as_path())
+                    || output
+                        .uplifted
+                        .as_deref()
+                        .map_or(false, |p| claimed.contains(p))
+            })
+        });
+        self.units.extend(entries);
+    }
+}
+
+/// Writes the unit index for every layout used by this build.
+///
+/// This is called after the build has finished successfully, while the
+/// layouts are still locked, so no other Cargo process can race with it.
+pub fn write_index(cx: &Context<'_, '_>) -> CargoResult<()> {
+    let mut entries: BTreeMap<CompileKind, Vec<(String, IndexEntry)>> = BTreeMap::new();
+    let mut units: Vec<&Unit> = cx.bcx.unit_graph.keys().collect();
+    units.sort();
+    for unit in units {
+        if unit.mode.is_doc_test() {
+            continue;
+        }
+        let files = cx.files();
+        let (out_dir, outputs) = if unit.mode.is_run_custom_build() {
+            (files.build_script_out_dir(unit), Vec::new())
+        } else {
+            let outputs = cx
+                .outputs(unit)?
+                .iter()
+                .map(|output| IndexOutput {
+                    path: output.path.clone(),
+                    uplifted: output.hardlink.clone(),
+                })
+                .collect();
+            (files.out_dir(unit), outputs)
+        };
+        let entry = IndexEntry {
+            pkg_id: unit.pkg.package_id(),
+            target_name: unit.target.name().to_string(),
+            target_kind: unit.target.kind().description().to_string(),
+            mode: mode_name(unit.mode),
+            platform: match unit.kind {
+                CompileKind::Host => None,
+                CompileKind::Target(t) => Some(t.short_name().to_string()),
+            },
+            out_dir,
+            outputs,
+        };
+        entries
+            .entry(unit.kind)
+            .or_default()
+            .push((files.metadata(unit).to_string(), entry));
+    }
+
+    for (kind, entries) in entries {
+        let layout = cx.files().layout(kind);
+        let mut index = LayoutIndex::load(layout);
+        index.merge(entries);
+        let contents = serde_json::to_string_pretty(&index)?;
+        paths::write_if_changed(layout.index_file(), contents)
+            .with_context(|| format!("failed to write {}", layout.index_file().display()))?;
+    }
+    Ok(())
+}
+
+/// Returns the same name for a [`CompileMode`] that `--unit-graph` uses.
+fn mode_name(mode: CompileMode) -> String {
+    match serde_json::to_value(mode) {
+        Ok(serde_json::Value::String(s)) => s,
+        _ => unreachable!("compile modes serialize to strings"),
+    }
 }
@@ -1,5 +1,5 @@
 use super::unit_graph::UnitGraph;
-use crate::core::resolver::errors::describe_path;
+use crate::core::resolver::errors::{describe_path, links_conflict_help};
 use crate::core::{PackageId, Resolve};
 use crate::util::errors::CargoResult;
 use std::collections::{HashMap, HashSet};
@@ -15,6 +15,10 @@ use std::collections::{HashMap, HashSet};
 /// This can probably be removed at some point in the future, though it might
 /// be worth considering fixing the index.
 ///
+/// With `-Zlinks-per-target` the resolver doesn't check `links`, so this is
+/// what reports conflicts, only between the packages of `unit_graph`, which
+/// are the ones built for the selected targets.
+///
 /// [rust-lang/cargo#4978]: https://github.com/rust-lang/cargo/pull/4978
 /// [`package.links`]: https://doc.rust-lang.org/nightly/cargo/reference/build-scripts.html#the-links-manifest-key
 pub fn validate_links(resolve: &Resolve, unit_graph: &UnitGraph) -> CargoResult<()> {
@@ -32,27 +36,36 @@ pub fn validate_links(resolve: &Resolve, unit_graph: &UnitGraph) -> CargoResult<
             None => continue,
         };
         if let Some(&prev) = links.get(lib) {
-            let prev_path = resolve
+            let prev_path: Vec<_> = resolve
                 .path_to_top(&prev)
                 .into_iter()
-                .map(|(p, d)| (p, d.and_then(|d| d.iter().next())));
+                .map(|(p, d)| (p, d.and_then(|d| d.iter().next())))
+                .collect();
             let pkg = unit.pkg.package_id();
-            let path = resolve
+            let path: Vec<_> = resolve
                 .path_to_top(&pkg)
                 .into_iter()
-                .map(|(p, d)| (p, d.and_then(|d| d.iter().next())));
+                .map(|(p, d)| (p, d.and_then(|d| d.iter().next())))
+                .collect();
+            // The dependencies pulling in each of the packages.
+            let edges = [&prev_path, &path].into_iter().filter_map(|path| {
+                let (parent, dep) = path.get(1)?;
+                Some((path[0].0.name(), **parent, (*dep)?))
+            });
+            let help = links_conflict_help(lib, edges);
             anyhow::bail!(
                 "multiple packages link to native library `{}`, \
                  but a native library can be linked only once\n\
                  \n\
                  {}\nlinks to native library `{}`\n\
                  \n\
-                 {}\nalso links to native library `{}`",
+                 {}\nalso links to native library `{}`{}",
+                lib,
+                describe_path(prev_path.into_iter()),
                 lib,
-                describe_path(prev_path),
+                describe_path(path.into_iter()),
                 lib,
-                describe_path(path),
-                lib
+                help
             )
         }
         links.insert(lib.to_string(), unit.pkg.package_id());
@@ -32,6 +32,7 @@
 //! [`ops::cargo_compile::compile`]: crate::ops::compile
 
 pub mod artifact;
+mod artifact_cache;
 mod build_config;
 pub(crate) mod build_context;
 mod build_plan;
@@ -65,19 +66,22 @@ use std::sync::Arc;
 
 use anyhow::{Context as _, Error};
 use lazycell::LazyCell;
-use log::{debug, trace};
+use tracing::{debug, trace};
 
-pub use self::build_config::{BuildConfig, CompileMode, MessageFormat, TimingOutput};
+pub use self::build_config::{
+    AdaptiveJobs, BuildConfig, CompileMode, ExportDirs, MessageFormat, TimingOutput,
+};
 pub use self::build_context::{
-    BuildContext, FileFlavor, FileType, RustDocFingerprint, RustcTargetData, TargetInfo,
+    BuildContext, FastLinker, FileFlavor, FileType, RustDocFingerprint, RustcTargetData,
+    RustdocRuntool, TargetInfo,
 };
 use self::build_plan::BuildPlan;
-pub use self::compilation::{Compilation, Doctest, UnitOutput};
+pub use self::compilation::{Compilation, Doctest, UnitOutput, Workdir};
 pub use self::compile_kind::{CompileKind, CompileTarget};
 pub use self::context::{Context, Metadata};
 pub use self::crate_type::CrateType;
 pub use self::custom_build::LinkArgTarget;
-pub use self::custom_build::{BuildOutput, BuildScriptOutputs, BuildScripts};
+pub use self::custom_build::{previous_output, BuildOutput, BuildScriptOutputs, BuildScripts};
 pub(crate) use self::fingerprint::DirtyReason;
 pub use self::job_queue::Freshness;
 use self::job_queue::{Job, JobQueue, JobState, Work};
@@ -90,7 +94,7 @@ pub use crate::core::compiler::unit::{Unit, UnitInterner};
 use crate::core::manifest::TargetSourcePath;
 use crate::core::profiles::{PanicStrategy, Profile, Strip};
 use crate::core::{Feature, PackageId, Target, Verbosity};
-use crate::util::errors::{CargoResult, VerboseError};
+use crate::util::errors::{CargoErrorKind, CargoResult, KindError, VerboseError};
 use crate::util::interning::InternedString;
 use crate::util::machine_message::{self, Message};
 use crate::util::toml::TomlDebugInfo;
@@ -190,7 +194,8 @@ fn compile<'cfg>(
             let work = if unit.mode.is_doc() || unit.mode.is_doc_scrape() {
                 rustdoc(cx, unit)?
             } else {
-                rustc(cx, unit, exec)?
+                let work = rustc(cx, unit, exec)?;
+                artifact_cache::wrap(cx, unit, work)?
             };
             work.then(link_targets(cx, unit, false)?)
         } else {
@@ -291,6 +296,7 @@ fn rustc(cx: &mut Context<'_, '_>, unit: &Unit, exec: &Arc<dyn Executor>) -> Car
     let root_output = cx.files().host_dest().to_path_buf();
     let target_dir = cx.bcx.ws.target_dir().into_path_unlocked();
     let pkg_root = unit.pkg.root().to_path_buf();
+    let case_sensitivity = cx.files().layout(unit.kind).case_sensitivity();
     let cwd = rustc
         .get_cwd()
         .unwrap_or_else(|| cx.bcx.config.cwd())
@@ -319,6 +325,7 @@ fn rustc(cx: &mut Context<'_, '_>, unit: &Unit, exec: &Arc<dyn Executor>) -> Car
     if hide_diagnostics_for_scrape_unit {
         output_options.show_diagnostics = false;
     }
+    let fast_linker = cx.bcx.fast_linker(unit).map(|linker| linker.name);
 
     return Ok(Work::new(move |state| {
         // Artifacts are in a different location than typical units,
@@ -368,7 +375,7 @@ fn rustc(cx: &mut Context<'_, '_>, unit: &Unit, exec: &Arc<dyn Executor>) -> Car
             // See rust-lang/cargo#8348.
             if output.hardlink.is_some() && output.path.exists() {
                 _ = paths::remove_file(&output.path).map_err(|e| {
-                    log::debug!(
+                    tracing::debug!(
                         "failed to delete previous output file `{:?}`: {e:?}",
                         output.path
                     );
@@ -427,12 +434,19 @@ fn rustc(cx: &mut Context<'_, '_>, unit: &Unit, exec: &Arc<dyn Executor>) -> Car
                     };
                     let name = descriptive_pkg_name(&name, &target, &mode);
                     format!("could not compile {name}{errors}{warnings}")
-                });
+                })
+                .map_err(|e| KindError::new(CargoErrorKind::Compile, e).into());
 
             if let Err(e) = result {
                 if let Some(diagnostic) = failed_scrape_diagnostic {
                     state.warning(diagnostic)?;
                 }
+                if let Some(linker) = fast_linker.filter(|_| output_options.linker_failed) {
+                    state.warning(format!(
+                        "`{name}` was linked with `{linker}` because of `build.prefer-fast-linker`, \
+                         set it to `false` to link with the default linker"
+                    ))?;
+                }
 
                 return Err(e);
             }
@@ -451,6 +465,7 @@ fn rustc(cx: &mut Context<'_, '_>, unit: &Unit, exec: &Arc<dyn Executor>) -> Car
                 &rustc,
                 // Do not track source files in the fingerprint for registry dependencies.
                 is_local,
+                case_sensitivity,
             )
             .with_context(|| {
                 internal(format!(
@@ -515,13 +530,13 @@ fn rustc(cx: &mut Context<'_, '_>, unit: &Unit, exec: &Arc<dyn Executor>) -> Car
 fn link_targets(cx: &mut Context<'_, '_>, unit: &Unit, fresh: bool) -> CargoResult<Work> {
     let bcx = cx.bcx;
     let outputs = cx.outputs(unit)?;
-    let export_dir = cx.files().export_dir();
     let package_id = unit.pkg.package_id();
     let manifest_path = PathBuf::from(unit.pkg.manifest_path());
     let profile = unit.profile.clone();
     let unit_mode = unit.mode;
     let features = unit.features.iter().map(|s| s.to_string()).collect();
     let json_messages = bcx.build_config.emit_json();
+    let artifact_link = bcx.build_config.artifact_link;
     let executable = cx.get_executable(unit)?;
     let mut target = Target::clone(&unit.target);
     if let TargetSourcePath::Metabuild = target.src_path() {
@@ -533,9 +548,11 @@ fn link_targets(cx: &mut Context<'_, '_>, unit: &Unit, fresh: bool) -> CargoResu
     Ok(Work::new(move |state| {
         // If we're a "root crate", e.g., the target of this compilation, then we
         // hard link our outputs out of the `deps` directory into the directory
-        // above. This means that `cargo build` will produce binaries in
-        // `target/debug` which one probably expects.
+        // above, or symlink or copy them with `build.artifact-link`. This means
+        // that `cargo build` will produce binaries in `target/debug` which one
+        // probably expects.
         let mut destinations = vec![];
+        let mut exported = vec![];
         for output in outputs.iter() {
             let src = &output.path;
             // This may have been a `cargo rustc` command which changes the
@@ -551,12 +568,12 @@ fn link_targets(cx: &mut Context<'_, '_>, unit: &Unit, fresh: bool) -> CargoResu
                 }
             };
             destinations.push(dst.clone());
-            paths::link_or_copy(src, dst)?;
+            paths::link_with(src, dst, artifact_link)?;
             if let Some(ref path) = output.export_path {
-                let export_dir = export_dir.as_ref().unwrap();
-                paths::create_dir_all(export_dir)?;
+                paths::create_dir_all(path.parent().unwrap())?;
 
                 paths::link_or_copy(src, path)?;
+                exported.push(path.clone());
             }
         }
 
@@ -587,6 +604,7 @@ fn link_targets(cx: &mut Context<'_, '_>, unit: &Unit, fresh: bool) -> CargoResu
                 profile: art_profile,
                 features,
                 filenames: destinations,
+                out_dir_filenames: exported,
                 executable,
                 fresh,
             }
@@ -715,7 +733,10 @@ fn prepare_rustdoc(cx: &Context<'_, '_>, unit: &Unit) -> CargoResult<ProcessBuil
     if let CompileKind::Target(target) = unit.kind {
         rustdoc.arg("--target").arg(target.rustc_target());
     }
-    let doc_dir = cx.files().out_dir(unit);
+    let doc_dir = match rustdoc_json_dir(cx, unit)? {
+        Some(json_dir) => json_dir,
+        None => cx.files().out_dir(unit),
+    };
     rustdoc.arg("-o").arg(&doc_dir);
     rustdoc.args(&features_args(unit));
     rustdoc.args(&check_cfg_args(cx, unit));
@@ -724,6 +745,7 @@ fn prepare_rustdoc(cx: &Context<'_, '_>, unit: &Unit) -> CargoResult<ProcessBuil
     add_allow_features(cx, &mut rustdoc);
 
     rustdoc.args(unit.pkg.manifest().lint_rustflags());
+    rustdoc.args(&unit.profile.rustdocflags);
     if let Some(args) = cx.bcx.extra_args_for(unit) {
         rustdoc.args(args);
     }
@@ -804,6 +826,22 @@ fn rustdoc(cx: &mut Context<'_, '_>, unit: &Unit) -> CargoResult<Work> {
         None
     };
 
+    let json_output = match rustdoc_json_dir(cx, unit)? {
+        Some(json_dir) => {
+            paths::create_dir_all(&json_dir)?;
+            Some((json_dir, cx.outputs(unit)?[0].path.clone()))
+        }
+        None => None,
+    };
+
+    // With `--show-coverage`, rustdoc prints the report instead of writing
+    // documentation.
+    let coverage_path = if cx.bcx.is_rustdoc_coverage(unit) {
+        Some(cx.outputs(unit)?[0].path.clone())
+    } else {
+        None
+    };
+
     let failed_scrape_units = Arc::clone(&cx.failed_scrape_units);
     let hide_diagnostics_for_scrape_unit = cx.bcx.unit_can_fail_for_docscraping(unit)
         && !matches!(cx.bcx.config.shell().verbosity(), Verbosity::Verbose);
@@ -839,7 +877,7 @@ fn rustdoc(cx: &mut Context<'_, '_>, unit: &Unit) -> CargoResult<Work> {
         }
 
         let crate_dir = doc_dir.join(&crate_name);
-        if crate_dir.exists() {
+        if crate_dir.exists() && coverage_path.is_none() {
             // Remove output from a previous build. This ensures that stale
             // files for removed items are removed.
             debug!("removing pre-existing doc directory {:?}", crate_dir);
@@ -847,9 +885,18 @@ fn rustdoc(cx: &mut Context<'_, '_>, unit: &Unit) -> CargoResult<Work> {
         }
         state.running(&rustdoc);
 
+        let mut coverage = String::new();
         let result = rustdoc
             .exec_with_streaming(
-                &mut |line| on_stdout_line(state, line, package_id, &target),
+                &mut |line| {
+                    if coverage_path.is_some() {
+                        coverage.push_str(line);
+                        coverage.push('\n');
+                        Ok(())
+                    } else {
+                        on_stdout_line(state, line, package_id, &target)
+                    }
+                },
                 &mut |line| {
                     on_stderr_line(
                         state,
@@ -862,7 +909,8 @@ fn rustdoc(cx: &mut Context<'_, '_>, unit: &Unit) -> CargoResult<Work> {
                 },
                 false,
             )
-            .with_context(|| format!("could not document `{}`", name));
+            .with_context(|| format!("could not document `{}`", name))
+            .map_err(|e| KindError::new(CargoErrorKind::Compile, e).into());
 
         if let Err(e) = result {
             if let Some(diagnostic) = failed_scrape_diagnostic {
@@ -872,6 +920,21 @@ fn rustdoc(cx: &mut Context<'_, '_>, unit: &Unit) -> CargoResult<Work> {
             return Err(e);
         }
 
+        if let Some(coverage_path) = coverage_path {
+            paths::write(&coverage_path, coverage)?;
+        }
+        if let Some((json_dir, output)) = json_output {
+            let json = json_dir.join(format!("{crate_name}.json"));
+            fs::rename(&json, &output).with_context(|| {
+                format!(
+                    "failed to move `{}` to `{}`",
+                    json.display(),
+                    output.display()
+                )
+            })?;
+            paths::remove_dir_all(json_dir)?;
+        }
+
         Ok(())
     }))
 }
@@ -941,7 +1004,7 @@ fn add_error_format_and_color(cx: &Context<'_, '_>, cmd: &mut ProcessBuilder) {
     cmd.arg(json);
 
     let config = cx.bcx.config;
-    if let Some(width) = config.shell().err_width().diagnostic_terminal_width() {
+    if let Some(width) = config.shell().diagnostic_width() {
         cmd.arg(format!("--diagnostic-width={width}"));
     }
 }
@@ -1119,6 +1182,9 @@ fn build_base_args(cx: &Context<'_, '_>, cmd: &mut ProcessBuilder, unit: &Unit)
         "linker=",
         bcx.linker(unit.kind).as_ref().map(|s| s.as_ref()),
     );
+    if let Some(linker) = bcx.fast_linker(unit) {
+        cmd.args(&linker.args);
+    }
     if incremental {
         let dir = cx.files().layout(unit.kind).incremental().as_os_str();
         opt(cmd, "-C", "incremental=", Some(dir));
@@ -1445,6 +1511,8 @@ struct OutputOptions {
     warnings_seen: usize,
     /// Tracks the number of errors we've seen so far.
     errors_seen: usize,
+    /// Whether rustc reported that linking failed.
+    linker_failed: bool,
 }
 
 impl OutputOptions {
@@ -1461,6 +1529,7 @@ impl OutputOptions {
             show_diagnostics: true,
             warnings_seen: 0,
             errors_seen: 0,
+            linker_failed: false,
         }
     }
 }
@@ -1592,6 +1661,9 @@ fn on_stderr_line_inner(
                     // Skip this line; we'll print our own summary at the end.
                     return Ok(true);
                 }
+                if msg.level == "error" && msg.message.starts_with("linking with `") {
+                    options.linker_failed = true;
+                }
                 // state.stderr will add a newline
                 if msg.rendered.ends_with('\n') {
                     msg.rendered.pop();
@@ -1720,6 +1792,7 @@ fn replay_output_cache(
         show_diagnostics,
         warnings_seen: 0,
         errors_seen: 0,
+        linker_failed: false,
     };
     Work::new(move |state| {
         if !path.exists() {
@@ -1788,6 +1861,18 @@ fn should_include_scrape_units(bcx: &BuildContext<'_, '_>, unit: &Unit) -> bool
     unit.mode.is_doc() && bcx.scrape_units.len() > 0 && bcx.ws.unit_needs_doc_scrape(unit)
 }
 
+/// Gets the directory `rustdoc` writes `<crate_name>.json` to with
+/// `--output-format json`, from where it is moved to the output of the unit,
+/// which also has the metadata hash in its name since crates of different
+/// packages may have the same name.
+fn rustdoc_json_dir(cx: &Context<'_, '_>, unit: &Unit) -> CargoResult<Option<PathBuf>> {
+    if !cx.bcx.is_rustdoc_json(unit) {
+        return Ok(None);
+    }
+    let output = &cx.outputs(unit)?[0].path;
+    Ok(Some(output.with_extension("")))
+}
+
 /// Gets the file path of function call information output from `rustdoc`.
 fn scrape_output_path(cx: &Context<'_, '_>, unit: &Unit) -> CargoResult<PathBuf> {
     assert!(unit.mode.is_doc() || unit.mode.is_doc_scrape());
@@ -1,36 +1,15 @@
 //! dep-info files for external build system integration.
 //! See [`output_depinfo`] for more.
 
-use cargo_util::paths::normalize_path;
 use std::collections::{BTreeSet, HashSet};
 use std::io::{BufWriter, Write};
 use std::path::{Path, PathBuf};
 
 use super::{fingerprint, Context, FileFlavor, Unit};
+use crate::util::dep_info::{self, render_filename};
 use crate::util::{internal, CargoResult};
 use cargo_util::paths;
-use log::debug;
-
-/// Bacially just normalizes a given path and converts it to a string.
-fn render_filename<P: AsRef<Path>>(path: P, basedir: Option<&str>) -> CargoResult<String> {
-    fn wrap_path(path: &Path) -> CargoResult<String> {
-        path.to_str()
-            .ok_or_else(|| internal(format!("path `{:?}` not utf-8", path)))
-            .map(|f| f.replace(" ", "\\ "))
-    }
-
-    let path = path.as_ref();
-    if let Some(basedir) = basedir {
-        let norm_path = normalize_path(path);
-        let norm_basedir = normalize_path(basedir.as_ref());
-        match norm_path.strip_prefix(norm_basedir) {
-            Ok(relpath) => wrap_path(relpath),
-            _ => wrap_path(path),
-        }
-    } else {
-        wrap_path(path)
-    }
-}
+use tracing::debug;
 
 /// Collects all dependencies of the `unit` for the output dep info file.
 ///
@@ -116,19 +95,11 @@ pub fn output_depinfo(cx: &mut Context<'_, '_>, unit: &Unit) -> CargoResult<()>
     let mut deps = BTreeSet::new();
     let mut visited = HashSet::new();
     let success = add_deps_for_unit(&mut deps, cx, unit, &mut visited).is_ok();
-    let basedir_string;
-    let basedir = match bcx.config.build_config()?.dep_info_basedir.clone() {
-        Some(value) => {
-            basedir_string = value
-                .resolve_path(bcx.config)
-                .as_os_str()
-                .to_str()
-                .ok_or_else(|| anyhow::format_err!("build.dep-info-basedir path not utf-8"))?
-                .to_string();
-            Some(basedir_string.as_str())
-        }
-        None => None,
+    let basedir = match &bcx.build_config.dep_info_basedir {
+        Some(value) => Some(dep_info::expand_basedir(value, bcx.config.cwd(), bcx.ws)),
+        None => dep_info::dep_info_basedir(bcx.ws)?,
     };
+    let basedir = basedir.as_deref();
     let deps = deps
         .iter()
         .map(|f| render_filename(f, basedir))
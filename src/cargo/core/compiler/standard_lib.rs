@@ -99,6 +99,9 @@ pub fn resolve_std<'cfg>(
         &Some(members),
         /*default_members*/ &None,
         /*exclude*/ &None,
+        /*members_from*/ &None,
+        /*default_run*/ &None,
+        /*default_test*/ &None,
         /*inheritable*/ &None,
         /*custom_metadata*/ &None,
     ));
@@ -5,6 +5,7 @@
 use super::{CompileMode, Unit};
 use crate::core::compiler::job_queue::JobId;
 use crate::core::compiler::{BuildContext, Context, TimingOutput};
+use crate::core::profiles::CompileTimes;
 use crate::core::PackageId;
 use crate::util::cpu::State;
 use crate::util::machine_message::{self, Message};
@@ -13,6 +14,7 @@ use anyhow::Context as _;
 use cargo_util::paths;
 use std::collections::HashMap;
 use std::io::{BufWriter, Write};
+use std::path::PathBuf;
 use std::thread::available_parallelism;
 use std::time::{Duration, Instant, SystemTime};
 
@@ -31,6 +33,9 @@ pub struct Timings<'cfg> {
     report_html: bool,
     /// If true, emits JSON information with timing information.
     report_json: bool,
+    /// Where to record the compile times of the libraries, for the
+    /// `auto-tune` of the profile.
+    compile_times: Option<PathBuf>,
     /// When Cargo started.
     start: Instant,
     /// A rendered string of when compilation started.
@@ -99,7 +104,11 @@ impl<'cfg> Timings<'cfg> {
         let has_report = |what| bcx.build_config.timing_outputs.contains(&what);
         let report_html = has_report(TimingOutput::Html);
         let report_json = has_report(TimingOutput::Json);
-        let enabled = report_html | report_json;
+        let compile_times = bcx
+            .profiles
+            .auto_tune_decisions()
+            .map(|_| bcx.profiles.compile_times_path(bcx.ws));
+        let enabled = report_html | report_json | compile_times.is_some();
 
         let mut root_map: HashMap<PackageId, Vec<String>> = HashMap::new();
         for unit in root_units {
@@ -122,7 +131,7 @@ impl<'cfg> Timings<'cfg> {
             match State::current() {
                 Ok(state) => Some(state),
                 Err(e) => {
-                    log::info!("failed to get CPU state, CPU tracking disabled: {:?}", e);
+                    tracing::info!("failed to get CPU state, CPU tracking disabled: {:?}", e);
                     None
                 }
             }
@@ -135,6 +144,7 @@ impl<'cfg> Timings<'cfg> {
             enabled,
             report_html,
             report_json,
+            compile_times,
             start: bcx.config.creation_time(),
             start_str,
             root_targets,
@@ -276,7 +286,7 @@ impl<'cfg> Timings<'cfg> {
         let current = match State::current() {
             Ok(s) => s,
             Err(e) => {
-                log::info!("failed to get CPU state: {:?}", e);
+                tracing::info!("failed to get CPU state: {:?}", e);
                 return;
             }
         };
@@ -303,6 +313,17 @@ impl<'cfg> Timings<'cfg> {
             self.report_html(cx, error)
                 .with_context(|| "failed to save timing report")?;
         }
+        if let Some(path) = &self.compile_times {
+            let mut times = HashMap::new();
+            for ut in &self.unit_times {
+                if ut.unit.mode == CompileMode::Build && ut.unit.target.is_lib() {
+                    let time = times.entry(ut.unit.pkg.package_id()).or_insert(0.0);
+                    *time = ut.duration.max(*time);
+                }
+            }
+            CompileTimes::record(path, times)
+                .with_context(|| format!("failed to record compile times in {}", path.display()))?;
+        }
         Ok(())
     }
 
@@ -17,7 +17,7 @@
 
 use std::collections::{HashMap, HashSet};
 
-use log::trace;
+use tracing::trace;
 
 use crate::core::compiler::artifact::match_artifacts_kind_with_targets;
 use crate::core::compiler::unit_graph::{UnitDep, UnitGraph};
@@ -31,7 +31,7 @@ use crate::core::resolver::Resolve;
 use crate::core::{Dependency, Package, PackageId, PackageSet, Target, TargetKind, Workspace};
 use crate::ops::resolve_all_features;
 use crate::util::interning::InternedString;
-use crate::util::Config;
+use crate::util::{profile, Config};
 use crate::CargoResult;
 
 const IS_NO_ARTIFACT_DEP: Option<&'static Artifact> = None;
@@ -101,6 +101,7 @@ pub fn build_unit_dependencies<'a, 'cfg>(
         // in the dep graph without a root.
         return Ok(HashMap::new());
     }
+    let _p = profile::start("building unit graph");
     let (std_resolve, std_features) = match std_resolve {
         Some((r, f)) => (Some(r), Some(f)),
         None => (None, None),
@@ -279,7 +280,18 @@ fn compute_deps(
             None => continue,
         };
         let dep_pkg = state.get(dep_pkg_id);
-        let mode = check_or_build_mode(unit.mode, dep_lib);
+        let mode = if unit.mode.is_check()
+            && state.config.cli_unstable().check_build_deps
+            && !state.is_std
+            && !state.ws.is_member(dep_pkg)
+        {
+            // With `-Zcheck-build-deps`, dependencies outside of the
+            // workspace are built like `cargo build` and `cargo test` do,
+            // so they can reuse them instead of building them again.
+            CompileMode::Build
+        } else {
+            check_or_build_mode(unit.mode, dep_lib)
+        };
         let dep_unit_for = unit_for.with_dependency(unit, dep_lib, unit_for.root_compile_kind());
 
         let start = ret.len();
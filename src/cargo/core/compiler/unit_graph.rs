@@ -88,7 +88,9 @@ pub fn emit_serialized_unit_graph(
         .enumerate()
         .map(|(i, val)| (val.0, i))
         .collect();
-    let roots = root_units.iter().map(|root| indices[root]).collect();
+    let mut roots: Vec<_> = root_units.iter().map(|root| indices[root]).collect();
+    roots.sort_unstable();
+    roots.dedup();
     let ser_units = units
         .iter()
         .map(|(unit, unit_deps)| {
@@ -483,6 +483,15 @@ features! {
 
     // Allow specifying rustflags directly in a profile
     (stable, workspace_inheritance, "1.64", "reference/unstable.html#workspace-inheritance"),
+
+    // Allow negative patterns like `!crates/experimental-*` in `workspace.members`.
+    (unstable, negative_member_globs, "", "reference/unstable.html#negative-member-globs"),
+
+    // Allow including the members of other workspaces with `workspace.members-from`.
+    (unstable, nested_workspaces, "", "reference/unstable.html#nested-workspaces"),
+
+    // Allow choosing what `cargo run` and `cargo test` run in the root of a workspace.
+    (unstable, workspace_default_targets, "", "reference/unstable.html#workspace-default-targets"),
 }
 
 pub struct Feature {
@@ -717,41 +726,84 @@ unstable_cli_options!(
 
     // All other unstable features.
     // Please keep this list lexicographically ordered.
+    adaptive_jobs: bool = ("Enable `build.jobs = \"auto\"` to run fewer jobs while the system load or memory pressure is high"),
     advanced_env: bool = (HIDDEN),
+    artifact_cache: bool = ("Reuse compiled registry dependencies across workspaces through a cache in $CARGO_HOME"),
+    artifact_link: bool = ("Choose how final artifacts are placed in the target directory with `build.artifact-link`"),
     avoid_dev_deps: bool = ("Avoid installing dev-dependencies if possible"),
     binary_dep_depinfo: bool = ("Track changes to dependency artifacts"),
     bindeps: bool = ("Allow Cargo packages to depend on bin, cdylib, and staticlib crates, and use the artifacts built by those crates"),
     #[serde(deserialize_with = "deserialize_build_std")]
     build_std: Option<Vec<String>>  = ("Enable Cargo to compile the standard library itself as part of a crate graph compilation"),
     build_std_features: Option<Vec<String>>  = ("Configure features enabled for the standard library itself when building the standard library"),
+    cache_overlay: bool = ("Enable the `cache.overlay-dir` config to keep the package cache in a writable directory when `$CARGO_HOME` is read-only"),
+    cancellation: bool = ("Cancel operations cleanly on Ctrl-C, and abort builds running longer than `build.timeout`"),
+    check_build_deps: bool = ("Build the dependencies of checked units like `cargo build` and `cargo test` do, so they are shared with them"),
     #[serde(deserialize_with = "deserialize_check_cfg")]
     check_cfg: Option<(/*features:*/ bool, /*well_known_names:*/ bool, /*well_known_values:*/ bool, /*output:*/ bool)> = ("Specify scope of compile-time checking of `cfg` names/values"),
+    client_certs: bool = ("Enable client certificates for mutual TLS with `http.ssl-client-cert` and `registries.<name>.client-cert`"),
     codegen_backend: bool = ("Enable the `codegen-backend` option in profiles in .cargo/config.toml file"),
+    config_append: bool = ("Allow `--config KEY+=VALUE` to append to arrays"),
     config_include: bool = ("Enable the `include` key in config files"),
+    crate_compression: bool = ("Enable the `package.compression-level` config key"),
+    crate_signatures: bool = ("Verify the signatures of `.crate` files from registries with a signature key"),
     credential_process: bool = ("Add a config setting to fetch registry authentication tokens by calling an external process"),
+    dep_info_basedir: bool = ("Expand `{workspace-root}` and `{target-dir}` in `build.dep-info-basedir`, and override it with `--dep-info-basedir`"),
+    dependency_policy: bool = ("Enable the `[dependency-policy]` config table to forbid git and path dependencies"),
+    device_login: bool = ("Log in to registries that advertise it in the browser with `cargo login`"),
     direct_minimal_versions: bool = ("Resolve minimal dependency versions instead of maximum (direct dependencies only)"),
+    doc_browser_templates: bool = ("Expand `{path}` and `{url}` in the arguments of `doc.browser`"),
     doctest_xcompile: bool = ("Compile and run doctests for non-host target using runner config"),
     dual_proc_macros: bool = ("Build proc-macros for both the host and the target"),
     features: Option<Vec<String>>  = (HIDDEN),
     gitoxide: Option<GitoxideFeatures> = ("Use gitoxide for the given git interactions, or all of them if no argument is given"),
     host_config: bool = ("Enable the [host] section in the .cargo/config.toml file"),
+    hyperlinks: bool = ("Enable the `term.hyperlinks` config to display file paths and packages as hyperlinks"),
+    incremental_dir: bool = ("Enable the `build.incremental-dir` and `profile.<name>.incremental-dir` config keys to relocate incremental compilation data"),
+    index_snapshots: bool = ("Pin the index of git registries to a revision or date with `registries.<name>.snapshot`"),
+    install_settings: bool = ("List the settings packages were installed with in `cargo install --list --verbose`"),
+    jobserver_accounting: bool = ("Set `NUM_JOBS` of build scripts to the jobserver tokens available, and warn when they use more CPUs than `-j` allows"),
+    layout_index: bool = ("Write an index of each unit's output files into the target directory"),
+    links_per_target: bool = ("Only report `links` conflicts between packages built for the selected targets"),
     lints: bool = ("Pass `[lints]` to the linting tools"),
+    lock_wait_timeout: bool = ("Enable the `package-cache.lock-wait-timeout` config to fail instead of waiting forever for the package cache lock"),
+    lockfile_overlay: bool = ("Apply a local `Cargo.lock.local` overlay on top of `Cargo.lock`"),
+    message_catalogs: bool = ("Display translated messages for the `term.language` config"),
     minimal_versions: bool = ("Resolve minimal dependency versions instead of maximum"),
     msrv_policy: bool = ("Enable rust-version aware policy within cargo"),
     mtime_on_use: bool = ("Configure Cargo to update the mtime of used files"),
     next_lockfile_bump: bool = (HIDDEN),
     no_index_update: bool = ("Do not update the registry index even if the cache is outdated"),
+    output_width: bool = ("Enable the `term.width` config and the `--output-width` flag to set the width of diagnostics"),
+    package_workspace: bool = ("Verify workspace members against the packaged form of the members they depend on"),
     panic_abort_tests: bool = ("Enable support to run tests with -Cpanic=abort"),
+    parallel_doctests: bool = ("Run the doctests of several packages in parallel, and note the doctests found or skipped"),
+    plain_progress: bool = ("Enable `term.progress.style = \"plain\"`, to display progress as lines of text for screen readers"),
+    plugin_manifest: bool = ("Read descriptions, completions and minimum Cargo versions of external subcommands from `$CARGO_HOME/plugins.toml`"),
+    prefer_fast_linker: bool = ("Enable `build.prefer-fast-linker` to link with mold or lld when they are installed"),
+    profile_auto_tune: bool = ("Enable `profile.<name>.auto-tune` to optimize heavy dependencies and not workspace members"),
     profile_rustflags: bool = ("Enable the `rustflags` option in profiles in .cargo/config.toml file"),
-    publish_timeout: bool = ("Enable the `publish.timeout` key in .cargo/config.toml file"),
+    proxy_config: bool = ("Enable `http.no-proxy`, per-registry proxies, and the detection of system proxies"),
+    publish_timeout: bool = ("Enable the `publish.wait-timeout` key in .cargo/config.toml file"),
     registry_auth: bool = ("Authentication for alternative registries, and generate registry authentication tokens using asymmetric cryptography"),
+    resolver_explain: bool = ("Explain version conflicts in dependency resolution errors"),
+    run_workdir: bool = ("Choose the working directory of `cargo run` and `cargo test` with `--workdir` and `run.workdir`"),
     rustdoc_map: bool = ("Allow passing external documentation mappings to rustdoc"),
     rustdoc_scrape_examples: bool = ("Allows Rustdoc to scrape code examples from reverse-dependencies"),
+    rustflags_append: bool = ("Enable `build.rustflags-append` and `build.rustflags-prepend`, and deduplicate rustflags"),
     script: bool = ("Enable support for single-file, `.rs` packages"),
+    self_profile: bool = ("Write a profile of the time Cargo spends in each of its phases to the target directory"),
     separate_nightlies: bool = (HIDDEN),
     skip_rustdoc_fingerprint: bool = (HIDDEN),
+    stream_color: bool = ("Enable the `term.color.stdout` and `term.color.stderr` config to color stdout and stderr separately"),
     target_applies_to_host: bool = ("Enable the `target-applies-to-host` key in the .cargo/config.toml file"),
+    target_cfg_rustdocflags: bool = ("Enable the `rustdocflags` key in `[target.<cfg>]` tables of the .cargo/config.toml file"),
+    target_overrides: bool = ("Enable the `[target-overrides]` table of the .cargo/config.toml file to pass flags to rustc for some packages"),
+    trusted_publishing: bool = ("Publish to registries with `trusted-publishing` by exchanging the OIDC token of the CI job"),
     unstable_options: bool = ("Allow the usage of unstable options"),
+    use_cached: bool = ("Only use registry packages that are already downloaded"),
+    vcs_detection: bool = ("Check for uncommitted changes in Mercurial and Jujutsu repositories when packaging, and detect Jujutsu repositories in `cargo new`"),
+    verbose_config_resolution: bool = ("Enable `term.verbose-config-resolution` to warn when config file values are overridden by environment variables or `--config`"),
 );
 
 const STABILIZED_COMPILE_PROGRESS: &str = "The progress bar is now always \
@@ -1086,7 +1138,10 @@ impl CliUnstable {
 
             // Unstable features
             // Sorted alphabetically:
+            "adaptive-jobs" => self.adaptive_jobs = parse_empty(k, v)?,
             "advanced-env" => self.advanced_env = parse_empty(k, v)?,
+            "artifact-cache" => self.artifact_cache = parse_empty(k, v)?,
+            "artifact-link" => self.artifact_link = parse_empty(k, v)?,
             "avoid-dev-deps" => self.avoid_dev_deps = parse_empty(k, v)?,
             "binary-dep-depinfo" => self.binary_dep_depinfo = parse_empty(k, v)?,
             "bindeps" => self.bindeps = parse_empty(k, v)?,
@@ -1094,13 +1149,24 @@ impl CliUnstable {
                 self.build_std = Some(crate::core::compiler::standard_lib::parse_unstable_flag(v))
             }
             "build-std-features" => self.build_std_features = Some(parse_features(v)),
+            "cache-overlay" => self.cache_overlay = parse_empty(k, v)?,
+            "cancellation" => self.cancellation = parse_empty(k, v)?,
+            "check-build-deps" => self.check_build_deps = parse_empty(k, v)?,
             "check-cfg" => {
                 self.check_cfg = v.map_or(Ok(None), |v| parse_check_cfg(v.split(',')))?
             }
+            "client-certs" => self.client_certs = parse_empty(k, v)?,
             "codegen-backend" => self.codegen_backend = parse_empty(k, v)?,
+            "config-append" => self.config_append = parse_empty(k, v)?,
             "config-include" => self.config_include = parse_empty(k, v)?,
+            "crate-compression" => self.crate_compression = parse_empty(k, v)?,
+            "crate-signatures" => self.crate_signatures = parse_empty(k, v)?,
             "credential-process" => self.credential_process = parse_empty(k, v)?,
+            "dep-info-basedir" => self.dep_info_basedir = parse_empty(k, v)?,
+            "dependency-policy" => self.dependency_policy = parse_empty(k, v)?,
+            "device-login" => self.device_login = parse_empty(k, v)?,
             "direct-minimal-versions" => self.direct_minimal_versions = parse_empty(k, v)?,
+            "doc-browser-templates" => self.doc_browser_templates = parse_empty(k, v)?,
             "doctest-xcompile" => self.doctest_xcompile = parse_empty(k, v)?,
             "dual-proc-macros" => self.dual_proc_macros = parse_empty(k, v)?,
             "gitoxide" => {
@@ -1110,24 +1176,53 @@ impl CliUnstable {
                 )?
             }
             "host-config" => self.host_config = parse_empty(k, v)?,
+            "hyperlinks" => self.hyperlinks = parse_empty(k, v)?,
+            "incremental-dir" => self.incremental_dir = parse_empty(k, v)?,
+            "index-snapshots" => self.index_snapshots = parse_empty(k, v)?,
+            "install-settings" => self.install_settings = parse_empty(k, v)?,
+            "jobserver-accounting" => self.jobserver_accounting = parse_empty(k, v)?,
+            "layout-index" => self.layout_index = parse_empty(k, v)?,
+            "links-per-target" => self.links_per_target = parse_empty(k, v)?,
             "lints" => self.lints = parse_empty(k, v)?,
+            "lock-wait-timeout" => self.lock_wait_timeout = parse_empty(k, v)?,
+            "lockfile-overlay" => self.lockfile_overlay = parse_empty(k, v)?,
             "next-lockfile-bump" => self.next_lockfile_bump = parse_empty(k, v)?,
+            "message-catalogs" => self.message_catalogs = parse_empty(k, v)?,
             "minimal-versions" => self.minimal_versions = parse_empty(k, v)?,
             "msrv-policy" => self.msrv_policy = parse_empty(k, v)?,
             // can also be set in .cargo/config or with and ENV
             "mtime-on-use" => self.mtime_on_use = parse_empty(k, v)?,
             "no-index-update" => self.no_index_update = parse_empty(k, v)?,
+            "output-width" => self.output_width = parse_empty(k, v)?,
+            "package-workspace" => self.package_workspace = parse_empty(k, v)?,
             "panic-abort-tests" => self.panic_abort_tests = parse_empty(k, v)?,
+            "parallel-doctests" => self.parallel_doctests = parse_empty(k, v)?,
+            "plain-progress" => self.plain_progress = parse_empty(k, v)?,
+            "plugin-manifest" => self.plugin_manifest = parse_empty(k, v)?,
+            "prefer-fast-linker" => self.prefer_fast_linker = parse_empty(k, v)?,
+            "profile-auto-tune" => self.profile_auto_tune = parse_empty(k, v)?,
             "profile-rustflags" => self.profile_rustflags = parse_empty(k, v)?,
+            "proxy-config" => self.proxy_config = parse_empty(k, v)?,
             "publish-timeout" => self.publish_timeout = parse_empty(k, v)?,
             "registry-auth" => self.registry_auth = parse_empty(k, v)?,
+            "resolver-explain" => self.resolver_explain = parse_empty(k, v)?,
+            "run-workdir" => self.run_workdir = parse_empty(k, v)?,
             "rustdoc-map" => self.rustdoc_map = parse_empty(k, v)?,
             "rustdoc-scrape-examples" => self.rustdoc_scrape_examples = parse_empty(k, v)?,
+            "rustflags-append" => self.rustflags_append = parse_empty(k, v)?,
             "separate-nightlies" => self.separate_nightlies = parse_empty(k, v)?,
             "skip-rustdoc-fingerprint" => self.skip_rustdoc_fingerprint = parse_empty(k, v)?,
             "script" => self.script = parse_empty(k, v)?,
+            "self-profile" => self.self_profile = parse_empty(k, v)?,
+            "stream-color" => self.stream_color = parse_empty(k, v)?,
             "target-applies-to-host" => self.target_applies_to_host = parse_empty(k, v)?,
+            "target-cfg-rustdocflags" => self.target_cfg_rustdocflags = parse_empty(k, v)?,
+            "target-overrides" => self.target_overrides = parse_empty(k, v)?,
+            "trusted-publishing" => self.trusted_publishing = parse_empty(k, v)?,
             "unstable-options" => self.unstable_options = parse_empty(k, v)?,
+            "use-cached" => self.use_cached = parse_empty(k, v)?,
+            "vcs-detection" => self.vcs_detection = parse_empty(k, v)?,
+            "verbose-config-resolution" => self.verbose_config_resolution = parse_empty(k, v)?,
             _ => bail!("unknown `-Z` flag specified: {}", k),
         }
 
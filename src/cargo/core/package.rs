@@ -1,5 +1,6 @@
 use std::cell::{Cell, Ref, RefCell, RefMut};
 use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt;
 use std::hash;
@@ -13,12 +14,13 @@ use bytesize::ByteSize;
 use curl::easy::Easy;
 use curl::multi::{EasyHandle, Multi};
 use lazycell::LazyCell;
-use log::debug;
 use semver::Version;
 use serde::Serialize;
+use tracing::debug;
 
 use crate::core::compiler::{CompileKind, RustcTargetData};
 use crate::core::dependency::DepKind;
+use crate::core::features::CliUnstable;
 use crate::core::resolver::features::ForceAllTargets;
 use crate::core::resolver::{HasDevUnits, Resolve};
 use crate::core::source::MaybePackage;
@@ -29,6 +31,8 @@ use crate::util::errors::{CargoResult, HttpNotSuccessful};
 use crate::util::interning::InternedString;
 use crate::util::network::http::http_handle_and_timeout;
 use crate::util::network::http::HttpTimeout;
+use crate::util::network::http::RegistryHttpConfig;
+use crate::util::network::report::http_version;
 use crate::util::network::retry::{Retry, RetryResult};
 use crate::util::network::sleep::SleepTracker;
 use crate::util::{self, internal, Config, Progress, ProgressStyle};
@@ -104,6 +108,10 @@ pub struct SerializedPackage {
     metabuild: Option<Vec<String>>,
     default_run: Option<String>,
     rust_version: Option<String>,
+    /// Contents of the `.cargo_vcs_info.json` file added by `cargo package`,
+    /// for packages from registries.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    vcs_info: Option<serde_json::Value>,
 }
 
 impl Package {
@@ -209,7 +217,7 @@ impl Package {
         self.targets().iter().any(|t| t.is_example() || t.is_bin())
     }
 
-    pub fn serialized(&self) -> SerializedPackage {
+    pub fn serialized(&self, unstable_flags: &CliUnstable) -> SerializedPackage {
         let summary = self.manifest().summary();
         let package_id = summary.package_id();
         let manmeta = self.manifest().metadata();
@@ -263,7 +271,23 @@ impl Package {
             publish: self.publish().as_ref().cloned(),
             default_run: self.manifest().default_run().map(|s| s.to_owned()),
             rust_version: self.rust_version().map(|s| s.to_owned()),
+            vcs_info: if unstable_flags.unstable_options {
+                self.vcs_info()
+            } else {
+                None
+            },
+        }
+    }
+
+    /// Reads the `.cargo_vcs_info.json` file that `cargo package` added to
+    /// the package, if any. Only packages that aren't from a path can have
+    /// one.
+    fn vcs_info(&self) -> Option<serde_json::Value> {
+        if self.package_id().source_id().is_path() {
+            return None;
         }
+        let contents = std::fs::read_to_string(self.root().join(".cargo_vcs_info.json")).ok()?;
+        serde_json::from_str(&contents).ok()
     }
 }
 
@@ -364,6 +388,9 @@ pub struct Downloads<'a, 'cfg> {
     /// trigger a timeout; reset `next_speed_check` and set this back to the
     /// configured threshold.
     next_speed_check_bytes_threshold: Cell<u64>,
+    /// The HTTP settings of the registries downloaded from, see
+    /// [`RegistryHttpConfig`].
+    registry_http: HashMap<SourceId, RegistryHttpConfig>,
     /// Global filesystem lock to ensure only one Cargo is downloading at a
     /// time.
     _lock: PackageCacheLock<'cfg>,
@@ -464,10 +491,24 @@ impl<'cfg> PackageSet<'cfg> {
             timeout,
             next_speed_check: Cell::new(Instant::now()),
             next_speed_check_bytes_threshold: Cell::new(0),
+            registry_http: HashMap::new(),
             _lock: self.config.acquire_package_cache_lock()?,
         })
     }
 
+    /// Returns whether `id` can be loaded without downloading anything.
+    ///
+    /// The package cache lock must be held while calling this.
+    pub fn is_downloaded(&self, id: PackageId) -> bool {
+        if let Some(Some(_)) = self.packages.get(&id).map(|slot| slot.borrow()) {
+            return true;
+        }
+        let sources = self.sources.borrow();
+        sources
+            .get(id.source_id())
+            .map_or(false, |source| source.is_downloaded(id))
+    }
+
     pub fn get_one(&self, id: PackageId) -> CargoResult<&Package> {
         if let Some(pkg) = self.packages.get(&id).and_then(|slot| slot.borrow()) {
             return Ok(pkg);
@@ -695,6 +736,11 @@ impl<'a, 'cfg> Downloads<'a, 'cfg> {
         let (url, descriptor, authorization) = match pkg {
             MaybePackage::Ready(pkg) => {
                 debug!("{} doesn't need a download", id);
+                if id.source_id().is_registry() {
+                    if let Some(mut report) = self.set.config.network_report() {
+                        report.record_cache_hit();
+                    }
+                }
                 assert!(slot.fill(pkg).is_ok());
                 return Ok(Some(slot.borrow().unwrap()));
             }
@@ -715,6 +761,11 @@ impl<'a, 'cfg> Downloads<'a, 'cfg> {
         assert!(self.pending_ids.insert(id));
 
         let (mut handle, _timeout) = http_handle_and_timeout(self.set.config)?;
+        let registry_http = match self.registry_http.entry(id.source_id()) {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(RegistryHttpConfig::new(self.set.config, id.source_id())?),
+        };
+        registry_http.configure(self.set.config, &mut handle)?;
         handle.get(true)?;
         handle.url(&url)?;
         handle.follow_location(true)?; // follow redirects
@@ -807,6 +858,7 @@ impl<'a, 'cfg> Downloads<'a, 'cfg> {
     /// # Panics
     ///
     /// This function will panic if there are no remaining downloads.
+    #[tracing::instrument(skip_all)]
     pub fn wait(&mut self) -> CargoResult<&'a Package> {
         let (dl, data) = loop {
             assert_eq!(self.pending.len(), self.pending_ids.len());
@@ -822,6 +874,12 @@ impl<'a, 'cfg> Downloads<'a, 'cfg> {
             let mut handle = self.set.multi.remove(handle)?;
             self.pending_ids.remove(&dl.id);
 
+            // The status line of the last response, after any redirects.
+            let http_version = headers
+                .iter()
+                .rev()
+                .find_map(|h| http_version(h.as_bytes()));
+
             // Check if this was a spurious error. If it was a spurious error
             // then we want to re-enqueue our request for another attempt and
             // then we wait for another request to finish.
@@ -866,6 +924,10 @@ impl<'a, 'cfg> Downloads<'a, 'cfg> {
                     Ok(data)
                 })
             };
+            if let Some(mut report) = self.set.config.network_report() {
+                let retried = matches!(ret, RetryResult::Retry(_));
+                report.record(&mut handle, &dl.url, http_version, retried);
+            }
             match ret {
                 RetryResult::Success(data) => break (dl, data),
                 RetryResult::Err(e) => {
@@ -961,6 +1023,7 @@ impl<'a, 'cfg> Downloads<'a, 'cfg> {
         // actually block waiting for I/O to happen, which we achieve with the
         // `wait` method on `multi`.
         loop {
+            self.set.config.cancellation().check()?;
             self.add_sleepers()?;
             let n = tls::set(self, || {
                 self.set
@@ -20,21 +20,40 @@
 //! Profile settings can be overridden for specific packages and build-time crates.
 //! The precedence is explained in [`ProfileMaker`].
 //! The algorithm happens within [`ProfileMaker::get_profile`].
+//!
+//! ## Auto-tuning
+//!
+//! With `-Zprofile-auto-tune`, a profile with `auto-tune = true` picks the
+//! `opt-level` of each package itself, see [`Profiles::auto_tune`]. It relies
+//! on the compile times of the previous builds, recorded by the timings of
+//! the job queue into a [`CompileTimes`] file in the target directory.
 
 use crate::core::compiler::{CompileKind, CompileTarget, Unit};
 use crate::core::dependency::Artifact;
 use crate::core::resolver::features::FeaturesFor;
 use crate::core::{PackageId, PackageIdSpec, Resolve, Shell, Target, Workspace};
+use crate::util::config::ConfigRelativePath;
 use crate::util::interning::InternedString;
 use crate::util::toml::{
     ProfilePackageSpec, StringOrBool, TomlDebugInfo, TomlProfile, TomlProfiles,
 };
 use crate::util::{closest_msg, config, CargoResult, Config};
 use anyhow::{bail, Context as _};
+use cargo_util::paths;
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::hash::Hash;
+use std::path::{Path, PathBuf};
 use std::{cmp, fmt, hash};
 
+/// The compile time, in seconds, from which a dependency is worth optimizing
+/// with `auto-tune`.
+const AUTO_TUNE_HEAVY_SECS: f64 = 2.0;
+
+/// The most levels of dependencies a package may have below it to be
+/// optimized by `auto-tune`. Optimizing a package deep in the graph doesn't
+/// make the code of its own dependencies any faster.
+const AUTO_TUNE_MAX_DEPTH: usize = 1;
+
 /// Collection of all profiles.
 ///
 /// To get a specific [`Profile`], you usually create this and call [`get_profile`] then.
@@ -46,6 +65,13 @@ pub struct Profiles {
     /// - `CARGO_INCREMENTAL` environment variable.
     /// - `build.incremental` config value.
     incremental: Option<bool>,
+    /// Where rustc stores the incremental data, instead of the `incremental`
+    /// directory of the profile in the target directory, set with
+    /// `-Zincremental-dir`.
+    incremental_dir: Option<PathBuf>,
+    /// The directories set with `-Zincremental-dir` for any of the profiles,
+    /// which `cargo clean` removes along with the target directory.
+    all_incremental_dirs: Vec<PathBuf>,
     /// Map of profile name to directory name for that profile.
     dir_names: HashMap<InternedString, InternedString>,
     /// The profile makers. Key is the profile name.
@@ -59,6 +85,19 @@ pub struct Profiles {
     requested_profile: InternedString,
     /// The host target for rustc being used by this `Profiles`.
     rustc_host: InternedString,
+    /// The `opt-level` chosen for each package when the requested profile
+    /// has `auto-tune = true`, see [`Profiles::auto_tune`].
+    auto_tune: Option<HashMap<PackageId, AutoTuneDecision>>,
+}
+
+/// How `auto-tune` builds a package.
+#[derive(Clone, Debug)]
+pub struct AutoTuneDecision {
+    /// The `opt-level` of the package, or `None` to keep the one of the
+    /// profile.
+    pub opt_level: Option<InternedString>,
+    /// Why, for `cargo report auto-tune`.
+    pub reason: String,
 }
 
 impl Profiles {
@@ -73,11 +112,14 @@ impl Profiles {
 
         let mut profile_makers = Profiles {
             incremental,
+            incremental_dir: None,
+            all_incremental_dirs: Vec::new(),
             dir_names: Self::predefined_dir_names(),
             by_name: HashMap::new(),
             original_profiles: profiles.clone(),
             requested_profile,
             rustc_host,
+            auto_tune: None,
         };
 
         Self::add_root_profiles(&mut profile_makers, &profiles);
@@ -101,6 +143,14 @@ impl Profiles {
         for (name, profile) in &profiles {
             profile_makers.add_maker(*name, profile, &profiles)?;
         }
+        profile_makers.incremental_dir = incremental_dir(config, requested_profile, &profiles)?;
+        for name in profiles.keys() {
+            if let Some(dir) = incremental_dir(config, *name, &profiles)? {
+                if !profile_makers.all_incremental_dirs.contains(&dir) {
+                    profile_makers.all_incremental_dirs.push(dir);
+                }
+            }
+        }
         // Verify that the requested profile is defined *somewhere*.
         // This simplifies the API (no need for CargoResult), and enforces
         // assumptions about how config profiles are loaded.
@@ -264,7 +314,9 @@ impl Profiles {
         kind: CompileKind,
     ) -> Profile {
         let maker = self.get_profile_maker(self.requested_profile).unwrap();
-        let mut profile = maker.get_profile(Some(pkg_id), is_member, unit_for.is_for_host());
+        let auto_tune = self.auto_tune.as_ref().and_then(|d| d.get(&pkg_id));
+        let mut profile =
+            maker.get_profile(Some(pkg_id), is_member, unit_for.is_for_host(), auto_tune);
 
         // Dealing with `panic=abort` and `panic=unwind` requires some special
         // treatment. Be sure to process all the various options here.
@@ -326,7 +378,9 @@ impl Profiles {
     pub fn base_profile(&self) -> Profile {
         let profile_name = self.requested_profile;
         let maker = self.get_profile_maker(profile_name).unwrap();
-        maker.get_profile(None, /*is_member*/ true, /*is_for_host*/ false)
+        maker.get_profile(
+            None, /*is_member*/ true, /*is_for_host*/ false, None,
+        )
     }
 
     /// Gets the directory name for a profile, like `debug` or `release`.
@@ -337,6 +391,108 @@ impl Profiles {
             .unwrap_or(&self.requested_profile)
     }
 
+    /// Gets the directory set with `-Zincremental-dir` where rustc stores the
+    /// incremental data of the requested profile, if any.
+    pub fn incremental_dir(&self) -> Option<&Path> {
+        self.incremental_dir.as_deref()
+    }
+
+    /// Gets the directories set with `-Zincremental-dir` for all the
+    /// profiles, with the incremental data of every profile and target.
+    pub fn all_incremental_dirs(&self) -> &[PathBuf] {
+        &self.all_incremental_dirs
+    }
+
+    /// Chooses the `opt-level` of the packages of `resolve` when the requested
+    /// profile has `auto-tune = true`:
+    ///
+    /// * Workspace members and other path packages, which are edited and
+    ///   rebuilt often, aren't optimized.
+    /// * Dependencies which took long to compile in the previous builds and
+    ///   have few dependencies of their own are fully optimized: they are the
+    ///   libraries doing the heavy lifting, like compression or image codecs,
+    ///   which are slow when not optimized and rarely rebuilt.
+    /// * Other dependencies keep the `opt-level` of the profile.
+    ///
+    /// Units for the host keep their settings, and so do the packages with a
+    /// `[profile.<name>.package]` override. Only the settings the profile
+    /// doesn't set are tuned: with an `opt-level` in the profile, every
+    /// package keeps it. Packages are never built below the `opt-level` the
+    /// profile resolves to, so nothing is tuned for a profile which
+    /// optimizes, like `release`.
+    pub fn auto_tune(&mut self, ws: &Workspace<'_>, resolve: &Resolve) -> CargoResult<()> {
+        if !ws.config().cli_unstable().profile_auto_tune {
+            return Ok(());
+        }
+        let maker = self.get_profile_maker(self.requested_profile)?;
+        if maker.toml.as_ref().and_then(|toml| toml.auto_tune) != Some(true) {
+            return Ok(());
+        }
+        let profile_opt_level = maker
+            .toml
+            .as_ref()
+            .map_or(false, |toml| toml.opt_level.is_some());
+        let resolved_opt_level = maker.get_profile(None, true, false, None).opt_level;
+        let compile_times = CompileTimes::load(&self.compile_times_path(ws));
+        let members: HashSet<_> = ws.members().map(|pkg| pkg.package_id()).collect();
+        let mut depths = HashMap::new();
+        let mut decisions = HashMap::new();
+        for pkg_id in resolve.iter() {
+            let depth = dependency_depth(resolve, pkg_id, &mut depths);
+            let dependencies = match depth {
+                0 => "has no dependencies".to_string(),
+                1 => "depends on 1 level of packages".to_string(),
+                n => format!("depends on {n} levels of packages"),
+            };
+            let (opt_level, reason) = if profile_opt_level {
+                (None, "`opt-level` set in the profile".to_string())
+            } else if resolved_opt_level != "0" {
+                (
+                    None,
+                    format!("the profile has `opt-level` {resolved_opt_level}"),
+                )
+            } else if members.contains(&pkg_id) {
+                (Some("0"), "workspace member".to_string())
+            } else if pkg_id.source_id().is_path() {
+                (Some("0"), "local package".to_string())
+            } else if depth > AUTO_TUNE_MAX_DEPTH {
+                (None, dependencies)
+            } else {
+                match compile_times.get(pkg_id) {
+                    None => (None, "no compile time recorded yet".to_string()),
+                    Some(secs) if secs < AUTO_TUNE_HEAVY_SECS => {
+                        (None, format!("compiled in {secs:.1}s"))
+                    }
+                    Some(secs) => (Some("3"), format!("compiled in {secs:.1}s, {dependencies}")),
+                }
+            };
+            decisions.insert(
+                pkg_id,
+                AutoTuneDecision {
+                    opt_level: opt_level.map(InternedString::new),
+                    reason,
+                },
+            );
+        }
+        self.auto_tune = Some(decisions);
+        Ok(())
+    }
+
+    /// The choices of [`Profiles::auto_tune`], if the requested profile has
+    /// `auto-tune = true`.
+    pub fn auto_tune_decisions(&self) -> Option<&HashMap<PackageId, AutoTuneDecision>> {
+        self.auto_tune.as_ref()
+    }
+
+    /// The file where the compile times used by `auto-tune` are recorded,
+    /// in the host directory of the requested profile.
+    pub fn compile_times_path(&self, ws: &Workspace<'_>) -> PathBuf {
+        ws.target_dir()
+            .as_path_unlocked()
+            .join(self.get_dir_name())
+            .join(".auto-tune.json")
+    }
+
     /// Used to check for overrides for non-existing packages.
     pub fn validate_packages(
         &self,
@@ -388,6 +544,8 @@ impl Profiles {
 /// - `[profile.dev.package."*"]` -- this cannot apply to workspace members.
 /// - `[profile.dev.build-override]` -- this can only apply to `build.rs` scripts
 ///   and their dependencies.
+/// - The `opt-level` chosen by `auto-tune` -- this cannot apply to build
+///   scripts and their dependencies.
 /// - `[profile.dev]`
 /// - Default (hard-coded) values.
 #[derive(Debug, Clone)]
@@ -416,6 +574,7 @@ impl ProfileMaker {
         pkg_id: Option<PackageId>,
         is_member: bool,
         is_for_host: bool,
+        auto_tune: Option<&AutoTuneDecision>,
     ) -> Profile {
         let mut profile = self.default.clone();
 
@@ -450,6 +609,18 @@ impl ProfileMaker {
             // below so the unit can be reused, otherwise we can avoid emitting
             // the unit's debuginfo.
             profile.debuginfo = DebugInfo::Deferred(profile.debuginfo.into_inner());
+        } else if let Some(opt_level) = auto_tune.and_then(|d| d.opt_level) {
+            // `auto-tune` leaves the codegen units to the default of rustc,
+            // which suits the chosen `opt-level`, unless the profile sets
+            // them.
+            profile.opt_level = opt_level;
+            if self
+                .toml
+                .as_ref()
+                .map_or(true, |toml| toml.codegen_units.is_none())
+            {
+                profile.codegen_units = None;
+            }
         }
         // ... and next comes any other sorts of overrides specified in
         // profiles, such as `[profile.release.build-override]` or
@@ -555,6 +726,9 @@ fn merge_profile(profile: &mut Profile, toml: &TomlProfile) {
     if let Some(flags) = &toml.rustflags {
         profile.rustflags = flags.clone();
     }
+    if let Some(flags) = &toml.rustdocflags {
+        profile.rustdocflags = flags.clone();
+    }
     profile.strip = match toml.strip {
         Some(StringOrBool::Bool(true)) => Strip::Named(InternedString::new("symbols")),
         None | Some(StringOrBool::Bool(false)) => Strip::None,
@@ -598,6 +772,8 @@ pub struct Profile {
     #[serde(skip_serializing_if = "Vec::is_empty")] // remove when `rustflags` is stablized
     // Note that `rustflags` is used for the cargo-feature `profile_rustflags`
     pub rustflags: Vec<InternedString>,
+    #[serde(skip_serializing_if = "Vec::is_empty")] // remove when `rustdocflags` is stablized
+    pub rustdocflags: Vec<InternedString>,
 }
 
 impl Default for Profile {
@@ -618,6 +794,7 @@ impl Default for Profile {
             panic: PanicStrategy::Unwind,
             strip: Strip::None,
             rustflags: vec![],
+            rustdocflags: vec![],
         }
     }
 }
@@ -646,6 +823,7 @@ compact_debug! {
                 panic
                 strip
                 rustflags
+                rustdocflags
             )]
         }
     }
@@ -1201,6 +1379,100 @@ fn merge_config_profiles(
     Ok(profiles)
 }
 
+/// Reads the directory where rustc stores incremental data for the
+/// `requested_profile`, from the `profile.<name>.incremental-dir` config of it
+/// or the profiles it inherits from, then from `build.incremental-dir`.
+///
+/// The location is not part of the fingerprint of units: the incremental data
+/// is only a cache for rustc, so moving it doesn't make units dirty.
+fn incremental_dir(
+    config: &Config,
+    requested_profile: InternedString,
+    profiles: &BTreeMap<InternedString, TomlProfile>,
+) -> CargoResult<Option<PathBuf>> {
+    if !config.cli_unstable().incremental_dir {
+        return Ok(None);
+    }
+    let mut name = Some(requested_profile);
+    let mut seen = HashSet::new();
+    while let Some(n) = name.filter(|n| seen.insert(*n)) {
+        let key = format!("profile.{n}.incremental-dir");
+        if let Some(dir) = config.get::<Option<ConfigRelativePath>>(&key)? {
+            return Ok(Some(dir.resolve_path(config)));
+        }
+        name = profiles.get(&n).and_then(|profile| profile.inherits);
+    }
+    Ok(config
+        .build_config()?
+        .incremental_dir
+        .as_ref()
+        .map(|dir| dir.resolve_path(config)))
+}
+
+/// The number of levels of dependencies below `pkg_id` in `resolve`, 0 for a
+/// package without dependencies.
+fn dependency_depth(
+    resolve: &Resolve,
+    pkg_id: PackageId,
+    depths: &mut HashMap<PackageId, usize>,
+) -> usize {
+    if let Some(depth) = depths.get(&pkg_id) {
+        return *depth;
+    }
+    // Breaks the cycles of dev-dependencies.
+    depths.insert(pkg_id, 0);
+    let depth = resolve
+        .deps(pkg_id)
+        .map(|(dep_id, _)| dependency_depth(resolve, dep_id, depths) + 1)
+        .max()
+        .unwrap_or(0);
+    depths.insert(pkg_id, depth);
+    depth
+}
+
+/// The compile times of the packages in the previous builds of a profile,
+/// used by `auto-tune`.
+///
+/// Only the libraries are recorded, as they are the units `auto-tune` changes
+/// the `opt-level` of. A library built both for the host and the target
+/// counts once, with its longest compile time.
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct CompileTimes {
+    /// The seconds it took to compile each package the last time it was
+    /// built.
+    packages: BTreeMap<PackageId, f64>,
+}
+
+impl CompileTimes {
+    /// Loads the compile times at `path`, or none if it can't be read.
+    pub fn load(path: &Path) -> CompileTimes {
+        let Ok(contents) = paths::read(path) else {
+            return CompileTimes::default();
+        };
+        serde_json::from_str(&contents).unwrap_or_else(|e| {
+            tracing::debug!("failed to parse {}: {e}", path.display());
+            CompileTimes::default()
+        })
+    }
+
+    /// The seconds it took to compile `pkg_id` the last time it was built.
+    pub fn get(&self, pkg_id: PackageId) -> Option<f64> {
+        self.packages.get(&pkg_id).copied()
+    }
+
+    /// Adds the compile times of the packages built by a build to the ones
+    /// recorded at `path`.
+    pub fn record(path: &Path, times: HashMap<PackageId, f64>) -> CargoResult<()> {
+        if times.is_empty() {
+            return Ok(());
+        }
+        let mut compile_times = CompileTimes::load(path);
+        compile_times.packages.extend(times);
+        let contents = serde_json::to_string_pretty(&compile_times)?;
+        paths::write(path, contents)
+    }
+}
+
 /// Helper for fetching a profile from config.
 fn get_config_profile(ws: &Workspace<'_>, name: &str) -> CargoResult<Option<TomlProfile>> {
     let profile: Option<config::Value<TomlProfile>> =
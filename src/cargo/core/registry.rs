@@ -8,7 +8,7 @@ use crate::util::errors::CargoResult;
 use crate::util::interning::InternedString;
 use crate::util::{CanonicalUrl, Config};
 use anyhow::{bail, Context as _};
-use log::{debug, trace};
+use tracing::{debug, trace};
 use url::Url;
 
 /// Source of information about a group of packages.
@@ -31,6 +31,11 @@ pub trait Registry {
     fn describe_source(&self, source: SourceId) -> String;
     fn is_replaced(&self, source: SourceId) -> bool;
 
+    /// Whether the package is available without accessing the network.
+    fn is_downloaded(&self, _pkg: PackageId) -> bool {
+        true
+    }
+
     /// Block until all outstanding Poll::Pending requests are Poll::Ready.
     fn block_until_ready(&mut self) -> CargoResult<()>;
 }
@@ -719,6 +724,13 @@ impl<'cfg> Registry for PackageRegistry<'cfg> {
         }
     }
 
+    fn is_downloaded(&self, pkg: PackageId) -> bool {
+        match self.sources.get(pkg.source_id()) {
+            Some(src) => src.is_downloaded(pkg),
+            None => true,
+        }
+    }
+
     fn block_until_ready(&mut self) -> CargoResult<()> {
         for (source_id, source) in self.sources.sources_mut() {
             source
@@ -876,7 +888,7 @@ fn summary_for_patch(
         // Since the locked patch did not match anything, try the unlocked one.
         let orig_matches =
             ready!(source.query_vec(orig_patch, QueryKind::Exact)).unwrap_or_else(|e| {
-                log::warn!(
+                tracing::warn!(
                     "could not determine unlocked summaries for dep {:?}: {:?}",
                     orig_patch,
                     e
@@ -895,7 +907,7 @@ fn summary_for_patch(
 
     let name_summaries =
         ready!(source.query_vec(&name_only_dep, QueryKind::Exact)).unwrap_or_else(|e| {
-            log::warn!(
+            tracing::warn!(
                 "failed to do name-only summary query for {:?}: {:?}",
                 name_only_dep,
                 e
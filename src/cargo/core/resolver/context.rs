@@ -6,9 +6,9 @@ use crate::core::{Dependency, PackageId, SourceId, Summary};
 use crate::util::interning::InternedString;
 use crate::util::Graph;
 use anyhow::format_err;
-use log::debug;
 use std::collections::HashMap;
 use std::num::NonZeroU64;
+use tracing::debug;
 
 pub use super::encode::Metadata;
 pub use super::encode::{EncodableDependency, EncodablePackageId, EncodableResolve};
@@ -24,8 +24,9 @@ pub struct Context {
     pub activations: Activations,
     /// list the features that are activated for each package
     pub resolve_features: im_rc::HashMap<PackageId, FeaturesSet>,
-    /// get the package that will be linking to a native library by its links attribute
-    pub links: im_rc::HashMap<InternedString, PackageId>,
+    /// get the package that will be linking to a native library by its links attribute,
+    /// `None` if conflicts are only checked for the units being built, with `-Zlinks-per-target`
+    pub links: Option<im_rc::HashMap<InternedString, PackageId>>,
     /// for each package the list of names it can see,
     /// then for each name the exact version that name represents and whether the name is public.
     pub public_dependency: Option<PublicDependency>,
@@ -78,11 +79,15 @@ impl PackageId {
 }
 
 impl Context {
-    pub fn new(check_public_visible_dependencies: bool) -> Context {
+    pub fn new(check_public_visible_dependencies: bool, check_links: bool) -> Context {
         Context {
             age: 0,
             resolve_features: im_rc::HashMap::new(),
-            links: im_rc::HashMap::new(),
+            links: if check_links {
+                Some(im_rc::HashMap::new())
+            } else {
+                None
+            },
             public_dependency: if check_public_visible_dependencies {
                 Some(PublicDependency::new())
             } else {
@@ -117,8 +122,8 @@ impl Context {
                 );
             }
             im_rc::hashmap::Entry::Vacant(v) => {
-                if let Some(link) = summary.links() {
-                    if self.links.insert(link, id).is_some() {
+                if let (Some(link), Some(links)) = (summary.links(), &mut self.links) {
+                    if links.insert(link, id).is_some() {
                         return Err(format_err!(
                             "Attempting to resolve a dependency with more than \
                              one crate with links={}.\nThis will not build as \
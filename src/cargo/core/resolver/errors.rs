@@ -3,6 +3,8 @@ use std::task::Poll;
 
 use crate::core::{Dependency, PackageId, QueryKind, Registry, Summary};
 use crate::util::edit_distance::edit_distance;
+use crate::util::errors::LinkedError;
+use crate::util::interning::InternedString;
 use crate::util::{Config, VersionExt};
 use anyhow::Error;
 
@@ -23,6 +25,11 @@ impl ResolveError {
         }
     }
 
+    /// The error this adds the path of packages to.
+    pub fn cause(&self) -> &Error {
+        &self.cause
+    }
+
     /// Returns a path of packages from the package whose requirements could not be resolved up to
     /// the root.
     pub fn package_path(&self) -> &[PackageId] {
@@ -68,6 +75,25 @@ impl From<(PackageId, ConflictReason)> for ActivateError {
     }
 }
 
+/// The activation error `msg`, where the name of `dep`, when it is the first
+/// thing quoted, is displayed as a hyperlink to its documentation on docs.rs
+/// with `term.hyperlinks` if it comes from crates.io.
+fn link_dep_name(msg: String, dep: &Dependency) -> anyhow::Error {
+    let name = dep.package_name();
+    let start = msg.find('`').map(|quote| quote + 1);
+    match start {
+        Some(start) if dep.source_id().is_crates_io() && msg[start..].starts_with(&*name) => {
+            let end = start + name.len();
+            LinkedError::new()
+                .push(&msg[..start])
+                .push_link(name, format!("https://docs.rs/{name}"))
+                .push(&msg[end..])
+                .into()
+        }
+        _ => anyhow::Error::msg(msg),
+    }
+}
+
 pub(super) fn activation_error(
     cx: &Context,
     registry: &mut dyn Registry,
@@ -141,6 +167,16 @@ pub(super) fn activation_error(
                     msg.push_str("Try to adjust your dependencies so that only one package uses the links ='");
                     msg.push_str(&*dep.package_name());
                     msg.push_str("' value. For more information, see https://doc.rust-lang.org/cargo/reference/resolver.html#links.");
+                    let prev_path = cx.parents.path_to_bottom(p);
+                    let prev_edge = prev_path.get(1).and_then(|&(prev_parent, deps)| {
+                        Some((p.name(), *prev_parent, deps?.iter().next()?))
+                    });
+                    let edges = prev_edge.into_iter().chain([(
+                        dep.package_name(),
+                        parent.package_id(),
+                        dep,
+                    )]);
+                    msg.push_str(&links_conflict_help(link, edges));
                 }
                 ConflictReason::MissingFeatures(features) => {
                     msg.push_str("\n\nthe package `");
@@ -208,13 +244,26 @@ pub(super) fn activation_error(
                     msg.push_str(&describe_path_in_context(cx, p));
                 }
             }
+            if config.map_or(false, |config| config.cli_unstable().resolver_explain) {
+                let semver_conflicts: Vec<_> = conflicting_activations
+                    .iter()
+                    .filter(|(_, r)| matches!(r, ConflictReason::Semver))
+                    .map(|(p, _)| **p)
+                    .collect();
+                msg.push_str(&explain_semver_conflict(
+                    cx,
+                    parent.package_id(),
+                    dep,
+                    &semver_conflicts,
+                ));
+            }
         }
 
         msg.push_str("\n\nfailed to select a version for `");
         msg.push_str(&*dep.package_name());
         msg.push_str("` which could resolve this conflict");
 
-        return to_resolve_err(anyhow::format_err!("{}", msg));
+        return to_resolve_err(link_dep_name(msg, dep));
     }
 
     // We didn't actually find any candidates, so we need to
@@ -289,6 +338,18 @@ pub(super) fn activation_error(
                 msg.push_str("\nperhaps a crate was updated and forgotten to be re-vendored?");
             }
 
+            let offline = config.map_or(false, |config| {
+                config.offline() || config.cli_unstable().use_cached
+            });
+            if offline && dep.source_id().is_registry() {
+                let cached: Vec<_> = candidates
+                    .iter()
+                    .filter(|s| registry.is_downloaded(s.package_id()))
+                    .map(|s| s.version())
+                    .collect();
+                msg.push_str(&describe_cached_versions(dep, &cached));
+            }
+
             msg
         } else {
             // Maybe the user mistyped the name? Like `dep-thing` when `Dep_Thing`
@@ -373,7 +434,153 @@ pub(super) fn activation_error(
         }
     }
 
-    to_resolve_err(anyhow::format_err!("{}", msg))
+    to_resolve_err(link_dep_name(msg, dep))
+}
+
+/// Lists the versions of `dep` that can be used offline, newest first, and
+/// suggests switching to one of them if `dep` is locked to another version.
+fn describe_cached_versions(dep: &Dependency, cached: &[&semver::Version]) -> String {
+    let name = dep.package_name();
+    if cached.is_empty() {
+        return format!("\nno version of `{name}` is available offline");
+    }
+    let mut versions: Vec<_> = cached.iter().take(5).map(|v| v.to_string()).collect();
+    if cached.len() > 5 {
+        versions.push("...".into());
+    }
+    let mut msg = format!(
+        "\nversions of `{name}` available offline: {}",
+        versions.join(", ")
+    );
+    if dep.version_req().is_locked() {
+        if let Some(v) = cached
+            .iter()
+            .find(|v| dep.version_req().matches_unlocked(v))
+        {
+            msg.push_str(&format!(
+                "\nconsider running `cargo update -p {name} --precise {v} --offline` \
+                 to use a version that is available offline"
+            ));
+        }
+    }
+    msg
+}
+
+/// Explains why the previously selected `conflicts` rule out every candidate
+/// for `dep` of `parent`: which requirements clash and which dependency
+/// chains make both of them necessary.
+fn explain_semver_conflict(
+    cx: &Context,
+    parent: PackageId,
+    dep: &Dependency,
+    conflicts: &[PackageId],
+) -> String {
+    use std::fmt::Write;
+
+    let name = dep.package_name();
+    let mut msg = String::from("\n\nexplanation:");
+    write!(
+        msg,
+        "\n  `{}` requires `{} {}`",
+        parent,
+        name,
+        dep.version_req()
+    )
+    .unwrap();
+
+    let chain = |path: &[(&PackageId, Option<_>)]| {
+        let mut chain: Vec<_> = path.iter().rev().map(|(id, _)| id.to_string()).collect();
+        chain.push(name.to_string());
+        chain.join(" -> ")
+    };
+    let parent_path = cx.parents.path_to_bottom(&parent);
+    for conflict in conflicts {
+        let path = cx.parents.path_to_bottom(conflict);
+        let Some((dependent, Some(deps))) = path.get(1) else {
+            continue;
+        };
+        let req = deps
+            .iter()
+            .next()
+            .map_or_else(|| "*".to_string(), |d| d.version_req().to_string());
+        write!(
+            msg,
+            "\n  but `{}` requires `{} {}`, which selected `{}`",
+            dependent, name, req, conflict
+        )
+        .unwrap();
+
+        // Both requirements are needed because of the closest package that
+        // depends on the parent and on the dependent of the conflict.
+        let common = parent_path.iter().enumerate().find_map(|(i, (id, _))| {
+            let j = path[1..].iter().position(|(other, _)| other == id)?;
+            Some((i, j + 1))
+        });
+        match common {
+            Some((0, 1)) => {}
+            Some((i, j)) => write!(
+                msg,
+                "\n  and both are needed because of `{}` and `{}`",
+                chain(&parent_path[..=i]),
+                chain(&path[1..=j])
+            )
+            .unwrap(),
+            None => {}
+        }
+    }
+    write!(
+        msg,
+        "\n  and only one semver-compatible version of `{}` can be selected",
+        name
+    )
+    .unwrap();
+    msg
+}
+
+/// Suggests how to remove one of the packages linking to the native library
+/// `link` from the dependency graph.
+///
+/// `edges` are the dependencies through which the conflicting packages, named
+/// by the first element, were pulled in, along with the packages declaring
+/// them.
+pub(crate) fn links_conflict_help<'a>(
+    link: &str,
+    edges: impl Iterator<Item = (InternedString, PackageId, &'a Dependency)>,
+) -> String {
+    use std::fmt::Write;
+
+    let mut help = String::new();
+    for (name, parent, dep) in edges {
+        if dep.is_optional() {
+            write!(
+                help,
+                "\nhelp: `{}` is an optional dependency of package `{}`, \
+                 disabling the features that enable it removes this path",
+                name, parent
+            )
+            .unwrap();
+        }
+        if let Some(platform) = dep.platform() {
+            write!(
+                help,
+                "\nnote: `{}` is only a dependency of package `{}` for `{}`, \
+                 `-Zlinks-per-target` only reports conflicts between packages \
+                 built for the selected targets",
+                name, parent, platform
+            )
+            .unwrap();
+        }
+    }
+    write!(
+        help,
+        "\nhelp: to keep a single package linking to `{}`, update the dependents \
+         so they agree on one package, using `[patch]` for dependencies outside \
+         the workspace, see \
+         https://doc.rust-lang.org/cargo/reference/overriding-dependencies.html#the-patch-section",
+        link
+    )
+    .unwrap();
+    help
 }
 
 /// Returns String representation of dependency chain for a particular `pkgid`
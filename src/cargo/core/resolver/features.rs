@@ -42,6 +42,7 @@ use crate::core::compiler::{CompileKind, CompileTarget, RustcTargetData};
 use crate::core::dependency::{ArtifactTarget, DepKind, Dependency};
 use crate::core::resolver::types::FeaturesSet;
 use crate::core::resolver::{Resolve, ResolveBehavior};
+use crate::core::summary::FeatureMap;
 use crate::core::{FeatureValue, PackageId, PackageIdSpec, PackageSet, Workspace};
 use crate::util::interning::InternedString;
 use crate::util::CargoResult;
@@ -311,6 +312,79 @@ impl CliFeatures {
     }
 }
 
+/// The maximum number of combinations [`feature_matrix`] computes for a
+/// package.
+const MAX_FEATURE_COMBINATIONS: usize = 1024;
+
+/// Computes the feature combinations of a package with `features` that
+/// `cargo check --all-features-matrix` checks.
+///
+/// Combinations enabling the same set of features, like `{a}` and `{a, b}`
+/// when `a` enables `b`, are only included once, so this is the powerset of
+/// features pruned by the implications between them. Each combination lists
+/// only the features that aren't enabled by another one of the combination.
+/// The first combination has no features at all, and the ones after it grow
+/// one feature at a time, up to `max_depth` features if it is set.
+pub fn feature_matrix(
+    features: &FeatureMap,
+    max_depth: Option<usize>,
+) -> CargoResult<Vec<BTreeSet<InternedString>>> {
+    // All the features enabled by `explicit`, directly or through other
+    // features, including the implicit features of optional dependencies.
+    let closure = |explicit: &BTreeSet<InternedString>| {
+        let mut enabled = BTreeSet::new();
+        let mut pending: Vec<_> = explicit.iter().copied().collect();
+        while let Some(feature) = pending.pop() {
+            if !enabled.insert(feature) {
+                continue;
+            }
+            for value in features.get(&feature).into_iter().flatten() {
+                match value {
+                    FeatureValue::Feature(f) => pending.push(*f),
+                    FeatureValue::DepFeature {
+                        dep_name,
+                        weak: false,
+                        ..
+                    } if features.contains_key(dep_name) => pending.push(*dep_name),
+                    _ => {}
+                }
+            }
+        }
+        enabled
+    };
+
+    let mut seen = HashSet::new();
+    seen.insert(BTreeSet::new());
+    let mut matrix = vec![BTreeSet::new()];
+    let mut next = 0;
+    while let Some(explicit) = matrix.get(next).cloned() {
+        next += 1;
+        if max_depth.map_or(false, |max_depth| explicit.len() >= max_depth) {
+            continue;
+        }
+        let enabled = closure(&explicit);
+        for &feature in features.keys().filter(|f| !enabled.contains(*f)) {
+            let mut combination = explicit.clone();
+            combination.insert(feature);
+            if !seen.insert(closure(&combination)) {
+                continue;
+            }
+            // Drop the features implied by the new one.
+            let implied = closure(&BTreeSet::from([feature]));
+            combination.retain(|f| *f == feature || !implied.contains(f));
+            if matrix.len() == MAX_FEATURE_COMBINATIONS {
+                bail!(
+                    "there are more than {} combinations of features\n\
+                     help: pass `--matrix-depth` to only check combinations of fewer features",
+                    MAX_FEATURE_COMBINATIONS
+                );
+            }
+            matrix.push(combination);
+        }
+    }
+    Ok(matrix)
+}
+
 impl ResolvedFeatures {
     /// Returns the list of features that are enabled for the given package.
     pub fn activated_features(
@@ -470,7 +544,7 @@ impl<'a, 'cfg> FeatureResolver<'a, 'cfg> {
             deferred_weak_dependencies: HashMap::new(),
         };
         r.do_resolve(specs, cli_features)?;
-        log::debug!("features={:#?}", r.activated_features);
+        tracing::debug!("features={:#?}", r.activated_features);
         if r.opts.compare {
             r.compare();
         }
@@ -518,7 +592,7 @@ impl<'a, 'cfg> FeatureResolver<'a, 'cfg> {
         fk: FeaturesFor,
         fvs: &[FeatureValue],
     ) -> CargoResult<()> {
-        log::trace!("activate_pkg {} {}", pkg_id.name(), fk);
+        tracing::trace!("activate_pkg {} {}", pkg_id.name(), fk);
         // Add an empty entry to ensure everything is covered. This is intended for
         // finding bugs where the resolver missed something it should have visited.
         // Remove this in the future if `activated_features` uses an empty default.
@@ -566,7 +640,7 @@ impl<'a, 'cfg> FeatureResolver<'a, 'cfg> {
         fk: FeaturesFor,
         fv: &FeatureValue,
     ) -> CargoResult<()> {
-        log::trace!("activate_fv {} {} {}", pkg_id.name(), fk, fv);
+        tracing::trace!("activate_fv {} {} {}", pkg_id.name(), fk, fv);
         match fv {
             FeatureValue::Feature(f) => {
                 self.activate_rec(pkg_id, fk, *f)?;
@@ -593,7 +667,7 @@ impl<'a, 'cfg> FeatureResolver<'a, 'cfg> {
         fk: FeaturesFor,
         feature_to_enable: InternedString,
     ) -> CargoResult<()> {
-        log::trace!(
+        tracing::trace!(
             "activate_rec {} {} feat={}",
             pkg_id.name(),
             fk,
@@ -615,7 +689,7 @@ impl<'a, 'cfg> FeatureResolver<'a, 'cfg> {
                 // TODO: this should only happen for optional dependencies.
                 // Other cases should be validated by Summary's `build_feature_map`.
                 // Figure out some way to validate this assumption.
-                log::debug!(
+                tracing::debug!(
                     "pkg {:?} does not define feature {}",
                     pkg_id,
                     feature_to_enable
@@ -654,7 +728,7 @@ impl<'a, 'cfg> FeatureResolver<'a, 'cfg> {
                 }
                 if let Some(to_enable) = &to_enable {
                     for dep_feature in to_enable {
-                        log::trace!(
+                        tracing::trace!(
                             "activate deferred {} {} -> {}/{}",
                             pkg_id.name(),
                             fk,
@@ -697,7 +771,7 @@ impl<'a, 'cfg> FeatureResolver<'a, 'cfg> {
                     {
                         // This is weak, but not yet activated. Defer in case
                         // something comes along later and enables it.
-                        log::trace!(
+                        tracing::trace!(
                             "deferring feature {} {} -> {}/{}",
                             pkg_id.name(),
                             fk,
@@ -63,7 +63,7 @@ use std::mem;
 use std::rc::Rc;
 use std::time::{Duration, Instant};
 
-use log::{debug, trace};
+use tracing::{debug, trace};
 
 use crate::core::PackageIdSpec;
 use crate::core::{Dependency, PackageId, Registry, Summary};
@@ -82,7 +82,7 @@ pub use self::encode::Metadata;
 pub use self::encode::{EncodableDependency, EncodablePackageId, EncodableResolve};
 pub use self::errors::{ActivateError, ActivateResult, ResolveError};
 pub use self::features::{CliFeatures, ForceAllTargets, HasDevUnits};
-pub use self::resolve::{Resolve, ResolveVersion};
+pub use self::resolve::{Provenance, Resolve, ResolveVersion};
 pub use self::types::{ResolveBehavior, ResolveOpts};
 pub use self::version_prefs::{VersionOrdering, VersionPreferences};
 
@@ -131,6 +131,7 @@ mod version_prefs;
 ///
 ///     When we have a decision for how to implement is without breaking existing functionality
 ///     this flag can be removed.
+#[tracing::instrument(skip_all)]
 pub fn resolve(
     summaries: &[(Summary, ResolveOpts)],
     replacements: &[(PackageIdSpec, Dependency)],
@@ -148,10 +149,16 @@ pub fn resolve(
         Some(config) => config.cli_unstable().direct_minimal_versions,
         None => false,
     };
+    // With `-Zlinks-per-target`, `links` conflicts are left to `validate_links`,
+    // which only sees the packages built for the selected targets.
+    let check_links = match config {
+        Some(config) => !config.cli_unstable().links_per_target,
+        None => true,
+    };
     let mut registry =
         RegistryQueryer::new(registry, replacements, version_prefs, minimal_versions);
     let cx = loop {
-        let cx = Context::new(check_public_visible_dependencies);
+        let cx = Context::new(check_public_visible_dependencies, check_links);
         let cx = activate_deps_loop(
             cx,
             &mut registry,
@@ -792,8 +799,8 @@ impl RemainingCandidates {
             // package in a dependency graph, globally, with that particular
             // `links` key. If this candidate links to something that's already
             // linked to by a different package then we've gotta skip this.
-            if let Some(link) = b.links() {
-                if let Some(&a) = cx.links.get(&link) {
+            if let (Some(link), Some(links)) = (b.links(), &cx.links) {
+                if let Some(&a) = links.get(&link) {
                     if a != b_id {
                         conflicting_prev_active
                             .entry(a)
@@ -3,10 +3,11 @@ use crate::core::dependency::DepKind;
 use crate::core::{Dependency, PackageId, PackageIdSpec, Summary, Target};
 use crate::util::errors::CargoResult;
 use crate::util::interning::InternedString;
-use crate::util::Graph;
+use crate::util::{CanonicalUrl, Graph};
 use std::borrow::Borrow;
 use std::collections::{HashMap, HashSet};
 use std::fmt;
+use url::Url;
 
 /// Represents a fully-resolved package dependency graph. Each node in the graph
 /// is a package and edges represent dependencies between packages.
@@ -38,6 +39,10 @@ pub struct Resolve {
     /// registry every time it runs, and keeps the resolve in a locked state
     /// so it doesn't re-resolve the unused entries.
     unused_patches: Vec<PackageId>,
+    /// Packages from a `[patch]` entry, with the URL of the source they
+    /// patch. This isn't saved in `Cargo.lock`, so it is only known for a
+    /// resolve computed with the patches registered.
+    patched: HashMap<PackageId, CanonicalUrl>,
     /// A map from packages to a set of their public dependencies
     public_dependencies: HashMap<PackageId, HashSet<PackageId>>,
     /// Version of the `Cargo.lock` format, see
@@ -46,6 +51,36 @@ pub struct Resolve {
     summaries: HashMap<PackageId, Summary>,
 }
 
+/// Where a package of a [`Resolve`] comes from, when a `[patch]` or
+/// `[replace]` entry is used instead of the source the dependencies on it
+/// point to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Provenance {
+    /// From a `[patch]` entry for the source with this URL.
+    Patch(CanonicalUrl),
+    /// From a `[replace]` entry, replacing this package.
+    Replace(PackageId),
+}
+
+impl Provenance {
+    /// The URL of the source the package is used instead of.
+    pub fn source_url(&self) -> Url {
+        match self {
+            Provenance::Patch(url) => url.raw_canonicalized_url().clone(),
+            Provenance::Replace(original) => original.source_id().url().clone(),
+        }
+    }
+}
+
+impl fmt::Display for Provenance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Provenance::Patch(url) => write!(f, "patch for {}", url.raw_canonicalized_url()),
+            Provenance::Replace(original) => write!(f, "replaces {original}"),
+        }
+    }
+}
+
 /// A version to indicate how a `Cargo.lock` should be serialized.
 ///
 /// When creating a new lockfile, the version with `#[default]` is used.
@@ -123,6 +158,7 @@ impl Resolve {
             checksums,
             metadata,
             unused_patches,
+            patched: HashMap::new(),
             reverse_replacements,
             public_dependencies,
             version,
@@ -139,11 +175,17 @@ impl Resolve {
         self.graph.path_to_top(pkg)
     }
 
-    pub fn register_used_patches(&mut self, patches: &[Summary]) {
-        for summary in patches {
-            if !self.graph.contains(&summary.package_id()) {
-                self.unused_patches.push(summary.package_id())
-            };
+    /// Records which of the `[patch]` entries, by the URL of the source they
+    /// patch, are used by this resolve, and which aren't.
+    pub fn register_used_patches(&mut self, patches: &HashMap<CanonicalUrl, Vec<Summary>>) {
+        for (url, summaries) in patches {
+            for summary in summaries {
+                if self.graph.contains(&summary.package_id()) {
+                    self.patched.insert(summary.package_id(), url.clone());
+                } else {
+                    self.unused_patches.push(summary.package_id())
+                }
+            }
         }
     }
 
@@ -307,6 +349,17 @@ unable to verify that `{0}` is the same as when the lockfile was generated
         &self.unused_patches
     }
 
+    /// Whether `pkg` is from a `[patch]` or `[replace]` entry, and what it is
+    /// used instead of.
+    pub fn provenance(&self, pkg: PackageId) -> Option<Provenance> {
+        if let Some(url) = self.patched.get(&pkg) {
+            return Some(Provenance::Patch(url.clone()));
+        }
+        self.reverse_replacements
+            .get(&pkg)
+            .map(|&original| Provenance::Replace(original))
+    }
+
     pub fn checksums(&self) -> &HashMap<PackageId, Option<String>> {
         &self.checksums
     }
@@ -396,7 +449,7 @@ impl PartialEq for Resolve {
             checksums metadata unused_patches public_dependencies summaries
             |
             // fields to ignore
-            version
+            version patched
         }
     }
 }
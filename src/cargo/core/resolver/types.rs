@@ -55,6 +55,7 @@ impl ResolverProgress {
         // to amortize the cost of the current time lookup.
         self.ticks += 1;
         if let Some(config) = config {
+            config.cancellation().check()?;
             if config.shell().is_err_tty()
                 && !self.printed
                 && self.ticks % 1000 == 0
@@ -5,6 +5,8 @@ use std::io::IsTerminal;
 use termcolor::Color::{Cyan, Green, Red, Yellow};
 use termcolor::{self, Color, ColorSpec, StandardStream, WriteColor};
 
+use crate::core::PackageId;
+use crate::util::config::MessageCatalog;
 use crate::util::errors::CargoResult;
 
 pub enum TtyWidth {
@@ -56,6 +58,17 @@ pub struct Shell {
     /// Flag that indicates the current line needs to be cleared before
     /// printing. Used when a progress bar is currently displayed.
     needs_clear: bool,
+    /// Translations of the statuses and messages.
+    catalog: MessageCatalog,
+    /// Whether output must not move the cursor or erase what was printed,
+    /// for screen readers. Progress is then printed as lines of text.
+    plain_output: bool,
+    /// The width diagnostics and messages are wrapped to, from `term.width`
+    /// or `--output-width`, instead of the width of the terminal.
+    output_width: Option<usize>,
+    /// Whether file paths and packages are displayed as hyperlinks, from
+    /// `term.hyperlinks`.
+    hyperlinks: bool,
 }
 
 impl fmt::Debug for Shell {
@@ -65,10 +78,15 @@ impl fmt::Debug for Shell {
                 .debug_struct("Shell")
                 .field("verbosity", &self.verbosity)
                 .finish(),
-            ShellOut::Stream { color_choice, .. } => f
+            ShellOut::Stream {
+                stdout_color,
+                stderr_color,
+                ..
+            } => f
                 .debug_struct("Shell")
                 .field("verbosity", &self.verbosity)
-                .field("color_choice", &color_choice)
+                .field("stdout_color", &stdout_color)
+                .field("stderr_color", &stderr_color)
                 .finish(),
         }
     }
@@ -83,7 +101,10 @@ enum ShellOut {
         stdout: StandardStream,
         stderr: StandardStream,
         stderr_tty: bool,
-        color_choice: ColorChoice,
+        /// The color choice of stdout, which can differ from the one of
+        /// stderr to keep stdout clean for JSON.
+        stdout_color: ColorChoice,
+        stderr_color: ColorChoice,
     },
 }
 
@@ -107,11 +128,16 @@ impl Shell {
             output: ShellOut::Stream {
                 stdout: StandardStream::stdout(auto_clr.to_termcolor_color_choice(Stream::Stdout)),
                 stderr: StandardStream::stderr(auto_clr.to_termcolor_color_choice(Stream::Stderr)),
-                color_choice: ColorChoice::CargoAuto,
+                stdout_color: ColorChoice::CargoAuto,
+                stderr_color: ColorChoice::CargoAuto,
                 stderr_tty: std::io::stderr().is_terminal(),
             },
             verbosity: Verbosity::Verbose,
             needs_clear: false,
+            catalog: MessageCatalog::default(),
+            plain_output: false,
+            output_width: None,
+            hyperlinks: false,
         }
     }
 
@@ -121,6 +147,10 @@ impl Shell {
             output: ShellOut::Write(out),
             verbosity: Verbosity::Verbose,
             needs_clear: false,
+            catalog: MessageCatalog::default(),
+            plain_output: false,
+            output_width: None,
+            hyperlinks: false,
         }
     }
 
@@ -139,8 +169,14 @@ impl Shell {
                 if self.needs_clear {
                     self.err_erase_line();
                 }
-                self.output
-                    .message_stderr(status, message, color, justified)
+                let status = status.to_string();
+                self.output.message_stderr(
+                    &self.catalog.status(&status),
+                    message,
+                    color,
+                    justified,
+                    self.output_width,
+                )
             }
         }
     }
@@ -165,6 +201,14 @@ impl Shell {
         }
     }
 
+    /// Returns the width of diagnostics, which is relayed to rustc via
+    /// `--diagnostic-width`: the configured output width, or the width of
+    /// the terminal.
+    pub fn diagnostic_width(&self) -> Option<usize> {
+        self.output_width
+            .or_else(|| self.err_width().diagnostic_terminal_width())
+    }
+
     /// Returns `true` if stderr is a tty.
     pub fn is_err_tty(&self) -> bool {
         match self.output {
@@ -191,7 +235,7 @@ impl Shell {
 
     /// Erase from cursor to end of line.
     pub fn err_erase_line(&mut self) {
-        if self.err_supports_color() {
+        if self.err_supports_color() && !self.plain_output {
             imp::err_erase_line(self);
             self.needs_clear = false;
         }
@@ -254,8 +298,13 @@ impl Shell {
         if self.needs_clear {
             self.err_erase_line();
         }
-        self.output
-            .message_stderr(&"error", Some(&message), Red, false)
+        self.output.message_stderr(
+            &self.catalog.status("error"),
+            Some(&message),
+            Red,
+            false,
+            self.output_width,
+        )
     }
 
     /// Prints an amber 'warning' message.
@@ -271,6 +320,69 @@ impl Shell {
         self.print(&"note", Some(&message), Cyan, false)
     }
 
+    /// Sets the translations of the statuses and messages.
+    pub fn set_message_catalog(&mut self, catalog: MessageCatalog) {
+        self.catalog = catalog;
+    }
+
+    /// Gets the translations of the statuses and messages, see
+    /// [`message!`](crate::message).
+    pub fn message_catalog(&self) -> &MessageCatalog {
+        &self.catalog
+    }
+
+    /// Sets whether output must not move the cursor or erase lines.
+    pub fn set_plain_output(&mut self, plain_output: bool) {
+        self.plain_output = plain_output;
+    }
+
+    /// Gets whether output must not move the cursor or erase lines.
+    pub fn plain_output(&self) -> bool {
+        self.plain_output
+    }
+
+    /// Sets the width messages are wrapped to, instead of the width of the
+    /// terminal.
+    pub fn set_output_width(&mut self, width: Option<usize>) {
+        self.output_width = width;
+    }
+
+    /// Gets the width messages are wrapped to, if one is set.
+    pub fn output_width(&self) -> Option<usize> {
+        self.output_width
+    }
+
+    /// Sets whether file paths and packages are displayed as hyperlinks.
+    pub fn set_hyperlinks(&mut self, hyperlinks: bool) {
+        self.hyperlinks = hyperlinks;
+    }
+
+    /// Displays `text` as a hyperlink to `url`, if hyperlinks are enabled.
+    pub fn hyperlink<D: fmt::Display>(&self, url: impl fmt::Display, text: D) -> Hyperlink<D> {
+        Hyperlink {
+            url: self.hyperlinks.then(|| url.to_string()),
+            text,
+        }
+    }
+
+    /// Displays a package ID as a hyperlink to the directory of a local
+    /// package, or to the documentation of a crates.io package on docs.rs,
+    /// if hyperlinks are enabled.
+    pub fn package_hyperlink(&self, id: PackageId) -> Hyperlink<PackageId> {
+        let source_id = id.source_id();
+        let url = if source_id.is_path() {
+            Some(source_id.url().to_string())
+        } else if source_id.is_crates_io() {
+            Some(format!("https://docs.rs/{}/{}", id.name(), id.version()))
+        } else {
+            None
+        };
+        Hyperlink {
+            url: url.filter(|_| self.hyperlinks),
+            text: id,
+        }
+    }
+
     /// Updates the verbosity of the shell.
     pub fn set_verbosity(&mut self, verbosity: Verbosity) {
         self.verbosity = verbosity;
@@ -281,41 +393,45 @@ impl Shell {
         self.verbosity
     }
 
-    /// Updates the color choice (always, never, or auto) from a string..
+    /// Updates the color choice (always, never, or auto) of both stdout and
+    /// stderr from a string.
     pub fn set_color_choice(&mut self, color: Option<&str>) -> CargoResult<()> {
+        self.set_stream_color_choices(color, color)
+    }
+
+    /// Updates the color choices (always, never, or auto) of stdout and
+    /// stderr separately from strings. With "auto", each stream uses color
+    /// only if it is a terminal.
+    pub fn set_stream_color_choices(
+        &mut self,
+        stdout_color: Option<&str>,
+        stderr_color: Option<&str>,
+    ) -> CargoResult<()> {
         if let ShellOut::Stream {
             ref mut stdout,
             ref mut stderr,
-            ref mut color_choice,
+            stdout_color: ref mut stdout_choice,
+            stderr_color: ref mut stderr_choice,
             ..
         } = self.output
         {
-            let cfg = match color {
-                Some("always") => ColorChoice::Always,
-                Some("never") => ColorChoice::Never,
-
-                Some("auto") | None => ColorChoice::CargoAuto,
-
-                Some(arg) => anyhow::bail!(
-                    "argument for --color must be auto, always, or \
-                     never, but found `{}`",
-                    arg
-                ),
-            };
-            *color_choice = cfg;
-            *stdout = StandardStream::stdout(cfg.to_termcolor_color_choice(Stream::Stdout));
-            *stderr = StandardStream::stderr(cfg.to_termcolor_color_choice(Stream::Stderr));
+            *stdout_choice = ColorChoice::parse(stdout_color)?;
+            *stderr_choice = ColorChoice::parse(stderr_color)?;
+            *stdout =
+                StandardStream::stdout(stdout_choice.to_termcolor_color_choice(Stream::Stdout));
+            *stderr =
+                StandardStream::stderr(stderr_choice.to_termcolor_color_choice(Stream::Stderr));
         }
         Ok(())
     }
 
-    /// Gets the current color choice.
+    /// Gets the current color choice of stderr.
     ///
     /// If we are not using a color stream, this will always return `Never`, even if the color
     /// choice has been set to something else.
     pub fn color_choice(&self) -> ColorChoice {
         match self.output {
-            ShellOut::Stream { color_choice, .. } => color_choice,
+            ShellOut::Stream { stderr_color, .. } => stderr_color,
             ShellOut::Write(_) => ColorChoice::Never,
         }
     }
@@ -398,6 +514,24 @@ impl Shell {
     }
 }
 
+/// Text displayed as an [OSC 8] hyperlink, which terminals that support them
+/// make clickable, see [`Shell::hyperlink`].
+///
+/// [OSC 8]: https://gist.github.com/egmontkob/eb114294efbcd5adb1944c9f3cb5feda
+pub struct Hyperlink<D> {
+    url: Option<String>,
+    text: D,
+}
+
+impl<D: fmt::Display> fmt::Display for Hyperlink<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.url {
+            Some(url) => write!(f, "\x1b]8;;{url}\x1b\\{}\x1b]8;;\x1b\\", self.text),
+            None => self.text.fmt(f),
+        }
+    }
+}
+
 impl Default for Shell {
     fn default() -> Self {
         Self::new()
@@ -407,14 +541,25 @@ impl Default for Shell {
 impl ShellOut {
     /// Prints out a message with a status. The status comes first, and is bold plus the given
     /// color. The status can be justified, in which case the max width that will right align is
-    /// 12 chars.
+    /// 12 chars. With a `width`, the message of a status that isn't justified, like warnings,
+    /// is wrapped to fit in it.
     fn message_stderr(
         &mut self,
         status: &dyn fmt::Display,
         message: Option<&dyn fmt::Display>,
         color: Color,
         justified: bool,
+        width: Option<usize>,
     ) -> CargoResult<()> {
+        let wrapped;
+        let message = match (message, width) {
+            (Some(message), Some(width)) if !justified => {
+                let offset = status.to_string().chars().count() + 2;
+                wrapped = wrap_message(&message.to_string(), width, offset);
+                Some(&wrapped as &dyn fmt::Display)
+            }
+            (message, _) => message,
+        };
         match *self {
             ShellOut::Stream { ref mut stderr, .. } => {
                 stderr.reset()?;
@@ -496,7 +641,56 @@ impl ShellOut {
     }
 }
 
+/// Wraps the lines of `message` at spaces so they fit in `width` columns,
+/// when possible. The first line starts at column `offset`, after the status,
+/// and lines that are wrapped are continued with the leading whitespace of
+/// the line.
+fn wrap_message(message: &str, width: usize, offset: usize) -> String {
+    let mut wrapped = String::with_capacity(message.len());
+    for (i, line) in message.split('\n').enumerate() {
+        if i > 0 {
+            wrapped.push('\n');
+        }
+        let words = line.trim_start();
+        let leading = &line[..line.len() - words.len()];
+        wrapped.push_str(leading);
+        let mut column = if i == 0 { offset } else { 0 } + leading.chars().count();
+        for (j, word) in words.split(' ').enumerate() {
+            let word_width = word.chars().count();
+            if j > 0 {
+                if column + 1 + word_width > width {
+                    wrapped.push('\n');
+                    wrapped.push_str(leading);
+                    column = leading.chars().count();
+                } else {
+                    wrapped.push(' ');
+                    column += 1;
+                }
+            }
+            wrapped.push_str(word);
+            column += word_width;
+        }
+    }
+    wrapped
+}
+
 impl ColorChoice {
+    /// Parses a color choice, where `None` is "auto".
+    fn parse(color: Option<&str>) -> CargoResult<ColorChoice> {
+        match color {
+            Some("always") => Ok(ColorChoice::Always),
+            Some("never") => Ok(ColorChoice::Never),
+
+            Some("auto") | None => Ok(ColorChoice::CargoAuto),
+
+            Some(arg) => anyhow::bail!(
+                "argument for --color must be auto, always, or \
+                 never, but found `{}`",
+                arg
+            ),
+        }
+    }
+
     /// Converts our color choice to termcolor's version.
     fn to_termcolor_color_choice(self, stream: Stream) -> termcolor::ColorChoice {
         match self {
@@ -23,7 +23,7 @@ use crate::util::{CargoResult, Config};
 
 mod source_id;
 
-pub use self::source_id::{GitReference, SourceId};
+pub use self::source_id::{register_source_factory, GitReference, SourceFactory, SourceId};
 
 /// An abstraction of different sources of Cargo packages.
 ///
@@ -148,6 +148,14 @@ pub trait Source {
         Ok(())
     }
 
+    /// Returns whether the package is available locally, in which case
+    /// [`Source::download`] won't need to go to the network.
+    ///
+    /// The package cache lock must be held while calling this.
+    fn is_downloaded(&self, _pkg: PackageId) -> bool {
+        true
+    }
+
     /// Describes this source in a human readable fashion, used for display in
     /// resolver error messages currently.
     fn describe(&self) -> String;
@@ -166,6 +174,13 @@ pub trait Source {
     /// as yanked. This ignores the yanked whitelist.
     fn is_yanked(&mut self, _pkg: PackageId) -> Poll<CargoResult<bool>>;
 
+    /// Returns the prebuilt binaries of a package this source advertises,
+    /// for every target. Only registry sources can have them, see
+    /// [`PrebuiltBinary`].
+    fn prebuilt_binaries(&mut self, _pkg: PackageId) -> Poll<CargoResult<Vec<PrebuiltBinary>>> {
+        Poll::Ready(Ok(Vec::new()))
+    }
+
     /// Block until all outstanding [`Poll::Pending`] requests are [`Poll::Ready`].
     ///
     /// After calling this function, the source should return `Poll::Ready` for
@@ -176,6 +191,28 @@ pub trait Source {
     fn block_until_ready(&mut self) -> CargoResult<()>;
 }
 
+/// A binary of a package built ahead of time by its registry, which
+/// `cargo install --from-binary-registry` can install instead of building
+/// the package.
+///
+/// Registries advertise them with a `bin-dl` URL template in their
+/// `config.json`, and the SHA-256 checksums of the binaries of each target in
+/// the `bins` field of the index entries.
+#[derive(Clone, Debug)]
+pub struct PrebuiltBinary {
+    /// The target triple it was built for.
+    pub target: String,
+    /// The file name of the binary, with the executable suffix of `target`.
+    pub name: String,
+    /// Where to download it from.
+    pub url: String,
+    /// The SHA-256 checksum of the binary.
+    pub checksum: String,
+    /// The `Authorization` header to download it with, for registries
+    /// requiring authentication.
+    pub authorization: Option<String>,
+}
+
 /// Defines how a dependency query will be performed for a [`Source`].
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub enum QueryKind {
@@ -260,6 +297,10 @@ impl<'a, T: Source + ?Sized + 'a> Source for Box<T> {
         (**self).verify(pkg)
     }
 
+    fn is_downloaded(&self, pkg: PackageId) -> bool {
+        (**self).is_downloaded(pkg)
+    }
+
     fn describe(&self) -> String {
         (**self).describe()
     }
@@ -276,6 +317,10 @@ impl<'a, T: Source + ?Sized + 'a> Source for Box<T> {
         (**self).is_yanked(pkg)
     }
 
+    fn prebuilt_binaries(&mut self, pkg: PackageId) -> Poll<CargoResult<Vec<PrebuiltBinary>>> {
+        (**self).prebuilt_binaries(pkg)
+    }
+
     fn block_until_ready(&mut self) -> CargoResult<()> {
         (**self).block_until_ready()
     }
@@ -332,6 +377,10 @@ impl<'a, T: Source + ?Sized + 'a> Source for &'a mut T {
         (**self).verify(pkg)
     }
 
+    fn is_downloaded(&self, pkg: PackageId) -> bool {
+        (**self).is_downloaded(pkg)
+    }
+
     fn describe(&self) -> String {
         (**self).describe()
     }
@@ -348,6 +397,10 @@ impl<'a, T: Source + ?Sized + 'a> Source for &'a mut T {
         (**self).is_yanked(pkg)
     }
 
+    fn prebuilt_binaries(&mut self, pkg: PackageId) -> Poll<CargoResult<Vec<PrebuiltBinary>>> {
+        (**self).prebuilt_binaries(pkg)
+    }
+
     fn block_until_ready(&mut self) -> CargoResult<()> {
         (**self).block_until_ready()
     }
@@ -2,22 +2,92 @@ use crate::core::PackageId;
 use crate::sources::registry::CRATES_IO_HTTP_INDEX;
 use crate::sources::{DirectorySource, CRATES_IO_DOMAIN, CRATES_IO_INDEX, CRATES_IO_REGISTRY};
 use crate::sources::{GitSource, PathSource, RegistrySource};
+use crate::util::interning::InternedString;
 use crate::util::{config, CanonicalUrl, CargoResult, Config, IntoUrl};
-use log::trace;
 use serde::de;
 use serde::ser;
 use std::cmp::{self, Ordering};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{self, Formatter};
 use std::hash::{self, Hash};
 use std::path::{Path, PathBuf};
 use std::ptr;
-use std::sync::Mutex;
 use std::sync::OnceLock;
+use std::sync::{Arc, Mutex};
+use tracing::trace;
 use url::Url;
 
 static SOURCE_ID_CACHE: OnceLock<Mutex<HashSet<&'static SourceIdInner>>> = OnceLock::new();
 
+/// Factories of the source protocols registered with
+/// [`register_source_factory`], keyed by their scheme.
+static SOURCE_FACTORIES: OnceLock<Mutex<HashMap<String, Arc<dyn SourceFactory>>>> = OnceLock::new();
+
+/// Schemes of the source protocols built into Cargo, which can't be
+/// registered again.
+const BUILTIN_SCHEMES: &[&str] = &[
+    "git",
+    "path",
+    "registry",
+    "sparse",
+    "local-registry",
+    "directory",
+];
+
+/// Creates the [`Source`] of a source protocol that isn't built into Cargo.
+///
+/// Applications embedding Cargo can implement this to experiment with new
+/// ways of distributing packages, and register it for a scheme with
+/// [`register_source_factory`]. Source URLs like `<scheme>+<url>`, for example
+/// in `Cargo.lock`, then refer to [`SourceId`]s created by
+/// [`SourceId::for_custom`], and loading them calls [`SourceFactory::load`].
+///
+/// [`Source`]: super::Source
+pub trait SourceFactory: Send + Sync {
+    /// Creates the source of `source_id`.
+    ///
+    /// * `yanked_whitelist` --- Packages allowed to be used, even if they are yanked.
+    fn load<'a>(
+        &self,
+        source_id: SourceId,
+        config: &'a Config,
+        yanked_whitelist: &HashSet<PackageId>,
+    ) -> CargoResult<Box<dyn super::Source + 'a>>;
+}
+
+/// Registers `factory` to create the sources with URLs like `<scheme>+<url>`.
+///
+/// Fails if `scheme` is invalid, built into Cargo or already registered.
+pub fn register_source_factory(scheme: &str, factory: Box<dyn SourceFactory>) -> CargoResult<()> {
+    let valid = scheme.starts_with(|c: char| c.is_ascii_lowercase())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if !valid {
+        anyhow::bail!(
+            "invalid source protocol `{scheme}`, \
+             expected lowercase letters, digits and `-` starting with a letter"
+        );
+    }
+    if BUILTIN_SCHEMES.contains(&scheme) {
+        anyhow::bail!("source protocol `{scheme}` is built into Cargo and can't be registered");
+    }
+    let mut factories = SOURCE_FACTORIES
+        .get_or_init(|| Default::default())
+        .lock()
+        .unwrap();
+    if factories.contains_key(scheme) {
+        anyhow::bail!("source protocol `{scheme}` is already registered");
+    }
+    factories.insert(scheme.to_string(), Arc::from(factory));
+    Ok(())
+}
+
+/// Gets the factory registered for `scheme`, if any.
+fn source_factory(scheme: &str) -> Option<Arc<dyn SourceFactory>> {
+    SOURCE_FACTORIES.get()?.lock().unwrap().get(scheme).cloned()
+}
+
 /// Unique identifier for a source of packages.
 ///
 /// Cargo uniquely identifies packages using [`PackageId`], a combination of the
@@ -75,6 +145,9 @@ enum SourceKind {
     LocalRegistry,
     /// A directory-based registry.
     Directory,
+    /// A source protocol registered with [`register_source_factory`],
+    /// identified by its scheme.
+    Custom(InternedString),
 }
 
 /// Information to find a specific commit in a Git repository.
@@ -187,6 +260,10 @@ impl SourceId {
                 let url = url.into_url()?;
                 SourceId::new(SourceKind::Path, url, None)
             }
+            kind if source_factory(kind).is_some() => {
+                let url = url.into_url()?;
+                SourceId::for_custom(kind, &url)
+            }
             kind => Err(anyhow::format_err!("unsupported source protocol: {}", kind)),
         }
     }
@@ -248,6 +325,14 @@ impl SourceId {
         SourceId::new(SourceKind::Directory, url, None)
     }
 
+    /// Creates a `SourceId` of a source protocol registered with
+    /// [`register_source_factory`].
+    ///
+    /// Loading the source fails if no factory is registered for `scheme`.
+    pub fn for_custom(scheme: &str, url: &Url) -> CargoResult<SourceId> {
+        SourceId::new(SourceKind::Custom(scheme.into()), url.clone(), None)
+    }
+
     /// Returns the `SourceId` corresponding to the main repository.
     ///
     /// This is the main cargo registry by default, but it can be overridden in
@@ -389,6 +474,15 @@ impl SourceId {
         matches!(self.inner.kind, SourceKind::Git(_))
     }
 
+    /// Gets the scheme if this source is of a protocol registered with
+    /// [`register_source_factory`], otherwise `None`.
+    pub fn custom_scheme(self) -> Option<&'static str> {
+        match self.inner.kind {
+            SourceKind::Custom(scheme) => Some(scheme.as_str()),
+            _ => None,
+        }
+    }
+
     /// Creates an implementation of `Source` corresponding to this ID.
     ///
     /// * `yanked_whitelist` --- Packages allowed to be used, even if they are yanked.
@@ -429,6 +523,10 @@ impl SourceId {
                 };
                 Ok(Box::new(DirectorySource::new(&path, self, config)))
             }
+            SourceKind::Custom(scheme) => match source_factory(&scheme) {
+                Some(factory) => factory.load(self, config, yanked_whitelist),
+                None => anyhow::bail!("unsupported source protocol: {}", scheme),
+            },
         }
     }
 
@@ -594,6 +692,7 @@ impl fmt::Display for SourceId {
             }
             SourceKind::LocalRegistry => write!(f, "registry `{}`", url_display(&self.inner.url)),
             SourceKind::Directory => write!(f, "dir {}", url_display(&self.inner.url)),
+            SourceKind::Custom(scheme) => write!(f, "{} {}", scheme, url_display(&self.inner.url)),
         }
     }
 }
@@ -718,6 +817,10 @@ impl Ord for SourceKind {
             (SourceKind::Directory, _) => Ordering::Less,
             (_, SourceKind::Directory) => Ordering::Greater,
 
+            (SourceKind::Custom(a), SourceKind::Custom(b)) => a.cmp(b),
+            (SourceKind::Custom(_), _) => Ordering::Less,
+            (_, SourceKind::Custom(_)) => Ordering::Greater,
+
             (SourceKind::Git(a), SourceKind::Git(b)) => a.cmp(b),
         }
     }
@@ -777,6 +880,11 @@ impl<'a> fmt::Display for SourceIdAsUrl<'a> {
                 ref url,
                 ..
             } => write!(f, "directory+{}", url),
+            SourceIdInner {
+                kind: SourceKind::Custom(scheme),
+                ref url,
+                ..
+            } => write!(f, "{}+{}", scheme, url),
         }
     }
 }
@@ -931,6 +1039,53 @@ mod tests {
         assert_eq!(crate::util::hex::short_hash(&source_id), "6568fe2c2fab5bfe");
     }
 
+    #[test]
+    fn custom_source_protocol() {
+        use super::{register_source_factory, SourceFactory};
+        use crate::core::{PackageId, Source};
+        use crate::util::CargoResult;
+        use std::collections::HashSet;
+
+        struct Oci;
+
+        impl SourceFactory for Oci {
+            fn load<'a>(
+                &self,
+                _source_id: SourceId,
+                _config: &'a Config,
+                _yanked_whitelist: &HashSet<PackageId>,
+            ) -> CargoResult<Box<dyn Source + 'a>> {
+                anyhow::bail!("not implemented")
+            }
+        }
+
+        let err = SourceId::from_url("oci+https://ghcr.io/foo").unwrap_err();
+        assert_eq!(err.to_string(), "unsupported source protocol: oci");
+
+        register_source_factory("oci", Box::new(Oci)).unwrap();
+        let err = register_source_factory("oci", Box::new(Oci)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "source protocol `oci` is already registered"
+        );
+        let err = register_source_factory("sparse", Box::new(Oci)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "source protocol `sparse` is built into Cargo and can't be registered"
+        );
+
+        let source_id = SourceId::from_url("oci+https://ghcr.io/foo").unwrap();
+        assert_eq!(source_id.custom_scheme(), Some("oci"));
+        assert!(!source_id.is_registry());
+        assert_eq!(source_id.url().as_str(), "https://ghcr.io/foo");
+        assert_eq!(source_id.as_url().to_string(), "oci+https://ghcr.io/foo");
+        assert_eq!(source_id.to_string(), "oci https://ghcr.io/foo");
+
+        let url = "https://ghcr.io/foo".into_url().unwrap();
+        assert_eq!(SourceId::for_custom("oci", &url).unwrap(), source_id);
+        assert_ne!(SourceId::for_registry(&url).unwrap(), source_id);
+    }
+
     #[test]
     fn serde_roundtrip() {
         let url = "sparse+https://my-crates.io/".into_url().unwrap();
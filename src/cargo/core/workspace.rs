@@ -5,9 +5,9 @@ use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
 use anyhow::{anyhow, bail, Context as _};
-use glob::glob;
+use glob::{glob, Pattern};
 use itertools::Itertools;
-use log::debug;
+use tracing::debug;
 use url::Url;
 
 use crate::core::compiler::Unit;
@@ -22,8 +22,10 @@ use crate::sources::{PathSource, CRATES_IO_INDEX, CRATES_IO_REGISTRY};
 use crate::util::edit_distance;
 use crate::util::errors::{CargoResult, ManifestError};
 use crate::util::interning::InternedString;
-use crate::util::toml::{read_manifest, InheritableFields, TomlDependency, TomlProfiles};
-use crate::util::{config::ConfigRelativePath, Config, Filesystem, IntoUrl};
+use crate::util::toml::{
+    read_manifest, read_manifest_from_toml, InheritableFields, TomlDependency, TomlProfiles,
+};
+use crate::util::{config::ConfigRelativePath, profile, Config, Filesystem, IntoUrl};
 use cargo_util::paths;
 use cargo_util::paths::normalize_path;
 use pathdiff::diff_paths;
@@ -97,6 +99,22 @@ pub struct Workspace<'cfg> {
 
     /// Workspace-level custom metadata
     custom_metadata: Option<toml::Value>,
+
+    /// The binary `cargo run` runs in the root of the workspace, from
+    /// `workspace.default-run`.
+    default_run: Option<String>,
+
+    /// The test targets `cargo test` runs in the root of the workspace, from
+    /// `workspace.default-test`.
+    default_test: Option<Vec<String>>,
+
+    /// Root manifests of the workspaces whose members were included with
+    /// `workspace.members-from`.
+    nested_roots: Vec<PathBuf>,
+
+    /// Patches added with [`Workspace::add_patch`], which take precedence
+    /// over the ones from the configuration and manifests.
+    extra_patches: HashMap<Url, Vec<Dependency>>,
 }
 
 // Separate structure for tracking loaded packages (to avoid loading anything
@@ -172,6 +190,9 @@ pub struct WorkspaceRootConfig {
     members: Option<Vec<String>>,
     default_members: Option<Vec<String>>,
     exclude: Vec<String>,
+    members_from: Vec<String>,
+    default_run: Option<String>,
+    default_test: Option<Vec<String>>,
     inheritable_fields: InheritableFields,
     custom_metadata: Option<toml::Value>,
 }
@@ -184,6 +205,7 @@ impl<'cfg> Workspace<'cfg> {
     /// root and all member packages. It will then validate the workspace
     /// before returning it, so `Ok` is only returned for valid workspaces.
     pub fn new(manifest_path: &Path, config: &'cfg Config) -> CargoResult<Workspace<'cfg>> {
+        let _p = profile::start("loading workspace");
         let mut ws = Workspace::new_default(manifest_path.to_path_buf(), config);
         ws.target_dir = config.target_dir()?;
 
@@ -196,15 +218,23 @@ impl<'cfg> Workspace<'cfg> {
             ws.root_manifest = ws.find_root(manifest_path)?;
         }
 
-        ws.custom_metadata = ws
-            .load_workspace_config()?
-            .and_then(|cfg| cfg.custom_metadata);
-        ws.find_members()?;
-        ws.set_resolve_behavior();
-        ws.validate()?;
+        ws.load_members()?;
         Ok(ws)
     }
 
+    /// Loads the configuration of the workspace and its members once its
+    /// root is known, and validates them.
+    fn load_members(&mut self) -> CargoResult<()> {
+        if let Some(root_config) = self.load_workspace_config()? {
+            self.custom_metadata = root_config.custom_metadata;
+            self.default_run = root_config.default_run;
+            self.default_test = root_config.default_test;
+        }
+        self.find_members()?;
+        self.set_resolve_behavior();
+        self.validate()
+    }
+
     fn new_default(current_manifest: PathBuf, config: &'cfg Config) -> Workspace<'cfg> {
         Workspace {
             config,
@@ -224,6 +254,10 @@ impl<'cfg> Workspace<'cfg> {
             ignore_lock: false,
             resolve_behavior: ResolveBehavior::V1,
             custom_metadata: None,
+            default_run: None,
+            default_test: None,
+            nested_roots: Vec::new(),
+            extra_patches: HashMap::new(),
         }
     }
 
@@ -471,38 +505,35 @@ impl<'cfg> Workspace<'cfg> {
         };
 
         let from_config = self.config_patch()?;
-        if from_config.is_empty() {
+        if from_config.is_empty() && self.nested_roots.is_empty() && self.extra_patches.is_empty() {
             return Ok(from_manifest.clone());
         }
-        if from_manifest.is_empty() {
-            return Ok(from_config);
-        }
-
-        // We could just chain from_manifest and from_config,
-        // but that's not quite right as it won't deal with overlaps.
-        let mut combined = from_config;
-        for (url, deps_from_manifest) in from_manifest {
-            if let Some(deps_from_config) = combined.get_mut(url) {
-                // We want from_config to take precedence for each patched name.
-                // NOTE: This is inefficient if the number of patches is large!
-                let mut from_manifest_pruned = deps_from_manifest.clone();
-                for dep_from_config in &mut *deps_from_config {
-                    if let Some(i) = from_manifest_pruned.iter().position(|dep_from_manifest| {
-                        // XXX: should this also take into account version numbers?
-                        dep_from_config.name_in_toml() == dep_from_manifest.name_in_toml()
-                    }) {
-                        from_manifest_pruned.swap_remove(i);
-                    }
-                }
-                // Whatever is left does not exist in manifest dependencies.
-                deps_from_config.extend(from_manifest_pruned);
-            } else {
-                combined.insert(url.clone(), deps_from_manifest.clone());
-            }
+
+        // Extra patches take precedence over the config, which takes
+        // precedence over the root manifest, which takes precedence over
+        // nested workspaces, in the order they are listed.
+        let mut combined = self.extra_patches.clone();
+        merge_patches(&mut combined, &from_config);
+        merge_patches(&mut combined, from_manifest);
+        for nested_root in self.nested_roots.iter() {
+            let from_nested = match self.packages.get(nested_root) {
+                MaybePackage::Package(p) => p.manifest().patch(),
+                MaybePackage::Virtual(vm) => vm.patch(),
+            };
+            merge_patches(&mut combined, from_nested);
         }
         Ok(combined)
     }
 
+    /// Patches the source at `url` with `dep`, as if it was listed in the
+    /// `[patch]` table of the root manifest, but taking precedence over it.
+    ///
+    /// This is used by `cargo package` to verify packages against the
+    /// packaged form of other workspace members.
+    pub fn add_patch(&mut self, url: Url, dep: Dependency) {
+        self.extra_patches.entry(url).or_default().push(dep);
+    }
+
     /// Returns an iterator over all packages in this workspace
     pub fn members(&self) -> impl Iterator<Item = &Package> {
         let packages = &self.packages;
@@ -599,6 +630,25 @@ impl<'cfg> Workspace<'cfg> {
         self.custom_metadata.as_ref()
     }
 
+    /// The binary `cargo run` runs when invoked in the root of the workspace
+    /// without `-p`, if the selected packages have several.
+    pub fn default_run(&self) -> Option<&str> {
+        self.default_run.as_deref()
+    }
+
+    /// The test targets `cargo test` runs when invoked in the root of the
+    /// workspace without `-p` or target selection flags.
+    pub fn default_test(&self) -> Option<&[String]> {
+        self.default_test.as_deref()
+    }
+
+    /// Whether Cargo was invoked in the root of the workspace, where the
+    /// workspace-level defaults of [`Workspace::default_run`] and
+    /// [`Workspace::default_test`] apply.
+    pub fn is_invoked_in_root(&self) -> bool {
+        self.root_manifest() == self.current_manifest
+    }
+
     pub fn load_workspace_config(&mut self) -> CargoResult<Option<WorkspaceRootConfig>> {
         // If we didn't find a root, it must mean there is no [workspace] section, and thus no
         // metadata.
@@ -695,6 +745,15 @@ impl<'cfg> Workspace<'cfg> {
                 })?;
         }
 
+        for nested_root in workspace_config.members_from_paths() {
+            self.find_nested_members(&nested_root).with_context(|| {
+                format!(
+                    "failed to load members of the workspace `{}`",
+                    nested_root.display()
+                )
+            })?;
+        }
+
         self.find_path_deps(&root_manifest_path, &root_manifest_path, false)?;
 
         if let Some(default) = default_members_paths {
@@ -730,6 +789,37 @@ impl<'cfg> Workspace<'cfg> {
         Ok(())
     }
 
+    /// Adds the members of the workspace rooted at `nested_root`, which is
+    /// listed in `workspace.members-from`, as members of this workspace.
+    ///
+    /// The nested workspace is discovered like a regular one, including its
+    /// `exclude` list and path dependencies, but it may not nest other
+    /// workspaces itself.
+    fn find_nested_members(&mut self, nested_root: &Path) -> CargoResult<()> {
+        let nested_config = match self.packages.load(nested_root)?.workspace_config() {
+            WorkspaceConfig::Root(config) => config.clone(),
+            WorkspaceConfig::Member { .. } => {
+                bail!("`{}` is not the root of a workspace", nested_root.display())
+            }
+        };
+        if !nested_config.members_from.is_empty() {
+            bail!("nested workspaces cannot use `workspace.members-from` themselves");
+        }
+        self.nested_roots.push(nested_root.to_path_buf());
+
+        let members = nested_config.members.as_deref().unwrap_or_default();
+        for path in nested_config.members_paths(members)? {
+            self.find_path_deps(&path.join("Cargo.toml"), nested_root, false)
+                .with_context(|| {
+                    format!(
+                        "failed to load manifest for workspace member `{}`",
+                        path.display()
+                    )
+                })?;
+        }
+        self.find_path_deps(nested_root, nested_root, false)
+    }
+
     fn find_path_deps(
         &mut self,
         manifest_path: &Path,
@@ -794,6 +884,16 @@ impl<'cfg> Workspace<'cfg> {
         }
     }
 
+    /// Returns the entry of `workspace.members` that matched the member
+    /// `pkg`, or `None` if it became a member some other way, such as being
+    /// the root package or a path dependency.
+    pub fn matching_member_glob(&self, pkg: &Package) -> Option<&str> {
+        match self.root_maybe().workspace_config() {
+            WorkspaceConfig::Root(config) => config.matching_member_glob(pkg.root()),
+            WorkspaceConfig::Member { .. } => None,
+        }
+    }
+
     pub fn resolve_behavior(&self) -> ResolveBehavior {
         self.resolve_behavior
     }
@@ -859,7 +959,7 @@ impl<'cfg> Workspace<'cfg> {
             .iter()
             .filter(|&member| {
                 let config = self.packages.get(member).workspace_config();
-                matches!(config, WorkspaceConfig::Root(_))
+                matches!(config, WorkspaceConfig::Root(_)) && !self.nested_roots.contains(member)
             })
             .map(|member| member.parent().unwrap().to_path_buf())
             .collect();
@@ -892,6 +992,13 @@ impl<'cfg> Workspace<'cfg> {
             if root == self.root_manifest {
                 continue;
             }
+            // Members of nested workspaces belong to their own root.
+            if root
+                .as_ref()
+                .map_or(false, |r| self.nested_roots.contains(r))
+            {
+                continue;
+            }
 
             match root {
                 Some(root) => {
@@ -1001,7 +1108,13 @@ impl<'cfg> Workspace<'cfg> {
                 if !manifest.replace().is_empty() {
                     emit_warning("replace")?;
                 }
-                if !manifest.patch().is_empty() {
+                // Patches of nested workspaces are merged into the ones of the
+                // root, see `root_patch`.
+                if !manifest.patch().is_empty()
+                    && !self
+                        .nested_roots
+                        .contains(&pkg.manifest_path().to_path_buf())
+                {
                     emit_warning("patch")?;
                 }
                 if let Some(behavior) = manifest.resolve_behavior() {
@@ -1011,6 +1124,36 @@ impl<'cfg> Workspace<'cfg> {
                     }
                 }
             }
+            // Packages that are nested roots were checked above already.
+            for nested_root in self.nested_roots.iter() {
+                let MaybePackage::Virtual(vm) = self.packages.get(nested_root) else {
+                    continue;
+                };
+                let emit_warning = |what| -> CargoResult<()> {
+                    let msg = format!(
+                        "{} for the nested workspace will be ignored, \
+                         specify {} at the workspace root:\n\
+                         nested workspace: {}\n\
+                         workspace:        {}",
+                        what,
+                        what,
+                        nested_root.display(),
+                        root_manifest.display(),
+                    );
+                    self.config.shell().warn(&msg)
+                };
+                if vm.profiles().is_some() {
+                    emit_warning("profiles")?;
+                }
+                if !vm.replace().is_empty() {
+                    emit_warning("replace")?;
+                }
+                if let Some(behavior) = vm.resolve_behavior() {
+                    if behavior != self.resolve_behavior {
+                        emit_warning("resolver")?;
+                    }
+                }
+            }
             if let MaybePackage::Virtual(vm) = self.root_maybe() {
                 if vm.resolve_behavior().is_none() {
                     if let Some(edition) = self
@@ -1087,9 +1230,7 @@ impl<'cfg> Workspace<'cfg> {
             for warning in warnings {
                 if warning.is_critical {
                     let err = anyhow::format_err!("{}", warning.message);
-                    let cx =
-                        anyhow::format_err!("failed to parse manifest at `{}`", path.display());
-                    return Err(err.context(cx));
+                    return Err(crate::util::toml::parse_error(err, &path).into());
                 } else {
                     let msg = if self.root_manifest.is_none() {
                         warning.message.to_string()
@@ -1555,6 +1696,133 @@ impl<'cfg> Workspace<'cfg> {
     }
 }
 
+/// Builds a [`Workspace`] from manifests held in memory, instead of reading
+/// `Cargo.toml` files, for tools generating packages which then drive
+/// operations like [`ops::compile`] on them.
+///
+/// Each manifest is interpreted as if it was the `Cargo.toml` file of its
+/// directory, and the workspace is then discovered like with
+/// [`Workspace::new`]: `workspace.members` lists the members, path
+/// dependencies are followed, and `workspace = true` fields are inherited from
+/// the root. Manifests which weren't added are read from the filesystem as
+/// usual. The source files of the targets, as well as `Cargo.lock` and the
+/// target directory, are still on disk.
+///
+/// ```no_run
+/// # fn f(config: &cargo::Config) -> cargo::CargoResult<()> {
+/// use cargo::core::WorkspaceBuilder;
+///
+/// let root = std::path::Path::new("/path/to/generated");
+/// let ws = WorkspaceBuilder::new(root, config)
+///     .manifest("", toml::toml! { [workspace] members = ["api"] })
+///     .manifest(
+///         "api",
+///         toml::toml! {
+///             [package]
+///             name = "api"
+///             version = "0.1.0"
+///
+///             [lib]
+///             path = "/path/to/generated/api.rs"
+///         },
+///     )
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct WorkspaceBuilder<'cfg> {
+    config: &'cfg Config,
+    root_dir: PathBuf,
+    /// The manifests added, by the directory of the package.
+    manifests: BTreeMap<PathBuf, toml::Table>,
+    current_dir: Option<PathBuf>,
+    target_dir: Option<Filesystem>,
+}
+
+impl<'cfg> WorkspaceBuilder<'cfg> {
+    /// Creates a builder for the workspace whose root is `root_dir`, which
+    /// must be an absolute path.
+    pub fn new(root_dir: &Path, config: &'cfg Config) -> WorkspaceBuilder<'cfg> {
+        WorkspaceBuilder {
+            config,
+            root_dir: root_dir.to_path_buf(),
+            manifests: BTreeMap::new(),
+            current_dir: None,
+            target_dir: None,
+        }
+    }
+
+    /// Adds the manifest of the package, or the virtual manifest, in `dir`,
+    /// relative to the root of the workspace. Its own manifest must be added
+    /// with an empty `dir`.
+    pub fn manifest(mut self, dir: impl AsRef<Path>, manifest: toml::Table) -> Self {
+        let dir = paths::normalize_path(&self.root_dir.join(dir));
+        self.manifests.insert(dir, manifest);
+        self
+    }
+
+    /// Sets the package the workspace is used from, like the directory Cargo
+    /// is run in, relative to the root. Defaults to the root.
+    pub fn current(mut self, dir: impl AsRef<Path>) -> Self {
+        self.current_dir = Some(paths::normalize_path(&self.root_dir.join(dir)));
+        self
+    }
+
+    /// Sets the target directory, instead of the configured one, or `target`
+    /// in the root.
+    pub fn target_dir(mut self, target_dir: Filesystem) -> Self {
+        self.target_dir = Some(target_dir);
+        self
+    }
+
+    pub fn build(self) -> CargoResult<Workspace<'cfg>> {
+        if self.root_dir.is_relative() {
+            bail!(
+                "the root of a workspace must be an absolute path, found `{}`",
+                self.root_dir.display()
+            );
+        }
+        let root_manifest = self.root_dir.join("Cargo.toml");
+        let current_dir = self.current_dir.unwrap_or_else(|| self.root_dir.clone());
+        for dir in [&self.root_dir, &current_dir] {
+            if !self.manifests.contains_key(dir) {
+                bail!("no manifest was added for `{}`", dir.display());
+            }
+        }
+
+        let mut ws = Workspace::new_default(current_dir.join("Cargo.toml"), self.config);
+        ws.target_dir = match self.target_dir {
+            Some(target_dir) => Some(target_dir),
+            None => self.config.target_dir()?,
+        };
+        // The root goes first, as members inherit from its `[workspace]`.
+        let mut manifests = self.manifests;
+        let root = manifests.remove(&self.root_dir).unwrap();
+        for (dir, manifest) in std::iter::once((self.root_dir.clone(), root)).chain(manifests) {
+            let manifest_path = dir.join("Cargo.toml");
+            let source_id = SourceId::for_path(&dir)?;
+            let (manifest, _nested_paths) =
+                read_manifest_from_toml(manifest, &manifest_path, source_id, self.config)?;
+            let package = match manifest {
+                EitherManifest::Real(manifest) => {
+                    MaybePackage::Package(Package::new(manifest, &manifest_path))
+                }
+                EitherManifest::Virtual(vm) => MaybePackage::Virtual(vm),
+            };
+            ws.packages.packages.insert(dir, package);
+        }
+
+        // Unlike `Workspace::new`, this doesn't look for a root above the
+        // one given.
+        ws.root_manifest = match ws.packages.get(&root_manifest).workspace_config() {
+            WorkspaceConfig::Root(_) => Some(root_manifest),
+            WorkspaceConfig::Member { .. } => None,
+        };
+        ws.load_members()?;
+        Ok(ws)
+    }
+}
+
 impl<'cfg> Packages<'cfg> {
     fn get(&self, manifest_path: &Path) -> &MaybePackage {
         self.maybe_get(manifest_path).unwrap()
@@ -1615,6 +1883,9 @@ impl WorkspaceRootConfig {
         members: &Option<Vec<String>>,
         default_members: &Option<Vec<String>>,
         exclude: &Option<Vec<String>>,
+        members_from: &Option<Vec<String>>,
+        default_run: &Option<String>,
+        default_test: &Option<Vec<String>>,
         inheritable: &Option<InheritableFields>,
         custom_metadata: &Option<toml::Value>,
     ) -> WorkspaceRootConfig {
@@ -1623,6 +1894,9 @@ impl WorkspaceRootConfig {
             members: members.clone(),
             default_members: default_members.clone(),
             exclude: exclude.clone().unwrap_or_default(),
+            members_from: members_from.clone().unwrap_or_default(),
+            default_run: default_run.clone(),
+            default_test: default_test.clone(),
             inheritable_fields: inheritable.clone().unwrap_or_default(),
             custom_metadata: custom_metadata.clone(),
         }
@@ -1631,6 +1905,17 @@ impl WorkspaceRootConfig {
     ///
     /// This method does **not** consider the `members` list.
     fn is_excluded(&self, manifest_path: &Path) -> bool {
+        // Negative patterns in `members` win over everything else. Invalid
+        // patterns are reported by `members_paths`.
+        let negated = self
+            .negative_member_patterns()
+            .unwrap_or_default()
+            .iter()
+            .any(|pat| manifest_path.ancestors().any(|p| pat.matches_path(p)));
+        if negated {
+            return true;
+        }
+
         let excluded = self
             .exclude
             .iter()
@@ -1650,10 +1935,29 @@ impl WorkspaceRootConfig {
         self.members.is_some()
     }
 
+    /// Returns the root manifests of the workspaces listed in `members-from`.
+    fn members_from_paths(&self) -> Vec<PathBuf> {
+        self.members_from
+            .iter()
+            .map(|path| {
+                let path = paths::normalize_path(&self.root_dir.join(path));
+                if path.ends_with("Cargo.toml") {
+                    path
+                } else {
+                    path.join("Cargo.toml")
+                }
+            })
+            .collect()
+    }
+
     fn members_paths(&self, globs: &[String]) -> CargoResult<Vec<PathBuf>> {
         let mut expanded_list = Vec::new();
+        let negated = self.negative_member_patterns()?;
 
         for glob in globs {
+            if glob.starts_with('!') {
+                continue;
+            }
             let pathbuf = self.root_dir.join(glob);
             let expanded_paths = Self::expand_member_path(&pathbuf)?;
 
@@ -1668,7 +1972,9 @@ impl WorkspaceRootConfig {
                 // Check and filter out non-directory paths to prevent pushing such accidental unwanted path
                 // as a member.
                 for expanded_path in expanded_paths {
-                    if expanded_path.is_dir() {
+                    if expanded_path.is_dir()
+                        && !negated.iter().any(|pat| pat.matches_path(&expanded_path))
+                    {
                         expanded_list.push(expanded_path);
                     }
                 }
@@ -1678,6 +1984,36 @@ impl WorkspaceRootConfig {
         Ok(expanded_list)
     }
 
+    /// Compiles the negative patterns of `members`, e.g. `!crates/experimental-*`.
+    fn negative_member_patterns(&self) -> CargoResult<Vec<Pattern>> {
+        let members = self.members.as_deref().unwrap_or_default();
+        members
+            .iter()
+            .filter_map(|glob| glob.strip_prefix('!'))
+            .map(|glob| self.member_pattern(glob))
+            .collect()
+    }
+
+    fn member_pattern(&self, glob: &str) -> CargoResult<Pattern> {
+        let path = paths::normalize_path(&self.root_dir.join(glob));
+        let path = path.to_string_lossy();
+        Pattern::new(&path).with_context(|| format!("could not parse pattern `{}`", path))
+    }
+
+    /// Returns the entry of `members` that matched the member in `member_dir`,
+    /// if any.
+    fn matching_member_glob(&self, member_dir: &Path) -> Option<&str> {
+        let members = self.members.as_deref().unwrap_or_default();
+        members
+            .iter()
+            .filter(|glob| !glob.starts_with('!'))
+            .find(|glob| {
+                self.member_pattern(glob)
+                    .map_or(false, |pat| pat.matches_path(member_dir))
+            })
+            .map(|glob| glob.as_str())
+    }
+
     fn expand_member_path(path: &Path) -> CargoResult<Vec<PathBuf>> {
         let path = match path.to_str() {
             Some(p) => p,
@@ -1695,6 +2031,32 @@ impl WorkspaceRootConfig {
     }
 }
 
+/// Adds the patches of `lower` to `combined`, except for the patched names
+/// `combined` already has a patch for.
+fn merge_patches(
+    combined: &mut HashMap<Url, Vec<Dependency>>,
+    lower: &HashMap<Url, Vec<Dependency>>,
+) {
+    for (url, deps_from_lower) in lower {
+        if let Some(deps) = combined.get_mut(url) {
+            // NOTE: This is inefficient if the number of patches is large!
+            let mut from_lower_pruned = deps_from_lower.clone();
+            for dep in &mut *deps {
+                if let Some(i) = from_lower_pruned.iter().position(|dep_from_lower| {
+                    // XXX: should this also take into account version numbers?
+                    dep.name_in_toml() == dep_from_lower.name_in_toml()
+                }) {
+                    from_lower_pruned.swap_remove(i);
+                }
+            }
+            // Whatever is left does not exist in the higher precedence patches.
+            deps.extend(from_lower_pruned);
+        } else {
+            combined.insert(url.clone(), deps_from_lower.clone());
+        }
+    }
+}
+
 pub fn resolve_relative_path(
     label: &str,
     old_root: &Path,
@@ -1796,7 +2158,7 @@ fn find_root_iter<'a>(
             }
         })
         .map(|path| path.curr.join("Cargo.toml"))
-        .filter(|ances_manifest_path| ances_manifest_path.exists())
+        .filter(|ances_manifest_path| config.file_overlay().exists(ances_manifest_path))
 }
 
 struct LookBehindWindow<'a, T: ?Sized> {
@@ -146,10 +146,11 @@
 
 use crate::core::shell::Verbosity::Verbose;
 use crate::core::Shell;
+use crate::util::errors::LinkedError;
 use anyhow::Error;
-use log::debug;
+use tracing::debug;
 
-pub use crate::util::errors::{AlreadyPrintedError, InternalError, VerboseError};
+pub use crate::util::errors::{AlreadyPrintedError, CargoErrorKind, InternalError, VerboseError};
 pub use crate::util::{indented_lines, CargoResult, CliError, CliResult, Config};
 pub use crate::version::version;
 
@@ -223,15 +224,19 @@ fn _display_error(err: &Error, shell: &mut Shell, as_err: bool) -> bool {
         if err.is::<AlreadyPrintedError>() {
             break;
         }
+        let msg = match LinkedError::of(err) {
+            Some(linked) => linked.render(shell),
+            None => err.to_string(),
+        };
         if i == 0 {
             if as_err {
-                drop(shell.error(&err));
+                drop(shell.error(&msg));
             } else {
-                drop(writeln!(shell.err(), "{}", err));
+                drop(writeln!(shell.err(), "{}", msg));
             }
         } else {
             drop(writeln!(shell.err(), "\nCaused by:"));
-            drop(write!(shell.err(), "{}", indented_lines(&err.to_string())));
+            drop(write!(shell.err(), "{}", indented_lines(&msg)));
         }
     }
     false
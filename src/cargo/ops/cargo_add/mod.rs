@@ -26,6 +26,7 @@ use crate::core::Registry;
 use crate::core::Shell;
 use crate::core::Summary;
 use crate::core::Workspace;
+use crate::message;
 use crate::util::toml_mut::dependency::Dependency;
 use crate::util::toml_mut::dependency::GitSource;
 use crate::util::toml_mut::dependency::MaybeWorkspace;
@@ -217,7 +218,8 @@ pub fn add(workspace: &Workspace<'_>, options: &AddOptions<'_>) -> CargoResult<(
     }
 
     if options.dry_run {
-        options.config.shell().warn("aborting add due to dry run")?;
+        let msg = message!(options.config, "aborting add due to dry run");
+        options.config.shell().warn(msg)?;
     } else {
         manifest.write()?;
     }
@@ -291,10 +293,13 @@ fn resolve_dependency(
             let dependency = crate_spec.to_dependency()?.set_source(src);
             let selected = select_package(&dependency, config, registry)?;
             if dependency.name != selected.name {
-                config.shell().warn(format!(
-                    "translating `{}` to `{}`",
-                    dependency.name, selected.name,
-                ))?;
+                let msg = message!(
+                    config,
+                    "translating `{from}` to `{to}`",
+                    from = dependency.name,
+                    to = selected.name,
+                );
+                config.shell().warn(msg)?;
             }
             selected
         } else {
@@ -316,10 +321,13 @@ fn resolve_dependency(
             let dependency = crate_spec.to_dependency()?.set_source(src);
             let selected = select_package(&dependency, config, registry)?;
             if dependency.name != selected.name {
-                config.shell().warn(format!(
-                    "translating `{}` to `{}`",
-                    dependency.name, selected.name,
-                ))?;
+                let msg = message!(
+                    config,
+                    "translating `{from}` to `{to}`",
+                    from = dependency.name,
+                    to = selected.name,
+                );
+                config.shell().warn(msg)?;
             }
             selected
         } else {
@@ -384,10 +392,13 @@ fn resolve_dependency(
             )?;
 
             if dependency.name != latest.name {
-                config.shell().warn(format!(
-                    "translating `{}` to `{}`",
-                    dependency.name, latest.name,
-                ))?;
+                let msg = message!(
+                    config,
+                    "translating `{from}` to `{to}`",
+                    from = dependency.name,
+                    to = latest.name,
+                );
+                config.shell().warn(msg)?;
                 dependency.name = latest.name; // Normalize the name
             }
             dependency = dependency.set_source(latest.source.expect("latest always has a source"));
@@ -611,14 +622,17 @@ fn get_latest_dependency(
                         })?;
 
                     if latest_msrv.version() < latest.version() {
-                        config.shell().warn(format_args!(
+                        let msg = message!(
+                            config,
                             "ignoring `{dependency}@{latest_version}` (which has a rust-version of \
                              {latest_rust_version}) to satisfy this package's rust-version of \
                              {rust_version} (use `--ignore-rust-version` to override)",
+                            dependency = dependency,
                             latest_version = latest.version(),
                             latest_rust_version = latest.rust_version().unwrap(),
                             rust_version = spec.rust_version().unwrap(),
-                        ))?;
+                        );
+                        config.shell().warn(msg)?;
 
                         latest = latest_msrv;
                     }
@@ -937,17 +951,31 @@ fn print_action_msg(shell: &mut Shell, dep: &DependencyUI, section: &[String]) -
         }
         None => {}
     }
-    write!(message, " to")?;
-    if dep.optional().unwrap_or(false) {
-        write!(message, " optional")?;
-    }
     let section = if section.len() == 1 {
         section[0].clone()
     } else {
-        format!("{} for target `{}`", &section[2], &section[1])
+        message!(
+            shell = shell,
+            "{table} for target `{target}`",
+            table = section[2],
+            target = section[1],
+        )
+    };
+    let message = if dep.optional().unwrap_or(false) {
+        message!(
+            shell = shell,
+            "{dep} to optional {section}.",
+            dep = message,
+            section = section,
+        )
+    } else {
+        message!(
+            shell = shell,
+            "{dep} to {section}.",
+            dep = message,
+            section = section,
+        )
     };
-    write!(message, " {section}")?;
-    write!(message, ".")?;
     shell.status("Adding", message)
 }
 
@@ -0,0 +1,289 @@
+//! Core of `cargo version bump`.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+
+use cargo_util::paths;
+
+use crate::core::{Package, Workspace};
+use crate::message;
+use crate::util::toml_mut::manifest::LocalManifest;
+use crate::CargoResult;
+use crate::Config;
+
+/// The part of a version to increment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BumpLevel {
+    Major,
+    Minor,
+    Patch,
+}
+
+impl BumpLevel {
+    /// Increments `version`, resetting the parts after the incremented one
+    /// and dropping its pre-release and build metadata.
+    fn bump(self, version: &semver::Version) -> semver::Version {
+        match self {
+            BumpLevel::Major => semver::Version::new(version.major + 1, 0, 0),
+            BumpLevel::Minor => semver::Version::new(version.major, version.minor + 1, 0),
+            BumpLevel::Patch => {
+                semver::Version::new(version.major, version.minor, version.patch + 1)
+            }
+        }
+    }
+}
+
+/// Bump the version of packages of a workspace.
+#[derive(Debug)]
+pub struct BumpVersionOptions<'a> {
+    /// The packages to bump.
+    pub packages: Vec<&'a Package>,
+    /// Which part of the versions to increment.
+    pub level: BumpLevel,
+    /// Whether or not to actually write the manifests.
+    pub dry_run: bool,
+}
+
+/// A package getting a new version.
+struct Bumped {
+    root: PathBuf,
+    new_version: semver::Version,
+}
+
+/// Bumps the version of the selected packages, in their manifest or in
+/// `workspace.package` for the ones inheriting it, and updates the `version`
+/// requirements of the path dependencies of the workspace on them.
+///
+/// Bumping an inherited version bumps every member inheriting it.
+pub fn bump_version(ws: &Workspace<'_>, options: &BumpVersionOptions<'_>) -> CargoResult<()> {
+    let config = ws.config();
+    let root_manifest_path = ws.root_manifest().to_path_buf();
+    let mut manifests = BTreeMap::new();
+    manifests.insert(
+        root_manifest_path.clone(),
+        LocalManifest::try_new(&root_manifest_path)?,
+    );
+    for member in ws.members() {
+        let path = member.manifest_path().to_path_buf();
+        if !manifests.contains_key(&path) {
+            manifests.insert(path.clone(), LocalManifest::try_new(&path)?);
+        }
+    }
+
+    // The members with `version.workspace = true`.
+    let inheriting: HashSet<_> = ws
+        .members()
+        .filter(|pkg| {
+            manifests[pkg.manifest_path()]
+                .data
+                .get("package")
+                .and_then(|p| p.get("version"))
+                .and_then(|v| v.as_table_like())
+                .and_then(|v| v.get("workspace"))
+                .and_then(|w| w.as_bool())
+                == Some(true)
+        })
+        .map(|pkg| pkg.package_id())
+        .collect();
+
+    let mut bumped: BTreeMap<String, Bumped> = BTreeMap::new();
+    let mut bump_workspace = false;
+    for pkg in &options.packages {
+        if inheriting.contains(&pkg.package_id()) {
+            bump_workspace = true;
+            continue;
+        }
+        bump_package(config, &mut bumped, pkg, options.level)?;
+        let manifest = manifests.get_mut(pkg.manifest_path()).unwrap();
+        set_version(
+            &mut manifest.data["package"]["version"],
+            &bumped[pkg.name().as_str()].new_version.to_string(),
+        );
+    }
+    if bump_workspace {
+        let version = manifests[&root_manifest_path]
+            .data
+            .get("workspace")
+            .and_then(|w| w.get("package"))
+            .and_then(|p| p.get("version"))
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::format_err!("`workspace.package.version` is not set"))?;
+        let version = semver::Version::parse(version)?;
+        let new_version = options.level.bump(&version);
+        let msg = message!(
+            config,
+            "workspace from {old} to {new}",
+            old = version,
+            new = new_version,
+        );
+        config.shell().status("Bumping", msg)?;
+        let manifest = manifests.get_mut(&root_manifest_path).unwrap();
+        set_version(
+            &mut manifest.data["workspace"]["package"]["version"],
+            &new_version.to_string(),
+        );
+        for member in ws.members() {
+            if inheriting.contains(&member.package_id()) {
+                bump_package(config, &mut bumped, member, options.level)?;
+            }
+        }
+    }
+
+    for (path, manifest) in &mut manifests {
+        let dir = path.parent().unwrap();
+        let display_path = path.strip_prefix(ws.root()).unwrap_or(path);
+        let mut tables = Vec::new();
+        if path == &root_manifest_path {
+            tables.push(vec!["workspace".to_string(), "dependencies".to_string()]);
+        }
+        if manifest.data.contains_key("package") {
+            tables.extend(manifest.get_sections().into_iter().map(|(table, _)| {
+                table
+                    .to_table()
+                    .into_iter()
+                    .map(String::from)
+                    .collect::<Vec<_>>()
+            }));
+        }
+        for table in tables {
+            let Some(deps) = get_table_mut(&mut manifest.data, &table) else {
+                continue;
+            };
+            for (key, dep) in deps.iter_mut() {
+                let Some(dep) = dep.as_table_like_mut() else {
+                    continue;
+                };
+                let name = dep
+                    .get("package")
+                    .and_then(|p| p.as_str())
+                    .unwrap_or(key.get());
+                let Some(bumped) = bumped.get(name) else {
+                    continue;
+                };
+                let Some(dep_path) = dep.get("path").and_then(|p| p.as_str()) else {
+                    continue;
+                };
+                if paths::normalize_path(&dir.join(dep_path)) != bumped.root {
+                    continue;
+                }
+                let Some(req) = dep.get("version").and_then(|v| v.as_str()) else {
+                    continue;
+                };
+                match bump_req(req, &bumped.new_version) {
+                    Some(new_req) if new_req != req => {
+                        let msg = message!(
+                            config,
+                            "dependency `{dep}` in {manifest} from \"{old}\" to \"{new}\"",
+                            dep = key.get(),
+                            manifest = display_path.display(),
+                            old = req,
+                            new = new_req,
+                        );
+                        config.shell().status("Updating", msg)?;
+                        set_version(dep.get_mut("version").unwrap(), &new_req);
+                    }
+                    Some(_) => {}
+                    None => {
+                        let matches = semver::VersionReq::parse(req)
+                            .map_or(false, |req| req.matches(&bumped.new_version));
+                        if !matches {
+                            let msg = message!(
+                                config,
+                                "the requirement \"{req}\" of dependency `{dep}` in {manifest} \
+                                 doesn't match the new version {new}, update it manually",
+                                req = req,
+                                dep = key.get(),
+                                manifest = display_path.display(),
+                                new = bumped.new_version,
+                            );
+                            config.shell().warn(msg)?;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    if options.dry_run {
+        let msg = message!(config, "aborting bump due to dry run");
+        config.shell().warn(msg)?;
+        return Ok(());
+    }
+    for (path, manifest) in &manifests {
+        let contents = manifest.data.to_string();
+        if contents != paths::read(path)? {
+            paths::write(path, contents)?;
+        }
+    }
+    Ok(())
+}
+
+/// Records the new version of `pkg` in `bumped`.
+fn bump_package(
+    config: &Config,
+    bumped: &mut BTreeMap<String, Bumped>,
+    pkg: &Package,
+    level: BumpLevel,
+) -> CargoResult<()> {
+    let new_version = level.bump(pkg.version());
+    let msg = message!(
+        config,
+        "{name} from {old} to {new}",
+        name = pkg.name(),
+        old = pkg.version(),
+        new = new_version,
+    );
+    config.shell().status("Bumping", msg)?;
+    bumped.insert(
+        pkg.name().to_string(),
+        Bumped {
+            root: pkg.root().to_path_buf(),
+            new_version,
+        },
+    );
+    Ok(())
+}
+
+/// Replaces the string of `item`, keeping its formatting.
+fn set_version(item: &mut toml_edit::Item, version: &str) {
+    let Some(value) = item.as_value_mut() else {
+        return;
+    };
+    let decor = value.decor().clone();
+    *value = version.into();
+    *value.decor_mut() = decor;
+}
+
+/// Gets the dependency table at `path`, if it exists.
+fn get_table_mut<'a>(
+    doc: &'a mut toml_edit::Document,
+    path: &[String],
+) -> Option<&'a mut dyn toml_edit::TableLike> {
+    let mut item = doc.as_item_mut();
+    for segment in path {
+        item = item.get_mut(segment)?;
+    }
+    item.as_table_like_mut()
+}
+
+/// Updates a requirement made of a single comparator like "0.1", "=1.2.3"
+/// or "~1.2" to `version`, keeping its operator and precision. Returns `None`
+/// for other requirements.
+fn bump_req(req: &str, version: &semver::Version) -> Option<String> {
+    let req = req.trim();
+    let (op, rest) = ["=", "^", "~", ">="]
+        .iter()
+        .find_map(|op| Some((*op, req.strip_prefix(op)?)))
+        .unwrap_or(("", req));
+    let rest = rest.trim_start();
+    let parts: Vec<_> = rest.split('.').collect();
+    if parts.len() > 3 || parts.iter().any(|p| p.parse::<u64>().is_err()) {
+        return None;
+    }
+    let new = match parts.len() {
+        1 => format!("{}", version.major),
+        2 => format!("{}.{}", version.major, version.minor),
+        _ => version.to_string(),
+    };
+    Some(format!("{op}{new}"))
+}
@@ -0,0 +1,604 @@
+//! Implementation of `cargo bundle export` and `cargo bundle import`, see
+//! [`bundle_export`] and [`bundle_import`].
+//!
+//! A bundle is a tar archive with everything needed to build a workspace
+//! offline. It starts with a [`BundleManifest`] in `bundle.json`, followed by:
+//!
+//! * For each registry, `registry/<hash>/<name>-<version>.crate` for each of
+//!   its packages, and `registry/<hash>/index/<path>` with the lines of the
+//!   index describing them. This is the layout of a local registry.
+//! * For each git repository, `git/<hash>.bundle`, a git bundle with the
+//!   locked revisions.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context as _};
+use cargo_util::registry::make_dep_path;
+use cargo_util::{paths, Sha256};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::core::{GitReference, PackageId, SourceId, Workspace};
+use crate::message;
+use crate::ops;
+use crate::sources::{GitSource, RegistrySource, SourceConfigMap, CRATES_IO_REGISTRY};
+use crate::util::{hex, restricted_names, CargoResult, Config};
+
+/// Name of the manifest of a bundle, the first file of the archive.
+const MANIFEST: &str = "bundle.json";
+
+/// Version of the format of bundles.
+const BUNDLE_VERSION: u32 = 1;
+
+/// Describes the contents of a bundle.
+#[derive(Serialize, Deserialize)]
+struct BundleManifest {
+    version: u32,
+    registries: Vec<BundledRegistry>,
+    git: Vec<BundledGit>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BundledRegistry {
+    /// The source the packages are locked to, like `registry+<url>`.
+    source: String,
+    /// The directory of the registry in the bundle, under `registry/`.
+    dir: String,
+    packages: Vec<BundledPackage>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BundledPackage {
+    name: String,
+    version: String,
+    /// SHA-256 of the `.crate` file.
+    checksum: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BundledGit {
+    url: String,
+    /// The path of the git bundle in the bundle.
+    path: String,
+    revs: Vec<String>,
+}
+
+impl BundledRegistry {
+    fn crate_path(&self, pkg: &BundledPackage) -> String {
+        format!("registry/{}/{}-{}.crate", self.dir, pkg.name, pkg.version)
+    }
+
+    fn index_path(&self, name: &str) -> String {
+        format!(
+            "registry/{}/index/{}",
+            self.dir,
+            make_dep_path(&name.to_lowercase(), false)
+        )
+    }
+}
+
+/// Exports the packages of the lock file of `ws` from registries and git
+/// repositories, downloading them if needed, into a bundle at `output`.
+pub fn bundle_export(ws: &Workspace<'_>, output: &Path) -> CargoResult<()> {
+    let config = ws.config();
+    let (packages, resolve) = ops::resolve_ws(ws)?;
+    packages.get_many(resolve.iter())?;
+    let _lock = config.acquire_package_cache_lock()?;
+    let sources = SourceConfigMap::new(config)?;
+
+    let mut registries: BTreeMap<SourceId, Vec<PackageId>> = BTreeMap::new();
+    let mut repos: BTreeMap<Url, BTreeSet<String>> = BTreeMap::new();
+    for id in resolve.iter() {
+        let source_id = id.source_id();
+        if source_id.is_path() {
+            continue;
+        }
+        let replaced = sources.replacement(source_id)?.map(|(id, _)| id);
+        if source_id.is_remote_registry()
+            && replaced.map_or(true, |replaced| replaced.is_remote_registry())
+        {
+            registries.entry(source_id).or_default().push(id);
+        } else if source_id.is_git() && replaced.is_none() {
+            let rev = source_id.precise().expect("git dependencies are locked");
+            repos
+                .entry(source_id.url().clone())
+                .or_default()
+                .insert(rev.to_string());
+        } else {
+            let msg = message!(
+                config,
+                "skipping `{id}`, only packages from remote registries and git repositories are bundled",
+                id = id,
+            );
+            config.shell().warn(msg)?;
+        }
+    }
+
+    // The manifest comes first, so the checksums of all the packages are
+    // computed before the archive is written. The files are then streamed to
+    // it one at a time.
+    let mut manifest = BundleManifest {
+        version: BUNDLE_VERSION,
+        registries: Vec::new(),
+        git: Vec::new(),
+    };
+    let mut srcs = Vec::new();
+    let mut files = Vec::new();
+    for (source_id, ids) in registries {
+        let replaced = sources
+            .replacement(source_id)?
+            .map_or(source_id, |(id, _)| id);
+        let mut src = RegistrySource::remote(replaced, &HashSet::new(), config)?;
+        let mut registry = BundledRegistry {
+            source: source_id.as_url().to_string(),
+            dir: hex::short_hash(&source_id),
+            packages: Vec::new(),
+        };
+        let mut index_files: BTreeMap<String, Vec<u8>> = BTreeMap::new();
+        for id in ids {
+            let replaced_id = id.map_source(source_id, replaced);
+            let checksum = Sha256::new()
+                .update_file(&src.downloaded_crate(replaced_id)?)
+                .with_context(|| format!("failed to read the `.crate` file of `{}`", id))?
+                .finish_hex();
+            if let Some(Some(expected)) = resolve.checksums().get(&id) {
+                if *expected != checksum {
+                    bail!(
+                        "the cached `.crate` file of `{}` doesn't match its checksum in the lock file",
+                        id
+                    );
+                }
+            }
+            let pkg = BundledPackage {
+                name: id.name().to_string(),
+                version: id.version().to_string(),
+                checksum,
+            };
+            let index = index_files
+                .entry(registry.index_path(&pkg.name))
+                .or_default();
+            index.extend(src.index_line(replaced_id)?);
+            index.push(b'\n');
+            files.push(BundleFile::Crate {
+                path: registry.crate_path(&pkg),
+                src: srcs.len(),
+                id: replaced_id,
+            });
+            registry.packages.push(pkg);
+        }
+        files.extend(
+            index_files
+                .into_iter()
+                .map(|(path, data)| BundleFile::Data { path, data }),
+        );
+        srcs.push(src);
+        manifest.registries.push(registry);
+    }
+    for (url, revs) in repos {
+        let source_id = SourceId::for_git(&url, GitReference::DefaultBranch)?;
+        let db_path = GitSource::new(source_id, config)?.db_path();
+        let db_path = config.assert_package_cache_locked(&db_path).to_path_buf();
+        let path = format!("git/{}.bundle", hex::short_hash(&source_id));
+        manifest.git.push(BundledGit {
+            url: url.to_string(),
+            path: path.clone(),
+            revs: revs.iter().cloned().collect(),
+        });
+        files.push(BundleFile::Git {
+            path,
+            db_path,
+            revs,
+        });
+    }
+
+    let res = write_bundle(output, &manifest, &mut srcs, files);
+    if res.is_err() {
+        let _ = paths::remove_file(output);
+    }
+    res?;
+
+    let count: usize = manifest.registries.iter().map(|r| r.packages.len()).sum();
+    let msg = message!(
+        config,
+        "{count} packages and {git} git repositories to `{path}`",
+        count = count,
+        git = manifest.git.len(),
+        path = output.display(),
+    );
+    config.shell().status("Exported", msg)?;
+    Ok(())
+}
+
+/// A file of a bundle, besides its manifest.
+enum BundleFile {
+    /// The `.crate` file of `id`, read from the source at index `src`.
+    Crate {
+        path: String,
+        src: usize,
+        id: PackageId,
+    },
+    Data {
+        path: String,
+        data: Vec<u8>,
+    },
+    /// A git bundle of the revisions `revs` of the git database at `db_path`.
+    Git {
+        path: String,
+        db_path: PathBuf,
+        revs: BTreeSet<String>,
+    },
+}
+
+/// Writes the bundle with `manifest` and `files` to `output`.
+fn write_bundle(
+    output: &Path,
+    manifest: &BundleManifest,
+    srcs: &mut [RegistrySource<'_>],
+    files: Vec<BundleFile>,
+) -> CargoResult<()> {
+    let file = File::create(output)
+        .with_context(|| format!("failed to create bundle `{}`", output.display()))?;
+    let mut archive = tar::Builder::new(BufWriter::new(file));
+    let json = serde_json::to_vec_pretty(manifest)?;
+    append(&mut archive, MANIFEST, json.len() as u64, &json[..])?;
+    for file in files {
+        match file {
+            BundleFile::Crate { path, src, id } => {
+                let file = srcs[src].downloaded_crate(id)?;
+                let len = file.metadata()?.len();
+                append(&mut archive, &path, len, file)?;
+            }
+            BundleFile::Data { path, data } => {
+                append(&mut archive, &path, data.len() as u64, &data[..])?;
+            }
+            BundleFile::Git {
+                path,
+                db_path,
+                revs,
+            } => {
+                let bundle = git_bundle(&db_path, &revs)?;
+                append(&mut archive, &path, bundle.len() as u64, &bundle[..])?;
+            }
+        }
+    }
+    archive
+        .into_inner()?
+        .flush()
+        .with_context(|| format!("failed to write bundle `{}`", output.display()))?;
+    Ok(())
+}
+
+fn append(
+    archive: &mut tar::Builder<impl Write>,
+    path: &str,
+    size: u64,
+    data: impl Read,
+) -> CargoResult<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(size);
+    header.set_mode(0o644);
+    header.set_cksum();
+    archive
+        .append_data(&mut header, path, data)
+        .with_context(|| format!("failed to add `{}` to the bundle", path))
+}
+
+/// Creates a git bundle of the revisions `revs` of the git database at
+/// `db_path`, with a ref for each one.
+fn git_bundle(db_path: &Path, revs: &BTreeSet<String>) -> CargoResult<Vec<u8>> {
+    let repo = git2::Repository::open(db_path)
+        .with_context(|| format!("failed to open git database `{}`", db_path.display()))?;
+    let mut builder = repo.packbuilder()?;
+    let mut bundle = b"# v2 git bundle\n".to_vec();
+    for rev in revs {
+        builder.insert_commit(git2::Oid::from_str(rev)?)?;
+        writeln!(bundle, "{} refs/cargo/{}", rev, rev)?;
+    }
+    bundle.push(b'\n');
+    let mut pack = git2::Buf::new();
+    builder.write_buf(&mut pack)?;
+    bundle.extend_from_slice(&pack);
+    Ok(bundle)
+}
+
+/// Imports the bundle at `path` into `$CARGO_HOME`, after checking the
+/// checksums of its packages.
+///
+/// The packages from registries are added to local registries in
+/// `$CARGO_HOME/bundle`, which replace the original registries in the config
+/// file of `$CARGO_HOME`. The git repositories are added to the git databases
+/// of Cargo's git cache, where the locked revisions are found without
+/// fetching.
+pub fn bundle_import(config: &Config, path: &Path) -> CargoResult<()> {
+    let _lock = config.acquire_package_cache_lock()?;
+    let file =
+        File::open(path).with_context(|| format!("failed to open bundle `{}`", path.display()))?;
+    let mut archive = tar::Archive::new(file);
+    let mut entries = archive.entries()?;
+
+    let manifest: BundleManifest = match entries.next() {
+        Some(entry) => {
+            let entry = entry?;
+            if entry.path()?.to_str() != Some(MANIFEST) {
+                bail!(
+                    "`{}` isn't a bundle, it doesn't start with `{MANIFEST}`",
+                    path.display()
+                );
+            }
+            serde_json::from_reader(entry)
+                .with_context(|| format!("failed to parse `{MANIFEST}`"))?
+        }
+        None => bail!("`{}` isn't a bundle, it is empty", path.display()),
+    };
+    if manifest.version != BUNDLE_VERSION {
+        bail!(
+            "the bundle has version {} of the format, but only version {} is supported",
+            manifest.version,
+            BUNDLE_VERSION
+        );
+    }
+
+    // Only the files listed in the manifest are read, which also keeps them
+    // inside of the directory they are read to.
+    let mut expected = HashSet::new();
+    for registry in &manifest.registries {
+        let source_id = SourceId::from_url(&registry.source)?;
+        if registry.dir != hex::short_hash(&source_id) {
+            bail!(
+                "the bundle is corrupt, `{}` has the wrong directory",
+                registry.source
+            );
+        }
+        for pkg in &registry.packages {
+            validate_package(pkg)?;
+            expected.insert(registry.crate_path(pkg));
+            expected.insert(registry.index_path(&pkg.name));
+        }
+    }
+    for git in &manifest.git {
+        expected.insert(git.path.clone());
+    }
+    let mut contents = BTreeMap::new();
+    for entry in entries {
+        let mut entry = entry?;
+        let name = entry.path()?.to_string_lossy().into_owned();
+        if !expected.contains(&name) {
+            bail!(
+                "the bundle is corrupt, it has an unexpected file `{}`",
+                name
+            );
+        }
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data)?;
+        contents.insert(name, data);
+    }
+    if let Some(missing) = expected.iter().find(|path| !contents.contains_key(*path)) {
+        bail!("the bundle is corrupt, `{}` is missing", missing);
+    }
+
+    for registry in &manifest.registries {
+        for pkg in &registry.packages {
+            let data = &contents[&registry.crate_path(pkg)];
+            let actual = Sha256::new().update(data).finish_hex();
+            if actual != pkg.checksum {
+                bail!(
+                    "checksum of `{} v{}` in the bundle is {}, expected {}",
+                    pkg.name,
+                    pkg.version,
+                    actual,
+                    pkg.checksum
+                );
+            }
+            let index = String::from_utf8_lossy(&contents[&registry.index_path(&pkg.name)]);
+            let listed = index
+                .lines()
+                .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+                .any(|line| {
+                    line["vers"] == pkg.version.as_str() && line["cksum"] == actual.as_str()
+                });
+            if !listed {
+                bail!(
+                    "the index in the bundle doesn't list `{} v{}` with checksum {}",
+                    pkg.name,
+                    pkg.version,
+                    actual
+                );
+            }
+        }
+    }
+
+    let home = config.home().as_path_unlocked();
+    let mut replacements = Vec::new();
+    let mut count = 0;
+    for registry in &manifest.registries {
+        let dst = home.join("bundle/registry").join(&registry.dir);
+        paths::create_dir_all(&dst)?;
+        for pkg in &registry.packages {
+            let crate_path = registry.crate_path(pkg);
+            paths::write(
+                dst.join(format!("{}-{}.crate", pkg.name, pkg.version)),
+                &contents[&crate_path],
+            )?;
+            let index_path = registry.index_path(&pkg.name);
+            merge_index_file(
+                &dst.join("index")
+                    .join(make_dep_path(&pkg.name.to_lowercase(), false)),
+                &contents[&index_path],
+            )?;
+            count += 1;
+        }
+        replacements.push((
+            SourceId::from_url(&registry.source)?,
+            registry.dir.clone(),
+            dst,
+        ));
+    }
+    for git in &manifest.git {
+        let url = Url::parse(&git.url)
+            .with_context(|| format!("the bundle is corrupt, invalid git url `{}`", git.url))?;
+        let source_id = SourceId::for_git(&url, GitReference::DefaultBranch)?;
+        let db_path = GitSource::new(source_id, config)?.db_path();
+        let db_path = config.assert_package_cache_locked(&db_path);
+        import_git_bundle(db_path, &contents[&git.path], &git.revs)
+            .with_context(|| format!("failed to import the git repository `{}`", git.url))?;
+    }
+    if !replacements.is_empty() {
+        replace_registries(config, &replacements)?;
+    }
+
+    let msg = message!(
+        config,
+        "{count} packages and {git} git repositories from `{path}`",
+        count = count,
+        git = manifest.git.len(),
+        path = path.display(),
+    );
+    config.shell().status("Imported", msg)?;
+    Ok(())
+}
+
+/// Checks the name and version of `pkg`, which are used in the paths the
+/// bundle is imported to, so that they stay inside of its directory.
+fn validate_package(pkg: &BundledPackage) -> CargoResult<()> {
+    restricted_names::validate_package_name(&pkg.name, "package name", "")
+        .with_context(|| format!("the bundle is corrupt, invalid package `{}`", pkg.name))?;
+    semver::Version::parse(&pkg.version).with_context(|| {
+        format!(
+            "the bundle is corrupt, invalid version `{}` of `{}`",
+            pkg.version, pkg.name
+        )
+    })?;
+    if [&pkg.name, &pkg.version]
+        .iter()
+        .any(|s| s.contains(['/', '\\']) || s.contains(".."))
+    {
+        bail!(
+            "the bundle is corrupt, invalid package `{} v{}`",
+            pkg.name,
+            pkg.version
+        );
+    }
+    Ok(())
+}
+
+/// Adds the lines of `index` to the index file at `path` of a local registry,
+/// except the versions it already has.
+fn merge_index_file(path: &Path, index: &[u8]) -> CargoResult<()> {
+    let existing = match paths::read(path) {
+        Ok(existing) => existing,
+        Err(_) if !path.exists() => String::new(),
+        Err(e) => return Err(e),
+    };
+    let version = |line: &str| {
+        serde_json::from_str::<serde_json::Value>(line)
+            .ok()
+            .and_then(|line| line["vers"].as_str().map(str::to_string))
+    };
+    let known: HashSet<_> = existing.lines().filter_map(version).collect();
+    let mut merged = existing.clone();
+    for line in String::from_utf8_lossy(index).lines() {
+        if version(line).map_or(false, |v| !known.contains(&v)) {
+            merged.push_str(line);
+            merged.push('\n');
+        }
+    }
+    if merged != existing {
+        paths::create_dir_all(path.parent().unwrap())?;
+        paths::write(path, merged)?;
+    }
+    Ok(())
+}
+
+/// Adds the objects of the git bundle `bundle` to the git database at
+/// `db_path`, creating it if needed, and checks that it has `revs` then.
+fn import_git_bundle(db_path: &Path, bundle: &[u8], revs: &[String]) -> CargoResult<()> {
+    const HEADER: &[u8] = b"# v2 git bundle\n";
+    let Some(rest) = bundle.strip_prefix(HEADER) else {
+        bail!("the git bundle is corrupt, it has no header");
+    };
+    // The refs of the header end with an empty line, the pack follows.
+    let Some(end) = rest.windows(2).position(|w| w == b"\n\n") else {
+        bail!("the git bundle is corrupt, its refs don't end");
+    };
+    let pack = &rest[end + 2..];
+
+    let repo = match git2::Repository::open_bare(db_path) {
+        Ok(repo) => repo,
+        Err(_) => {
+            paths::create_dir_all(db_path)?;
+            git2::Repository::init_bare(db_path)?
+        }
+    };
+    let odb = repo.odb()?;
+    let mut writer = odb.packwriter()?;
+    writer.write_all(pack)?;
+    writer.commit()?;
+    for rev in revs {
+        let oid = git2::Oid::from_str(rev)?;
+        if repo.find_commit(oid).is_err() {
+            bail!("the git bundle doesn't have the revision {}", rev);
+        }
+    }
+    Ok(())
+}
+
+/// Replaces the registries of `replacements` with the local registries
+/// imported for them, in the config file of `$CARGO_HOME`.
+fn replace_registries(
+    config: &Config,
+    replacements: &[(SourceId, String, PathBuf)],
+) -> CargoResult<()> {
+    let path = config.home_config_path();
+    let contents = if path.exists() {
+        paths::read(&path)?
+    } else {
+        String::new()
+    };
+    let mut doc: toml_edit::Document = contents
+        .parse()
+        .with_context(|| format!("failed to parse `{}`", path.display()))?;
+    let sources = doc
+        .entry("source")
+        .or_insert_with(|| {
+            let mut table = toml_edit::Table::new();
+            table.set_implicit(true);
+            toml_edit::Item::Table(table)
+        })
+        .as_table_mut()
+        .with_context(|| format!("`source` in `{}` isn't a table", path.display()))?;
+    for (source_id, dir, dst) in replacements {
+        let bundle_name = format!("bundle-{}", dir);
+        let name = if source_id.is_crates_io() {
+            CRATES_IO_REGISTRY.to_string()
+        } else {
+            source_id.as_url().to_string()
+        };
+        let source = sources
+            .entry(&name)
+            .or_insert_with(|| toml_edit::Item::Table(toml_edit::Table::new()))
+            .as_table_mut()
+            .with_context(|| format!("`source.{}` in `{}` isn't a table", name, path.display()))?;
+        if let Some(existing) = source.get("replace-with").and_then(|v| v.as_str()) {
+            if existing != bundle_name {
+                bail!(
+                    "`{}` is already replaced with `{}` in `{}`",
+                    name,
+                    existing,
+                    path.display()
+                );
+            }
+        }
+        if !source_id.is_crates_io() {
+            source["registry"] = toml_edit::value(source_id.url().as_str());
+        }
+        source["replace-with"] = toml_edit::value(bundle_name.as_str());
+        let mut local = toml_edit::Table::new();
+        local["local-registry"] = toml_edit::value(dst.to_string_lossy().replace('\\', "/"));
+        sources.insert(&bundle_name, toml_edit::Item::Table(local));
+    }
+    paths::write(&path, doc.to_string())?;
+    Ok(())
+}
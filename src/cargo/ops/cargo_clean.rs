@@ -1,6 +1,7 @@
 use crate::core::compiler::{CompileKind, CompileMode, Layout, RustcTargetData};
 use crate::core::profiles::Profiles;
 use crate::core::{PackageIdSpec, TargetKind, Workspace};
+use crate::message;
 use crate::ops;
 use crate::util::edit_distance;
 use crate::util::errors::CargoResult;
@@ -24,6 +25,8 @@ pub struct CleanOptions<'a> {
     pub requested_profile: InternedString,
     /// Whether to just clean the doc directory
     pub doc: bool,
+    /// Whether to just clean the incremental directories of the profile
+    pub incremental_only: bool,
 }
 
 /// Cleans the package's build artifacts.
@@ -39,6 +42,12 @@ pub fn clean(ws: &Workspace<'_>, opts: &CleanOptions<'_>) -> CargoResult<()> {
 
     let profiles = Profiles::new(ws, opts.requested_profile)?;
 
+    // If the incremental-only option is set, we just want to delete the
+    // incremental directories, which may be outside of the target directory.
+    if opts.incremental_only {
+        return clean_incremental(ws, opts, &profiles);
+    }
+
     if opts.profile_specified {
         // After parsing profiles we know the dir-name of the profile, if a profile
         // was passed from the command line. If so, delete only the directory of
@@ -53,6 +62,17 @@ pub fn clean(ws: &Workspace<'_>, opts: &CleanOptions<'_>) -> CargoResult<()> {
     // Note that we don't bother grabbing a lock here as we're just going to
     // blow it all away anyway.
     if opts.spec.is_empty() {
+        // The incremental data relocated with `-Zincremental-dir` isn't in
+        // the target directory, remove it as well.
+        if opts.profile_specified {
+            if let Some(incremental_dir) = profiles.incremental_dir() {
+                clean_entire_folder(&incremental_dir.join(profiles.get_dir_name()), config)?;
+            }
+        } else {
+            for incremental_dir in profiles.all_incremental_dirs() {
+                clean_entire_folder(incremental_dir, config)?;
+            }
+        }
         return clean_entire_folder(&target_dir.into_path_unlocked(), config);
     }
 
@@ -61,15 +81,18 @@ pub fn clean(ws: &Workspace<'_>, opts: &CleanOptions<'_>) -> CargoResult<()> {
     let target_data = RustcTargetData::new(ws, &requested_kinds)?;
     let (pkg_set, resolve) = ops::resolve_ws(ws)?;
     let prof_dir_name = profiles.get_dir_name();
-    let host_layout = Layout::new(ws, None, &prof_dir_name)?;
+    let incremental_dir = profiles.incremental_dir();
+    let host_layout = Layout::new(ws, None, &prof_dir_name, incremental_dir)?;
     // Convert requested kinds to a Vec of layouts.
     let target_layouts: Vec<(CompileKind, Layout)> = requested_kinds
         .into_iter()
         .filter_map(|kind| match kind {
-            CompileKind::Target(target) => match Layout::new(ws, Some(target), &prof_dir_name) {
-                Ok(layout) => Some(Ok((kind, layout))),
-                Err(e) => Some(Err(e)),
-            },
+            CompileKind::Target(target) => {
+                match Layout::new(ws, Some(target), &prof_dir_name, incremental_dir) {
+                    Ok(layout) => Some(Ok((kind, layout))),
+                    Err(e) => Some(Err(e)),
+                }
+            }
             CompileKind::Host => None,
         })
         .collect::<CargoResult<_>>()?;
@@ -100,20 +123,24 @@ pub fn clean(ws: &Workspace<'_>, opts: &CleanOptions<'_>) -> CargoResult<()> {
         // Translate the spec to a Package.
         let spec = PackageIdSpec::parse(spec_str)?;
         if spec.version().is_some() {
-            config.shell().warn(&format!(
-                "version qualifier in `-p {}` is ignored, \
-                cleaning all versions of `{}` found",
-                spec_str,
-                spec.name()
-            ))?;
+            let msg = message!(
+                config,
+                "version qualifier in `-p {spec}` is ignored, \
+                 cleaning all versions of `{name}` found",
+                spec = spec_str,
+                name = spec.name(),
+            );
+            config.shell().warn(msg)?;
         }
         if spec.url().is_some() {
-            config.shell().warn(&format!(
-                "url qualifier in `-p {}` ignored, \
-                cleaning all versions of `{}` found",
-                spec_str,
-                spec.name()
-            ))?;
+            let msg = message!(
+                config,
+                "url qualifier in `-p {spec}` ignored, \
+                 cleaning all versions of `{name}` found",
+                spec = spec_str,
+                name = spec.name(),
+            );
+            config.shell().warn(msg)?;
         }
         let matches: Vec<_> = resolve.iter().filter(|id| spec.matches(*id)).collect();
         if matches.is_empty() {
@@ -227,6 +254,30 @@ pub fn clean(ws: &Workspace<'_>, opts: &CleanOptions<'_>) -> CargoResult<()> {
     Ok(())
 }
 
+/// Removes the incremental compilation data of the profile, for the host and
+/// the requested targets. The build artifacts stay fresh, rustc only has to
+/// compile the crates from scratch the next time they are rebuilt.
+fn clean_incremental(
+    ws: &Workspace<'_>,
+    opts: &CleanOptions<'_>,
+    profiles: &Profiles,
+) -> CargoResult<()> {
+    let config = ws.config();
+    let dir_name = profiles.get_dir_name();
+    let incremental_dir = profiles.incremental_dir();
+    let mut targets = vec![None];
+    for kind in CompileKind::from_requested_targets(config, &opts.targets)? {
+        if let CompileKind::Target(target) = kind {
+            targets.push(Some(target));
+        }
+    }
+    for target in targets {
+        let layout = Layout::new(ws, target, &dir_name, incremental_dir)?;
+        clean_entire_folder(layout.incremental(), config)?;
+    }
+    Ok(())
+}
+
 fn escape_glob_path(pattern: &Path) -> CargoResult<String> {
     let pattern = pattern
         .to_str()
@@ -0,0 +1,45 @@
+//! Explains the choices of `profile.<name>.auto-tune`, for
+//! `cargo report auto-tune`.
+
+use crate::core::compiler::UnitInterner;
+use crate::core::Workspace;
+use crate::drop_println;
+use crate::ops::{create_bcx, CompileOptions};
+use crate::util::CargoResult;
+
+/// Prints the `opt-level` `auto-tune` chose for each package built by
+/// `options`, and why.
+///
+/// Nothing is built, the choices rely on the compile times recorded by the
+/// previous builds.
+pub fn report_auto_tune(ws: &Workspace<'_>, options: &CompileOptions) -> CargoResult<()> {
+    let config = ws.config();
+    let interner = UnitInterner::new();
+    let bcx = create_bcx(ws, options, &interner)?;
+    let profile = options.build_config.requested_profile;
+    let Some(decisions) = bcx.profiles.auto_tune_decisions() else {
+        anyhow::bail!(
+            "profile `{profile}` doesn't set `auto-tune = true`, \
+             or `-Zprofile-auto-tune` wasn't passed"
+        );
+    };
+
+    let mut built: Vec<_> = bcx
+        .unit_graph
+        .keys()
+        .map(|unit| unit.pkg.package_id())
+        .collect();
+    built.sort();
+    built.dedup();
+    for pkg_id in built {
+        let Some(decision) = decisions.get(&pkg_id) else {
+            continue;
+        };
+        let setting = match decision.opt_level {
+            Some(opt_level) => format!("opt-level {opt_level}"),
+            None => format!("`{profile}` profile"),
+        };
+        drop_println!(config, "{pkg_id}: {setting} ({})", decision.reason);
+    }
+    Ok(())
+}
@@ -0,0 +1,69 @@
+//! Compiles packages with every combination of their features, for
+//! `cargo check --all-features-matrix`.
+
+use std::rc::Rc;
+
+use anyhow::{bail, Context as _};
+
+use crate::core::resolver::features::{feature_matrix, CliFeatures};
+use crate::core::{FeatureValue, Workspace};
+use crate::message;
+use crate::ops::{self, CompileOptions, Packages};
+use crate::util::CargoResult;
+
+/// Compiles each package selected by `options` once per combination of its
+/// features, see [`feature_matrix`], with `--no-default-features`.
+/// `max_depth` limits the number of features of a combination.
+///
+/// A failed combination doesn't stop the others from being compiled, they
+/// are all listed in the error at the end.
+pub fn compile_feature_matrix(
+    ws: &Workspace<'_>,
+    options: &CompileOptions,
+    max_depth: Option<usize>,
+) -> CargoResult<()> {
+    let config = ws.config();
+    let mut failed = Vec::new();
+    let mut total = 0;
+    for pkg in options.spec.get_packages(ws)? {
+        let matrix = feature_matrix(pkg.summary().features(), max_depth)
+            .with_context(|| format!("failed to compute the feature matrix of `{}`", pkg.name()))?;
+        for (i, features) in matrix.iter().enumerate() {
+            let list: Vec<_> = features.iter().map(|f| f.as_str()).collect();
+            let list = list.join(",");
+            let msg = message!(
+                config,
+                "`{name}` with [{list}] ({i}/{total})",
+                name = pkg.name(),
+                list = list,
+                i = i + 1,
+                total = matrix.len(),
+            );
+            config.shell().status("Features", msg)?;
+            let mut options = options.clone();
+            options.spec = Packages::Packages(vec![pkg.name().to_string()]);
+            options.cli_features = CliFeatures {
+                features: Rc::new(features.iter().map(|f| FeatureValue::Feature(*f)).collect()),
+                all_features: false,
+                uses_default_features: false,
+            };
+            total += 1;
+            if let Err(e) = ops::compile(ws, &options) {
+                crate::display_error(&e, &mut config.shell());
+                let mut flags = format!("-p {} --no-default-features", pkg.name());
+                if !list.is_empty() {
+                    flags.push_str(&format!(" --features {list}"));
+                }
+                failed.push(flags);
+            }
+        }
+    }
+    if !failed.is_empty() {
+        bail!(
+            "{} of {total} feature combinations failed, rerun them with:\n  {}",
+            failed.len(),
+            failed.join("\n  ")
+        );
+    }
+    Ok(())
+}
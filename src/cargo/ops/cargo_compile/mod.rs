@@ -35,10 +35,13 @@
 //! [`drain_the_queue`]: crate::core::compiler::job_queue
 //! ["Cargo Target"]: https://doc.rust-lang.org/nightly/cargo/reference/cargo-targets.html
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
 use std::sync::Arc;
 
+use anyhow::Context as _;
+
 use crate::core::compiler::unit_dependencies::build_unit_dependencies;
 use crate::core::compiler::unit_graph::{self, UnitDep, UnitGraph};
 use crate::core::compiler::{standard_lib, CrateType, TargetInfo};
@@ -48,11 +51,12 @@ use crate::core::compiler::{DefaultExecutor, Executor, UnitInterner};
 use crate::core::profiles::Profiles;
 use crate::core::resolver::features::{self, CliFeatures, FeaturesFor};
 use crate::core::resolver::{HasDevUnits, Resolve};
-use crate::core::{PackageId, PackageSet, SourceId, TargetKind, Workspace};
+use crate::core::{PackageId, PackageIdSpec, PackageSet, SourceId, TargetKind, Workspace};
 use crate::drop_println;
+use crate::message;
 use crate::ops;
 use crate::ops::resolve::WorkspaceResolve;
-use crate::util::config::Config;
+use crate::util::config::{Config, TargetOverrideConfig};
 use crate::util::interning::InternedString;
 use crate::util::{profile, CargoResult, StableHasher};
 
@@ -62,6 +66,13 @@ pub use compile_filter::{CompileFilter, FilterRule, LibRule};
 mod unit_generator;
 use unit_generator::UnitGenerator;
 
+mod feature_matrix;
+pub use feature_matrix::compile_feature_matrix;
+mod auto_tune;
+pub use auto_tune::report_auto_tune;
+mod native_deps;
+pub use native_deps::report_native_deps;
+
 mod packages;
 
 pub use packages::Packages;
@@ -142,6 +153,7 @@ pub fn compile_with_exec<'a>(
 }
 
 /// Like [`compile_with_exec`] but without warnings from manifest parsing.
+#[tracing::instrument(skip_all)]
 pub fn compile_ws<'a>(
     ws: &Workspace<'a>,
     options: &CompileOptions,
@@ -153,9 +165,105 @@ pub fn compile_ws<'a>(
         unit_graph::emit_serialized_unit_graph(&bcx.roots, &bcx.unit_graph, ws.config())?;
         return Compilation::new(&bcx);
     }
-    let _p = profile::start("compiling");
+    if options.build_config.print_flags {
+        print_unit_flags(&bcx)?;
+        return Compilation::new(&bcx);
+    }
+    let p = profile::start("compiling");
     let cx = Context::new(&bcx)?;
-    cx.compile(exec)
+    let result = cx.compile(exec);
+    drop(p);
+    if ws.config().cli_unstable().self_profile {
+        // A failure to save the profile shouldn't hide the result of the build.
+        if let Err(e) = save_self_profile(ws) {
+            crate::display_warning_with_error(
+                "failed to save self-profile",
+                &e,
+                &mut ws.config().shell(),
+            );
+        }
+    }
+    result
+}
+
+/// Saves the phases recorded for `-Zself-profile` to
+/// `target/cargo-self-profile`.
+fn save_self_profile(ws: &Workspace<'_>) -> CargoResult<()> {
+    let dir = ws
+        .target_dir()
+        .into_path_unlocked()
+        .join("cargo-self-profile");
+    if let Some(path) = profile::save_self_profile(&dir)? {
+        let msg = message!(ws.config(), "saved to {path}", path = path.display());
+        ws.config()
+            .shell()
+            .status_with_color("Profile", msg, termcolor::Color::Cyan)?;
+    }
+    Ok(())
+}
+
+/// Prints the flags passed to the compiler for each unit of `bcx`, for
+/// `--print-flags`: the ones from `[lints]`, the `rustflags` of the profile
+/// of the unit, which may be set for a single package with
+/// `[profile.dev.package.foo]`, the ones given to `cargo rustc`, and the
+/// rustflags or rustdocflags from the environment and config, in the order
+/// they are passed.
+///
+/// Units with the same flags, like a test and a build of a library, are
+/// printed once.
+fn print_unit_flags(bcx: &BuildContext<'_, '_>) -> CargoResult<()> {
+    let mut units: Vec<_> = bcx
+        .unit_graph
+        .keys()
+        .filter(|unit| !unit.mode.is_run_custom_build())
+        .collect();
+    units.sort();
+    let mut lines = Vec::new();
+    for unit in units {
+        let is_doc = unit.mode.is_doc() || unit.mode.is_doc_test();
+        let mut flags: Vec<&str> = unit
+            .pkg
+            .manifest()
+            .lint_rustflags()
+            .iter()
+            .map(String::as_str)
+            .collect();
+        if is_doc {
+            flags.extend(unit.profile.rustdocflags.iter().map(|f| f.as_str()));
+        } else {
+            flags.extend(unit.profile.rustflags.iter().map(|f| f.as_str()));
+        }
+        flags.extend(
+            bcx.extra_args_for(unit)
+                .into_iter()
+                .flatten()
+                .map(String::as_str),
+        );
+        let tool = if is_doc {
+            flags.extend(bcx.rustdocflags_args(unit).iter().map(String::as_str));
+            "rustdoc"
+        } else {
+            flags.extend(bcx.rustflags_args(unit).iter().map(String::as_str));
+            "rustc"
+        };
+        let kind = match &unit.kind {
+            CompileKind::Host => "host",
+            CompileKind::Target(target) => target.short_name(),
+        };
+        let line = format!(
+            "{} {} ({kind}) {tool}: {}",
+            unit.pkg.package_id(),
+            unit.target.description_named(),
+            flags.join(" ")
+        );
+        if !lines.contains(&line) {
+            lines.push(line);
+        }
+    }
+    for line in lines {
+        drop_println!(bcx.config, "{}", line.trim_end());
+    }
+    Ok(())
 }
 
 /// Executes `rustc --print <VALUE>`.
@@ -196,6 +304,7 @@ pub fn print<'a>(
 ///
 /// For how it works and what data it collects,
 /// please see the [module-level documentation](self).
+#[tracing::instrument(skip_all)]
 pub fn create_bcx<'a, 'cfg>(
     ws: &'a Workspace<'cfg>,
     options: &'a CompileOptions,
@@ -222,16 +331,20 @@ pub fn create_bcx<'a, 'cfg>(
         | CompileMode::Bench
         | CompileMode::RunCustomBuild => {
             if ws.config().get_env("RUST_FLAGS").is_ok() {
-                config.shell().warn(
-                    "Cargo does not read `RUST_FLAGS` environment variable. Did you mean `RUSTFLAGS`?",
-                )?;
+                let msg = message!(
+                    config,
+                    "Cargo does not read `RUST_FLAGS` environment variable. Did you mean `RUSTFLAGS`?"
+                );
+                config.shell().warn(msg)?;
             }
         }
         CompileMode::Doc { .. } | CompileMode::Doctest | CompileMode::Docscrape => {
             if ws.config().get_env("RUSTDOC_FLAGS").is_ok() {
-                config.shell().warn(
+                let msg = message!(
+                    config,
                     "Cargo does not read `RUSTDOC_FLAGS` environment variable. Did you mean `RUSTDOCFLAGS`?"
-                )?;
+                );
+                config.shell().warn(msg)?;
             }
         }
     }
@@ -240,6 +353,7 @@ pub fn create_bcx<'a, 'cfg>(
     let target_data = RustcTargetData::new(ws, &build_config.requested_kinds)?;
 
     let specs = spec.to_package_id_specs(ws)?;
+    check_members_rust_version(ws, &specs, &target_data.rustc.version, honor_rust_version)?;
     let has_dev_units = {
         // Rustdoc itself doesn't need dev-dependencies. But to scrape examples from packages in the
         // workspace, if any of those packages need dev-dependencies, then we need include dev-dependencies
@@ -328,12 +442,13 @@ pub fn create_bcx<'a, 'cfg>(
         );
     }
 
-    let profiles = Profiles::new(ws, build_config.requested_profile)?;
+    let mut profiles = Profiles::new(ws, build_config.requested_profile)?;
     profiles.validate_packages(
         ws.profiles(),
         &mut config.shell(),
         workspace_resolve.as_ref().unwrap_or(&resolve),
     )?;
+    profiles.auto_tune(ws, &resolve)?;
 
     // If `--target` has not been specified, then the unit graph is built
     // assuming `--target $HOST` was specified. See
@@ -443,7 +558,17 @@ pub fn create_bcx<'a, 'cfg>(
         );
     }
 
+    ops::dependency_policy::check_git_deps(
+        config,
+        &resolve,
+        &unit_graph,
+        build_config.requested_profile,
+    )?;
+
     let mut extra_compiler_args = HashMap::new();
+    if config.cli_unstable().target_overrides {
+        add_target_overrides(config, &unit_graph, &mut extra_compiler_args)?;
+    }
     if let Some(args) = extra_args {
         if units.len() != 1 {
             anyhow::bail!(
@@ -453,7 +578,39 @@ pub fn create_bcx<'a, 'cfg>(
                 extra_args_name
             );
         }
-        extra_compiler_args.insert(units[0].clone(), args);
+        extra_compiler_args
+            .entry(units[0].clone())
+            .or_default()
+            .extend(args);
+    }
+
+    if build_config.rustdoc_json {
+        for unit in units.iter().filter(|unit| unit.mode.is_doc()) {
+            extra_compiler_args
+                .entry(unit.clone())
+                .or_default()
+                .extend(["-Zunstable-options", "--output-format", "json"].map(String::from));
+        }
+    }
+
+    if build_config.rustdoc_coverage || build_config.rustdoc_deny_warnings {
+        for unit in units.iter().filter(|unit| unit.mode.is_doc()) {
+            let args = extra_compiler_args.entry(unit.clone()).or_default();
+            if build_config.rustdoc_coverage {
+                args.extend(
+                    [
+                        "-Zunstable-options",
+                        "--show-coverage",
+                        "--output-format",
+                        "json",
+                    ]
+                    .map(String::from),
+                );
+            }
+            if build_config.rustdoc_deny_warnings {
+                args.extend(["-D", "warnings"].map(String::from));
+            }
+        }
     }
 
     for unit in units
@@ -478,22 +635,13 @@ pub fn create_bcx<'a, 'cfg>(
     }
 
     if honor_rust_version {
-        // Remove any pre-release identifiers for easier comparison
         let current_version = &target_data.rustc.version;
-        let untagged_version = semver::Version::new(
-            current_version.major,
-            current_version.minor,
-            current_version.patch,
-        );
-
         for unit in unit_graph.keys() {
             let version = match unit.pkg.rust_version() {
                 Some(v) => v,
                 None => continue,
             };
-
-            let req = semver::VersionReq::parse(version).unwrap();
-            if req.matches(&untagged_version) {
+            if rust_version_matches(version, current_version) {
                 continue;
             }
 
@@ -544,6 +692,140 @@ pub fn create_bcx<'a, 'cfg>(
     Ok(bcx)
 }
 
+/// Adds the flags of the `[target-overrides.<spec>]` config tables to the
+/// units compiled by rustc for the packages matching `<spec>`, for
+/// `-Ztarget-overrides`.
+///
+/// These are passed like the arguments of `cargo rustc`, so they are part of
+/// the fingerprint of the units, and changing them only rebuilds the matching
+/// packages and the ones depending on them.
+fn add_target_overrides(
+    config: &Config,
+    unit_graph: &UnitGraph,
+    extra_compiler_args: &mut HashMap<Unit, Vec<String>>,
+) -> CargoResult<()> {
+    let Some(overrides) =
+        config.get::<Option<BTreeMap<String, TargetOverrideConfig>>>("target-overrides")?
+    else {
+        return Ok(());
+    };
+    for (spec, target_override) in overrides {
+        let spec = PackageIdSpec::parse(&spec).with_context(|| {
+            format!("invalid package ID specification in `target-overrides.{spec}`")
+        })?;
+        let mut args = Vec::new();
+        if let Some(rustflags) = &target_override.rustflags {
+            args.extend(rustflags.as_slice().iter().cloned());
+        }
+        if let Some(cfgs) = &target_override.cfgs {
+            for cfg in cfgs.as_slice() {
+                args.extend(["--cfg".to_string(), cfg.clone()]);
+            }
+        }
+        for unit in unit_graph.keys().filter(|unit| {
+            !(unit.mode.is_doc() || unit.mode.is_doc_test() || unit.mode.is_run_custom_build())
+                && spec.matches(unit.pkg.package_id())
+        }) {
+            extra_compiler_args
+                .entry(unit.clone())
+                .or_default()
+                .extend(args.iter().cloned());
+        }
+    }
+    Ok(())
+}
+
+/// Checks the `rust-version` of the workspace members selected by `specs`
+/// against the active rustc before anything is resolved or built, with
+/// `-Zmsrv-policy`.
+///
+/// Unlike the check of the unit graph, this also runs with
+/// `--ignore-rust-version`, warning instead of failing, and points at the
+/// manifest setting the constraint, along with the toolchain file which may
+/// have selected the active rustc.
+fn check_members_rust_version(
+    ws: &Workspace<'_>,
+    specs: &[PackageIdSpec],
+    current_version: &semver::Version,
+    honor_rust_version: bool,
+) -> CargoResult<()> {
+    let config = ws.config();
+    if !config.cli_unstable().msrv_policy {
+        return Ok(());
+    }
+    for pkg in ws.members() {
+        if !specs.iter().any(|spec| spec.matches(pkg.package_id())) {
+            continue;
+        }
+        let Some(version) = pkg.rust_version() else {
+            continue;
+        };
+        if rust_version_matches(version, current_version) {
+            continue;
+        }
+        if !honor_rust_version {
+            let msg = message!(
+                config,
+                "ignoring `rust-version = \"{version}\"` of package `{pkg}`, \
+                 the currently active rustc version is {current}",
+                version = version,
+                pkg = pkg.package_id(),
+                current = current_version,
+            );
+            config.shell().warn(msg)?;
+            continue;
+        }
+        let toolchain_file = match toolchain_file(config) {
+            Some(path) => format!(
+                "\nthe active toolchain may be selected by `{}`",
+                path.display()
+            ),
+            None => String::new(),
+        };
+        anyhow::bail!(
+            "package `{}` cannot be built because it requires rustc {} or newer, \
+             while the currently active rustc version is {}\n\
+             `rust-version` is set by workspace member `{}` in `{}`{}\n\
+             Either upgrade to rustc {} or newer, or pass `--ignore-rust-version` to build anyway",
+            pkg.package_id(),
+            version,
+            current_version,
+            pkg.name(),
+            pkg.manifest_path().display(),
+            toolchain_file,
+            version,
+        );
+    }
+    Ok(())
+}
+
+/// Whether the rustc `current_version` satisfies the `rust-version` of a
+/// package, ignoring its pre-release identifiers.
+fn rust_version_matches(rust_version: &str, current_version: &semver::Version) -> bool {
+    let untagged_version = semver::Version::new(
+        current_version.major,
+        current_version.minor,
+        current_version.patch,
+    );
+    semver::VersionReq::parse(rust_version)
+        .unwrap()
+        .matches(&untagged_version)
+}
+
+/// Finds the `rust-toolchain.toml` or `rust-toolchain` file rustup would
+/// pick the toolchain from, unless it is overridden by `RUSTUP_TOOLCHAIN`.
+fn toolchain_file(config: &Config) -> Option<PathBuf> {
+    if config.get_env_os("RUSTUP_TOOLCHAIN").is_some() {
+        return None;
+    }
+    config.cwd().ancestors().find_map(|dir| {
+        ["rust-toolchain.toml", "rust-toolchain"]
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file())
+    })
+}
+
 /// This is used to rebuild the unit graph, sharing host dependencies if possible.
 ///
 /// This will translate any unit's `CompileKind::Target(host)` to
@@ -753,7 +1035,7 @@ fn remove_duplicate_doc(
             .into_iter()
             .partition(|unit| cb(unit) && !root_units.contains(unit));
         for unit in to_remove {
-            log::debug!(
+            tracing::debug!(
                 "removing duplicate doc due to {} for package {} target `{}`",
                 reason,
                 unit.pkg,
@@ -0,0 +1,106 @@
+//! Summarizes the native libraries required by build scripts, for
+//! `cargo report native-deps`.
+
+use std::fmt::Write as _;
+
+use crate::core::compiler::{self, BuildOutput, CompileKind, Context, UnitInterner};
+use crate::core::Workspace;
+use crate::drop_println;
+use crate::message;
+use crate::ops::{create_bcx, CompileOptions};
+use crate::util::{CargoResult, Config};
+
+/// Prints the system libraries, search paths, cfgs and environment
+/// variables of the build scripts of the packages selected by `options` and
+/// their dependencies, for packaging them with the libraries they need.
+///
+/// Build scripts aren't run, their outputs are read from the previous build
+/// in the target directory, or from the overrides of their `links` library in
+/// the config.
+pub fn report_native_deps(ws: &Workspace<'_>, options: &CompileOptions) -> CargoResult<()> {
+    let config = ws.config();
+    let interner = UnitInterner::new();
+    let bcx = create_bcx(ws, options, &interner)?;
+    let mut cx = Context::new(&bcx)?;
+    cx.prepare_without_compiling()?;
+
+    let mut units: Vec<_> = bcx
+        .unit_graph
+        .keys()
+        .filter(|unit| unit.mode.is_run_custom_build())
+        .collect();
+    units.sort();
+    let mut not_built = Vec::new();
+    let mut reported = 0;
+    for unit in units {
+        let links = unit.pkg.manifest().links();
+        let (output, source) =
+            match links.and_then(|l| bcx.target_data.script_override(l, unit.kind)) {
+                Some(output) => (output.clone(), " (overridden in config)"),
+                None => match compiler::previous_output(&mut cx, unit) {
+                    Some(output) => (output, ""),
+                    None => {
+                        not_built.push(unit.pkg.package_id());
+                        continue;
+                    }
+                },
+            };
+        if links.is_none() && output.library_links.is_empty() && output.library_paths.is_empty() {
+            continue;
+        }
+
+        let mut header = unit.pkg.package_id().to_string();
+        if let Some(links) = links {
+            write!(header, " links = \"{links}\"")?;
+        }
+        if let CompileKind::Target(target) = unit.kind {
+            write!(header, " for {}", target.short_name())?;
+        }
+        drop_println!(config, "{header}{source}");
+        print_output(config, &output);
+        reported += 1;
+    }
+
+    if reported == 0 && not_built.is_empty() {
+        let msg = message!(config, "no build script links to native libraries");
+        config.shell().note(msg)?;
+    }
+    if !not_built.is_empty() {
+        let list: Vec<_> = not_built.iter().map(|id| format!("  {id}")).collect();
+        let msg = message!(
+            config,
+            "the build scripts of these packages didn't run yet, \
+             build the packages to report what they link to:\n{list}",
+            list = list.join("\n"),
+        );
+        config.shell().warn(msg)?;
+    }
+    Ok(())
+}
+
+/// Prints the parts of the output of a build script that matter to build
+/// environments.
+fn print_output(config: &Config, output: &BuildOutput) {
+    let libraries: Vec<_> = output.library_links.iter().map(String::as_str).collect();
+    let paths: Vec<_> = output
+        .library_paths
+        .iter()
+        .map(|p| p.display().to_string())
+        .collect();
+    let env: Vec<_> = output
+        .rerun_if_env_changed
+        .iter()
+        .map(String::as_str)
+        .collect();
+    let cfgs: Vec<_> = output.cfgs.iter().map(String::as_str).collect();
+    for (name, values) in [
+        ("libraries", libraries.join(", ")),
+        ("search paths", paths.join(", ")),
+        ("environment variables", env.join(", ")),
+        ("cfgs", cfgs.join(", ")),
+    ] {
+        if !values.is_empty() {
+            drop_println!(config, "  {name}: {values}");
+        }
+    }
+}
@@ -12,8 +12,9 @@ use crate::core::resolver::features::{self, FeaturesFor};
 use crate::core::resolver::{HasDevUnits, Resolve};
 use crate::core::{FeatureValue, Package, PackageSet, Summary, Target};
 use crate::core::{TargetKind, Workspace};
+use crate::message;
 use crate::util::restricted_names::is_glob_pattern;
-use crate::util::{closest_msg, CargoResult};
+use crate::util::{closest_msg, CargoResult, Config};
 
 use super::compile_filter::{CompileFilter, FilterRule, LibRule};
 use super::packages::build_glob;
@@ -331,17 +332,23 @@ impl<'a> UnitGenerator<'a, '_> {
                         mode: self.mode,
                     }));
                     if self.mode == CompileMode::Test {
-                        if let Some(t) = pkg
-                            .targets()
-                            .iter()
-                            .find(|t| t.is_lib() && t.doctested() && t.doctestable())
-                        {
-                            proposals.push(Proposal {
-                                pkg,
-                                target: t,
-                                requires_features: false,
-                                mode: CompileMode::Doctest,
-                            });
+                        match pkg.targets().iter().find(|t| t.is_lib() && t.doctestable()) {
+                            Some(t) if t.doctested() => {
+                                proposals.push(Proposal {
+                                    pkg,
+                                    target: t,
+                                    requires_features: false,
+                                    mode: CompileMode::Doctest,
+                                });
+                            }
+                            Some(_)
+                                if self.ws.config().cli_unstable().parallel_doctests
+                                    && self.ws.config().extra_verbose() =>
+                            {
+                                let msg = skipped_doctests_msg(self.ws.config(), pkg);
+                                self.ws.config().shell().note(msg)?;
+                            }
+                            _ => {}
                         }
                     }
                 }
@@ -356,21 +363,32 @@ impl<'a> UnitGenerator<'a, '_> {
             } => {
                 if *lib != LibRule::False {
                     let mut libs = Vec::new();
+                    let mut skipped_doctests = false;
                     for proposal in self.filter_targets(Target::is_lib, false, self.mode) {
                         let Proposal { target, pkg, .. } = proposal;
                         if self.mode.is_doc_test() && !target.doctestable() {
                             let types = target.rustc_crate_types();
                             let types_str: Vec<&str> = types.iter().map(|t| t.as_str()).collect();
-                            self.ws.config().shell().warn(format!(
-                      "doc tests are not supported for crate type(s) `{}` in package `{}`",
-                      types_str.join(", "),
-                      pkg.name()
-                  ))?;
+                            let msg = message!(
+                                self.ws.config(),
+                                "doc tests are not supported for crate type(s) `{types}` in package `{pkg}`",
+                                types = types_str.join(", "),
+                                pkg = pkg.name(),
+                            );
+                            self.ws.config().shell().warn(msg)?;
+                        } else if self.mode.is_doc_test()
+                            && !target.doctested()
+                            && self.ws.config().cli_unstable().parallel_doctests
+                        {
+                            let msg = skipped_doctests_msg(self.ws.config(), pkg);
+                            self.ws.config().shell().note(msg)?;
+                            skipped_doctests = true;
                         } else {
                             libs.push(proposal)
                         }
                     }
-                    if !all_targets && libs.is_empty() && *lib == LibRule::True {
+                    if !all_targets && libs.is_empty() && !skipped_doctests && *lib == LibRule::True
+                    {
                         let names = self
                             .packages
                             .iter()
@@ -489,12 +507,15 @@ impl<'a> UnitGenerator<'a, '_> {
         if !skipped_examples.is_empty() {
             let mut shell = self.ws.config().shell();
             let example_str = skipped_examples.join(", ");
-            shell.warn(format!(
+            let msg = message!(
+                shell = shell,
                 "\
 Rustdoc did not scrape the following examples because they require dev-dependencies: {example_str}
     If you want Rustdoc to scrape these examples, then add `doc-scrape-examples = true`
-    to the [[example]] target configuration of at least one example."
-            ))?;
+    to the [[example]] target configuration of at least one example.",
+                example_str = example_str,
+            );
+            shell.warn(msg)?;
         }
 
         Ok(scrape_proposals)
@@ -505,6 +526,10 @@ Rustdoc did not scrape the following examples because they require dev-dependenc
     /// We want to emit a warning to make sure the user knows that this run is a no-op,
     /// and their code remains unchecked despite cargo not returning any errors
     fn unmatched_target_filters(&self, units: &[Unit]) -> CargoResult<()> {
+        // `--doc` without units already noted why the doctests are skipped.
+        if self.mode.is_doc_test() && self.ws.config().cli_unstable().parallel_doctests {
+            return Ok(());
+        }
         let mut shell = self.ws.config().shell();
         if let CompileFilter::Only {
             all_targets,
@@ -536,11 +561,20 @@ Rustdoc did not scrape the following examples because they require dev-dependenc
                     filters.pop();
                 }
 
-                return shell.warn(format!(
-                    "Target {}{} specified, but no targets matched. This is a no-op",
-                    if miss_count > 1 { "filters" } else { "filter" },
-                    filters,
-                ));
+                let msg = if miss_count > 1 {
+                    message!(
+                        shell = shell,
+                        "Target filters{filters} specified, but no targets matched. This is a no-op",
+                        filters = filters,
+                    )
+                } else {
+                    message!(
+                        shell = shell,
+                        "Target filter{filters} specified, but no targets matched. This is a no-op",
+                        filters = filters,
+                    )
+                };
+                return shell.warn(msg);
             }
         }
 
@@ -568,11 +602,14 @@ Rustdoc did not scrape the following examples because they require dev-dependenc
             match &fv {
                 FeatureValue::Feature(f) => {
                     if !summary.features().contains_key(f) {
-                        shell.warn(format!(
-                            "invalid feature `{}` in required-features of target `{}`: \
-                      `{}` is not present in [features] section",
-                            fv, target_name, fv
-                        ))?;
+                        let msg = message!(
+                            shell = shell,
+                            "invalid feature `{feature}` in required-features of target `{target}`: \
+                             `{feature}` is not present in [features] section",
+                            feature = fv,
+                            target = target_name,
+                        );
+                        shell.warn(msg)?;
                     }
                 }
                 FeatureValue::Dep { .. } => {
@@ -607,19 +644,28 @@ Rustdoc did not scrape the following examples because they require dev-dependenc
                                     dep.name_in_toml() == *dep_feature && dep.is_optional()
                                 })
                             {
-                                shell.warn(format!(
-                                    "invalid feature `{}` in required-features of target `{}`: \
-                              feature `{}` does not exist in package `{}`",
-                                    fv, target_name, dep_feature, dep_id
-                                ))?;
+                                let msg = message!(
+                                    shell = shell,
+                                    "invalid feature `{feature}` in required-features of target `{target}`: \
+                                     feature `{dep_feature}` does not exist in package `{dep}`",
+                                    feature = fv,
+                                    target = target_name,
+                                    dep_feature = dep_feature,
+                                    dep = dep_id,
+                                );
+                                shell.warn(msg)?;
                             }
                         }
                         None => {
-                            shell.warn(format!(
-                                "invalid feature `{}` in required-features of target `{}`: \
-                          dependency `{}` does not exist",
-                                fv, target_name, dep_name
-                            ))?;
+                            let msg = message!(
+                                shell = shell,
+                                "invalid feature `{feature}` in required-features of target `{target}`: \
+                                 dependency `{dep}` does not exist",
+                                feature = fv,
+                                target = target_name,
+                                dep = dep_name,
+                            );
+                            shell.warn(msg)?;
                         }
                     }
                 }
@@ -712,3 +758,13 @@ Rustdoc did not scrape the following examples because they require dev-dependenc
         Ok(scrape_units)
     }
 }
+
+/// The message for a package whose doctests aren't run because its library
+/// sets `doctest = false`.
+fn skipped_doctests_msg(config: &Config, pkg: &Package) -> String {
+    message!(
+        config,
+        "skipping the doctests of `{pkg}`, its library sets `doctest = false`",
+        pkg = pkg.name(),
+    )
+}
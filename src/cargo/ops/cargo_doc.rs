@@ -1,10 +1,14 @@
-use crate::core::{Shell, Workspace};
+use crate::core::compiler::Compilation;
+use crate::core::{PackageId, TargetKind, Workspace};
+use crate::drop_println;
 use crate::ops;
-use crate::util::config::{Config, PathAndArgs};
+use crate::util::browser;
 use crate::util::CargoResult;
+use anyhow::{bail, Context as _};
+use cargo_util::paths;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::Path;
-use std::path::PathBuf;
-use std::process::Command;
 
 /// Strongly typed options for the `cargo doc` command.
 #[derive(Debug)]
@@ -19,57 +23,229 @@ pub struct DocOptions {
 pub fn doc(ws: &Workspace<'_>, options: &DocOptions) -> CargoResult<()> {
     let compilation = ops::compile(ws, &options.compile_opts)?;
 
+    if options.compile_opts.build_config.rustdoc_json {
+        write_json_index(ws, &compilation)?;
+    }
+
+    if options.compile_opts.build_config.rustdoc_coverage {
+        report_coverage(ws, &compilation)?;
+    }
+
     if options.open_result {
-        let name = &compilation
-            .root_crate_names
-            .get(0)
-            .ok_or_else(|| anyhow::anyhow!("no crates with documentation"))?;
+        let name = crate_to_open(ws, &compilation)?;
         let kind = options.compile_opts.build_config.single_requested_kind()?;
         let path = compilation.root_output[&kind]
             .with_file_name("doc")
             .join(&name)
             .join("index.html");
         if path.exists() {
-            let config_browser = {
-                let cfg: Option<PathAndArgs> = ws.config().get("doc.browser")?;
-                cfg.map(|path_args| (path_args.path.resolve_program(ws.config()), path_args.args))
-            };
-
-            let mut shell = ws.config().shell();
-            shell.status("Opening", path.display())?;
-            open_docs(&path, &mut shell, config_browser, ws.config())?;
+            ws.config().shell().status("Opening", path.display())?;
+            browser::open_path(ws.config(), &path)?;
         }
     }
 
     Ok(())
 }
 
-fn open_docs(
-    path: &Path,
-    shell: &mut Shell,
-    config_browser: Option<(PathBuf, Vec<String>)>,
-    config: &Config,
-) -> CargoResult<()> {
-    let browser =
-        config_browser.or_else(|| Some((PathBuf::from(config.get_env_os("BROWSER")?), Vec::new())));
-
-    match browser {
-        Some((browser, initial_args)) => {
-            if let Err(e) = Command::new(&browser).args(initial_args).arg(path).status() {
-                shell.warn(format!(
-                    "Couldn't open docs with {}: {}",
-                    browser.to_string_lossy(),
-                    e
-                ))?;
-            }
+/// The index of the crates documented by `cargo rustdoc --output-format json`.
+#[derive(Serialize)]
+struct JsonIndex<'a> {
+    crates: Vec<JsonIndexEntry<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonIndexEntry<'a> {
+    crate_name: String,
+    package_id: PackageId,
+    name: &'a str,
+    version: String,
+    target_kind: &'a TargetKind,
+    path: &'a Path,
+}
+
+/// Writes the index of the JSON documentation of the root units to
+/// `json-index.json` in the documentation directory of each target. Crate
+/// names can't contain `-`, so this name is never used by rustdoc.
+fn write_json_index(ws: &Workspace<'_>, compilation: &Compilation<'_>) -> CargoResult<()> {
+    let mut indexes: BTreeMap<&Path, Vec<JsonIndexEntry<'_>>> = BTreeMap::new();
+    for output in &compilation.rustdoc_json {
+        let Some(dir) = output.path.parent() else {
+            continue;
+        };
+        let pkg_id = output.unit.pkg.package_id();
+        indexes.entry(dir).or_default().push(JsonIndexEntry {
+            crate_name: output.unit.target.crate_name(),
+            package_id: pkg_id,
+            name: pkg_id.name().as_str(),
+            version: pkg_id.version().to_string(),
+            target_kind: output.unit.target.kind(),
+            path: &output.path,
+        });
+    }
+    for (dir, mut crates) in indexes {
+        crates.sort_by(|a, b| (&a.crate_name, a.package_id).cmp(&(&b.crate_name, b.package_id)));
+        let path = dir.join("json-index.json");
+        let json = serde_json::to_string_pretty(&JsonIndex { crates })?;
+        paths::write(&path, format!("{json}\n"))?;
+        ws.config().shell().status("Generated", path.display())?;
+    }
+    Ok(())
+}
+
+/// The documentation coverage of a file, or the sum of the ones of several
+/// files, as reported by `rustdoc --show-coverage --output-format json`.
+#[derive(Clone, Copy, Default, Deserialize, Serialize)]
+struct Coverage {
+    total: u64,
+    with_docs: u64,
+    total_examples: u64,
+    with_examples: u64,
+}
+
+impl Coverage {
+    fn add(&mut self, other: &Coverage) {
+        self.total += other.total;
+        self.with_docs += other.with_docs;
+        self.total_examples += other.total_examples;
+        self.with_examples += other.with_examples;
+    }
+
+    /// The percentage of the items which are documented.
+    fn percentage(&self) -> f64 {
+        percentage(self.with_docs, self.total)
+    }
+}
+
+fn percentage(n: u64, total: u64) -> f64 {
+    if total == 0 {
+        100.0
+    } else {
+        n as f64 * 100.0 / total as f64
+    }
+}
+
+/// The coverage of the crates documented by `cargo doc --show-coverage`.
+#[derive(Serialize)]
+struct CoverageReport<'a> {
+    crates: Vec<CrateCoverage<'a>>,
+    total: Coverage,
+}
+
+#[derive(Serialize)]
+struct CrateCoverage<'a> {
+    crate_name: String,
+    package_id: PackageId,
+    target_kind: &'a TargetKind,
+    #[serde(flatten)]
+    coverage: Coverage,
+}
+
+/// Sums up the coverage reports rustdoc wrote for each root unit, prints them
+/// along with their total, and writes them to `coverage.json` in the
+/// documentation directory of each target.
+///
+/// Fails if the total is below `doc.min-coverage`.
+fn report_coverage(ws: &Workspace<'_>, compilation: &Compilation<'_>) -> CargoResult<()> {
+    let config = ws.config();
+    let min_coverage = config.get::<Option<u32>>("doc.min-coverage")?;
+    if let Some(min) = min_coverage {
+        if min > 100 {
+            bail!("`doc.min-coverage` must be a percentage between 0 and 100, got {min}");
         }
-        None => {
-            if let Err(e) = opener::open(&path) {
-                let e = e.into();
-                crate::display_warning_with_error("couldn't open docs", &e, shell);
+    }
+
+    let mut reports: BTreeMap<&Path, Vec<CrateCoverage<'_>>> = BTreeMap::new();
+    for output in &compilation.rustdoc_coverage {
+        let Some(dir) = output.path.parent() else {
+            continue;
+        };
+        let contents = paths::read(&output.path)?;
+        let files: BTreeMap<String, Coverage> =
+            serde_json::from_str(&contents).with_context(|| {
+                format!(
+                    "failed to parse the coverage report at `{}`",
+                    output.path.display()
+                )
+            })?;
+        let mut coverage = Coverage::default();
+        for file in files.values() {
+            coverage.add(file);
+        }
+        reports.entry(dir).or_default().push(CrateCoverage {
+            crate_name: output.unit.target.crate_name(),
+            package_id: output.unit.pkg.package_id(),
+            target_kind: output.unit.target.kind(),
+            coverage,
+        });
+    }
+
+    let mut below_min = Vec::new();
+    for (dir, mut crates) in reports {
+        crates.sort_by(|a, b| (&a.crate_name, a.package_id).cmp(&(&b.crate_name, b.package_id)));
+        let mut total = Coverage::default();
+        for c in &crates {
+            total.add(&c.coverage);
+        }
+
+        let print_row = |coverage: &Coverage, name: &str| {
+            drop_println!(
+                config,
+                "{:>10}  {:>9.1}%  {:>10}  {:>9.1}%  {}",
+                format!("{}/{}", coverage.with_docs, coverage.total),
+                coverage.percentage(),
+                format!("{}/{}", coverage.with_examples, coverage.total_examples),
+                percentage(coverage.with_examples, coverage.total_examples),
+                name
+            );
+        };
+        drop_println!(
+            config,
+            "{:>10}  {:>10}  {:>10}  {:>10}  Crate",
+            "Documented",
+            "Percentage",
+            "Examples",
+            "Percentage"
+        );
+        for c in &crates {
+            let name = format!("{} v{}", c.crate_name, c.package_id.version());
+            print_row(&c.coverage, &name);
+        }
+        print_row(&total, "Total");
+
+        let path = dir.join("coverage.json");
+        let json = serde_json::to_string_pretty(&CoverageReport { crates, total })?;
+        paths::write(&path, format!("{json}\n"))?;
+        config.shell().status("Generated", path.display())?;
+
+        if let Some(min) = min_coverage {
+            if total.percentage() < f64::from(min) {
+                below_min.push(total.percentage());
             }
         }
-    };
+    }
 
+    if let (Some(min), Some(coverage)) = (min_coverage, below_min.first()) {
+        bail!(
+            "the documentation coverage of {coverage:.1}% is below \
+             the `doc.min-coverage` of {min}%"
+        );
+    }
     Ok(())
 }
+
+/// The crate whose documentation `--open` opens: the first documented crate
+/// of the package in the current directory, or else the first documented
+/// crate.
+fn crate_to_open<'a>(ws: &Workspace<'_>, compilation: &'a Compilation<'_>) -> CargoResult<&'a str> {
+    let names = &compilation.root_crate_names;
+    let current = ws.current_opt().and_then(|pkg| {
+        pkg.targets()
+            .iter()
+            .filter(|t| t.documented())
+            .find_map(|t| names.iter().find(|name| **name == t.crate_name()))
+    });
+    current
+        .or_else(|| names.first())
+        .map(String::as_str)
+        .ok_or_else(|| anyhow::anyhow!("no crates with documentation"))
+}
@@ -1,16 +1,32 @@
 use crate::core::compiler::standard_lib;
 use crate::core::compiler::{BuildConfig, CompileMode, RustcTargetData};
-use crate::core::{PackageSet, Resolve, Workspace};
+use crate::core::{PackageId, PackageSet, Resolve, Workspace};
+use crate::message;
 use crate::ops;
 use crate::util::config::JobsConfig;
 use crate::util::CargoResult;
 use crate::util::Config;
+use serde::Serialize;
 use std::collections::HashSet;
 
 pub struct FetchOptions<'a> {
     pub config: &'a Config,
     /// The target arch triple to fetch dependencies for
     pub targets: Vec<String>,
+    /// Resolve and update the registry indexes, but don't download packages
+    pub dry_run: bool,
+    /// Print a JSON line for every package that is needed
+    pub json: bool,
+}
+
+/// A line of `cargo fetch --json` output.
+#[derive(Serialize)]
+struct FetchedPackage {
+    id: PackageId,
+    /// Whether the package was already available locally, as opposed to
+    /// being downloaded by this invocation (or by the real one, when doing a
+    /// dry run).
+    fresh: bool,
 }
 
 /// Executes `cargo fetch`.
@@ -74,7 +90,32 @@ pub fn fetch<'a>(
         packages.add_set(std_package_set);
     }
 
-    packages.get_many(to_download)?;
+    to_download.sort();
+    let fresh = {
+        let _lock = config.acquire_package_cache_lock()?;
+        to_download
+            .iter()
+            .map(|&id| packages.is_downloaded(id))
+            .collect::<Vec<_>>()
+    };
+
+    if options.dry_run {
+        if !options.json {
+            for (id, _) in to_download.iter().zip(&fresh).filter(|(_, f)| !**f) {
+                config.shell().status("Downloading", id)?;
+            }
+            let msg = message!(config, "not downloading packages due to dry run");
+            config.shell().warn(msg)?;
+        }
+    } else {
+        packages.get_many(to_download.iter().copied())?;
+    }
+
+    if options.json {
+        for (&id, &fresh) in to_download.iter().zip(&fresh) {
+            config.shell().print_json(&FetchedPackage { id, fresh })?;
+        }
+    }
 
     Ok((resolve, packages))
 }
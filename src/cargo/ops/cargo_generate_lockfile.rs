@@ -2,13 +2,16 @@ use crate::core::registry::PackageRegistry;
 use crate::core::resolver::features::{CliFeatures, HasDevUnits};
 use crate::core::{PackageId, PackageIdSpec};
 use crate::core::{Resolve, SourceId, Workspace};
+use crate::drop_println;
+use crate::message;
 use crate::ops;
 use crate::util::config::Config;
 use crate::util::CargoResult;
 use anyhow::Context;
-use log::debug;
+use serde::Serialize;
 use std::collections::{BTreeMap, HashSet};
 use termcolor::Color::{self, Cyan, Green, Red, Yellow};
+use tracing::debug;
 
 pub struct UpdateOptions<'a> {
     pub config: &'a Config,
@@ -17,6 +20,16 @@ pub struct UpdateOptions<'a> {
     pub aggressive: bool,
     pub dry_run: bool,
     pub workspace: bool,
+    /// Print a summary of the changes grouped by kind after updating.
+    pub summary: bool,
+    /// Print the summary as JSON instead.
+    pub json: bool,
+}
+
+pub struct DedupeOptions<'a> {
+    pub config: &'a Config,
+    /// Write the changes to `Cargo.lock` instead of only printing them.
+    pub write: bool,
 }
 
 pub fn generate_lockfile(ws: &Workspace<'_>) -> CargoResult<()> {
@@ -128,10 +141,108 @@ pub fn update_lockfile(ws: &Workspace<'_>, opts: &UpdateOptions<'_>) -> CargoRes
     )?;
 
     // Summarize what is changing for the user.
+    let changes = print_lockfile_changes(opts.config, &previous_resolve, &resolve)?;
+    if opts.summary || opts.json {
+        print_summary(opts, &previous_resolve, &resolve, &changes)?;
+    }
+    if opts.dry_run {
+        let msg = message!(opts.config, "not updating lockfile due to dry run");
+        opts.config.shell().warn(msg)?;
+    } else {
+        ops::write_pkg_lockfile(ws, &mut resolve)?;
+    }
+    return Ok(());
+
+    fn fill_with_deps<'a>(
+        resolve: &'a Resolve,
+        dep: PackageId,
+        set: &mut HashSet<PackageId>,
+        visited: &mut HashSet<PackageId>,
+    ) {
+        if !visited.insert(dep) {
+            return;
+        }
+        set.insert(dep);
+        for (dep, _) in resolve.deps_not_replaced(dep) {
+            fill_with_deps(resolve, dep, set, visited);
+        }
+    }
+}
+
+/// Reduces the versions of registry packages that are locked more than once.
+///
+/// The resolver never locks two semver compatible versions of a package, but
+/// after incremental updates a package depending on an older version may also
+/// accept a newer one that is locked for another package, like with a
+/// `>=0.1, <0.3` requirement. Every version but the newest is unlocked while
+/// still being preferred, so dependencies only move to versions already in
+/// `Cargo.lock`, and nothing else is updated.
+pub fn dedupe_lockfile(ws: &Workspace<'_>, opts: &DedupeOptions<'_>) -> CargoResult<()> {
+    let _lock = ws.config().acquire_package_cache_lock()?;
+
+    let Some(previous_resolve) = ops::load_pkg_lockfile(ws)? else {
+        anyhow::bail!(
+            "there is no `Cargo.lock` to deduplicate, \
+             run `cargo generate-lockfile` to create one"
+        )
+    };
+
+    let mut locked: BTreeMap<_, Vec<PackageId>> = BTreeMap::new();
+    for id in previous_resolve
+        .iter()
+        .filter(|id| id.source_id().is_registry())
+    {
+        locked
+            .entry((id.name(), id.source_id()))
+            .or_default()
+            .push(id);
+    }
+    let mut to_unlock = HashSet::new();
+    for mut ids in locked.into_values() {
+        ids.sort();
+        // Keep the newest version locked.
+        ids.pop();
+        to_unlock.extend(ids);
+    }
+
+    let mut registry = PackageRegistry::new(opts.config)?;
+    let mut resolve =
+        ops::resolve_with_unlocked_edges(&mut registry, ws, &previous_resolve, &to_unlock)?;
+
+    let changes = print_lockfile_changes(opts.config, &previous_resolve, &resolve)?;
+    if changes
+        .iter()
+        .all(|(removed, added)| removed.is_empty() && added.is_empty())
+    {
+        let msg = message!(
+            opts.config,
+            "no duplicate packages can be removed from `Cargo.lock`"
+        );
+        opts.config.shell().note(msg)?;
+    } else if opts.write {
+        ops::write_pkg_lockfile(ws, &mut resolve)?;
+    } else {
+        let msg = message!(
+            opts.config,
+            "not updating lockfile, pass `--write` to apply the changes"
+        );
+        opts.config.shell().warn(msg)?;
+    }
+    Ok(())
+}
+
+/// Prints the packages that differ between the two resolves, grouped by name
+/// and source, and returns the `(removed, added)` package IDs of each group.
+fn print_lockfile_changes(
+    config: &Config,
+    previous_resolve: &Resolve,
+    resolve: &Resolve,
+) -> CargoResult<Vec<(Vec<PackageId>, Vec<PackageId>)>> {
     let print_change = |status: &str, msg: String, color: Color| {
-        opts.config.shell().status_with_color(status, msg, color)
+        config.shell().status_with_color(status, msg, color)
     };
-    for (removed, added) in compare_dependency_graphs(&previous_resolve, &resolve) {
+    let changes = compare_dependency_graphs(previous_resolve, resolve);
+    for (removed, added) in changes.iter() {
         if removed.len() == 1 && added.len() == 1 {
             let msg = if removed[0].source_id().is_git() {
                 format!(
@@ -157,29 +268,7 @@ pub fn update_lockfile(ws: &Workspace<'_>, opts: &UpdateOptions<'_>) -> CargoRes
             }
         }
     }
-    if opts.dry_run {
-        opts.config
-            .shell()
-            .warn("not updating lockfile due to dry run")?;
-    } else {
-        ops::write_pkg_lockfile(ws, &mut resolve)?;
-    }
-    return Ok(());
-
-    fn fill_with_deps<'a>(
-        resolve: &'a Resolve,
-        dep: PackageId,
-        set: &mut HashSet<PackageId>,
-        visited: &mut HashSet<PackageId>,
-    ) {
-        if !visited.insert(dep) {
-            return;
-        }
-        set.insert(dep);
-        for (dep, _) in resolve.deps_not_replaced(dep) {
-            fill_with_deps(resolve, dep, set, visited);
-        }
-    }
+    return Ok(changes);
 
     fn compare_dependency_graphs(
         previous_resolve: &Resolve,
@@ -255,3 +344,138 @@ pub fn update_lockfile(ws: &Workspace<'_>, opts: &UpdateOptions<'_>) -> CargoRes
         changes.into_iter().map(|(_, v)| v).collect()
     }
 }
+
+/// Changes made by `cargo update`, grouped by kind.
+#[derive(Serialize, Default)]
+struct UpdateSummary {
+    upgraded: Vec<SummaryEntry>,
+    downgraded: Vec<SummaryEntry>,
+    added: Vec<SummaryEntry>,
+    removed: Vec<SummaryEntry>,
+}
+
+#[derive(Serialize)]
+struct SummaryEntry {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    old: Option<PackageId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    new: Option<PackageId>,
+    /// The dependency requirements that pull in the package. For removed
+    /// packages these come from the previous lock file, which only records
+    /// the dependent packages.
+    required_by: Vec<Requirement>,
+}
+
+#[derive(Serialize)]
+struct Requirement {
+    package: PackageId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    req: Option<String>,
+}
+
+impl SummaryEntry {
+    fn new(old: Option<PackageId>, new: Option<PackageId>, resolve: &Resolve) -> SummaryEntry {
+        let id = new.or(old).unwrap();
+        let mut required_by = Vec::new();
+        for parent in resolve.iter() {
+            for (dep_id, deps) in resolve.deps(parent) {
+                if dep_id != id {
+                    continue;
+                }
+                // Lock files don't record the requirements, only the edges.
+                if deps.is_empty() {
+                    required_by.push(Requirement {
+                        package: parent,
+                        req: None,
+                    });
+                    continue;
+                }
+                let mut reqs: Vec<_> = deps.iter().map(|d| d.version_req().to_string()).collect();
+                reqs.sort();
+                reqs.dedup();
+                required_by.extend(reqs.into_iter().map(|req| Requirement {
+                    package: parent,
+                    req: Some(req),
+                }));
+            }
+        }
+        required_by.sort_by(|a, b| (a.package, &a.req).cmp(&(b.package, &b.req)));
+        SummaryEntry {
+            old,
+            new,
+            required_by,
+        }
+    }
+
+    fn describe(&self) -> String {
+        let mut out = match (self.old, self.new) {
+            (Some(old), Some(new)) if old.source_id().is_git() => {
+                format!("{} -> #{}", old, &new.source_id().precise().unwrap()[..8])
+            }
+            (Some(old), Some(new)) => format!("{} -> v{}", old, new.version()),
+            (Some(id), None) | (None, Some(id)) => id.to_string(),
+            (None, None) => unreachable!(),
+        };
+        if !self.required_by.is_empty() {
+            let reqs: Vec<_> = self
+                .required_by
+                .iter()
+                .map(|r| match &r.req {
+                    Some(req) => format!("{} requires {}", r.package.name(), req),
+                    None => format!("previously required by {}", r.package),
+                })
+                .collect();
+            out.push_str(&format!(" ({})", reqs.join(", ")));
+        }
+        out
+    }
+}
+
+fn print_summary(
+    opts: &UpdateOptions<'_>,
+    previous_resolve: &Resolve,
+    resolve: &Resolve,
+    changes: &[(Vec<PackageId>, Vec<PackageId>)],
+) -> CargoResult<()> {
+    let mut summary = UpdateSummary::default();
+    for (removed, added) in changes {
+        if removed.len() == 1 && added.len() == 1 {
+            let entry = SummaryEntry::new(Some(removed[0]), Some(added[0]), resolve);
+            if removed[0].version() > added[0].version() {
+                summary.downgraded.push(entry);
+            } else {
+                summary.upgraded.push(entry);
+            }
+        } else {
+            summary.removed.extend(
+                removed
+                    .iter()
+                    .map(|&id| SummaryEntry::new(Some(id), None, previous_resolve)),
+            );
+            summary.added.extend(
+                added
+                    .iter()
+                    .map(|&id| SummaryEntry::new(None, Some(id), resolve)),
+            );
+        }
+    }
+
+    if opts.json {
+        return opts.config.shell().print_json(&summary);
+    }
+    for (title, entries) in [
+        ("Upgraded", &summary.upgraded),
+        ("Downgraded", &summary.downgraded),
+        ("Added", &summary.added),
+        ("Removed", &summary.removed),
+    ] {
+        if entries.is_empty() {
+            continue;
+        }
+        drop_println!(opts.config, "{}:", title);
+        for entry in entries {
+            drop_println!(opts.config, "  {}", entry.describe());
+        }
+    }
+    Ok(())
+}
@@ -1,24 +1,30 @@
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::task::Poll;
 use std::{env, fs};
 
-use crate::core::compiler::{CompileKind, DefaultExecutor, Executor, UnitOutput};
+use crate::core::compiler::{CompileKind, CompileTarget, DefaultExecutor, Executor, UnitOutput};
+use crate::core::resolver::CliFeatures;
 use crate::core::{
-    Dependency, Edition, Package, PackageId, PackageIdSpec, Source, SourceId, Target, Workspace,
+    Dependency, Edition, Package, PackageId, PackageIdSpec, PrebuiltBinary, Source, SourceId,
+    Target, Verbosity, Workspace,
 };
 use crate::ops::{common_for_install_and_uninstall::*, FilterRule};
 use crate::ops::{CompileFilter, Packages};
 use crate::sources::{GitSource, PathSource, SourceConfigMap};
 use crate::util::errors::CargoResult;
+use crate::util::interning::InternedString;
+use crate::util::network::http::{http_handle, RegistryHttpConfig};
 use crate::util::{Config, Filesystem, Rustc, ToSemver, VersionReqExt};
-use crate::{drop_println, ops};
+use crate::{drop_println, message, ops};
 
 use anyhow::{bail, format_err, Context as _};
-use cargo_util::paths;
+use cargo_util::{paths, Sha256};
+use curl::easy::List;
 use itertools::Itertools;
 use semver::VersionReq;
-use tempfile::Builder as TempFileBuilder;
+use tempfile::{Builder as TempFileBuilder, TempDir};
 
 struct Transaction {
     bins: Vec<PathBuf>,
@@ -46,6 +52,9 @@ struct InstallablePackage<'cfg, 'a> {
     vers: Option<&'a str>,
     force: bool,
     no_track: bool,
+    /// The prebuilt binaries the registry advertises for `pkg`, with
+    /// `--from-binary-registry`.
+    prebuilt: Vec<PrebuiltBinary>,
 
     pkg: Package,
     ws: Workspace<'cfg>,
@@ -66,6 +75,7 @@ impl<'cfg, 'a> InstallablePackage<'cfg, 'a> {
         original_opts: &'a ops::CompileOptions,
         force: bool,
         no_track: bool,
+        from_binary_registry: bool,
         needs_update_if_source_is_index: bool,
     ) -> CargoResult<Option<InstallablePackage<'cfg, 'a>>> {
         if let Some(name) = krate {
@@ -79,6 +89,7 @@ impl<'cfg, 'a> InstallablePackage<'cfg, 'a> {
         }
 
         let dst = root.join("bin").into_path_unlocked();
+        let mut prebuilt = Vec::new();
         let pkg = {
             let dep = {
                 if let Some(krate) = krate {
@@ -153,14 +164,26 @@ impl<'cfg, 'a> InstallablePackage<'cfg, 'a> {
                     &dst,
                     force,
                 ) {
-                    let msg = format!(
-                        "package `{}` is already installed, use --force to override",
-                        pkg
+                    let msg = message!(
+                        config,
+                        "package `{pkg}` is already installed, use --force to override",
+                        pkg = pkg,
                     );
                     config.shell().status("Ignored", &msg)?;
                     return Ok(None);
                 }
-                select_dep_pkg(&mut source, dep, config, needs_update_if_source_is_index)?
+                let pkg =
+                    select_dep_pkg(&mut source, dep, config, needs_update_if_source_is_index)?;
+                if from_binary_registry {
+                    let _lock = config.acquire_package_cache_lock()?;
+                    prebuilt = loop {
+                        match source.prebuilt_binaries(pkg.package_id())? {
+                            Poll::Ready(prebuilt) => break prebuilt,
+                            Poll::Pending => source.block_until_ready()?,
+                        }
+                    };
+                }
+                pkg
             } else {
                 bail!(
                     "must specify a crate to install from \
@@ -175,10 +198,8 @@ impl<'cfg, 'a> InstallablePackage<'cfg, 'a> {
         // If we're installing in --locked mode and there's no `Cargo.lock` published
         // ie. the bin was published before https://github.com/rust-lang/cargo/pull/7026
         if config.locked() && !ws.root().join("Cargo.lock").exists() {
-            config.shell().warn(format!(
-                "no Cargo.lock file published in {}",
-                pkg.to_string()
-            ))?;
+            let msg = message!(config, "no Cargo.lock file published in {pkg}", pkg = pkg);
+            config.shell().warn(msg)?;
         }
         let pkg = if source_id.is_git() {
             // Don't use ws.current() in order to keep the package source as a git source so that
@@ -201,12 +222,14 @@ impl<'cfg, 'a> InstallablePackage<'cfg, 'a> {
 
         if from_cwd {
             if pkg.manifest().edition() == Edition::Edition2015 {
-                config.shell().warn(
+                let msg = message!(
+                    config,
                     "Using `cargo install` to install the binaries for the \
                      package in current working directory is deprecated, \
                      use `cargo install --path .` instead. \
-                     Use `cargo build` if you want to simply build the package.",
-                )?
+                     Use `cargo build` if you want to simply build the package."
+                );
+                config.shell().warn(msg)?
             } else {
                 bail!(
                     "Using `cargo install` to install the binaries for the \
@@ -237,6 +260,7 @@ impl<'cfg, 'a> InstallablePackage<'cfg, 'a> {
             vers,
             force,
             no_track,
+            prebuilt,
 
             pkg,
             ws,
@@ -252,9 +276,10 @@ impl<'cfg, 'a> InstallablePackage<'cfg, 'a> {
         } else if is_installed(
             &ip.pkg, config, &ip.opts, &ip.rustc, &ip.target, &ip.root, &dst, force,
         )? {
-            let msg = format!(
-                "package `{}` is already installed, use --force to override",
-                ip.pkg
+            let msg = message!(
+                config,
+                "package `{pkg}` is already installed, use --force to override",
+                pkg = ip.pkg,
             );
             config.shell().status("Ignored", &msg)?;
             return Ok(None);
@@ -311,34 +336,43 @@ impl<'cfg, 'a> InstallablePackage<'cfg, 'a> {
 
         self.check_yanked_install()?;
 
-        let exec: Arc<dyn Executor> = Arc::new(DefaultExecutor);
-        let compile = ops::compile_ws(&self.ws, &self.opts, &exec).with_context(|| {
-            if let Some(td) = td_opt.take() {
-                // preserve the temporary directory, so the user can inspect it
-                drop(td.into_path());
-            }
-
-            format!(
-                "failed to compile `{}`, intermediate artifacts can be \
-                 found at `{}`.\nTo reuse those artifacts with a future \
-                 compilation, set the environment variable \
-                 `CARGO_TARGET_DIR` to that path.",
-                self.pkg,
-                self.ws.target_dir().display()
-            )
-        })?;
-        let mut binaries: Vec<(&str, &Path)> = compile
-            .binaries
-            .iter()
-            .map(|UnitOutput { path, .. }| {
-                let name = path.file_name().unwrap();
-                if let Some(s) = name.to_str() {
-                    Ok((s, path.as_ref()))
-                } else {
-                    bail!("Binary `{:?}` name can't be serialized into string", name)
+        let prebuilt = self.download_prebuilt()?;
+        let compile;
+        let mut binaries: Vec<(&str, &Path)> = if let Some((_dir, prebuilt)) = &prebuilt {
+            prebuilt
+                .iter()
+                .map(|(name, path)| (name.as_str(), path.as_path()))
+                .collect()
+        } else {
+            let exec: Arc<dyn Executor> = Arc::new(DefaultExecutor);
+            compile = ops::compile_ws(&self.ws, &self.opts, &exec).with_context(|| {
+                if let Some(td) = td_opt.take() {
+                    // preserve the temporary directory, so the user can inspect it
+                    drop(td.into_path());
                 }
-            })
-            .collect::<CargoResult<_>>()?;
+
+                format!(
+                    "failed to compile `{}`, intermediate artifacts can be \
+                     found at `{}`.\nTo reuse those artifacts with a future \
+                     compilation, set the environment variable \
+                     `CARGO_TARGET_DIR` to that path.",
+                    self.pkg,
+                    self.ws.target_dir().display()
+                )
+            })?;
+            compile
+                .binaries
+                .iter()
+                .map(|UnitOutput { path, .. }| {
+                    let name = path.file_name().unwrap();
+                    if let Some(s) = name.to_str() {
+                        Ok((s, path.as_ref()))
+                    } else {
+                        bail!("Binary `{:?}` name can't be serialized into string", name)
+                    }
+                })
+                .collect::<CargoResult<_>>()?
+        };
         if binaries.is_empty() {
             // Cargo already warns the user if they use a target specifier that matches nothing,
             // but we want to error if the user asked for a _particular_ binary to be installed,
@@ -377,9 +411,8 @@ impl<'cfg, 'a> InstallablePackage<'cfg, 'a> {
                 .filter(|t| t.is_executable())
                 .collect();
             if !binaries.is_empty() {
-                self.config
-                    .shell()
-                    .warn(make_warning_about_missing_features(&binaries))?;
+                let msg = make_warning_about_missing_features(self.config, &binaries);
+                self.config.shell().warn(msg)?;
             }
 
             return Ok(false);
@@ -460,6 +493,7 @@ impl<'cfg, 'a> InstallablePackage<'cfg, 'a> {
         if let Some(mut tracker) = tracker {
             tracker.mark_installed(
                 &self.pkg,
+                &dst,
                 &successful_bins,
                 self.vers.map(|s| s.to_string()),
                 &self.opts,
@@ -471,9 +505,12 @@ impl<'cfg, 'a> InstallablePackage<'cfg, 'a> {
                 remove_orphaned_bins(&self.ws, &mut tracker, &duplicates, &self.pkg, &dst)
             {
                 // Don't hard error on remove.
-                self.config
-                    .shell()
-                    .warn(format!("failed to remove orphan: {:?}", e))?;
+                let msg = message!(
+                    self.config,
+                    "failed to remove orphan: {error}",
+                    error = format!("{:?}", e),
+                );
+                self.config.shell().warn(msg)?;
             }
 
             match tracker.save() {
@@ -507,21 +544,23 @@ impl<'cfg, 'a> InstallablePackage<'cfg, 'a> {
         }
 
         if duplicates.is_empty() {
-            self.config.shell().status(
-                "Installed",
-                format!(
-                    "package `{}` {}",
-                    self.pkg,
-                    executables(successful_bins.iter())
-                ),
-            )?;
+            let msg = message!(
+                self.config,
+                "package `{pkg}` {executables}",
+                pkg = self.pkg,
+                executables = executables(successful_bins.iter()),
+            );
+            self.config.shell().status("Installed", msg)?;
             Ok(true)
         } else {
             if !to_install.is_empty() {
-                self.config.shell().status(
-                    "Installed",
-                    format!("package `{}` {}", self.pkg, executables(to_install.iter())),
-                )?;
+                let msg = message!(
+                    self.config,
+                    "package `{pkg}` {executables}",
+                    pkg = self.pkg,
+                    executables = executables(to_install.iter()),
+                );
+                self.config.shell().status("Installed", msg)?;
             }
             // Invert the duplicate map.
             let mut pkg_map = BTreeMap::new();
@@ -531,20 +570,111 @@ impl<'cfg, 'a> InstallablePackage<'cfg, 'a> {
                 pkg_map.entry(key).or_insert_with(Vec::new).push(bin_name);
             }
             for (pkg_descr, bin_names) in &pkg_map {
-                self.config.shell().status(
-                    "Replaced",
-                    format!(
-                        "package `{}` with `{}` {}",
-                        pkg_descr,
-                        self.pkg,
-                        executables(bin_names.iter())
-                    ),
-                )?;
+                let msg = message!(
+                    self.config,
+                    "package `{old}` with `{pkg}` {executables}",
+                    old = pkg_descr,
+                    pkg = self.pkg,
+                    executables = executables(bin_names.iter()),
+                );
+                self.config.shell().status("Replaced", msg)?;
             }
             Ok(true)
         }
     }
 
+    /// Downloads the prebuilt binaries advertised by the registry into a
+    /// temporary directory, if they can replace building the package.
+    ///
+    /// They are built with the default features and the `release` profile,
+    /// so they are only used when installing with these, and only when there
+    /// is one for each of the binaries to install. Otherwise, or if they
+    /// can't be downloaded, the package is built from source as usual.
+    fn download_prebuilt(&self) -> CargoResult<Option<(TempDir, Vec<(String, PathBuf)>)>> {
+        if self.prebuilt.is_empty() {
+            return Ok(None);
+        }
+        let features = &self.opts.cli_features;
+        if !features.features.is_empty()
+            || features.all_features
+            || !features.uses_default_features
+            || self.opts.build_config.requested_profile != "release"
+        {
+            let msg = message!(
+                self.config,
+                "building `{pkg}` from source, its prebuilt binaries are only \
+                 available with the default features and the `release` profile",
+                pkg = self.pkg,
+            );
+            self.config.shell().note(msg)?;
+            return Ok(None);
+        }
+        let mut binaries = Vec::new();
+        for name in exe_names(&self.pkg, &self.opts.filter) {
+            let prebuilt = self
+                .prebuilt
+                .iter()
+                .find(|bin| bin.target == self.target && bin.name == name);
+            let Some(prebuilt) = prebuilt else {
+                let msg = message!(
+                    self.config,
+                    "building `{pkg}` from source, the registry has no prebuilt \
+                     binary `{name}` for `{target}`",
+                    pkg = self.pkg,
+                    name = name,
+                    target = self.target,
+                );
+                self.config.shell().note(msg)?;
+                return Ok(None);
+            };
+            binaries.push(prebuilt);
+        }
+
+        let dir = TempFileBuilder::new().prefix("cargo-install").tempdir()?;
+        let mut downloaded = Vec::new();
+        for prebuilt in binaries {
+            let msg = message!(
+                self.config,
+                "prebuilt binary `{name}` for `{target}`",
+                name = prebuilt.name,
+                target = prebuilt.target,
+            );
+            self.config.shell().status("Downloading", msg)?;
+            let data = match download_prebuilt_binary(self.config, self.source_id, prebuilt) {
+                Ok(data) => data,
+                Err(e) => {
+                    let msg = message!(
+                        self.config,
+                        "failed to download the prebuilt binary `{name}` of `{pkg}`, \
+                         building it from source instead: {error}",
+                        name = prebuilt.name,
+                        pkg = self.pkg,
+                        error = format!("{:#}", e),
+                    );
+                    self.config.shell().warn(msg)?;
+                    return Ok(None);
+                }
+            };
+            let actual = Sha256::new().update(&data).finish_hex();
+            if actual != prebuilt.checksum {
+                bail!(
+                    "failed to verify the checksum of the prebuilt binary `{}` of `{}`",
+                    prebuilt.name,
+                    self.pkg
+                );
+            }
+            let path = dir.path().join(&prebuilt.name);
+            paths::write(&path, &data)?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+            }
+            downloaded.push((prebuilt.name.clone(), path));
+        }
+        Ok(Some((dir, downloaded)))
+    }
+
     fn check_yanked_install(&self) -> CargoResult<()> {
         if self.ws.ignore_lock() || !self.ws.root().join("Cargo.lock").exists() {
             return Ok(());
@@ -557,12 +687,47 @@ impl<'cfg, 'a> InstallablePackage<'cfg, 'a> {
             self.ws.config(),
             &pkg_set,
             &resolve,
-            "consider running without --locked",
+            &message!(self.ws.config(), "consider running without --locked"),
         )
     }
 }
 
-fn make_warning_about_missing_features(binaries: &[&Target]) -> String {
+/// Downloads a prebuilt binary of a package of the registry `source_id`.
+fn download_prebuilt_binary(
+    config: &Config,
+    source_id: SourceId,
+    prebuilt: &PrebuiltBinary,
+) -> CargoResult<Vec<u8>> {
+    let mut handle = http_handle(config)?;
+    RegistryHttpConfig::new(config, source_id)?.configure(config, &mut handle)?;
+    handle.get(true)?;
+    handle.url(&prebuilt.url)?;
+    handle.follow_location(true)?;
+    if let Some(authorization) = &prebuilt.authorization {
+        let mut headers = List::new();
+        headers.append(&format!("Authorization: {authorization}"))?;
+        handle.http_headers(headers)?;
+    }
+    let mut body = Vec::new();
+    {
+        let mut transfer = handle.transfer();
+        transfer.write_function(|buf| {
+            body.extend_from_slice(buf);
+            Ok(buf.len())
+        })?;
+        transfer
+            .perform()
+            .with_context(|| format!("failed to download from `{}`", prebuilt.url))?;
+    }
+    let code = handle.response_code()?;
+    // `file://` URLs don't have a response code.
+    if code != 200 && code != 0 {
+        bail!("failed to download from `{}`, got {code}", prebuilt.url);
+    }
+    Ok(body)
+}
+
+fn make_warning_about_missing_features(config: &Config, binaries: &[&Target]) -> String {
     let max_targets_listed = 7;
     let target_features_message = binaries
         .iter()
@@ -575,14 +740,21 @@ fn make_warning_about_missing_features(binaries: &[&Target]) -> String {
                 .iter()
                 .map(|f| format!("`{f}`"))
                 .join(", ");
-            format!("  {name} requires the features: {features}")
+            message!(
+                config,
+                "  {name} requires the features: {features}",
+                name = name,
+                features = features,
+            )
         })
         .join("\n");
 
     let additional_bins_message = if binaries.len() > max_targets_listed {
-        format!(
-            "\n{} more targets also requires features not enabled. See them in the Cargo.toml file.",
-            binaries.len() - max_targets_listed
+        message!(
+            config,
+            "\n{count} more targets also requires features not enabled. \
+             See them in the Cargo.toml file.",
+            count = binaries.len() - max_targets_listed,
         )
     } else {
         "".into()
@@ -593,11 +765,15 @@ fn make_warning_about_missing_features(binaries: &[&Target]) -> String {
         .map(|f| f.join(" "))
         .unwrap_or_default();
 
-    format!(
+    message!(
+        config,
         "\
 none of the package's binaries are available for install using the selected features
-{target_features_message}{additional_bins_message}
-Consider enabling some of the needed features by passing, e.g., `--features=\"{example_features}\"`"
+{targets}{additional}
+Consider enabling some of the needed features by passing, e.g., `--features=\"{features}\"`",
+        targets = target_features_message,
+        additional = additional_bins_message,
+        features = example_features,
     )
 }
 
@@ -610,6 +786,7 @@ pub fn install(
     opts: &ops::CompileOptions,
     force: bool,
     no_track: bool,
+    from_binary_registry: bool,
 ) -> CargoResult<()> {
     let root = resolve_root(root, config)?;
     let dst = root.join("bin").into_path_unlocked();
@@ -622,7 +799,18 @@ pub fn install(
             .map(|(k, v)| (Some(k), v))
             .unwrap_or((None, None));
         let installable_pkg = InstallablePackage::new(
-            config, root, map, krate, source_id, from_cwd, vers, opts, force, no_track, true,
+            config,
+            root,
+            map,
+            krate,
+            source_id,
+            from_cwd,
+            vers,
+            opts,
+            force,
+            no_track,
+            from_binary_registry,
+            true,
         )?;
         let mut installed_anything = true;
         if let Some(installable_pkg) = installable_pkg {
@@ -652,6 +840,7 @@ pub fn install(
                     opts,
                     force,
                     no_track,
+                    from_binary_registry,
                     !did_update,
                 ) {
                     Ok(Some(installable_pkg)) => {
@@ -695,12 +884,17 @@ pub fn install(
 
         let mut summary = vec![];
         if !succeeded.is_empty() {
-            summary.push(format!("Successfully installed {}!", succeeded.join(", ")));
+            summary.push(message!(
+                config,
+                "Successfully installed {names}!",
+                names = succeeded.join(", "),
+            ));
         }
         if !failed.is_empty() {
-            summary.push(format!(
-                "Failed to install {} (see error(s) above).",
-                failed.join(", ")
+            summary.push(message!(
+                config,
+                "Failed to install {names} (see error(s) above).",
+                names = failed.join(", "),
             ));
         }
         if !succeeded.is_empty() || !failed.is_empty() {
@@ -717,11 +911,13 @@ pub fn install(
         let dst_in_path = env::split_paths(&path).any(|path| path == dst);
 
         if !dst_in_path {
-            config.shell().warn(&format!(
-                "be sure to add `{}` to your PATH to be \
-             able to run the installed binaries",
-                dst.display()
-            ))?;
+            let msg = message!(
+                config,
+                "be sure to add `{dir}` to your PATH to be \
+                 able to run the installed binaries",
+                dir = dst.display(),
+            );
+            config.shell().warn(msg)?;
         }
     }
 
@@ -857,11 +1053,139 @@ fn parse_semver_flag(v: &str) -> CargoResult<VersionReq> {
 pub fn install_list(dst: Option<&str>, config: &Config) -> CargoResult<()> {
     let root = resolve_root(dst, config)?;
     let tracker = InstallTracker::load(config, &root)?;
+    let verbose =
+        config.cli_unstable().install_settings && config.shell().verbosity() == Verbosity::Verbose;
     for (k, v) in tracker.all_installed_bins() {
         drop_println!(config, "{}:", k);
         for bin in v {
             drop_println!(config, "    {}", bin);
         }
+        if !verbose {
+            continue;
+        }
+        // The settings recorded in the install root, to audit and reproduce
+        // the installs made outside of `$CARGO_HOME`.
+        drop_println!(config, "    source: {}", k.source_id());
+        let Some(settings) = tracker.install_settings(*k) else {
+            continue;
+        };
+        if !settings.features.is_empty() {
+            drop_println!(config, "    features: {}", settings.features.join(", "));
+        }
+        if settings.all_features {
+            drop_println!(config, "    all-features: true");
+        }
+        if settings.no_default_features {
+            drop_println!(config, "    no-default-features: true");
+        }
+        drop_println!(config, "    profile: {}", settings.profile);
+        if let Some(target) = &settings.target {
+            drop_println!(config, "    target: {}", target);
+        }
+        // The first line of `rustc -vV`.
+        if let Some(rustc) = settings.rustc.as_deref().and_then(|r| r.lines().next()) {
+            drop_println!(config, "    rustc: {}", rustc);
+        }
+    }
+    Ok(())
+}
+
+/// Reinstalls the packages with binaries which were deleted or modified
+/// outside of Cargo, with the features, profile and target they were
+/// installed with. The other settings come from `opts`.
+pub fn install_repair(
+    root: Option<&str>,
+    config: &Config,
+    opts: &ops::CompileOptions,
+) -> CargoResult<()> {
+    let root = resolve_root(root, config)?;
+    let dst = root.join("bin").into_path_unlocked();
+    let (damaged, checked) = {
+        // Unlocked before reinstalling, which locks it again.
+        let tracker = InstallTracker::load(config, &root)?;
+        tracker.damaged_installs(&dst)?
+    };
+    if damaged.is_empty() {
+        let msg = message!(
+            config,
+            "{checked} installed binaries, none were deleted or modified",
+            checked = checked,
+        );
+        config.shell().status("Checked", msg)?;
+        return Ok(());
+    }
+
+    let map = SourceConfigMap::new(config)?;
+    let host = config.load_global_rustc(None)?.host;
+    let mut failed = Vec::new();
+    for install in &damaged {
+        for (bin, deleted) in &install.bins {
+            let msg = if *deleted {
+                message!(
+                    config,
+                    "binary `{bin}` of package `{pkg}` was deleted",
+                    bin = bin,
+                    pkg = install.pkg_id,
+                )
+            } else {
+                message!(
+                    config,
+                    "binary `{bin}` of package `{pkg}` was modified",
+                    bin = bin,
+                    pkg = install.pkg_id,
+                )
+            };
+            config.shell().warn(msg)?;
+        }
+        let reinstall = || -> CargoResult<()> {
+            let mut opts = opts.clone();
+            let settings = &install.settings;
+            opts.cli_features = CliFeatures::from_command_line(
+                &settings.features,
+                settings.all_features,
+                !settings.no_default_features,
+            )?;
+            opts.build_config.requested_profile = InternedString::new(&settings.profile);
+            if let Some(target) = &settings.target {
+                opts.build_config.requested_kinds = if target == host.as_str() {
+                    vec![CompileKind::Host]
+                } else {
+                    vec![CompileKind::Target(CompileTarget::new(target)?)]
+                };
+            }
+            let source_id = install.pkg_id.source_id();
+            let vers = source_id
+                .is_registry()
+                .then(|| format!("={}", install.pkg_id.version()));
+            let installable_pkg = InstallablePackage::new(
+                config,
+                root.clone(),
+                map.clone(),
+                Some(install.pkg_id.name().as_str()),
+                source_id,
+                false,
+                vers.as_deref(),
+                &opts,
+                true,
+                false,
+                false,
+                true,
+            )?;
+            if let Some(installable_pkg) = installable_pkg {
+                installable_pkg.install_one()?;
+            }
+            Ok(())
+        };
+        if let Err(e) = reinstall() {
+            crate::display_error(&e, &mut config.shell());
+            failed.push(install.pkg_id.to_string());
+        }
+    }
+    if !failed.is_empty() {
+        bail!(
+            "failed to repair {} (see error(s) above)",
+            failed.iter().map(|p| format!("`{p}`")).join(", ")
+        );
     }
     Ok(())
 }
@@ -903,14 +1227,13 @@ fn remove_orphaned_bins(
         for bin in bins {
             let full_path = dst.join(bin);
             if full_path.exists() {
-                ws.config().shell().status(
-                    "Removing",
-                    format!(
-                        "executable `{}` from previous version {}",
-                        full_path.display(),
-                        old_pkg
-                    ),
-                )?;
+                let msg = message!(
+                    ws.config(),
+                    "executable `{path}` from previous version {pkg}",
+                    path = full_path.display(),
+                    pkg = old_pkg,
+                );
+                ws.config().shell().status("Removing", msg)?;
                 paths::remove_file(&full_path)
                     .with_context(|| format!("failed to remove {:?}", full_path))?;
             }
@@ -0,0 +1,167 @@
+//! Implementation of `cargo analyze msrv`.
+//!
+//! The dependency tree of the selected packages is resolved and turned into a
+//! unit graph as for `cargo check`, without building anything. Every package
+//! reachable from a member is then compared with the `rust-version` of the
+//! member, using its own `rust-version`, or the first Rust version supporting
+//! its edition when it has none. No other toolchain is needed, so the result
+//! is only as good as the `rust-version` fields of the dependencies.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt::Write as _;
+
+use crate::core::compiler::unit_graph::UnitGraph;
+use crate::core::compiler::{Unit, UnitInterner};
+use crate::core::{Package, PackageId, Workspace};
+use crate::message;
+use crate::ops::{create_bcx, CompileOptions};
+use crate::util::CargoResult;
+
+/// Checks that the packages built for the members selected by
+/// `compile_opts` don't require a newer rustc than the `rust-version` of
+/// these members, reporting how each offending package is pulled in.
+pub fn verify_msrv(ws: &Workspace<'_>, compile_opts: &CompileOptions) -> CargoResult<()> {
+    let config = ws.config();
+    let mut compile_opts = compile_opts.clone();
+    // The rustc running Cargo doesn't matter here.
+    compile_opts.honor_rust_version = false;
+    let interner = UnitInterner::new();
+    let bcx = create_bcx(ws, &compile_opts, &interner)?;
+
+    let mut members: Vec<&Package> = bcx.roots.iter().map(|unit| &unit.pkg).collect();
+    members.sort_by_key(|pkg| pkg.package_id());
+    members.dedup();
+    let mut errors = Vec::new();
+    for member in members {
+        let Some(msrv) = member.rust_version() else {
+            let msg = message!(
+                config,
+                "package `{pkg}` has no `rust-version`, skipping it",
+                pkg = member.package_id(),
+            );
+            config.shell().warn(msg)?;
+            continue;
+        };
+        let msrv_version = parse_rust_version(msrv);
+
+        let roots: Vec<_> = bcx
+            .roots
+            .iter()
+            .filter(|unit| unit.pkg.package_id() == member.package_id())
+            .collect();
+        let (order, parents) = reachable_units(&bcx.unit_graph, &roots);
+        let mut offending: BTreeMap<PackageId, (String, &Unit)> = BTreeMap::new();
+        for unit in order {
+            let pkg_id = unit.pkg.package_id();
+            if pkg_id == member.package_id() || offending.contains_key(&pkg_id) {
+                continue;
+            }
+            let Some((version, reason)) = required_rust_version(&unit.pkg) else {
+                continue;
+            };
+            if version > msrv_version {
+                offending.insert(pkg_id, (reason, unit));
+            }
+        }
+
+        if offending.is_empty() {
+            let msg = message!(
+                config,
+                "dependencies of `{pkg}` with its `rust-version` {msrv}",
+                pkg = member.package_id(),
+                msrv = msrv,
+            );
+            config.shell().status("Verified", msg)?;
+            continue;
+        }
+        let mut error = format!(
+            "the dependencies of `{}` are not compatible with its `rust-version` {}",
+            member.package_id(),
+            msrv
+        );
+        for (pkg_id, (reason, unit)) in offending {
+            write!(error, "\n\npackage `{pkg_id}` requires rustc {reason}").unwrap();
+            for parent in chain(&parents, unit) {
+                write!(
+                    error,
+                    "\n    ... which is a dependency of package `{parent}`"
+                )
+                .unwrap();
+            }
+        }
+        errors.push(error);
+    }
+
+    if !errors.is_empty() {
+        anyhow::bail!("{}", errors.join("\n\n"));
+    }
+    Ok(())
+}
+
+/// Walks the unit graph breadth-first from `roots`, returning the reachable
+/// units in the order they were reached, along with the unit each one was
+/// first reached from, so that following the parents gives the shortest
+/// chain back to a root.
+fn reachable_units<'a>(
+    unit_graph: &'a UnitGraph,
+    roots: &[&'a Unit],
+) -> (Vec<&'a Unit>, HashMap<&'a Unit, Option<&'a Unit>>) {
+    let mut parents = HashMap::new();
+    let mut order = Vec::new();
+    let mut queue = VecDeque::new();
+    for &root in roots {
+        if parents.insert(root, None).is_none() {
+            queue.push_back(root);
+        }
+    }
+    while let Some(unit) = queue.pop_front() {
+        order.push(unit);
+        let mut deps: Vec<_> = unit_graph[unit].iter().map(|dep| &dep.unit).collect();
+        deps.sort();
+        for dep in deps {
+            if !parents.contains_key(dep) {
+                parents.insert(dep, Some(unit));
+                queue.push_back(dep);
+            }
+        }
+    }
+    (order, parents)
+}
+
+/// The packages pulling in `unit`, from its direct dependent up to the root.
+fn chain<'a>(parents: &HashMap<&'a Unit, Option<&'a Unit>>, unit: &'a Unit) -> Vec<PackageId> {
+    let mut chain: Vec<PackageId> = Vec::new();
+    let mut current = unit;
+    while let Some(parent) = parents[current] {
+        let pkg_id = parent.pkg.package_id();
+        // Build scripts and libraries of a package are separate units.
+        if pkg_id != unit.pkg.package_id() && chain.last() != Some(&pkg_id) {
+            chain.push(pkg_id);
+        }
+        current = parent;
+    }
+    chain
+}
+
+/// The oldest rustc `pkg` supports, with a description of where it comes
+/// from, or `None` if it supports any.
+fn required_rust_version(pkg: &Package) -> Option<(semver::Version, String)> {
+    if let Some(rust_version) = pkg.rust_version() {
+        return Some((parse_rust_version(rust_version), rust_version.to_string()));
+    }
+    let edition = pkg.manifest().edition();
+    let version = edition.first_version()?;
+    let reason = format!("{}.{} (edition {})", version.major, version.minor, edition);
+    Some((version, reason))
+}
+
+/// Parses a `rust-version` like "1.56" or "1.56.1", which was validated when
+/// loading the manifest.
+fn parse_rust_version(rust_version: &str) -> semver::Version {
+    let mut parts = rust_version.split('.').map(|p| p.parse().unwrap_or(0));
+    semver::Version::new(
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+    )
+}
@@ -1,15 +1,17 @@
 use crate::core::{Edition, Shell, Workspace};
+use crate::message;
 use crate::util::errors::CargoResult;
 use crate::util::important_paths::find_root_manifest_for_wd;
-use crate::util::{existing_vcs_repo, FossilRepo, GitRepo, HgRepo, PijulRepo};
+use crate::util::{
+    existing_vcs_repo, FossilRepo, GitRepo, HgRepo, IgnoreList, JjRepo, PijulRepo, Vcs,
+};
 use crate::util::{restricted_names, Config};
-use anyhow::{anyhow, Context as _};
+use anyhow::Context as _;
 use cargo_util::paths;
 use serde::de;
 use serde::Deserialize;
 use std::collections::BTreeMap;
 use std::ffi::OsStr;
-use std::io::{BufRead, BufReader, ErrorKind};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::{fmt, slice};
@@ -18,6 +20,7 @@ use std::{fmt, slice};
 pub enum VersionControl {
     Git,
     Hg,
+    Jj,
     Pijul,
     Fossil,
     NoVcs,
@@ -30,6 +33,7 @@ impl FromStr for VersionControl {
         match s {
             "git" => Ok(VersionControl::Git),
             "hg" => Ok(VersionControl::Hg),
+            "jj" => Ok(VersionControl::Jj),
             "pijul" => Ok(VersionControl::Pijul),
             "fossil" => Ok(VersionControl::Fossil),
             "none" => Ok(VersionControl::NoVcs),
@@ -38,6 +42,29 @@ impl FromStr for VersionControl {
     }
 }
 
+impl VersionControl {
+    /// The VCSes that can be detected in an existing directory.
+    const DETECTED: [VersionControl; 5] = [
+        VersionControl::Git,
+        VersionControl::Hg,
+        VersionControl::Jj,
+        VersionControl::Pijul,
+        VersionControl::Fossil,
+    ];
+
+    /// The implementation of this VCS, `None` for `NoVcs`.
+    fn vcs(self) -> Option<&'static dyn Vcs> {
+        match self {
+            VersionControl::Git => Some(&GitRepo),
+            VersionControl::Hg => Some(&HgRepo),
+            VersionControl::Jj => Some(&JjRepo),
+            VersionControl::Pijul => Some(&PijulRepo),
+            VersionControl::Fossil => Some(&FossilRepo),
+            VersionControl::NoVcs => None,
+        }
+    }
+}
+
 impl<'de> de::Deserialize<'de> for VersionControl {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -214,12 +241,14 @@ fn check_name(
                 name_help
             );
         } else {
-            shell.warn(format!(
-                "the name `{}` will not support binary \
+            let msg = message!(
+                shell = shell,
+                "the name `{name}` will not support binary \
                 executables with that name, \
                 it conflicts with cargo's build directory names",
-                name
-            ))?;
+                name = name,
+            );
+            shell.warn(msg)?;
         }
     }
     if name == "test" {
@@ -230,12 +259,14 @@ fn check_name(
         );
     }
     if ["core", "std", "alloc", "proc_macro", "proc-macro"].contains(&name) {
-        shell.warn(format!(
-            "the name `{}` is part of Rust's standard library\n\
-            It is recommended to use a different name to avoid problems.{}",
-            name,
-            bin_help()
-        ))?;
+        let msg = message!(
+            shell = shell,
+            "the name `{name}` is part of Rust's standard library\n\
+            It is recommended to use a different name to avoid problems.{help}",
+            name = name,
+            help = bin_help(),
+        );
+        shell.warn(msg)?;
     }
     if restricted_names::is_windows_reserved(name) {
         if cfg!(windows) {
@@ -245,19 +276,23 @@ fn check_name(
                 name_help
             );
         } else {
-            shell.warn(format!(
-                "the name `{}` is a reserved Windows filename\n\
+            let msg = message!(
+                shell = shell,
+                "the name `{name}` is a reserved Windows filename\n\
                 This package will not work on Windows platforms.",
-                name
-            ))?;
+                name = name,
+            );
+            shell.warn(msg)?;
         }
     }
     if restricted_names::is_non_ascii_name(name) {
-        shell.warn(format!(
-            "the name `{}` contains non-ASCII characters\n\
+        let msg = message!(
+            shell = shell,
+            "the name `{name}` contains non-ASCII characters\n\
             Non-ASCII crate names are not supported by Rust.",
-            name
-        ))?;
+            name = name,
+        );
+        shell.warn(msg)?;
     }
 
     Ok(())
@@ -268,10 +303,13 @@ fn check_path(path: &Path, shell: &mut Shell) -> CargoResult<()> {
     // warn if the path contains characters that will break `env::join_paths`
     if let Err(_) = paths::join_paths(slice::from_ref(&OsStr::new(path)), "") {
         let path = path.to_string_lossy();
-        shell.warn(format!(
+        let msg = message!(
+            shell = shell,
             "the path `{path}` contains invalid PATH characters (usually `:`, `;`, or `\"`)\n\
-            It is recommended to use a different name to avoid problems."
-        ))?;
+            It is recommended to use a different name to avoid problems.",
+            path = path,
+        );
+        shell.warn(msg)?;
     }
     Ok(())
 }
@@ -495,10 +533,13 @@ pub fn init(opts: &NewOptions, config: &Config) -> CargoResult<NewProjectKind> {
         } else {
             NewProjectKind::Lib
         };
-        config.shell().warn(format!(
-            "file `{}` seems to be a {} file",
-            src_paths_types[0].relative_path, file_type
-        ))?;
+        let msg = message!(
+            config,
+            "file `{path}` seems to be a {kind} file",
+            path = src_paths_types[0].relative_path,
+            kind = file_type,
+        );
+        config.shell().warn(msg)?;
         src_paths_types[0].bin = has_bin
     } else if src_paths_types.len() > 1 && !has_bin {
         // We have found both lib and bin files and the user would like us to treat both as libs
@@ -516,37 +557,25 @@ pub fn init(opts: &NewOptions, config: &Config) -> CargoResult<NewProjectKind> {
     let mut version_control = opts.version_control;
 
     if version_control == None {
-        let mut num_detected_vcses = 0;
-
-        if path.join(".git").exists() {
-            version_control = Some(VersionControl::Git);
-            num_detected_vcses += 1;
-        }
-
-        if path.join(".hg").exists() {
-            version_control = Some(VersionControl::Hg);
-            num_detected_vcses += 1;
-        }
-
-        if path.join(".pijul").exists() {
-            version_control = Some(VersionControl::Pijul);
-            num_detected_vcses += 1;
-        }
-
-        if path.join(".fossil").exists() {
-            version_control = Some(VersionControl::Fossil);
-            num_detected_vcses += 1;
+        let mut detected: Vec<_> = VersionControl::DETECTED
+            .into_iter()
+            .filter(|vcs| path.join(vcs.vcs().unwrap().marker()).exists())
+            .collect();
+        // A Jujutsu repository colocated with git has both `.jj` and `.git`.
+        if detected.contains(&VersionControl::Jj) {
+            detected.retain(|vcs| *vcs != VersionControl::Git);
         }
 
         // if none exists, maybe create git, like in `cargo new`
 
-        if num_detected_vcses > 1 {
+        if detected.len() > 1 {
             anyhow::bail!(
-                "more than one of .hg, .git, .pijul, .fossil configurations \
+                "more than one of .hg, .git, .jj, .pijul, .fossil configurations \
                  found and the ignore file can't be filled in as \
                  a result. specify --vcs to override detection"
             );
         }
+        version_control = detected.pop();
     }
 
     let mkopts = MkOptions {
@@ -569,166 +598,18 @@ pub fn init(opts: &NewOptions, config: &Config) -> CargoResult<NewProjectKind> {
     Ok(kind)
 }
 
-/// IgnoreList
-struct IgnoreList {
-    /// git like formatted entries
-    ignore: Vec<String>,
-    /// mercurial formatted entries
-    hg_ignore: Vec<String>,
-    /// Fossil-formatted entries.
-    fossil_ignore: Vec<String>,
-}
-
-impl IgnoreList {
-    /// constructor to build a new ignore file
-    fn new() -> IgnoreList {
-        IgnoreList {
-            ignore: Vec::new(),
-            hg_ignore: Vec::new(),
-            fossil_ignore: Vec::new(),
-        }
-    }
-
-    /// Add a new entry to the ignore list. Requires three arguments with the
-    /// entry in possibly three different formats. One for "git style" entries,
-    /// one for "mercurial style" entries and one for "fossil style" entries.
-    fn push(&mut self, ignore: &str, hg_ignore: &str, fossil_ignore: &str) {
-        self.ignore.push(ignore.to_string());
-        self.hg_ignore.push(hg_ignore.to_string());
-        self.fossil_ignore.push(fossil_ignore.to_string());
-    }
-
-    /// Return the correctly formatted content of the ignore file for the given
-    /// version control system as `String`.
-    fn format_new(&self, vcs: VersionControl) -> String {
-        let ignore_items = match vcs {
-            VersionControl::Hg => &self.hg_ignore,
-            VersionControl::Fossil => &self.fossil_ignore,
-            _ => &self.ignore,
-        };
-
-        ignore_items.join("\n") + "\n"
-    }
-
-    /// format_existing is used to format the IgnoreList when the ignore file
-    /// already exists. It reads the contents of the given `BufRead` and
-    /// checks if the contents of the ignore list are already existing in the
-    /// file.
-    fn format_existing<T: BufRead>(&self, existing: T, vcs: VersionControl) -> CargoResult<String> {
-        let mut existing_items = Vec::new();
-        for (i, item) in existing.lines().enumerate() {
-            match item {
-                Ok(s) => existing_items.push(s),
-                Err(err) => match err.kind() {
-                    ErrorKind::InvalidData => {
-                        return Err(anyhow!(
-                            "Character at line {} is invalid. Cargo only supports UTF-8.",
-                            i
-                        ))
-                    }
-                    _ => return Err(anyhow!(err)),
-                },
-            }
-        }
-
-        let ignore_items = match vcs {
-            VersionControl::Hg => &self.hg_ignore,
-            VersionControl::Fossil => &self.fossil_ignore,
-            _ => &self.ignore,
-        };
-
-        let mut out = String::new();
-
-        // Fossil does not support `#` comments.
-        if vcs != VersionControl::Fossil {
-            out.push_str("\n\n# Added by cargo\n");
-            if ignore_items
-                .iter()
-                .any(|item| existing_items.contains(item))
-            {
-                out.push_str("#\n# already existing elements were commented out\n");
-            }
-            out.push('\n');
-        }
-
-        for item in ignore_items {
-            if existing_items.contains(item) {
-                if vcs == VersionControl::Fossil {
-                    // Just merge for Fossil.
-                    continue;
-                }
-                out.push('#');
-            }
-            out.push_str(item);
-            out.push('\n');
-        }
-
-        Ok(out)
-    }
-}
-
-/// Writes the ignore file to the given directory. If the ignore file for the
-/// given vcs system already exists, its content is read and duplicate ignore
-/// file entries are filtered out.
-fn write_ignore_file(base_path: &Path, list: &IgnoreList, vcs: VersionControl) -> CargoResult<()> {
-    // Fossil only supports project-level settings in a dedicated subdirectory.
-    if vcs == VersionControl::Fossil {
-        paths::create_dir_all(base_path.join(".fossil-settings"))?;
-    }
-
-    for fp_ignore in match vcs {
-        VersionControl::Git => vec![base_path.join(".gitignore")],
-        VersionControl::Hg => vec![base_path.join(".hgignore")],
-        VersionControl::Pijul => vec![base_path.join(".ignore")],
-        // Fossil has a cleaning functionality configured in a separate file.
-        VersionControl::Fossil => vec![
-            base_path.join(".fossil-settings/ignore-glob"),
-            base_path.join(".fossil-settings/clean-glob"),
-        ],
-        VersionControl::NoVcs => return Ok(()),
-    } {
-        let ignore: String = match paths::open(&fp_ignore) {
-            Err(err) => match err.downcast_ref::<std::io::Error>() {
-                Some(io_err) if io_err.kind() == ErrorKind::NotFound => list.format_new(vcs),
-                _ => return Err(err),
-            },
-            Ok(file) => list.format_existing(BufReader::new(file), vcs)?,
-        };
-
-        paths::append(&fp_ignore, ignore.as_bytes())?;
-    }
-
-    Ok(())
-}
-
-/// Initializes the correct VCS system based on the provided config.
 fn init_vcs(path: &Path, vcs: VersionControl, config: &Config) -> CargoResult<()> {
-    match vcs {
-        VersionControl::Git => {
-            if !path.join(".git").exists() {
+    match vcs.vcs() {
+        Some(vcs) => {
+            if !path.join(vcs.marker()).exists() {
                 // Temporary fix to work around bug in libgit2 when creating a
                 // directory in the root of a posix filesystem.
                 // See: https://github.com/libgit2/libgit2/issues/5130
                 paths::create_dir_all(path)?;
-                GitRepo::init(path, config.cwd())?;
+                vcs.init(path, config.cwd())?;
             }
         }
-        VersionControl::Hg => {
-            if !path.join(".hg").exists() {
-                HgRepo::init(path, config.cwd())?;
-            }
-        }
-        VersionControl::Pijul => {
-            if !path.join(".pijul").exists() {
-                PijulRepo::init(path, config.cwd())?;
-            }
-        }
-        VersionControl::Fossil => {
-            if !path.join(".fossil").exists() {
-                FossilRepo::init(path, config.cwd())?;
-            }
-        }
-        VersionControl::NoVcs => {
+        None => {
             paths::create_dir_all(path)?;
         }
     };
@@ -750,7 +631,7 @@ fn mk(config: &Config, opts: &MkOptions<'_>) -> CargoResult<()> {
     }
 
     let vcs = opts.version_control.unwrap_or_else(|| {
-        let in_existing_vcs = existing_vcs_repo(path.parent().unwrap_or(path), config.cwd());
+        let in_existing_vcs = existing_vcs_repo(path.parent().unwrap_or(path), config);
         match (cfg.version_control, in_existing_vcs) {
             (None, false) => VersionControl::Git,
             (Some(opt), false) => opt,
@@ -759,7 +640,9 @@ fn mk(config: &Config, opts: &MkOptions<'_>) -> CargoResult<()> {
     });
 
     init_vcs(path, vcs, config)?;
-    write_ignore_file(path, &ignore, vcs)?;
+    if let Some(vcs) = vcs.vcs() {
+        ignore.write(path, vcs)?;
+    }
 
     // Create `Cargo.toml` file with necessary `[lib]` and `[[bin]]` sections, if needed.
     let mut manifest = toml_edit::Document::new();
@@ -879,7 +762,7 @@ mod tests {
                 .arg(&path_of_source_file)
                 .exec_with_output()
             {
-                log::warn!("failed to call rustfmt: {:#}", e);
+                tracing::warn!("failed to call rustfmt: {:#}", e);
             }
         }
     }
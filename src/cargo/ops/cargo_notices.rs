@@ -0,0 +1,168 @@
+//! Implementation of `cargo report notices`.
+//!
+//! The dependency tree of the selected packages is resolved and turned into a
+//! unit graph as for `cargo build`, without building anything, which also
+//! downloads their sources. The packages linked into the artifacts are the
+//! ones reachable from the root units without going through a build script or
+//! a proc-macro, which only run while compiling. The license files found at
+//! the root of each of them, besides the workspace members, are then printed
+//! as a single notices document.
+
+use std::collections::{BTreeMap, HashSet};
+
+use anyhow::Context as _;
+use cargo_util::paths;
+use serde::Serialize;
+
+use crate::core::compiler::unit_graph::UnitGraph;
+use crate::core::compiler::{Unit, UnitInterner};
+use crate::core::{Package, PackageId, Workspace};
+use crate::message;
+use crate::ops::{create_bcx, CompileOptions};
+use crate::util::CargoResult;
+use crate::{drop_print, drop_println};
+
+/// File name prefixes of license files, compared case-insensitively.
+const LICENSE_FILE_PREFIXES: &[&str] = &["license", "licence", "copying", "notice"];
+
+pub struct NoticesOptions {
+    pub compile_opts: CompileOptions,
+    /// Print the notices as JSON instead of a text document.
+    pub json: bool,
+}
+
+#[derive(Serialize)]
+struct Notices<'a> {
+    packages: Vec<PackageNotice<'a>>,
+}
+
+#[derive(Serialize)]
+struct PackageNotice<'a> {
+    id: PackageId,
+    name: &'a str,
+    version: String,
+    license: Option<&'a str>,
+    license_files: Vec<LicenseFile>,
+}
+
+#[derive(Serialize)]
+struct LicenseFile {
+    /// The path of the file, relative to the root of the package.
+    path: String,
+    text: String,
+}
+
+/// Prints the license files of the third-party packages linked into the
+/// artifacts of the packages selected by `options`.
+pub fn report_notices(ws: &Workspace<'_>, options: &NoticesOptions) -> CargoResult<()> {
+    let config = ws.config();
+    let interner = UnitInterner::new();
+    let bcx = create_bcx(ws, &options.compile_opts, &interner)?;
+
+    let mut packages = BTreeMap::new();
+    for pkg in linked_packages(&bcx.unit_graph, &bcx.roots) {
+        if ws.is_member(pkg) {
+            continue;
+        }
+        packages.insert(pkg.package_id(), pkg);
+    }
+
+    let mut notices = Vec::new();
+    for (id, pkg) in packages {
+        let license_files = license_files(pkg)?;
+        if license_files.is_empty() {
+            let msg = message!(config, "no license file found in package `{id}`", id = id);
+            config.shell().warn(msg)?;
+        }
+        let metadata = pkg.manifest().metadata();
+        notices.push(PackageNotice {
+            id,
+            name: id.name().as_str(),
+            version: id.version().to_string(),
+            license: metadata.license.as_deref(),
+            license_files,
+        });
+    }
+
+    if options.json {
+        let notices = Notices { packages: notices };
+        drop_println!(config, "{}", serde_json::to_string(&notices)?);
+    } else {
+        drop_print!(config, "{}", format_notices(&notices));
+    }
+    Ok(())
+}
+
+/// The packages of the units reachable from `roots`, without going through
+/// build scripts and proc-macros.
+fn linked_packages<'a>(unit_graph: &'a UnitGraph, roots: &'a [Unit]) -> Vec<&'a Package> {
+    let mut visited = HashSet::new();
+    let mut stack: Vec<&Unit> = roots.iter().collect();
+    let mut packages = Vec::new();
+    while let Some(unit) = stack.pop() {
+        if unit.target.is_custom_build() || unit.target.proc_macro() {
+            continue;
+        }
+        if !visited.insert(unit) {
+            continue;
+        }
+        packages.push(&unit.pkg);
+        stack.extend(unit_graph[unit].iter().map(|dep| &dep.unit));
+    }
+    packages
+}
+
+/// Reads the license files at the root of `pkg`, and its `license-file`.
+fn license_files(pkg: &Package) -> CargoResult<Vec<LicenseFile>> {
+    // Keyed by absolute path, as the `license-file` may be one of the others.
+    let mut found = BTreeMap::new();
+    let cx = || format!("failed to read directory `{}`", pkg.root().display());
+    for entry in std::fs::read_dir(pkg.root()).with_context(cx)? {
+        let entry = entry.with_context(cx)?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let lowercase = name.to_lowercase();
+        if entry.file_type().with_context(cx)?.is_file()
+            && LICENSE_FILE_PREFIXES
+                .iter()
+                .any(|prefix| lowercase.starts_with(prefix))
+        {
+            found.insert(entry.path(), name);
+        }
+    }
+    if let Some(license_file) = &pkg.manifest().metadata().license_file {
+        let path = paths::normalize_path(&pkg.root().join(license_file));
+        if path.is_file() {
+            found
+                .entry(path)
+                .or_insert_with(|| license_file.replace('\\', "/"));
+        }
+    }
+
+    let mut files = Vec::new();
+    for (abs_path, path) in found {
+        let text = paths::read_bytes(&abs_path)?;
+        files.push(LicenseFile {
+            path,
+            text: String::from_utf8_lossy(&text).trim_end().to_string(),
+        });
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+fn format_notices(notices: &[PackageNotice<'_>]) -> String {
+    let heavy = "=".repeat(80);
+    let light = "-".repeat(80);
+    let mut out = String::from("THIRD-PARTY SOFTWARE NOTICES\n");
+    for notice in notices {
+        out.push_str(&format!("\n{heavy}\n{}\n", notice.id));
+        out.push_str(&format!(
+            "License: {}\n",
+            notice.license.unwrap_or("not specified")
+        ));
+        for file in &notice.license_files {
+            out.push_str(&format!("{light}\n{}:\n\n{}\n", file.path, file.text));
+        }
+    }
+    out
+}
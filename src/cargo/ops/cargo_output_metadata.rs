@@ -2,12 +2,15 @@ use crate::core::compiler::artifact::match_artifacts_kind_with_targets;
 use crate::core::compiler::{CompileKind, RustcTargetData};
 use crate::core::dependency::DepKind;
 use crate::core::package::SerializedPackage;
-use crate::core::resolver::{features::CliFeatures, HasDevUnits, Resolve};
-use crate::core::{Package, PackageId, Workspace};
+use crate::core::resolver::features::{CliFeatures, FeaturesFor};
+use crate::core::resolver::{HasDevUnits, Provenance, Resolve};
+use crate::core::{Feature, Package, PackageId, Workspace};
+use crate::ops::resolve::WorkspaceResolve;
 use crate::ops::{self, Packages};
 use crate::util::interning::InternedString;
 use crate::util::CargoResult;
-use cargo_platform::Platform;
+use anyhow::Context as _;
+use cargo_platform::{Cfg, Platform};
 use serde::Serialize;
 use std::collections::BTreeMap;
 use std::path::PathBuf;
@@ -19,6 +22,13 @@ pub struct OutputMetadataOptions {
     pub no_deps: bool,
     pub version: u32,
     pub filter_platforms: Vec<String>,
+    /// The `<TARGET>[,<CFG>...]` of `--resolve-for`, to report what is
+    /// activated when building for the target, a triple or `host`. The `cfg`
+    /// values are added to the ones of `--cfg`.
+    pub resolve_for: Option<String>,
+    /// The `cfg` values of `--cfg`, to evaluate platform-specific
+    /// dependencies against instead of the ones of the targets.
+    pub cfgs: Vec<Cfg>,
 }
 
 /// Loads the manifest, resolves the dependencies of the package to the concrete
@@ -32,19 +42,42 @@ pub fn output_metadata(ws: &Workspace<'_>, opt: &OutputMetadataOptions) -> Cargo
             VERSION
         );
     }
-    let (packages, resolve) = if opt.no_deps {
-        let packages = ws.members().map(|pkg| pkg.serialized()).collect();
-        (packages, None)
+    // Everything is listed by package ID, so that the output doesn't depend
+    // on the order of `workspace.members`, or on hash map iteration order.
+    let mut members: Vec<_> = ws.members().collect();
+    members.sort_unstable_by_key(|pkg| pkg.package_id());
+    let mut default_members: Vec<_> = ws.default_members().map(|pkg| pkg.package_id()).collect();
+    default_members.sort_unstable();
+
+    let (packages, resolve, activated) = if opt.no_deps {
+        let packages = members
+            .iter()
+            .map(|pkg| pkg.serialized(ws.config().cli_unstable()))
+            .collect();
+        (packages, None, None)
     } else {
-        let (packages, resolve) = build_resolve_graph(ws, opt)?;
-        (packages, Some(resolve))
+        let (packages, resolve, activated) = build_resolve_graph(ws, opt)?;
+        (packages, Some(resolve), activated)
     };
 
+    let workspace_member_globs = ws
+        .unstable_features()
+        .is_enabled(Feature::negative_member_globs())
+        .then(|| {
+            ws.members()
+                .filter_map(|pkg| {
+                    Some((pkg.package_id(), ws.matching_member_glob(pkg)?.to_string()))
+                })
+                .collect()
+        });
+
     Ok(ExportInfo {
         packages,
-        workspace_members: ws.members().map(|pkg| pkg.package_id()).collect(),
-        workspace_default_members: ws.default_members().map(|pkg| pkg.package_id()).collect(),
+        workspace_members: members.iter().map(|pkg| pkg.package_id()).collect(),
+        workspace_member_globs,
+        workspace_default_members: default_members,
         resolve,
+        activated,
         target_directory: ws.target_dir().into_path_unlocked(),
         version: VERSION,
         workspace_root: ws.root().to_path_buf(),
@@ -59,8 +92,15 @@ pub fn output_metadata(ws: &Workspace<'_>, opt: &OutputMetadataOptions) -> Cargo
 pub struct ExportInfo {
     packages: Vec<SerializedPackage>,
     workspace_members: Vec<PackageId>,
+    /// The entry of `workspace.members` that matched each member, only
+    /// included with `cargo-features = ["negative-member-globs"]`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    workspace_member_globs: Option<BTreeMap<PackageId, String>>,
     workspace_default_members: Vec<PackageId>,
     resolve: Option<MetadataResolve>,
+    /// What is activated when building for the target of `--resolve-for`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    activated: Option<MetadataActivated>,
     target_directory: PathBuf,
     version: u32,
     workspace_root: PathBuf,
@@ -73,12 +113,65 @@ struct MetadataResolve {
     root: Option<PackageId>,
 }
 
+/// The packages, features and dependencies a build for a single target
+/// activates, as computed by the feature resolver.
+#[derive(Serialize)]
+struct MetadataActivated {
+    target: String,
+    /// The `cfg` values of the target, which `target.'cfg(..)'` dependencies
+    /// are matched against.
+    cfgs: Vec<String>,
+    packages: Vec<ActivatedPackage>,
+}
+
+#[derive(Serialize)]
+struct ActivatedPackage {
+    id: PackageId,
+    /// Whether the package is built for the host, as a build dependency or
+    /// proc-macro, or a dependency of one.
+    host: bool,
+    features: Vec<InternedString>,
+    dependencies: Vec<PackageId>,
+}
+
 #[derive(Serialize)]
 struct MetadataResolveNode {
     id: PackageId,
     dependencies: Vec<PackageId>,
     deps: Vec<Dep>,
     features: Vec<InternedString>,
+    /// Set when the package is from a `[patch]` or `[replace]` entry.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    provenance: Option<MetadataProvenance>,
+}
+
+#[derive(Serialize)]
+struct MetadataProvenance {
+    /// `patch` or `replace`.
+    kind: &'static str,
+    /// The URL of the source the package is used instead of.
+    source: String,
+    /// The package replaced by a `[replace]` entry.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    replaces: Option<PackageId>,
+}
+
+impl From<Provenance> for MetadataProvenance {
+    fn from(provenance: Provenance) -> MetadataProvenance {
+        let source = provenance.source_url().to_string();
+        match provenance {
+            Provenance::Patch(_) => MetadataProvenance {
+                kind: "patch",
+                source,
+                replaces: None,
+            },
+            Provenance::Replace(original) => MetadataProvenance {
+                kind: "replace",
+                source,
+                replaces: Some(original),
+            },
+        }
+    }
 }
 
 #[derive(Serialize)]
@@ -121,15 +214,42 @@ struct DepKindInfo {
 fn build_resolve_graph(
     ws: &Workspace<'_>,
     metadata_opts: &OutputMetadataOptions,
-) -> CargoResult<(Vec<SerializedPackage>, MetadataResolve)> {
+) -> CargoResult<(
+    Vec<SerializedPackage>,
+    MetadataResolve,
+    Option<MetadataActivated>,
+)> {
+    // `--resolve-for` filters like `--filter-platform` does.
+    let mut cfgs = metadata_opts.cfgs.clone();
+    let filter_platforms = match &metadata_opts.resolve_for {
+        Some(resolve_for) => {
+            let mut parts = resolve_for.split(',');
+            let target = parts.next().unwrap();
+            for cfg in parts {
+                cfgs.push(cfg.parse().with_context(|| {
+                    format!("invalid `cfg` value `{cfg}` in `--resolve-for {resolve_for}`")
+                })?);
+            }
+            let triple = if target == "host" {
+                ws.config().load_global_rustc(Some(ws))?.host.to_string()
+            } else {
+                target.to_string()
+            };
+            vec![triple]
+        }
+        None => metadata_opts.filter_platforms.clone(),
+    };
     // TODO: Without --filter-platform, features are being resolved for `host` only.
     // How should this work?
-    let requested_kinds =
-        CompileKind::from_requested_targets(ws.config(), &metadata_opts.filter_platforms)?;
-    let target_data = RustcTargetData::new(ws, &requested_kinds)?;
+    let requested_kinds = CompileKind::from_requested_targets(ws.config(), &filter_platforms)?;
+    let target_data = if cfgs.is_empty() {
+        RustcTargetData::new(ws, &requested_kinds)?
+    } else {
+        RustcTargetData::with_cfgs(ws, &requested_kinds, cfgs)?
+    };
     // Resolve entire workspace.
     let specs = Packages::All.to_package_id_specs(ws)?;
-    let force_all = if metadata_opts.filter_platforms.is_empty() {
+    let force_all = if filter_platforms.is_empty() {
         crate::core::resolver::features::ForceAllTargets::Yes
     } else {
         crate::core::resolver::features::ForceAllTargets::No
@@ -167,18 +287,45 @@ fn build_resolve_graph(
             &requested_kinds,
         )?;
     }
+    let activated = match &metadata_opts.resolve_for {
+        Some(_) => {
+            let kind = requested_kinds[0];
+            let mut walker = ActivatedWalker {
+                ws,
+                ws_resolve: &ws_resolve,
+                package_map: &package_map,
+                target_data: &target_data,
+                kind,
+                activated: BTreeMap::new(),
+            };
+            for member_pkg in ws.members() {
+                walker.visit(member_pkg.package_id(), false);
+            }
+            Some(MetadataActivated {
+                target: filter_platforms[0].clone(),
+                cfgs: target_data
+                    .cfg(kind)
+                    .iter()
+                    .map(|cfg| cfg.to_string())
+                    .collect(),
+                packages: walker.activated.into_values().collect(),
+            })
+        }
+        None => None,
+    };
+
     // Get a Vec of Packages.
     let actual_packages = package_map
         .into_iter()
         .filter_map(|(pkg_id, pkg)| node_map.get(&pkg_id).map(|_| pkg))
-        .map(|pkg| pkg.serialized())
+        .map(|pkg| pkg.serialized(ws.config().cli_unstable()))
         .collect();
 
     let mr = MetadataResolve {
         nodes: node_map.into_iter().map(|(_pkg_id, node)| node).collect(),
         root: ws.current_opt().map(|pkg| pkg.package_id()),
     };
-    Ok((actual_packages, mr))
+    Ok((actual_packages, mr, activated))
 }
 
 fn build_resolve_graph_r(
@@ -329,6 +476,7 @@ fn build_resolve_graph_r(
         dependencies: dumb_deps,
         deps,
         features,
+        provenance: resolve.provenance(pkg_id).map(MetadataProvenance::from),
     };
     node_map.insert(pkg_id, node);
     for dep_id in to_visit {
@@ -344,3 +492,77 @@ fn build_resolve_graph_r(
 
     Ok(())
 }
+
+/// Walks the packages a build for a single target activates, for
+/// `--resolve-for`.
+///
+/// This filters dependencies like [`unit_dependencies`] does, though for
+/// whole packages instead of single targets.
+///
+/// [`unit_dependencies`]: crate::core::compiler::unit_dependencies
+struct ActivatedWalker<'a, 'cfg> {
+    ws: &'a Workspace<'cfg>,
+    ws_resolve: &'a WorkspaceResolve<'cfg>,
+    package_map: &'a BTreeMap<PackageId, Package>,
+    target_data: &'a RustcTargetData<'cfg>,
+    kind: CompileKind,
+    activated: BTreeMap<(PackageId, bool), ActivatedPackage>,
+}
+
+impl ActivatedWalker<'_, '_> {
+    /// Adds `pkg_id` with the features and dependencies the feature resolver
+    /// activated for it, when built for the target, or for the host if `host`
+    /// is set, and then its dependencies.
+    fn visit(&mut self, pkg_id: PackageId, host: bool) {
+        if self.activated.contains_key(&(pkg_id, host)) {
+            return;
+        }
+        // See `build_resolve_graph_r` for why IDs are normalized.
+        let normalize_id = |id| -> PackageId { *self.package_map.get_key_value(&id).unwrap().0 };
+        let features_for = FeaturesFor::from_for_host(host);
+        let resolved_features = &self.ws_resolve.resolved_features;
+        let is_member = self.ws.is_member(&self.package_map[&pkg_id]);
+        let mut to_visit = Vec::new();
+        for (dep_id, deps) in self.ws_resolve.targeted_resolve.deps(pkg_id) {
+            for dep in deps.iter() {
+                // Dev-dependencies are only built for workspace members.
+                if dep.kind() == DepKind::Development && !is_member {
+                    continue;
+                }
+                let dep_kind = if host || dep.is_build() {
+                    CompileKind::Host
+                } else {
+                    self.kind
+                };
+                if !self.target_data.dep_platform_activated(dep, dep_kind) {
+                    continue;
+                }
+                if dep.is_optional()
+                    && !resolved_features.is_dep_activated(pkg_id, features_for, dep.name_in_toml())
+                {
+                    continue;
+                }
+                let dep_host =
+                    dep_kind == CompileKind::Host || self.package_map[&dep_id].proc_macro();
+                to_visit.push((dep_id, dep_host));
+            }
+        }
+        to_visit.sort();
+        to_visit.dedup();
+        let mut dependencies: Vec<_> = to_visit
+            .iter()
+            .map(|(dep_id, _)| normalize_id(*dep_id))
+            .collect();
+        dependencies.dedup();
+        let package = ActivatedPackage {
+            id: normalize_id(pkg_id),
+            host,
+            features: resolved_features.activated_features(pkg_id, features_for),
+            dependencies,
+        };
+        self.activated.insert((pkg_id, host), package);
+        for (dep_id, dep_host) in to_visit {
+            self.visit(dep_id, dep_host);
+        }
+    }
+}
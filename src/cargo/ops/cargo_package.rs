@@ -1,4 +1,4 @@
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs::{self, File};
 use std::io::prelude::*;
 use std::io::SeekFrom;
@@ -8,29 +8,35 @@ use std::sync::Arc;
 use std::task::Poll;
 
 use crate::core::compiler::{BuildConfig, CompileMode, DefaultExecutor, Executor};
+use crate::core::dependency::DepKind;
 use crate::core::resolver::CliFeatures;
 use crate::core::{registry::PackageRegistry, resolver::HasDevUnits};
+use crate::core::{Dependency, Package, PackageId, PackageSet, Resolve, SourceId};
 use crate::core::{Feature, Shell, Verbosity, Workspace};
-use crate::core::{Package, PackageId, PackageSet, Resolve, SourceId};
 use crate::sources::PathSource;
 use crate::util::config::JobsConfig;
 use crate::util::errors::CargoResult;
 use crate::util::toml::TomlManifest;
-use crate::util::{self, human_readable_bytes, restricted_names, Config, FileLock};
-use crate::{drop_println, ops};
+use crate::util::{
+    self, git_dirty_files, human_readable_bytes, restricted_names, Config, FileLock, GitFileStatus,
+    HgRepo, JjRepo, Vcs,
+};
+use crate::{drop_println, message, ops};
 use anyhow::Context as _;
 use cargo_util::paths;
 use flate2::read::GzDecoder;
 use flate2::{Compression, GzBuilder};
-use log::debug;
 use serde::Serialize;
-use tar::{Archive, Builder, EntryType, Header, HeaderMode};
+use tar::{Archive, Builder, EntryType, Header};
+use tracing::debug;
+use url::Url;
 
 pub struct PackageOpts<'cfg> {
     pub config: &'cfg Config,
     pub list: bool,
     pub check_metadata: bool,
     pub allow_dirty: bool,
+    pub require_clean_vcs: bool,
     pub verify: bool,
     pub jobs: Option<JobsConfig>,
     pub keep_going: bool,
@@ -64,7 +70,7 @@ enum GeneratedFile {
     Manifest,
     /// Generates `Cargo.lock` in some cases (like if there is a binary).
     Lockfile,
-    /// Adds a `.cargo_vcs_info.json` file if in a (clean) git repo.
+    /// Adds a `.cargo_vcs_info.json` file if in a git repo.
     VcsInfo(VcsInfo),
 }
 
@@ -78,12 +84,27 @@ struct VcsInfo {
 #[derive(Serialize)]
 struct GitVcsInfo {
     sha1: String,
+    /// Whether files of the package had uncommitted changes, only possible
+    /// with `--allow-dirty` and `-Zunstable-options`.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    dirty: bool,
 }
 
 pub fn package_one(
     ws: &Workspace<'_>,
     pkg: &Package,
     opts: &PackageOpts<'_>,
+) -> CargoResult<Option<FileLock>> {
+    package_one_with_local_deps(ws, pkg, opts, &[])
+}
+
+/// Like [`package_one`], but verifies `pkg` against the packaged form of
+/// `local_deps`, the workspace members that were packaged before it.
+fn package_one_with_local_deps(
+    ws: &Workspace<'_>,
+    pkg: &Package,
+    opts: &PackageOpts<'_>,
+    local_deps: &[PackageId],
 ) -> CargoResult<Option<FileLock>> {
     let config = ws.config();
     let mut src = PathSource::new(pkg.root(), pkg.package_id().source_id(), config);
@@ -94,18 +115,21 @@ pub fn package_one(
     }
 
     if !pkg.manifest().exclude().is_empty() && !pkg.manifest().include().is_empty() {
-        config.shell().warn(
+        let msg = message!(
+            config,
             "both package.include and package.exclude are specified; \
-             the exclude list will be ignored",
-        )?;
+             the exclude list will be ignored"
+        );
+        config.shell().warn(msg)?;
     }
     let src_files = src.list_files(pkg)?;
 
     // Check (git) repository state, getting the current commit hash if not
-    // dirty.
-    let vcs_info = if !opts.allow_dirty {
-        // This will error if a dirty repo is found.
-        check_repo_state(pkg, &src_files, config)?
+    // dirty. With `-Zunstable-options`, the commit of a dirty repo is
+    // recorded as well with `--allow-dirty`.
+    let vcs_info = if !opts.allow_dirty || config.cli_unstable().unstable_options {
+        // This will error if a dirty repo is found without `--allow-dirty`.
+        check_repo_state(pkg, &src_files, config, opts)?
     } else {
         None
     };
@@ -146,7 +170,8 @@ pub fn package_one(
         .with_context(|| "failed to prepare local package for uploading")?;
     if opts.verify {
         dst.seek(SeekFrom::Start(0))?;
-        run_verify(ws, pkg, &dst, opts).with_context(|| "failed to verify package tarball")?
+        run_verify(ws, pkg, &dst, opts, local_deps)
+            .with_context(|| "failed to verify package tarball")?
     }
 
     dst.seek(SeekFrom::Start(0))?;
@@ -164,9 +189,12 @@ pub fn package_one(
     let uncompressed = human_readable_bytes(uncompressed_size);
     let compressed = human_readable_bytes(compressed_size);
 
-    let message = format!(
-        "{} files, {:.1}{} ({:.1}{} compressed)",
-        filecount, uncompressed.0, uncompressed.1, compressed.0, compressed.1,
+    let message = message!(
+        config,
+        "{count} files, {size} ({compressed} compressed)",
+        count = filecount,
+        size = format!("{:.1}{}", uncompressed.0, uncompressed.1),
+        compressed = format!("{:.1}{}", compressed.0, compressed.1),
     );
     // It doesn't really matter if this fails.
     drop(config.shell().status("Packaged", message));
@@ -175,10 +203,18 @@ pub fn package_one(
 }
 
 pub fn package(ws: &Workspace<'_>, opts: &PackageOpts<'_>) -> CargoResult<Option<Vec<FileLock>>> {
-    let pkgs = ws.members_with_features(
+    let mut pkgs = ws.members_with_features(
         &opts.to_package.to_package_id_specs(ws)?,
         &opts.cli_features,
     )?;
+    // With `-Zpackage-workspace`, the members are verified against the
+    // packaged form of the other members they depend on, so those have to be
+    // packaged first.
+    let cross_verify = opts.config.cli_unstable().package_workspace && opts.verify && !opts.list;
+    if cross_verify {
+        pkgs = local_dependency_order(pkgs);
+    }
+    let mut local_deps = Vec::new();
 
     let mut dsts = Vec::with_capacity(pkgs.len());
 
@@ -190,7 +226,7 @@ pub fn package(ws: &Workspace<'_>, opts: &PackageOpts<'_>) -> CargoResult<Option
     }
 
     for (pkg, cli_features) in pkgs {
-        let result = package_one(
+        let result = package_one_with_local_deps(
             ws,
             pkg,
             &PackageOpts {
@@ -198,6 +234,7 @@ pub fn package(ws: &Workspace<'_>, opts: &PackageOpts<'_>) -> CargoResult<Option
                 list: opts.list,
                 check_metadata: opts.check_metadata,
                 allow_dirty: opts.allow_dirty,
+                require_clean_vcs: opts.require_clean_vcs,
                 verify: opts.verify,
                 jobs: opts.jobs.clone(),
                 keep_going: opts.keep_going,
@@ -205,8 +242,12 @@ pub fn package(ws: &Workspace<'_>, opts: &PackageOpts<'_>) -> CargoResult<Option
                 targets: opts.targets.clone(),
                 cli_features: cli_features,
             },
+            &local_deps,
         )?;
 
+        if cross_verify {
+            local_deps.push(pkg.package_id());
+        }
         if !opts.list {
             dsts.push(result.unwrap());
         }
@@ -220,6 +261,36 @@ pub fn package(ws: &Workspace<'_>, opts: &PackageOpts<'_>) -> CargoResult<Option
     }
 }
 
+/// Sorts `pkgs` so that members come after the other members in `pkgs` they
+/// depend on, ignoring dev-dependencies. Members that are part of a cycle keep
+/// their order at the end.
+fn local_dependency_order<T>(mut pkgs: Vec<(&Package, T)>) -> Vec<(&Package, T)> {
+    let mut sorted = Vec::with_capacity(pkgs.len());
+    let mut placed = HashSet::new();
+    loop {
+        let ready = pkgs.iter().position(|(pkg, _)| {
+            pkg.dependencies()
+                .iter()
+                .filter(|dep| dep.kind() != DepKind::Development)
+                .all(|dep| {
+                    placed.contains(&dep.source_id())
+                        || !pkgs.iter().any(|(other, _)| {
+                            other.package_id() != pkg.package_id()
+                                && other.package_id().source_id() == dep.source_id()
+                        })
+                })
+        });
+        let Some(i) = ready else {
+            break;
+        };
+        let (pkg, extra) = pkgs.remove(i);
+        placed.insert(pkg.package_id().source_id());
+        sorted.push((pkg, extra));
+    }
+    sorted.extend(pkgs);
+    sorted
+}
+
 /// Builds list of files to archive.
 fn build_ar_list(
     ws: &Workspace<'_>,
@@ -334,17 +405,18 @@ fn check_for_file_and_add(
             // The file exists somewhere outside of the package.
             let file_name = file_path.file_name().unwrap();
             if result.iter().any(|ar| ar.rel_path == file_name) {
-                ws.config().shell().warn(&format!(
-                    "{} `{}` appears to be a path outside of the package, \
-                            but there is already a file named `{}` in the root of the package. \
-                            The archived crate will contain the copy in the root of the package. \
-                            Update the {} to point to the path relative \
-                            to the root of the package to remove this warning.",
-                    label,
-                    file_path.display(),
-                    file_name.to_str().unwrap(),
-                    label,
-                ))?;
+                let msg = message!(
+                    ws.config(),
+                    "{label} `{path}` appears to be a path outside of the package, \
+                     but there is already a file named `{name}` in the root of the package. \
+                     The archived crate will contain the copy in the root of the package. \
+                     Update the {label} to point to the path relative \
+                     to the root of the package to remove this warning.",
+                    label = label,
+                    path = file_path.display(),
+                    name = file_name.to_str().unwrap(),
+                );
+                ws.config().shell().warn(msg)?;
             } else {
                 result.push(ArchiveFile {
                     rel_path: PathBuf::from(file_name),
@@ -366,16 +438,23 @@ fn warn_on_nonexistent_file(
     let rel_msg = if path.is_absolute() {
         "".to_string()
     } else {
-        format!(" (relative to `{}`)", pkg.root().display())
+        message!(
+            ws.config(),
+            " (relative to `{root}`)",
+            root = pkg.root().display(),
+        )
     };
-    ws.config().shell().warn(&format!(
-        "{manifest_key_name} `{}` does not appear to exist{}.\n\
-                Please update the {manifest_key_name} setting in the manifest at `{}`\n\
-                This may become a hard error in the future.",
-        path.display(),
-        rel_msg,
-        pkg.manifest_path().display()
-    ))
+    let msg = message!(
+        ws.config(),
+        "{key} `{path}` does not appear to exist{relative}.\n\
+         Please update the {key} setting in the manifest at `{manifest}`\n\
+         This may become a hard error in the future.",
+        key = manifest_key_name,
+        path = path.display(),
+        relative = rel_msg,
+        manifest = pkg.manifest_path().display(),
+    );
+    ws.config().shell().warn(msg)
 }
 
 /// Construct `Cargo.lock` for the package to be published.
@@ -418,7 +497,7 @@ fn build_lock(ws: &Workspace<'_>, orig_pkg: &Package) -> CargoResult<String> {
         config,
         &pkg_set,
         &new_resolve,
-        "consider updating to a version that is not yanked",
+        &message!(config, "consider updating to a version that is not yanked"),
     )?;
 
     ops::resolve_to_string(&tmp_ws, &mut new_resolve)
@@ -455,11 +534,13 @@ fn check_metadata(pkg: &Package, config: &Config) -> CargoResult<()> {
         }
         things.push_str(missing.last().unwrap());
 
-        config.shell().warn(&format!(
+        let msg = message!(
+            config,
             "manifest has no {things}.\n\
              See https://doc.rust-lang.org/cargo/reference/manifest.html#package-metadata for more info.",
-            things = things
-        ))?
+            things = things,
+        );
+        config.shell().warn(msg)?
     }
 
     Ok(())
@@ -467,12 +548,15 @@ fn check_metadata(pkg: &Package, config: &Config) -> CargoResult<()> {
 
 /// Checks if the package source is in a *git* DVCS repository. If *git*, and
 /// the source is *dirty* (e.g., has uncommitted changes) then `bail!` with an
-/// informative message. Otherwise return the sha1 hash of the current *HEAD*
-/// commit, or `None` if no repo is found.
+/// informative message, unless `--allow-dirty` is passed. Otherwise return the
+/// sha1 hash of the current *HEAD* commit, or `None` if no repo is found.
+///
+/// With `--require-clean-vcs`, not finding a repo is an error as well.
 fn check_repo_state(
     p: &Package,
     src_files: &[PathBuf],
     config: &Config,
+    opts: &PackageOpts<'_>,
 ) -> CargoResult<Option<VcsInfo>> {
     if let Ok(repo) = git2::Repository::discover(p.root()) {
         if let Some(workdir) = repo.workdir() {
@@ -490,46 +574,108 @@ fn check_repo_state(
                         .and_then(|p| p.to_str())
                         .unwrap_or("")
                         .replace("\\", "/");
-                    return Ok(Some(VcsInfo {
-                        git: git(p, src_files, &repo)?,
-                        path_in_vcs,
-                    }));
+                    let Some(git) = git(p, src_files, &repo, opts.allow_dirty)? else {
+                        return Ok(None);
+                    };
+                    return Ok(Some(VcsInfo { git, path_in_vcs }));
                 }
             }
-            config.shell().verbose(|shell| {
-                shell.warn(format!(
-                    "No (git) Cargo.toml found at `{}` in workdir `{}`",
-                    path.display(),
-                    workdir.display()
-                ))
-            })?;
+            if !opts.allow_dirty {
+                config.shell().verbose(|shell| {
+                    let msg = message!(
+                        shell = shell,
+                        "No (git) Cargo.toml found at `{path}` in workdir `{workdir}`",
+                        path = path.display(),
+                        workdir = workdir.display(),
+                    );
+                    shell.warn(msg)
+                })?;
+            }
         }
-    } else {
+    } else if !opts.allow_dirty {
         config.shell().verbose(|shell| {
-            shell.warn(format!("No (git) VCS found for `{}`", p.root().display()))
+            let msg = message!(
+                shell = shell,
+                "No (git) VCS found for `{root}`",
+                root = p.root().display(),
+            );
+            shell.warn(msg)
         })?;
     }
 
+    if opts.require_clean_vcs {
+        anyhow::bail!(
+            "`{}` is not checked into a git repository\n\n\
+             `--require-clean-vcs` requires the package to be committed into git",
+            p.manifest_path().display()
+        );
+    }
+
+    // Other VCSes can't record the commit in `.cargo_vcs_info.json`, but can
+    // still tell whether the files to package are committed.
+    if !opts.allow_dirty && config.cli_unstable().vcs_detection {
+        for vcs in [&HgRepo as &dyn Vcs, &JjRepo] {
+            let Some(dirty_files) = vcs.dirty_files(p.root(), config.cwd())? else {
+                continue;
+            };
+            debug!("found a {} repo for {:?}", vcs.name(), p.root());
+            let dirty_src_files = dirty_src_files(p, src_files, &dirty_files);
+            if !dirty_src_files.is_empty() {
+                return Err(uncommitted_changes_error(vcs.name(), &dirty_src_files));
+            }
+            return Ok(None);
+        }
+    }
+
     // No VCS with a checked in `Cargo.toml` found, so we don't know if the
     // directory is dirty or not, thus we have to assume that it's clean.
     return Ok(None);
 
-    fn git(p: &Package, src_files: &[PathBuf], repo: &git2::Repository) -> CargoResult<GitVcsInfo> {
+    fn git(
+        p: &Package,
+        src_files: &[PathBuf],
+        repo: &git2::Repository,
+        allow_dirty: bool,
+    ) -> CargoResult<Option<GitVcsInfo>> {
         // This is a collection of any dirty or untracked files. This covers:
         // - new/modified/deleted/renamed/type change (index or worktree)
         // - untracked files (which are "new" worktree files)
         // - ignored (in case the user has an `include` directive that
         //   conflicts with .gitignore).
-        let mut dirty_files = Vec::new();
-        collect_statuses(repo, &mut dirty_files)?;
-        // Include each submodule so that the error message can provide
-        // specifically *which* files in a submodule are modified.
-        status_submodules(repo, &mut dirty_files)?;
-
-        // Find the intersection of dirty in git, and the src_files that would
-        // be packaged. This is a lazy n^2 check, but seems fine with
-        // thousands of files.
-        let dirty_src_files: Vec<String> = src_files
+        // - the same in submodules, and submodules checked out at another
+        //   commit than the recorded one.
+        let dirty_files: Vec<PathBuf> = git_dirty_files(repo, true)?
+            .into_iter()
+            .filter(|file| {
+                // It is OK to include Cargo.lock even if it is ignored.
+                !(file.path.ends_with("Cargo.lock") && file.status == GitFileStatus::Ignored)
+            })
+            .map(|file| file.path)
+            .collect();
+
+        let dirty_src_files = dirty_src_files(p, src_files, &dirty_files);
+        if dirty_src_files.is_empty() {
+            let rev_obj = repo.revparse_single("HEAD")?;
+            Ok(Some(GitVcsInfo {
+                sha1: rev_obj.id().to_string(),
+                dirty: false,
+            }))
+        } else if allow_dirty {
+            // There may not be a commit yet when packaging a dirty repo.
+            Ok(repo.revparse_single("HEAD").ok().map(|rev_obj| GitVcsInfo {
+                sha1: rev_obj.id().to_string(),
+                dirty: true,
+            }))
+        } else {
+            Err(uncommitted_changes_error("git", &dirty_src_files))
+        }
+    }
+
+    // Helper to find the intersection of dirty in the VCS, and the src_files
+    // that would be packaged. This is a lazy n^2 check, but seems fine with
+    // thousands of files.
+    fn dirty_src_files(p: &Package, src_files: &[PathBuf], dirty_files: &[PathBuf]) -> Vec<String> {
+        src_files
             .iter()
             .filter(|src_file| dirty_files.iter().any(|path| src_file.starts_with(path)))
             .map(|path| {
@@ -538,76 +684,42 @@ fn check_repo_state(
                     .display()
                     .to_string()
             })
-            .collect();
-        if dirty_src_files.is_empty() {
-            let rev_obj = repo.revparse_single("HEAD")?;
-            Ok(GitVcsInfo {
-                sha1: rev_obj.id().to_string(),
-            })
-        } else {
-            anyhow::bail!(
-                "{} files in the working directory contain changes that were \
-                 not yet committed into git:\n\n{}\n\n\
-                 to proceed despite this and include the uncommitted changes, pass the `--allow-dirty` flag",
-                dirty_src_files.len(),
-                dirty_src_files.join("\n")
-            )
-        }
+            .collect()
     }
 
-    // Helper to collect dirty statuses for a single repo.
-    fn collect_statuses(
-        repo: &git2::Repository,
-        dirty_files: &mut Vec<PathBuf>,
-    ) -> CargoResult<()> {
-        let mut status_opts = git2::StatusOptions::new();
-        // Exclude submodules, as they are being handled manually by recursing
-        // into each one so that details about specific files can be
-        // retrieved.
-        status_opts
-            .exclude_submodules(true)
-            .include_ignored(true)
-            .include_untracked(true);
-        let repo_statuses = repo.statuses(Some(&mut status_opts)).with_context(|| {
-            format!(
-                "failed to retrieve git status from repo {}",
-                repo.path().display()
-            )
-        })?;
-        let workdir = repo.workdir().unwrap();
-        let this_dirty = repo_statuses.iter().filter_map(|entry| {
-            let path = entry.path().expect("valid utf-8 path");
-            if path.ends_with("Cargo.lock") && entry.status() == git2::Status::IGNORED {
-                // It is OK to include Cargo.lock even if it is ignored.
-                return None;
-            }
-            // Use an absolute path, so that comparing paths is easier
-            // (particularly with submodules).
-            Some(workdir.join(path))
-        });
-        dirty_files.extend(this_dirty);
-        Ok(())
+    fn uncommitted_changes_error(vcs: &str, dirty_src_files: &[String]) -> anyhow::Error {
+        anyhow::format_err!(
+            "{} files in the working directory contain changes that were \
+             not yet committed into {}:\n\n{}\n\n\
+             to proceed despite this and include the uncommitted changes, pass the `--allow-dirty` flag",
+            dirty_src_files.len(),
+            vcs,
+            dirty_src_files.join("\n")
+        )
     }
+}
 
-    // Helper to collect dirty statuses while recursing into submodules.
-    fn status_submodules(
-        repo: &git2::Repository,
-        dirty_files: &mut Vec<PathBuf>,
-    ) -> CargoResult<()> {
-        for submodule in repo.submodules()? {
-            // Ignore submodules that don't open, they are probably not initialized.
-            // If its files are required, then the verification step should fail.
-            if let Ok(sub_repo) = submodule.open() {
-                status_submodules(&sub_repo, dirty_files)?;
-                collect_statuses(&sub_repo, dirty_files)?;
-            }
-        }
-        Ok(())
+/// The gzip compression level of `.crate` files, set by the
+/// `package.compression-level` config.
+fn compression(config: &Config) -> CargoResult<Compression> {
+    if !config.cli_unstable().crate_compression {
+        return Ok(Compression::best());
+    }
+    match config.get::<Option<u32>>("package.compression-level")? {
+        None => Ok(Compression::best()),
+        Some(level @ 0..=9) => Ok(Compression::new(level)),
+        Some(level) => anyhow::bail!(
+            "invalid `package.compression-level` `{level}`, \
+             expected a level between 0 and 9"
+        ),
     }
 }
 
 /// Compresses and packages a list of [`ArchiveFile`]s and writes into the given file.
 ///
+/// The files are archived in the order given, which [`build_ar_list`] sorts,
+/// so the same sources always produce a byte-identical archive.
+///
 /// Returns the uncompressed size of the contents of the new archive file.
 fn tar(
     ws: &Workspace<'_>,
@@ -616,11 +728,15 @@ fn tar(
     dst: &File,
     filename: &str,
 ) -> CargoResult<u64> {
-    // Prepare the encoder and its header.
+    // Prepare the encoder and its header. The gzip header has a fixed
+    // timestamp and operating system so the same sources always compress to
+    // the same bytes.
     let filename = Path::new(filename);
     let encoder = GzBuilder::new()
         .filename(paths::path2bytes(filename)?)
-        .write(dst, Compression::best());
+        .mtime(0)
+        .operating_system(GZIP_OS_UNKNOWN)
+        .write(dst, compression(ws.config())?);
 
     // Put all package files into a compressed archive.
     let mut ar = Builder::new(encoder);
@@ -640,7 +756,6 @@ fn tar(
         config
             .shell()
             .verbose(|shell| shell.status("Archiving", &rel_str))?;
-        let mut header = Header::new_gnu();
         match contents {
             FileContents::OnDisk(disk_path) => {
                 let mut file = File::open(&disk_path).with_context(|| {
@@ -649,8 +764,7 @@ fn tar(
                 let metadata = file.metadata().with_context(|| {
                     format!("could not learn metadata for: `{}`", disk_path.display())
                 })?;
-                header.set_metadata_in_mode(&metadata, HeaderMode::Deterministic);
-                header.set_cksum();
+                let mut header = archive_header(metadata.len(), is_executable(&metadata));
                 ar.append_data(&mut header, &ar_path, &mut file)
                     .with_context(|| {
                         format!("could not archive source file `{}`", disk_path.display())
@@ -663,12 +777,7 @@ fn tar(
                     GeneratedFile::Lockfile => build_lock(ws, pkg)?,
                     GeneratedFile::VcsInfo(ref s) => serde_json::to_string_pretty(s)?,
                 };
-                header.set_entry_type(EntryType::file());
-                header.set_mode(0o644);
-                header.set_size(contents.len() as u64);
-                // use something nonzero to avoid rust-lang/cargo#9512
-                header.set_mtime(1);
-                header.set_cksum();
+                let mut header = archive_header(contents.len() as u64, false);
                 ar.append_data(&mut header, &ar_path, contents.as_bytes())
                     .with_context(|| format!("could not archive source file `{}`", rel_str))?;
                 uncompressed_size += contents.len() as u64;
@@ -681,6 +790,44 @@ fn tar(
     Ok(uncompressed_size)
 }
 
+/// The modification time of every file in a `.crate` file, the date of the
+/// first commit of what would become Rust. Use something nonzero to avoid
+/// rust-lang/cargo#9512.
+const ARCHIVE_MTIME: u64 = 1153704088;
+
+/// The "unknown" operating system in a gzip header.
+const GZIP_OS_UNKNOWN: u8 = 255;
+
+/// Creates the header of a file in a `.crate` file.
+///
+/// Only the size and whether the file is executable come from the file. The
+/// timestamp, owner and the rest of the permissions are fixed, so the archive
+/// doesn't depend on when, where, or by whom it was created.
+fn archive_header(size: u64, executable: bool) -> Header {
+    let mut header = Header::new_gnu();
+    header.set_entry_type(EntryType::file());
+    header.set_size(size);
+    header.set_mode(if executable { 0o755 } else { 0o644 });
+    header.set_uid(0);
+    header.set_gid(0);
+    header.set_mtime(ARCHIVE_MTIME);
+    header.set_cksum();
+    header
+}
+
+/// Whether the owner may execute the file. Always `false` on platforms
+/// without an execute permission bit.
+#[cfg(unix)]
+fn is_executable(metadata: &fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o100 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_metadata: &fs::Metadata) -> bool {
+    false
+}
+
 /// Generate warnings when packaging Cargo.lock, and the resolve have changed.
 fn compare_resolve(
     config: &Config,
@@ -722,17 +869,19 @@ fn compare_resolve(
                     .collect();
                 match previous_versions.len() {
                     0 => String::new(),
-                    1 => format!(
-                        ", previous version was `{}`",
-                        previous_versions[0].version()
+                    1 => message!(
+                        config,
+                        ", previous version was `{version}`",
+                        version = previous_versions[0].version(),
                     ),
-                    _ => format!(
-                        ", previous versions were: {}",
-                        previous_versions
+                    _ => message!(
+                        config,
+                        ", previous versions were: {versions}",
+                        versions = previous_versions
                             .iter()
                             .map(|pkg_id| format!("`{}`", pkg_id.version()))
                             .collect::<Vec<_>>()
-                            .join(", ")
+                            .join(", "),
                     ),
                 }
             }
@@ -740,9 +889,10 @@ fn compare_resolve(
                 // This can happen for multi-sourced dependencies like
                 // `{path="...", version="..."}` or `[patch]` replacement.
                 // `[replace]` is not captured in Cargo.lock.
-                format!(
-                    ", was originally sourced from `{}`",
-                    removed_candidates[0].source_id()
+                message!(
+                    config,
+                    ", was originally sourced from `{source}`",
+                    source = removed_candidates[0].source_id(),
                 )
             }
             _ => {
@@ -753,15 +903,18 @@ fn compare_resolve(
                     .map(|pkg_id| format!("`{}`", pkg_id.source_id()))
                     .collect::<Vec<_>>()
                     .join(", ");
-                format!(
-                    ", was originally sourced from one of these sources: {}",
-                    comma_list
+                message!(
+                    config,
+                    ", was originally sourced from one of these sources: {sources}",
+                    sources = comma_list,
                 )
             }
         };
-        let msg = format!(
-            "package `{}` added to the packaged Cargo.lock file{}",
-            pkg_id, extra
+        let msg = message!(
+            config,
+            "package `{pkg}` added to the packaged Cargo.lock file{extra}",
+            pkg = pkg_id,
+            extra = extra,
         );
         config.shell().note(msg)?;
     }
@@ -801,12 +954,14 @@ pub fn check_yanked(
 
     for (pkg_id, is_yanked) in results {
         if is_yanked? {
-            config.shell().warn(format!(
-                "package `{}` in Cargo.lock is yanked in registry `{}`, {}",
-                pkg_id,
-                pkg_id.source_id().display_registry_name(),
-                hint
-            ))?;
+            let msg = message!(
+                config,
+                "package `{pkg}` in Cargo.lock is yanked in registry `{registry}`, {hint}",
+                pkg = pkg_id,
+                registry = pkg_id.source_id().display_registry_name(),
+                hint = hint,
+            );
+            config.shell().warn(msg)?;
         }
     }
     Ok(())
@@ -817,6 +972,7 @@ fn run_verify(
     pkg: &Package,
     tar: &FileLock,
     opts: &PackageOpts<'_>,
+    local_deps: &[PackageId],
 ) -> CargoResult<()> {
     let config = ws.config();
 
@@ -841,7 +997,11 @@ fn run_verify(
     let mut src = PathSource::new(&dst, id, ws.config());
     let new_pkg = src.root_package()?;
     let pkg_fingerprint = hash_all(&dst)?;
-    let ws = Workspace::ephemeral(new_pkg, config, None, true)?;
+    let patches = local_dep_patches(&new_pkg, local_deps, tar.parent())?;
+    let mut ws = Workspace::ephemeral(new_pkg, config, None, true)?;
+    for (url, dep) in patches {
+        ws.add_patch(url, dep);
+    }
 
     let rustc_args = if pkg
         .manifest()
@@ -897,6 +1057,37 @@ fn run_verify(
     Ok(())
 }
 
+/// Patches the dependencies of `pkg` on `local_deps` with the extracted
+/// packages of `local_deps` in `package_dir`, so `pkg` is verified against
+/// them instead of published versions.
+fn local_dep_patches(
+    pkg: &Package,
+    local_deps: &[PackageId],
+    package_dir: &Path,
+) -> CargoResult<Vec<(Url, Dependency)>> {
+    let mut patches = Vec::new();
+    let mut seen = HashSet::new();
+    for dep in pkg.dependencies() {
+        let Some(local) = local_deps
+            .iter()
+            .find(|id| dep.matches_ignoring_source(**id))
+        else {
+            continue;
+        };
+        if !seen.insert((dep.source_id(), *local)) {
+            continue;
+        }
+        let path = package_dir.join(format!("{}-{}", local.name(), local.version()));
+        let patch = Dependency::parse(
+            local.name(),
+            Some(&format!("={}", local.version())),
+            SourceId::for_path(&path)?,
+        )?;
+        patches.push((dep.source_id().url().clone(), patch));
+    }
+    Ok(patches)
+}
+
 fn hash_all(path: &Path) -> CargoResult<HashMap<PathBuf, u64>> {
     fn wrap(path: &Path) -> CargoResult<HashMap<PathBuf, u64>> {
         let mut result = HashMap::new();
@@ -985,11 +1176,13 @@ fn check_filename(file: &Path, shell: &mut Shell) -> CargoResult<()> {
         )
     }
     if restricted_names::is_windows_reserved_path(file) {
-        shell.warn(format!(
-            "file {} is a reserved Windows filename, \
-                it will not work on Windows platforms",
-            file.display()
-        ))?;
+        let msg = message!(
+            shell = shell,
+            "file {file} is a reserved Windows filename, \
+             it will not work on Windows platforms",
+            file = file.display(),
+        );
+        shell.warn(msg)?;
     }
     Ok(())
 }
@@ -4,12 +4,13 @@ use std::io;
 use std::path::{Path, PathBuf};
 
 use crate::core::{EitherManifest, Package, PackageId, SourceId};
+use crate::message;
 use crate::util::errors::CargoResult;
 use crate::util::important_paths::find_project_manifest_exact;
 use crate::util::toml::read_manifest;
 use crate::util::Config;
 use cargo_util::paths;
-use log::{info, trace};
+use tracing::{info, trace};
 
 pub fn read_package(
     path: &Path,
@@ -187,11 +188,13 @@ fn read_nested_packages(
             // by users so we can hide the warning about those since the user is unlikely
             // to care about those cases.
             if pkg.publish().is_none() {
-                let _ = config.shell().warn(format!(
-                    "skipping duplicate package `{}` found at `{}`",
-                    pkg.name(),
-                    path.display()
-                ));
+                let msg = message!(
+                    config,
+                    "skipping duplicate package `{name}` found at `{path}`",
+                    name = pkg.name(),
+                    path = path.display(),
+                );
+                let _ = config.shell().warn(msg);
             }
         }
     }
@@ -1,6 +1,7 @@
 //! Core of cargo-remove command
 
 use crate::core::Package;
+use crate::message;
 use crate::util::toml_mut::manifest::DepTable;
 use crate::util::toml_mut::manifest::LocalManifest;
 use crate::CargoResult;
@@ -35,14 +36,22 @@ pub fn remove(options: &RemoveOptions<'_>) -> CargoResult<()> {
 
     for dep in &options.dependencies {
         let section = if dep_table.len() >= 3 {
-            format!("{} for target `{}`", &dep_table[2], &dep_table[1])
+            message!(
+                options.config,
+                "{table} for target `{target}`",
+                table = dep_table[2],
+                target = dep_table[1],
+            )
         } else {
             dep_table[0].clone()
         };
-        options
-            .config
-            .shell()
-            .status("Removing", format!("{dep} from {section}"))?;
+        let msg = message!(
+            options.config,
+            "{dep} from {section}",
+            dep = dep,
+            section = section,
+        );
+        options.config.shell().status("Removing", msg)?;
 
         manifest.remove_from_table(&dep_table, dep)?;
 
@@ -53,10 +62,8 @@ pub fn remove(options: &RemoveOptions<'_>) -> CargoResult<()> {
     }
 
     if options.dry_run {
-        options
-            .config
-            .shell()
-            .warn("aborting remove due to dry run")?;
+        let msg = message!(options.config, "aborting remove due to dry run");
+        options.config.shell().warn(msg)?;
     } else {
         manifest.write()?;
     }
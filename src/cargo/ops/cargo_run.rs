@@ -2,15 +2,18 @@ use std::ffi::OsString;
 use std::iter;
 use std::path::Path;
 
-use crate::core::compiler::UnitOutput;
-use crate::core::{TargetKind, Workspace};
+use crate::core::compiler::{UnitOutput, Workdir};
+use crate::core::{Package, Target, TargetKind, Workspace};
 use crate::ops;
+use crate::util::machine_message::{self, Message};
 use crate::util::CargoResult;
 
+/// Builds and runs the binary selected by `options`, in `workdir`.
 pub fn run(
     ws: &Workspace<'_>,
     options: &ops::CompileOptions,
     args: &[OsString],
+    workdir: Workdir,
 ) -> CargoResult<()> {
     let config = ws.config();
 
@@ -21,20 +24,48 @@ pub fn run(
     // We compute the `bins` here *just for diagnosis*. The actual set of
     // packages to be run is determined by the `ops::compile` call below.
     let packages = options.spec.get_packages(ws)?;
-    let bins: Vec<_> = packages
-        .into_iter()
-        .flat_map(|pkg| {
-            iter::repeat(pkg).zip(pkg.manifest().targets().iter().filter(|target| {
-                !target.is_lib()
-                    && !target.is_custom_build()
-                    && if !options.filter.is_specific() {
-                        target.is_bin()
-                    } else {
-                        options.filter.target_run(target)
-                    }
-            }))
-        })
-        .collect();
+    let mut bins = runnable_targets(&packages, options);
+
+    // In the root of a workspace, `workspace.default-run` picks the binary
+    // when the selected packages have several.
+    let default_run_options;
+    let options = match ws.default_run() {
+        Some(name)
+            if bins.len() > 1
+                && !options.filter.is_specific()
+                && options.spec == ops::Packages::Default
+                && ws.is_invoked_in_root() =>
+        {
+            bins.retain(|(_pkg, target)| target.name() == name);
+            match bins.len() {
+                0 => anyhow::bail!(
+                    "`workspace.default-run` in `{}` is `{}`, \
+                     but none of the selected packages has a binary with that name",
+                    ws.root_manifest().display(),
+                    name
+                ),
+                1 => {}
+                _ => {
+                    let mut pkgs: Vec<_> = bins.iter().map(|(pkg, _)| pkg.name()).collect();
+                    pkgs.sort();
+                    anyhow::bail!(
+                        "`workspace.default-run` in `{}` is `{}`, \
+                         but several of the selected packages have a binary with that name: {}\n\
+                         Use the `-p` option to specify a package.",
+                        ws.root_manifest().display(),
+                        name,
+                        pkgs.join(", ")
+                    )
+                }
+            }
+            default_run_options = ops::CompileOptions {
+                filter: ops::CompileFilter::single_bin(name.to_string()),
+                ..options.clone()
+            };
+            &default_run_options
+        }
+        _ => options,
+    };
 
     if bins.is_empty() {
         if !options.filter.is_specific() {
@@ -61,13 +92,27 @@ pub fn run(
                 .map(|(_pkg, target)| target.name())
                 .collect();
             names.sort();
-            anyhow::bail!(
+            let mut msg = String::from(
                 "`cargo run` could not determine which binary to run. \
                  Use the `--bin` option to specify a binary, \
-                 or the `default-run` manifest key.\n\
-                 available binaries: {}",
-                names.join(", ")
-            )
+                 or the `default-run` manifest key.",
+            );
+            // Several of the selected packages set `default-run`.
+            let mut default_runs: Vec<_> = packages
+                .iter()
+                .filter_map(|pkg| {
+                    let bin = pkg.manifest().default_run()?;
+                    Some(format!("`{}` (package `{}`)", bin, pkg.name()))
+                })
+                .collect();
+            if default_runs.len() > 1 {
+                default_runs.sort();
+                msg.push_str(&format!(
+                    "\nconflicting `default-run` keys: {}",
+                    default_runs.join(", ")
+                ));
+            }
+            anyhow::bail!("{}\navailable binaries: {}", msg, names.join(", "))
         } else {
             anyhow::bail!(
                 "`cargo run` can run at most one executable, but \
@@ -86,21 +131,59 @@ pub fn run(
         path,
         script_meta,
     } = &compile.binaries[0];
+    let pkg = bins[0].0;
+    let cwd = workdir.resolve(ws, pkg)?;
+    // The path of the executable is only relative when it is run in the
+    // current directory.
     let exe = match path.strip_prefix(config.cwd()) {
+        Ok(_) if cwd != config.cwd() => path.to_path_buf(),
         Ok(path) if path.file_name() == Some(path.as_os_str()) => Path::new(".").join(path),
         Ok(path) => path.to_path_buf(),
         Err(_) => path.to_path_buf(),
     };
-    let pkg = bins[0].0;
     let mut process = compile.target_process(exe, unit.kind, pkg, *script_meta)?;
 
-    // Sets the working directory of the child process to the current working
-    // directory of the parent process.
+    // Sets the working directory of the child process, the current working
+    // directory of the parent process by default.
     // Overrides the default working directory of the `ProcessBuilder` returned
     // by `compile.target_process` (the package's root directory)
-    process.args(args).cwd(config.cwd());
+    process.args(args).cwd(&cwd);
+
+    if options.build_config.emit_json() && config.cli_unstable().run_workdir {
+        let msg = machine_message::Run {
+            package_id: pkg.package_id(),
+            manifest_path: pkg.manifest_path(),
+            target: &unit.target,
+            executable: path,
+            cwd: &cwd,
+        }
+        .to_json_string();
+        crate::drop_println!(config, "{}", msg);
+    }
 
     config.shell().status("Running", process.to_string())?;
 
     process.exec_replace()
 }
+
+/// The targets of `packages` that `cargo run` could run with the filter of
+/// `options`.
+fn runnable_targets<'a>(
+    packages: &[&'a Package],
+    options: &ops::CompileOptions,
+) -> Vec<(&'a Package, &'a Target)> {
+    packages
+        .iter()
+        .flat_map(|pkg| {
+            iter::repeat(*pkg).zip(pkg.manifest().targets().iter().filter(|target| {
+                !target.is_lib()
+                    && !target.is_custom_build()
+                    && if !options.filter.is_specific() {
+                        target.is_bin()
+                    } else {
+                        options.filter.target_run(target)
+                    }
+            }))
+        })
+        .collect()
+}
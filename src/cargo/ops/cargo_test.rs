@@ -1,19 +1,35 @@
-use crate::core::compiler::{Compilation, CompileKind, Doctest, Metadata, Unit, UnitOutput};
+use crate::core::compiler::{
+    Compilation, CompileKind, CompileMode, Doctest, Metadata, RustdocRuntool, Unit, UnitOutput,
+    Workdir,
+};
 use crate::core::shell::Verbosity;
 use crate::core::{TargetKind, Workspace};
+use crate::message;
 use crate::ops;
 use crate::util::errors::CargoResult;
+use crate::util::machine_message::{self, Message};
 use crate::util::{add_path_args, CliError, CliResult, Config};
 use anyhow::format_err;
 use cargo_util::{ProcessBuilder, ProcessError};
+use std::collections::BTreeMap;
 use std::ffi::OsString;
 use std::fmt::Write;
+use std::iter;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::thread;
 
 pub struct TestOptions {
     pub compile_opts: ops::CompileOptions,
     pub no_run: bool,
     pub no_fail_fast: bool,
+    /// The directory to run the test executables in. Doctests always run in
+    /// the package root.
+    pub workdir: Workdir,
+    /// Prints a `test-binary` JSON message for each test executable to
+    /// stdout. Only used with `no_run`.
+    pub list_binaries: bool,
 }
 
 /// The kind of test.
@@ -63,12 +79,37 @@ impl UnitTestError {
 /// On error, the returned [`CliError`] will have the appropriate process exit
 /// code that Cargo should use.
 pub fn run_tests(ws: &Workspace<'_>, options: &TestOptions, test_args: &[&str]) -> CliResult {
+    let default_test_options;
+    let options = match workspace_default_test(ws, options)? {
+        Some(filter) => {
+            default_test_options = TestOptions {
+                compile_opts: ops::CompileOptions {
+                    filter,
+                    ..options.compile_opts.clone()
+                },
+                no_run: options.no_run,
+                no_fail_fast: options.no_fail_fast,
+                workdir: options.workdir.clone(),
+                list_binaries: options.list_binaries,
+            };
+            &default_test_options
+        }
+        None => options,
+    };
     let compilation = compile_tests(ws, options)?;
 
     if options.no_run {
-        if !options.compile_opts.build_config.emit_json() {
+        if emits_test_binaries(ws, options) {
+            emit_test_binaries(ws, options, test_args, &compilation, TestKind::Test)?;
+        } else if !options.compile_opts.build_config.emit_json() {
             display_no_run_information(ws, test_args, &compilation, "unittests")?;
         }
+        // Doctests are only compiled with `--doc`, as it needs rustdoc's
+        // unstable `--no-run`.
+        if options.compile_opts.build_config.mode == CompileMode::Doctest {
+            let errors = run_doc_tests(ws, options, test_args, &compilation)?;
+            return no_fail_fast_err(ws, &options.compile_opts, &errors);
+        }
         return Ok(());
     }
     let mut errors = run_unit_tests(ws, options, test_args, &compilation, TestKind::Test)?;
@@ -78,6 +119,49 @@ pub fn run_tests(ws: &Workspace<'_>, options: &TestOptions, test_args: &[&str])
     no_fail_fast_err(ws, &options.compile_opts, &errors)
 }
 
+/// The filter selecting the test targets of `workspace.default-test`, if
+/// `cargo test` is invoked in the root of the workspace without `-p` or
+/// target selection flags.
+fn workspace_default_test(
+    ws: &Workspace<'_>,
+    options: &TestOptions,
+) -> CargoResult<Option<ops::CompileFilter>> {
+    let compile_opts = &options.compile_opts;
+    let Some(names) = ws.default_test() else {
+        return Ok(None);
+    };
+    if compile_opts.filter.is_specific()
+        || compile_opts.build_config.mode != CompileMode::Test
+        || compile_opts.spec != ops::Packages::Default
+        || !ws.is_invoked_in_root()
+    {
+        return Ok(None);
+    }
+    let packages = compile_opts.spec.get_packages(ws)?;
+    for name in names {
+        let found = packages.iter().any(|pkg| {
+            pkg.targets()
+                .iter()
+                .any(|t| t.is_test() && t.name() == name)
+        });
+        if !found {
+            anyhow::bail!(
+                "`workspace.default-test` in `{}` lists `{}`, \
+                 which is not a test target of the selected packages",
+                ws.root_manifest().display(),
+                name
+            );
+        }
+    }
+    Ok(Some(ops::CompileFilter::new(
+        ops::LibRule::False,
+        ops::FilterRule::none(),
+        ops::FilterRule::Just(names.to_vec()),
+        ops::FilterRule::none(),
+        ops::FilterRule::none(),
+    )))
+}
+
 /// Compiles and runs benchmarks.
 ///
 /// On error, the returned [`CliError`] will have the appropriate process exit
@@ -86,7 +170,9 @@ pub fn run_benches(ws: &Workspace<'_>, options: &TestOptions, args: &[&str]) ->
     let compilation = compile_tests(ws, options)?;
 
     if options.no_run {
-        if !options.compile_opts.build_config.emit_json() {
+        if emits_test_binaries(ws, options) {
+            emit_test_binaries(ws, options, args, &compilation, TestKind::Bench)?;
+        } else if !options.compile_opts.build_config.emit_json() {
             display_no_run_information(ws, args, &compilation, "benches")?;
         }
         return Ok(());
@@ -126,7 +212,7 @@ fn run_unit_tests(
         script_meta,
     } in compilation.tests.iter()
     {
-        let (exe_display, cmd) = cmd_builds(
+        let (exe_display, mut cmd) = cmd_builds(
             config,
             cwd,
             unit,
@@ -136,12 +222,25 @@ fn run_unit_tests(
             compilation,
             "unittests",
         )?;
+        let workdir = options.workdir.resolve(ws, &unit.pkg)?;
+        cmd.cwd(&workdir);
         config
             .shell()
             .concise(|shell| shell.status("Running", &exe_display))?;
         config
             .shell()
             .verbose(|shell| shell.status("Running", &cmd))?;
+        if options.compile_opts.build_config.emit_json() && config.cli_unstable().run_workdir {
+            let msg = machine_message::Run {
+                package_id: unit.pkg.package_id(),
+                manifest_path: unit.pkg.manifest_path(),
+                target: &unit.target,
+                executable: path,
+                cwd: &workdir,
+            }
+            .to_json_string();
+            crate::drop_println!(config, "{}", msg);
+        }
 
         if let Err(e) = cmd.exec() {
             let code = fail_fast_code(&e);
@@ -159,7 +258,12 @@ fn run_unit_tests(
     Ok(errors)
 }
 
-/// Runs doc tests.
+/// Runs doc tests, or only compiles them with `--doc --no-run`.
+///
+/// With `-Zparallel-doctests`, the doctests of several crates run in
+/// parallel, up to the number of jobs. Their output is then buffered to be
+/// displayed crate by crate, in the order they would have run one after the
+/// other.
 ///
 /// Returns a `Vec` of tests that failed when `--no-fail-fast` is used.
 /// If `--no-fail-fast` is *not* used, then this returns an `Err`.
@@ -172,7 +276,10 @@ fn run_doc_tests(
     let config = ws.config();
     let mut errors = Vec::new();
     let doctest_xcompile = config.cli_unstable().doctest_xcompile;
+    let parallel_doctests = config.cli_unstable().parallel_doctests;
 
+    let mut units = Vec::new();
+    let mut cmds = Vec::new();
     for doctest_info in &compilation.to_doc_test {
         let Doctest {
             args,
@@ -183,21 +290,38 @@ fn run_doc_tests(
             env,
         } = doctest_info;
 
-        if !doctest_xcompile {
+        // Cross-compiled doctests run with the runner of their target, when
+        // rustdoc can take it without `-Zdoctest-xcompile`.
+        let runner = match unit.kind {
+            CompileKind::Target(target) if target.short_name() != compilation.host => {
+                compilation.target_runner(unit.kind)
+            }
+            _ => None,
+        };
+        let run_with_runner =
+            runner.is_some() && compilation.rustdoc_runtool == RustdocRuntool::Stable;
+        if !doctest_xcompile && !run_with_runner {
             match unit.kind {
                 CompileKind::Host => {}
                 CompileKind::Target(target) => {
                     if target.short_name() != compilation.host {
                         // Skip doctests, -Zdoctest-xcompile not enabled.
+                        let reason = if runner.is_some() {
+                            "rustdoc doesn't support running them with the target's runner"
+                        } else {
+                            "cross-compilation doctests are not yet supported without a `runner`"
+                        };
                         config.shell().verbose(|shell| {
-                            shell.note(format!(
-                                "skipping doctests for {} ({}), \
-                                 cross-compilation doctests are not yet supported\n\
+                            let msg = message!(
+                                shell = shell,
+                                "skipping doctests for {pkg} ({target}), {reason}\n\
                                  See https://doc.rust-lang.org/nightly/cargo/reference/unstable.html#doctest-xcompile \
                                  for more information.",
-                                unit.pkg,
-                                unit.target.description_named()
-                            ))
+                                pkg = unit.pkg,
+                                target = unit.target.description_named(),
+                                reason = reason,
+                            );
+                            shell.note(msg)
                         })?;
                         continue;
                     }
@@ -205,7 +329,6 @@ fn run_doc_tests(
             }
         }
 
-        config.shell().status("Doc-tests", unit.target.name())?;
         let mut p = compilation.rustdoc_process(unit, *script_meta)?;
 
         for (var, value) in env {
@@ -227,11 +350,18 @@ fn run_doc_tests(
             p.arg("-Zunstable-options");
             p.arg("--enable-per-target-ignores");
             if let Some((runtool, runtool_args)) = compilation.target_runner(unit.kind) {
-                p.arg("--runtool").arg(runtool);
-                for arg in runtool_args {
-                    p.arg("--runtool-arg").arg(arg);
-                }
+                let runtool_flags = match compilation.rustdoc_runtool {
+                    RustdocRuntool::Unsupported => RustdocRuntool::Unstable,
+                    flags => flags,
+                };
+                runtool_flags.add_args(&mut p, runtool, runtool_args);
             }
+        } else if let Some((runtool, runtool_args)) = runner.filter(|_| run_with_runner) {
+            compilation
+                .rustdoc_runtool
+                .add_args(&mut p, runtool, runtool_args);
+        }
+        if doctest_xcompile || run_with_runner {
             if let Some(linker) = linker {
                 let mut joined = OsString::from("linker=");
                 joined.push(linker);
@@ -260,16 +390,51 @@ fn run_doc_tests(
             p.arg("--test-args").arg("--quiet");
         }
 
+        // The output is piped to be displayed by Cargo, so keep its colors.
+        if parallel_doctests {
+            if config.shell().err_supports_color() {
+                p.arg("--color").arg("always");
+            }
+            if config.shell().out_supports_color()
+                && !test_args.iter().any(|a| a.starts_with("--color"))
+            {
+                p.arg("--test-args").arg("--color=always");
+            }
+        }
+
         p.args(args);
 
-        if *unstable_opts {
+        if *unstable_opts || options.no_run {
             p.arg("-Zunstable-options");
         }
+        if options.no_run {
+            p.arg("--no-run");
+        }
 
-        config
-            .shell()
-            .verbose(|shell| shell.status("Running", p.to_string()))?;
-        if let Err(e) = p.exec() {
+        units.push(unit);
+        cmds.push(p);
+    }
+
+    let mut report = |unit: &Unit, found: Option<usize>, result: CargoResult<()>| {
+        if let Some(found) = found.filter(|_| config.extra_verbose()) {
+            let msg = if found == 1 {
+                message!(
+                    config,
+                    "found {found} doctest in `{pkg}`",
+                    found = found,
+                    pkg = unit.pkg.name(),
+                )
+            } else {
+                message!(
+                    config,
+                    "found {found} doctests in `{pkg}`",
+                    found = found,
+                    pkg = unit.pkg.name(),
+                )
+            };
+            config.shell().note(msg)?;
+        }
+        if let Err(e) = result {
             let code = fail_fast_code(&e);
             let unit_err = UnitTestError {
                 unit: unit.clone(),
@@ -281,10 +446,142 @@ fn run_doc_tests(
                 return Err(CliError::code(code));
             }
         }
+        Ok(())
+    };
+
+    if !parallel_doctests {
+        for (unit, p) in units.iter().zip(&cmds) {
+            config.shell().status("Doc-tests", unit.target.name())?;
+            config
+                .shell()
+                .verbose(|shell| shell.status("Running", p.to_string()))?;
+            report(unit, None, p.exec())?;
+        }
+        return Ok(errors);
     }
+
+    let jobs = options.compile_opts.build_config.jobs as usize;
+    if jobs <= 1 || cmds.len() <= 1 {
+        for (unit, p) in units.iter().zip(&cmds) {
+            config.shell().status("Doc-tests", unit.target.name())?;
+            config
+                .shell()
+                .verbose(|shell| shell.status("Running", p.to_string()))?;
+            let (found, result) = exec_doctest(p, &|line| {
+                match line {
+                    OutputLine::Stdout(line) => writeln!(config.shell().out(), "{line}")?,
+                    OutputLine::Stderr(line) => writeln!(config.shell().err(), "{line}")?,
+                }
+                Ok(())
+            });
+            report(unit, found, result)?;
+        }
+        return Ok(errors);
+    }
+
+    let next = AtomicUsize::new(0);
+    let failed = AtomicBool::new(false);
+    let fail_fast = !options.no_fail_fast;
+    let (tx, rx) = mpsc::channel();
+    thread::scope(|s| {
+        for _ in 0..jobs.min(cmds.len()) {
+            let (tx, next, failed, cmds) = (tx.clone(), &next, &failed, &cmds);
+            s.spawn(move || loop {
+                let i = next.fetch_add(1, Ordering::SeqCst);
+                if i >= cmds.len() || (fail_fast && failed.load(Ordering::SeqCst)) {
+                    break;
+                }
+                let lines = Mutex::new(Vec::new());
+                let (found, result) = exec_doctest(&cmds[i], &|line| {
+                    lines.lock().unwrap().push(line.to_owned());
+                    Ok(())
+                });
+                if result.is_err() {
+                    failed.store(true, Ordering::SeqCst);
+                }
+                let output = (lines.into_inner().unwrap(), found, result);
+                if tx.send((i, output)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(tx);
+
+        // Display the doctests in order, as soon as the ones before them are
+        // done.
+        let mut done = BTreeMap::new();
+        let mut next_displayed = 0;
+        for (i, output) in rx {
+            done.insert(i, output);
+            while let Some((lines, found, result)) = done.remove(&next_displayed) {
+                let unit = units[next_displayed];
+                config.shell().status("Doc-tests", unit.target.name())?;
+                config
+                    .shell()
+                    .verbose(|shell| shell.status("Running", cmds[next_displayed].to_string()))?;
+                for line in lines {
+                    match line {
+                        OutputLine::Stdout(line) => writeln!(config.shell().out(), "{line}")?,
+                        OutputLine::Stderr(line) => writeln!(config.shell().err(), "{line}")?,
+                    }
+                }
+                report(unit, found, result)?;
+                next_displayed += 1;
+            }
+        }
+        Ok::<_, CliError>(())
+    })?;
     Ok(errors)
 }
 
+/// A line of the output of a doctest.
+#[derive(Clone)]
+enum OutputLine<T> {
+    Stdout(T),
+    Stderr(T),
+}
+
+impl OutputLine<&str> {
+    fn to_owned(&self) -> OutputLine<String> {
+        match *self {
+            OutputLine::Stdout(line) => OutputLine::Stdout(line.to_string()),
+            OutputLine::Stderr(line) => OutputLine::Stderr(line.to_string()),
+        }
+    }
+}
+
+/// Runs the doctests of a crate, passing each line of their output to
+/// `on_line`.
+///
+/// Returns the number of doctests libtest found, if it reported it.
+fn exec_doctest(
+    p: &ProcessBuilder,
+    on_line: &dyn Fn(OutputLine<&str>) -> CargoResult<()>,
+) -> (Option<usize>, CargoResult<()>) {
+    let mut found = None;
+    let result = p.exec_with_streaming(
+        &mut |line| {
+            if found.is_none() {
+                found = parse_doctest_count(line);
+            }
+            on_line(OutputLine::Stdout(line))
+        },
+        &mut |line| on_line(OutputLine::Stderr(line)),
+        false,
+    );
+    (found, result.map(drop))
+}
+
+/// Parses the `running 3 tests` line libtest starts its output with.
+fn parse_doctest_count(line: &str) -> Option<usize> {
+    let (count, rest) = line.strip_prefix("running ")?.split_once(' ')?;
+    if rest == "test" || rest == "tests" {
+        count.parse().ok()
+    } else {
+        None
+    }
+}
+
 /// Displays human-readable descriptions of the test executables.
 ///
 /// This is used when `cargo test --no-run` is used.
@@ -323,6 +620,73 @@ fn display_no_run_information(
     return Ok(());
 }
 
+/// Whether `--no-run` prints a `test-binary` JSON message for each test
+/// executable, with `--list-binaries`, or `--message-format=json` and
+/// `-Zunstable-options`.
+fn emits_test_binaries(ws: &Workspace<'_>, options: &TestOptions) -> bool {
+    options.list_binaries
+        || (options.compile_opts.build_config.emit_json()
+            && ws.config().cli_unstable().unstable_options)
+}
+
+/// Prints a `test-binary` JSON message for each test executable of
+/// `compilation`, with what external test runners need to run it like Cargo
+/// would.
+fn emit_test_binaries(
+    ws: &Workspace<'_>,
+    options: &TestOptions,
+    test_args: &[&str],
+    compilation: &Compilation<'_>,
+    test_kind: TestKind,
+) -> CargoResult<()> {
+    let config = ws.config();
+    let cwd = config.cwd();
+    let mut test_args = test_args.to_vec();
+    if matches!(test_kind, TestKind::Bench) {
+        test_args.push("--bench");
+    }
+    for UnitOutput {
+        unit,
+        path,
+        script_meta,
+    } in compilation.tests.iter()
+    {
+        let (_exe_display, cmd) = cmd_builds(
+            config,
+            cwd,
+            unit,
+            path,
+            script_meta,
+            &test_args,
+            compilation,
+            "unittests",
+        )?;
+        let workdir = options.workdir.resolve(ws, &unit.pkg)?;
+        let command = iter::once(cmd.get_program())
+            .chain(cmd.get_args())
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect();
+        let env = compilation.target_cargo_env(unit.kind, &unit.pkg)?;
+        let msg = machine_message::TestBinary {
+            package_id: unit.pkg.package_id(),
+            manifest_path: unit.pkg.manifest_path(),
+            target: &unit.target,
+            kind: match test_kind {
+                TestKind::Bench => "bench",
+                _ => "test",
+            },
+            harness: unit.target.harness(),
+            executable: path,
+            command,
+            cwd: &workdir,
+            env,
+        }
+        .to_json_string();
+        crate::drop_println!(config, "{}", msg);
+    }
+    Ok(())
+}
+
 /// Creates a [`ProcessBuilder`] for executing a single test.
 ///
 /// Returns a tuple `(exe_display, process)` where `exe_display` is a string
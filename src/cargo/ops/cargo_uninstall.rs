@@ -1,19 +1,52 @@
 use crate::core::PackageId;
 use crate::core::{PackageIdSpec, SourceId};
+use crate::message;
 use crate::ops::common_for_install_and_uninstall::*;
 use crate::sources::PathSource;
 use crate::util::errors::CargoResult;
+use crate::util::CanonicalUrl;
 use crate::util::Config;
 use crate::util::Filesystem;
 use anyhow::bail;
 use cargo_util::paths;
 use std::collections::BTreeSet;
 use std::env;
+use std::path::PathBuf;
+use url::Url;
+
+/// The installed packages removed by [`uninstall_installed`].
+#[derive(Debug)]
+pub enum InstalledPackages {
+    /// Every package of the install root.
+    All,
+    /// The packages installed from this git repository, whatever the branch,
+    /// tag or revision.
+    Git(Url),
+    /// The packages installed from this directory, or from a directory under
+    /// it.
+    Path(PathBuf),
+}
+
+impl InstalledPackages {
+    fn matches(&self, pkgid: PackageId) -> CargoResult<bool> {
+        let source_id = pkgid.source_id();
+        Ok(match self {
+            InstalledPackages::All => true,
+            InstalledPackages::Git(url) => {
+                source_id.is_git() && source_id.canonical_url() == &CanonicalUrl::new(url)?
+            }
+            InstalledPackages::Path(dir) => source_id.local_path().map_or(false, |path| {
+                paths::normalize_path(&path).starts_with(paths::normalize_path(dir))
+            }),
+        })
+    }
+}
 
 pub fn uninstall(
     root: Option<&str>,
     specs: Vec<&str>,
     bins: &[String],
+    dry_run: bool,
     config: &Config,
 ) -> CargoResult<()> {
     if specs.len() > 1 && !bins.is_empty() {
@@ -22,50 +55,133 @@ pub fn uninstall(
 
     let root = resolve_root(root, config)?;
     let scheduled_error = if specs.len() == 1 {
-        uninstall_one(&root, specs[0], bins, config)?;
+        uninstall_one(&root, specs[0], bins, dry_run, config)?;
         false
     } else if specs.is_empty() {
-        uninstall_cwd(&root, bins, config)?;
+        uninstall_cwd(&root, bins, dry_run, config)?;
         false
     } else {
-        let mut succeeded = vec![];
-        let mut failed = vec![];
-        for spec in specs {
-            let root = root.clone();
-            match uninstall_one(&root, spec, bins, config) {
-                Ok(()) => succeeded.push(spec),
-                Err(e) => {
-                    crate::display_error(&e, &mut config.shell());
-                    failed.push(spec)
-                }
-            }
-        }
+        uninstall_each(
+            specs,
+            |spec| spec,
+            dry_run,
+            config,
+            |spec| uninstall_one(&root, spec, bins, dry_run, config),
+        )?
+    };
 
-        let mut summary = vec![];
-        if !succeeded.is_empty() {
-            summary.push(format!(
-                "Successfully uninstalled {}!",
-                succeeded.join(", ")
-            ));
-        }
-        if !failed.is_empty() {
-            summary.push(format!(
-                "Failed to uninstall {} (see error(s) above).",
-                failed.join(", ")
-            ));
-        }
+    if scheduled_error {
+        bail!("some packages failed to uninstall");
+    }
+    warn_dry_run(dry_run, config)
+}
 
-        if !succeeded.is_empty() || !failed.is_empty() {
-            config.shell().status("Summary", summary.join(" "))?;
+/// Uninstalls all the binaries of the packages of `root` selected by
+/// `packages`, based on the packages recorded by the install tracker.
+pub fn uninstall_installed(
+    root: Option<&str>,
+    packages: &InstalledPackages,
+    dry_run: bool,
+    config: &Config,
+) -> CargoResult<()> {
+    let root = resolve_root(root, config)?;
+    let mut pkgids = Vec::new();
+    {
+        let tracker = InstallTracker::load(config, &root)?;
+        for (pkgid, _) in tracker.all_installed_bins() {
+            if packages.matches(*pkgid)? {
+                pkgids.push(*pkgid);
+            }
         }
+    }
+    if pkgids.is_empty() {
+        let msg = match packages {
+            InstalledPackages::All => message!(config, "no packages are installed"),
+            InstalledPackages::Git(url) => {
+                message!(config, "no packages are installed from `{url}`", url = url)
+            }
+            InstalledPackages::Path(dir) => message!(
+                config,
+                "no packages are installed from `{dir}`",
+                dir = dir.display(),
+            ),
+        };
+        config.shell().warn(msg)?;
+        return Ok(());
+    }
 
-        !failed.is_empty()
-    };
+    let scheduled_error = uninstall_each(
+        pkgids,
+        |pkgid| pkgid.name().as_str(),
+        dry_run,
+        config,
+        |pkgid| {
+            let tracker = InstallTracker::load(config, &root)?;
+            uninstall_pkgid(&root, tracker, pkgid, &[], dry_run, config)
+        },
+    )?;
 
     if scheduled_error {
         bail!("some packages failed to uninstall");
     }
+    warn_dry_run(dry_run, config)
+}
+
+/// Calls `uninstall` for each of `items`, displaying its errors and a
+/// summary of the `name`s of the items. Returns whether any of them failed.
+fn uninstall_each<'a, T: Copy>(
+    items: Vec<T>,
+    name: impl Fn(T) -> &'a str,
+    dry_run: bool,
+    config: &Config,
+    mut uninstall: impl FnMut(T) -> CargoResult<()>,
+) -> CargoResult<bool> {
+    let mut succeeded = vec![];
+    let mut failed = vec![];
+    for item in items {
+        match uninstall(item) {
+            Ok(()) => succeeded.push(name(item)),
+            Err(e) => {
+                crate::display_error(&e, &mut config.shell());
+                failed.push(name(item))
+            }
+        }
+    }
+
+    let mut summary = vec![];
+    if !succeeded.is_empty() && dry_run {
+        summary.push(message!(
+            config,
+            "Would uninstall {names}.",
+            names = succeeded.join(", "),
+        ));
+    } else if !succeeded.is_empty() {
+        summary.push(message!(
+            config,
+            "Successfully uninstalled {names}!",
+            names = succeeded.join(", "),
+        ));
+    }
+    if !failed.is_empty() {
+        summary.push(message!(
+            config,
+            "Failed to uninstall {names} (see error(s) above).",
+            names = failed.join(", "),
+        ));
+    }
 
+    if !succeeded.is_empty() || !failed.is_empty() {
+        config.shell().status("Summary", summary.join(" "))?;
+    }
+
+    Ok(!failed.is_empty())
+}
+
+fn warn_dry_run(dry_run: bool, config: &Config) -> CargoResult<()> {
+    if dry_run {
+        let msg = message!(config, "aborting uninstall due to dry run");
+        config.shell().warn(msg)?;
+    }
     Ok(())
 }
 
@@ -73,15 +189,21 @@ pub fn uninstall_one(
     root: &Filesystem,
     spec: &str,
     bins: &[String],
+    dry_run: bool,
     config: &Config,
 ) -> CargoResult<()> {
     let tracker = InstallTracker::load(config, root)?;
     let all_pkgs = tracker.all_installed_bins().map(|(pkg_id, _set)| *pkg_id);
     let pkgid = PackageIdSpec::query_str(spec, all_pkgs)?;
-    uninstall_pkgid(root, tracker, pkgid, bins, config)
+    uninstall_pkgid(root, tracker, pkgid, bins, dry_run, config)
 }
 
-fn uninstall_cwd(root: &Filesystem, bins: &[String], config: &Config) -> CargoResult<()> {
+fn uninstall_cwd(
+    root: &Filesystem,
+    bins: &[String],
+    dry_run: bool,
+    config: &Config,
+) -> CargoResult<()> {
     let tracker = InstallTracker::load(config, root)?;
     let source_id = SourceId::for_path(config.cwd())?;
     let mut src = path_source(source_id, config)?;
@@ -92,7 +214,7 @@ fn uninstall_cwd(root: &Filesystem, bins: &[String], config: &Config) -> CargoRe
         config,
     )?;
     let pkgid = pkg.package_id();
-    uninstall_pkgid(root, tracker, pkgid, bins, config)
+    uninstall_pkgid(root, tracker, pkgid, bins, dry_run, config)
 }
 
 fn uninstall_pkgid(
@@ -100,6 +222,7 @@ fn uninstall_pkgid(
     mut tracker: InstallTracker,
     pkgid: PackageId,
     bins: &[String],
+    dry_run: bool,
     config: &Config,
 ) -> CargoResult<()> {
     let mut to_remove = Vec::new();
@@ -145,10 +268,16 @@ fn uninstall_pkgid(
         }
         tracker.remove(pkgid, &bins);
     }
-    tracker.save()?;
+    if !dry_run {
+        tracker.save()?;
+    }
     for bin in to_remove {
-        config.shell().status("Removing", bin.display())?;
-        paths::remove_file(bin)?;
+        if dry_run {
+            config.shell().status("Would uninstall", bin.display())?;
+        } else {
+            config.shell().status("Removing", bin.display())?;
+            paths::remove_file(bin)?;
+        }
     }
 
     Ok(())
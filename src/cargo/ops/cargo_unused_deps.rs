@@ -0,0 +1,238 @@
+//! Implementation of `cargo analyze unused-deps`.
+//!
+//! All targets of the selected packages are checked with rustc's
+//! `unused_crate_dependencies` lint forced on. An [`Executor`] records which
+//! of the crates passed with `--extern` each rustc invocation reported as
+//! unused, and a dependency of the manifest is reported when every target it
+//! was passed to reported it.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use cargo_util::ProcessBuilder;
+
+use crate::core::compiler::{BuildContext, CompileMode, Context, Executor, Unit, UnitInterner};
+use crate::core::dependency::DepKind;
+use crate::core::manifest::Target;
+use crate::core::{Dependency, PackageId, Workspace};
+use crate::drop_println;
+use crate::message;
+use crate::ops::cargo_remove::{self, RemoveOptions};
+use crate::ops::{create_bcx, CompileOptions};
+use crate::util::toml_mut::manifest::DepTable;
+use crate::util::CargoResult;
+
+/// The lint reporting the crates passed with `--extern` that aren't used.
+const LINT: &str = "unused_crate_dependencies";
+
+pub struct UnusedDepsOptions {
+    pub compile_opts: CompileOptions,
+    /// Remove the unused dependencies from the manifests, like `cargo remove`.
+    pub remove: bool,
+}
+
+/// Reports the dependencies of the packages selected by `options` that none
+/// of their targets use, and removes them with `remove`.
+///
+/// Crates that are only used from doctests, or only linked for their
+/// symbols, are reported too, so the results need a review.
+pub fn unused_deps(ws: &Workspace<'_>, options: &UnusedDepsOptions) -> CargoResult<()> {
+    let config = ws.config();
+    let interner = UnitInterner::new();
+    let bcx = create_bcx(ws, &options.compile_opts, &interner)?;
+    let members: HashSet<_> = ws.members().map(|pkg| pkg.package_id()).collect();
+    let exec = Arc::new(UnusedDepsExecutor {
+        members,
+        unused: Mutex::new(HashMap::new()),
+    });
+    let cx = Context::new(&bcx)?;
+    cx.compile(&(exec.clone() as Arc<dyn Executor>))?;
+    let unused = exec.unused.lock().unwrap();
+
+    let mut pkgs: Vec<_> = bcx.roots.iter().map(|unit| &unit.pkg).collect();
+    pkgs.sort_by_key(|pkg| pkg.package_id());
+    pkgs.dedup();
+    let mut found = false;
+    for pkg in pkgs {
+        let deps = pkg.dependencies();
+        // Whether each dependency was passed to a target, and whether one of
+        // them used it.
+        let mut passed = vec![false; deps.len()];
+        let mut used = vec![false; deps.len()];
+        for unit in checked_units(&bcx, pkg.package_id()) {
+            let Some(unused) = unused.get(&(pkg.package_id(), unit.target.clone(), unit.mode))
+            else {
+                continue;
+            };
+            for unit_dep in &bcx.unit_graph[unit] {
+                if unit_dep.unit.pkg.package_id() == pkg.package_id()
+                    || !unit_dep.unit.target.is_lib()
+                {
+                    continue;
+                }
+                for (i, dep) in deps.iter().enumerate() {
+                    if dep.package_name() == unit_dep.unit.pkg.name()
+                        && dep.explicit_name_in_toml() == unit_dep.dep_name
+                        && dep_kinds(unit).contains(&dep.kind())
+                    {
+                        passed[i] = true;
+                        used[i] |= !unused.contains(unit_dep.extern_crate_name.as_str());
+                    }
+                }
+            }
+        }
+
+        let mut tables: BTreeMap<(DepKind, Option<String>), Vec<&Dependency>> = BTreeMap::new();
+        for (i, dep) in deps.iter().enumerate() {
+            if passed[i] && !used[i] {
+                let platform = dep.platform().map(|p| p.to_string());
+                tables.entry((dep.kind(), platform)).or_default().push(dep);
+            }
+        }
+        if tables.is_empty() {
+            continue;
+        }
+        found = true;
+        drop_println!(config, "{}", pkg.package_id());
+        for ((kind, platform), deps) in &tables {
+            let table = match platform {
+                Some(platform) => format!("target.'{platform}'.{}", kind.kind_table()),
+                None => kind.kind_table().to_string(),
+            };
+            let names: Vec<_> = deps.iter().map(|dep| dep.name_in_toml().as_str()).collect();
+            drop_println!(config, "  [{table}] {}", names.join(", "));
+        }
+
+        if options.remove {
+            for ((kind, platform), deps) in tables {
+                let mut section = DepTable::new().set_kind(kind);
+                if let Some(platform) = platform {
+                    section = section.set_target(platform);
+                }
+                cargo_remove::remove(&RemoveOptions {
+                    config,
+                    spec: pkg,
+                    dependencies: deps
+                        .iter()
+                        .map(|dep| dep.name_in_toml().to_string())
+                        .collect(),
+                    section,
+                    dry_run: false,
+                })?;
+            }
+        }
+    }
+
+    if !found {
+        let msg = message!(config, "no unused dependencies found");
+        config.shell().note(msg)?;
+    } else if !options.remove {
+        let msg = message!(
+            config,
+            "crates only used by doctests or linked for their symbols are listed too, \
+             pass `--remove` to remove the dependencies"
+        );
+        config.shell().note(msg)?;
+    }
+    Ok(())
+}
+
+/// The units of `pkg_id` that are checked, including its build script.
+fn checked_units<'a>(bcx: &'a BuildContext<'_, '_>, pkg_id: PackageId) -> Vec<&'a Unit> {
+    let mut units: Vec<_> = bcx
+        .unit_graph
+        .keys()
+        .filter(|unit| unit.pkg.package_id() == pkg_id && !unit.mode.is_run_custom_build())
+        .collect();
+    units.sort();
+    units
+}
+
+/// The kinds of dependencies passed to `unit`.
+fn dep_kinds(unit: &Unit) -> &'static [DepKind] {
+    if unit.target.is_custom_build() {
+        &[DepKind::Build]
+    } else if unit.mode.is_any_test() || unit.target.is_example() {
+        &[DepKind::Normal, DepKind::Development]
+    } else {
+        &[DepKind::Normal]
+    }
+}
+
+/// Forces [`LINT`] on for the workspace members, always rebuilding them, and
+/// records the crates reported by it instead of printing the warnings.
+struct UnusedDepsExecutor {
+    members: HashSet<PackageId>,
+    /// The crates that weren't used by each target, by the package, target
+    /// and mode of the unit.
+    unused: Mutex<HashMap<(PackageId, Target, CompileMode), HashSet<String>>>,
+}
+
+impl Executor for UnusedDepsExecutor {
+    fn exec(
+        &self,
+        cmd: &ProcessBuilder,
+        id: PackageId,
+        target: &Target,
+        mode: CompileMode,
+        on_stdout_line: &mut dyn FnMut(&str) -> CargoResult<()>,
+        on_stderr_line: &mut dyn FnMut(&str) -> CargoResult<()>,
+    ) -> CargoResult<()> {
+        if !self.members.contains(&id) {
+            return cmd
+                .exec_with_streaming(on_stdout_line, on_stderr_line, false)
+                .map(drop);
+        }
+        let mut cmd = cmd.clone();
+        cmd.arg(format!("--force-warn={LINT}"));
+        let mut unused = HashSet::new();
+        cmd.exec_with_streaming(
+            on_stdout_line,
+            &mut |line| match unused_crate(line) {
+                Some(name) => {
+                    unused.insert(name);
+                    Ok(())
+                }
+                None => on_stderr_line(line),
+            },
+            false,
+        )?;
+
+        let mut all = self.unused.lock().unwrap();
+        // A target built several times only leaves out the crates that are
+        // unused every time.
+        all.entry((id, target.clone(), mode))
+            .and_modify(|prev| prev.retain(|name| unused.contains(name)))
+            .or_insert(unused);
+        Ok(())
+    }
+
+    fn force_rebuild(&self, unit: &Unit) -> bool {
+        self.members.contains(&unit.pkg.package_id()) && !unit.mode.is_run_custom_build()
+    }
+}
+
+/// Gets the name of the crate of a JSON diagnostic from [`LINT`], like
+/// "extern crate `bar` is unused in crate `foo`".
+fn unused_crate(line: &str) -> Option<String> {
+    #[derive(serde::Deserialize)]
+    struct Diagnostic {
+        message: String,
+        code: Option<Code>,
+    }
+    #[derive(serde::Deserialize)]
+    struct Code {
+        code: String,
+    }
+
+    if !line.starts_with('{') {
+        return None;
+    }
+    let diagnostic: Diagnostic = serde_json::from_str(line).ok()?;
+    if diagnostic.code?.code != LINT {
+        return None;
+    }
+    let (_, rest) = diagnostic.message.split_once('`')?;
+    let (name, _) = rest.split_once('`')?;
+    Some(name.to_string())
+}
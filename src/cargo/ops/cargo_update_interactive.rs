@@ -0,0 +1,297 @@
+//! Interactive mode of `cargo update`, see [`update_interactive`].
+
+use std::collections::{BTreeMap, HashSet};
+use std::io::BufRead;
+use std::path::PathBuf;
+use std::task::Poll;
+
+use anyhow::{bail, Context as _};
+
+use crate::core::registry::PackageRegistry;
+use crate::core::{Dependency, Package, PackageId, QueryKind, Registry, Workspace};
+use crate::drop_println;
+use crate::message;
+use crate::ops::{self, UpdateOptions};
+use crate::util::toml_mut::dependency::{RegistrySource, Source};
+use crate::util::toml_mut::manifest::{DepTable, LocalManifest};
+use crate::util::CargoResult;
+
+/// An update of a direct dependency that the user can select.
+struct Candidate {
+    /// The currently locked package.
+    locked: PackageId,
+    /// The version to update to.
+    version: semver::Version,
+    /// Dependency entries whose requirement has to be changed to allow
+    /// `version`. Empty for semver compatible updates.
+    manifest_edits: Vec<ManifestEdit>,
+}
+
+struct ManifestEdit {
+    manifest_path: PathBuf,
+    table: Vec<String>,
+    key: String,
+}
+
+/// Lists the available updates of the direct dependencies of the workspace
+/// members, asks the user which of them to apply and applies them.
+///
+/// Updates that are semver compatible with every requirement only change
+/// `Cargo.lock`, other updates also change the requirements in the manifests.
+/// Versions that need a newer `rust-version` than the member are skipped.
+pub fn update_interactive(ws: &Workspace<'_>, opts: &UpdateOptions<'_>) -> CargoResult<()> {
+    let config = opts.config;
+    let (_, resolve) = ops::resolve_ws(ws)?;
+    let candidates = {
+        let _lock = config.acquire_package_cache_lock()?;
+        let mut registry = PackageRegistry::new(config)?;
+        registry.lock_patches();
+        find_candidates(ws, &resolve, &mut registry)?
+    };
+    if candidates.is_empty() {
+        let msg = message!(config, "all direct dependencies are up to date");
+        config.shell().note(msg)?;
+        return Ok(());
+    }
+
+    drop_println!(config, "Available updates:");
+    for (i, candidate) in candidates.iter().enumerate() {
+        let note = if candidate.manifest_edits.is_empty() {
+            ""
+        } else {
+            " (changes `Cargo.toml`)"
+        };
+        drop_println!(
+            config,
+            "  {}) {} -> v{}{}",
+            i + 1,
+            candidate.locked,
+            candidate.version,
+            note
+        );
+    }
+    drop_println!(
+        config,
+        "Select the updates to apply (for example `1 3` or `all`), or press enter to skip:"
+    );
+    let mut line = String::new();
+    std::io::stdin()
+        .lock()
+        .read_line(&mut line)
+        .context("failed to read the selection")?;
+
+    // If both updates of a package are selected, the later, incompatible one
+    // wins, whatever order they were typed in.
+    let mut selection = parse_selection(&line, candidates.len())?;
+    selection.sort_unstable();
+    let mut selected = BTreeMap::new();
+    for i in selection {
+        selected.insert(candidates[i].locked, &candidates[i]);
+    }
+
+    let mut edited = false;
+    for candidate in selected.values() {
+        for edit in &candidate.manifest_edits {
+            apply_manifest_edit(edit, &candidate.version)?;
+            edited = true;
+        }
+    }
+    let reloaded;
+    let ws = if edited {
+        reloaded = Workspace::new(ws.root_manifest(), config)?;
+        &reloaded
+    } else {
+        ws
+    };
+
+    for candidate in selected.values() {
+        // Updating a package with a changed requirement re-resolves the
+        // others whose requirements changed as well.
+        let still_locked = ops::load_pkg_lockfile(ws)?.map_or(false, |resolve| {
+            resolve.iter().any(|id| id == candidate.locked)
+        });
+        if !still_locked {
+            continue;
+        }
+        let precise = candidate.version.to_string();
+        let update_opts = UpdateOptions {
+            config,
+            to_update: vec![format!(
+                "{}@{}",
+                candidate.locked.name(),
+                candidate.locked.version()
+            )],
+            precise: Some(&precise),
+            aggressive: false,
+            dry_run: false,
+            workspace: false,
+            summary: opts.summary,
+            json: opts.json,
+        };
+        ops::update_lockfile(ws, &update_opts)?;
+    }
+    Ok(())
+}
+
+fn find_candidates(
+    ws: &Workspace<'_>,
+    resolve: &crate::core::Resolve,
+    registry: &mut PackageRegistry<'_>,
+) -> CargoResult<Vec<Candidate>> {
+    // Registry dependencies of the members along with every requirement on
+    // them.
+    let mut direct: BTreeMap<PackageId, Vec<(&Package, &Dependency)>> = BTreeMap::new();
+    for member in ws.members() {
+        for (dep_id, deps) in resolve.deps(member.package_id()) {
+            if !dep_id.source_id().is_registry() {
+                continue;
+            }
+            let reqs = direct.entry(dep_id).or_default();
+            reqs.extend(deps.iter().map(|dep| (member, dep)));
+        }
+    }
+
+    // Make sure the sources are updated instead of being pinned to the lock
+    // file.
+    let sources: HashSet<_> = direct
+        .keys()
+        .map(|id| id.source_id().with_precise(None))
+        .collect();
+    registry.add_sources(sources)?;
+
+    let mut candidates = Vec::new();
+    for (locked, reqs) in direct {
+        let query = Dependency::parse(locked.name(), None, locked.source_id())?;
+        let summaries = loop {
+            match registry.query_vec(&query, QueryKind::Exact) {
+                Poll::Ready(res) => break res?,
+                Poll::Pending => registry.block_until_ready()?,
+            }
+        };
+        let msrv = reqs
+            .iter()
+            .filter_map(|(member, _)| member.rust_version())
+            .map(parse_rust_version)
+            .min();
+        let mut versions: Vec<_> = summaries
+            .iter()
+            .filter(|s| {
+                let rust_version = s.rust_version().map(|v| parse_rust_version(&v));
+                s.version() > locked.version()
+                    && (s.version().pre.is_empty() || !locked.version().pre.is_empty())
+                    && msrv.map_or(true, |msrv| rust_version.map_or(true, |v| v <= msrv))
+            })
+            .map(|s| s.version().clone())
+            .collect();
+        versions.sort();
+
+        let compatible = versions
+            .iter()
+            .filter(|v| {
+                reqs.iter()
+                    .all(|(_, dep)| dep.version_req().matches_unlocked(v))
+            })
+            .last();
+        if let Some(version) = compatible {
+            candidates.push(Candidate {
+                locked,
+                version: version.clone(),
+                manifest_edits: Vec::new(),
+            });
+        }
+
+        let Some(latest) = versions.last() else {
+            continue;
+        };
+        if Some(latest) == compatible {
+            continue;
+        }
+        if let Some(manifest_edits) = manifest_edits(&reqs, latest)? {
+            candidates.push(Candidate {
+                locked,
+                version: latest.clone(),
+                manifest_edits,
+            });
+        }
+    }
+    Ok(candidates)
+}
+
+/// Finds the dependency entries that have to change to allow `version`.
+///
+/// Returns `None` if one of them is inherited from the workspace, which isn't
+/// supported.
+fn manifest_edits(
+    reqs: &[(&Package, &Dependency)],
+    version: &semver::Version,
+) -> CargoResult<Option<Vec<ManifestEdit>>> {
+    let mut edits = Vec::new();
+    for (member, dep) in reqs {
+        if dep.version_req().matches_unlocked(version) {
+            continue;
+        }
+        let mut table = DepTable::from(dep.kind());
+        if let Some(platform) = dep.platform() {
+            table = table.set_target(platform.to_string());
+        }
+        let table: Vec<String> = table.to_table().into_iter().map(String::from).collect();
+        let key = dep.name_in_toml().to_string();
+
+        let manifest = LocalManifest::try_new(member.manifest_path())?;
+        let inherited = manifest
+            .get_dependency_versions(&key)
+            .filter(|(t, _)| t.to_table() == table)
+            .any(|(_, dep)| matches!(dep.map(|d| d.source), Ok(Some(Source::Workspace(_)))));
+        if inherited {
+            return Ok(None);
+        }
+        edits.push(ManifestEdit {
+            manifest_path: member.manifest_path().to_owned(),
+            table,
+            key,
+        });
+    }
+    Ok(Some(edits))
+}
+
+fn apply_manifest_edit(edit: &ManifestEdit, version: &semver::Version) -> CargoResult<()> {
+    let mut manifest = LocalManifest::try_new(&edit.manifest_path)?;
+    let dep = manifest
+        .get_dependency_versions(&edit.key)
+        .find(|(t, _)| t.to_table() == edit.table)
+        .map(|(_, dep)| dep)
+        .with_context(|| {
+            format!(
+                "failed to find dependency `{}` in {}",
+                edit.key,
+                edit.manifest_path.display()
+            )
+        })??;
+    let dep = dep.set_source(RegistrySource::new(version.to_string()));
+    manifest.insert_into_table(&edit.table, &dep)?;
+    manifest.write()
+}
+
+fn parse_selection(line: &str, len: usize) -> CargoResult<Vec<usize>> {
+    let line = line.trim();
+    if line == "all" {
+        return Ok((0..len).collect());
+    }
+    line.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+        .map(|s| match s.parse::<usize>() {
+            Ok(n) if 1 <= n && n <= len => Ok(n - 1),
+            _ => bail!("invalid selection `{s}`, expected numbers from 1 to {len} or `all`"),
+        })
+        .collect()
+}
+
+/// Parses a `rust-version` like `1.60` into its components.
+fn parse_rust_version(rust_version: &str) -> (u64, u64, u64) {
+    let mut parts = rust_version.split('.').map(|p| p.parse().unwrap_or(0));
+    (
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+    )
+}
@@ -0,0 +1,185 @@
+//! Implementation of `cargo why-version`, see [`why_version`].
+
+use std::task::Poll;
+
+use semver::Version;
+
+use crate::core::dependency::DepKind;
+use crate::core::registry::PackageRegistry;
+use crate::core::{Dependency, PackageId, PackageIdSpec, QueryKind, Registry, Workspace};
+use crate::drop_println;
+use crate::ops;
+use crate::util::{CargoResult, VersionInterval};
+
+/// A requirement of a dependent on the explained package.
+struct Requirement<'a> {
+    parent: PackageId,
+    dep: &'a Dependency,
+    /// The newest available version the requirement allows.
+    newest: Option<&'a Version>,
+}
+
+impl Requirement<'_> {
+    fn describe(&self) -> String {
+        let mut labels = Vec::new();
+        match self.dep.kind() {
+            DepKind::Normal => {}
+            DepKind::Development => labels.push("dev".to_string()),
+            DepKind::Build => labels.push("build".to_string()),
+        }
+        if let Some(platform) = self.dep.platform() {
+            labels.push(platform.to_string());
+        }
+        let labels = if labels.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", labels.join(", "))
+        };
+        format!(
+            "{} v{}{} requires {}",
+            self.parent.name(),
+            self.parent.version(),
+            labels,
+            self.dep.version_req()
+        )
+    }
+}
+
+/// Explains why the package of `spec` is locked at its version.
+///
+/// The requirements come from the edges of the resolve to the package, and
+/// are compared with the versions available in its source. When the newest
+/// version isn't allowed, the binding constraint is the requirement that
+/// doesn't allow it and allows the lowest newest version, so relaxing it is
+/// the first step to upgrade.
+pub fn why_version(ws: &Workspace<'_>, spec: &str) -> CargoResult<()> {
+    let config = ws.config();
+    let (_, resolve) = ops::resolve_ws(ws)?;
+    let id = PackageIdSpec::query_str(spec, resolve.iter())?;
+
+    let mut edges = Vec::new();
+    for parent in resolve.iter() {
+        for (dep_id, deps) in resolve.deps(parent) {
+            if dep_id == id {
+                edges.extend(deps.iter().map(|dep| (parent, dep)));
+            }
+        }
+    }
+    edges.sort_by_key(|(parent, dep)| (*parent, dep.kind()));
+
+    drop_println!(config, "{} v{}", id.name(), id.version());
+    if edges.is_empty() {
+        drop_println!(config, "no package depends on it");
+        return Ok(());
+    }
+
+    let versions = if id.source_id().is_registry() {
+        available_versions(ws, id)?
+    } else {
+        Vec::new()
+    };
+    let requirements: Vec<_> = edges
+        .into_iter()
+        .map(|(parent, dep)| Requirement {
+            parent,
+            dep,
+            newest: versions
+                .iter()
+                .filter(|v| dep.version_req().matches_unlocked(v))
+                .last(),
+        })
+        .collect();
+
+    drop_println!(config, "requirements:");
+    let mut allowed = VersionInterval::ANY;
+    for req in &requirements {
+        let interval = req.dep.version_req().interval();
+        drop_println!(config, "  {} ({})", req.describe(), interval);
+        allowed = allowed.intersect(&interval);
+    }
+    drop_println!(config, "allowed by all requirements: {}", allowed);
+
+    let Some(newest) = versions.last() else {
+        if !id.source_id().is_registry() {
+            drop_println!(
+                config,
+                "only packages from registries have other versions to upgrade to"
+            );
+        }
+        return Ok(());
+    };
+    drop_println!(config, "newest version: v{}", newest);
+    let newest_allowed = versions
+        .iter()
+        .filter(|v| {
+            requirements
+                .iter()
+                .all(|req| req.dep.version_req().matches_unlocked(v))
+        })
+        .last();
+    // `None` sorts first: a requirement allowing no available version binds
+    // the most.
+    let binding = requirements
+        .iter()
+        .filter(|req| req.newest != Some(newest))
+        .min_by_key(|req| req.newest);
+    match binding {
+        None if newest > id.version() => drop_println!(
+            config,
+            "no requirement prevents an upgrade to v{}, run `cargo update -p {}@{}`",
+            newest,
+            id.name(),
+            id.version()
+        ),
+        None => drop_println!(config, "v{} is the newest version", id.version()),
+        Some(binding) => {
+            let allows = match binding.newest {
+                Some(version) => format!("allows up to v{version}"),
+                None => "allows none of the available versions".to_string(),
+            };
+            drop_println!(
+                config,
+                "binding constraint: {}, which {}",
+                binding.describe(),
+                allows
+            );
+            if let Some(version) = newest_allowed.filter(|v| *v > id.version()) {
+                drop_println!(
+                    config,
+                    "v{} is allowed by all requirements, run `cargo update -p {}@{}`",
+                    version,
+                    id.name(),
+                    id.version()
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The versions of the package of `id` in its source, newest last, without
+/// pre-releases unless `id` is one.
+fn available_versions(ws: &Workspace<'_>, id: PackageId) -> CargoResult<Vec<Version>> {
+    let config = ws.config();
+    let _lock = config.acquire_package_cache_lock()?;
+    let mut registry = PackageRegistry::new(config)?;
+    registry.lock_patches();
+    // Make sure the source is updated instead of being pinned to the lock
+    // file.
+    registry.add_sources([id.source_id().with_precise(None)])?;
+    let query = Dependency::parse(id.name(), None, id.source_id())?;
+    let summaries = loop {
+        match registry.query_vec(&query, QueryKind::Exact) {
+            Poll::Ready(res) => break res?,
+            Poll::Pending => registry.block_until_ready()?,
+        }
+    };
+    let mut versions: Vec<_> = summaries
+        .iter()
+        .map(|s| s.version().clone())
+        .filter(|v| v.pre.is_empty() || !id.version().pre.is_empty())
+        .collect();
+    versions.sort();
+    versions.dedup();
+    Ok(versions)
+}
@@ -1,12 +1,13 @@
 use std::collections::{btree_map, BTreeMap, BTreeSet};
 use std::env;
+use std::fs;
 use std::io::prelude::*;
-use std::io::SeekFrom;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::task::Poll;
 
 use anyhow::{bail, format_err, Context as _};
+use cargo_util::Sha256;
 use ops::FilterRule;
 use serde::{Deserialize, Serialize};
 
@@ -27,6 +28,11 @@ use crate::util::{FileLock, Filesystem};
 /// changes in v1 that are not in v2 (such as when an older version of Cargo
 /// is used), it will automatically propagate those changes to v2.
 ///
+/// v2 also records the hash and size of each installed binary, so that
+/// `cargo install --repair` can find the binaries that were deleted or
+/// modified outside of Cargo. Being optional, these fields didn't need a new
+/// version of the file.
+///
 /// This maintains a filesystem lock, preventing other instances of Cargo from
 /// modifying at the same time. Drop the value to unlock.
 ///
@@ -76,15 +82,62 @@ struct InstallInfo {
     /// Either the host or the value specified in `--target`.
     /// None if unknown (when loading from v1).
     target: Option<String>,
-    /// Output of `rustc -V`.
+    /// Output of `rustc -vV`.
     /// None if unknown (when loading from v1).
-    /// Currently not used, possibly may be used in the future.
+    /// Only displayed by `cargo install --list --verbose`.
     rustc: Option<String>,
+    /// The hash and size of the installed binaries, by name.
+    /// Missing for binaries installed by older versions of Cargo, and may
+    /// be stale for binaries they reinstalled.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    bin_hashes: BTreeMap<String, BinHash>,
     /// Forwards compatibility.
     #[serde(flatten)]
     other: BTreeMap<String, serde_json::Value>,
 }
 
+/// The contents of an installed binary, to tell whether it was modified.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+struct BinHash {
+    /// SHA-256 of the binary, in hex.
+    sha256: String,
+    size: u64,
+}
+
+impl BinHash {
+    fn of(path: &Path) -> CargoResult<BinHash> {
+        Ok(BinHash {
+            sha256: Sha256::new().update_path(path)?.finish_hex(),
+            size: fs::metadata(path)?.len(),
+        })
+    }
+}
+
+/// An installed package with binaries which were deleted or modified
+/// outside of Cargo, see [`InstallTracker::damaged_installs`].
+pub struct DamagedInstall {
+    pub pkg_id: PackageId,
+    /// The names of the damaged binaries, and whether each was deleted
+    /// rather than modified.
+    pub bins: BTreeMap<String, bool>,
+    pub settings: InstallSettings,
+}
+
+/// The settings an installed package was built with, as recorded in the
+/// install root, see [`InstallTracker::install_settings`].
+pub struct InstallSettings {
+    /// Features explicitly enabled.
+    pub features: Vec<String>,
+    pub all_features: bool,
+    pub no_default_features: bool,
+    /// The profile it was built with.
+    pub profile: String,
+    /// The target it was built for, None if unknown.
+    pub target: Option<String>,
+    /// Output of `rustc -vV` for the compiler which built it, None if unknown.
+    pub rustc: Option<String>,
+}
+
 /// Tracking information for the set of installed packages.
 #[derive(Default, Deserialize, Serialize)]
 pub struct CrateListingV1 {
@@ -263,10 +316,11 @@ impl InstallTracker {
             .collect()
     }
 
-    /// Mark that a package was installed.
+    /// Mark that a package was installed, with its `bins` in `dst`.
     pub fn mark_installed(
         &mut self,
         package: &Package,
+        dst: &Path,
         bins: &BTreeSet<String>,
         version_req: Option<String>,
         opts: &CompileOptions,
@@ -274,20 +328,56 @@ impl InstallTracker {
         rustc: &str,
     ) {
         self.v2
-            .mark_installed(package, bins, version_req, opts, target, rustc);
+            .mark_installed(package, dst, bins, version_req, opts, target, rustc);
         self.v1.mark_installed(package, bins);
     }
 
+    /// Finds the installed packages with binaries in `dst` which were
+    /// deleted, or modified since Cargo installed them. Binaries without a
+    /// recorded hash can only be found deleted.
+    ///
+    /// Returns them along with the number of binaries which were checked.
+    pub fn damaged_installs(&self, dst: &Path) -> CargoResult<(Vec<DamagedInstall>, usize)> {
+        let mut damaged = Vec::new();
+        let mut checked = 0;
+        for (pkg_id, info) in &self.v2.installs {
+            let mut bins = BTreeMap::new();
+            for bin in &info.bins {
+                checked += 1;
+                let path = dst.join(bin);
+                if !path.exists() {
+                    bins.insert(bin.clone(), true);
+                    continue;
+                }
+                let Some(recorded) = info.bin_hashes.get(bin) else {
+                    continue;
+                };
+                // The size is enough to tell most modifications.
+                if fs::metadata(&path)?.len() != recorded.size || &BinHash::of(&path)? != recorded {
+                    bins.insert(bin.clone(), false);
+                }
+            }
+            if !bins.is_empty() {
+                damaged.push(DamagedInstall {
+                    pkg_id: *pkg_id,
+                    bins,
+                    settings: info.settings(),
+                });
+            }
+        }
+        Ok((damaged, checked))
+    }
+
     /// Save tracking information to disk.
-    pub fn save(&self) -> CargoResult<()> {
-        self.v1.save(&self.v1_lock).with_context(|| {
+    pub fn save(&mut self) -> CargoResult<()> {
+        self.v1.save(&mut self.v1_lock).with_context(|| {
             format!(
                 "failed to write crate metadata at `{}`",
                 self.v1_lock.path().to_string_lossy()
             )
         })?;
 
-        self.v2.save(&self.v2_lock).with_context(|| {
+        self.v2.save(&mut self.v2_lock).with_context(|| {
             format!(
                 "failed to write crate metadata at `{}`",
                 self.v2_lock.path().to_string_lossy()
@@ -303,6 +393,12 @@ impl InstallTracker {
         self.v1.v1.iter()
     }
 
+    /// The settings a particular package was installed with.
+    /// Returns None if the package is not installed.
+    pub fn install_settings(&self, pkg_id: PackageId) -> Option<InstallSettings> {
+        self.v2.installs.get(&pkg_id).map(InstallInfo::settings)
+    }
+
     /// Set of binaries installed by a particular package.
     /// Returns None if the package is not installed.
     pub fn installed_bins(&self, pkg_id: PackageId) -> Option<&BTreeSet<String>> {
@@ -354,13 +450,9 @@ impl CrateListingV1 {
         }
     }
 
-    fn save(&self, lock: &FileLock) -> CargoResult<()> {
-        let mut file = lock.file();
-        file.seek(SeekFrom::Start(0))?;
-        file.set_len(0)?;
+    fn save(&self, lock: &mut FileLock) -> CargoResult<()> {
         let data = toml::to_string_pretty(self)?;
-        file.write_all(data.as_bytes())?;
-        Ok(())
+        lock.write_atomic(data.as_bytes())
     }
 }
 
@@ -400,6 +492,7 @@ impl CrateListingV2 {
     fn mark_installed(
         &mut self,
         pkg: &Package,
+        dst: &Path,
         bins: &BTreeSet<String>,
         version_req: Option<String>,
         opts: &CompileOptions,
@@ -410,6 +503,18 @@ impl CrateListingV2 {
         for info in &mut self.installs.values_mut() {
             for bin in bins {
                 info.bins.remove(bin);
+                info.bin_hashes.remove(bin);
+            }
+        }
+        let mut bin_hashes = BTreeMap::new();
+        for bin in bins {
+            match BinHash::of(&dst.join(bin)) {
+                Ok(hash) => {
+                    bin_hashes.insert(bin.clone(), hash);
+                }
+                // It can't be checked by `--repair` then, but is still
+                // installed.
+                Err(e) => tracing::debug!("failed to hash `{bin}`: {e:?}"),
             }
         }
         // Remove entries where `bins` is empty.
@@ -431,6 +536,7 @@ impl CrateListingV2 {
             info.profile = opts.build_config.requested_profile.to_string();
             info.target = Some(target.to_string());
             info.rustc = Some(rustc.to_string());
+            info.bin_hashes.append(&mut bin_hashes);
         } else {
             self.installs.insert(
                 pkg.package_id(),
@@ -443,6 +549,7 @@ impl CrateListingV2 {
                     profile: opts.build_config.requested_profile.to_string(),
                     target: Some(target.to_string()),
                     rustc: Some(rustc.to_string()),
+                    bin_hashes,
                     other: BTreeMap::new(),
                 },
             );
@@ -457,19 +564,16 @@ impl CrateListingV2 {
 
         for bin in bins {
             info_entry.get_mut().bins.remove(bin);
+            info_entry.get_mut().bin_hashes.remove(bin);
         }
         if info_entry.get().bins.is_empty() {
             info_entry.remove();
         }
     }
 
-    fn save(&self, lock: &FileLock) -> CargoResult<()> {
-        let mut file = lock.file();
-        file.seek(SeekFrom::Start(0))?;
-        file.set_len(0)?;
+    fn save(&self, lock: &mut FileLock) -> CargoResult<()> {
         let data = serde_json::to_string(self)?;
-        file.write_all(data.as_bytes())?;
-        Ok(())
+        lock.write_atomic(data.as_bytes())
     }
 }
 
@@ -484,10 +588,22 @@ impl InstallInfo {
             profile: "release".to_string(),
             target: None,
             rustc: None,
+            bin_hashes: BTreeMap::new(),
             other: BTreeMap::new(),
         }
     }
 
+    fn settings(&self) -> InstallSettings {
+        InstallSettings {
+            features: self.features.iter().cloned().collect(),
+            all_features: self.all_features,
+            no_default_features: self.no_default_features,
+            profile: self.profile.clone(),
+            target: self.target.clone(),
+            rustc: self.rustc.clone(),
+        }
+    }
+
     /// Determine if this installation is "up to date", or if it needs to be reinstalled.
     ///
     /// This does not do Package/Source/Version checking.
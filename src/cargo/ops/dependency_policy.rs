@@ -0,0 +1,130 @@
+//! Checks of the `[dependency-policy]` config table, enabled with
+//! `-Zdependency-policy`.
+//!
+//! * `forbid-git-deps` rejects builds which include packages from git
+//!   repositories, either in every profile or only in the listed ones.
+//!   It is checked against the unit graph, so only the packages actually
+//!   built count.
+//! * `forbid-path-deps-on-publish` rejects publishing packages with path
+//!   dependencies, which are published as plain registry dependencies and
+//!   may differ from the local code the package was tested with.
+
+use std::collections::BTreeSet;
+
+use serde::Deserialize;
+
+use crate::core::compiler::unit_graph::UnitGraph;
+use crate::core::resolver::errors::describe_path;
+use crate::core::{Package, Resolve};
+use crate::util::interning::InternedString;
+use crate::util::CargoResult;
+use crate::Config;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct DependencyPolicyConfig {
+    forbid_git_deps: Option<ForbidGitDeps>,
+    forbid_path_deps_on_publish: Option<bool>,
+}
+
+/// The value of `dependency-policy.forbid-git-deps`.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ForbidGitDeps {
+    Always(bool),
+    /// The names of the profiles where git dependencies are forbidden.
+    Profiles(Vec<String>),
+}
+
+impl ForbidGitDeps {
+    fn applies_to(&self, profile: &str) -> bool {
+        match self {
+            ForbidGitDeps::Always(forbid) => *forbid,
+            ForbidGitDeps::Profiles(profiles) => profiles.iter().any(|p| p == profile),
+        }
+    }
+}
+
+fn policy(config: &Config) -> CargoResult<DependencyPolicyConfig> {
+    if !config.cli_unstable().dependency_policy {
+        return Ok(Default::default());
+    }
+    Ok(config
+        .get::<Option<DependencyPolicyConfig>>("dependency-policy")?
+        .unwrap_or_default())
+}
+
+/// Errors if `dependency-policy.forbid-git-deps` applies to `profile` and
+/// a package of `unit_graph` comes from a git repository, showing how each
+/// of them is pulled in.
+pub fn check_git_deps(
+    config: &Config,
+    resolve: &Resolve,
+    unit_graph: &UnitGraph,
+    profile: InternedString,
+) -> CargoResult<()> {
+    let Some(forbid) = policy(config)?.forbid_git_deps else {
+        return Ok(());
+    };
+    if !forbid.applies_to(&profile) {
+        return Ok(());
+    }
+    let git_pkgs: BTreeSet<_> = unit_graph
+        .keys()
+        .map(|unit| unit.pkg.package_id())
+        .filter(|pkg_id| pkg_id.source_id().is_git())
+        .collect();
+    if git_pkgs.is_empty() {
+        return Ok(());
+    }
+    let paths: Vec<_> = git_pkgs
+        .iter()
+        .map(|pkg_id| {
+            describe_path(
+                resolve
+                    .path_to_top(pkg_id)
+                    .into_iter()
+                    .map(|(p, d)| (p, d.and_then(|d| d.iter().next()))),
+            )
+        })
+        .collect();
+    anyhow::bail!(
+        "git dependencies are forbidden in the `{}` profile by `dependency-policy.forbid-git-deps`\n\
+         \n\
+         {}",
+        profile,
+        paths.join("\n\n")
+    )
+}
+
+/// Errors if `dependency-policy.forbid-path-deps-on-publish` is set and
+/// `pkg` would be published with path dependencies.
+pub fn check_path_deps_on_publish(config: &Config, pkg: &Package) -> CargoResult<()> {
+    if policy(config)?.forbid_path_deps_on_publish != Some(true) {
+        return Ok(());
+    }
+    let deps: Vec<_> = pkg
+        .dependencies()
+        .iter()
+        // Dev-dependencies without a version are removed when publishing.
+        .filter(|dep| dep.source_id().is_path() && (dep.is_transitive() || dep.specified_req()))
+        .map(|dep| {
+            format!(
+                "\n    ... has path dependency `{}` on `{}`",
+                dep.name_in_toml(),
+                dep.source_id().url().to_file_path().unwrap().display()
+            )
+        })
+        .collect();
+    if deps.is_empty() {
+        return Ok(());
+    }
+    anyhow::bail!(
+        "path dependencies cannot be published with \
+         `dependency-policy.forbid-path-deps-on-publish`\n\
+         \n\
+         package `{}`{}",
+        pkg.package_id(),
+        deps.concat()
+    )
+}
@@ -46,21 +46,26 @@ use std::{env, fs, str};
 
 use anyhow::{bail, Context as _};
 use cargo_util::{exit_status_to_string, is_simple_exit_code, paths, ProcessBuilder};
-use log::{debug, trace, warn};
 use rustfix::diagnostics::Diagnostic;
 use rustfix::{self, CodeFix};
 use semver::Version;
+use tracing::{debug, trace, warn};
 
 use crate::core::compiler::RustcTargetData;
 use crate::core::resolver::features::{DiffMap, FeatureOpts, FeatureResolver, FeaturesFor};
 use crate::core::resolver::{HasDevUnits, Resolve, ResolveBehavior};
-use crate::core::{Edition, MaybePackage, PackageId, Workspace};
+use crate::core::{Edition, MaybePackage, Package, PackageId, PackageIdSpec, Workspace};
+use crate::message;
 use crate::ops::resolve::WorkspaceResolve;
-use crate::ops::{self, CompileOptions};
+use crate::ops::{self, CompileOptions, Packages};
+use crate::sources::{CRATES_IO_INDEX, CRATES_IO_REGISTRY};
 use crate::util::diagnostic_server::{Message, RustfixDiagnosticServer};
 use crate::util::errors::CargoResult;
+use crate::util::toml_mut::manifest::LocalManifest;
 use crate::util::Config;
-use crate::util::{existing_vcs_repo, LockServer, LockServerClient};
+use crate::util::{
+    existing_vcs_repo, git_dirty_files, GitFileStatus, LockServer, LockServerClient,
+};
 use crate::{drop_eprint, drop_eprintln};
 
 /// **Internal only.**
@@ -80,6 +85,12 @@ const IDIOMS_ENV_INTERNAL: &str = "__CARGO_FIX_IDIOMS";
 
 pub struct FixOptions {
     pub edition: bool,
+    /// Migrate the selected packages through the editions up to this one,
+    /// updating their manifests after each edition.
+    pub to_edition: Option<Edition>,
+    /// Rewrite the `[replace]` table of the workspace into `[patch]` tables
+    /// instead of fixing any code.
+    pub replace_to_patch: bool,
     pub idioms: bool,
     pub compile_opts: CompileOptions,
     pub allow_dirty: bool,
@@ -90,6 +101,17 @@ pub struct FixOptions {
 
 pub fn fix(ws: &Workspace<'_>, opts: &mut FixOptions) -> CargoResult<()> {
     check_version_control(ws.config(), opts)?;
+    if opts.replace_to_patch {
+        return migrate_replace_to_patch(ws);
+    }
+    if let Some(to_edition) = opts.to_edition {
+        return migrate_to_edition(ws, opts, to_edition);
+    }
+    fix_once(ws, opts)
+}
+
+/// Runs a single `cargo check` applying the fixes.
+fn fix_once(ws: &Workspace<'_>, opts: &mut FixOptions) -> CargoResult<()> {
     if opts.edition {
         check_resolver_change(ws, opts)?;
     }
@@ -143,11 +165,276 @@ pub fn fix(ws: &Workspace<'_>, opts: &mut FixOptions) -> CargoResult<()> {
     Ok(())
 }
 
+/// Migrates the selected packages to `to_edition`, one edition at a time.
+///
+/// Each step fixes the packages not yet on `to_edition` for their next
+/// edition, then sets that edition in their manifests, so the members of a
+/// workspace with mixed editions each go through the editions they need.
+fn migrate_to_edition(
+    ws: &Workspace<'_>,
+    opts: &mut FixOptions,
+    to_edition: Edition,
+) -> CargoResult<()> {
+    let config = ws.config();
+    let selected: HashSet<PackageId> = opts
+        .compile_opts
+        .spec
+        .get_packages(ws)?
+        .iter()
+        .map(|pkg| pkg.package_id())
+        .collect();
+    for pkg in ws.members() {
+        if selected.contains(&pkg.package_id()) && pkg.manifest().edition() > to_edition {
+            let msg = message!(
+                config,
+                "`{name}` is on edition {edition}, which is newer than {to_edition}, \
+                 skipping it",
+                name = pkg.name(),
+                edition = pkg.manifest().edition(),
+                to_edition = to_edition,
+            );
+            config.shell().warn(msg)?;
+        }
+    }
+
+    let require_optional_deps = ws.require_optional_deps();
+    // Each step moves the pending packages to their next edition, so all of
+    // them are on `to_edition` after at most one step per edition.
+    for _ in 0..=Edition::CLI_VALUES.len() {
+        // Reload the workspace to pick up the editions set by the last step.
+        let mut ws = Workspace::new(ws.root_manifest(), config)?;
+        ws.set_require_optional_deps(require_optional_deps);
+        let pending: Vec<&Package> = ws
+            .members()
+            .filter(|pkg| {
+                selected.contains(&pkg.package_id()) && pkg.manifest().edition() < to_edition
+            })
+            .collect();
+        if pending.is_empty() {
+            return Ok(());
+        }
+        let inherited = check_inherited_edition(&ws, &pending)?;
+        opts.compile_opts.spec = Packages::Packages(
+            pending
+                .iter()
+                .map(|pkg| PackageIdSpec::from_package_id(pkg.package_id()).to_string())
+                .collect(),
+        );
+        fix_once(&ws, opts)?;
+        set_next_edition(&ws, &pending, inherited)?;
+    }
+    bail!("failed to migrate the selected packages to edition {to_edition}")
+}
+
+/// Checks that a package inheriting `workspace.package.edition` is only
+/// migrated along with all the other members inheriting it, since migrating
+/// it changes the edition of the whole workspace. Returns whether any of
+/// `pending` inherits its edition.
+fn check_inherited_edition(ws: &Workspace<'_>, pending: &[&Package]) -> CargoResult<bool> {
+    let is_pending = |pkg: &Package| pending.iter().any(|p| p.package_id() == pkg.package_id());
+    let mut inheriting = Vec::new();
+    for pkg in ws.members() {
+        if inherits_edition(pkg)? {
+            inheriting.push(pkg);
+        }
+    }
+    let Some(migrated) = inheriting.iter().find(|pkg| is_pending(pkg)) else {
+        return Ok(false);
+    };
+    if let Some(skipped) = inheriting.iter().find(|pkg| !is_pending(pkg)) {
+        bail!(
+            "`{}` inherits `workspace.package.edition` along with `{}`, \
+             select both packages to migrate their edition",
+            migrated.name(),
+            skipped.name()
+        );
+    }
+    Ok(true)
+}
+
+/// Whether `pkg` has `edition.workspace = true`.
+fn inherits_edition(pkg: &Package) -> CargoResult<bool> {
+    let manifest = LocalManifest::try_new(pkg.manifest_path())?;
+    Ok(manifest
+        .data
+        .get("package")
+        .and_then(|p| p.get("edition"))
+        .and_then(|e| e.as_table_like())
+        .and_then(|e| e.get("workspace"))
+        .and_then(|w| w.as_bool())
+        == Some(true))
+}
+
+/// Sets the edition after the current one of `pending` in their manifests,
+/// or in `workspace.package.edition` if `inherited`.
+fn set_next_edition(ws: &Workspace<'_>, pending: &[&Package], inherited: bool) -> CargoResult<()> {
+    let config = ws.config();
+    for pkg in pending {
+        if inherits_edition(pkg)? {
+            continue;
+        }
+        let from = pkg.manifest().edition();
+        let to = from.saturating_next();
+        let mut manifest = LocalManifest::try_new(pkg.manifest_path())?;
+        manifest.data["package"]["edition"] = toml_edit::value(to.to_string());
+        manifest.write()?;
+        let msg = message!(
+            config,
+            "edition of `{name}` from {from} to {to}",
+            name = pkg.name(),
+            from = from,
+            to = to,
+        );
+        config.shell().status("Updating", msg)?;
+    }
+    if inherited {
+        let from = pending
+            .iter()
+            .find(|pkg| inherits_edition(pkg).unwrap_or(false))
+            .map(|pkg| pkg.manifest().edition())
+            .unwrap();
+        let to = from.saturating_next();
+        let mut manifest = LocalManifest::try_new(ws.root_manifest())?;
+        manifest.data["workspace"]["package"]["edition"] = toml_edit::value(to.to_string());
+        // `LocalManifest::write` refuses to write virtual manifests.
+        paths::write(ws.root_manifest(), manifest.data.to_string())?;
+        let msg = message!(
+            config,
+            "edition of the workspace from {from} to {to}",
+            from = from,
+            to = to,
+        );
+        config.shell().status("Updating", msg)?;
+    }
+    Ok(())
+}
+
+/// Rewrites the `[replace]` table of the workspace root manifest into
+/// `[patch]` tables, keeping the comments and formatting of its entries.
+///
+/// A manifest can't have both tables, so nothing is changed unless every
+/// entry can be migrated.
+fn migrate_replace_to_patch(ws: &Workspace<'_>) -> CargoResult<()> {
+    let config = ws.config();
+    let mut manifest = LocalManifest::try_new(ws.root_manifest())?;
+    let Some(replace) = manifest.data.remove("replace") else {
+        let msg = message!(
+            config,
+            "no `[replace]` table to migrate in `{manifest}`",
+            manifest = ws.root_manifest().display(),
+        );
+        config.shell().warn(msg)?;
+        return Ok(());
+    };
+    let Ok(mut replace) = replace.into_table() else {
+        bail!(
+            "`replace` in `{}` is not a table",
+            ws.root_manifest().display()
+        );
+    };
+
+    // The `[patch]` table and package name of each entry.
+    let mut migrations = Vec::new();
+    let mut failed = false;
+    for (key, item) in replace.iter() {
+        let reason = match PackageIdSpec::parse(key) {
+            Err(_) => Some("it is not a valid package ID specification".to_string()),
+            Ok(spec) => {
+                let source = match spec.url().map(|url| url.as_str()) {
+                    None | Some(CRATES_IO_INDEX) => CRATES_IO_REGISTRY.to_string(),
+                    Some(url) => url.to_string(),
+                };
+                let name = spec.name();
+                let is_git_or_path = item
+                    .as_table_like()
+                    .map_or(false, |t| t.contains_key("git") || t.contains_key("path"));
+                if !is_git_or_path {
+                    Some("`[patch]` only supports `git` and `path` replacements here".to_string())
+                } else if migrations
+                    .iter()
+                    .any(|(_, s, n)| *s == source && *n == name)
+                {
+                    Some(format!(
+                        "another entry already replaces `{name}` from the same source"
+                    ))
+                } else {
+                    migrations.push((key.to_string(), source, name));
+                    None
+                }
+            }
+        };
+        if let Some(reason) = reason {
+            let msg = message!(
+                config,
+                "cannot migrate `[replace]` entry `{key}`: {reason}",
+                key = key,
+                reason = reason,
+            );
+            config.shell().warn(msg)?;
+            failed = true;
+        }
+    }
+    if failed {
+        bail!(
+            "`[replace]` was not migrated, since a manifest can't have both \
+             `[replace]` and `[patch]`; migrate the entries above by hand"
+        );
+    }
+
+    let patch = manifest.data["patch"].or_insert({
+        let mut patch = toml_edit::Table::new();
+        patch.set_implicit(true);
+        toml_edit::Item::Table(patch)
+    });
+    let Some(patch) = patch.as_table_mut() else {
+        bail!(
+            "`patch` in `{}` is not a table",
+            ws.root_manifest().display()
+        );
+    };
+    let mut decor = Some(replace.decor().clone());
+    for (key, source, name) in migrations {
+        let (replace_key, item) = replace.remove_entry(&key).unwrap();
+        let table = patch.entry(&source).or_insert_with(|| {
+            let mut table = toml_edit::Table::new();
+            if let Some(position) = replace.position() {
+                table.set_position(position);
+            }
+            // The comments before `[replace]` go before the first table.
+            if let Some(decor) = decor.take() {
+                *table.decor_mut() = decor;
+            }
+            toml_edit::Item::Table(table)
+        });
+        let Some(table) = table.as_table_like_mut() else {
+            bail!(
+                "`patch.{source}` in `{}` is not a table",
+                ws.root_manifest().display()
+            );
+        };
+        table.insert(name.as_str(), item);
+        if let Some(decor) = table.key_decor_mut(name.as_str()) {
+            *decor = replace_key.decor().clone();
+        }
+        let patch_key = toml_edit::Key::new(source.as_str());
+        let msg = message!(
+            config,
+            "`[replace]` entry `{key}` to `[patch.{patch_key}]`",
+            key = key,
+            patch_key = patch_key.display_repr(),
+        );
+        config.shell().status("Migrating", msg)?;
+    }
+    // `LocalManifest::write` refuses to write virtual manifests.
+    paths::write(ws.root_manifest(), manifest.data.to_string())?;
+    Ok(())
+}
+
 fn check_version_control(config: &Config, opts: &FixOptions) -> CargoResult<()> {
     if opts.allow_no_vcs {
         return Ok(());
     }
-    if !existing_vcs_repo(config.cwd(), config.cwd()) {
+    if !existing_vcs_repo(config.cwd(), config) {
         bail!(
             "no VCS found for this package and `cargo fix` can potentially \
              perform destructive changes; if you'd like to suppress this \
@@ -162,28 +449,25 @@ fn check_version_control(config: &Config, opts: &FixOptions) -> CargoResult<()>
     let mut dirty_files = Vec::new();
     let mut staged_files = Vec::new();
     if let Ok(repo) = git2::Repository::discover(config.cwd()) {
-        let mut repo_opts = git2::StatusOptions::new();
-        repo_opts.include_ignored(false);
-        repo_opts.include_untracked(true);
-        for status in repo.statuses(Some(&mut repo_opts))?.iter() {
-            if let Some(path) = status.path() {
-                match status.status() {
-                    git2::Status::CURRENT => (),
-                    git2::Status::INDEX_NEW
-                    | git2::Status::INDEX_MODIFIED
-                    | git2::Status::INDEX_DELETED
-                    | git2::Status::INDEX_RENAMED
-                    | git2::Status::INDEX_TYPECHANGE => {
-                        if !opts.allow_staged {
-                            staged_files.push(path.to_string())
-                        }
+        let workdir = repo.workdir().unwrap_or_else(|| repo.path()).to_path_buf();
+        for file in git_dirty_files(&repo, false)? {
+            let path = file.path.strip_prefix(&workdir).unwrap_or(&file.path);
+            let mut path = path.display().to_string();
+            // Untracked directories aren't recursed into, like `git status`.
+            if file.status == GitFileStatus::Untracked && file.path.is_dir() {
+                path.push('/');
+            }
+            match file.status {
+                GitFileStatus::Staged => {
+                    if !opts.allow_staged {
+                        staged_files.push(path)
                     }
-                    _ => {
-                        if !opts.allow_dirty {
-                            dirty_files.push(path.to_string())
-                        }
+                }
+                GitFileStatus::Dirty | GitFileStatus::Untracked | GitFileStatus::Ignored => {
+                    if !opts.allow_dirty {
+                        dirty_files.push(path)
                     }
-                };
+                }
             }
         }
     }
@@ -279,9 +563,11 @@ fn check_resolver_change(ws: &Workspace<'_>, opts: &FixOptions) -> CargoResult<(
     // Only display unique changes with dev-dependencies.
     with_dev_diffs.retain(|k, vals| without_dev_diffs.get(k) != Some(vals));
     let config = ws.config();
-    config.shell().note(
-        "Switching to Edition 2021 will enable the use of the version 2 feature resolver in Cargo.",
-    )?;
+    let msg = message!(
+        config,
+        "Switching to Edition 2021 will enable the use of the version 2 feature resolver in Cargo."
+    );
+    config.shell().note(msg)?;
     drop_eprintln!(
         config,
         "This may cause some dependencies to be built with fewer features enabled than previously."
@@ -332,14 +618,16 @@ fn report_maybe_diesel(config: &Config, resolve: &Resolve) -> CargoResult<()> {
     }
 
     if resolve.iter().any(is_broken_diesel) && resolve.iter().any(is_broken_diesel_migration) {
-        config.shell().note(
+        let msg = message!(
+            config,
             "\
 This project appears to use both diesel and diesel_migrations. These packages have
 a known issue where the build may fail due to the version 2 resolver preventing
 feature unification between those two packages. Please update to at least diesel 1.4.8
 to prevent this issue from happening.
-",
-        )?;
+"
+        );
+        config.shell().note(msg)?;
     }
     Ok(())
 }
@@ -1,6 +1,9 @@
+use std::collections::HashSet;
 use std::io::prelude::*;
+use std::path::PathBuf;
 
 use crate::core::{resolver, Resolve, ResolveVersion, Workspace};
+use crate::message;
 use crate::util::errors::CargoResult;
 use crate::util::toml as cargo_toml;
 use crate::util::Filesystem;
@@ -19,8 +22,29 @@ pub fn load_pkg_lockfile(ws: &Workspace<'_>) -> CargoResult<Option<Resolve>> {
     f.read_to_string(&mut s)
         .with_context(|| format!("failed to read file: {}", f.path().display()))?;
 
+    let overlay = match overlay_path(ws) {
+        Some(path) => {
+            let contents = cargo_util::paths::read(&path)?;
+            let overlay: toml::Table = cargo_toml::parse_document(&contents, &path, ws.config())
+                .with_context(|| {
+                    format!("failed to parse lock file overlay at: {}", path.display())
+                })?;
+            let msg = message!(
+                ws.config(),
+                "using lock file overlay `{path}`, `Cargo.lock` will not be updated",
+                path = path.display(),
+            );
+            ws.config().shell().warn(msg)?;
+            Some(overlay)
+        }
+        None => None,
+    };
+
     let resolve = (|| -> CargoResult<Option<Resolve>> {
-        let resolve: toml::Table = cargo_toml::parse_document(&s, f.path(), ws.config())?;
+        let mut resolve: toml::Table = cargo_toml::parse_document(&s, f.path(), ws.config())?;
+        if let Some(overlay) = overlay {
+            apply_overlay(&mut resolve, overlay)?;
+        }
         let v: resolver::EncodableResolve = resolve.try_into()?;
         Ok(Some(v.into_resolve(&s, ws)?))
     })()
@@ -61,6 +85,13 @@ pub fn write_pkg_lockfile(ws: &Workspace<'_>, resolve: &mut Resolve) -> CargoRes
         );
     }
 
+    // The resolve was guided by the overlay, so writing it would leak the
+    // local overrides into the canonical lock file. This comes after the
+    // `--locked` check, which still fails when `Cargo.lock` is out of date.
+    if orig.is_some() && overlay_path(ws).is_some() {
+        return Ok(());
+    }
+
     // While we're updating the lock file anyway go ahead and update its
     // encoding to whatever the latest default is. That way we can slowly roll
     // out lock file updates as they're otherwise already updated, and changes
@@ -82,11 +113,7 @@ pub fn write_pkg_lockfile(ws: &Workspace<'_>, resolve: &mut Resolve) -> CargoRes
     // Ok, if that didn't work just write it out
     lock_root
         .open_rw("Cargo.lock", ws.config(), "Cargo.lock file")
-        .and_then(|mut f| {
-            f.file().set_len(0)?;
-            f.write_all(out.as_bytes())?;
-            Ok(())
-        })
+        .and_then(|mut f| f.write_atomic(out.as_bytes()))
         .with_context(|| {
             format!(
                 "failed to write {}",
@@ -241,6 +268,52 @@ fn emit_package(dep: &toml::Table, out: &mut String) {
     }
 }
 
+/// Path of the `Cargo.lock.local` overlay, if `-Zlockfile-overlay` is enabled
+/// and the file exists next to an existing `Cargo.lock`.
+fn overlay_path(ws: &Workspace<'_>) -> Option<PathBuf> {
+    if !ws.config().cli_unstable().lockfile_overlay {
+        return None;
+    }
+    let lock_root = lock_root(ws);
+    let lock_root = lock_root.as_path_unlocked();
+    if !lock_root.join("Cargo.lock").exists() {
+        return None;
+    }
+    let path = lock_root.join("Cargo.lock.local");
+    path.exists().then_some(path)
+}
+
+/// Replaces every `[[package]]` entry of `lock` with the entries of `overlay`
+/// that have the same name, and adds the ones that don't exist yet.
+fn apply_overlay(lock: &mut toml::Table, overlay: toml::Table) -> CargoResult<()> {
+    let Some(overlay) = overlay.get("package") else {
+        return Ok(());
+    };
+    let overlay = overlay
+        .as_array()
+        .context("`package` in the lock file overlay must be an array")?;
+    let names = overlay
+        .iter()
+        .map(|pkg| {
+            pkg.get("name")
+                .and_then(|name| name.as_str())
+                .context("package in the lock file overlay is missing a `name`")
+        })
+        .collect::<CargoResult<HashSet<_>>>()?;
+
+    let packages = lock
+        .entry("package")
+        .or_insert_with(|| toml::Value::Array(Vec::new()))
+        .as_array_mut()
+        .context("`package` in the lock file must be an array")?;
+    packages.retain(|pkg| {
+        let name = pkg.get("name").and_then(|name| name.as_str());
+        !name.map_or(false, |name| names.contains(name))
+    });
+    packages.extend(overlay.iter().cloned());
+    Ok(())
+}
+
 fn lock_root(ws: &Workspace<'_>) -> Filesystem {
     if ws.root_maybe().is_embedded() {
         ws.target_dir()
@@ -1,26 +1,37 @@
 use crate::sources::CRATES_IO_DOMAIN;
 
+pub use self::cargo_bump_version::{bump_version, BumpLevel, BumpVersionOptions};
+pub use self::cargo_bundle::{bundle_export, bundle_import};
 pub use self::cargo_clean::{clean, CleanOptions};
 pub use self::cargo_compile::{
-    compile, compile_with_exec, compile_ws, create_bcx, print, resolve_all_features, CompileOptions,
+    compile, compile_feature_matrix, compile_with_exec, compile_ws, create_bcx, print,
+    report_auto_tune, report_native_deps, resolve_all_features, CompileOptions,
 };
 pub use self::cargo_compile::{CompileFilter, FilterRule, LibRule, Packages};
 pub use self::cargo_doc::{doc, DocOptions};
 pub use self::cargo_fetch::{fetch, FetchOptions};
+pub use self::cargo_generate_lockfile::dedupe_lockfile;
 pub use self::cargo_generate_lockfile::generate_lockfile;
 pub use self::cargo_generate_lockfile::update_lockfile;
+pub use self::cargo_generate_lockfile::DedupeOptions;
 pub use self::cargo_generate_lockfile::UpdateOptions;
-pub use self::cargo_install::{install, install_list};
+pub use self::cargo_install::{install, install_list, install_repair};
+pub use self::cargo_msrv::verify_msrv;
 pub use self::cargo_new::{init, new, NewOptions, NewProjectKind, VersionControl};
+pub use self::cargo_notices::{report_notices, NoticesOptions};
 pub use self::cargo_output_metadata::{output_metadata, ExportInfo, OutputMetadataOptions};
 pub use self::cargo_package::{check_yanked, package, package_one, PackageOpts};
 pub use self::cargo_pkgid::pkgid;
 pub use self::cargo_read_manifest::{read_package, read_packages};
 pub use self::cargo_run::run;
 pub use self::cargo_test::{run_benches, run_tests, TestOptions};
-pub use self::cargo_uninstall::uninstall;
+pub use self::cargo_uninstall::{uninstall, uninstall_installed, InstalledPackages};
+pub use self::cargo_unused_deps::{unused_deps, UnusedDepsOptions};
+pub use self::cargo_update_interactive::update_interactive;
+pub use self::cargo_why_version::why_version;
 pub use self::fix::{fix, fix_exec_rustc, fix_get_proxy_lock_addr, FixOptions};
 pub use self::lockfile::{load_pkg_lockfile, resolve_to_string, write_pkg_lockfile};
+pub use self::registry::info;
 pub use self::registry::modify_owners;
 pub use self::registry::publish;
 pub use self::registry::registry_login;
@@ -31,12 +42,14 @@ pub use self::registry::OwnersOptions;
 pub use self::registry::PublishOpts;
 pub use self::registry::RegistryCredentialConfig;
 pub use self::resolve::{
-    add_overrides, get_resolved_packages, resolve_with_previous, resolve_ws, resolve_ws_with_opts,
-    WorkspaceResolve,
+    add_overrides, get_resolved_packages, resolve_with_previous, resolve_with_unlocked_edges,
+    resolve_ws, resolve_ws_with_opts, WorkspaceResolve,
 };
 pub use self::vendor::{vendor, VendorOptions};
 
 pub mod cargo_add;
+mod cargo_bump_version;
+mod cargo_bundle;
 mod cargo_clean;
 pub(crate) mod cargo_compile;
 pub mod cargo_config;
@@ -44,7 +57,9 @@ mod cargo_doc;
 mod cargo_fetch;
 mod cargo_generate_lockfile;
 mod cargo_install;
+mod cargo_msrv;
 mod cargo_new;
+mod cargo_notices;
 mod cargo_output_metadata;
 mod cargo_package;
 mod cargo_pkgid;
@@ -53,11 +68,16 @@ pub mod cargo_remove;
 mod cargo_run;
 mod cargo_test;
 mod cargo_uninstall;
+mod cargo_unused_deps;
+mod cargo_update_interactive;
+mod cargo_why_version;
 mod common_for_install_and_uninstall;
+pub(crate) mod dependency_policy;
 mod fix;
 pub(crate) mod lockfile;
 pub(crate) mod registry;
 pub(crate) mod resolve;
+pub(crate) mod semver_check;
 pub mod tree;
 mod vendor;
 
@@ -0,0 +1,269 @@
+//! Implementation of `cargo info`, which displays information about a package.
+//!
+//! Packages of the workspace are described from their manifest. Other packages
+//! are looked up in the index of the registry, and downloaded to read their
+//! manifest, so `cargo info --offline` works for packages Cargo has downloaded
+//! before.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::Path;
+use std::task::Poll;
+
+use anyhow::{bail, Context as _};
+
+use crate::core::dependency::DepKind;
+use crate::core::{Dependency, FeatureValue, Package, QueryKind, Source, SourceId, Workspace};
+use crate::sources::SourceConfigMap;
+use crate::util::interning::InternedString;
+use crate::util::IntoUrl;
+use crate::{drop_println, CargoResult, Config};
+
+/// The number of versions to list for packages from a registry.
+const VERSIONS_TO_LIST: usize = 5;
+
+/// Displays information about the package `spec`, which is `name` or
+/// `name@version`, where `version` is a version requirement.
+///
+/// A member of `ws` named `name` is described from its manifest, anything else
+/// is looked up in the registry selected by `index` or `reg`.
+pub fn info(
+    spec: &str,
+    config: &Config,
+    ws: Option<&Workspace<'_>>,
+    index: Option<String>,
+    reg: Option<String>,
+) -> CargoResult<()> {
+    let (name, version) = match spec.split_once('@') {
+        Some((name, version)) => (name, Some(version)),
+        None => (spec, None),
+    };
+
+    if index.is_none() && reg.is_none() {
+        let member = ws.and_then(|ws| {
+            ws.members().find(|pkg| {
+                pkg.name() == name
+                    && version.map_or(true, |v| {
+                        semver::VersionReq::parse(v).map_or(false, |req| req.matches(pkg.version()))
+                    })
+            })
+        });
+        if let Some(pkg) = member {
+            print_header(config, pkg);
+            drop_println!(config, "version: {}", pkg.version());
+            print_details(config, pkg)?;
+            return Ok(());
+        }
+    }
+
+    // Unlike the commands using the web API, this follows source replacement
+    // like a build does, so vendored packages can be inspected too.
+    let sid = match (reg, index) {
+        (None, None) => SourceId::crates_io(config)?,
+        (_, Some(index)) => SourceId::for_registry(&index.into_url()?)?,
+        (Some(reg), None) => SourceId::alt_registry(config, &reg)?,
+    };
+    let map = SourceConfigMap::new(config)?;
+    let mut source = map.load(sid, &HashSet::new())?;
+    let dep = Dependency::parse(name, version, sid)?;
+
+    // All versions, to show the latest ones even if `version` selects an
+    // older one.
+    let summaries = {
+        let _lock = config.acquire_package_cache_lock()?;
+        let all = Dependency::parse(name, None, sid)?;
+        let mut summaries = loop {
+            match source.query_vec(&all, QueryKind::Exact)? {
+                Poll::Ready(summaries) => break summaries,
+                Poll::Pending => source.block_until_ready()?,
+            }
+        };
+        summaries.sort_by(|a, b| b.version().cmp(a.version()));
+        summaries
+    };
+    // Prefer stable versions, unless only pre-releases match.
+    let matching = || {
+        summaries
+            .iter()
+            .filter(|s| dep.version_req().matches(s.version()))
+    };
+    let Some(selected) = matching()
+        .find(|s| s.version().pre.is_empty())
+        .or_else(|| matching().next())
+    else {
+        match version {
+            Some(version) => bail!(
+                "could not find `{name}` in {} with version `{version}`",
+                sid
+            ),
+            None => bail!("could not find `{name}` in {}", sid),
+        }
+    };
+    let selected = selected.clone();
+
+    // The manifest isn't part of the index, so download the package for it.
+    let pkg = Box::new(source).download_now(selected.package_id(), config)?;
+
+    print_header(config, &pkg);
+    let latest = summaries[0].version();
+    if latest != selected.version() {
+        drop_println!(config, "version: {} (latest {latest})", selected.version());
+    } else {
+        drop_println!(config, "version: {}", selected.version());
+    }
+    let mut versions: Vec<_> = summaries
+        .iter()
+        .take(VERSIONS_TO_LIST)
+        .map(|s| s.version().to_string())
+        .collect();
+    if summaries.len() > VERSIONS_TO_LIST {
+        versions.push(format!("and {} more", summaries.len() - VERSIONS_TO_LIST));
+    }
+    drop_println!(config, "versions: {}", versions.join(", "));
+    print_details(config, &pkg)
+}
+
+/// Prints the name, keywords and description of a package.
+fn print_header(config: &Config, pkg: &Package) {
+    let metadata = pkg.manifest().metadata();
+    let keywords: String = metadata
+        .keywords
+        .iter()
+        .map(|keyword| format!(" #{keyword}"))
+        .collect();
+    drop_println!(config, "{}{keywords}", pkg.name());
+    if let Some(description) = &metadata.description {
+        drop_println!(config, "{}", description.trim_end());
+    }
+}
+
+/// Prints the metadata, features and dependencies of a package.
+fn print_details(config: &Config, pkg: &Package) -> CargoResult<()> {
+    let summary = pkg.summary();
+    let metadata = pkg.manifest().metadata();
+    let license = metadata
+        .license
+        .as_deref()
+        .or(metadata.license_file.as_deref());
+    for (field, value) in [
+        ("license", license),
+        ("rust-version", summary.rust_version().as_deref()),
+        ("documentation", metadata.documentation.as_deref()),
+        ("homepage", metadata.homepage.as_deref()),
+        ("repository", metadata.repository.as_deref()),
+    ] {
+        if let Some(value) = value {
+            drop_println!(config, "{field}: {value}");
+        }
+    }
+
+    print_features(config, pkg)?;
+
+    let deps: Vec<_> = summary
+        .dependencies()
+        .iter()
+        .filter(|dep| dep.kind() == DepKind::Normal)
+        .collect();
+    if !deps.is_empty() {
+        drop_println!(config, "dependencies:");
+        for dep in deps {
+            let optional = if dep.is_optional() { " (optional)" } else { "" };
+            drop_println!(
+                config,
+                "  {}@{}{optional}",
+                dep.package_name(),
+                dep.version_req()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Prints the features of a package, marking the ones enabled by default with
+/// `+`, along with their descriptions from the manifest, see
+/// [`feature_descriptions`].
+fn print_features(config: &Config, pkg: &Package) -> CargoResult<()> {
+    let features = pkg.summary().features();
+    if features.is_empty() {
+        return Ok(());
+    }
+    let descriptions = feature_descriptions(pkg.root())?;
+
+    // Features enabled by `default`, directly or through other features.
+    let mut enabled = BTreeSet::new();
+    let mut pending = vec![InternedString::new("default")];
+    while let Some(feature) = pending.pop() {
+        if let Some(values) = features.get(&feature) {
+            if enabled.insert(feature) {
+                pending.extend(values.iter().filter_map(|value| match value {
+                    FeatureValue::Feature(f) => Some(*f),
+                    _ => None,
+                }));
+            }
+        }
+    }
+
+    // List `default` first.
+    let names: Vec<_> = features
+        .keys()
+        .filter(|name| name.as_str() == "default")
+        .chain(features.keys().filter(|name| name.as_str() != "default"))
+        .collect();
+    let width = names.iter().map(|name| name.len()).max().unwrap_or(0);
+    drop_println!(config, "features:");
+    for name in names {
+        let marker = if enabled.contains(name) { '+' } else { ' ' };
+        let values: Vec<_> = features[name].iter().map(|v| v.to_string()).collect();
+        let mut line = format!(" {marker}{name:width$} = [{}]", values.join(", "));
+        if let Some(description) = descriptions.get(name.as_str()) {
+            line.push_str(&format!("  # {description}"));
+        }
+        drop_println!(config, "{line}");
+    }
+    Ok(())
+}
+
+/// Gets the descriptions of features from the comments of the `[features]`
+/// table of the manifest in `root`, either on the lines before a feature, or
+/// after its value.
+///
+/// Packages from registries have them in the original `Cargo.toml.orig`, as
+/// the normalized `Cargo.toml` has no comments.
+fn feature_descriptions(root: &Path) -> CargoResult<BTreeMap<String, String>> {
+    let orig = root.join("Cargo.toml.orig");
+    let path = if orig.exists() {
+        orig
+    } else {
+        root.join("Cargo.toml")
+    };
+    let contents = cargo_util::paths::read(&path)?;
+    let doc: toml_edit::Document = contents
+        .parse()
+        .with_context(|| format!("failed to parse manifest at `{}`", path.display()))?;
+    let Some(features) = doc.get("features").and_then(|f| f.as_table_like()) else {
+        return Ok(BTreeMap::new());
+    };
+
+    let comment = |text: Option<&str>| {
+        let lines: Vec<_> = text
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix('#'))
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+        (!lines.is_empty()).then(|| lines.join(" "))
+    };
+    let mut descriptions = BTreeMap::new();
+    for (name, value) in features.iter() {
+        let before = features
+            .get_key_value(name)
+            .and_then(|(key, _)| comment(key.decor().prefix().and_then(|p| p.as_str())));
+        let after = value
+            .as_value()
+            .and_then(|v| comment(v.decor().suffix().and_then(|s| s.as_str())));
+        if let Some(description) = before.or(after) {
+            descriptions.insert(name.to_string(), description);
+        }
+    }
+    Ok(descriptions)
+}
@@ -1,14 +1,27 @@
 //! Interacts with the registry [login API][1].
 //!
 //! This doesn't really call any web API at this moment. Instead, it's just an
-//! operation for `cargo login`.
+//! operation for `cargo login`. With `-Zdevice-login`, registries can
+//! advertise the [OAuth 2.0 device authorization grant][2] to hand out tokens,
+//! see [`device_login`].
 //!
 //! [1]: https://doc.rust-lang.org/nightly/cargo/reference/registry-web-api.html#login
+//! [2]: https://www.rfc-editor.org/rfc/rfc8628
 
 use std::io::IsTerminal;
+use std::time::{Duration, Instant};
 
+use anyhow::{bail, Context as _};
+use curl::easy::List;
+use serde::Deserialize;
+
+use crate::core::SourceId;
+use crate::message;
+use crate::sources::registry::DeviceAuthorization;
 use crate::util::auth;
 use crate::util::auth::AuthorizationError;
+use crate::util::browser;
+use crate::util::network::http::{http_handle, RegistryHttpConfig};
 use crate::CargoResult;
 use crate::Config;
 use cargo_credential::LoginOptions;
@@ -16,6 +29,29 @@ use cargo_credential::Secret;
 
 use super::get_source_id;
 use super::registry;
+use super::registry_config;
+
+/// The response of the device authorization endpoint.
+#[derive(Deserialize)]
+struct DeviceCodeResponse {
+    device_code: String,
+    user_code: String,
+    verification_uri: String,
+    /// Like `verification_uri`, but with the `user_code` already filled in.
+    verification_uri_complete: Option<String>,
+    /// Seconds until the codes expire.
+    expires_in: u64,
+    /// Seconds to wait between polling the token endpoint.
+    interval: Option<u64>,
+}
+
+/// The response of the token endpoint, either a token or an error.
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
 
 pub fn registry_login(
     config: &Config,
@@ -43,6 +79,15 @@ pub fn registry_login(
     }
     let token = token_from_cmdline.or_else(|| token_from_stdin.as_deref().map(Secret::from));
 
+    let mut token_from_device = None;
+    if token.is_none() && config.cli_unstable().device_login {
+        let cfg = registry_config(config, source_ids.replacement, false)?;
+        if let Some(device) = cfg.device_authorization {
+            token_from_device = Some(device_login(config, source_ids.original, &device)?);
+        }
+    }
+    let token = token.or_else(|| token_from_device.as_ref().map(|t| t.as_deref()));
+
     let options = LoginOptions {
         token,
         login_url: login_url.as_deref(),
@@ -51,3 +96,117 @@ pub fn registry_login(
     auth::login(config, &source_ids.original, options)?;
     Ok(())
 }
+
+/// Obtains a token from the registry `sid` with the device authorization
+/// grant: the user approves the login in the browser, while Cargo polls the
+/// token endpoint until the token is issued.
+fn device_login(
+    config: &Config,
+    sid: SourceId,
+    device: &DeviceAuthorization,
+) -> CargoResult<Secret<String>> {
+    let (code, body) = post_form(
+        config,
+        sid,
+        &device.url,
+        &[("client_id", &device.client_id)],
+    )
+    .with_context(|| format!("failed to request a device code from {sid}"))?;
+    if code != 200 {
+        bail!(
+            "failed to request a device code from {sid}, got {code}\nbody:\n{}",
+            String::from_utf8_lossy(&body)
+        );
+    }
+    let response: DeviceCodeResponse = serde_json::from_slice(&body)
+        .with_context(|| format!("failed to parse the device code from {sid}"))?;
+
+    let url = response
+        .verification_uri_complete
+        .as_deref()
+        .unwrap_or(&response.verification_uri);
+    let msg = message!(
+        config,
+        "to log in to {sid}, open {url} and enter the code `{code}`",
+        sid = sid,
+        url = url,
+        code = response.user_code,
+    );
+    config.shell().note(msg)?;
+    browser::open_url(config, url)?;
+
+    let deadline = Instant::now() + Duration::from_secs(response.expires_in);
+    let mut interval = Duration::from_secs(response.interval.unwrap_or(5));
+    loop {
+        std::thread::sleep(interval);
+        if Instant::now() > deadline {
+            bail!("the device code of {sid} expired before the login was approved");
+        }
+        let (_, body) = post_form(
+            config,
+            sid,
+            &device.token_url,
+            &[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+                ("device_code", &response.device_code),
+                ("client_id", &device.client_id),
+            ],
+        )
+        .with_context(|| format!("failed to request a token from {sid}"))?;
+        let token: TokenResponse = serde_json::from_slice(&body)
+            .with_context(|| format!("failed to parse the token response from {sid}"))?;
+        if let Some(token) = token.access_token {
+            return Ok(Secret::from(token));
+        }
+        match token.error.as_deref() {
+            Some("authorization_pending") => {}
+            Some("slow_down") => interval += Duration::from_secs(5),
+            Some("access_denied") => bail!("the login to {sid} was denied"),
+            Some("expired_token") => {
+                bail!("the device code of {sid} expired before the login was approved")
+            }
+            error => {
+                let mut msg = format!(
+                    "failed to log in to {sid}: {}",
+                    error.unwrap_or("no token in the response")
+                );
+                if let Some(description) = token.error_description {
+                    msg.push_str(&format!(" ({description})"));
+                }
+                bail!(msg)
+            }
+        }
+    }
+}
+
+/// Sends a form to `url` with the HTTP settings of the registry `sid`, and
+/// returns the response code and body.
+fn post_form(
+    config: &Config,
+    sid: SourceId,
+    url: &str,
+    form: &[(&str, &str)],
+) -> CargoResult<(u32, Vec<u8>)> {
+    let form = url::form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(form)
+        .finish();
+    let mut handle = http_handle(config)?;
+    RegistryHttpConfig::new(config, sid)?.configure(config, &mut handle)?;
+    handle.url(url)?;
+    handle.post(true)?;
+    handle.post_fields_copy(form.as_bytes())?;
+    let mut headers = List::new();
+    headers.append("Accept: application/json")?;
+    handle.http_headers(headers)?;
+
+    let mut body = Vec::new();
+    {
+        let mut transfer = handle.transfer();
+        transfer.write_function(|buf| {
+            body.extend_from_slice(buf);
+            Ok(buf.len())
+        })?;
+        transfer.perform()?;
+    }
+    Ok((handle.response_code()?, body))
+}
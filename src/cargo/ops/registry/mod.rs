@@ -2,11 +2,13 @@
 //!
 //! [1]: https://doc.rust-lang.org/nightly/cargo/reference/registry-web-api.html
 
+mod info;
 mod login;
 mod logout;
 mod owner;
 mod publish;
 mod search;
+mod trusted_publishing;
 mod yank;
 
 use std::collections::HashSet;
@@ -19,13 +21,15 @@ use crates_io::{self, Registry};
 
 use crate::core::source::Source;
 use crate::core::SourceId;
+use crate::sources::registry::RegistryConfig;
 use crate::sources::{RegistrySource, SourceConfigMap};
 use crate::util::auth;
 use crate::util::config::{Config, PathAndArgs};
 use crate::util::errors::CargoResult;
-use crate::util::network::http::http_handle;
+use crate::util::network::http::{http_handle, RegistryHttpConfig};
 use crate::util::IntoUrl;
 
+pub use self::info::info;
 pub use self::login::registry_login;
 pub use self::logout::registry_logout;
 pub use self::owner::modify_owners;
@@ -117,23 +121,7 @@ fn registry(
         auth::cache_token_from_commandline(config, &source_ids.original, token);
     }
 
-    let cfg = {
-        let _lock = config.acquire_package_cache_lock()?;
-        let mut src = RegistrySource::remote(source_ids.replacement, &HashSet::new(), config)?;
-        // Only update the index if `force_update` is set.
-        if force_update {
-            src.invalidate_cache()
-        }
-        let cfg = loop {
-            match src.config()? {
-                Poll::Pending => src
-                    .block_until_ready()
-                    .with_context(|| format!("failed to update {}", source_ids.replacement))?,
-                Poll::Ready(cfg) => break cfg,
-            }
-        };
-        cfg.expect("remote registries must have config")
-    };
+    let cfg = registry_config(config, source_ids.replacement, force_update)?;
     let api_host = cfg
         .api
         .ok_or_else(|| format_err!("{} does not support API commands", source_ids.replacement))?;
@@ -149,13 +137,39 @@ fn registry(
     } else {
         None
     };
-    let handle = http_handle(config)?;
+    let mut handle = http_handle(config)?;
+    RegistryHttpConfig::new(config, source_ids.original)?.configure(config, &mut handle)?;
     Ok((
         Registry::new_handle(api_host, token, handle, cfg.auth_required),
         source_ids,
     ))
 }
 
+/// Loads the `config.json` of the remote registry `sid`.
+///
+/// * `force_update`: If `true`, forces the index to be updated.
+fn registry_config(
+    config: &Config,
+    sid: SourceId,
+    force_update: bool,
+) -> CargoResult<RegistryConfig> {
+    let _lock = config.acquire_package_cache_lock()?;
+    let mut src = RegistrySource::remote(sid, &HashSet::new(), config)?;
+    // Only update the index if `force_update` is set.
+    if force_update {
+        src.invalidate_cache()
+    }
+    let cfg = loop {
+        match src.config()? {
+            Poll::Pending => src
+                .block_until_ready()
+                .with_context(|| format!("failed to update {}", sid))?,
+            Poll::Ready(cfg) => break cfg,
+        }
+    };
+    Ok(cfg.expect("remote registries must have config"))
+}
+
 /// Gets the SourceId for an index or registry setting.
 ///
 /// The `index` and `reg` values are from the command-line or config settings.
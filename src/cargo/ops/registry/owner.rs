@@ -9,7 +9,9 @@ use cargo_credential::Secret;
 use crate::core::Workspace;
 use crate::drop_print;
 use crate::drop_println;
+use crate::message;
 use crate::util::important_paths::find_root_manifest_for_wd;
+use crate::util::network::retry::with_retry;
 use crate::CargoResult;
 use crate::Config;
 
@@ -46,23 +48,28 @@ pub fn modify_owners(config: &Config, opts: &OwnersOptions) -> CargoResult<()> {
 
     if let Some(ref v) = opts.to_add {
         let v = v.iter().map(|s| &s[..]).collect::<Vec<_>>();
-        let msg = registry.add_owners(&name, &v).with_context(|| {
-            format!(
-                "failed to invite owners to crate `{}` on registry at {}",
-                name,
-                registry.host()
-            )
-        })?;
+        let msg =
+            with_retry(config, || Ok(registry.add_owners(&name, &v)?)).with_context(|| {
+                format!(
+                    "failed to invite owners to crate `{}` on registry at {}",
+                    name,
+                    registry.host()
+                )
+            })?;
 
         config.shell().status("Owner", msg)?;
     }
 
     if let Some(ref v) = opts.to_remove {
         let v = v.iter().map(|s| &s[..]).collect::<Vec<_>>();
-        config
-            .shell()
-            .status("Owner", format!("removing {:?} from crate {}", v, name))?;
-        registry.remove_owners(&name, &v).with_context(|| {
+        let msg = message!(
+            config,
+            "removing {owners} from crate {name}",
+            owners = format!("{:?}", v),
+            name = name,
+        );
+        config.shell().status("Owner", msg)?;
+        with_retry(config, || Ok(registry.remove_owners(&name, &v)?)).with_context(|| {
             format!(
                 "failed to remove owners from crate `{}` on registry at {}",
                 name,
@@ -72,13 +79,14 @@ pub fn modify_owners(config: &Config, opts: &OwnersOptions) -> CargoResult<()> {
     }
 
     if opts.list {
-        let owners = registry.list_owners(&name).with_context(|| {
-            format!(
-                "failed to list owners of crate `{}` on registry at {}",
-                name,
-                registry.host()
-            )
-        })?;
+        let owners =
+            with_retry(config, || Ok(registry.list_owners(&name)?)).with_context(|| {
+                format!(
+                    "failed to list owners of crate `{}` on registry at {}",
+                    name,
+                    registry.host()
+                )
+            })?;
         for owner in owners.iter() {
             drop_print!(config, "{}", owner.login);
             match (owner.name.as_ref(), owner.email.as_ref()) {
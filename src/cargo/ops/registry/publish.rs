@@ -24,6 +24,7 @@ use crate::core::Package;
 use crate::core::QueryKind;
 use crate::core::SourceId;
 use crate::core::Workspace;
+use crate::message;
 use crate::ops;
 use crate::ops::PackageOpts;
 use crate::ops::Packages;
@@ -37,6 +38,7 @@ use crate::CargoResult;
 use crate::Config;
 
 use super::super::check_dep_has_version;
+use super::trusted_publishing;
 
 pub struct PublishOpts<'cfg> {
     pub config: &'cfg Config,
@@ -44,6 +46,10 @@ pub struct PublishOpts<'cfg> {
     pub index: Option<String>,
     pub verify: bool,
     pub allow_dirty: bool,
+    pub require_clean_vcs: bool,
+    /// Compare the public API with the previous version, see
+    /// [`ops::semver_check`].
+    pub semver_check: bool,
     pub jobs: Option<JobsConfig>,
     pub keep_going: bool,
     pub to_publish: ops::Packages,
@@ -85,10 +91,12 @@ pub fn publish(ws: &Workspace<'_>, opts: &PublishOpts<'_>) -> CargoResult<()> {
             if default_registry != CRATES_IO_REGISTRY {
                 // Don't change the registry for crates.io and don't warn the user.
                 // crates.io will be defaulted even without this.
-                opts.config.shell().note(&format!(
-                    "Found `{}` as only allowed registry. Publishing to it automatically.",
-                    default_registry
-                ))?;
+                let msg = message!(
+                    opts.config,
+                    "Found `{registry}` as only allowed registry. Publishing to it automatically.",
+                    registry = default_registry,
+                );
+                opts.config.shell().note(msg)?;
                 publish_registry = Some(default_registry.clone());
             }
         }
@@ -111,18 +119,30 @@ pub fn publish(ws: &Workspace<'_>, opts: &PublishOpts<'_>) -> CargoResult<()> {
             );
         }
     }
+    ops::dependency_policy::check_path_deps_on_publish(opts.config, pkg)?;
+
     // This is only used to confirm that we can create a token before we build the package.
     // This causes the credential provider to be called an extra time, but keeps the same order of errors.
     let ver = pkg.version().to_string();
     let operation = Operation::Read;
 
+    let source_ids = super::get_source_id(
+        opts.config,
+        opts.index.as_deref(),
+        publish_registry.as_deref(),
+    )?;
+    let trusted = opts.token.is_none()
+        && !opts.dry_run
+        && trusted_publishing::enabled(opts.config, &source_ids.original)?;
+
     let (mut registry, reg_ids) = super::registry(
         opts.config,
         opts.token.as_ref().map(Secret::as_deref),
         opts.index.as_deref(),
         publish_registry.as_deref(),
         true,
-        Some(operation).filter(|_| !opts.dry_run),
+        // Trusted publishers have no token until it is exchanged below.
+        Some(operation).filter(|_| !opts.dry_run && !trusted),
     )?;
     verify_dependencies(pkg, &registry, reg_ids.original)?;
 
@@ -137,15 +157,20 @@ pub fn publish(ws: &Workspace<'_>, opts: &PublishOpts<'_>) -> CargoResult<()> {
             list: false,
             check_metadata: true,
             allow_dirty: opts.allow_dirty,
+            require_clean_vcs: opts.require_clean_vcs,
             to_package: Packages::Default,
             targets: opts.targets.clone(),
             jobs: opts.jobs.clone(),
             keep_going: opts.keep_going,
-            cli_features,
+            cli_features: cli_features.clone(),
         },
     )?
     .unwrap();
 
+    if opts.semver_check {
+        ops::semver_check::check_publish(ws, pkg, reg_ids.original, &cli_features)?;
+    }
+
     if !opts.dry_run {
         let hash = cargo_util::Sha256::new()
             .update_file(tarball.file())?
@@ -155,30 +180,52 @@ pub fn publish(ws: &Workspace<'_>, opts: &PublishOpts<'_>) -> CargoResult<()> {
             vers: &ver,
             cksum: &hash,
         };
-        registry.set_token(Some(auth::auth_token(
-            &opts.config,
-            &reg_ids.original,
-            None,
-            operation,
-            vec![],
-        )?));
+        // The short-lived token of a trusted publisher is only exchanged
+        // once nothing but the upload is left, so that it is always revoked.
+        let token = if trusted {
+            trusted_publishing::token(
+                opts.config,
+                opts.index.as_deref(),
+                publish_registry.as_deref(),
+            )?
+            .expose()
+        } else {
+            auth::auth_token(&opts.config, &reg_ids.original, None, operation, vec![])?
+        };
+        registry.set_token(Some(token));
     }
 
     opts.config
         .shell()
         .status("Uploading", pkg.package_id().to_string())?;
-    transmit(
+    let result = transmit(
         opts.config,
         pkg,
         tarball.file(),
         &mut registry,
         reg_ids.original,
         opts.dry_run,
-    )?;
+    );
+    if trusted {
+        if let Err(e) = registry.revoke_trusted_publishing_token() {
+            let msg = message!(
+                opts.config,
+                "failed to revoke the trusted publishing token of {registry}: {error}",
+                registry = reg_ids.original,
+                error = e,
+            );
+            opts.config.shell().warn(msg)?;
+        }
+    }
+    result?;
     if !opts.dry_run {
         const DEFAULT_TIMEOUT: u64 = 60;
         let timeout = if opts.config.cli_unstable().publish_timeout {
-            let timeout: Option<u64> = opts.config.get("publish.timeout")?;
+            // `publish.timeout` is the older name of `publish.wait-timeout`.
+            let mut timeout: Option<u64> = opts.config.get("publish.wait-timeout")?;
+            if timeout.is_none() {
+                timeout = opts.config.get("publish.timeout")?;
+            }
             timeout.unwrap_or(DEFAULT_TIMEOUT)
         } else {
             DEFAULT_TIMEOUT
@@ -212,14 +259,21 @@ fn wait_for_publish(
     let max = timeout.as_secs() as usize;
     // Short does not include the registry name.
     let short_pkg_description = format!("{} v{}", pkg.name(), pkg.version());
-    config.shell().status(
-        "Uploaded",
-        format!("{short_pkg_description} to {source_description}"),
-    )?;
-    config.shell().note(format!(
-        "Waiting for `{short_pkg_description}` to be available at {source_description}.\n\
-        You may press ctrl-c to skip waiting; the crate should be available shortly."
-    ))?;
+    let msg = message!(
+        config,
+        "{pkg} to {source}",
+        pkg = short_pkg_description,
+        source = source_description,
+    );
+    config.shell().status("Uploaded", msg)?;
+    let msg = message!(
+        config,
+        "Waiting for `{pkg}` to be available at {source}.\n\
+         You may press ctrl-c to skip waiting; the crate should be available shortly.",
+        pkg = short_pkg_description,
+        source = source_description,
+    );
+    config.shell().note(msg)?;
     let mut progress = Progress::with_style("Waiting", ProgressStyle::Ratio, config);
     progress.tick_now(0, max, "")?;
     let is_available = loop {
@@ -250,13 +304,21 @@ fn wait_for_publish(
 
         let elapsed = now.elapsed();
         if timeout < elapsed {
-            config.shell().warn(format!(
-                "timed out waiting for `{short_pkg_description}` to be available in {source_description}",
-            ))?;
-            config.shell().note(
+            let msg = message!(
+                config,
+                "timed out after {secs}s waiting for `{pkg}` to be available in {source}",
+                secs = timeout.as_secs(),
+                pkg = short_pkg_description,
+                source = source_description,
+            );
+            config.shell().warn(msg)?;
+            let msg = message!(
+                config,
                 "The registry may have a backlog that is delaying making the \
-                crate available. The crate should be available soon.",
-            )?;
+                 crate available. The crate should be available soon, \
+                 set `publish.wait-timeout` to wait longer."
+            );
+            config.shell().note(msg)?;
             break false;
         }
 
@@ -264,10 +326,13 @@ fn wait_for_publish(
         std::thread::sleep(sleep_time);
     };
     if is_available {
-        config.shell().status(
-            "Published",
-            format!("{short_pkg_description} at {source_description}"),
-        )?;
+        let msg = message!(
+            config,
+            "{pkg} at {source}",
+            pkg = short_pkg_description,
+            source = source_description,
+        );
+        config.shell().status("Published", msg)?;
     }
 
     Ok(())
@@ -387,7 +452,8 @@ fn transmit(
 
     // Do not upload if performing a dry run
     if dry_run {
-        config.shell().warn("aborting upload due to dry run")?;
+        let msg = message!(config, "aborting upload due to dry run");
+        config.shell().warn(msg)?;
         return Ok(());
     }
 
@@ -431,24 +497,26 @@ fn transmit(
         .with_context(|| format!("failed to publish to registry at {}", registry.host()))?;
 
     if !warnings.invalid_categories.is_empty() {
-        let msg = format!(
+        let msg = message!(
+            config,
             "the following are not valid category slugs and were \
-             ignored: {}. Please see https://crates.io/category_slugs \
+             ignored: {categories}. Please see https://crates.io/category_slugs \
              for the list of all category slugs. \
              ",
-            warnings.invalid_categories.join(", ")
+            categories = warnings.invalid_categories.join(", "),
         );
         config.shell().warn(&msg)?;
     }
 
     if !warnings.invalid_badges.is_empty() {
-        let msg = format!(
-            "the following are not valid badges and were ignored: {}. \
+        let msg = message!(
+            config,
+            "the following are not valid badges and were ignored: {badges}. \
              Either the badge type specified is unknown or a required \
              attribute is missing. Please see \
              https://doc.rust-lang.org/cargo/reference/manifest.html#package-metadata \
              for valid badge types and their required attributes.",
-            warnings.invalid_badges.join(", ")
+            badges = warnings.invalid_badges.join(", "),
         );
         config.shell().warn(&msg)?;
     }
@@ -6,30 +6,80 @@ use std::cmp;
 use std::iter::repeat;
 
 use anyhow::Context as _;
+use serde::Serialize;
 use termcolor::Color;
 use termcolor::ColorSpec;
 use url::Url;
 
+use crate::core::shell::Verbosity;
+use crate::util::network::retry::with_retry;
 use crate::util::truncate_with_ellipsis;
 use crate::CargoResult;
 use crate::Config;
 
+/// The output of `cargo search --json`.
+#[derive(Serialize)]
+struct SearchResults<'a> {
+    crates: Vec<SearchResult<'a>>,
+    /// The number of crates matching the query, on all pages.
+    total: u32,
+}
+
+#[derive(Serialize)]
+struct SearchResult<'a> {
+    name: &'a str,
+    version: &'a str,
+    description: Option<&'a str>,
+    downloads: Option<u64>,
+    recent_downloads: Option<u64>,
+    rust_version: Option<&'a str>,
+}
+
+/// Searches the registry for `query`, printing the `page`th page of `limit`
+/// results, starting at 1.
 pub fn search(
     query: &str,
     config: &Config,
     index: Option<String>,
     limit: u32,
+    page: u32,
+    json: bool,
     reg: Option<String>,
 ) -> CargoResult<()> {
     let (mut registry, source_ids) =
         super::registry(config, None, index.as_deref(), reg.as_deref(), false, None)?;
-    let (crates, total_crates) = registry.search(query, limit).with_context(|| {
+    let (crates, total_crates) = with_retry(config, || {
+        if page > 1 {
+            Ok(registry.search_page(query, limit, page)?)
+        } else {
+            Ok(registry.search(query, limit)?)
+        }
+    })
+    .with_context(|| {
         format!(
             "failed to retrieve search results from the registry at {}",
             registry.host()
         )
     })?;
 
+    if json {
+        let crates = crates
+            .iter()
+            .map(|krate| SearchResult {
+                name: &krate.name,
+                version: &krate.max_version,
+                description: krate.description.as_deref(),
+                downloads: krate.downloads,
+                recent_downloads: krate.recent_downloads,
+                rust_version: krate.rust_version.as_deref(),
+            })
+            .collect();
+        return config.shell().print_json(&SearchResults {
+            crates,
+            total: total_crates,
+        });
+    }
+
     let names = crates
         .iter()
         .map(|krate| format!("{} = \"{}\"", krate.name, krate.max_version))
@@ -39,11 +89,34 @@ pub fn search(
 
     let description_length = cmp::max(80, 128 - description_margin);
 
+    let verbose = config.shell().verbosity() == Verbosity::Verbose;
     let descriptions = crates.iter().map(|krate| {
-        krate
+        let description = krate
             .description
             .as_ref()
-            .map(|desc| truncate_with_ellipsis(&desc.replace("\n", " "), description_length))
+            .map(|desc| truncate_with_ellipsis(&desc.replace("\n", " "), description_length));
+        if !verbose {
+            return description;
+        }
+        // Show the details the registry provides along with the description.
+        let mut details = Vec::new();
+        if let Some(downloads) = krate.downloads {
+            details.push(format!("{downloads} downloads"));
+        }
+        if let Some(recent_downloads) = krate.recent_downloads {
+            details.push(format!("{recent_downloads} recent downloads"));
+        }
+        if let Some(rust_version) = &krate.rust_version {
+            details.push(format!("rust-version {rust_version}"));
+        }
+        if details.is_empty() {
+            return description;
+        }
+        let details = format!("({})", details.join(", "));
+        Some(match description {
+            Some(desc) => format!("{desc} {details}"),
+            None => details,
+        })
     });
 
     for (name, description) in names.into_iter().zip(descriptions) {
@@ -70,15 +143,17 @@ pub fn search(
     }
 
     let search_max_limit = 100;
-    if total_crates > limit && limit < search_max_limit {
+    // The number of crates up to and including this page.
+    let shown = page.saturating_mul(limit);
+    if total_crates > shown && limit < search_max_limit {
         let _ = config.shell().write_stdout(
             format_args!(
                 "... and {} crates more (use --limit N to see more)\n",
-                total_crates - limit
+                total_crates - shown
             ),
             &ColorSpec::new(),
         );
-    } else if total_crates > limit && limit >= search_max_limit {
+    } else if total_crates > shown && limit >= search_max_limit {
         let extra = if source_ids.original.is_crates_io() {
             let url = Url::parse_with_params("https://crates.io/search", &[("q", query)])?;
             format!(" (go to {url} to see more)")
@@ -86,7 +161,7 @@ pub fn search(
             String::new()
         };
         let _ = config.shell().write_stdout(
-            format_args!("... and {} crates more{}\n", total_crates - limit, extra),
+            format_args!("... and {} crates more{}\n", total_crates - shown, extra),
             &ColorSpec::new(),
         );
     }
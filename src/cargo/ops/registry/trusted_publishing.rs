@@ -0,0 +1,120 @@
+//! Publishing without long-lived tokens, as a trusted publisher.
+//!
+//! This is unstable and requires `-Ztrusted-publishing`.
+//!
+//! When `trusted-publishing` is enabled for a registry, `cargo publish` takes
+//! the [OIDC] token the CI provider issues for the running job, and exchanges
+//! it with the registry for a short-lived token, which is revoked once the
+//! upload is done. The registry decides whether to trust the job based on the
+//! claims of the OIDC token, like the repository and workflow it belongs to.
+//!
+//! [OIDC]: https://openid.net/developers/how-connect-works/
+
+use anyhow::{bail, Context as _};
+use cargo_credential::Secret;
+use curl::easy::List;
+use serde::Deserialize;
+
+use crate::core::SourceId;
+use crate::util::network::http::{http_handle, RegistryHttpConfig};
+use crate::CargoResult;
+use crate::Config;
+
+/// The response of the GitHub Actions endpoint for OIDC tokens.
+#[derive(Deserialize)]
+struct GitHubToken {
+    value: String,
+}
+
+/// Whether `trusted-publishing` is enabled for the registry `sid`, from the
+/// `registries.<name>.trusted-publishing` config.
+pub(super) fn enabled(config: &Config, sid: &SourceId) -> CargoResult<bool> {
+    if !config.cli_unstable().trusted_publishing {
+        return Ok(false);
+    }
+    let enabled = config.registry_settings(sid)?.trusted_publishing;
+    Ok(enabled.unwrap_or(false))
+}
+
+/// Gets the OIDC token of the running CI job for the registry `sid`, whose
+/// web API is at `api`.
+///
+/// The token is taken from the `CARGO_OIDC_TOKEN` environment variable, like
+/// the ID tokens of GitLab CI, or requested from GitHub Actions with the host
+/// of `api` as audience.
+pub(super) fn oidc_token(
+    config: &Config,
+    sid: &SourceId,
+    api: &str,
+) -> CargoResult<Secret<String>> {
+    if let Ok(token) = config.get_env("CARGO_OIDC_TOKEN") {
+        return Ok(Secret::from(token));
+    }
+    let (Ok(url), Ok(request_token)) = (
+        config.get_env("ACTIONS_ID_TOKEN_REQUEST_URL"),
+        config.get_env("ACTIONS_ID_TOKEN_REQUEST_TOKEN"),
+    ) else {
+        bail!(
+            "trusted publishing to {sid} requires an OIDC token, \
+             but neither `CARGO_OIDC_TOKEN` is set nor running in GitHub Actions \
+             with the `id-token: write` permission"
+        );
+    };
+    let audience = url::Url::parse(api)
+        .ok()
+        .and_then(|api| api.host_str().map(str::to_string))
+        .unwrap_or_else(|| api.to_string());
+    let mut url = url::Url::parse(&url)
+        .with_context(|| format!("invalid `ACTIONS_ID_TOKEN_REQUEST_URL`: {url}"))?;
+    url.query_pairs_mut().append_pair("audience", &audience);
+
+    let mut handle = http_handle(config)?;
+    RegistryHttpConfig::new(config, *sid)?.configure(config, &mut handle)?;
+    handle.get(true)?;
+    handle.url(url.as_str())?;
+    let mut headers = List::new();
+    headers.append(&format!("Authorization: bearer {request_token}"))?;
+    handle.http_headers(headers)?;
+    let mut body = Vec::new();
+    {
+        let mut transfer = handle.transfer();
+        transfer.write_function(|buf| {
+            body.extend_from_slice(buf);
+            Ok(buf.len())
+        })?;
+        transfer
+            .perform()
+            .context("failed to request an OIDC token from GitHub Actions")?;
+    }
+    let code = handle.response_code()?;
+    if code != 200 {
+        bail!(
+            "failed to request an OIDC token from GitHub Actions, got {code}\nbody:\n{}",
+            String::from_utf8_lossy(&body)
+        );
+    }
+    let token: GitHubToken = serde_json::from_slice(&body)
+        .context("failed to parse the OIDC token from GitHub Actions")?;
+    Ok(Secret::from(token.value))
+}
+
+/// Exchanges the OIDC token of the running CI job for a short-lived token of
+/// the registry selected by `index` or `reg`, to publish with.
+pub(super) fn token(
+    config: &Config,
+    index: Option<&str>,
+    reg: Option<&str>,
+) -> CargoResult<Secret<String>> {
+    // The index was already updated by `cargo publish`.
+    let (mut registry, source_ids) = super::registry(config, None, index, reg, false, None)?;
+    let jwt = oidc_token(config, &source_ids.original, registry.host())?;
+    let token = registry
+        .trusted_publishing_token(jwt.as_deref().expose())
+        .with_context(|| {
+            format!(
+                "failed to exchange the OIDC token for a token of {}",
+                source_ids.original
+            )
+        })?;
+    Ok(Secret::from(token))
+}
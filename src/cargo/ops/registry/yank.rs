@@ -12,6 +12,7 @@ use crate::core::Workspace;
 use crate::util::config::Config;
 use crate::util::errors::CargoResult;
 use crate::util::important_paths::find_root_manifest_for_wd;
+use crate::util::network::retry::with_retry;
 
 pub fn yank(
     config: &Config,
@@ -59,7 +60,7 @@ pub fn yank(
     let package_spec = format!("{}@{}", name, version);
     if undo {
         config.shell().status("Unyank", package_spec)?;
-        registry.unyank(&name, &version).with_context(|| {
+        with_retry(config, || Ok(registry.unyank(&name, &version)?)).with_context(|| {
             format!(
                 "failed to undo a yank from the registry at {}",
                 registry.host()
@@ -67,8 +68,7 @@ pub fn yank(
         })?;
     } else {
         config.shell().status("Yank", package_spec)?;
-        registry
-            .yank(&name, &version)
+        with_retry(config, || Ok(registry.yank(&name, &version)?))
             .with_context(|| format!("failed to yank from the registry at {}", registry.host()))?;
     }
 
@@ -66,13 +66,14 @@ use crate::core::resolver::{
 use crate::core::summary::Summary;
 use crate::core::Feature;
 use crate::core::{GitReference, PackageId, PackageIdSpec, PackageSet, SourceId, Workspace};
+use crate::message;
 use crate::ops;
 use crate::sources::PathSource;
 use crate::util::errors::CargoResult;
 use crate::util::{profile, CanonicalUrl};
 use anyhow::Context as _;
-use log::{debug, trace};
 use std::collections::{HashMap, HashSet};
+use tracing::{debug, trace};
 
 /// Result for `resolve_ws_with_opts`.
 pub struct WorkspaceResolve<'cfg> {
@@ -90,12 +91,6 @@ pub struct WorkspaceResolve<'cfg> {
     pub resolved_features: ResolvedFeatures,
 }
 
-const UNUSED_PATCH_WARNING: &str = "\
-Check that the patched package version and available features are compatible
-with the dependency requirements. If the patch has a different version from
-what is locked in the Cargo.lock file, run `cargo update` to use the new
-version. This may also occur with an optional dependency that is not enabled.";
-
 /// Resolves all dependencies for the workspace using the previous
 /// lock file as a guide if present.
 ///
@@ -122,6 +117,7 @@ pub fn resolve_ws<'a>(ws: &Workspace<'a>) -> CargoResult<(PackageSet<'a>, Resolv
 ///
 /// `specs` may be empty, which indicates it should resolve all workspace
 /// members. In this case, `opts.all_features` must be `true`.
+#[tracing::instrument(skip_all)]
 pub fn resolve_ws_with_opts<'cfg>(
     ws: &Workspace<'cfg>,
     target_data: &RustcTargetData<'cfg>,
@@ -154,19 +150,22 @@ pub fn resolve_ws_with_opts<'cfg>(
                 .iter()
                 .any(|r| replace_spec.matches(r) && !dep.matches_id(r))
             {
-                ws.config()
-                    .shell()
-                    .warn(format!("package replacement is not used: {}", replace_spec))?
+                let msg = message!(
+                    ws.config(),
+                    "package replacement is not used: {spec}",
+                    spec = replace_spec,
+                );
+                ws.config().shell().warn(msg)?
             }
 
             if dep.features().len() != 0 || !dep.uses_default_features() {
-                ws.config()
-                .shell()
-                .warn(format!(
-                    "replacement for `{}` uses the features mechanism. \
+                let msg = message!(
+                    ws.config(),
+                    "replacement for `{name}` uses the features mechanism. \
                     default-features and features will not take effect because the replacement dependency does not support this mechanism",
-                    dep.package_name()
-                ))?
+                    name = dep.package_name(),
+                );
+                ws.config().shell().warn(msg)?
             }
         }
 
@@ -278,6 +277,57 @@ pub fn resolve_with_previous<'cfg>(
     to_avoid: Option<&HashSet<PackageId>>,
     specs: &[PackageIdSpec],
     register_patches: bool,
+) -> CargoResult<Resolve> {
+    resolve_with_previous_inner(
+        registry,
+        ws,
+        cli_features,
+        has_dev_units,
+        previous,
+        to_avoid,
+        false,
+        specs,
+        register_patches,
+    )
+}
+
+/// Resolves all dependencies of the workspace like [`resolve_with_previous`],
+/// except that the packages of `to_unlock` are still preferred over the other
+/// versions of them.
+///
+/// Only the locks pointing at those packages are removed, so the packages
+/// depending on them can switch to another version that is in `previous`,
+/// while nothing is updated to a version that isn't.
+pub fn resolve_with_unlocked_edges<'cfg>(
+    registry: &mut PackageRegistry<'cfg>,
+    ws: &Workspace<'cfg>,
+    previous: &Resolve,
+    to_unlock: &HashSet<PackageId>,
+) -> CargoResult<Resolve> {
+    resolve_with_previous_inner(
+        registry,
+        ws,
+        &CliFeatures::new_all(true),
+        HasDevUnits::Yes,
+        Some(previous),
+        Some(to_unlock),
+        true,
+        &[],
+        true,
+    )
+}
+
+#[tracing::instrument(skip_all)]
+fn resolve_with_previous_inner<'cfg>(
+    registry: &mut PackageRegistry<'cfg>,
+    ws: &Workspace<'cfg>,
+    cli_features: &CliFeatures,
+    has_dev_units: HasDevUnits,
+    previous: Option<&Resolve>,
+    to_avoid: Option<&HashSet<PackageId>>,
+    prefer_avoided: bool,
+    specs: &[PackageIdSpec],
+    register_patches: bool,
 ) -> CargoResult<Resolve> {
     // We only want one Cargo at a time resolving a crate graph since this can
     // involve a lot of frobbing of the global caches.
@@ -445,6 +495,12 @@ pub fn resolve_with_previous<'cfg>(
             debug!("attempting to prefer {}", id);
             version_prefs.prefer_package_id(id);
         }
+        if prefer_avoided {
+            for &id in to_avoid.into_iter().flatten() {
+                debug!("attempting to prefer unlocked {}", id);
+                version_prefs.prefer_package_id(id);
+            }
+        }
     }
 
     if register_patches {
@@ -506,12 +562,7 @@ pub fn resolve_with_previous<'cfg>(
             .require(Feature::public_dependency())
             .is_ok(),
     )?;
-    let patches: Vec<_> = registry
-        .patches()
-        .values()
-        .flat_map(|v| v.iter().cloned())
-        .collect();
-    resolved.register_used_patches(&patches[..]);
+    resolved.register_used_patches(registry.patches());
 
     if register_patches && !resolved.unused_patches().is_empty() {
         emit_warnings_of_unused_patches(ws, &resolved, registry)?;
@@ -799,8 +850,6 @@ fn emit_warnings_of_unused_patches(
     resolve: &Resolve,
     registry: &PackageRegistry<'_>,
 ) -> CargoResult<()> {
-    const MESSAGE: &str = "was not used in the crate graph.";
-
     // Patch package with the source URLs being patch
     let mut patch_pkgid_to_urls = HashMap::new();
     for (url, summaries) in registry.patches().iter() {
@@ -834,17 +883,18 @@ fn emit_warnings_of_unused_patches(
                     .iter()
                     .all(|id| !patched_urls.contains(id.canonical_url())) =>
             {
-                use std::fmt::Write;
-                let mut msg = String::new();
-                writeln!(msg, "Patch `{}` {}", unused, MESSAGE)?;
-                write!(
-                    msg,
-                    "Perhaps you misspelled the source URL being patched.\n\
-                    Possible URLs for `[patch.<URL>]`:",
-                )?;
-                for id in ids.iter() {
-                    write!(msg, "\n    {}", id.display_registry_name())?;
-                }
+                let urls: String = ids
+                    .iter()
+                    .map(|id| format!("\n    {}", id.display_registry_name()))
+                    .collect();
+                let msg = message!(
+                    ws.config(),
+                    "Patch `{pkg}` was not used in the crate graph.\n\
+                    Perhaps you misspelled the source URL being patched.\n\
+                    Possible URLs for `[patch.<URL>]`:{urls}",
+                    pkg = unused,
+                    urls = urls,
+                );
                 ws.config().shell().warn(msg)?;
             }
             _ => unemitted_unused_patches.push(unused),
@@ -855,11 +905,24 @@ fn emit_warnings_of_unused_patches(
     if !unemitted_unused_patches.is_empty() {
         let warnings: Vec<_> = unemitted_unused_patches
             .iter()
-            .map(|pkgid| format!("Patch `{}` {}", pkgid, MESSAGE))
+            .map(|pkgid| {
+                message!(
+                    ws.config(),
+                    "Patch `{pkg}` was not used in the crate graph.",
+                    pkg = pkgid,
+                )
+            })
             .collect();
-        ws.config()
-            .shell()
-            .warn(format!("{}\n{}", warnings.join("\n"), UNUSED_PATCH_WARNING))?;
+        let msg = message!(
+            ws.config(),
+            "{warnings}\n\
+Check that the patched package version and available features are compatible
+with the dependency requirements. If the patch has a different version from
+what is locked in the Cargo.lock file, run `cargo update` to use the new
+version. This may also occur with an optional dependency that is not enabled.",
+            warnings = warnings.join("\n"),
+        );
+        ws.config().shell().warn(msg)?;
     }
 
     return Ok(());
@@ -0,0 +1,519 @@
+//! The semver check of `cargo publish --semver-check`.
+//!
+//! The library of the package is documented with rustdoc's JSON output, along
+//! with the one of the latest version published before it, and their public
+//! APIs are compared. Public items are found by walking the modules from the
+//! crate root, following re-exports, so an item is known by the paths users
+//! can name it with rather than where it is defined. Each path maps to a
+//! description of the item with the ids of the JSON stripped, which changes
+//! when the signature of the item does.
+//!
+//! Removing or changing an item is breaking, as is adding a variant to an
+//! exhaustive enum, a public field to a struct without private fields, or a
+//! required method to a trait. Other changes, like new bounds on generic
+//! items, aren't detected.
+
+use std::collections::{BTreeMap, HashSet};
+use std::task::Poll;
+
+use anyhow::Context as _;
+use cargo_util::paths;
+use serde_json::Value;
+
+use crate::core::compiler::CompileMode;
+use crate::core::resolver::CliFeatures;
+use crate::core::{Dependency, Package, QueryKind, Source, SourceId, Workspace};
+use crate::message;
+use crate::ops::{self, CompileFilter, CompileOptions, Packages};
+use crate::sources::SourceConfigMap;
+use crate::util::{CargoResult, Filesystem};
+use crate::Config;
+
+/// Compares the public API of the library of `pkg` with the one of its
+/// latest version published to `registry` before it, failing if it has
+/// breaking changes and the version of `pkg` isn't semver incompatible with
+/// the previous one.
+pub fn check_publish(
+    ws: &Workspace<'_>,
+    pkg: &Package,
+    registry: SourceId,
+    cli_features: &CliFeatures,
+) -> CargoResult<()> {
+    let config = ws.config();
+    if !pkg.targets().iter().any(|t| t.is_lib()) {
+        let msg = message!(
+            config,
+            "skipping the semver check of `{name}`, it has no library",
+            name = pkg.name(),
+        );
+        config.shell().note(msg)?;
+        return Ok(());
+    }
+    let Some(previous) = previous_version(ws, pkg, registry)? else {
+        let msg = message!(
+            config,
+            "skipping the semver check of `{name}`, no previous version is published to {registry}",
+            name = pkg.name(),
+            registry = registry,
+        );
+        config.shell().note(msg)?;
+        return Ok(());
+    };
+    let msg = message!(
+        config,
+        "public API of `{pkg}` with v{previous}",
+        pkg = pkg.package_id(),
+        previous = previous.version(),
+    );
+    config.shell().status("Comparing", msg)?;
+    let target_dir = ws.target_dir().join("semver-check");
+    let old = public_api(&rustdoc_json(
+        config,
+        &previous,
+        target_dir.join(format!("{}-{}", previous.name(), previous.version())),
+        cli_features,
+    )?)?;
+    let new = public_api(&rustdoc_json(
+        config,
+        pkg,
+        target_dir.join(format!("{}-{}", pkg.name(), pkg.version())),
+        cli_features,
+    )?)?;
+
+    let changes = breaking_changes(&old, &new);
+    if changes.is_empty() {
+        return Ok(());
+    }
+    let changes: String = changes.iter().map(|c| format!("\n  {c}")).collect();
+    if is_major_bump(previous.version(), pkg.version()) {
+        let msg = message!(
+            config,
+            "the public API of `{name}` has breaking changes since v{previous}:{changes}",
+            name = pkg.name(),
+            previous = previous.version(),
+            changes = changes,
+        );
+        config.shell().note(msg)?;
+        return Ok(());
+    }
+    anyhow::bail!(
+        "the public API of `{}` has breaking changes since v{}, \
+         which need a new major version instead of v{}:{}\n\n\
+         Bump the major version, or publish without `--semver-check` if these \
+         changes are expected to be compatible.",
+        pkg.name(),
+        previous.version(),
+        pkg.version(),
+        changes
+    )
+}
+
+/// Downloads the latest version of `pkg` published to `registry` before the
+/// version of `pkg`.
+fn previous_version(
+    ws: &Workspace<'_>,
+    pkg: &Package,
+    registry: SourceId,
+) -> CargoResult<Option<Package>> {
+    let config = ws.config();
+    let _lock = config.acquire_package_cache_lock()?;
+    let mut source = SourceConfigMap::empty(config)?.load(registry, &HashSet::new())?;
+    source.invalidate_cache();
+    let req = format!("<{}", pkg.version());
+    let query = Dependency::parse(pkg.name(), Some(&req), registry)?;
+    let summaries = loop {
+        match source.query_vec(&query, QueryKind::Exact)? {
+            Poll::Ready(summaries) => break summaries,
+            Poll::Pending => source.block_until_ready()?,
+        }
+    };
+    let Some(pkg_id) = summaries.iter().map(|s| s.package_id()).max() else {
+        return Ok(None);
+    };
+    let pkg = Box::new(&mut source).download_now(pkg_id, config)?;
+    Ok(Some(pkg))
+}
+
+/// Documents the library of `pkg` in `target_dir` with rustdoc's JSON output,
+/// which is unstable, returning the parsed JSON.
+fn rustdoc_json(
+    config: &Config,
+    pkg: &Package,
+    target_dir: Filesystem,
+    cli_features: &CliFeatures,
+) -> CargoResult<Value> {
+    let ws = Workspace::ephemeral(pkg.clone(), config, Some(target_dir.clone()), false)?;
+    let mut opts = CompileOptions::new(config, CompileMode::Doc { deps: false })?;
+    opts.spec = Packages::Packages(vec![pkg.name().to_string()]);
+    opts.filter = CompileFilter::lib_only();
+    opts.cli_features = cli_features.clone();
+    opts.target_rustdoc_args = Some(
+        ["-Zunstable-options", "--output-format", "json"]
+            .map(String::from)
+            .to_vec(),
+    );
+    ops::compile(&ws, &opts)
+        .with_context(|| format!("failed to document `{}` with rustdoc", pkg.package_id()))?;
+
+    let lib = pkg.targets().iter().find(|t| t.is_lib()).unwrap();
+    let path = target_dir
+        .into_path_unlocked()
+        .join("doc")
+        .join(format!("{}.json", lib.crate_name()));
+    let json = paths::read(&path)?;
+    serde_json::from_str(&json)
+        .with_context(|| format!("failed to parse the rustdoc JSON at `{}`", path.display()))
+}
+
+/// Whether going from `old` to `new` is allowed to break the public API.
+fn is_major_bump(old: &semver::Version, new: &semver::Version) -> bool {
+    if old.major != new.major {
+        true
+    } else if old.major != 0 {
+        false
+    } else if old.minor != new.minor {
+        true
+    } else {
+        old.minor == 0 && old.patch != new.patch
+    }
+}
+
+/// A public item of the API of a crate.
+#[derive(Debug)]
+struct ApiItem {
+    kind: &'static str,
+    /// Describes the item, changing along with its signature.
+    signature: String,
+    /// Whether adding this item breaks existing users, like a variant of an
+    /// exhaustive enum.
+    breaking_if_added: bool,
+}
+
+/// Lists the breaking changes from the API `old` to the API `new`.
+///
+/// The items of a removed item, like its fields or methods, aren't listed.
+fn breaking_changes(
+    old: &BTreeMap<String, ApiItem>,
+    new: &BTreeMap<String, ApiItem>,
+) -> Vec<String> {
+    let mut changes = Vec::new();
+    let mut removed: Option<&str> = None;
+    for (path, old_item) in old {
+        if let Some(parent) = removed {
+            let rest = path.strip_prefix(parent).unwrap_or_default();
+            if rest.starts_with("::") || rest.starts_with(": ") {
+                continue;
+            }
+        }
+        match new.get(path) {
+            None => {
+                changes.push(format!("{} `{path}` was removed", old_item.kind));
+                removed = Some(path);
+            }
+            Some(new_item)
+                if new_item.kind != old_item.kind || new_item.signature != old_item.signature =>
+            {
+                changes.push(format!("{} `{path}` changed", old_item.kind))
+            }
+            Some(_) => {}
+        }
+    }
+    for (path, new_item) in new {
+        if new_item.breaking_if_added && !old.contains_key(path) {
+            changes.push(format!("{} `{path}` was added", new_item.kind));
+        }
+    }
+    changes
+}
+
+/// Collects the public API of the crate documented in `json`, by path.
+fn public_api(json: &Value) -> CargoResult<BTreeMap<String, ApiItem>> {
+    let index = json
+        .get("index")
+        .and_then(Value::as_object)
+        .context("unsupported rustdoc JSON, it has no `index`")?;
+    let root = &json["root"];
+    let name = index
+        .get(&id_key(root))
+        .and_then(|item| item["name"].as_str())
+        .context("unsupported rustdoc JSON, its `root` isn't in its `index`")?;
+    let mut walker = ApiWalker {
+        index,
+        items: BTreeMap::new(),
+        modules: HashSet::new(),
+    };
+    walker.walk(root, name.to_string());
+    Ok(walker.items)
+}
+
+/// The key of an id in the `index` of the JSON, which is a number or a
+/// string depending on the version of the format.
+fn id_key(id: &Value) -> String {
+    match id {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// The kind of `item`, and the fields specific to it.
+fn inner(item: &Value) -> Option<(&str, &Value)> {
+    let (kind, inner) = item.get("inner")?.as_object()?.iter().next()?;
+    Some((kind.as_str(), inner))
+}
+
+/// Describes `value` without the ids it refers to other items with, which
+/// differ between runs of rustdoc.
+fn describe(value: &Value) -> String {
+    fn strip_ids(value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                map.remove("id");
+                map.values_mut().for_each(strip_ids);
+            }
+            Value::Array(values) => values.iter_mut().for_each(strip_ids),
+            _ => {}
+        }
+    }
+    let mut value = value.clone();
+    strip_ids(&mut value);
+    value.to_string()
+}
+
+fn is_non_exhaustive(item: &Value) -> bool {
+    item["attrs"].to_string().contains("non_exhaustive")
+}
+
+struct ApiWalker<'a> {
+    index: &'a serde_json::Map<String, Value>,
+    items: BTreeMap<String, ApiItem>,
+    /// The modules being walked, against cycles of glob re-exports.
+    modules: HashSet<String>,
+}
+
+impl<'a> ApiWalker<'a> {
+    fn item(&self, id: &Value) -> Option<&'a Value> {
+        self.index.get(&id_key(id))
+    }
+
+    fn add(&mut self, path: String, kind: &'static str, signature: String, breaking: bool) {
+        self.items.insert(
+            path,
+            ApiItem {
+                kind,
+                signature,
+                breaking_if_added: breaking,
+            },
+        );
+    }
+
+    /// Adds the item `id` at `path`, with the items it contains.
+    fn walk(&mut self, id: &Value, path: String) {
+        let Some(item) = self.item(id) else {
+            return;
+        };
+        let Some((kind, inner)) = inner(item) else {
+            return;
+        };
+        match kind {
+            "module" => {
+                self.add(path.clone(), "module", String::new(), false);
+                self.walk_module(id, &path);
+            }
+            "struct" | "union" => {
+                let kind = if kind == "struct" { "struct" } else { "union" };
+                self.add(path.clone(), kind, describe(&inner["generics"]), false);
+                let (fields, has_stripped) = match &inner["kind"] {
+                    Value::Object(k) if k.contains_key("plain") => (
+                        &k["plain"]["fields"],
+                        k["plain"]["has_stripped_fields"].as_bool() == Some(true),
+                    ),
+                    Value::Object(k) if k.contains_key("tuple") => (
+                        &k["tuple"],
+                        k["tuple"]
+                            .as_array()
+                            .map_or(false, |fields| fields.iter().any(Value::is_null)),
+                    ),
+                    _ => (&inner["fields"], inner["has_stripped_fields"] == true),
+                };
+                let breaking = !has_stripped && !is_non_exhaustive(item);
+                self.walk_fields(fields, &path, breaking);
+                self.walk_impls(&inner["impls"], &path);
+            }
+            "enum" => {
+                self.add(path.clone(), "enum", describe(&inner["generics"]), false);
+                let breaking = !is_non_exhaustive(item);
+                for variant_id in inner["variants"].as_array().into_iter().flatten() {
+                    self.walk_variant(variant_id, &path, breaking);
+                }
+                self.walk_impls(&inner["impls"], &path);
+            }
+            "trait" => {
+                let signature = describe(&serde_json::json!([
+                    inner["generics"],
+                    inner["bounds"],
+                    inner["is_unsafe"]
+                ]));
+                self.add(path.clone(), "trait", signature, false);
+                for item_id in inner["items"].as_array().into_iter().flatten() {
+                    let Some(trait_item) = self.item(item_id) else {
+                        continue;
+                    };
+                    let (Some(name), Some((kind, inner))) =
+                        (trait_item["name"].as_str(), self::inner(trait_item))
+                    else {
+                        continue;
+                    };
+                    let required = kind == "function" && inner["has_body"] == false;
+                    let signature = describe(inner);
+                    self.add(format!("{path}::{name}"), "trait item", signature, required);
+                }
+            }
+            "function" => {
+                let signature = describe(&serde_json::json!([
+                    inner["sig"],
+                    inner["decl"],
+                    inner["generics"],
+                    inner["header"]
+                ]));
+                self.add(path, "function", signature, false);
+            }
+            "constant" => self.add(path, "constant", describe(&inner["type"]), false),
+            "static" => {
+                let signature = describe(&serde_json::json!([inner["type"], inner["is_mutable"]]));
+                self.add(path, "static", signature, false);
+            }
+            "type_alias" => self.add(path, "type alias", describe(inner), false),
+            "macro" | "proc_macro" => self.add(path, "macro", String::new(), false),
+            _ => self.add(path, "item", String::new(), false),
+        }
+    }
+
+    /// Adds the items of the module `id` at `path`.
+    fn walk_module(&mut self, id: &Value, path: &str) {
+        if !self.modules.insert(id_key(id)) {
+            return;
+        }
+        let children = self
+            .item(id)
+            .and_then(inner)
+            .and_then(|(_, inner)| inner["items"].as_array());
+        for child_id in children.into_iter().flatten() {
+            let Some(child) = self.item(child_id) else {
+                continue;
+            };
+            match inner(child) {
+                Some(("use", import)) => {
+                    let target = &import["id"];
+                    let target_kind = self.item(target).and_then(inner).map(|(kind, _)| kind);
+                    if import["is_glob"] == true {
+                        if target_kind == Some("module") {
+                            self.walk_module(target, path);
+                        }
+                        continue;
+                    }
+                    let Some(name) = import["name"].as_str() else {
+                        continue;
+                    };
+                    let child_path = format!("{path}::{name}");
+                    if target_kind.is_some() {
+                        self.walk(target, child_path);
+                    } else {
+                        // A re-export of another crate.
+                        let source = import["source"].as_str().unwrap_or_default().to_string();
+                        self.add(child_path, "re-export", source, false);
+                    }
+                }
+                _ => {
+                    let Some(name) = child["name"].as_str() else {
+                        continue;
+                    };
+                    let child_path = format!("{path}::{name}");
+                    self.walk(child_id, child_path);
+                }
+            }
+        }
+        self.modules.remove(&id_key(id));
+    }
+
+    /// Adds the public fields `fields` of a struct or variant at `path`.
+    fn walk_fields(&mut self, fields: &Value, path: &str, breaking_if_added: bool) {
+        for field_id in fields.as_array().into_iter().flatten() {
+            let Some(field) = self.item(field_id) else {
+                continue;
+            };
+            let (Some(name), Some((_, ty))) = (field["name"].as_str(), inner(field)) else {
+                continue;
+            };
+            let signature = describe(ty);
+            self.add(
+                format!("{path}::{name}"),
+                "field",
+                signature,
+                breaking_if_added,
+            );
+        }
+    }
+
+    /// Adds the variant `id` of an enum at `path`, with its fields.
+    fn walk_variant(&mut self, id: &Value, path: &str, breaking_if_added: bool) {
+        let Some(variant) = self.item(id) else {
+            return;
+        };
+        let (Some(name), Some((_, inner))) = (variant["name"].as_str(), inner(variant)) else {
+            return;
+        };
+        let path = format!("{path}::{name}");
+        let (shape, fields) = match &inner["kind"] {
+            Value::Object(k) if k.contains_key("tuple") => ("tuple", k["tuple"].clone()),
+            Value::Object(k) if k.contains_key("struct") => {
+                ("struct", k["struct"]["fields"].clone())
+            }
+            _ => ("plain", Value::Null),
+        };
+        self.add(
+            path.clone(),
+            "variant",
+            shape.to_string(),
+            breaking_if_added,
+        );
+        // Fields can't be added to existing variants.
+        self.walk_fields(&fields, &path, true);
+    }
+
+    /// Adds the inherent methods and the trait implementations of a type at
+    /// `path`.
+    fn walk_impls(&mut self, impls: &Value, path: &str) {
+        for impl_id in impls.as_array().into_iter().flatten() {
+            let Some((_, imp)) = self.item(impl_id).and_then(inner) else {
+                continue;
+            };
+            if !imp["blanket_impl"].is_null() {
+                continue;
+            }
+            if imp["trait"].is_null() {
+                for item_id in imp["items"].as_array().into_iter().flatten() {
+                    let Some(item) = self.item(item_id) else {
+                        continue;
+                    };
+                    let (Some(name), Some((kind, inner))) = (item["name"].as_str(), inner(item))
+                    else {
+                        continue;
+                    };
+                    let kind = if kind == "function" {
+                        "method"
+                    } else {
+                        "associated item"
+                    };
+                    let signature = describe(inner);
+                    self.add(format!("{path}::{name}"), kind, signature, false);
+                }
+            } else {
+                let negative = if imp["is_negative"] == true { "!" } else { "" };
+                let trait_path = imp["trait"]["path"].as_str().unwrap_or_default();
+                let args = describe(&imp["trait"]["args"]);
+                let key = format!("{path}: {negative}{trait_path}");
+                self.add(key, "trait impl", args, false);
+            }
+        }
+    }
+}
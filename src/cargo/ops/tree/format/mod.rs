@@ -14,12 +14,15 @@ enum Chunk {
     Repository,
     Features,
     LibName,
+    Provenance,
 }
 
 pub struct Pattern(Vec<Chunk>);
 
 impl Pattern {
-    pub fn new(format: &str) -> Result<Pattern, Error> {
+    /// Parses the `--format` string, where `{provenance}` requires
+    /// `unstable_options`.
+    pub fn new(format: &str, unstable_options: bool) -> Result<Pattern, Error> {
         let mut chunks = vec![];
 
         for raw in Parser::new(format) {
@@ -30,6 +33,15 @@ impl Pattern {
                 RawChunk::Argument("r") => Chunk::Repository,
                 RawChunk::Argument("f") => Chunk::Features,
                 RawChunk::Argument("lib") => Chunk::LibName,
+                RawChunk::Argument("provenance") => {
+                    if !unstable_options {
+                        bail!(
+                            "the `{{provenance}}` pattern is unstable, \
+                             pass `-Z unstable-options` to enable it"
+                        );
+                    }
+                    Chunk::Provenance
+                }
                 RawChunk::Argument(a) => {
                     bail!("unsupported pattern `{}`", a);
                 }
@@ -111,6 +123,11 @@ impl<'a> fmt::Display for Display<'a> {
                                 write!(fmt, "{}", target.crate_name())?;
                             }
                         }
+                        Chunk::Provenance => {
+                            if let Some(provenance) = self.graph.provenance(*package_id) {
+                                write!(fmt, "({provenance})")?;
+                            }
+                        }
                     }
                 }
             }
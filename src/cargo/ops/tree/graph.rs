@@ -4,7 +4,7 @@ use super::TreeOptions;
 use crate::core::compiler::{CompileKind, RustcTargetData};
 use crate::core::dependency::DepKind;
 use crate::core::resolver::features::{CliFeatures, FeaturesFor, ResolvedFeatures};
-use crate::core::resolver::Resolve;
+use crate::core::resolver::{Provenance, Resolve};
 use crate::core::{FeatureMap, FeatureValue, Package, PackageId, PackageIdSpec, Workspace};
 use crate::util::interning::InternedString;
 use crate::util::CargoResult;
@@ -69,6 +69,8 @@ pub struct Graph<'a> {
     index: HashMap<Node, usize>,
     /// Map for looking up packages.
     package_map: HashMap<PackageId, &'a Package>,
+    /// The packages from a `[patch]` or `[replace]` entry.
+    provenance: HashMap<PackageId, Provenance>,
     /// Set of indexes of feature nodes that were added via the command-line.
     ///
     /// For example `--features foo` will mark the "foo" node here.
@@ -82,12 +84,16 @@ pub struct Graph<'a> {
 }
 
 impl<'a> Graph<'a> {
-    fn new(package_map: HashMap<PackageId, &'a Package>) -> Graph<'a> {
+    fn new(
+        package_map: HashMap<PackageId, &'a Package>,
+        provenance: HashMap<PackageId, Provenance>,
+    ) -> Graph<'a> {
         Graph {
             nodes: Vec::new(),
             edges: Vec::new(),
             index: HashMap::new(),
             package_map,
+            provenance,
             cli_features: HashSet::new(),
             dep_name_map: HashMap::new(),
         }
@@ -148,6 +154,11 @@ impl<'a> Graph<'a> {
         self.package_map[&id]
     }
 
+    /// Whether the package is from a `[patch]` or `[replace]` entry.
+    pub fn provenance(&self, id: PackageId) -> Option<&Provenance> {
+        self.provenance.get(&id)
+    }
+
     fn package_id_for_index(&self, index: usize) -> PackageId {
         match self.nodes[index] {
             Node::Package { package_id, .. } => package_id,
@@ -166,7 +177,7 @@ impl<'a> Graph<'a> {
     pub fn from_reachable(&self, roots: &[usize]) -> Graph<'a> {
         // Graph built with features does not (yet) support --duplicates.
         assert!(self.dep_name_map.is_empty());
-        let mut new_graph = Graph::new(self.package_map.clone());
+        let mut new_graph = Graph::new(self.package_map.clone(), self.provenance.clone());
         // Maps old index to new index. None if not yet visited.
         let mut remap: Vec<Option<usize>> = vec![None; self.nodes.len()];
 
@@ -278,7 +289,11 @@ pub fn build<'a>(
     package_map: HashMap<PackageId, &'a Package>,
     opts: &TreeOptions,
 ) -> CargoResult<Graph<'a>> {
-    let mut graph = Graph::new(package_map);
+    let provenance = package_map
+        .keys()
+        .filter_map(|&id| Some((id, resolve.provenance(id)?)))
+        .collect();
+    let mut graph = Graph::new(package_map, provenance);
     let mut members_with_features = ws.members_with_features(specs, cli_features)?;
     members_with_features.sort_unstable_by_key(|e| e.0.package_id());
     for (member, cli_features) in members_with_features {
@@ -642,7 +657,7 @@ fn add_feature_rec(
                 let dep_indexes = match graph.dep_name_map[&package_index].get(dep_name) {
                     Some(indexes) => indexes.clone(),
                     None => {
-                        log::debug!(
+                        tracing::debug!(
                             "enabling feature {} on {}, found {}/{}, \
                              dep appears to not be enabled",
                             feature_name,
@@ -5,10 +5,12 @@ use crate::core::compiler::{CompileKind, RustcTargetData};
 use crate::core::dependency::DepKind;
 use crate::core::resolver::{features::CliFeatures, ForceAllTargets, HasDevUnits};
 use crate::core::{Package, PackageId, PackageIdSpec, Workspace};
+use crate::message;
 use crate::ops::{self, Packages};
 use crate::util::{CargoResult, Config};
 use crate::{drop_print, drop_println};
 use anyhow::Context;
+use cargo_platform::Cfg;
 use graph::Graph;
 use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
@@ -24,6 +26,9 @@ pub struct TreeOptions {
     pub packages: Packages,
     /// The platform to filter for.
     pub target: Target,
+    /// The `cfg` values of `--cfg`, to evaluate platform-specific
+    /// dependencies against instead of the ones of the targets.
+    pub cfgs: Vec<Cfg>,
     /// The dependency kinds to display.
     pub edge_kinds: HashSet<EdgeKind>,
     pub invert: Vec<String>,
@@ -135,7 +140,11 @@ pub fn build_and_print(ws: &Workspace<'_>, opts: &TreeOptions) -> CargoResult<()
     // TODO: Target::All is broken with -Zfeatures=itarget. To handle that properly,
     // `FeatureResolver` will need to be taught what "all" means.
     let requested_kinds = CompileKind::from_requested_targets(ws.config(), &requested_targets)?;
-    let target_data = RustcTargetData::new(ws, &requested_kinds)?;
+    let target_data = if opts.cfgs.is_empty() {
+        RustcTargetData::new(ws, &requested_kinds)?
+    } else {
+        RustcTargetData::with_cfgs(ws, &requested_kinds, opts.cfgs.clone())?
+    };
     let specs = opts.packages.to_package_id_specs(ws)?;
     let has_dev = if opts
         .edge_kinds
@@ -214,11 +223,13 @@ pub fn build_and_print(ws: &Workspace<'_>, opts: &TreeOptions) -> CargoResult<()
         .collect::<CargoResult<Vec<PackageIdSpec>>>()?;
 
     if root_indexes.len() == 0 {
-        ws.config().shell().warn(
+        let msg = message!(
+            ws.config(),
             "nothing to print.\n\n\
         To find dependencies that require specific target platforms, \
-        try to use option `--target all` first, and then narrow your search scope accordingly.",
-        )?;
+        try to use option `--target all` first, and then narrow your search scope accordingly."
+        );
+        ws.config().shell().warn(msg)?;
     } else {
         print(ws.config(), opts, root_indexes, &pkgs_to_prune, &graph)?;
     }
@@ -233,7 +244,7 @@ fn print(
     pkgs_to_prune: &[PackageIdSpec],
     graph: &Graph<'_>,
 ) -> CargoResult<()> {
-    let format = Pattern::new(&opts.format)
+    let format = Pattern::new(&opts.format, config.cli_unstable().unstable_options)
         .with_context(|| format!("tree format `{}` not valid", opts.format))?;
 
     let symbols = match opts.charset {
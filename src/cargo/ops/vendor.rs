@@ -1,10 +1,13 @@
 use crate::core::package::MANIFEST_PREAMBLE;
+use crate::core::registry::PackageRegistry;
+use crate::core::resolver::{CliFeatures, HasDevUnits};
 use crate::core::shell::Verbosity;
-use crate::core::{GitReference, Package, Workspace};
+use crate::core::{GitReference, Package, Source, SourceId, Workspace};
+use crate::message;
 use crate::ops;
 use crate::sources::path::PathSource;
-use crate::sources::CRATES_IO_REGISTRY;
-use crate::util::{try_canonicalize, CargoResult, Config};
+use crate::sources::{DirectorySource, CRATES_IO_REGISTRY};
+use crate::util::{self, try_canonicalize, CargoResult, Config};
 use anyhow::{bail, Context as _};
 use cargo_util::{paths, Sha256};
 use serde::Serialize;
@@ -20,6 +23,8 @@ pub struct VendorOptions<'a> {
     pub versioned_dirs: bool,
     pub destination: &'a Path,
     pub extra: Vec<PathBuf>,
+    /// Only check that the destination is up to date, without changing it.
+    pub verify: bool,
 }
 
 pub fn vendor(ws: &Workspace<'_>, opts: &VendorOptions<'_>) -> CargoResult<()> {
@@ -31,6 +36,9 @@ pub fn vendor(ws: &Workspace<'_>, opts: &VendorOptions<'_>) -> CargoResult<()> {
         extra_workspaces.push(ws);
     }
     let workspaces = extra_workspaces.iter().chain(Some(ws)).collect::<Vec<_>>();
+    if opts.verify {
+        return verify(config, &workspaces, opts);
+    }
     let vendor_config = sync(config, &workspaces, opts).with_context(|| "failed to sync")?;
 
     if config.shell().verbosity() != Verbosity::Quiet {
@@ -217,10 +225,14 @@ fn sync(
             continue;
         }
 
-        config.shell().status(
-            "Vendoring",
-            &format!("{} ({}) to {}", id, src.to_string_lossy(), dst.display()),
-        )?;
+        let msg = message!(
+            config,
+            "{id} ({src}) to {dst}",
+            id = id,
+            src = src.to_string_lossy(),
+            dst = dst.display(),
+        );
+        config.shell().status("Vendoring", msg)?;
 
         let _ = fs::remove_dir_all(&dst);
         let pathsource = PathSource::new(src, id.source_id(), config);
@@ -315,6 +327,135 @@ fn sync(
     Ok(VendorConfig { source: config })
 }
 
+/// Checks that the vendor directory contains exactly the packages in the lock
+/// files of `workspaces`, and that none of them were modified since they were
+/// vendored.
+///
+/// The workspaces are resolved again first, like `cargo vendor` does, so a
+/// lock file out of date with the manifests is reported too. It isn't
+/// written.
+///
+/// Every problem is reported as a warning before failing, so that all of
+/// them can be fixed at once.
+fn verify(
+    config: &Config,
+    workspaces: &[&Workspace<'_>],
+    opts: &VendorOptions<'_>,
+) -> CargoResult<()> {
+    let mut locked = BTreeMap::new();
+    let mut problems = Vec::new();
+    for ws in workspaces {
+        let previous = ops::load_pkg_lockfile(ws)?.with_context(|| {
+            format!(
+                "no `Cargo.lock` found for `{}`, run `cargo vendor` to create it \
+                 along with the vendor directory",
+                ws.root_manifest().display()
+            )
+        })?;
+        let mut registry = PackageRegistry::new(config)?;
+        let resolve = ops::resolve_with_previous(
+            &mut registry,
+            ws,
+            &CliFeatures::new_all(true),
+            HasDevUnits::Yes,
+            Some(&previous),
+            None,
+            &[],
+            true,
+        )?;
+        if resolve.iter().collect::<BTreeSet<_>>() != previous.iter().collect::<BTreeSet<_>>() {
+            problems.push(format!(
+                "`Cargo.lock` of `{}` is out of date with its dependencies",
+                ws.root_manifest().display()
+            ));
+        }
+        for id in resolve.iter().filter(|id| !id.source_id().is_path()) {
+            let checksum = resolve.checksums().get(&id).cloned().flatten();
+            locked.insert((id.name(), id.version().clone()), checksum);
+        }
+    }
+
+    let destination = config.cwd().join(opts.destination);
+    if !destination.is_dir() {
+        bail!(
+            "vendor directory `{}` does not exist, run `cargo vendor` to create it",
+            opts.destination.display()
+        );
+    }
+    let source_id = SourceId::for_directory(&destination)?;
+    let mut source = DirectorySource::new(&destination, source_id, config);
+    source.block_until_ready()?;
+    source.load_all()?;
+    let vendored = source
+        .packages()
+        .map(|pkg| ((pkg.name(), pkg.version().clone()), pkg))
+        .collect::<BTreeMap<_, _>>();
+
+    for ((name, version), pkg) in vendored.iter() {
+        let Some(checksum) = locked.get(&(*name, version.clone())) else {
+            problems.push(message!(
+                config,
+                "`{name} v{version}` is vendored in `{path}` but is not in `Cargo.lock`",
+                name = name,
+                version = version,
+                path = pkg.root().display(),
+            ));
+            continue;
+        };
+        if let Some(checksum) = checksum {
+            if pkg.summary().checksum() != Some(checksum.as_str()) {
+                let path = pkg.root().join(".cargo-checksum.json");
+                problems.push(message!(
+                    config,
+                    "the package checksum of `{name} v{version}` in `{path}` \
+                     does not match `Cargo.lock`",
+                    name = name,
+                    version = version,
+                    path = path.display(),
+                ));
+            }
+        }
+        if let Err(e) = source.verify(pkg.package_id()) {
+            problems.push(message!(
+                config,
+                "the vendored files of `{name} v{version}` were modified: {error}",
+                name = name,
+                version = version,
+                error = format!("{e:#}"),
+            ));
+        }
+    }
+    for (name, version) in locked.keys() {
+        if !vendored.contains_key(&(*name, version.clone())) {
+            problems.push(message!(
+                config,
+                "`{name} v{version}` is in `Cargo.lock` but is not vendored",
+                name = name,
+                version = version,
+            ));
+        }
+    }
+
+    if !problems.is_empty() {
+        for problem in problems.iter() {
+            config.shell().warn(problem)?;
+        }
+        bail!(
+            "vendor directory `{}` does not match `Cargo.lock`\n\
+             run `cargo vendor` to update it",
+            opts.destination.display()
+        );
+    }
+    let msg = message!(
+        config,
+        "{count} vendored packages in `{path}`",
+        count = vendored.len(),
+        path = opts.destination.display(),
+    );
+    config.shell().status("Verified", msg)?;
+    Ok(())
+}
+
 fn cp_sources(
     pkg: &Package,
     src: &Path,
@@ -327,11 +468,12 @@ fn cp_sources(
         let relative = p.strip_prefix(&src).unwrap();
 
         match relative.to_str() {
-            // Skip git config files as they're not relevant to builds most of
-            // the time and if we respect them (e.g.  in git) then it'll
-            // probably mess with the checksums when a vendor dir is checked
-            // into someone else's source control
-            Some(".gitattributes") | Some(".gitignore") | Some(".git") => continue,
+            // Skip git config files and the ignore files of VCSs as they're
+            // not relevant to builds most of the time and if we respect them
+            // (e.g.  in git) then it'll probably mess with the checksums when
+            // a vendor dir is checked into someone else's source control
+            Some(".gitattributes") | Some(".git") => continue,
+            _ if util::is_ignore_file(relative) => continue,
 
             // Temporary Cargo files
             Some(".cargo-ok") => continue,
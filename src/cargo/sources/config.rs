@@ -10,8 +10,8 @@ use crate::util::config::{self, ConfigRelativePath, OptValue};
 use crate::util::errors::CargoResult;
 use crate::util::{Config, IntoUrl};
 use anyhow::{bail, Context as _};
-use log::debug;
 use std::collections::{HashMap, HashSet};
+use tracing::debug;
 use url::Url;
 
 /// Represents the entire [`[source]` replacement table][1] in Cargo configuration.
@@ -134,9 +134,54 @@ impl<'cfg> SourceConfigMap<'cfg> {
     ) -> CargoResult<Box<dyn Source + 'cfg>> {
         debug!("loading: {}", id);
 
+        let Some((new_id, name)) = self.replacement(id)? else {
+            return id.load(self.config, yanked_whitelist);
+        };
+        let orig_name = &self.id2name[&id];
+
+        let new_src = new_id.load(
+            self.config,
+            &yanked_whitelist
+                .iter()
+                .map(|p| p.map_source(id, new_id))
+                .collect(),
+        )?;
+        let old_src = id.load(self.config, yanked_whitelist)?;
+        if !new_src.supports_checksums() && old_src.supports_checksums() {
+            bail!(
+                "\
+cannot replace `{orig}` with `{name}`, the source `{orig}` supports \
+checksums, but `{name}` does not
+
+a lock file compatible with `{orig}` cannot be generated in this situation
+",
+                orig = orig_name,
+                name = name
+            );
+        }
+
+        if old_src.requires_precise() && id.precise().is_none() {
+            bail!(
+                "\
+the source {orig} requires a lock file to be present first before it can be
+used against vendored source code
+
+remove the source replacement configuration, generate a lock file, and then
+restore the source replacement configuration to continue the build
+",
+                orig = orig_name
+            );
+        }
+
+        Ok(Box::new(ReplacedSource::new(id, new_id, new_src)))
+    }
+
+    /// Returns the source that `id` is replaced with through `replace-with`,
+    /// along with the name of that source, or `None` if it isn't replaced.
+    pub fn replacement(&self, id: SourceId) -> CargoResult<Option<(SourceId, &str)>> {
         let mut name = match self.id2name.get(&id) {
             Some(name) => name,
-            None => return id.load(self.config, yanked_whitelist),
+            None => return Ok(None),
         };
         let mut cfg_loc = "";
         let orig_name = name;
@@ -164,7 +209,7 @@ impl<'cfg> SourceConfigMap<'cfg> {
                     name = s;
                     cfg_loc = c;
                 }
-                None if id == cfg.id => return id.load(self.config, yanked_whitelist),
+                None if id == cfg.id => return Ok(None),
                 None => {
                     break cfg.id.with_precise(id.precise().map(|s| s.to_string()));
                 }
@@ -180,42 +225,7 @@ impl<'cfg> SourceConfigMap<'cfg> {
                 )
             }
         };
-
-        let new_src = new_id.load(
-            self.config,
-            &yanked_whitelist
-                .iter()
-                .map(|p| p.map_source(id, new_id))
-                .collect(),
-        )?;
-        let old_src = id.load(self.config, yanked_whitelist)?;
-        if !new_src.supports_checksums() && old_src.supports_checksums() {
-            bail!(
-                "\
-cannot replace `{orig}` with `{name}`, the source `{orig}` supports \
-checksums, but `{name}` does not
-
-a lock file compatible with `{orig}` cannot be generated in this situation
-",
-                orig = orig_name,
-                name = name
-            );
-        }
-
-        if old_src.requires_precise() && id.precise().is_none() {
-            bail!(
-                "\
-the source {orig} requires a lock file to be present first before it can be
-used against vendored source code
-
-remove the source replacement configuration, generate a lock file, and then
-restore the source replacement configuration to continue the build
-",
-                orig = orig_name
-            );
-        }
-
-        Ok(Box::new(ReplacedSource::new(id, new_id, new_src)))
+        Ok(Some((new_id, name)))
     }
 
     /// Adds a source config with an associated name.
@@ -1,5 +1,7 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fmt::{self, Debug, Formatter};
+use std::fs;
 use std::path::{Path, PathBuf};
 use std::task::Poll;
 
@@ -7,11 +9,14 @@ use crate::core::source::MaybePackage;
 use crate::core::{Dependency, Package, PackageId, QueryKind, Source, SourceId, Summary};
 use crate::sources::PathSource;
 use crate::util::errors::CargoResult;
-use crate::util::Config;
+use crate::util::interning::InternedString;
+use crate::util::{short_hash, Config};
 
 use anyhow::Context as _;
 use cargo_util::{paths, Sha256};
-use serde::Deserialize;
+use filetime::FileTime;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
 
 /// `DirectorySource` contains a number of crates on the file system. It was
 /// designed for representing vendored dependencies for `cargo vendor`.
@@ -27,8 +32,8 @@ use serde::Deserialize;
 ///   directories and never went deeper.
 /// * There must be a [`Checksum`] file `.cargo-checksum.json` file at the same
 ///   level of `Cargo.toml` to ensure the integrity when a directory source was
-///   created (usually by `cargo vendor`). A failure to find or parse a single
-///   checksum results in a denial of loading any package in this source.
+///   created (usually by `cargo vendor`). A failure to find or parse the
+///   checksum of a package results in a denial of loading that package.
 /// * Otherwise, there is no other restrction of the name of directories. At
 ///   this moment, it is `cargo vendor` that defines the layout and the name of
 ///   each directory.
@@ -48,16 +53,27 @@ use serde::Deserialize;
 /// ├── skipped-no-manifest/
 /// │  ├── src/
 /// │  └── .cargo-checksum.json
-/// └── no-checksum-so-fails-the-entire-source-reading/
+/// └── no-checksum-so-fails-loading-this-package/
 ///    └── Cargo.toml
 /// ```
+///
+/// Vendor directories may contain thousands of packages, of which a single
+/// build only needs a few. To avoid parsing every manifest and hashing every
+/// file on each invocation, an [`Index`] of the source is kept in
+/// `$CARGO_HOME/directory-index`. It records the name of the package in each
+/// directory, so that packages are only loaded once the resolver asks for
+/// them, and the checksums of files that were already verified.
 pub struct DirectorySource<'cfg> {
     /// The unique identifier of this source.
     source_id: SourceId,
     /// The root path of this source.
     root: PathBuf,
-    /// Packages that this sources has discovered.
+    /// Packages that this sources has loaded so far.
     packages: HashMap<PackageId, (Package, Checksum)>,
+    /// Directories of packages that haven't been loaded yet, by package name.
+    unloaded: HashMap<InternedString, Vec<PathBuf>>,
+    /// Cached information about the packages of this source.
+    index: RefCell<Index>,
     config: &'cfg Config,
     updated: bool,
 }
@@ -74,6 +90,91 @@ struct Checksum {
     files: HashMap<String, String>,
 }
 
+/// Bumped whenever the format of [`Index`] changes, which discards old
+/// indexes.
+const INDEX_VERSION: u32 = 1;
+
+/// Information about a directory source that is expensive to compute, kept
+/// between invocations.
+///
+/// Everything in here is keyed by the modification time and size of the
+/// files it was derived from, and is ignored once they change.
+#[derive(Default, Serialize, Deserialize)]
+struct Index {
+    version: u32,
+    /// Indexed packages, by the name of their directory.
+    packages: HashMap<String, IndexedPackage>,
+    /// Whether this has changed since it was loaded.
+    #[serde(skip)]
+    dirty: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IndexedPackage {
+    /// The package name from `Cargo.toml`.
+    name: InternedString,
+    /// The stamps of `Cargo.toml` and `.cargo-checksum.json`.
+    stamps: [FileStamp; 2],
+    /// Files whose checksum has been computed, and the checksum.
+    files: HashMap<String, (FileStamp, String)>,
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct FileStamp {
+    mtime: i64,
+    mtime_nanos: u32,
+    size: u64,
+}
+
+impl FileStamp {
+    fn new(path: &Path) -> Option<FileStamp> {
+        let meta = fs::metadata(path).ok()?;
+        let mtime = FileTime::from_last_modification_time(&meta);
+        Some(FileStamp {
+            mtime: mtime.unix_seconds(),
+            mtime_nanos: mtime.nanoseconds(),
+            size: meta.len(),
+        })
+    }
+
+    /// Returns whether any later change to the file is guaranteed to change
+    /// its modification time, which isn't the case for files modified within
+    /// the granularity of the filesystem timestamps.
+    fn is_settled(&self) -> bool {
+        self.mtime + 2 < FileTime::now().unix_seconds()
+    }
+}
+
+fn package_stamps(path: &Path) -> Option<[FileStamp; 2]> {
+    Some([
+        FileStamp::new(&path.join("Cargo.toml"))?,
+        FileStamp::new(&path.join(".cargo-checksum.json"))?,
+    ])
+}
+
+impl Index {
+    /// Loads the index at `path`, which is empty if it doesn't exist or can't
+    /// be read.
+    fn load(path: &Path) -> Index {
+        paths::read(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<Index>(&contents).ok())
+            .filter(|index| index.version == INDEX_VERSION)
+            .unwrap_or_default()
+    }
+
+    fn save(&self, path: &Path) -> CargoResult<()> {
+        let contents = serde_json::to_vec(self)?;
+        paths::create_dir_all(path.parent().unwrap())?;
+        // Concurrent processes may be saving the same index, write to a
+        // temporary file so that readers never see a partial one.
+        let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+        paths::write(&tmp, contents)?;
+        fs::rename(&tmp, path).with_context(|| format!("failed to rename `{}`", tmp.display()))?;
+        Ok(())
+    }
+}
+
 impl<'cfg> DirectorySource<'cfg> {
     pub fn new(path: &Path, id: SourceId, config: &'cfg Config) -> DirectorySource<'cfg> {
         DirectorySource {
@@ -81,9 +182,109 @@ impl<'cfg> DirectorySource<'cfg> {
             root: path.to_path_buf(),
             config,
             packages: HashMap::new(),
+            unloaded: HashMap::new(),
+            index: RefCell::new(Index::default()),
             updated: false,
         }
     }
+
+    /// Returns the packages loaded so far.
+    ///
+    /// Use [`DirectorySource::load_all`] first to get all packages.
+    pub fn packages(&self) -> impl Iterator<Item = &Package> {
+        self.packages.values().map(|p| &p.0)
+    }
+
+    /// Loads all packages of this source which haven't been loaded yet.
+    pub fn load_all(&mut self) -> CargoResult<()> {
+        let unloaded = std::mem::take(&mut self.unloaded);
+        for path in unloaded.into_values().flatten() {
+            self.load(&path)?;
+        }
+        Ok(())
+    }
+
+    /// Loads the packages that may be named `name`.
+    fn load_name(&mut self, name: InternedString) -> CargoResult<()> {
+        for path in self.unloaded.remove(&name).unwrap_or_default() {
+            self.load(&path)?;
+        }
+        Ok(())
+    }
+
+    /// Reads the package in the directory `path`, and adds it to the index.
+    fn load(&mut self, path: &Path) -> CargoResult<()> {
+        let mut src = PathSource::new(path, self.source_id, self.config);
+        src.update()?;
+        let mut pkg = src.root_package()?;
+
+        let cksum_file = path.join(".cargo-checksum.json");
+        let cksum = paths::read(&path.join(cksum_file)).with_context(|| {
+            format!(
+                "failed to load checksum `.cargo-checksum.json` \
+                 of {} v{}",
+                pkg.package_id().name(),
+                pkg.package_id().version()
+            )
+        })?;
+        let cksum: Checksum = serde_json::from_str(&cksum).with_context(|| {
+            format!(
+                "failed to decode `.cargo-checksum.json` of \
+                 {} v{}",
+                pkg.package_id().name(),
+                pkg.package_id().version()
+            )
+        })?;
+
+        if let Some(package) = &cksum.package {
+            pkg.manifest_mut()
+                .summary_mut()
+                .set_checksum(package.clone());
+        }
+
+        let dir = path.file_name().and_then(|s| s.to_str());
+        let stamps = package_stamps(path).filter(|s| s.iter().all(FileStamp::is_settled));
+        if let (Some(dir), Some(stamps)) = (dir, stamps) {
+            let index = self.index.get_mut();
+            let files = match index.packages.remove(dir) {
+                Some(prev) if prev.name == pkg.name() => prev.files,
+                _ => HashMap::new(),
+            };
+            index.packages.insert(
+                dir.to_string(),
+                IndexedPackage {
+                    name: pkg.name(),
+                    stamps,
+                    files,
+                },
+            );
+            index.dirty = true;
+        }
+
+        self.packages.insert(pkg.package_id(), (pkg, cksum));
+        Ok(())
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.config
+            .home()
+            .join("directory-index")
+            .into_path_unlocked()
+            .join(format!("{}.json", short_hash(&self.root)))
+    }
+}
+
+impl<'cfg> Drop for DirectorySource<'cfg> {
+    fn drop(&mut self) {
+        if !self.index.get_mut().dirty {
+            return;
+        }
+        // The index is only a cache, failing to save it isn't fatal.
+        let path = self.index_path();
+        if let Err(e) = self.index.get_mut().save(&path) {
+            debug!("failed to save directory source index: {e:?}");
+        }
+    }
 }
 
 impl<'cfg> Debug for DirectorySource<'cfg> {
@@ -102,6 +303,13 @@ impl<'cfg> Source for DirectorySource<'cfg> {
         if !self.updated {
             return Poll::Pending;
         }
+        let loaded = match kind {
+            QueryKind::Exact => self.load_name(dep.package_name()),
+            QueryKind::Fuzzy => self.load_all(),
+        };
+        if let Err(e) = loaded {
+            return Poll::Ready(Err(e));
+        }
         let packages = self.packages.values().map(|p| &p.0);
         let matches = packages.filter(|pkg| match kind {
             QueryKind::Exact => dep.matches(pkg.summary()),
@@ -130,6 +338,8 @@ impl<'cfg> Source for DirectorySource<'cfg> {
             return Ok(());
         }
         self.packages.clear();
+        self.unloaded.clear();
+        let mut index = Index::load(&self.index_path());
         let entries = self.root.read_dir().with_context(|| {
             format!(
                 "failed to read root of directory source: {}",
@@ -137,6 +347,8 @@ impl<'cfg> Source for DirectorySource<'cfg> {
             )
         })?;
 
+        let mut to_load = Vec::new();
+        let mut indexed = HashMap::new();
         for entry in entries {
             let entry = entry?;
             let path = entry.path();
@@ -169,34 +381,28 @@ impl<'cfg> Source for DirectorySource<'cfg> {
                 continue;
             }
 
-            let mut src = PathSource::new(&path, self.source_id, self.config);
-            src.update()?;
-            let mut pkg = src.root_package()?;
-
-            let cksum_file = path.join(".cargo-checksum.json");
-            let cksum = paths::read(&path.join(cksum_file)).with_context(|| {
-                format!(
-                    "failed to load checksum `.cargo-checksum.json` \
-                     of {} v{}",
-                    pkg.package_id().name(),
-                    pkg.package_id().version()
-                )
-            })?;
-            let cksum: Checksum = serde_json::from_str(&cksum).with_context(|| {
-                format!(
-                    "failed to decode `.cargo-checksum.json` of \
-                     {} v{}",
-                    pkg.package_id().name(),
-                    pkg.package_id().version()
-                )
-            })?;
-
-            if let Some(package) = &cksum.package {
-                pkg.manifest_mut()
-                    .summary_mut()
-                    .set_checksum(package.clone());
+            // Packages that didn't change since they were indexed are only
+            // loaded once they are queried.
+            let dir = path.file_name().and_then(|s| s.to_str());
+            let cached = dir
+                .and_then(|dir| index.packages.remove_entry(dir))
+                .filter(|(_, pkg)| Some(pkg.stamps) == package_stamps(&path));
+            match cached {
+                Some((dir, pkg)) => {
+                    self.unloaded.entry(pkg.name).or_default().push(path);
+                    indexed.insert(dir, pkg);
+                }
+                None => to_load.push(path),
             }
-            self.packages.insert(pkg.package_id(), (pkg, cksum));
+        }
+        // Whatever is left belongs to directories that were removed or changed.
+        index.dirty = !index.packages.is_empty();
+        index.packages = indexed;
+        index.version = INDEX_VERSION;
+        *self.index.get_mut() = index;
+
+        for path in to_load {
+            self.load(&path)?;
         }
 
         self.updated = true;
@@ -204,6 +410,7 @@ impl<'cfg> Source for DirectorySource<'cfg> {
     }
 
     fn download(&mut self, id: PackageId) -> CargoResult<MaybePackage> {
+        self.load_name(id.name())?;
         self.packages
             .get(&id)
             .map(|p| &p.0)
@@ -226,12 +433,35 @@ impl<'cfg> Source for DirectorySource<'cfg> {
             None => anyhow::bail!("failed to find entry for `{}` in directory source", id),
         };
 
-        for (file, cksum) in cksum.files.iter() {
-            let file = pkg.root().join(file);
+        let mut index = self.index.borrow_mut();
+        let index = &mut *index;
+        let mut indexed = pkg
+            .root()
+            .file_name()
+            .and_then(|s| s.to_str())
+            .and_then(|dir| index.packages.get_mut(dir));
+
+        for (name, cksum) in cksum.files.iter() {
+            let file = pkg.root().join(name);
+            // Files that didn't change since they were verified last time
+            // don't need to be hashed again.
+            let stamp = FileStamp::new(&file);
+            if let (Some(stamp), Some(indexed)) = (stamp, &indexed) {
+                let cached = indexed.files.get(name);
+                if cached.map_or(false, |(s, c)| *s == stamp && c == cksum) {
+                    continue;
+                }
+            }
             let actual = Sha256::new()
                 .update_path(&file)
                 .with_context(|| format!("failed to calculate checksum of: {}", file.display()))?
                 .finish_hex();
+            if let (Some(stamp), Some(indexed)) = (stamp, &mut indexed) {
+                if stamp.is_settled() {
+                    indexed.files.insert(name.clone(), (stamp, actual.clone()));
+                    index.dirty = true;
+                }
+            }
             if &*actual != cksum {
                 anyhow::bail!(
                     "the listed checksum of `{}` has changed:\n\
@@ -6,12 +6,12 @@ use crate::util::{human_readable_bytes, network, MetricsCounter, Progress};
 use crate::{CargoResult, Config};
 use cargo_util::paths;
 use gix::bstr::{BString, ByteSlice};
-use log::debug;
 use std::cell::RefCell;
 use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Weak};
 use std::time::{Duration, Instant};
+use tracing::debug;
 
 /// For the time being, `repo_path` makes it easy to instantiate a gitoxide repo just for fetching.
 /// In future this may change to be the gitoxide repository itself.
@@ -27,6 +27,7 @@ pub fn with_retry_and_progress(
           + Send
           + Sync),
 ) -> CargoResult<()> {
+    let cancelled = config.cancellation().flag();
     std::thread::scope(|s| {
         let mut progress_bar = Progress::new("Fetch", config);
         let is_shallow = config
@@ -34,6 +35,7 @@ pub fn with_retry_and_progress(
             .gitoxide
             .map_or(false, |gix| gix.shallow_deps || gix.shallow_index);
         network::retry::with_retry(config, || {
+            let cancelled = &cancelled;
             let progress_root: Arc<gix::progress::tree::Root> =
                 gix::progress::tree::root::Options {
                     initial_capacity: 10,
@@ -44,18 +46,16 @@ pub fn with_retry_and_progress(
             let thread = s.spawn(move || {
                 let mut progress = progress_root.add_child("operation");
                 let mut urls = RefCell::new(Default::default());
-                let res = cb(
-                    &repo_path,
-                    &AtomicBool::default(),
-                    &mut progress,
-                    &mut |url| {
-                        *urls.borrow_mut() = Some(url.to_owned());
-                    },
-                );
+                let res = cb(&repo_path, cancelled, &mut progress, &mut |url| {
+                    *urls.borrow_mut() = Some(url.to_owned());
+                });
                 amend_authentication_hints(res, urls.get_mut().take())
             });
             translate_progress_to_bar(&mut progress_bar, root, is_shallow)?;
-            thread.join().expect("no panic in scoped thread")
+            let result = thread.join().expect("no panic in scoped thread");
+            // The error of a cancelled fetch isn't worth retrying.
+            config.cancellation().check()?;
+            result
         })
     })
 }
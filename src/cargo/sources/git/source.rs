@@ -7,12 +7,12 @@ use crate::sources::git::utils::GitRemote;
 use crate::sources::PathSource;
 use crate::util::errors::CargoResult;
 use crate::util::hex::short_hash;
-use crate::util::Config;
+use crate::util::{Config, Filesystem};
 use anyhow::Context;
 use cargo_util::paths::exclude_from_backups_and_indexing;
-use log::trace;
 use std::fmt::{self, Debug, Formatter};
 use std::task::Poll;
+use tracing::trace;
 use url::Url;
 
 /// `GitSource` contains one or more packages gathering from a Git repository.
@@ -119,6 +119,12 @@ impl<'cfg> GitSource<'cfg> {
         self.remote.url()
     }
 
+    /// Gets the path of the Git database of this source, in Cargo's Git cache
+    /// directory.
+    pub fn db_path(&self) -> Filesystem {
+        self.config.git_path().join("db").join(&self.ident)
+    }
+
     /// Returns the packages discovered by this source. It may fetch the Git
     /// repository as well as walk the filesystem if package informations
     /// haven't yet updated.
@@ -223,7 +229,21 @@ impl<'cfg> Source for GitSource<'cfg> {
 
         let db_path = git_path.join("db").join(&self.ident);
 
-        let db = self.remote.db_at(&db_path).ok();
+        let mut db = self.remote.db_at(&db_path).ok();
+        // The database of the read-only layer of the package cache is used
+        // when it has the locked revision and this one doesn't.
+        if let Some(rev) = self.locked_rev {
+            if !db.as_ref().map_or(false, |db| db.contains(rev)) {
+                if let Some(readonly) = self
+                    .config
+                    .readonly_cache_path(&db_path)
+                    .and_then(|path| self.remote.db_at(&path).ok())
+                    .filter(|db| db.contains(rev))
+                {
+                    db = Some(readonly);
+                }
+            }
+        }
         let (db, actual_rev) = match (self.locked_rev, db) {
             // If we have a locked revision, and we have a preexisting database
             // which has that revision, then no update needs to happen.
@@ -282,6 +302,11 @@ impl<'cfg> Source for GitSource<'cfg> {
             .join("checkouts")
             .join(&self.ident)
             .join(short_id.as_str());
+        // Likewise for a checkout of the read-only layer.
+        let checkout_path = match self.config.readonly_cache_path(&checkout_path) {
+            Some(readonly) if db.existing_checkout(actual_rev, &readonly).is_some() => readonly,
+            _ => checkout_path,
+        };
         db.copy_to(actual_rev, &checkout_path, self.config)?;
 
         let source_id = self.source_id.with_precise(Some(actual_rev.to_string()));
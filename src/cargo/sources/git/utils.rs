@@ -11,7 +11,6 @@ use anyhow::{anyhow, Context as _};
 use cargo_util::{paths, ProcessBuilder};
 use curl::easy::List;
 use git2::{self, ErrorClass, ObjectType, Oid};
-use log::{debug, info};
 use serde::ser;
 use serde::Serialize;
 use std::borrow::Cow;
@@ -21,6 +20,7 @@ use std::process::Command;
 use std::str;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
+use tracing::{debug, info};
 use url::Url;
 
 /// A file indicates that if present, `git reset` has been done and a repo
@@ -184,11 +184,7 @@ impl GitDatabase {
         // A non-fresh checkout can happen if the checkout operation was
         // interrupted. In that case, the checkout gets deleted and a new
         // clone is created.
-        let checkout = match git2::Repository::open(dest)
-            .ok()
-            .map(|repo| GitCheckout::new(self, rev, repo))
-            .filter(|co| co.is_fresh())
-        {
+        let checkout = match self.existing_checkout(rev, dest) {
             Some(co) => co,
             None => GitCheckout::clone_into(dest, self, rev, cargo_config)?,
         };
@@ -196,6 +192,14 @@ impl GitDatabase {
         Ok(checkout)
     }
 
+    /// Opens the checkout of `rev` at `dest`, if it exists and is fresh.
+    pub fn existing_checkout(&self, rev: git2::Oid, dest: &Path) -> Option<GitCheckout<'_>> {
+        git2::Repository::open(dest)
+            .ok()
+            .map(|repo| GitCheckout::new(self, rev, repo))
+            .filter(|co| co.is_fresh())
+    }
+
     /// Get a short OID for a `revision`, usually 7 chars or more if ambiguous.
     pub fn to_short_id(&self, revision: git2::Oid) -> CargoResult<GitShortID> {
         let obj = self.repo.find_object(revision, None)?;
@@ -811,7 +815,7 @@ pub fn with_fetch_options(
     let config_known_hosts = ssh_config.and_then(|ssh| ssh.known_hosts.as_ref());
     let diagnostic_home_config = config.diagnostic_home_config();
     network::retry::with_retry(config, || {
-        with_authentication(config, url, git_config, |f| {
+        let result = with_authentication(config, url, git_config, |f| {
             let port = Url::parse(url).ok().and_then(|url| url.port());
             let mut last_update = Instant::now();
             let mut rcb = git2::RemoteCallbacks::new();
@@ -862,6 +866,7 @@ pub fn with_fetch_options(
                 progress
                     .tick(stats.indexed_objects(), stats.total_objects(), &msg)
                     .is_ok()
+                    && !config.cancellation().is_cancelled()
             });
 
             // Create a local anonymous remote in the repository to fetch the
@@ -869,8 +874,10 @@ pub fn with_fetch_options(
             let mut opts = git2::FetchOptions::new();
             opts.remote_callbacks(rcb);
             cb(opts)
-        })?;
-        Ok(())
+        });
+        // The error of a cancelled fetch isn't worth retrying.
+        config.cancellation().check()?;
+        result
     })
 }
 
@@ -887,6 +894,7 @@ pub fn with_fetch_options(
 /// at this time. It could be extended when libgit2 supports shallow clones.
 ///
 /// [`-Zgitoxide`]: https://doc.rust-lang.org/nightly/cargo/reference/unstable.html#gitoxide
+#[tracing::instrument(skip(repo, config, remote_kind))]
 pub fn fetch(
     repo: &mut git2::Repository,
     remote_url: &str,
@@ -1185,6 +1193,7 @@ fn fetch_with_cli(
     config
         .shell()
         .verbose(|s| s.status("Running", &cmd.to_string()))?;
+    config.cancellation().check()?;
     cmd.exec()?;
     Ok(())
 }
@@ -1277,7 +1286,7 @@ fn clean_repo_temp_files(repo: &git2::Repository) {
     let pattern = match path.to_str() {
         Some(p) => p,
         None => {
-            log::warn!("cannot convert {path:?} to a string");
+            tracing::warn!("cannot convert {path:?} to a string");
             return;
         }
     };
@@ -1288,8 +1297,10 @@ fn clean_repo_temp_files(repo: &git2::Repository) {
     for path in paths {
         if let Ok(path) = path {
             match paths::remove_file(&path) {
-                Ok(_) => log::debug!("removed stale temp git file {path:?}"),
-                Err(e) => log::warn!("failed to remove {path:?} while cleaning temp files: {e}"),
+                Ok(_) => tracing::debug!("removed stale temp git file {path:?}"),
+                Err(e) => {
+                    tracing::warn!("failed to remove {path:?} while cleaning temp files: {e}")
+                }
             }
         }
     }
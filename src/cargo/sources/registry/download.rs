@@ -8,7 +8,8 @@ use cargo_credential::Operation;
 use cargo_util::registry::make_dep_path;
 use cargo_util::Sha256;
 
-use crate::core::PackageId;
+use crate::core::{PackageId, PrebuiltBinary};
+use crate::sources::registry::index::PrebuiltBins;
 use crate::sources::registry::MaybeLock;
 use crate::sources::registry::RegistryConfig;
 use crate::util::auth;
@@ -25,6 +26,8 @@ const VERSION_TEMPLATE: &str = "{version}";
 const PREFIX_TEMPLATE: &str = "{prefix}";
 const LOWER_PREFIX_TEMPLATE: &str = "{lowerprefix}";
 const CHECKSUM_TEMPLATE: &str = "{sha256-checksum}";
+const TARGET_TEMPLATE: &str = "{target}";
+const BIN_TEMPLATE: &str = "{bin}";
 
 /// Checks if `pkg` is downloaded and ready under the directory at `cache_path`.
 /// If not, returns a URL to download it from.
@@ -52,6 +55,14 @@ pub(super) fn download(
             return Ok(MaybeLock::Ready(dst));
         }
     }
+    // The read-only layer of the package cache may have it otherwise.
+    if let Some(readonly) = config.readonly_cache_path(path) {
+        if let Ok(dst) = File::open(readonly) {
+            if dst.metadata()?.len() > 0 {
+                return Ok(MaybeLock::Ready(dst));
+            }
+        }
+    }
 
     let mut url = registry_config.dl;
     if !url.contains(CRATE_TEMPLATE)
@@ -97,6 +108,54 @@ pub(super) fn download(
     })
 }
 
+/// Lists the prebuilt binaries of `pkg` from the checksums of its index
+/// entry, with their URLs made from the `bin-dl` template of the registry.
+///
+/// This is primarily called by [`Source::prebuilt_binaries`](crate::core::Source::prebuilt_binaries).
+pub(super) fn prebuilt_binaries(
+    config: &Config,
+    pkg: PackageId,
+    bins: &PrebuiltBins,
+    registry_config: RegistryConfig,
+) -> CargoResult<Vec<PrebuiltBinary>> {
+    let Some(template) = registry_config.bin_dl else {
+        return Ok(Vec::new());
+    };
+    let authorization = if registry_config.auth_required && !bins.is_empty() {
+        Some(auth::auth_token(
+            config,
+            &pkg.source_id(),
+            None,
+            Operation::Read,
+            vec![],
+        )?)
+    } else {
+        None
+    };
+    let prefix = make_dep_path(&pkg.name(), true);
+    let mut binaries = Vec::new();
+    for (target, checksums) in bins {
+        for (name, checksum) in checksums {
+            let url = template
+                .replace(CRATE_TEMPLATE, &*pkg.name())
+                .replace(VERSION_TEMPLATE, &pkg.version().to_string())
+                .replace(PREFIX_TEMPLATE, &prefix)
+                .replace(LOWER_PREFIX_TEMPLATE, &prefix.to_lowercase())
+                .replace(CHECKSUM_TEMPLATE, checksum)
+                .replace(TARGET_TEMPLATE, target)
+                .replace(BIN_TEMPLATE, name);
+            binaries.push(PrebuiltBinary {
+                target: target.clone(),
+                name: name.clone(),
+                url,
+                checksum: checksum.clone(),
+                authorization: authorization.clone(),
+            });
+        }
+    }
+    Ok(binaries)
+}
+
 /// Verifies the integrity of `data` with `checksum` and persists it under the
 /// directory at `cache_path`.
 ///
@@ -145,7 +204,13 @@ pub(super) fn is_crate_downloaded(
     let path = cache_path.join(pkg.tarball_name());
     let path = config.assert_package_cache_locked(&path);
     if let Ok(meta) = fs::metadata(path) {
-        return meta.len() > 0;
+        if meta.len() > 0 {
+            return true;
+        }
     }
-    false
+    // The read-only layer of the package cache may have it otherwise.
+    config
+        .readonly_cache_path(path)
+        .and_then(|readonly| fs::metadata(readonly).ok())
+        .map_or(false, |meta| meta.len() > 0)
 }
@@ -5,7 +5,8 @@ use crate::sources::registry::download;
 use crate::sources::registry::MaybeLock;
 use crate::sources::registry::{LoadResponse, RegistryConfig, RegistryData};
 use crate::util::errors::{CargoResult, HttpNotSuccessful};
-use crate::util::network::http::http_handle;
+use crate::util::network::http::{http_handle, RegistryHttpConfig};
+use crate::util::network::report::http_version;
 use crate::util::network::retry::{Retry, RetryResult};
 use crate::util::network::sleep::SleepTracker;
 use crate::util::{auth, Config, Filesystem, IntoUrl, Progress, ProgressStyle};
@@ -14,7 +15,6 @@ use cargo_credential::Operation;
 use cargo_util::paths;
 use curl::easy::{Easy, List};
 use curl::multi::{EasyHandle, Multi};
-use log::{debug, trace};
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 use std::fs::{self, File};
@@ -23,6 +23,7 @@ use std::path::{Path, PathBuf};
 use std::str;
 use std::task::{ready, Poll};
 use std::time::Duration;
+use tracing::{debug, trace};
 use url::Url;
 
 // HTTP headers
@@ -102,6 +103,9 @@ pub struct HttpRegistry<'cfg> {
 
     /// Disables status messages.
     quiet: bool,
+
+    /// The HTTP settings of this registry from its `[registries.<name>]` table.
+    registry_http: RegistryHttpConfig,
 }
 
 /// State for currently pending index file downloads.
@@ -153,6 +157,8 @@ struct Headers {
     last_modified: Option<String>,
     etag: Option<String>,
     www_authenticate: Vec<String>,
+    /// The HTTP version of the status line of the last response.
+    http_version: Option<String>,
     /// All headers, including explicit headers above.
     all: Vec<String>,
 }
@@ -227,6 +233,7 @@ impl<'cfg> HttpRegistry<'cfg> {
             login_url: None,
             auth_error_headers: vec![],
             quiet: false,
+            registry_http: RegistryHttpConfig::new(config, source_id)?,
         })
     }
 
@@ -307,7 +314,8 @@ impl<'cfg> HttpRegistry<'cfg> {
             let mut handle = self.multi.remove(handle)?;
             let data = download.data.take();
             let url = self.full_url(&download.path);
-            let result = match download.retry.r#try(|| {
+            let http_version = download.header_map.borrow_mut().http_version.take();
+            let ret = download.retry.r#try(|| {
                 result.with_context(|| format!("failed to download from `{}`", url))?;
                 let code = handle.response_code()?;
                 // Keep this list of expected status codes in sync with the codes handled in `load`
@@ -327,7 +335,12 @@ impl<'cfg> HttpRegistry<'cfg> {
                     }
                 };
                 Ok((data, code))
-            }) {
+            });
+            if let Some(mut report) = self.config.network_report() {
+                let retried = matches!(ret, RetryResult::Retry(_));
+                report.record(&mut handle, &url, http_version, retried);
+            }
+            let result = match ret {
                 RetryResult::Success((data, code)) => Ok(CompletedDownload {
                     response_code: code,
                     data,
@@ -394,11 +407,11 @@ impl<'cfg> HttpRegistry<'cfg> {
                 Ok(json) => {
                     self.registry_config = Some(json);
                 }
-                Err(e) => log::debug!("failed to decode cached config.json: {}", e),
+                Err(e) => tracing::debug!("failed to decode cached config.json: {}", e),
             },
             Err(e) => {
                 if e.kind() != ErrorKind::NotFound {
-                    log::debug!("failed to read config.json cache: {}", e)
+                    tracing::debug!("failed to read config.json cache: {}", e)
                 }
             }
         }
@@ -423,7 +436,7 @@ impl<'cfg> HttpRegistry<'cfg> {
                 self.registry_config = Some(serde_json::from_slice(&raw_data)?);
                 if paths::create_dir_all(&config_json_path.parent().unwrap()).is_ok() {
                     if let Err(e) = fs::write(&config_json_path, &raw_data) {
-                        log::debug!("failed to write config.json cache: {}", e);
+                        tracing::debug!("failed to write config.json cache: {}", e);
                     }
                 }
                 Poll::Ready(Ok(self.registry_config.as_ref().unwrap()))
@@ -617,6 +630,7 @@ impl<'cfg> RegistryData for HttpRegistry<'cfg> {
         self.start_fetch()?;
 
         let mut handle = http_handle(self.config)?;
+        self.registry_http.configure(self.config, &mut handle)?;
         let full_url = self.full_url(path);
         debug!("fetch {}", full_url);
         handle.get(true)?;
@@ -686,6 +700,14 @@ impl<'cfg> RegistryData for HttpRegistry<'cfg> {
 
         // And ditto for the header function.
         handle.header_function(move |buf| {
+            if let Some(version) = http_version(buf) {
+                tls::with(|downloads| {
+                    if let Some(downloads) = downloads {
+                        let mut header_map = downloads.pending[&token].0.header_map.borrow_mut();
+                        header_map.http_version = Some(version);
+                    }
+                });
+            }
             if let Some((tag, value)) = Self::handle_http_header(buf) {
                 tls::with(|downloads| {
                     if let Some(downloads) = downloads {
@@ -771,6 +793,7 @@ impl<'cfg> RegistryData for HttpRegistry<'cfg> {
         download::is_crate_downloaded(&self.cache_path, &self.config, pkg)
     }
 
+    #[tracing::instrument(skip_all)]
     fn block_until_ready(&mut self) -> CargoResult<()> {
         trace!(
             "block_until_ready: {} transfers pending",
@@ -779,6 +802,7 @@ impl<'cfg> RegistryData for HttpRegistry<'cfg> {
         self.downloads.blocking_calls += 1;
 
         loop {
+            self.config.cancellation().check()?;
             self.handle_completed_downloads()?;
             self.add_sleepers()?;
 
@@ -94,7 +94,6 @@ use crate::util::IntoUrl;
 use crate::util::{internal, CargoResult, Config, Filesystem, OptVersionReq, ToSemver};
 use anyhow::bail;
 use cargo_util::{paths, registry::make_dep_path};
-use log::{debug, info};
 use semver::Version;
 use serde::Deserialize;
 use std::borrow::Cow;
@@ -105,6 +104,7 @@ use std::io::ErrorKind;
 use std::path::Path;
 use std::str;
 use std::task::{ready, Poll};
+use tracing::{debug, info};
 
 /// The current version of [`SummariesCache`].
 const CURRENT_CACHE_VERSION: u8 = 3;
@@ -194,8 +194,18 @@ pub struct IndexSummary {
     pub yanked: bool,
     /// Schema version, see [`IndexPackage::v`].
     v: u32,
+    /// Signature of the package, see [`IndexPackage::sig`].
+    sig: Option<String>,
+    /// Prebuilt binaries of the package, see [`IndexPackage::bins`].
+    bins: Option<PrebuiltBins>,
+    /// Signature of the prebuilt binaries, see [`IndexPackage::bins_sig`].
+    bins_sig: Option<String>,
 }
 
+/// The checksums of the prebuilt binaries of a package, by binary name, by
+/// target.
+pub type PrebuiltBins = BTreeMap<String, BTreeMap<String, String>>;
+
 /// A representation of the cache on disk that Cargo maintains of summaries.
 ///
 /// Cargo will initially parse all summaries in the registry and will then
@@ -306,6 +316,31 @@ pub struct IndexPackage<'a> {
     /// Added in 2023 (see <https://github.com/rust-lang/crates.io/pull/6267>),
     /// can be `None` if published before then or if not set in the manifest.
     rust_version: Option<InternedString>,
+    /// Detached signature of the `.crate` file, a PASETO `v3.public` token
+    /// whose payload holds the `name`, `vers` and `cksum` of the package.
+    ///
+    /// Only checked with `-Zcrate-signatures` when the registry has a
+    /// signature key, see [`RegistryConfig::signature_key`].
+    ///
+    /// [`RegistryConfig::signature_key`]: super::RegistryConfig::signature_key
+    sig: Option<String>,
+    /// The SHA-256 checksums of the binaries the registry built ahead of
+    /// time, by file name, by target triple. They are downloaded from the
+    /// `bin-dl` URL of the registry.
+    ///
+    /// Only used by `cargo install --from-binary-registry`, see
+    /// [`PrebuiltBinary`](crate::core::PrebuiltBinary).
+    bins: Option<PrebuiltBins>,
+    /// Detached signature of the prebuilt binaries, a PASETO `v3.public`
+    /// token whose payload holds the `name`, `vers` and `bins` of the
+    /// package.
+    ///
+    /// Prebuilt binaries are only installed when the registry has a
+    /// signature key and this signature is valid, see
+    /// [`RegistryConfig::signature_key`].
+    ///
+    /// [`RegistryConfig::signature_key`]: super::RegistryConfig::signature_key
+    bins_sig: Option<String>,
     /// The schema version for this entry.
     ///
     /// If this is None, it defaults to version `1`. Entries with unknown
@@ -387,6 +422,58 @@ impl<'cfg> RegistryIndex<'cfg> {
             .ok_or_else(|| internal(format!("no hash listed for {}", pkg)))?))
     }
 
+    /// Returns the line of the index describing `pkg` in the on-disk cache of
+    /// the index, which is written when the summaries of `pkg` are loaded.
+    pub fn cached_line(&self, pkg: PackageId) -> CargoResult<Option<Vec<u8>>> {
+        let root = self.config.assert_package_cache_locked(&self.path);
+        let path = make_dep_path(&pkg.name().to_lowercase(), false);
+        let contents = match fs::read(root.join(".cache").join(path)) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let cache = SummariesCache::parse(&contents)?;
+        Ok(cache
+            .versions
+            .into_iter()
+            .find(|(version, _)| version == pkg.version())
+            .map(|(_, line)| line.to_vec()))
+    }
+
+    /// Returns the signature listed for a specified `PackageId`, if any.
+    /// Primarily for verifying the checksum returned by [`Self::hash`] was
+    /// published by the owner of the registry's signature key.
+    pub fn signature(
+        &mut self,
+        pkg: PackageId,
+        load: &mut dyn RegistryData,
+    ) -> Poll<CargoResult<Option<&str>>> {
+        let req = OptVersionReq::exact(pkg.version());
+        let summary = self.summaries(&pkg.name(), &req, load)?;
+        let summary = ready!(summary).next();
+        Poll::Ready(Ok(summary
+            .ok_or_else(|| internal(format!("no hash listed for {}", pkg)))?
+            .sig
+            .as_deref()))
+    }
+
+    /// Returns the checksums of the prebuilt binaries listed for a specified
+    /// `PackageId`, if any, along with their signature.
+    pub fn prebuilt_bins(
+        &mut self,
+        pkg: PackageId,
+        load: &mut dyn RegistryData,
+    ) -> Poll<CargoResult<Option<(&PrebuiltBins, Option<&str>)>>> {
+        let req = OptVersionReq::exact(pkg.version());
+        let summary = self.summaries(&pkg.name(), &req, load)?;
+        let summary = ready!(summary).next();
+        let summary = summary.ok_or_else(|| internal(format!("no hash listed for {}", pkg)))?;
+        Poll::Ready(Ok(summary
+            .bins
+            .as_ref()
+            .map(|bins| (bins, summary.bins_sig.as_deref()))))
+    }
+
     /// Load a list of summaries for `name` package in this registry which
     /// match `req`.
     ///
@@ -511,6 +598,13 @@ impl<'cfg> RegistryIndex<'cfg> {
         yanked_whitelist: &HashSet<PackageId>,
         f: &mut dyn FnMut(Summary),
     ) -> Poll<CargoResult<()>> {
+        if self.config.cli_unstable().use_cached {
+            // Only ever consider what is on disk, even if that means there
+            // are no matches at all.
+            return self
+                .query_inner_with_online(name, req, load, yanked_whitelist, f, false)
+                .map_ok(|_| ());
+        }
         if self.config.offline() {
             // This should only return `Poll::Ready(Ok(()))` if there is at least 1 match.
             //
@@ -673,23 +767,23 @@ impl Summaries {
                     index_version = Some(v);
                 }
                 Err(e) => {
-                    log::debug!("failed to parse {:?} cache: {}", relative, e);
+                    tracing::debug!("failed to parse {:?} cache: {}", relative, e);
                 }
             },
-            Err(e) => log::debug!("cache missing for {:?} error: {}", relative, e),
+            Err(e) => tracing::debug!("cache missing for {:?} error: {}", relative, e),
         }
 
         let response = ready!(load.load(root, relative, index_version.as_deref())?);
 
         match response {
             LoadResponse::CacheValid => {
-                log::debug!("fast path for registry cache of {:?}", relative);
+                tracing::debug!("fast path for registry cache of {:?}", relative);
                 return Poll::Ready(Ok(cached_summaries));
             }
             LoadResponse::NotFound => {
                 if let Err(e) = fs::remove_file(cache_path) {
                     if e.kind() != ErrorKind::NotFound {
-                        log::debug!("failed to remove from cache: {}", e);
+                        tracing::debug!("failed to remove from cache: {}", e);
                     }
                 }
                 return Poll::Ready(Ok(None));
@@ -701,7 +795,7 @@ impl Summaries {
                 // This is the fallback path where we actually talk to the registry backend to load
                 // information. Here we parse every single line in the index (as we need
                 // to find the versions)
-                log::debug!("slow path for {:?}", relative);
+                tracing::debug!("slow path for {:?}", relative);
                 let mut cache = SummariesCache::default();
                 let mut ret = Summaries::default();
                 ret.raw_data = raw_data;
@@ -722,7 +816,11 @@ impl Summaries {
                             // entries in the cache preventing those newer
                             // versions from reading them (that is, until the
                             // cache is rebuilt).
-                            log::info!("failed to parse {:?} registry package: {}", relative, e);
+                            tracing::info!(
+                                "failed to parse {:?} registry package: {}",
+                                relative,
+                                e
+                            );
                             continue;
                         }
                     };
@@ -731,7 +829,7 @@ impl Summaries {
                     ret.versions.insert(version, summary.into());
                 }
                 if let Some(index_version) = index_version {
-                    log::trace!("caching index_version {}", index_version);
+                    tracing::trace!("caching index_version {}", index_version);
                     let cache_bytes = cache.serialize(index_version.as_str());
                     // Once we have our `cache_bytes` which represents the `Summaries` we're
                     // about to return, write that back out to disk so future Cargo
@@ -743,7 +841,7 @@ impl Summaries {
                         let path = Filesystem::new(cache_path.clone());
                         config.assert_package_cache_locked(&path);
                         if let Err(e) = fs::write(cache_path, &cache_bytes) {
-                            log::info!("failed to write cache: {}", e);
+                            tracing::info!("failed to write cache: {}", e);
                         }
                     }
 
@@ -903,10 +1001,13 @@ impl IndexSummary {
             yanked,
             links,
             rust_version,
+            sig,
+            bins,
+            bins_sig,
             v,
         } = serde_json::from_slice(line)?;
         let v = v.unwrap_or(1);
-        log::trace!("json parsed registry {}/{}", name, vers);
+        tracing::trace!("json parsed registry {}/{}", name, vers);
         let pkgid = PackageId::new(name, &vers, source_id)?;
         let deps = deps
             .into_iter()
@@ -923,6 +1024,9 @@ impl IndexSummary {
             summary,
             yanked: yanked.unwrap_or(false),
             v,
+            sig,
+            bins,
+            bins_sig,
         })
     }
 }
@@ -192,13 +192,14 @@ use std::task::{ready, Poll};
 use anyhow::Context as _;
 use cargo_util::paths::{self, exclude_from_backups_and_indexing};
 use flate2::read::GzDecoder;
-use log::debug;
 use serde::Deserialize;
 use tar::Archive;
+use tracing::debug;
 
 use crate::core::dependency::Dependency;
 use crate::core::source::MaybePackage;
-use crate::core::{Package, PackageId, QueryKind, Source, SourceId, Summary};
+use crate::core::{Package, PackageId, PrebuiltBinary, QueryKind, Source, SourceId, Summary};
+use crate::message;
 use crate::sources::PathSource;
 use crate::util::hex;
 use crate::util::network::PollExt;
@@ -292,6 +293,38 @@ pub struct RegistryConfig {
     /// [RFC 3139]: https://rust-lang.github.io/rfcs/3139-cargo-alternative-registry-auth.html
     #[serde(default)]
     pub auth_required: bool,
+
+    /// PASERK `k3.public` key the index entries of this registry are signed
+    /// with. Only used with `-Zcrate-signatures`, see the [`signature`] module.
+    pub signature_key: Option<String>,
+
+    /// Endpoints to obtain a token with `cargo login` in the browser. Only
+    /// used with `-Zdevice-login`.
+    pub device_authorization: Option<DeviceAuthorization>,
+
+    /// Download endpoint for the prebuilt binaries listed in the `bins` field
+    /// of the index entries. Only used by `cargo install
+    /// --from-binary-registry`.
+    ///
+    /// Like [`RegistryConfig::dl`], this is a template: the markers of `dl`
+    /// are replaced, along with `{target}` and `{bin}`, by the target triple
+    /// and the file name of the binary.
+    pub bin_dl: Option<String>,
+}
+
+/// The `device-authorization` field of [`RegistryConfig`], advertising the
+/// [OAuth 2.0 device authorization grant] for `cargo login`.
+///
+/// [OAuth 2.0 device authorization grant]: https://www.rfc-editor.org/rfc/rfc8628
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct DeviceAuthorization {
+    /// The device authorization endpoint, which hands out the codes.
+    pub url: String,
+    /// The token endpoint, which is polled until the user approved the login.
+    pub token_url: String,
+    /// The client identifier of Cargo at the registry.
+    pub client_id: String,
 }
 
 /// Result from loading data from a registry.
@@ -423,6 +456,7 @@ mod http_remote;
 mod index;
 mod local;
 mod remote;
+mod signature;
 
 /// Generates a unique name for [`SourceId`] to have a unique path to put their
 /// index files.
@@ -456,10 +490,18 @@ impl<'cfg> RegistrySource<'cfg> {
                 .map_or(false, |gix| gix.fetch && gix.shallow_index)
                 && !source_id.is_sparse(),
         );
+        let snapshot = remote::RemoteRegistry::snapshot(config, source_id)?;
         let ops = if source_id.is_sparse() {
+            if snapshot.is_some() {
+                anyhow::bail!(
+                    "snapshots of the index of {source_id} aren't supported, \
+                     as it uses the sparse protocol"
+                );
+            }
             Box::new(http_remote::HttpRegistry::new(source_id, config, &name)?) as Box<_>
         } else {
-            Box::new(remote::RemoteRegistry::new(source_id, config, &name)) as Box<_>
+            let registry = remote::RemoteRegistry::new(source_id, config, &name);
+            Box::new(registry.with_snapshot(snapshot)) as Box<_>
         };
 
         Ok(RegistrySource::new(
@@ -516,6 +558,66 @@ impl<'cfg> RegistrySource<'cfg> {
         self.ops.config()
     }
 
+    /// Returns the `.crate` file of `package`, which has to be downloaded
+    /// already, see [`Source::download`].
+    pub fn downloaded_crate(&mut self, package: PackageId) -> CargoResult<File> {
+        let hash = loop {
+            match self.index.hash(package, &mut *self.ops)? {
+                Poll::Pending => self.block_until_ready()?,
+                Poll::Ready(hash) => break hash.to_string(),
+            }
+        };
+        match self.ops.download(package, &hash)? {
+            MaybeLock::Ready(file) => Ok(file),
+            MaybeLock::Download { .. } => {
+                anyhow::bail!("package `{}` isn't downloaded yet", package)
+            }
+        }
+    }
+
+    /// Returns the line of the index describing `package`, as it was received
+    /// from the registry.
+    pub fn index_line(&mut self, package: PackageId) -> CargoResult<Vec<u8>> {
+        // Loading the summaries writes the lines to the on-disk cache of the
+        // index, if they weren't already.
+        loop {
+            match self.index.hash(package, &mut *self.ops)? {
+                Poll::Pending => self.block_until_ready()?,
+                Poll::Ready(_) => break,
+            }
+        }
+        self.index
+            .cached_line(package)?
+            .ok_or_else(|| anyhow::format_err!("no index entry cached for `{}`", package))
+    }
+
+    /// Verifies the signature of the checksum of `package` if the registry
+    /// has a signature key, see the [`signature`] module.
+    fn verify_signature(&mut self, package: PackageId) -> CargoResult<()> {
+        let advertised = loop {
+            match self.ops.config()? {
+                Poll::Pending => self.block_until_ready()?,
+                Poll::Ready(cfg) => break cfg.and_then(|cfg| cfg.signature_key),
+            }
+        };
+        let Some(key) = signature::signature_key(self.config, package, advertised)? else {
+            return Ok(());
+        };
+        let hash = loop {
+            match self.index.hash(package, &mut *self.ops)? {
+                Poll::Pending => self.block_until_ready()?,
+                Poll::Ready(hash) => break hash.to_string(),
+            }
+        };
+        let sig = loop {
+            match self.index.signature(package, &mut *self.ops)? {
+                Poll::Pending => self.block_until_ready()?,
+                Poll::Ready(sig) => break sig,
+            }
+        };
+        signature::verify(package, &hash, sig, &key)
+    }
+
     /// Unpacks a downloaded package into a location where it's ready to be
     /// compiled.
     ///
@@ -565,11 +667,18 @@ impl<'cfg> RegistrySource<'cfg> {
         let path = dst.join(PACKAGE_SOURCE_LOCK);
         let path = self.config.assert_package_cache_locked(&path);
         let unpack_dir = path.parent().unwrap();
+        // The read-only layer of the package cache may have it unpacked
+        // already.
+        if let Some(readonly) = self.config.readonly_cache_path(path) {
+            if readonly.metadata().map_or(false, |meta| meta.len() > 0) {
+                return Ok(readonly.parent().unwrap().to_path_buf());
+            }
+        }
         match path.metadata() {
             Ok(meta) if meta.len() > 0 => return Ok(unpack_dir.to_path_buf()),
             Ok(_meta) => {
                 // See comment of `unpack_package` about why removing all stuff.
-                log::warn!("unexpected length of {path:?}, clearing cache");
+                tracing::warn!("unexpected length of {path:?}, clearing cache");
                 paths::remove_dir_all(dst.as_path_unlocked())?;
             }
             Err(e) if e.kind() == io::ErrorKind::NotFound => {}
@@ -796,7 +905,14 @@ impl<'cfg> Source for RegistrySource<'cfg> {
             }
         };
         match self.ops.download(package, hash)? {
-            MaybeLock::Ready(file) => self.get_pkg(package, &file).map(MaybePackage::Ready),
+            MaybeLock::Ready(file) => {
+                // The file may have been cached before signatures were
+                // checked, or by another Cargo in a cache overlay.
+                if self.config.cli_unstable().crate_signatures {
+                    self.verify_signature(package)?;
+                }
+                self.get_pkg(package, &file).map(MaybePackage::Ready)
+            }
             MaybeLock::Download {
                 url,
                 descriptor,
@@ -810,6 +926,9 @@ impl<'cfg> Source for RegistrySource<'cfg> {
     }
 
     fn finish_download(&mut self, package: PackageId, data: Vec<u8>) -> CargoResult<Package> {
+        if self.config.cli_unstable().crate_signatures {
+            self.verify_signature(package)?;
+        }
         let hash = loop {
             match self.index.hash(package, &mut *self.ops)? {
                 Poll::Pending => self.block_until_ready()?,
@@ -824,6 +943,10 @@ impl<'cfg> Source for RegistrySource<'cfg> {
         Ok(pkg.package_id().version().to_string())
     }
 
+    fn is_downloaded(&self, pkg: PackageId) -> bool {
+        self.ops.is_crate_downloaded(pkg)
+    }
+
     fn describe(&self) -> String {
         self.source_id.display_index()
     }
@@ -836,6 +959,35 @@ impl<'cfg> Source for RegistrySource<'cfg> {
         self.index.is_yanked(pkg, &mut *self.ops)
     }
 
+    fn prebuilt_binaries(&mut self, pkg: PackageId) -> Poll<CargoResult<Vec<PrebuiltBinary>>> {
+        let Some(registry_config) = ready!(self.ops.config()?) else {
+            return Poll::Ready(Ok(Vec::new()));
+        };
+        let Some((bins, sig)) = ready!(self.index.prebuilt_bins(pkg, &mut *self.ops)?) else {
+            return Poll::Ready(Ok(Vec::new()));
+        };
+        let (bins, sig) = (bins.clone(), sig.map(str::to_string));
+        let advertised = registry_config.signature_key.clone();
+        let Some(key) = signature::signature_key(self.config, pkg, advertised)? else {
+            let msg = message!(
+                self.config,
+                "not using the prebuilt binaries of `{pkg}`, \
+                 {source} has no signature key to verify them",
+                pkg = pkg,
+                source = pkg.source_id(),
+            );
+            self.config.shell().note(msg)?;
+            return Poll::Ready(Ok(Vec::new()));
+        };
+        signature::verify_bins(pkg, &bins, sig.as_deref(), &key)?;
+        Poll::Ready(download::prebuilt_binaries(
+            self.config,
+            pkg,
+            &bins,
+            registry_config,
+        ))
+    }
+
     fn block_until_ready(&mut self) -> CargoResult<()> {
         // Before starting to work on the registry, make sure that
         // `<cargo_home>/registry` is marked as excluded from indexing and
@@ -12,13 +12,13 @@ use crate::util::{Config, Filesystem};
 use anyhow::Context as _;
 use cargo_util::paths;
 use lazycell::LazyCell;
-use log::{debug, trace};
 use std::cell::{Cell, Ref, RefCell};
 use std::fs::File;
 use std::mem;
 use std::path::Path;
 use std::str;
 use std::task::{ready, Poll};
+use tracing::{debug, trace};
 
 /// A remote registry is a registry that lives at a remote URL (such as
 /// crates.io). The git index is cloned locally, and `.crate` files are
@@ -77,6 +77,9 @@ pub struct RemoteRegistry<'cfg> {
     needs_update: bool,
     /// Disables status messages.
     quiet: bool,
+    /// The revision or date the index is pinned to with
+    /// `registries.<name>.snapshot`, see [`RemoteRegistry::snapshot`].
+    snapshot: Option<String>,
 }
 
 impl<'cfg> RemoteRegistry<'cfg> {
@@ -97,9 +100,26 @@ impl<'cfg> RemoteRegistry<'cfg> {
             current_sha: Cell::new(None),
             needs_update: false,
             quiet: false,
+            snapshot: None,
         }
     }
 
+    /// Pins the index to `snapshot`, either a commit hash or a `YYYY-MM-DD`
+    /// date, which picks the last commit made before that day (in UTC).
+    pub fn with_snapshot(mut self, snapshot: Option<String>) -> Self {
+        self.snapshot = snapshot;
+        self
+    }
+
+    /// Gets the snapshot of the index of `source_id` from the configuration,
+    /// if `-Zindex-snapshots` is enabled.
+    pub fn snapshot(config: &Config, source_id: SourceId) -> CargoResult<Option<String>> {
+        if !config.cli_unstable().index_snapshots {
+            return Ok(None);
+        }
+        Ok(config.registry_settings(&source_id)?.snapshot)
+    }
+
     /// Creates intermediate dirs and initialize the repository.
     fn repo(&self) -> CargoResult<&git2::Repository> {
         self.repo.try_borrow_with(|| {
@@ -141,12 +161,68 @@ impl<'cfg> RemoteRegistry<'cfg> {
     fn head(&self) -> CargoResult<git2::Oid> {
         if self.head.get().is_none() {
             let repo = self.repo()?;
-            let oid = self.index_git_ref.resolve(repo)?;
+            let mut oid = self.index_git_ref.resolve(repo)?;
+            if let Some(snapshot) = &self.snapshot {
+                oid = self.resolve_snapshot(repo, oid, snapshot)?;
+            }
             self.head.set(Some(oid));
         }
         Ok(self.head.get().unwrap())
     }
 
+    /// Finds the commit `snapshot` refers to in the history of `head`.
+    ///
+    /// Errors about commits that might only be missing because the index
+    /// hasn't been fetched yet are plain [`git2::Error`]s, so that
+    /// [`RegistryData::load`] updates the index and tries again.
+    fn resolve_snapshot(
+        &self,
+        repo: &git2::Repository,
+        head: git2::Oid,
+        snapshot: &str,
+    ) -> CargoResult<git2::Oid> {
+        let Some(cutoff) = parse_snapshot_date(snapshot)? else {
+            let commit = repo
+                .revparse_single(snapshot)
+                .and_then(|obj| obj.peel_to_commit());
+            return match commit {
+                Ok(commit) => Ok(commit.id()),
+                Err(e) if !self.is_updated() => Err(e.into()),
+                Err(_) => anyhow::bail!(
+                    "failed to find the snapshot `{snapshot}` in the index of {}",
+                    self.source_id
+                ),
+            };
+        };
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as i64);
+        if cutoff > now {
+            anyhow::bail!(
+                "the snapshot `{snapshot}` of the index of {} is in the future",
+                self.source_id
+            );
+        }
+        // Commits made after the last fetch might still be missing.
+        if repo.find_commit(head)?.time().seconds() < cutoff && !self.is_updated() {
+            return Err(git2::Error::from_str("the index is older than the snapshot").into());
+        }
+        let mut walk = repo.revwalk()?;
+        walk.simplify_first_parent()?;
+        walk.push(head)?;
+        for oid in walk {
+            let oid = oid?;
+            if repo.find_commit(oid)?.time().seconds() < cutoff {
+                debug!("resolved snapshot `{snapshot}` to {oid}");
+                return Ok(oid);
+            }
+        }
+        anyhow::bail!(
+            "the index of {} has no commit made before the snapshot `{snapshot}`",
+            self.source_id
+        )
+    }
+
     /// Returns a [`git2::Tree`] object of the current HEAD commit of the
     /// underlying Git repository.
     fn tree(&self) -> CargoResult<Ref<'_, git2::Tree<'_>>> {
@@ -317,6 +393,7 @@ impl<'cfg> RegistryData for RemoteRegistry<'cfg> {
         }
     }
 
+    #[tracing::instrument(skip_all)]
     fn block_until_ready(&mut self) -> CargoResult<()> {
         if !self.needs_update {
             return Ok(());
@@ -420,6 +497,26 @@ impl<'cfg> RegistryData for RemoteRegistry<'cfg> {
     }
 }
 
+/// Parses a snapshot of the form `YYYY-MM-DD` into the Unix timestamp of the
+/// start of that day in UTC, or returns `None` if it isn't a date.
+fn parse_snapshot_date(snapshot: &str) -> CargoResult<Option<i64>> {
+    let is_date = snapshot.len() == 10
+        && snapshot.bytes().enumerate().all(|(i, b)| {
+            if i == 4 || i == 7 {
+                b == b'-'
+            } else {
+                b.is_ascii_digit()
+            }
+        });
+    if !is_date {
+        return Ok(None);
+    }
+    let format = time::format_description::parse("[year]-[month]-[day]").unwrap();
+    let date = time::Date::parse(snapshot, &format)
+        .with_context(|| format!("invalid date in index snapshot `{snapshot}`"))?;
+    Ok(Some(date.midnight().assume_utc().unix_timestamp()))
+}
+
 /// Implemented to just be sure to drop `tree` field before our other fields.
 /// See SAFETY inside [`RemoteRegistry::tree()`] for more.
 impl<'cfg> Drop for RemoteRegistry<'cfg> {
@@ -0,0 +1,148 @@
+//! Verification of the detached signatures of `.crate` files, see [`verify`],
+//! and of prebuilt binaries, see [`verify_bins`].
+//!
+//! Signatures of `.crate` files are unstable and only checked with
+//! `-Zcrate-signatures`.
+//!
+//! A registry opts in by listing a PASERK `k3.public` key as `signature-key`
+//! in its `config.json`. Every index entry then needs a `sig` field, a PASETO
+//! `v3.public` token signed by the corresponding secret key, whose payload is
+//! a JSON object like `{"name":"foo","vers":"1.0.0","cksum":"..."}`. As the
+//! checksum of the downloaded `.crate` file is verified against the `cksum`
+//! of the index, a valid signature of the checksum covers the whole file.
+//!
+//! The prebuilt binaries installed by `cargo install --from-binary-registry`
+//! are signed the same way: the `bins_sig` field of the index entry is a
+//! token whose payload is like `{"name":"foo","vers":"1.0.0","bins":{...}}`,
+//! where `bins` is the `bins` field of the index entry, so it covers the
+//! checksums of every binary. They are always checked, and prebuilt binaries
+//! aren't used from registries without a signature key.
+//!
+//! Users can pin the key of a registry with `signature-key` in the
+//! `[registry]` or `[registries.<name>]` tables of their configuration, which
+//! takes precedence over the key advertised by the registry, and requires
+//! signatures even if the registry doesn't advertise a key.
+
+use anyhow::Context as _;
+use pasetors::keys::AsymmetricPublicKey;
+use pasetors::token::UntrustedToken;
+use pasetors::version3::{PublicToken, V3};
+use serde::Deserialize;
+
+use crate::core::PackageId;
+use crate::sources::registry::index::PrebuiltBins;
+use crate::util::errors::CargoResult;
+use crate::util::Config;
+
+/// The payload of a signature.
+#[derive(Deserialize)]
+struct Message<'a> {
+    name: &'a str,
+    vers: &'a str,
+    cksum: &'a str,
+}
+
+/// The payload of a signature of prebuilt binaries.
+#[derive(Deserialize)]
+struct BinsMessage<'a> {
+    name: &'a str,
+    vers: &'a str,
+    bins: PrebuiltBins,
+}
+
+/// Gets the key to verify signatures of packages from the registry of `pkg`
+/// with, or `None` if they aren't signed.
+///
+/// `advertised` is the key listed in the `config.json` of the registry.
+pub(super) fn signature_key(
+    config: &Config,
+    pkg: PackageId,
+    advertised: Option<String>,
+) -> CargoResult<Option<String>> {
+    let pinned = config.registry_settings(&pkg.source_id())?.signature_key;
+    Ok(pinned.or(advertised))
+}
+
+/// Verifies that `sig` is a signature of `checksum` for `pkg` made by the
+/// owner of `key`.
+pub(super) fn verify(
+    pkg: PackageId,
+    checksum: &str,
+    sig: Option<&str>,
+    key: &str,
+) -> CargoResult<()> {
+    let key = parse_key(pkg, key)?;
+    let Some(sig) = sig else {
+        anyhow::bail!(
+            "`{pkg}` isn't signed, but {} requires signed packages",
+            pkg.source_id()
+        );
+    };
+    let payload = verify_token(&key, sig)
+        .with_context(|| format!("failed to verify the signature of `{pkg}`"))?;
+    let message: Message<'_> = serde_json::from_str(&payload)
+        .with_context(|| format!("failed to parse the signature of `{pkg}`"))?;
+    if message.name != pkg.name().as_str()
+        || message.vers != pkg.version().to_string()
+        || message.cksum != checksum
+    {
+        anyhow::bail!(
+            "the signature of `{pkg}` is for `{} v{}` with checksum `{}`, \
+             but the index lists checksum `{checksum}`",
+            message.name,
+            message.vers,
+            message.cksum
+        );
+    }
+    Ok(())
+}
+
+/// Verifies that `sig` is a signature of the checksums of the prebuilt
+/// binaries `bins` of `pkg` made by the owner of `key`.
+pub(super) fn verify_bins(
+    pkg: PackageId,
+    bins: &PrebuiltBins,
+    sig: Option<&str>,
+    key: &str,
+) -> CargoResult<()> {
+    let key = parse_key(pkg, key)?;
+    let Some(sig) = sig else {
+        anyhow::bail!(
+            "the prebuilt binaries of `{pkg}` aren't signed, \
+             but {} has a signature key",
+            pkg.source_id()
+        );
+    };
+    let payload = verify_token(&key, sig).with_context(|| {
+        format!("failed to verify the signature of the prebuilt binaries of `{pkg}`")
+    })?;
+    let message: BinsMessage<'_> = serde_json::from_str(&payload).with_context(|| {
+        format!("failed to parse the signature of the prebuilt binaries of `{pkg}`")
+    })?;
+    if message.name != pkg.name().as_str()
+        || message.vers != pkg.version().to_string()
+        || &message.bins != bins
+    {
+        anyhow::bail!(
+            "the signature of the prebuilt binaries of `{pkg}` doesn't match \
+             the binaries listed in the index"
+        );
+    }
+    Ok(())
+}
+
+/// Parses the PASERK `key` of the registry of `pkg`.
+fn parse_key(pkg: PackageId, key: &str) -> CargoResult<AsymmetricPublicKey<V3>> {
+    AsymmetricPublicKey::<V3>::try_from(key)
+        .map_err(|e| anyhow::format_err!("{e}"))
+        .with_context(|| format!("failed to parse the signature key of {}", pkg.source_id()))
+}
+
+/// Verifies that `sig` is a token signed by the owner of `key`, and returns
+/// its payload.
+fn verify_token(key: &AsymmetricPublicKey<V3>, sig: &str) -> CargoResult<String> {
+    let token = UntrustedToken::<pasetors::Public, V3>::try_from(sig)
+        .and_then(|token| PublicToken::verify(key, &token, None, None))
+        .map_err(|e| anyhow::format_err!("{e}"))?;
+    Ok(token.payload().to_string())
+}
@@ -1,5 +1,7 @@
 use crate::core::source::MaybePackage;
-use crate::core::{Dependency, Package, PackageId, QueryKind, Source, SourceId, Summary};
+use crate::core::{
+    Dependency, Package, PackageId, PrebuiltBinary, QueryKind, Source, SourceId, Summary,
+};
 use crate::util::errors::CargoResult;
 use std::task::Poll;
 
@@ -114,6 +116,11 @@ impl<'cfg> Source for ReplacedSource<'cfg> {
         self.inner.verify(id)
     }
 
+    fn is_downloaded(&self, id: PackageId) -> bool {
+        let id = id.with_source_id(self.replace_with);
+        self.inner.is_downloaded(id)
+    }
+
     fn describe(&self) -> String {
         if self.replace_with.is_crates_io() && self.to_replace.is_crates_io() {
             // Built-in source replacement of crates.io for sparse registry or tests
@@ -144,6 +151,11 @@ impl<'cfg> Source for ReplacedSource<'cfg> {
         self.inner.is_yanked(pkg)
     }
 
+    fn prebuilt_binaries(&mut self, pkg: PackageId) -> Poll<CargoResult<Vec<PrebuiltBinary>>> {
+        let pkg = pkg.with_source_id(self.replace_with);
+        self.inner.prebuilt_binaries(pkg)
+    }
+
     fn block_until_ready(&mut self) -> CargoResult<()> {
         self.inner
             .block_until_ready()
@@ -23,7 +23,7 @@ use crate::util::credential::adaptor::BasicProcessCredential;
 use crate::util::credential::paseto::PasetoCredential;
 
 use super::{
-    config::{CredentialCacheValue, OptValue, PathAndArgs},
+    config::{CredentialCacheValue, OptValue, PathAndArgs, SslClientCertConfig},
     credential::process::CredentialProcessCredential,
     credential::token::TokenCredential,
 };
@@ -41,6 +41,17 @@ pub struct RegistryConfig {
     pub secret_key_subject: Option<String>,
     #[serde(rename = "protocol")]
     _protocol: Option<String>,
+    // The keys of `RegistrySettings`, which aren't about authentication.
+    #[serde(rename = "signature-key")]
+    _signature_key: Option<String>,
+    #[serde(rename = "snapshot")]
+    _snapshot: Option<String>,
+    #[serde(rename = "proxy")]
+    _proxy: Option<String>,
+    #[serde(rename = "client-cert")]
+    _client_cert: Option<SslClientCertConfig>,
+    #[serde(rename = "trusted-publishing")]
+    _trusted_publishing: Option<bool>,
 }
 
 /// The `[registry]` table, which more keys than the `[registries.NAME]` tables.
@@ -70,6 +81,11 @@ impl RegistryConfigExtended {
             secret_key: self.secret_key,
             secret_key_subject: self.secret_key_subject,
             _protocol: None,
+            _signature_key: None,
+            _snapshot: None,
+            _proxy: None,
+            _client_cert: None,
+            _trusted_publishing: None,
         }
     }
 }
@@ -208,7 +224,7 @@ pub fn registry_credential_config_raw(
     config: &Config,
     sid: &SourceId,
 ) -> CargoResult<Option<RegistryConfig>> {
-    log::trace!("loading credential config for {}", sid);
+    tracing::trace!("loading credential config for {}", sid);
     config.load_credentials()?;
     if !sid.is_remote_registry() {
         bail!(
@@ -226,6 +242,18 @@ pub fn registry_credential_config_raw(
             .map(|c| c.to_registry_config()));
     }
 
+    if let Some(name) = registry_name(config, sid)? {
+        tracing::debug!("found alternative registry name `{name}` for {sid}");
+        config.get::<Option<RegistryConfig>>(&format!("registries.{name}"))
+    } else {
+        tracing::debug!("no registry name found for {sid}");
+        Ok(None)
+    }
+}
+
+/// Finds the name of the alternative registry `sid` in the
+/// `[registries]` table.
+pub fn registry_name(config: &Config, sid: &SourceId) -> CargoResult<Option<String>> {
     // Find the SourceId's name by its index URL. If environment variables
     // are available they will be preferred over configuration values.
     //
@@ -290,13 +318,7 @@ pub fn registry_credential_config_raw(
         }
     }
 
-    if let Some(name) = &name {
-        log::debug!("found alternative registry name `{name}` for {sid}");
-        config.get::<Option<RegistryConfig>>(&format!("registries.{name}"))
-    } else {
-        log::debug!("no registry name found for {sid}");
-        Ok(None)
-    }
+    Ok(name)
 }
 
 /// Use the `[credential-alias]` table to see if the provider name has been aliased.
@@ -304,7 +326,7 @@ fn resolve_credential_alias(config: &Config, mut provider: PathAndArgs) -> Vec<S
     if provider.args.is_empty() {
         let key = format!("credential-alias.{}", provider.path.raw_value());
         if let Ok(alias) = config.get::<PathAndArgs>(&key) {
-            log::debug!("resolving credential alias '{key}' -> '{alias:?}'");
+            tracing::debug!("resolving credential alias '{key}' -> '{alias:?}'");
             provider = alias;
         }
     }
@@ -428,7 +450,7 @@ fn credential_action(
     for provider in providers {
         let args: Vec<&str> = provider.iter().map(String::as_str).collect();
         let process = args[0];
-        log::debug!("attempting credential provider: {args:?}");
+        tracing::debug!("attempting credential provider: {args:?}");
         let provider: Box<dyn Credential> = match process {
             "cargo:token" => Box::new(TokenCredential::new(config)),
             "cargo:paseto" => Box::new(PasetoCredential::new(config)),
@@ -494,7 +516,7 @@ fn auth_token_optional(
     operation: Operation<'_>,
     headers: Vec<String>,
 ) -> CargoResult<Option<Secret<String>>> {
-    log::trace!("token requested for {}", sid.display_registry_name());
+    tracing::trace!("token requested for {}", sid.display_registry_name());
     let mut cache = config.credential_cache();
     let url = sid.canonical_url();
     if let Some(cached_token) = cache.get(url) {
@@ -504,7 +526,7 @@ fn auth_token_optional(
             .unwrap_or(true)
         {
             if cached_token.operation_independent || matches!(operation, Operation::Read) {
-                log::trace!("using token from in-memory cache");
+                tracing::trace!("using token from in-memory cache");
                 return Ok(Some(cached_token.token_value.clone()));
             }
         } else {
@@ -532,7 +554,7 @@ fn auth_token_optional(
         bail!("credential provider produced unexpected response for `get` request: {credential_response:?}")
     };
     let token = Secret::from(token);
-    log::trace!("found token");
+    tracing::trace!("found token");
     let expiration = match cache_control {
         CacheControl::Expires(expiration) => Some(expiration),
         CacheControl::Session => None,
@@ -0,0 +1,151 @@
+//! Opening documentation, reports and links in a browser, see [`open_path`]
+//! and [`open_url`].
+//!
+//! The browser is chosen in this order:
+//!
+//! * The `doc.browser` config value, a program with arguments. With
+//!   `-Zdoc-browser-templates`, `{path}` and `{url}` in the arguments are
+//!   replaced by what is opened, which is otherwise passed as the last
+//!   argument.
+//! * The `BROWSER` environment variable.
+//! * The default browser of the system.
+//!
+//! In WSL, the paths passed to a Windows browser, whose program ends with
+//! `.exe`, are translated to Windows paths with `wslpath`.
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use cargo_util::ProcessBuilder;
+use url::Url;
+
+use crate::util::config::{Config, PathAndArgs};
+use crate::util::CargoResult;
+
+/// What to open.
+#[derive(Clone, Copy)]
+enum Target<'a> {
+    Path(&'a Path),
+    Url(&'a str),
+}
+
+/// Opens the file at `path`, like the documentation of `cargo doc --open`.
+///
+/// Failing to run the browser is only a warning.
+pub fn open_path(config: &Config, path: &Path) -> CargoResult<()> {
+    open(config, Target::Path(path))
+}
+
+/// Opens `url`, like the verification page of `cargo login`.
+///
+/// Failing to run the browser is only a warning.
+pub fn open_url(config: &Config, url: &str) -> CargoResult<()> {
+    open(config, Target::Url(url))
+}
+
+fn open(config: &Config, target: Target<'_>) -> CargoResult<()> {
+    let browser = match config.get::<Option<PathAndArgs>>("doc.browser")? {
+        Some(browser) => Some((browser.path.resolve_program(config), browser.args)),
+        None => config
+            .get_env_os("BROWSER")
+            .map(|browser| (PathBuf::from(browser), Vec::new())),
+    };
+    let shown = match target {
+        Target::Path(path) => path.display().to_string(),
+        Target::Url(url) => url.to_string(),
+    };
+
+    let Some((browser, args)) = browser else {
+        let result = match target {
+            Target::Path(path) => opener::open(path),
+            Target::Url(url) => opener::open(url),
+        };
+        if let Err(e) = result {
+            let e = e.into();
+            crate::display_warning_with_error(
+                &format!("couldn't open {shown}"),
+                &e,
+                &mut config.shell(),
+            );
+        }
+        return Ok(());
+    };
+
+    let (path, url) = match target {
+        Target::Path(path) if is_windows_program(&browser) && is_wsl() => {
+            let path = wsl_to_windows_path(path);
+            let url = windows_path_to_url(&path.to_string_lossy());
+            (path, url)
+        }
+        Target::Path(path) => {
+            let url = Url::from_file_path(path)
+                .map(String::from)
+                .unwrap_or_else(|_| path.display().to_string());
+            (path.as_os_str().to_owned(), url)
+        }
+        Target::Url(url) => (OsString::from(url), url.to_string()),
+    };
+    let args = browser_args(config, args, path, &url);
+
+    if let Err(e) = Command::new(&browser).args(args).status() {
+        config.shell().warn(format!(
+            "couldn't open {shown} with {}: {e}",
+            browser.display()
+        ))?;
+    }
+    Ok(())
+}
+
+/// The arguments to pass to the browser: the ones from `doc.browser` with
+/// their `{path}` and `{url}` placeholders expanded, or followed by `path`
+/// when they have none.
+fn browser_args(config: &Config, args: Vec<String>, path: OsString, url: &str) -> Vec<OsString> {
+    let is_template = |arg: &String| arg.contains("{path}") || arg.contains("{url}");
+    if !config.cli_unstable().doc_browser_templates || !args.iter().any(is_template) {
+        let mut args: Vec<OsString> = args.into_iter().map(OsString::from).collect();
+        args.push(path);
+        return args;
+    }
+    let path = path.to_string_lossy();
+    args.into_iter()
+        .map(|arg| arg.replace("{path}", &path).replace("{url}", url).into())
+        .collect()
+}
+
+fn is_windows_program(program: &Path) -> bool {
+    program
+        .extension()
+        .map_or(false, |ext| ext.eq_ignore_ascii_case("exe"))
+}
+
+/// Whether Cargo runs in the Windows Subsystem for Linux.
+fn is_wsl() -> bool {
+    cfg!(target_os = "linux")
+        && std::fs::read_to_string("/proc/sys/kernel/osrelease").map_or(false, |release| {
+            release.to_lowercase().contains("microsoft")
+        })
+}
+
+/// Translates `path` to the path Windows programs see with `wslpath -w`,
+/// or keeps it when that fails.
+fn wsl_to_windows_path(path: &Path) -> OsString {
+    ProcessBuilder::new("wslpath")
+        .arg("-w")
+        .arg(path)
+        .exec_with_output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|windows_path| OsString::from(windows_path.trim_end()))
+        .unwrap_or_else(|| path.as_os_str().to_owned())
+}
+
+/// The `file:` URL of a Windows path, like `C:\dir` or
+/// `\\wsl.localhost\distro\dir`.
+fn windows_path_to_url(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    match path.strip_prefix("//") {
+        Some(unc) => format!("file://{unc}"),
+        None => format!("file:///{path}"),
+    }
+}
@@ -0,0 +1,149 @@
+//! Cancellation of the operations of Cargo, see [`CancellationToken`].
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+
+use crate::util::errors::CargoResult;
+
+/// A flag telling Cargo to stop what it's doing, shared by the clones of the
+/// token.
+///
+/// Every [`Config`](crate::Config) has one, see
+/// [`Config::cancellation`](crate::Config::cancellation). Once it is
+/// cancelled, the resolver, network transfers, git fetches and builds stop
+/// at their next check with a [`Cancelled`] error, and the processes run
+/// by the build are killed. Embedders can clone it before starting an
+/// operation, and cancel it from another thread.
+///
+/// A cancelled token stays cancelled.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    /// Cancels the operations checking this token.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Returns a [`Cancelled`] error if the token is cancelled.
+    pub fn check(&self) -> CargoResult<()> {
+        if self.is_cancelled() {
+            Err(Cancelled.into())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// The flag set when cancelled, for
+    /// [`ProcessBuilder::cancel_on`](cargo_util::ProcessBuilder::cancel_on).
+    pub fn flag(&self) -> Arc<AtomicBool> {
+        self.cancelled.clone()
+    }
+
+    /// Cancels this token on the first Ctrl-C, instead of terminating the
+    /// process. A second one terminates it as usual, after killing the
+    /// processes run with
+    /// [`ProcessBuilder::kill_process_tree`](cargo_util::ProcessBuilder::kill_process_tree),
+    /// which don't get the Ctrl-C of the terminal.
+    ///
+    /// Only one token of the process can be cancelled this way, this does
+    /// nothing when called again.
+    pub fn cancel_on_ctrl_c(&self) {
+        if CTRL_C.set(self.clone()).is_ok() {
+            imp::install_handler();
+        }
+    }
+}
+
+/// The token cancelled by Ctrl-C, see
+/// [`CancellationToken::cancel_on_ctrl_c`].
+static CTRL_C: OnceLock<CancellationToken> = OnceLock::new();
+
+/// Cancels [`CTRL_C`]. This only stores an atomic, so it can be called from a
+/// signal handler.
+fn cancel_ctrl_c() {
+    if let Some(token) = CTRL_C.get() {
+        token.cancel();
+    }
+}
+
+#[cfg(unix)]
+mod imp {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    pub fn install_handler() {
+        static HANDLED: AtomicBool = AtomicBool::new(false);
+
+        extern "C" fn handler(_: libc::c_int) {
+            if !HANDLED.swap(true, Ordering::SeqCst) {
+                super::cancel_ctrl_c();
+                return;
+            }
+            // The next Ctrl-C terminates the process.
+            cargo_util::kill_process_trees();
+            unsafe {
+                libc::signal(libc::SIGINT, libc::SIG_DFL);
+                libc::raise(libc::SIGINT);
+            }
+        }
+        unsafe {
+            let handler: extern "C" fn(libc::c_int) = handler;
+            libc::signal(libc::SIGINT, handler as libc::sighandler_t);
+        }
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use windows_sys::Win32::Foundation::{BOOL, FALSE, TRUE};
+    use windows_sys::Win32::System::Console::{SetConsoleCtrlHandler, CTRL_C_EVENT};
+
+    pub fn install_handler() {
+        static HANDLED: AtomicBool = AtomicBool::new(false);
+
+        unsafe extern "system" fn handler(ctrl_type: u32) -> BOOL {
+            // The next Ctrl-C, or any other event, goes to the default
+            // handler, which terminates the process.
+            if ctrl_type == CTRL_C_EVENT && !HANDLED.swap(true, Ordering::SeqCst) {
+                super::cancel_ctrl_c();
+                TRUE
+            } else {
+                cargo_util::kill_process_trees();
+                FALSE
+            }
+        }
+        unsafe {
+            SetConsoleCtrlHandler(Some(handler), TRUE);
+        }
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod imp {
+    pub fn install_handler() {}
+}
+
+/// The error of an operation stopped by its [`CancellationToken`].
+#[derive(Debug)]
+pub struct Cancelled;
+
+impl std::error::Error for Cancelled {}
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("operation cancelled")
+    }
+}
@@ -1,7 +1,8 @@
-use crate::core::compiler::{BuildConfig, MessageFormat, TimingOutput};
+use crate::core::compiler::{BuildConfig, MessageFormat, TimingOutput, Workdir};
 use crate::core::resolver::CliFeatures;
 use crate::core::{Edition, Workspace};
 use crate::ops::{CompileFilter, CompileOptions, NewOptions, Packages, VersionControl};
+use crate::util::errors::{CargoErrorKind, KindError};
 use crate::util::important_paths::find_root_manifest_for_wd;
 use crate::util::interning::InternedString;
 use crate::util::restricted_names::is_glob_pattern;
@@ -12,7 +13,8 @@ use crate::util::{
     print_available_packages, print_available_tests,
 };
 use crate::CargoResult;
-use anyhow::bail;
+use anyhow::{bail, Context as _};
+use cargo_platform::Cfg;
 use cargo_util::paths;
 use std::ffi::{OsStr, OsString};
 use std::path::Path;
@@ -166,6 +168,15 @@ pub trait CommandExt: Sized {
         self._arg(multi_opt("target", "TRIPLE", target))
     }
 
+    fn arg_target_cfg(self) -> Self {
+        self._arg(multi_opt(
+            "cfg",
+            "SPEC",
+            "Evaluate platform-specific dependencies against the given `cfg` \
+             values instead of the ones of the target (unstable)",
+        ))
+    }
+
     fn arg_target_dir(self) -> Self {
         self._arg(
             opt("target-dir", "Directory for all generated artifacts").value_name("DIRECTORY"),
@@ -191,17 +202,31 @@ pub trait CommandExt: Sized {
         self._arg(flag("unit-graph", "Output build graph in JSON (unstable)"))
     }
 
+    fn arg_print_flags(self) -> Self {
+        self._arg(flag(
+            "print-flags",
+            "Print the flags passed to the compiler for each unit (unstable)",
+        ))
+    }
+
+    fn arg_emit_env(self) -> Self {
+        self._arg(flag(
+            "emit-env",
+            "Write the environment and command line of each unit to the target directory (unstable)",
+        ))
+    }
+
     fn arg_new_opts(self) -> Self {
         self._arg(
             opt(
                 "vcs",
                 "Initialize a new repository for the given version \
-                 control system (git, hg, pijul, or fossil) or do not \
+                 control system (git, hg, jj, pijul, or fossil) or do not \
                  initialize any version control at all (none), overriding \
                  a global configuration.",
             )
             .value_name("VCS")
-            .value_parser(["git", "hg", "pijul", "fossil", "none"]),
+            .value_parser(["git", "hg", "jj", "pijul", "fossil", "none"]),
         )
         ._arg(flag("bin", "Use a binary (application) template [default]"))
         ._arg(flag("lib", "Use a library template"))
@@ -245,6 +270,20 @@ pub trait CommandExt: Sized {
         self._arg(flag("quiet", "Do not print cargo log messages").short('q'))
     }
 
+    fn arg_dep_info_basedir(self) -> Self {
+        self._arg(
+            opt(
+                "dep-info-basedir",
+                "Directory to make the paths of dep-info files relative to (unstable)",
+            )
+            .value_name("PATH"),
+        )
+    }
+
+    fn arg_workdir(self, help: &'static str) -> Self {
+        self._arg(opt("workdir", help).value_name("DIR"))
+    }
+
     fn arg_timings(self) -> Self {
         self._arg(
             optional_opt(
@@ -339,6 +378,18 @@ pub trait ArgMatchesExt {
         self._value_of(name).map(|path| config.cwd().join(path))
     }
 
+    /// The `--workdir` of `cargo run`, `cargo test` and `cargo bench`, or
+    /// else the `run.workdir` config, which need `-Zrun-workdir`.
+    fn workdir(&self, config: &Config) -> CargoResult<Option<Workdir>> {
+        let Some(value) = self._value_of("workdir") else {
+            return Workdir::from_config(config);
+        };
+        if !config.cli_unstable().run_workdir {
+            bail!("the `--workdir` flag is unstable, pass `-Z run-workdir` to enable it");
+        }
+        Ok(Some(Workdir::parse(value, config.cwd())))
+    }
+
     fn root_manifest(&self, config: &Config) -> CargoResult<PathBuf> {
         root_manifest(self._value_of("manifest-path").map(Path::new), config)
     }
@@ -533,7 +584,10 @@ pub trait ArgMatchesExt {
         build_config.requested_profile = self.get_profile_name(config, "dev", profile_checking)?;
         build_config.build_plan = self.flag("build-plan");
         build_config.unit_graph = self.flag("unit-graph");
+        build_config.print_flags = self.flag("print-flags");
+        build_config.emit_env = self.flag("emit-env");
         build_config.future_incompat_report = self.flag("future-incompat-report");
+        build_config.dep_info_basedir = self._value_of("dep-info-basedir").map(String::from);
 
         if self._contains("timings") {
             for timing_output in self._values_of("timings") {
@@ -577,6 +631,17 @@ pub trait ArgMatchesExt {
                 .cli_unstable()
                 .fail_if_stable_opt("--unit-graph", 8002)?;
         }
+        if build_config.print_flags && !config.cli_unstable().unstable_options {
+            bail!("the `--print-flags` flag is unstable, pass `-Z unstable-options` to enable it");
+        }
+        if build_config.emit_env && !config.cli_unstable().unstable_options {
+            bail!("the `--emit-env` flag is unstable, pass `-Z unstable-options` to enable it");
+        }
+        if build_config.dep_info_basedir.is_some() && !config.cli_unstable().dep_info_basedir {
+            bail!(
+                "the `--dep-info-basedir` flag is unstable, pass `-Z dep-info-basedir` to enable it"
+            );
+        }
 
         let opts = CompileOptions {
             build_config,
@@ -624,6 +689,22 @@ pub trait ArgMatchesExt {
         )
     }
 
+    /// Parses the values of `--cfg`, like `unix` or `feature="foo"`.
+    fn target_cfgs(&self, config: &Config) -> CargoResult<Vec<Cfg>> {
+        let values = self._values_of("cfg");
+        if !values.is_empty() && !config.cli_unstable().unstable_options {
+            bail!("the `--cfg` flag is unstable, pass `-Z unstable-options` to enable it");
+        }
+        values
+            .iter()
+            .map(|value| {
+                value
+                    .parse()
+                    .with_context(|| format!("invalid `--cfg` value `{value}`"))
+            })
+            .collect()
+    }
+
     fn compile_options_for_single_package(
         &self,
         config: &Config,
@@ -644,6 +725,7 @@ pub trait ArgMatchesExt {
         let vcs = self._value_of("vcs").map(|vcs| match vcs {
             "git" => VersionControl::Git,
             "hg" => VersionControl::Hg,
+            "jj" => VersionControl::Jj,
             "pijul" => VersionControl::Pijul,
             "fossil" => VersionControl::Fossil,
             "none" => VersionControl::NoVcs,
@@ -788,7 +870,9 @@ pub fn root_manifest(manifest_path: Option<&Path>, config: &Config) -> CargoResu
             anyhow::bail!("the manifest-path must be a path to a Cargo.toml file")
         }
         if !path.exists() {
-            anyhow::bail!("manifest path `{}` does not exist", manifest_path.display())
+            let error =
+                anyhow::format_err!("manifest path `{}` does not exist", manifest_path.display());
+            return Err(KindError::new(CargoErrorKind::ManifestNotFound, error).into());
         }
         if path.is_dir() {
             anyhow::bail!(
@@ -7,6 +7,11 @@ use serde::{de, de::IntoDeserializer};
 use std::collections::HashSet;
 use std::vec;
 
+/// Name of the struct passed to `deserialize_struct` by the types with a
+/// custom `Deserialize` that can also be a string, like `term.progress`, so
+/// that their visitor is given the string. Other structs can't be a string.
+pub(super) const STRING_OR_TABLE: &str = "$__cargo_string_or_table";
+
 /// Serde deserializer used to convert config values to a target type using
 /// `Config::get`.
 #[derive(Clone)]
@@ -104,6 +109,15 @@ impl<'de, 'config> de::Deserializer<'de> for Deserializer<'config> {
         if name == value::NAME && fields == value::FIELDS {
             return visitor.visit_map(ValueDeserializer::new(self)?);
         }
+        // Keys which are a string or a table, like `term.progress`, are given
+        // the string to their visitor.
+        if name == STRING_OR_TABLE {
+            if let Some(CV::String(s, def)) = self.config.get_cv_with_env(&self.key)? {
+                return visitor
+                    .visit_string(s)
+                    .map_err(|e: ConfigError| e.with_key_context(&self.key, def));
+            }
+        }
         visitor.visit_map(ConfigMapAccess::new_struct(self, fields)?)
     }
 
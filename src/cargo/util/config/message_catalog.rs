@@ -0,0 +1,206 @@
+//! Translations of the messages Cargo displays, selected with `term.language`
+//! and `-Zmessage-catalogs`.
+//!
+//! English is built in. Other languages are read from
+//! `$CARGO_HOME/catalogs/<language>.toml`, or from the
+//! `share/cargo/catalogs` directory of the Cargo installation, where
+//! distributions ship their translations:
+//!
+//! ```toml
+//! # The statuses, like `Compiling`, and the `warning`, `error` and `note`
+//! # labels.
+//! [status]
+//! Compiling = "Compilation"
+//! warning = "avertissement"
+//!
+//! # Messages, keyed by their English template.
+//! [messages]
+//! "{dep} from {section}" = "{dep} de {section}"
+//! ```
+//!
+//! Messages are formatted with [`message!`](crate::message), where the
+//! `{name}` placeholders of the template are replaced by the arguments.
+//! Anything without a translation is displayed in English.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::{self, Write as _};
+use std::path::PathBuf;
+
+use anyhow::{bail, Context as _};
+use cargo_util::paths;
+use serde::Deserialize;
+
+use super::Config;
+use crate::util::errors::CargoResult;
+
+/// The language of the built-in messages.
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// Translations of statuses and messages, empty for English.
+#[derive(Debug, Default, Deserialize)]
+pub struct MessageCatalog {
+    /// Statuses, like `Compiling`, and the labels of warnings, errors and
+    /// notes, by their English text.
+    #[serde(default)]
+    status: HashMap<String, String>,
+    /// Message templates, by their English template.
+    #[serde(default)]
+    messages: HashMap<String, String>,
+}
+
+impl MessageCatalog {
+    /// Translates a status, or the label of a warning, error or note.
+    pub fn status<'a>(&'a self, status: &'a str) -> &'a str {
+        self.status.get(status).map_or(status, |s| s.as_str())
+    }
+
+    /// Translates `template` and replaces its `{name}` placeholders with the
+    /// argument of that name. `{{` and `}}` are literal braces.
+    pub fn format(&self, template: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+        let template = self.messages.get(template).map_or(template, |t| t.as_str());
+        let mut out = String::with_capacity(template.len());
+        for piece in Pieces(template) {
+            match piece {
+                Piece::Text(text) => out.push_str(text),
+                Piece::Placeholder(name) => match args.iter().find(|(n, _)| *n == name) {
+                    Some((_, value)) => drop(write!(out, "{value}")),
+                    None => drop(write!(out, "{{{name}}}")),
+                },
+            }
+        }
+        out
+    }
+
+    /// Checks that every message only uses the placeholders of its English
+    /// template.
+    fn validate(&self) -> CargoResult<()> {
+        for (english, translated) in &self.messages {
+            let known = placeholders(english);
+            if let Some(unknown) = placeholders(translated).difference(&known).next() {
+                bail!(
+                    "translation of message \"{english}\" uses unknown placeholder `{{{unknown}}}`"
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Formats a message through the [`MessageCatalog`] of the shell of
+/// `$config`, see [`MessageCatalog::format`]. Where the shell is already
+/// borrowed, it is passed with `shell = ` instead.
+///
+/// ```ignore
+/// let msg = message!(config, "{dep} from {section}", dep = dep, section = section);
+/// config.shell().status("Removing", msg)?;
+///
+/// let msg = message!(shell = shell, "{dep} from {section}", dep = dep, section = section);
+/// shell.status("Removing", msg)?;
+/// ```
+#[macro_export]
+macro_rules! message {
+    (shell = $shell:expr, $template:literal $(, $name:ident = $value:expr)* $(,)?) => ({
+        let args: &[(&str, &dyn std::fmt::Display)] = &[$((stringify!($name), &$value)),*];
+        $shell.message_catalog().format($template, args)
+    });
+    ($config:expr, $template:literal $(, $name:ident = $value:expr)* $(,)?) => ({
+        let args: &[(&str, &dyn std::fmt::Display)] = &[$((stringify!($name), &$value)),*];
+        // Bound so the shell is released before the message is used.
+        let msg = $config.shell().message_catalog().format($template, args);
+        msg
+    });
+}
+
+enum Piece<'a> {
+    Text(&'a str),
+    Placeholder(&'a str),
+}
+
+/// Splits a template into text and `{name}` placeholders.
+struct Pieces<'a>(&'a str);
+
+impl<'a> Iterator for Pieces<'a> {
+    type Item = Piece<'a>;
+
+    fn next(&mut self) -> Option<Piece<'a>> {
+        let rest = self.0;
+        if rest.is_empty() {
+            return None;
+        }
+        if rest.starts_with("{{") || rest.starts_with("}}") {
+            self.0 = &rest[2..];
+            return Some(Piece::Text(&rest[..1]));
+        }
+        if let Some(after) = rest.strip_prefix('{') {
+            if let Some(end) = after.find('}') {
+                let name = &after[..end];
+                if !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                    self.0 = &after[end + 1..];
+                    return Some(Piece::Placeholder(name));
+                }
+            }
+        }
+        // Braces that don't start a placeholder are kept as is.
+        let end = rest[1..].find(['{', '}']).map_or(rest.len(), |i| i + 1);
+        self.0 = &rest[end..];
+        Some(Piece::Text(&rest[..end]))
+    }
+}
+
+fn placeholders(template: &str) -> BTreeSet<&str> {
+    Pieces(template)
+        .filter_map(|piece| match piece {
+            Piece::Placeholder(name) => Some(name),
+            Piece::Text(_) => None,
+        })
+        .collect()
+}
+
+impl Config {
+    /// Loads the catalog of `language`, a tag like `de` or `pt-BR`, which is
+    /// `None` for English. A catalog of the primary language, like `pt`, is
+    /// used when there is none for the region.
+    pub fn load_message_catalog(&self, language: &str) -> CargoResult<Option<MessageCatalog>> {
+        let primary = language.split(['-', '_']).next().unwrap_or(language);
+        if primary.eq_ignore_ascii_case(DEFAULT_LANGUAGE) {
+            return Ok(None);
+        }
+        let dirs = self.message_catalog_dirs();
+        let candidates = [language, primary];
+        let Some(path) = candidates
+            .iter()
+            .flat_map(|name| dirs.iter().map(move |dir| dir.join(format!("{name}.toml"))))
+            .find(|path| path.exists())
+        else {
+            let dirs: Vec<_> = dirs
+                .iter()
+                .map(|dir| format!("`{}`", dir.display()))
+                .collect();
+            bail!(
+                "no message catalog found for language `{language}` in {}",
+                dirs.join(" or ")
+            );
+        };
+        let load = || -> CargoResult<MessageCatalog> {
+            let catalog: MessageCatalog = toml::from_str(&paths::read(&path)?)?;
+            catalog.validate()?;
+            Ok(catalog)
+        };
+        let catalog = load().with_context(|| format!("failed to load `{}`", path.display()))?;
+        Ok(Some(catalog))
+    }
+
+    /// The directories searched for catalogs, `$CARGO_HOME/catalogs` first.
+    fn message_catalog_dirs(&self) -> Vec<PathBuf> {
+        let mut dirs = vec![self.home().as_path_unlocked().join("catalogs")];
+        if let Some(prefix) = self
+            .cargo_exe()
+            .ok()
+            .and_then(|exe| exe.parent())
+            .and_then(|bin| bin.parent())
+        {
+            dirs.push(prefix.join("share/cargo/catalogs"));
+        }
+        dirs
+    }
+}
@@ -52,38 +52,42 @@
 use std::borrow::Cow;
 use std::cell::{RefCell, RefMut};
 use std::collections::hash_map::Entry::{Occupied, Vacant};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::env;
 use std::ffi::{OsStr, OsString};
 use std::fmt;
-use std::fs::{self, File};
+use std::fs::{self, File, OpenOptions};
 use std::io::prelude::*;
 use std::io::{self, SeekFrom};
 use std::mem;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::Once;
-use std::time::Instant;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use self::ConfigValue as CV;
 use crate::core::compiler::rustdoc::RustdocExternMap;
+use crate::core::compiler::ExportDirs;
 use crate::core::shell::Verbosity;
 use crate::core::{features, CliUnstable, Shell, SourceId, Workspace, WorkspaceRootConfig};
 use crate::ops::RegistryCredentialConfig;
+use crate::sources::CRATES_IO_REGISTRY;
+use crate::util::auth;
 use crate::util::errors::CargoResult;
 use crate::util::network::http::configure_http_handle;
 use crate::util::network::http::http_handle;
+use crate::util::network::report::NetworkReport;
 use crate::util::toml as cargo_toml;
-use crate::util::{internal, CanonicalUrl};
+use crate::util::{internal, profile, CancellationToken, CanonicalUrl};
 use crate::util::{try_canonicalize, validate_package_name};
-use crate::util::{FileLock, Filesystem, IntoUrl, IntoUrlWithBase, Rustc};
+use crate::util::{FileLock, FileOverlay, Filesystem, IntoUrl, IntoUrlWithBase, LockWait, Rustc};
 use anyhow::{anyhow, bail, format_err, Context as _};
 use cargo_credential::Secret;
-use cargo_util::paths;
+use cargo_util::paths::{self, LinkStrategy};
 use curl::easy::Easy;
 use lazycell::LazyCell;
 use serde::de::IntoDeserializer as _;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 use toml_edit::Item;
 use url::Url;
@@ -106,11 +110,20 @@ pub use target::{TargetCfgConfig, TargetConfig};
 mod environment;
 use environment::Env;
 
+mod plugins;
+pub use plugins::{PluginManifest, SubcommandInfo, PLUGIN_MANIFEST};
+
+mod message_catalog;
+pub use message_catalog::{MessageCatalog, DEFAULT_LANGUAGE};
+
+pub mod schema;
+
 // Helper macro for creating typed access methods.
 macro_rules! get_value_typed {
     ($name:ident, $ty:ty, $variant:ident, $expected:expr) => {
         /// Low-level private method for getting a config value as an OptValue.
         fn $name(&self, key: &ConfigKey) -> Result<OptValue<$ty>, ConfigError> {
+            self.report_overridden(key);
             let cv = self.get_cv(key)?;
             let env = self.get_config_env::<$ty>(key)?;
             match (cv, env) {
@@ -205,12 +218,22 @@ pub struct Config {
     env: Env,
     /// Tracks which sources have been updated to avoid multiple updates.
     updated_sources: LazyCell<RefCell<HashSet<SourceId>>>,
+    /// Statistics about network requests, if enabled with `--network-report`.
+    network_report: Option<RefCell<NetworkReport>>,
+    /// Cancels the operations using this config.
+    cancellation: CancellationToken,
     /// Cache of credentials from configuration or credential providers.
     /// Maps from url to credential value.
     credential_cache: LazyCell<RefCell<HashMap<CanonicalUrl, CredentialCacheValue>>>,
     /// Lock, if held, of the global package cache along with the number of
     /// acquisitions so far.
     package_cache_lock: RefCell<Option<(Option<FileLock>, usize)>>,
+    /// The name of the subcommand being run, recorded as the holder of the
+    /// package cache lock.
+    subcommand: Option<String>,
+    /// The writable directory holding the package cache when `$CARGO_HOME`
+    /// is read-only, see [`Config::cache_overlay`].
+    cache_overlay: LazyCell<Option<Filesystem>>,
     /// Cached configuration parsed by Cargo
     http_config: LazyCell<CargoHttpConfig>,
     future_incompat_config: LazyCell<CargoFutureIncompatConfig>,
@@ -238,6 +261,16 @@ pub struct Config {
     pub nightly_features_allowed: bool,
     /// WorkspaceRootConfigs that have been found
     pub ws_roots: RefCell<HashMap<PathBuf, WorkspaceRootConfig>>,
+    /// Contents read instead of the ones of manifests and configuration
+    /// files on disk.
+    file_overlay: FileOverlay,
+    /// Whether to warn when a value of a config file is overridden, from
+    /// `term.verbose-config-resolution`.
+    verbose_config_resolution: bool,
+    /// The values of config files replaced by `--config` arguments, by key.
+    cli_overridden: HashMap<String, ConfigValue>,
+    /// The keys whose overridden value was already reported.
+    reported_overrides: RefCell<HashSet<String>>,
 }
 
 impl Config {
@@ -298,8 +331,12 @@ impl Config {
             target_dir: None,
             env,
             updated_sources: LazyCell::new(),
+            network_report: None,
+            cancellation: CancellationToken::new(),
             credential_cache: LazyCell::new(),
             package_cache_lock: RefCell::new(None),
+            subcommand: None,
+            cache_overlay: LazyCell::new(),
             http_config: LazyCell::new(),
             future_incompat_config: LazyCell::new(),
             net_config: LazyCell::new(),
@@ -310,6 +347,10 @@ impl Config {
             env_config: LazyCell::new(),
             nightly_features_allowed: matches!(&*features::channel(), "nightly" | "dev"),
             ws_roots: RefCell::new(HashMap::new()),
+            file_overlay: FileOverlay::default(),
+            verbose_config_resolution: false,
+            cli_overridden: HashMap::new(),
+            reported_overrides: RefCell::new(HashSet::new()),
         }
     }
 
@@ -339,22 +380,85 @@ impl Config {
     /// config file (to only be used for displaying a diagnostics suggestion,
     /// such as recommending where to add a config value).
     pub fn diagnostic_home_config(&self) -> String {
+        self.home_config_path().to_string_lossy().to_string()
+    }
+
+    /// Returns the path of the home config file, `config.toml` when there is
+    /// none yet.
+    pub fn home_config_path(&self) -> PathBuf {
         let home = self.home_path.as_path_unlocked();
-        let path = match self.get_file_path(home, "config", false) {
+        match self.get_file_path(home, "config", false) {
             Ok(Some(existing_path)) => existing_path,
             _ => home.join("config.toml"),
-        };
-        path.to_string_lossy().to_string()
+        }
     }
 
-    /// Gets the Cargo Git directory (`<cargo_home>/git`).
+    /// Gets the Cargo Git directory (`<cargo_home>/git`), which is in the
+    /// [overlay directory](Config::cache_overlay) when there is one.
     pub fn git_path(&self) -> Filesystem {
-        self.home_path.join("git")
+        self.cache_home().join("git")
     }
 
-    /// Gets the Cargo base directory for all registry information (`<cargo_home>/registry`).
+    /// Gets the Cargo base directory for all registry information (`<cargo_home>/registry`),
+    /// which is in the [overlay directory](Config::cache_overlay) when there is one.
     pub fn registry_base_path(&self) -> Filesystem {
-        self.home_path.join("registry")
+        self.cache_home().join("registry")
+    }
+
+    /// Returns the writable directory holding the package cache instead of
+    /// `$CARGO_HOME`, when `$CARGO_HOME` is read-only (e.g. a layer baked
+    /// into a container image) and `-Zcache-overlay` enables the
+    /// `cache.overlay-dir` config.
+    ///
+    /// The package cache in `$CARGO_HOME` is then a read-only layer, where
+    /// packages are still looked up with [`Config::readonly_cache_path`].
+    pub fn cache_overlay(&self) -> CargoResult<Option<&Filesystem>> {
+        self.cache_overlay
+            .try_borrow_with(|| {
+                if !self.cli_unstable().cache_overlay {
+                    return Ok(None);
+                }
+                let Some(dir) = self.get::<Option<ConfigRelativePath>>("cache.overlay-dir")? else {
+                    return Ok(None);
+                };
+                if !self.home_is_readonly() {
+                    return Ok(None);
+                }
+                Ok(Some(Filesystem::new(dir.resolve_path(self))))
+            })
+            .map(Option::as_ref)
+    }
+
+    /// Returns the path in the read-only layer of the package cache that
+    /// corresponds to `path` in the [overlay directory](Config::cache_overlay),
+    /// if there is one.
+    pub fn readonly_cache_path(&self, path: &Path) -> Option<PathBuf> {
+        let overlay = self.cache_overlay().ok().flatten()?;
+        let relative = path.strip_prefix(overlay.as_path_unlocked()).ok()?;
+        Some(self.home_path.as_path_unlocked().join(relative))
+    }
+
+    /// The directory holding the package cache. Errors in the config of the
+    /// overlay directory are reported when acquiring the package cache lock.
+    fn cache_home(&self) -> &Filesystem {
+        match self.cache_overlay() {
+            Ok(Some(overlay)) => overlay,
+            _ => &self.home_path,
+        }
+    }
+
+    /// Whether `$CARGO_HOME` can't be written to, as checked by opening the
+    /// file of the package cache lock for writing.
+    fn home_is_readonly(&self) -> bool {
+        let home = self.home_path.as_path_unlocked();
+        let res = paths::create_dir_all(home).and_then(|()| {
+            OpenOptions::new()
+                .write(true)
+                .create(true)
+                .open(home.join(".package-cache"))
+                .map_err(anyhow::Error::from)
+        });
+        matches!(res, Err(e) if maybe_readonly(&e))
     }
 
     /// Gets the Cargo registry index directory (`<cargo_home>/registry/index`).
@@ -481,6 +585,18 @@ impl Config {
             .borrow_mut()
     }
 
+    /// Starts recording statistics about network requests, see
+    /// [`Config::network_report`].
+    pub fn enable_network_report(&mut self) {
+        self.network_report = Some(RefCell::new(NetworkReport::default()));
+    }
+
+    /// Statistics about the network requests made so far, or `None` if they
+    /// aren't recorded.
+    pub fn network_report(&self) -> Option<RefMut<'_, NetworkReport>> {
+        self.network_report.as_ref().map(RefCell::borrow_mut)
+    }
+
     /// Cached credentials from credential providers or configuration.
     pub fn credential_cache(&self) -> RefMut<'_, HashMap<CanonicalUrl, CredentialCacheValue>> {
         self.credential_cache
@@ -530,6 +646,29 @@ impl Config {
         self.search_stop_path = Some(path);
     }
 
+    /// Reads `contents` instead of the file at `path` when loading manifests
+    /// and configuration files, even if it doesn't exist on disk.
+    ///
+    /// Configuration values which were already loaded aren't affected, see
+    /// [`Config::reload_rooted_at`].
+    pub fn set_file_overlay(&mut self, path: &Path, contents: String) {
+        self.file_overlay.insert(path, contents);
+        self.ws_roots.borrow_mut().clear();
+    }
+
+    /// Reads the file at `path` from disk again, after
+    /// [`Config::set_file_overlay`].
+    pub fn remove_file_overlay(&mut self, path: &Path) {
+        if self.file_overlay.remove(path).is_some() {
+            self.ws_roots.borrow_mut().clear();
+        }
+    }
+
+    /// The files read from memory instead of disk.
+    pub fn file_overlay(&self) -> &FileOverlay {
+        &self.file_overlay
+    }
+
     /// Reloads on-disk configuration values, starting at the given path and
     /// walking up its ancestors.
     pub fn reload_rooted_at<P: AsRef<Path>>(&mut self, path: P) -> CargoResult<()> {
@@ -599,7 +738,7 @@ impl Config {
         key: &ConfigKey,
         vals: &HashMap<String, ConfigValue>,
     ) -> CargoResult<Option<ConfigValue>> {
-        log::trace!("get cv {:?}", key);
+        tracing::trace!("get cv {:?}", key);
         if key.is_root() {
             // Returning the entire root table (for example `cargo config get`
             // with no key). The definition here shouldn't matter.
@@ -651,6 +790,7 @@ impl Config {
             // Root table can't have env value.
             return Ok(cv);
         }
+        self.report_overridden(key);
         let env = self.env.get_str(key.as_env_key());
         let env_def = Definition::Environment(key.as_env_key().to_string());
         let use_env = match (&cv, env) {
@@ -720,6 +860,57 @@ impl Config {
         }
     }
 
+    /// With `term.verbose-config-resolution`, warns once per key when the
+    /// value of `key` in a config file is overridden by an environment
+    /// variable or a `--config` argument.
+    ///
+    /// Lists aren't reported, as their values are merged instead.
+    fn report_overridden(&self, key: &ConfigKey) {
+        if !self.verbose_config_resolution
+            || self.reported_overrides.borrow().contains(&key.to_string())
+        {
+            return;
+        }
+        let Ok(Some(cv)) = self.get_cv(key) else {
+            return;
+        };
+        let scalar = |cv: &CV| match cv {
+            CV::Integer(i, _) => Some(i.to_string()),
+            CV::String(s, _) => Some(s.clone()),
+            CV::Boolean(b, _) => Some(b.to_string()),
+            CV::List(..) | CV::Table(..) => None,
+        };
+        let Some(value) = scalar(&cv) else {
+            return;
+        };
+        let env_def = Definition::Environment(key.as_env_key().to_string());
+        let (used, used_def, overridden) = match self.env.get_str(key.as_env_key()) {
+            Some(env) if env_def.is_higher_priority(cv.definition()) => {
+                (env.to_string(), &env_def, &cv)
+            }
+            _ => match self.cli_overridden.get(&key.to_string()) {
+                Some(overridden) if matches!(cv.definition(), Definition::Cli(_)) => {
+                    (value, cv.definition(), overridden)
+                }
+                _ => return,
+            },
+        };
+        let Some(overridden_value) = scalar(overridden) else {
+            return;
+        };
+        self.reported_overrides.borrow_mut().insert(key.to_string());
+        let _ = self.shell().warn(format!(
+            "`{key}` is set to `{used}` in {used_def}, \
+             overriding `{overridden_value}` in {}",
+            overridden.definition()
+        ));
+    }
+
+    /// Sets the name of the subcommand being run.
+    pub fn set_subcommand(&mut self, name: &str) {
+        self.subcommand = Some(name.to_string());
+    }
+
     /// Helper primarily for testing.
     pub fn set_env(&mut self, env: HashMap<String, String>) {
         self.env = Env::from_map(env);
@@ -999,7 +1190,11 @@ impl Config {
         // problems.
         let term = self.get::<TermConfig>("term").unwrap_or_default();
 
-        let color = color.or_else(|| term.color.as_deref());
+        let cli_color = color;
+        let color = color.or_else(|| match &term.color {
+            Some(ColorConfig::Both(color)) => Some(color.as_str()),
+            _ => None,
+        });
 
         // The command line takes precedence over configuration.
         let verbosity = match (verbose, quiet) {
@@ -1034,6 +1229,62 @@ impl Config {
 
         self.load_unstable_flags_from_config()?;
 
+        if self.progress_config.style == ProgressDisplay::Plain {
+            if self.cli_unstable().plain_progress {
+                self.shell().set_plain_output(true);
+            } else {
+                self.shell().warn(
+                    "the \"plain\" style of `term.progress` is unstable and ignored, \
+                     pass `-Zplain-progress` to enable it",
+                )?;
+            }
+        }
+
+        if let Some(ColorConfig::Streams { stdout, stderr }) = &term.color {
+            if !self.cli_unstable().stream_color {
+                bail!(
+                    "setting `term.color` to a table is unstable, \
+                     pass `-Zstream-color` to enable it"
+                );
+            }
+            if cli_color.is_none() {
+                self.shell()
+                    .set_stream_color_choices(stdout.as_deref(), stderr.as_deref())?;
+            }
+        }
+
+        if self.cli_unstable().output_width {
+            self.shell().set_output_width(term.width);
+        }
+
+        if self.cli_unstable().hyperlinks {
+            self.shell()
+                .set_hyperlinks(term.hyperlinks.unwrap_or(false));
+        }
+
+        self.verbose_config_resolution = self.cli_unstable().verbose_config_resolution
+            && term.verbose_config_resolution.unwrap_or(false);
+
+        if let Some(language) = term
+            .language
+            .filter(|_| self.cli_unstable().message_catalogs)
+        {
+            match self.load_message_catalog(&language) {
+                Ok(Some(catalog)) => self.shell().set_message_catalog(catalog),
+                Ok(None) => {}
+                Err(e) => crate::display_warning_with_error(
+                    "messages are displayed in English",
+                    &e,
+                    &mut self.shell(),
+                ),
+            }
+        }
+
+        if self.cli_unstable().self_profile {
+            profile::start_self_profile(self.creation_time);
+            profile::record("loading config", self.creation_time);
+        }
+
         Ok(())
     }
 
@@ -1181,7 +1432,9 @@ impl Config {
                 path.display()
             );
         }
-        let contents = fs::read_to_string(path)
+        let contents = self
+            .file_overlay
+            .read_to_string(path)
             .with_context(|| format!("failed to read configuration file `{}`", path.display()))?;
         let toml = cargo_toml::parse_document(&contents, path, self).with_context(|| {
             format!("could not parse TOML configuration in `{}`", path.display())
@@ -1287,15 +1540,23 @@ impl Config {
 
     /// Parses the CLI config args and returns them as a table.
     pub(crate) fn cli_args_as_table(&self) -> CargoResult<ConfigValue> {
+        Ok(self.cli_args_with_appends()?.0)
+    }
+
+    /// Loads the `--config` arguments like [`Config::cli_args_as_table`],
+    /// also returning the keys of the `KEY+=VALUE` arguments, along with
+    /// these arguments.
+    fn cli_args_with_appends(&self) -> CargoResult<(ConfigValue, Vec<(ConfigKey, String)>)> {
         let mut loaded_args = CV::Table(HashMap::new(), Definition::Cli(None));
+        let mut appends = Vec::new();
         let cli_args = match &self.cli_config {
             Some(cli_args) => cli_args,
-            None => return Ok(loaded_args),
+            None => return Ok((loaded_args, appends)),
         };
         let mut seen = HashSet::new();
         for arg in cli_args {
             let arg_as_path = self.cwd.join(arg);
-            let tmp_table = if !arg.is_empty() && arg_as_path.exists() {
+            let tmp_table = if !arg.is_empty() && self.file_overlay.exists(&arg_as_path) {
                 // --config path_to_file
                 let str_path = arg_as_path
                     .to_str()
@@ -1305,15 +1566,35 @@ impl Config {
                     .to_string();
                 self._load_file(&self.cwd().join(&str_path), &mut seen, true, WhyLoad::Cli)
                     .with_context(|| format!("failed to load config from `{}`", str_path))?
+            } else if !arg.contains('=') && arg.ends_with(".toml") {
+                bail!(
+                    "--config argument `{arg}` looks like a path to a config file, \
+                     but `{}` doesn't exist",
+                    arg_as_path.display()
+                );
             } else {
                 // We only want to allow "dotted key" (see https://toml.io/en/v1.0.0#keys)
                 // expressions followed by a value that's not an "inline table"
                 // (https://toml.io/en/v1.0.0#inline-table). Easiest way to check for that is to
                 // parse the value as a toml_edit::Document, and check that the (single)
                 // inner-most table is set via dotted keys.
-                let doc: toml_edit::Document = arg.parse().with_context(|| {
-                    format!("failed to parse value from --config argument `{arg}` as a dotted key expression")
-                })?;
+                //
+                // `KEY+=VALUE` isn't valid TOML, so it is only tried when the
+                // argument fails to parse.
+                let (doc, append): (toml_edit::Document, bool) = match arg.parse() {
+                    Ok(doc) => (doc, false),
+                    Err(e) => match arg
+                        .split_once("+=")
+                        .and_then(|(key, value)| format!("{key}={value}").parse().ok())
+                    {
+                        Some(doc) => (doc, true),
+                        None => {
+                            return Err(anyhow::Error::from(e).context(format!(
+                                "failed to parse value from --config argument `{arg}` as a dotted key expression"
+                            )))
+                        }
+                    },
+                };
                 fn non_empty_decor(d: &toml_edit::Decor) -> bool {
                     d.prefix()
                         .map_or(false, |p| !p.as_str().unwrap_or_default().trim().is_empty())
@@ -1348,6 +1629,18 @@ impl Config {
                                     sets a value to an inline table, which is not accepted"
                                 );
                             }
+                            Item::Value(v)
+                                if v.as_array().map_or(false, |array| {
+                                    array.iter().any(|v| v.is_inline_table())
+                                }) =>
+                            {
+                                // Config values can only be arrays of strings.
+                                let sets = if append { "appends" } else { "sets a value to" };
+                                bail!(
+                                    "--config argument `{arg}` \
+                                    {sets} an array of tables, which is not accepted"
+                                );
+                            }
                             Item::Value(v) => {
                                 if non_empty_decor(v.decor()) {
                                     bail!(
@@ -1383,6 +1676,31 @@ impl Config {
                         format!("failed to parse value from --config argument `{arg}`")
                     })?;
 
+                if append {
+                    if !self.cli_unstable().config_append {
+                        bail!(
+                            "--config argument `{arg}` appends with `+=`, which is unstable, \
+                             pass `-Z config-append` to enable it"
+                        );
+                    }
+                    let mut key = ConfigKey::new();
+                    let mut value = &toml_v;
+                    while let toml::Value::Table(table) = value {
+                        let (name, inner) = table.iter().next().expect("one key per table");
+                        key.push(name);
+                        value = inner;
+                    }
+                    if !value.is_array() {
+                        bail!(
+                            "--config argument `{arg}` appends a {}, \
+                             only arrays can be appended with `+=`",
+                            value.type_str()
+                        );
+                    }
+                    prepare_append(&mut loaded_args, &key, arg)?;
+                    appends.push((key, arg.clone()));
+                }
+
                 if toml_v
                     .get("registry")
                     .and_then(|v| v.as_table())
@@ -1431,31 +1749,73 @@ impl Config {
                 .merge(tmp_table, true)
                 .with_context(|| format!("failed to merge --config argument `{arg}`"))?;
         }
-        Ok(loaded_args)
+        Ok((loaded_args, appends))
     }
 
     /// Add config arguments passed on the command line.
     fn merge_cli_args(&mut self) -> CargoResult<()> {
-        let loaded_map = match self.cli_args_as_table()? {
+        let (loaded_args, appends) = self.cli_args_with_appends()?;
+        let loaded_map = match loaded_args {
             CV::Table(table, _def) => table,
             _ => unreachable!(),
         };
+        let mut overridden = HashMap::new();
         let values = self.values_mut()?;
+        let mut root = CV::Table(mem::take(values), Definition::Cli(None));
+        let prepared = appends
+            .iter()
+            .try_for_each(|(key, arg)| prepare_append(&mut root, key, arg));
+        let CV::Table(root, _) = root else {
+            unreachable!()
+        };
+        *values = root;
+        prepared?;
         for (key, value) in loaded_map.into_iter() {
             match values.entry(key) {
                 Vacant(entry) => {
                     entry.insert(value);
                 }
-                Occupied(mut entry) => entry.get_mut().merge(value, true).with_context(|| {
-                    format!(
-                        "failed to merge --config key `{}` into `{}`",
-                        entry.key(),
-                        entry.get().definition(),
-                    )
-                })?,
+                Occupied(mut entry) => {
+                    let mut config_key = ConfigKey::new();
+                    config_key.push(entry.key());
+                    record_overridden(&mut config_key, entry.get(), &value, &mut overridden);
+                    entry.get_mut().merge(value, true).with_context(|| {
+                        format!(
+                            "failed to merge --config key `{}` into `{}`",
+                            entry.key(),
+                            entry.get().definition(),
+                        )
+                    })?
+                }
             };
         }
-        Ok(())
+        self.cli_overridden = overridden;
+        return Ok(());
+
+        /// Records the values of `old` which the values of `new` replace when
+        /// merged into it, for `term.verbose-config-resolution`.
+        fn record_overridden(
+            key: &mut ConfigKey,
+            old: &CV,
+            new: &CV,
+            overridden: &mut HashMap<String, CV>,
+        ) {
+            match (old, new) {
+                (CV::Table(old, _), CV::Table(new, _)) => {
+                    for (name, new) in new {
+                        if let Some(old) = old.get(name) {
+                            key.push(name);
+                            record_overridden(key, old, new, overridden);
+                            key.pop();
+                        }
+                    }
+                }
+                (CV::List(..) | CV::Table(..), _) | (_, CV::List(..) | CV::Table(..)) => {}
+                (old, _) => {
+                    overridden.insert(key.to_string(), old.clone());
+                }
+            }
+        }
     }
 
     /// The purpose of this function is to aid in the transition to using
@@ -1472,8 +1832,8 @@ impl Config {
         let possible = dir.join(filename_without_extension);
         let possible_with_extension = dir.join(format!("{}.toml", filename_without_extension));
 
-        if possible.exists() {
-            if warn && possible_with_extension.exists() {
+        if self.file_overlay.exists(&possible) {
+            if warn && self.file_overlay.exists(&possible_with_extension) {
                 // We don't want to print a warning if the version
                 // without the extension is just a symlink to the version
                 // WITH an extension, which people may want to do to
@@ -1496,7 +1856,7 @@ impl Config {
             }
 
             Ok(Some(possible))
-        } else if possible_with_extension.exists() {
+        } else if self.file_overlay.exists(&possible_with_extension) {
             Ok(Some(possible_with_extension))
         } else {
             Ok(None)
@@ -1554,6 +1914,32 @@ impl Config {
         Ok(())
     }
 
+    /// Gets the settings of the registry `sid` which aren't about
+    /// authentication, from the `[registries]` entry with the index of `sid`,
+    /// or `registries.crates-io` for crates.io.
+    pub fn registry_settings(&self, sid: &SourceId) -> CargoResult<RegistrySettings> {
+        let name = if sid.is_crates_io() {
+            Some(CRATES_IO_REGISTRY.to_string())
+        } else if sid.is_remote_registry() {
+            auth::registry_name(self, sid)?
+        } else {
+            None
+        };
+        let Some(name) = name else {
+            return Ok(RegistrySettings::default());
+        };
+        // The keys are read one by one, as the `[registries.<name>]` tables
+        // also have the keys of `auth::RegistryConfig`.
+        let key = |key: &str| format!("registries.{name}.{key}");
+        Ok(RegistrySettings {
+            signature_key: self.get(&key("signature-key"))?,
+            snapshot: self.get(&key("snapshot"))?,
+            proxy: self.get(&key("proxy"))?,
+            client_cert: self.get(&key("client-cert"))?,
+            trusted_publishing: self.get(&key("trusted-publishing"))?,
+        })
+    }
+
     fn resolve_registry_index(&self, index: &Value<String>) -> CargoResult<Url> {
         // This handles relative file: URLs, relative to the config definition.
         let base = index
@@ -1837,6 +2223,12 @@ impl Config {
         self.creation_time
     }
 
+    /// The token cancelling the operations using this config, see
+    /// [`CancellationToken`].
+    pub fn cancellation(&self) -> &CancellationToken {
+        &self.cancellation
+    }
+
     /// Retrieves a config variable.
     ///
     /// This supports most serde `Deserialize` types. Examples:
@@ -1867,7 +2259,7 @@ impl Config {
             "package cache lock is not currently held, Cargo forgot to call \
              `acquire_package_cache_lock` before we got to this stack frame",
         );
-        assert!(ret.starts_with(self.home_path.as_path_unlocked()));
+        assert!(ret.starts_with(self.cache_home().as_path_unlocked()));
         ret
     }
 
@@ -1876,7 +2268,19 @@ impl Config {
     /// This lock is global per-process and can be acquired recursively. An RAII
     /// structure is returned to release the lock, and if this process
     /// abnormally terminates the lock is also released.
+    ///
+    /// With `-Zlock-wait-timeout`, the holder of the exclusive lock is
+    /// recorded in a file next to it, so that processes waiting for the lock,
+    /// even in other containers sharing `$CARGO_HOME`, can tell who they are
+    /// waiting for, and `package-cache.lock-wait-timeout` bounds how long to
+    /// wait.
+    ///
+    /// With an [overlay directory](Config::cache_overlay), the lock is in it,
+    /// and the registry indexes of the read-only layer that it doesn't have
+    /// yet are copied to it, since they are updated in place.
     pub fn acquire_package_cache_lock(&self) -> CargoResult<PackageCacheLock<'_>> {
+        let overlay = self.cache_overlay()?;
+        let home = overlay.unwrap_or(&self.home_path);
         let mut slot = self.package_cache_lock.borrow_mut();
         match *slot {
             // We've already acquired the lock in this process, so simply bump
@@ -1887,6 +2291,18 @@ impl Config {
             None => {
                 let path = ".package-cache";
                 let desc = "package cache";
+                let holder_path = self.package_cache_holder_path();
+                let read_holder = || PackageCacheHolder::read(&holder_path);
+                let wait = if self.cli_unstable().lock_wait_timeout {
+                    LockWait {
+                        timeout: self
+                            .get::<Option<u64>>("package-cache.lock-wait-timeout")?
+                            .map(Duration::from_secs),
+                        holder: Some(&read_holder),
+                    }
+                } else {
+                    LockWait::default()
+                };
 
                 // First, attempt to open an exclusive lock which is in general
                 // the purpose of this lock!
@@ -1904,11 +2320,19 @@ impl Config {
                 // someone else on the system we should synchronize with them,
                 // but if we can't even do that then we did our best and we just
                 // keep on chugging elsewhere.
-                match self.home_path.open_rw(path, self, desc) {
-                    Ok(lock) => *slot = Some((Some(lock), 1)),
+                match home.open_rw_waiting(path, self, desc, &wait) {
+                    Ok(lock) => {
+                        if self.cli_unstable().lock_wait_timeout {
+                            PackageCacheHolder::current(self).write(&holder_path);
+                        }
+                        if let Some(overlay) = overlay {
+                            self.copy_up_registry_indexes(overlay)?;
+                        }
+                        *slot = Some((Some(lock), 1));
+                    }
                     Err(e) => {
                         if maybe_readonly(&e) {
-                            let lock = self.home_path.open_ro(path, self, desc).ok();
+                            let lock = home.open_ro(path, self, desc).ok();
                             *slot = Some((lock, 1));
                             return Ok(PackageCacheLock(self));
                         }
@@ -1918,25 +2342,74 @@ impl Config {
                 }
             }
         }
-        return Ok(PackageCacheLock(self));
+        Ok(PackageCacheLock(self))
+    }
 
-        fn maybe_readonly(err: &anyhow::Error) -> bool {
-            err.chain().any(|err| {
-                if let Some(io) = err.downcast_ref::<io::Error>() {
-                    if io.kind() == io::ErrorKind::PermissionDenied {
-                        return true;
-                    }
+    pub fn release_package_cache_lock(&self) {}
 
-                    #[cfg(unix)]
-                    return io.raw_os_error() == Some(libc::EROFS);
-                }
+    fn package_cache_holder_path(&self) -> PathBuf {
+        self.cache_home()
+            .as_path_unlocked()
+            .join(".package-cache-holder")
+    }
 
-                false
-            })
+    /// Copies the registry indexes of the read-only layer of the package
+    /// cache that are missing from the `overlay` directory. Each one is
+    /// copied to a temporary directory first, so that an interrupted copy
+    /// isn't mistaken for an index.
+    fn copy_up_registry_indexes(&self, overlay: &Filesystem) -> CargoResult<()> {
+        let readonly = self.home_path.as_path_unlocked().join("registry/index");
+        let Ok(entries) = readonly.read_dir() else {
+            return Ok(());
+        };
+        let dst_base = overlay.as_path_unlocked().join("registry/index");
+        for entry in entries {
+            let entry = entry?;
+            let dst = dst_base.join(entry.file_name());
+            if !entry.file_type()?.is_dir() || dst.exists() {
+                continue;
+            }
+            let tmp = dst_base.join(format!(".{}.tmp", entry.file_name().to_string_lossy()));
+            if tmp.exists() {
+                paths::remove_dir_all(&tmp)?;
+            }
+            for src in walkdir::WalkDir::new(entry.path()) {
+                let src = src?;
+                let to = tmp.join(src.path().strip_prefix(entry.path())?);
+                if src.file_type().is_dir() {
+                    paths::create_dir_all(&to)?;
+                } else {
+                    // Not `fs::copy`, which would keep read-only permissions.
+                    paths::write(&to, paths::read_bytes(src.path())?)?;
+                }
+            }
+            fs::rename(&tmp, &dst).with_context(|| {
+                format!(
+                    "failed to copy the registry index at `{}` to `{}`",
+                    entry.path().display(),
+                    dst.display()
+                )
+            })?;
         }
+        Ok(())
     }
+}
 
-    pub fn release_package_cache_lock(&self) {}
+/// Whether `err` may come from writing to a read-only filesystem, or to a
+/// directory without the permissions to.
+fn maybe_readonly(err: &anyhow::Error) -> bool {
+    err.chain().any(|err| {
+        if let Some(io) = err.downcast_ref::<io::Error>() {
+            if io.kind() == io::ErrorKind::PermissionDenied {
+                return true;
+            }
+
+            #[cfg(unix)]
+            return io.raw_os_error() == Some(libc::EROFS);
+        }
+
+        false
+    })
 }
 
 /// Internal error for serde errors.
@@ -2216,6 +2689,46 @@ impl ConfigValue {
     }
 }
 
+/// Turns the value at `key` in `root` into an array, for the `--config`
+/// argument `arg` appending to it with `+=`.
+///
+/// A string is split on whitespace, like the strings of string lists.
+fn prepare_append(root: &mut CV, key: &ConfigKey, arg: &str) -> CargoResult<()> {
+    let mut cv = root;
+    for part in key.parts() {
+        cv = match cv {
+            CV::Table(table, _) => match table.get_mut(part) {
+                Some(cv) => cv,
+                None => return Ok(()),
+            },
+            // Merging reports the mismatch.
+            _ => return Ok(()),
+        };
+    }
+    match cv {
+        CV::List(..) => {}
+        CV::String(s, def) => {
+            let list = s
+                .split_whitespace()
+                .map(|s| (s.to_string(), def.clone()))
+                .collect();
+            *cv = CV::List(list, def.clone());
+        }
+        CV::Boolean(..) | CV::Integer(..) | CV::Table(..) => bail!(
+            "--config argument `{arg}` appends to `{key}`, \
+             but it is set to {} {} in {}, not an array",
+            if matches!(cv, CV::Integer(..)) {
+                "an"
+            } else {
+                "a"
+            },
+            cv.desc(),
+            cv.definition()
+        ),
+    }
+    Ok(())
+}
+
 pub fn homedir(cwd: &Path) -> Option<PathBuf> {
     ::home::cargo_home_with_cwd(cwd).ok()
 }
@@ -2376,15 +2889,111 @@ impl Drop for PackageCacheLock<'_> {
         let (_, cnt) = slot.as_mut().unwrap();
         *cnt -= 1;
         if *cnt == 0 {
-            *slot = None;
+            if let Some((Some(lock), _)) = slot.take() {
+                // Only the exclusive lock records its holder, which has to go
+                // away before the lock is released.
+                if lock.is_exclusive() && self.0.cli_unstable().lock_wait_timeout {
+                    let _ = paths::remove_file(self.0.package_cache_holder_path());
+                }
+            }
+        }
+    }
+}
+
+/// The process holding the exclusive package cache lock, as recorded in
+/// `$CARGO_HOME/.package-cache-holder`.
+///
+/// Only the name of the subcommand is recorded, since its arguments may
+/// contain secrets like `--token`.
+#[derive(Serialize, Deserialize)]
+struct PackageCacheHolder {
+    pid: u32,
+    hostname: Option<String>,
+    command: Option<String>,
+    /// When the lock was acquired, in seconds since the Unix epoch.
+    since: u64,
+}
+
+impl PackageCacheHolder {
+    fn current(config: &Config) -> PackageCacheHolder {
+        PackageCacheHolder {
+            pid: std::process::id(),
+            hostname: hostname(config),
+            command: config.subcommand.clone(),
+            since: unix_now(),
+        }
+    }
+
+    /// Records the holder at `path`, readable only by the current user. This
+    /// is only informational, so failing to do so is ignored.
+    fn write(&self, path: &Path) {
+        let res = serde_json::to_vec(self)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| {
+                let mut opts = OpenOptions::new();
+                opts.write(true).create(true).truncate(true);
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::OpenOptionsExt;
+                    opts.mode(0o600);
+                }
+                let mut file = opts.open(path)?;
+                file.write_all(&json)?;
+                Ok(())
+            });
+        if let Err(e) = res {
+            tracing::debug!("failed to record the package cache lock holder: {e:?}");
         }
     }
+
+    /// Describes the holder recorded at `path`, if any.
+    fn read(path: &Path) -> Option<String> {
+        let holder: PackageCacheHolder = serde_json::from_slice(&fs::read(path).ok()?).ok()?;
+        let command = match &holder.command {
+            Some(command) => format!("`cargo {command}`"),
+            None => "cargo".to_string(),
+        };
+        let host = match &holder.hostname {
+            Some(hostname) => format!(" on `{hostname}`"),
+            None => String::new(),
+        };
+        Some(format!(
+            "{} (pid {}{}) for {}s",
+            command,
+            holder.pid,
+            host,
+            unix_now().saturating_sub(holder.since)
+        ))
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg(unix)]
+fn hostname(_config: &Config) -> Option<String> {
+    let mut buf = [0u8; 256];
+    let r = unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) };
+    if r != 0 {
+        return None;
+    }
+    let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    Some(String::from_utf8_lossy(&buf[..len]).into_owned())
+}
+
+#[cfg(not(unix))]
+fn hostname(config: &Config) -> Option<String> {
+    config.get_env("COMPUTERNAME").ok()
 }
 
 #[derive(Debug, Default, Deserialize, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub struct CargoHttpConfig {
     pub proxy: Option<String>,
+    pub no_proxy: Option<StringList>,
     pub low_speed_limit: Option<u32>,
     pub timeout: Option<u64>,
     pub cainfo: Option<ConfigRelativePath>,
@@ -2393,6 +3002,44 @@ pub struct CargoHttpConfig {
     pub debug: Option<bool>,
     pub multiplexing: Option<bool>,
     pub ssl_version: Option<SslVersionConfig>,
+    pub ssl_client_cert: Option<SslClientCertConfig>,
+}
+
+/// The settings of a `[registries.<name>]` table which aren't about
+/// authentication, see [`auth::RegistryConfig`] for those.
+#[derive(Debug, Default)]
+pub struct RegistrySettings {
+    /// The key verifying the signatures of packages, with
+    /// `-Zcrate-signatures`.
+    pub signature_key: Option<String>,
+    /// The revision or date the index is pinned to, with `-Zindex-snapshots`.
+    pub snapshot: Option<String>,
+    /// The proxy of the registry, with `-Zproxy-config`.
+    pub proxy: Option<String>,
+    /// The client certificate of the registry, with `-Zclient-certs`.
+    pub client_cert: Option<SslClientCertConfig>,
+    /// Whether to publish with the OIDC token of the CI job, with
+    /// `-Ztrusted-publishing`.
+    pub trusted_publishing: Option<bool>,
+}
+
+/// A client certificate for mutual TLS, from `http.ssl-client-cert` or
+/// `registries.<name>.client-cert`.
+///
+/// ```toml
+/// [http.ssl-client-cert]
+/// cert = "client.pem"
+/// key = "client.key"
+/// ```
+///
+/// The `key-passphrase` is a secret, so it can only be set with an
+/// environment variable like `CARGO_HTTP_SSL_CLIENT_CERT_KEY_PASSPHRASE`.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct SslClientCertConfig {
+    pub cert: ConfigRelativePath,
+    pub key: Option<ConfigRelativePath>,
+    pub key_passphrase: Option<Value<Secret<String>>>,
 }
 
 #[derive(Debug, Default, Deserialize, PartialEq)]
@@ -2472,7 +3119,7 @@ pub struct CargoSshConfig {
 ///
 /// ```toml
 /// [build]
-/// jobs = "default" # Currently only support "default".
+/// jobs = "default" # Or "auto" with `-Zadaptive-jobs`.
 /// ```
 #[derive(Debug, Deserialize, Clone)]
 #[serde(untagged)]
@@ -2481,6 +3128,21 @@ pub enum JobsConfig {
     String(String),
 }
 
+/// Configuration for `build.adaptive-jobs`, the thresholds of
+/// `build.jobs = "auto"`.
+///
+/// ```toml
+/// [build.adaptive-jobs]
+/// max-load = 1.5          # load average per CPU
+/// min-free-memory = 1024  # MiB
+/// ```
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct AdaptiveJobsConfig {
+    pub max_load: Option<f64>,
+    pub min_free_memory: Option<u64>,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct CargoBuildConfig {
@@ -2489,15 +3151,45 @@ pub struct CargoBuildConfig {
     pub dep_info_basedir: Option<ConfigRelativePath>,
     pub target_dir: Option<ConfigRelativePath>,
     pub incremental: Option<bool>,
+    // unstable, `-Zincremental-dir`
+    pub incremental_dir: Option<ConfigRelativePath>,
     pub target: Option<BuildTargetConfig>,
     pub jobs: Option<JobsConfig>,
+    // unstable, `-Zadaptive-jobs`
+    pub adaptive_jobs: Option<AdaptiveJobsConfig>,
     pub rustflags: Option<StringList>,
     pub rustdocflags: Option<StringList>,
+    // unstable, `-Zrustflags-append`
+    pub rustflags_append: Option<StringList>,
+    pub rustflags_prepend: Option<StringList>,
+    pub rustdocflags_append: Option<StringList>,
+    pub rustdocflags_prepend: Option<StringList>,
     pub rustc_wrapper: Option<ConfigRelativePath>,
     pub rustc_workspace_wrapper: Option<ConfigRelativePath>,
     pub rustc: Option<ConfigRelativePath>,
     pub rustdoc: Option<ConfigRelativePath>,
-    pub out_dir: Option<ConfigRelativePath>,
+    pub out_dir: Option<BuildOutDirConfig>,
+    // unstable, `-Zartifact-link`
+    pub artifact_link: Option<ArtifactLink>,
+    // unstable, `-Zcancellation`
+    pub timeout: Option<u64>,
+    // unstable, `-Zprefer-fast-linker`
+    pub prefer_fast_linker: Option<bool>,
+}
+
+/// A `[target-overrides.<spec>]` table, with the flags passed to rustc when
+/// compiling the packages matching `<spec>`.
+///
+/// ```toml
+/// [target-overrides.foo]
+/// rustflags = ["-A", "deprecated"]
+/// cfgs = ["foo_internal"]
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TargetOverrideConfig {
+    pub rustflags: Option<StringList>,
+    pub cfgs: Option<StringList>,
 }
 
 /// Configuration for `build.target`.
@@ -2549,20 +3241,171 @@ impl BuildTargetConfig {
     }
 }
 
+/// Configuration for `build.out-dir`.
+///
+/// Accepts in the following forms:
+///
+/// ```toml
+/// out-dir = "out"
+/// out-dir = { bins = "dist/bin", cdylibs = "dist/lib" }
+/// out-dir = { all = "out", wasm = "dist/web" }
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(transparent)]
+pub struct BuildOutDirConfig {
+    inner: Value<BuildOutDirConfigInner>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum BuildOutDirConfigInner {
+    All(String),
+    PerKind(BTreeMap<String, String>),
+}
+
+impl BuildOutDirConfig {
+    /// Resolves the configured directories relative to the config file
+    /// they were defined in.
+    pub fn resolve(&self, config: &Config) -> CargoResult<ExportDirs> {
+        let root = self.inner.definition.root(config);
+        let dirs = match &self.inner.val {
+            BuildOutDirConfigInner::All(path) => ExportDirs::single(root.join(path)),
+            BuildOutDirConfigInner::PerKind(map) => {
+                let mut dirs = ExportDirs::default();
+                for (kind, path) in map {
+                    let slot = match kind.as_str() {
+                        "all" => &mut dirs.all,
+                        "bins" => &mut dirs.bins,
+                        "cdylibs" => &mut dirs.cdylibs,
+                        "dylibs" => &mut dirs.dylibs,
+                        "staticlibs" => &mut dirs.staticlibs,
+                        "rlibs" => &mut dirs.rlibs,
+                        "wasm" => &mut dirs.wasm,
+                        _ => bail!(
+                            "unknown artifact kind `{}` in `build.out-dir` (defined in {}), \
+                             expected one of `all`, `bins`, `cdylibs`, `dylibs`, \
+                             `staticlibs`, `rlibs` or `wasm`",
+                            kind,
+                            self.inner.definition
+                        ),
+                    };
+                    *slot = Some(root.join(path));
+                }
+                dirs
+            }
+        };
+        Ok(dirs)
+    }
+}
+
+/// Configuration for `build.artifact-link`, how final artifacts are placed
+/// in the target directory.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ArtifactLink {
+    Hardlink,
+    Symlink,
+    Copy,
+}
+
+impl ArtifactLink {
+    pub fn strategy(self) -> LinkStrategy {
+        match self {
+            ArtifactLink::Hardlink => LinkStrategy::Hardlink,
+            ArtifactLink::Symlink => LinkStrategy::Symlink,
+            ArtifactLink::Copy => LinkStrategy::Copy,
+        }
+    }
+}
+
 #[derive(Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
 struct TermConfig {
     verbose: Option<bool>,
     quiet: Option<bool>,
-    color: Option<String>,
+    #[serde(default)]
+    #[serde(deserialize_with = "color_or_streams")]
+    color: Option<ColorConfig>,
+    language: Option<String>,
+    width: Option<usize>,
+    hyperlinks: Option<bool>,
+    // unstable, `-Zverbose-config-resolution`
+    verbose_config_resolution: Option<bool>,
     #[serde(default)]
     #[serde(deserialize_with = "progress_or_string")]
     progress: Option<ProgressConfig>,
 }
 
+/// The `term.color` config, a color choice for both stdout and stderr, or a
+/// table with one for each of them with `-Zstream-color`.
+#[derive(Debug)]
+enum ColorConfig {
+    Both(String),
+    Streams {
+        stdout: Option<String>,
+        stderr: Option<String>,
+    },
+}
+
+fn color_or_streams<'de, D>(deserializer: D) -> Result<Option<ColorConfig>, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+{
+    struct ColorVisitor;
+
+    #[derive(Deserialize)]
+    struct StreamColors {
+        stdout: Option<String>,
+        stderr: Option<String>,
+    }
+
+    impl<'de> serde::de::Visitor<'de> for ColorVisitor {
+        type Value = Option<ColorConfig>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("a string (\"auto\", \"always\" or \"never\") or a table")
+        }
+
+        fn visit_str<E>(self, s: &str) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(Some(ColorConfig::Both(s.to_string())))
+        }
+
+        fn visit_none<E>(self) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(None)
+        }
+
+        fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: serde::de::Deserializer<'de>,
+        {
+            deserializer.deserialize_struct(de::STRING_OR_TABLE, &["stdout", "stderr"], self)
+        }
+
+        fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+        where
+            A: serde::de::MapAccess<'de>,
+        {
+            let StreamColors { stdout, stderr } =
+                StreamColors::deserialize(serde::de::value::MapAccessDeserializer::new(map))?;
+            Ok(Some(ColorConfig::Streams { stdout, stderr }))
+        }
+    }
+
+    deserializer.deserialize_option(ColorVisitor)
+}
+
 #[derive(Debug, Default, Deserialize)]
 pub struct ProgressConfig {
     pub when: ProgressWhen,
     pub width: Option<usize>,
+    #[serde(default)]
+    pub style: ProgressDisplay,
 }
 
 #[derive(Debug, Default, Deserialize)]
@@ -2574,6 +3417,18 @@ pub enum ProgressWhen {
     Always,
 }
 
+/// How progress is displayed, with `-Zplain-progress`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProgressDisplay {
+    /// A bar redrawn in place.
+    #[default]
+    Fancy,
+    /// Lines of text printed every few seconds, without moving the cursor,
+    /// for screen readers.
+    Plain,
+}
+
 fn progress_or_string<'de, D>(deserializer: D) -> Result<Option<ProgressConfig>, D::Error>
 where
     D: serde::de::Deserializer<'de>,
@@ -2584,25 +3439,25 @@ where
         type Value = Option<ProgressConfig>;
 
         fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-            formatter.write_str("a string (\"auto\" or \"never\") or a table")
+            formatter.write_str("a string (\"auto\", \"never\", \"plain\" or \"fancy\") or a table")
         }
 
         fn visit_str<E>(self, s: &str) -> Result<Self::Value, E>
         where
             E: serde::de::Error,
         {
-            match s {
-                "auto" => Ok(Some(ProgressConfig {
-                    when: ProgressWhen::Auto,
-                    width: None,
-                })),
-                "never" => Ok(Some(ProgressConfig {
-                    when: ProgressWhen::Never,
-                    width: None,
-                })),
-                "always" => Err(E::custom("\"always\" progress requires a `width` key")),
-                _ => Err(E::unknown_variant(s, &["auto", "never"])),
-            }
+            let (when, style) = match s {
+                "auto" | "fancy" => (ProgressWhen::Auto, ProgressDisplay::Fancy),
+                "never" => (ProgressWhen::Never, ProgressDisplay::Fancy),
+                "plain" => (ProgressWhen::Auto, ProgressDisplay::Plain),
+                "always" => return Err(E::custom("\"always\" progress requires a `width` key")),
+                _ => return Err(E::unknown_variant(s, &["auto", "never", "plain", "fancy"])),
+            };
+            Ok(Some(ProgressConfig {
+                when,
+                width: None,
+                style,
+            }))
         }
 
         fn visit_none<E>(self) -> Result<Self::Value, E>
@@ -2616,10 +3471,19 @@ where
         where
             D: serde::de::Deserializer<'de>,
         {
-            let pc = ProgressConfig::deserialize(deserializer)?;
+            deserializer.deserialize_struct(de::STRING_OR_TABLE, &["when", "width", "style"], self)
+        }
+
+        fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+        where
+            A: serde::de::MapAccess<'de>,
+        {
+            let pc =
+                ProgressConfig::deserialize(serde::de::value::MapAccessDeserializer::new(map))?;
             if let ProgressConfig {
                 when: ProgressWhen::Always,
                 width: None,
+                style: ProgressDisplay::Fancy,
             } = pc
             {
                 return Err(serde::de::Error::custom(
@@ -2691,7 +3555,7 @@ pub type EnvConfig = HashMap<String, EnvConfigValue>;
 /// a = 'a b c'
 /// b = ['a', 'b', 'c']
 /// ```
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct StringList(Vec<String>);
 
 impl StringList {
@@ -2786,7 +3650,7 @@ fn disables_multiplexing_for_bad_curl(
             .iter()
             .any(|v| curl_version.starts_with(v))
         {
-            log::info!("disabling multiplexing with proxy, curl version is {curl_version}");
+            tracing::info!("disabling multiplexing with proxy, curl version is {curl_version}");
             http.multiplexing = Some(false);
         }
     }
@@ -0,0 +1,100 @@
+//! Metadata of external subcommands registered in `$CARGO_HOME/plugins.toml`,
+//! with `-Zplugin-manifest`.
+//!
+//! External subcommands are still found as `cargo-<name>` executables in
+//! `PATH`, the manifest only describes them:
+//!
+//! ```toml
+//! [subcommands.foo]
+//! description = "Does foo things"
+//! min-cargo-version = "1.70"
+//! completions = ["--check", "--all"]
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{bail, Context as _};
+use cargo_util::paths;
+use serde::de::IntoDeserializer as _;
+use serde::Deserialize;
+
+use super::Config;
+use crate::util::errors::CargoResult;
+
+/// The name of the manifest in `$CARGO_HOME`.
+pub const PLUGIN_MANIFEST: &str = "plugins.toml";
+
+/// The contents of `$CARGO_HOME/plugins.toml`.
+#[derive(Debug, Default, Deserialize)]
+pub struct PluginManifest {
+    /// Metadata of external subcommands, by the name they are invoked with.
+    #[serde(default)]
+    pub subcommands: BTreeMap<String, SubcommandInfo>,
+}
+
+/// Metadata of an external subcommand.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SubcommandInfo {
+    /// Shown by `cargo --list`.
+    pub description: Option<String>,
+    /// The oldest version of Cargo the subcommand works with, like `1.70`.
+    pub min_cargo_version: Option<String>,
+    /// Arguments the shell completions offer for the subcommand.
+    #[serde(default)]
+    pub completions: Vec<String>,
+}
+
+impl SubcommandInfo {
+    /// Returns the `min-cargo-version` if the running Cargo is older than it.
+    pub fn unmet_min_cargo_version(&self) -> Option<&str> {
+        let min = self.min_cargo_version.as_deref()?;
+        let req = semver::VersionReq::parse(&format!(">={min}")).ok()?;
+        let current = semver::Version::parse(&crate::version().version).ok()?;
+        // Pre-release identifiers like `-nightly` don't make Cargo older.
+        let current = semver::Version::new(current.major, current.minor, current.patch);
+        (!req.matches(&current)).then_some(min)
+    }
+}
+
+impl Config {
+    /// Loads and validates `$CARGO_HOME/plugins.toml`, which is empty if the
+    /// file doesn't exist. Unknown keys are only warned about, so newer
+    /// manifests keep working.
+    pub fn plugin_manifest(&self) -> CargoResult<PluginManifest> {
+        let path = self.home().as_path_unlocked().join(PLUGIN_MANIFEST);
+        if !path.exists() {
+            return Ok(PluginManifest::default());
+        }
+        let contents = paths::read(&path)?;
+        let load = || -> CargoResult<(PluginManifest, BTreeSet<String>)> {
+            let toml: toml::Table = contents.parse()?;
+            let mut unused = BTreeSet::new();
+            let manifest: PluginManifest =
+                serde_ignored::deserialize(toml.into_deserializer(), |key| {
+                    unused.insert(key.to_string());
+                })?;
+            for (name, info) in &manifest.subcommands {
+                if let Some(version) = &info.min_cargo_version {
+                    // Like `rust-version`, without semver operators.
+                    let valid = semver::VersionReq::parse(version).is_ok()
+                        && version.chars().all(|c| c.is_ascii_digit() || c == '.');
+                    if !valid {
+                        bail!(
+                            "`min-cargo-version` of subcommand `{name}` must be a value \
+                             like \"1.70\", found `{version}`"
+                        );
+                    }
+                }
+            }
+            Ok((manifest, unused))
+        };
+        let (manifest, unused) =
+            load().with_context(|| format!("failed to load `{}`", path.display()))?;
+        for key in unused {
+            self.shell()
+                .warn(format!("unused key `{key}` in `{}`", path.display()))?;
+        }
+        Ok(manifest)
+    }
+}
@@ -0,0 +1,397 @@
+//! The schema of the known config keys, for `cargo --explain-config` and
+//! `cargo --schema-json`.
+//!
+//! [`known_keys`] are parsed from the [config reference], which Cargo is built
+//! with. Keys are written like in the reference, with placeholders like
+//! `<name>` for the parts chosen by the user.
+//!
+//! [config reference]: https://doc.rust-lang.org/nightly/cargo/reference/config.html
+
+use std::sync::OnceLock;
+
+use serde_json::{json, Map, Value};
+
+/// The schema of a config key, as documented in the config reference.
+#[derive(Debug)]
+pub struct KeySchema {
+    /// The key, like `build.jobs` or `registries.<name>.index`.
+    pub key: String,
+    /// The type, like `string (path)` or `integer or boolean`, or `table` for
+    /// tables of other keys.
+    pub ty: String,
+    pub default: Option<String>,
+    /// Environment variables which set the key.
+    pub env: Vec<String>,
+    /// The first paragraph of the documentation of the key.
+    pub doc: String,
+    /// Whether the key is no longer used by Cargo.
+    pub deprecated: bool,
+}
+
+impl KeySchema {
+    /// Whether `key` refers to this key, with placeholders matching any part
+    /// of `key`, except `<cfg>` which only matches `cfg()` expressions.
+    fn matches(&self, key: &str) -> bool {
+        let mut parts = key.split('.');
+        let mut schema_parts = self.key.split('.');
+        loop {
+            match (parts.next(), schema_parts.next()) {
+                (None, None) => return true,
+                (Some(part), Some(schema_part)) => {
+                    let part = part.trim_matches(|c| c == '\'' || c == '"');
+                    let is_cfg = part.starts_with("cfg(");
+                    let matches = match schema_part {
+                        "<cfg>" => is_cfg,
+                        "<triple>" => !is_cfg,
+                        p if p.starts_with('<') => true,
+                        p => p == part,
+                    };
+                    if !matches {
+                        return false;
+                    }
+                }
+                _ => return false,
+            }
+        }
+    }
+
+    /// Gets the JSON schema of the value of this key.
+    pub fn json_schema(&self) -> Value {
+        let types: Vec<_> = self
+            .ty
+            .split(" or ")
+            .filter_map(|ty| {
+                if ty.starts_with("array of strings") {
+                    Some(json!({ "type": "array", "items": { "type": "string" } }))
+                } else if ty.starts_with("string") {
+                    Some(json!({ "type": "string" }))
+                } else if ty.starts_with("bool") {
+                    Some(json!({ "type": "boolean" }))
+                } else if ty.starts_with("integer") {
+                    Some(json!({ "type": "integer" }))
+                } else if ty.ends_with("table") {
+                    Some(json!({ "type": "object" }))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        let mut schema = match <[Value; 1]>::try_from(types) {
+            Ok([ty]) => ty,
+            Err(types) if types.is_empty() => json!({}),
+            Err(types) => json!({ "anyOf": types }),
+        };
+        let schema_map = schema.as_object_mut().unwrap();
+        schema_map.insert("description".to_string(), self.doc.as_str().into());
+        if let Some(default) = &self.default {
+            schema_map.insert("x-default".to_string(), default.as_str().into());
+        }
+        if self.deprecated {
+            schema_map.insert("deprecated".to_string(), true.into());
+        }
+        schema
+    }
+}
+
+/// Finds the schema of `key`, which is either a key like `build.jobs`, or one
+/// with placeholders like `registries.<name>.index`.
+///
+/// Keys matching exactly are preferred over the ones matching placeholders,
+/// like `registries.crates-io.protocol` over `registries.<name>.index`.
+pub fn find(key: &str) -> Option<&'static KeySchema> {
+    let known_keys = known_keys();
+    known_keys
+        .iter()
+        .find(|schema| schema.key == key)
+        .or_else(|| known_keys.iter().find(|schema| schema.matches(key)))
+}
+
+/// Builds the JSON schema of a `.cargo/config.toml` file from
+/// [`known_keys`].
+pub fn json_schema() -> Value {
+    let mut root = json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "Cargo configuration",
+        "type": "object",
+    });
+    for schema in known_keys() {
+        let mut node = &mut root;
+        for part in schema.key.split('.') {
+            let obj = node.as_object_mut().unwrap();
+            obj.insert("type".to_string(), "object".into());
+            node = match part {
+                "<cfg>" => obj
+                    .entry("patternProperties")
+                    .or_insert_with(|| Value::Object(Map::new()))
+                    .as_object_mut()
+                    .unwrap()
+                    .entry(r"^cfg\(")
+                    .or_insert_with(|| json!({})),
+                p if p.starts_with('<') => obj
+                    .entry("additionalProperties")
+                    .or_insert_with(|| json!({})),
+                p => obj
+                    .entry("properties")
+                    .or_insert_with(|| Value::Object(Map::new()))
+                    .as_object_mut()
+                    .unwrap()
+                    .entry(p)
+                    .or_insert_with(|| json!({})),
+            };
+        }
+        let leaf = schema.json_schema();
+        let node = node.as_object_mut().unwrap();
+        for (k, v) in leaf.as_object().unwrap() {
+            node.insert(k.clone(), v.clone());
+        }
+    }
+    root
+}
+
+/// The config reference, which documents each key under a heading with its
+/// type, default and environment variables.
+const REFERENCE: &str = include_str!("../../../doc/src/reference/config.md");
+
+/// The config keys documented in the config reference.
+pub fn known_keys() -> &'static [KeySchema] {
+    static KNOWN_KEYS: OnceLock<Vec<KeySchema>> = OnceLock::new();
+    KNOWN_KEYS.get_or_init(|| parse_reference(REFERENCE))
+}
+
+/// Parses the keys of the config reference, whose headings are the keys in
+/// backticks, starting with `paths`. A table like `[build]` is a key of type
+/// `table`, unless it documents the type of its entries, like `[alias]`.
+fn parse_reference(reference: &str) -> Vec<KeySchema> {
+    let mut keys: Vec<KeySchema> = Vec::new();
+    let mut lines = reference
+        .lines()
+        .skip_while(|line| key_heading(line) != Some("paths"))
+        .peekable();
+    while let Some(heading) = lines.next().and_then(key_heading) {
+        let mut section = Vec::new();
+        let mut in_code = false;
+        while let Some(line) = lines.next_if(|line| in_code || !line.starts_with('#')) {
+            in_code ^= line.starts_with("```");
+            section.push(line);
+        }
+        while lines.next_if(|line| key_heading(line).is_none()).is_some() {}
+
+        let (bullets, doc) = parse_section(&section);
+        let field = |name: &str| bullets.iter().find_map(|b| b.strip_prefix(name));
+        let is_table = heading.starts_with('[');
+        let mut key = heading.trim_matches(|c| c == '[' || c == ']').to_string();
+        let mut ty = field("type: ").map(String::from);
+        if is_table && ty.is_some() {
+            key.push_str(".<name>");
+        }
+        let deprecated = ty.is_none() && (doc.contains("deprecated") || doc.contains("no longer"));
+        if deprecated {
+            ty = Some("any".to_string());
+        } else if ty.is_none() && key.contains("<cfg>") {
+            let triple_key = key.replace("<cfg>", "<triple>");
+            ty = keys
+                .iter()
+                .find(|k| k.key == triple_key)
+                .map(|k| k.ty.clone());
+        }
+        let default = field("default: ")
+            .filter(|default| *default != "none")
+            .map(|default| {
+                let code = default.strip_prefix('`').and_then(|d| d.strip_suffix('`'));
+                code.filter(|code| !code.contains('`')).unwrap_or(default)
+            });
+        let env = field("environment: ")
+            .map(|env| {
+                env.split('`')
+                    .skip(1)
+                    .step_by(2)
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+        keys.push(KeySchema {
+            key,
+            ty: ty.unwrap_or_else(|| "table".to_string()),
+            default: default.map(String::from),
+            env,
+            doc,
+            deprecated,
+        });
+    }
+    keys
+}
+
+/// Gets the text of a heading like ``#### `build.jobs` ``.
+fn key_heading(line: &str) -> Option<&str> {
+    line.trim_start_matches('#')
+        .strip_prefix(" `")
+        .filter(|_| line.starts_with("##"))
+        .and_then(|heading| heading.strip_suffix('`'))
+}
+
+/// Splits the lines of the section of a key into its lowercase `* Type: …`
+/// like bullets, and its first paragraph, without links.
+fn parse_section(section: &[&str]) -> (Vec<String>, String) {
+    let mut bullets: Vec<String> = Vec::new();
+    let mut doc = String::new();
+    for line in section {
+        if line.trim().is_empty() {
+            if !doc.is_empty() {
+                break;
+            }
+        } else if !doc.is_empty() {
+            let sep = if line.starts_with("* ") { '\n' } else { ' ' };
+            doc.push(sep);
+            doc.push_str(line.trim());
+        } else if let Some(bullet) = line.strip_prefix("* ") {
+            let bullet = match bullet.split_once(": ") {
+                Some((name, value)) => format!("{}: {value}", name.to_lowercase()),
+                None => bullet.to_string(),
+            };
+            bullets.push(bullet);
+        } else if let (Some(bullet), true) = (bullets.last_mut(), line.starts_with("  ")) {
+            bullet.push(' ');
+            bullet.push_str(line.trim());
+        } else {
+            doc.push_str(line.trim());
+        }
+    }
+    let bullets = bullets.iter().map(|b| strip_links(b)).collect();
+    (bullets, strip_links(&doc))
+}
+
+/// Replaces the Markdown links of `text`, like `[text](url)` or
+/// `[text][ref]`, by their text, keeping the brackets inside code spans.
+fn strip_links(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(i) = rest.find(|c| c == '[' || c == '`') {
+        out.push_str(&rest[..i]);
+        rest = &rest[i..];
+        if rest.starts_with('`') {
+            let end = rest[1..].find('`').map_or(rest.len(), |end| end + 2);
+            out.push_str(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+        let Some(end) = link_text_end(rest) else {
+            out.push('[');
+            rest = &rest[1..];
+            continue;
+        };
+        out.push_str(&strip_links(&rest[1..end]));
+        rest = &rest[end + 1..];
+        let target_end = match rest.chars().next() {
+            Some('(') => rest.find(')'),
+            Some('[') => rest.find(']'),
+            _ => None,
+        };
+        if let Some(target_end) = target_end {
+            rest = &rest[target_end + 1..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Finds the `]` closing the `[` starting `text`, skipping code spans.
+fn link_text_end(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut in_code = false;
+    for (i, c) in text.char_indices() {
+        match c {
+            '`' => in_code = !in_code,
+            '[' if !in_code => depth += 1,
+            ']' if !in_code => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reference_keys() {
+        let find = |key| {
+            known_keys()
+                .iter()
+                .find(|schema| schema.key == key)
+                .unwrap()
+        };
+
+        let jobs = find("build.jobs");
+        assert_eq!(jobs.ty, "integer or string");
+        assert_eq!(jobs.default.as_deref(), Some("number of logical CPUs"));
+        assert_eq!(jobs.env, ["CARGO_BUILD_JOBS"]);
+        assert!(jobs
+            .doc
+            .starts_with("Sets the maximum number of compiler processes"));
+
+        let rustc = find("build.rustc");
+        assert_eq!(rustc.env, ["CARGO_BUILD_RUSTC", "RUSTC"]);
+        assert_eq!(rustc.default.as_deref(), Some("\"rustc\""));
+        assert_eq!(
+            find("registries.crates-io.protocol").default.as_deref(),
+            Some("sparse")
+        );
+        assert_eq!(find("registries.<name>.index").default, None);
+
+        let alias = find("alias.<name>");
+        assert_eq!(alias.ty, "string or array of strings");
+        assert_eq!(find("build").ty, "table");
+        assert_eq!(find("env").ty, "table");
+
+        let runner = find("target.<cfg>.runner");
+        assert_eq!(
+            runner.ty,
+            "string or array of strings (program path with args)"
+        );
+        assert!(runner
+            .doc
+            .starts_with("This is similar to the target runner, but using a `cfg()` expression."));
+
+        let pipelining = find("build.pipelining");
+        assert!(pipelining.deprecated);
+        assert_eq!(pipelining.ty, "any");
+
+        assert!(find("patch").doc.starts_with(
+            "Just as you can override dependencies using `[patch]` in `Cargo.toml`, you can"
+        ));
+        let build_override = find("profile.<name>.build-override");
+        assert_eq!(build_override.ty, "table");
+        assert_eq!(
+            build_override.env,
+            ["CARGO_PROFILE_<name>_BUILD_OVERRIDE_<key>"]
+        );
+    }
+
+    #[test]
+    fn find_keys() {
+        assert_eq!(find("build.jobs").unwrap().key, "build.jobs");
+        assert_eq!(
+            find("registries.my-registry.index").unwrap().key,
+            "registries.<name>.index"
+        );
+        assert_eq!(
+            find("registries.crates-io.protocol").unwrap().key,
+            "registries.crates-io.protocol"
+        );
+        assert_eq!(
+            find("target.x86_64-unknown-linux-gnu.runner").unwrap().key,
+            "target.<triple>.runner"
+        );
+        assert_eq!(
+            find("target.'cfg(unix)'.runner").unwrap().key,
+            "target.<cfg>.runner"
+        );
+        assert!(find("build.jobz").is_none());
+    }
+}
@@ -34,7 +34,7 @@ impl<'a> Credential for CredentialProcessCredential {
         cmd.stdout(Stdio::piped());
         cmd.stdin(Stdio::piped());
         cmd.arg("--cargo-plugin");
-        log::debug!("credential-process: {cmd:?}");
+        tracing::debug!("credential-process: {cmd:?}");
         let mut child = cmd.spawn().map_err(|e| {
             cargo_credential::Error::Subprocess(format!(
                 "failed to spawn credential process `{}`: {e}",
@@ -46,7 +46,7 @@ impl<'a> Credential for CredentialProcessCredential {
         let mut buffer = String::new();
         output_from_child.read_line(&mut buffer)?;
         let credential_hello: CredentialHello = serde_json::from_str(&buffer)?;
-        log::debug!("credential-process > {credential_hello:?}");
+        tracing::debug!("credential-process > {credential_hello:?}");
 
         let req = CredentialRequest {
             v: cargo_credential::PROTOCOL_VERSION_1,
@@ -55,14 +55,14 @@ impl<'a> Credential for CredentialProcessCredential {
             args: args.to_vec(),
         };
         let request = serde_json::to_string(&req)?;
-        log::debug!("credential-process < {req:?}");
+        tracing::debug!("credential-process < {req:?}");
         writeln!(input_to_child, "{request}")?;
 
         buffer.clear();
         output_from_child.read_line(&mut buffer)?;
         let response: Result<CredentialResponse, cargo_credential::Error> =
             serde_json::from_str(&buffer)?;
-        log::debug!("credential-process > {response:?}");
+        tracing::debug!("credential-process > {response:?}");
         drop(input_to_child);
         let status = child.wait().expect("credential process never started");
         if !status.success() {
@@ -73,7 +73,7 @@ impl<'a> Credential for CredentialProcessCredential {
             ))
             .into());
         }
-        log::trace!("credential process exited successfully");
+        tracing::trace!("credential process exited successfully");
         response
     }
 }
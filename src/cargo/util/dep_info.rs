@@ -0,0 +1,333 @@
+//! Reading and writing dep-info files.
+//!
+//! Cargo deals with three kinds of dep-info files, see the [fingerprint
+//! module] for how they are used:
+//!
+//! * The `.d` files generated by `rustc`, read with [`parse_rustc_dep_info`].
+//! * The binary files stored in the fingerprint directory, which Cargo
+//!   translates the first ones into, read with [`parse_dep_info`].
+//! * The `.d` files written next to the artifacts in the output directory,
+//!   for build system integration. They have the same format as the ones of
+//!   `rustc`, but their paths are relative to `build.dep-info-basedir` when
+//!   it is set (see [`render_filename`]), which [`RustcDepInfo::resolve`]
+//!   undoes.
+//!
+//! External build tools can use this module to read the files Cargo
+//! generates the way Cargo reads them.
+//!
+//! [fingerprint module]: crate::core::compiler::fingerprint#dep-info-files
+
+use std::path::{Path, PathBuf};
+use std::str;
+
+use anyhow::bail;
+use cargo_util::paths;
+
+use crate::core::Workspace;
+use crate::util::errors::CargoResult;
+use crate::util::internal;
+
+/// The representation of the `.d` dep-info file generated by rustc
+#[derive(Debug, Default)]
+pub struct RustcDepInfo {
+    /// The list of files that the main target in the dep-info file depends on.
+    pub files: Vec<PathBuf>,
+    /// The list of environment variables we found that the rustc compilation
+    /// depends on.
+    ///
+    /// The first element of the pair is the name of the env var and the second
+    /// item is the value. `Some` means that the env var was set, and `None`
+    /// means that the env var wasn't actually set and the compilation depends
+    /// on it not being set.
+    pub env: Vec<(String, Option<String>)>,
+}
+
+impl RustcDepInfo {
+    /// Parses the contents of a `.d` dep-info file.
+    pub fn parse(contents: &str) -> CargoResult<RustcDepInfo> {
+        let mut ret = RustcDepInfo::default();
+        let mut found_deps = false;
+
+        for line in contents.lines() {
+            if let Some(rest) = line.strip_prefix("# env-dep:") {
+                let mut parts = rest.splitn(2, '=');
+                let env_var = match parts.next() {
+                    Some(s) => s,
+                    None => continue,
+                };
+                let env_val = match parts.next() {
+                    Some(s) => Some(unescape_env(s)?),
+                    None => None,
+                };
+                ret.env.push((unescape_env(env_var)?, env_val));
+            } else if let Some(pos) = line.find(": ") {
+                if found_deps {
+                    continue;
+                }
+                found_deps = true;
+                let mut deps = line[pos + 2..].split_whitespace();
+
+                while let Some(s) = deps.next() {
+                    let mut file = s.to_string();
+                    while file.ends_with('\\') {
+                        file.pop();
+                        file.push(' ');
+                        file.push_str(deps.next().ok_or_else(|| {
+                            internal("malformed dep-info format, trailing \\".to_string())
+                        })?);
+                    }
+                    ret.files.push(paths::normalize_verbatim(Path::new(&file)));
+                }
+            }
+        }
+        return Ok(ret);
+
+        // rustc tries to fit env var names and values all on a single line, which
+        // means it needs to escape `\r` and `\n`. The escape syntax used is "\n"
+        // which means that `\` also needs to be escaped.
+        fn unescape_env(s: &str) -> CargoResult<String> {
+            let mut ret = String::with_capacity(s.len());
+            let mut chars = s.chars();
+            while let Some(c) = chars.next() {
+                if c != '\\' {
+                    ret.push(c);
+                    continue;
+                }
+                match chars.next() {
+                    Some('\\') => ret.push('\\'),
+                    Some('n') => ret.push('\n'),
+                    Some('r') => ret.push('\r'),
+                    Some(c) => bail!("unknown escape character `{}`", c),
+                    None => bail!("unterminated escape character"),
+                }
+            }
+            Ok(ret)
+        }
+    }
+
+    /// Joins the relative paths of the files to `basedir`.
+    ///
+    /// This makes the paths of a dep-info file written by Cargo with
+    /// `build.dep-info-basedir` absolute again.
+    pub fn resolve(&mut self, basedir: &Path) {
+        for file in &mut self.files {
+            if file.is_relative() {
+                *file = paths::normalize_path(&basedir.join(&*file));
+            }
+        }
+    }
+}
+
+/// Parse the `.d` dep-info file generated by rustc.
+pub fn parse_rustc_dep_info(rustc_dep_info: &Path) -> CargoResult<RustcDepInfo> {
+    let contents = paths::read(rustc_dep_info)?;
+    RustcDepInfo::parse(&contents)
+}
+
+/// Parses Cargo's internal [`EncodedDepInfo`] structure that was previously
+/// serialized to disk.
+///
+/// Note that this is not rustc's `*.d` files.
+///
+/// Also note that rustc's `*.d` files are translated to Cargo-specific
+/// `EncodedDepInfo` files after compilations have finished in
+/// [`translate_dep_info`].
+///
+/// Returns `None` if the file is corrupt or couldn't be read from disk. This
+/// indicates that the crate should likely be rebuilt.
+///
+/// [`translate_dep_info`]: crate::core::compiler::fingerprint::translate_dep_info
+pub fn parse_dep_info(
+    pkg_root: &Path,
+    target_root: &Path,
+    dep_info: &Path,
+) -> CargoResult<Option<RustcDepInfo>> {
+    let data = match paths::read_bytes(dep_info) {
+        Ok(data) => data,
+        Err(_) => return Ok(None),
+    };
+    let info = match EncodedDepInfo::parse(&data) {
+        Some(info) => info,
+        None => {
+            tracing::warn!("failed to parse cargo's dep-info at {:?}", dep_info);
+            return Ok(None);
+        }
+    };
+    let mut ret = RustcDepInfo::default();
+    ret.env = info.env;
+    ret.files.extend(info.files.into_iter().map(|(ty, path)| {
+        match ty {
+            DepInfoPathType::PackageRootRelative => pkg_root.join(path),
+            // N.B. path might be absolute here in which case the join will have no effect
+            DepInfoPathType::TargetRootRelative => target_root.join(path),
+        }
+    }));
+    Ok(Some(ret))
+}
+
+/// Tells the associated path in [`EncodedDepInfo::files`] is relative to package root,
+/// target root, or absolute.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DepInfoPathType {
+    /// src/, e.g. src/lib.rs
+    PackageRootRelative,
+    /// target/debug/deps/lib...
+    /// or an absolute path /.../sysroot/...
+    TargetRootRelative,
+}
+
+/// Same as [`RustcDepInfo`] except avoids absolute paths as much as possible to
+/// allow moving around the target directory.
+///
+/// This is also stored in an optimized format to make parsing it fast because
+/// Cargo will read it for crates on all future compilations.
+#[derive(Debug, Default)]
+pub struct EncodedDepInfo {
+    pub files: Vec<(DepInfoPathType, PathBuf)>,
+    pub env: Vec<(String, Option<String>)>,
+}
+
+impl EncodedDepInfo {
+    pub fn parse(mut bytes: &[u8]) -> Option<EncodedDepInfo> {
+        let bytes = &mut bytes;
+        let nfiles = read_usize(bytes)?;
+        let mut files = Vec::with_capacity(nfiles as usize);
+        for _ in 0..nfiles {
+            let ty = match read_u8(bytes)? {
+                0 => DepInfoPathType::PackageRootRelative,
+                1 => DepInfoPathType::TargetRootRelative,
+                _ => return None,
+            };
+            let bytes = read_bytes(bytes)?;
+            files.push((ty, paths::bytes2path(bytes).ok()?));
+        }
+
+        let nenv = read_usize(bytes)?;
+        let mut env = Vec::with_capacity(nenv as usize);
+        for _ in 0..nenv {
+            let key = str::from_utf8(read_bytes(bytes)?).ok()?.to_string();
+            let val = match read_u8(bytes)? {
+                0 => None,
+                1 => Some(str::from_utf8(read_bytes(bytes)?).ok()?.to_string()),
+                _ => return None,
+            };
+            env.push((key, val));
+        }
+        return Some(EncodedDepInfo { files, env });
+
+        fn read_usize(bytes: &mut &[u8]) -> Option<usize> {
+            let ret = bytes.get(..4)?;
+            *bytes = &bytes[4..];
+            Some(u32::from_le_bytes(ret.try_into().unwrap()) as usize)
+        }
+
+        fn read_u8(bytes: &mut &[u8]) -> Option<u8> {
+            let ret = *bytes.get(0)?;
+            *bytes = &bytes[1..];
+            Some(ret)
+        }
+
+        fn read_bytes<'a>(bytes: &mut &'a [u8]) -> Option<&'a [u8]> {
+            let n = read_usize(bytes)? as usize;
+            let ret = bytes.get(..n)?;
+            *bytes = &bytes[n..];
+            Some(ret)
+        }
+    }
+
+    pub fn serialize(&self) -> CargoResult<Vec<u8>> {
+        let mut ret = Vec::new();
+        let dst = &mut ret;
+        write_usize(dst, self.files.len());
+        for (ty, file) in self.files.iter() {
+            match ty {
+                DepInfoPathType::PackageRootRelative => dst.push(0),
+                DepInfoPathType::TargetRootRelative => dst.push(1),
+            }
+            write_bytes(dst, paths::path2bytes(file)?);
+        }
+
+        write_usize(dst, self.env.len());
+        for (key, val) in self.env.iter() {
+            write_bytes(dst, key);
+            match val {
+                None => dst.push(0),
+                Some(val) => {
+                    dst.push(1);
+                    write_bytes(dst, val);
+                }
+            }
+        }
+        return Ok(ret);
+
+        fn write_bytes(dst: &mut Vec<u8>, val: impl AsRef<[u8]>) {
+            let val = val.as_ref();
+            write_usize(dst, val.len());
+            dst.extend_from_slice(val);
+        }
+
+        fn write_usize(dst: &mut Vec<u8>, val: usize) {
+            dst.extend(&u32::to_le_bytes(val as u32));
+        }
+    }
+}
+
+/// The directory the paths of the dep-info files in the output directory are
+/// relative to, from `build.dep-info-basedir`.
+///
+/// With `-Zdep-info-basedir`, the placeholders of [`expand_basedir`] are
+/// expanded.
+pub fn dep_info_basedir(ws: &Workspace<'_>) -> CargoResult<Option<PathBuf>> {
+    let config = ws.config();
+    let Some(basedir) = &config.build_config()?.dep_info_basedir else {
+        return Ok(None);
+    };
+    if !config.cli_unstable().dep_info_basedir {
+        return Ok(Some(basedir.resolve_path(config)));
+    }
+    let value = basedir.value();
+    Ok(Some(expand_basedir(
+        &value.val,
+        &value.definition.root(config),
+        ws,
+    )))
+}
+
+/// Expands a dep-info base directory starting with `{workspace-root}` or
+/// `{target-dir}`, or joins it to `base`.
+pub fn expand_basedir(value: &str, base: &Path, ws: &Workspace<'_>) -> PathBuf {
+    let target_dir = ws.target_dir().into_path_unlocked();
+    for (placeholder, dir) in [
+        ("{workspace-root}", ws.root()),
+        ("{target-dir}", target_dir.as_path()),
+    ] {
+        if let Some(rest) = value.strip_prefix(placeholder) {
+            return paths::normalize_path(&dir.join(rest.trim_start_matches(['/', '\\'])));
+        }
+    }
+    base.join(value)
+}
+
+/// Renders `path` for a dep-info file in the output directory.
+///
+/// The path is normalized and made relative to `basedir` when it is inside
+/// of it, and its spaces are escaped.
+pub fn render_filename<P: AsRef<Path>>(path: P, basedir: Option<&Path>) -> CargoResult<String> {
+    fn wrap_path(path: &Path) -> CargoResult<String> {
+        path.to_str()
+            .ok_or_else(|| internal(format!("path `{:?}` not utf-8", path)))
+            .map(|f| f.replace(" ", "\\ "))
+    }
+
+    let path = path.as_ref();
+    if let Some(basedir) = basedir {
+        let norm_path = paths::normalize_path(path);
+        let norm_basedir = paths::normalize_path(basedir);
+        match norm_path.strip_prefix(norm_basedir) {
+            Ok(relpath) => wrap_path(relpath),
+            _ => wrap_path(path),
+        }
+    } else {
+        wrap_path(path)
+    }
+}
@@ -56,7 +56,7 @@ impl<N: Hash + Eq, E: Hash + Eq, V> DependencyQueue<N, E, V> {
     }
 }
 
-impl<N: Hash + Eq + Clone, E: Eq + Hash + Clone, V> DependencyQueue<N, E, V> {
+impl<N: Hash + Ord + Clone, E: Eq + Hash + Clone, V> DependencyQueue<N, E, V> {
     /// Adds a new node and its dependencies to this queue.
     ///
     /// The `key` specified is a new node in the dependency graph, and the node
@@ -149,13 +149,20 @@ impl<N: Hash + Eq + Clone, E: Eq + Hash + Clone, V> DependencyQueue<N, E, V> {
     ///
     /// A package is ready to be built when it has 0 un-built dependencies. If
     /// `None` is returned then no packages are ready to be built.
+    ///
+    /// Packages with the same priority are dequeued in the reverse order of
+    /// their keys. The job queue starts the last package dequeued first, so
+    /// the same packages are always built in the order of their keys.
     pub fn dequeue(&mut self) -> Option<(N, V, usize)> {
         let (key, priority) = self
             .dep_map
             .iter()
             .filter(|(_, (deps, _))| deps.is_empty())
-            .map(|(key, _)| (key.clone(), self.priority[key]))
-            .max_by_key(|(_, priority)| *priority)?;
+            .map(|(key, _)| (key, self.priority[key]))
+            .max_by(|(a, a_priority), (b, b_priority)| {
+                a_priority.cmp(b_priority).then_with(|| a.cmp(b))
+            })?;
+        let key = key.clone();
         let (_, data) = self.dep_map.remove(&key).unwrap();
         Some((key, data, priority))
     }
@@ -252,4 +259,19 @@ mod test {
         q.finish(&4, &());
         assert_eq!(q.dequeue(), None);
     }
+
+    #[test]
+    fn equal_priority_in_reverse_key_order() {
+        let mut q = DependencyQueue::<_, (), _>::new();
+
+        for key in [4, 2, 5, 1, 3] {
+            q.queue(key, (), vec![], 1);
+        }
+        q.queue_finished();
+
+        for key in (1..=5).rev() {
+            assert_eq!(q.dequeue(), Some((key, (), 2)));
+        }
+        assert_eq!(q.dequeue(), None);
+    }
 }
@@ -3,9 +3,14 @@
 use anyhow::Error;
 use curl::easy::Easy;
 use std::fmt::{self, Write};
-use std::path::PathBuf;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use url::Url;
 
 use super::truncate_with_ellipsis;
+use super::Cancelled;
+use crate::core::resolver::ResolveError;
+use crate::core::Shell;
 
 pub type CargoResult<T> = anyhow::Result<T>;
 
@@ -87,7 +92,9 @@ impl HttpNotSuccessful {
                 .headers
                 .iter()
                 .filter(|header| {
-                    let Some((name, _)) = header.split_once(":") else { return false };
+                    let Some((name, _)) = header.split_once(":") else {
+                        return false;
+                    };
                     DEBUG_HEADERS.contains(&name.to_ascii_lowercase().trim())
                 })
                 .collect();
@@ -200,7 +207,7 @@ impl AlreadyPrintedError {
 
 impl std::error::Error for AlreadyPrintedError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        self.inner.source()
+        None
     }
 }
 
@@ -221,7 +228,7 @@ impl fmt::Display for AlreadyPrintedError {
 
 /// Error wrapper related to a particular manifest and providing it's path.
 ///
-/// This error adds no displayable info of it's own.
+/// This error adds no displayable info of its own.
 pub struct ManifestError {
     cause: Error,
     manifest: PathBuf,
@@ -281,6 +288,183 @@ impl<'a> Iterator for ManifestCauses<'a> {
 
 impl<'a> ::std::iter::FusedIterator for ManifestCauses<'a> {}
 
+// =============================================================================
+// Linked error
+
+/// An error whose message has parts displayed as hyperlinks when it is
+/// printed with `term.hyperlinks`, see [`LinkedError::render`]. Its `Display`
+/// is only the text, so the links never end up in other output.
+#[derive(Debug, Default)]
+pub struct LinkedError {
+    text: String,
+    /// The byte ranges of `text` displayed as hyperlinks, with their URLs.
+    links: Vec<(Range<usize>, String)>,
+    source: Option<Error>,
+}
+
+impl LinkedError {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends `text` to the message.
+    pub fn push(mut self, text: impl fmt::Display) -> Self {
+        write!(self.text, "{text}").unwrap();
+        self
+    }
+
+    /// Appends `text` to the message, displayed as a hyperlink to `url`.
+    pub fn push_link(mut self, text: impl fmt::Display, url: impl Into<String>) -> Self {
+        let start = self.text.len();
+        self = self.push(text);
+        self.links.push((start..self.text.len(), url.into()));
+        self
+    }
+
+    /// Appends `path` to the message, displayed as a hyperlink to the file.
+    pub fn push_file_link(self, path: &Path) -> Self {
+        match Url::from_file_path(path) {
+            Ok(url) => self.push_link(path.display(), url),
+            Err(()) => self.push(path.display()),
+        }
+    }
+
+    /// Sets the error this is the context of.
+    pub fn with_source(mut self, source: impl Into<Error>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// The message, with its hyperlinks if they are enabled in `shell`.
+    pub fn render(&self, shell: &Shell) -> String {
+        let mut rendered = String::new();
+        let mut end = 0;
+        for (range, url) in &self.links {
+            rendered.push_str(&self.text[end..range.start]);
+            write!(
+                rendered,
+                "{}",
+                shell.hyperlink(url, &self.text[range.clone()])
+            )
+            .unwrap();
+            end = range.end;
+        }
+        rendered.push_str(&self.text[end..]);
+        rendered
+    }
+
+    /// The [`LinkedError`] displayed as `err`, which may be wrapped in
+    /// errors displaying their cause, like [`ManifestError`].
+    pub fn of<'a>(err: &'a (dyn std::error::Error + 'static)) -> Option<&'a LinkedError> {
+        if let Some(err) = err.downcast_ref::<ManifestError>() {
+            return LinkedError::of(err.cause.as_ref());
+        }
+        if let Some(err) = err.downcast_ref::<ResolveError>() {
+            return LinkedError::of(err.cause().as_ref());
+        }
+        err.downcast_ref()
+    }
+}
+
+impl std::error::Error for LinkedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source.as_ref().map(|source| source.as_ref())
+    }
+}
+
+impl fmt::Display for LinkedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+// =============================================================================
+// Error kinds
+
+/// The kind of an error returned by Cargo, for library consumers to branch on
+/// without matching on error messages, see [`CargoErrorKind::of`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CargoErrorKind {
+    /// No manifest was found where one was expected.
+    ManifestNotFound,
+    /// A manifest couldn't be loaded, see [`ManifestError`].
+    Manifest,
+    /// The dependencies couldn't be resolved, see [`ResolveError`].
+    Resolve,
+    /// rustc or rustdoc reported errors, or a build script failed.
+    Compile,
+    /// An HTTP request didn't succeed, see [`HttpNotSuccessful`].
+    Http,
+    /// The operation was stopped by its [`CancellationToken`], or timed out,
+    /// see [`Cancelled`].
+    ///
+    /// [`CancellationToken`]: crate::util::CancellationToken
+    Cancelled,
+    /// Any other error.
+    Other,
+}
+
+impl CargoErrorKind {
+    /// Gets the kind of `err`, from the outermost error of its chain that has
+    /// one, looking into the error an [`AlreadyPrintedError`] wraps.
+    pub fn of(err: &Error) -> CargoErrorKind {
+        for cause in err.chain() {
+            if let Some(err) = cause.downcast_ref::<KindError>() {
+                return err.kind;
+            } else if let Some(err) = cause.downcast_ref::<AlreadyPrintedError>() {
+                return CargoErrorKind::of(&err.inner);
+            } else if cause.is::<ManifestError>() {
+                return CargoErrorKind::Manifest;
+            } else if cause.is::<ResolveError>() {
+                return CargoErrorKind::Resolve;
+            } else if cause.is::<HttpNotSuccessful>() {
+                return CargoErrorKind::Http;
+            } else if cause.is::<Cancelled>() {
+                return CargoErrorKind::Cancelled;
+            }
+        }
+        CargoErrorKind::Other
+    }
+}
+
+/// Error wrapper giving a [`CargoErrorKind`] to an error that has no type of
+/// its own.
+///
+/// This error adds no displayable info of its own.
+pub struct KindError {
+    kind: CargoErrorKind,
+    inner: Error,
+}
+
+impl KindError {
+    pub fn new(kind: CargoErrorKind, inner: Error) -> KindError {
+        KindError { kind, inner }
+    }
+
+    pub fn kind(&self) -> CargoErrorKind {
+        self.kind
+    }
+}
+
+impl std::error::Error for KindError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.inner.source()
+    }
+}
+
+impl fmt::Debug for KindError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl fmt::Display for KindError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
 // =============================================================================
 // CLI errors
 
@@ -0,0 +1,69 @@
+//! See [`FileOverlay`].
+
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use cargo_util::paths;
+
+use crate::util::errors::CargoResult;
+
+/// Contents read instead of the ones of files on disk, when loading
+/// manifests and configuration files.
+///
+/// This lets an embedder, like an IDE, load a workspace the way it looks
+/// with the unsaved buffers of an editor. Files in the overlay don't need to
+/// exist on disk: they are seen as existing when looking for a workspace root
+/// or for configuration files.
+///
+/// Paths are compared after being normalized, but they aren't canonicalized,
+/// so they should be absolute and spelled like the ones Cargo builds from the
+/// manifest path and the current directory.
+#[derive(Debug, Default)]
+pub struct FileOverlay {
+    files: HashMap<PathBuf, String>,
+}
+
+impl FileOverlay {
+    /// Makes reads of `path` return `contents` from now on.
+    pub fn insert(&mut self, path: &Path, contents: String) -> Option<String> {
+        self.files.insert(paths::normalize_path(path), contents)
+    }
+
+    /// Reads `path` from disk again.
+    pub fn remove(&mut self, path: &Path) -> Option<String> {
+        self.files.remove(&paths::normalize_path(path))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    fn get(&self, path: &Path) -> Option<&String> {
+        if self.files.is_empty() {
+            return None;
+        }
+        self.files.get(&paths::normalize_path(path))
+    }
+
+    /// Whether `path` is in the overlay or exists on disk.
+    pub fn exists(&self, path: &Path) -> bool {
+        self.get(path).is_some() || path.exists()
+    }
+
+    /// Reads `path` like [`paths::read`].
+    pub fn read(&self, path: &Path) -> CargoResult<String> {
+        match self.get(path) {
+            Some(contents) => Ok(contents.clone()),
+            None => paths::read(path),
+        }
+    }
+
+    /// Reads `path` like [`std::fs::read_to_string`].
+    pub fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        match self.get(path) {
+            Some(contents) => Ok(contents.clone()),
+            None => std::fs::read_to_string(path),
+        }
+    }
+}
@@ -2,10 +2,12 @@ use std::fs::{File, OpenOptions};
 use std::io;
 use std::io::{Read, Seek, SeekFrom, Write};
 use std::path::{Display, Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
 
 use crate::util::errors::CargoResult;
 use crate::util::Config;
-use anyhow::Context as _;
+use anyhow::{bail, Context as _};
 use cargo_util::paths;
 use sys::*;
 use termcolor::Color::Cyan;
@@ -39,12 +41,39 @@ impl FileLock {
         &self.path
     }
 
+    /// Whether this is an exclusive lock, as opposed to a shared one.
+    pub fn is_exclusive(&self) -> bool {
+        self.state == State::Exclusive
+    }
+
     /// Returns the parent path containing this file
     pub fn parent(&self) -> &Path {
         assert_ne!(self.state, State::Unlocked);
         self.path.parent().unwrap()
     }
 
+    /// Replaces the contents of the locked file at once, with
+    /// [`paths::write_atomic`], which makes it a new file. The new file is
+    /// locked before it replaces the old one, and this lock holds it from
+    /// then on.
+    pub fn write_atomic(&mut self, contents: &[u8]) -> CargoResult<()> {
+        assert_eq!(self.state, State::Exclusive);
+        let f = paths::write_atomic_with(&self.path, contents, |f| {
+            if is_on_nfs_mount(&self.path) {
+                return Ok(());
+            }
+            // Nobody else can have the new file yet, this doesn't block.
+            match try_lock_exclusive(f) {
+                Err(e) if !error_unsupported(&e) => Err(e),
+                _ => Ok(()),
+            }
+        })?;
+        if let Some(old) = self.f.replace(f) {
+            let _ = unlock(&old);
+        }
+        Ok(())
+    }
+
     /// Removes all sibling files to this locked file.
     ///
     /// This can be useful if a directory is locked with a sentinel file but it
@@ -99,6 +128,15 @@ impl Drop for FileLock {
     }
 }
 
+/// How to wait for a lock held by another process.
+#[derive(Default)]
+pub struct LockWait<'a> {
+    /// Fails instead of waiting longer than this, `None` waits forever.
+    pub timeout: Option<Duration>,
+    /// Describes the process holding the lock, if that's known.
+    pub holder: Option<&'a dyn Fn() -> Option<String>>,
+}
+
 /// A "filesystem" is intended to be a globally shared, hence locked, resource
 /// in Cargo.
 ///
@@ -168,6 +206,20 @@ impl Filesystem {
     /// The returned file can be accessed to look at the path and also has
     /// read/write access to the underlying file.
     pub fn open_rw<P>(&self, path: P, config: &Config, msg: &str) -> CargoResult<FileLock>
+    where
+        P: AsRef<Path>,
+    {
+        self.open_rw_waiting(path, config, msg, &LockWait::default())
+    }
+
+    /// Like [`Filesystem::open_rw`], but waits for the lock as `wait` says.
+    pub fn open_rw_waiting<P>(
+        &self,
+        path: P,
+        config: &Config,
+        msg: &str,
+        wait: &LockWait<'_>,
+    ) -> CargoResult<FileLock>
     where
         P: AsRef<Path>,
     {
@@ -177,6 +229,7 @@ impl Filesystem {
             State::Exclusive,
             config,
             msg,
+            wait,
         )
     }
 
@@ -199,6 +252,7 @@ impl Filesystem {
             State::Shared,
             config,
             msg,
+            &LockWait::default(),
         )
     }
 
@@ -209,44 +263,70 @@ impl Filesystem {
         state: State,
         config: &Config,
         msg: &str,
+        wait: &LockWait<'_>,
     ) -> CargoResult<FileLock> {
         let path = self.root.join(path);
 
-        // If we want an exclusive lock then if we fail because of NotFound it's
-        // likely because an intermediate directory didn't exist, so try to
-        // create the directory and then continue.
-        let f = opts
-            .open(&path)
-            .or_else(|e| {
-                if e.kind() == io::ErrorKind::NotFound && state == State::Exclusive {
-                    paths::create_dir_all(path.parent().unwrap())?;
-                    Ok(opts.open(&path)?)
-                } else {
-                    Err(anyhow::Error::from(e))
+        loop {
+            // If we want an exclusive lock then if we fail because of NotFound it's
+            // likely because an intermediate directory didn't exist, so try to
+            // create the directory and then continue.
+            let f = opts
+                .open(&path)
+                .or_else(|e| {
+                    if e.kind() == io::ErrorKind::NotFound && state == State::Exclusive {
+                        paths::create_dir_all(path.parent().unwrap())?;
+                        Ok(opts.open(&path)?)
+                    } else {
+                        Err(anyhow::Error::from(e))
+                    }
+                })
+                .with_context(|| format!("failed to open: {}", path.display()))?;
+            match state {
+                State::Exclusive => {
+                    acquire(
+                        config,
+                        msg,
+                        &path,
+                        wait,
+                        &|| try_lock_exclusive(&f),
+                        &|| lock_exclusive(&f),
+                    )?;
+                }
+                State::Shared => {
+                    acquire(config, msg, &path, wait, &|| try_lock_shared(&f), &|| {
+                        lock_shared(&f)
+                    })?;
                 }
-            })
-            .with_context(|| format!("failed to open: {}", path.display()))?;
-        match state {
-            State::Exclusive => {
-                acquire(config, msg, &path, &|| try_lock_exclusive(&f), &|| {
-                    lock_exclusive(&f)
-                })?;
+                State::Unlocked => {}
             }
-            State::Shared => {
-                acquire(config, msg, &path, &|| try_lock_shared(&f), &|| {
-                    lock_shared(&f)
-                })?;
+            // The file may have been replaced by `FileLock::write_atomic`
+            // while we were waiting for its lock, then the new one has to be
+            // locked instead.
+            if state != State::Unlocked && !is_file_at(&f, &path) {
+                continue;
             }
-            State::Unlocked => {}
+            return Ok(FileLock {
+                f: Some(f),
+                path,
+                state,
+            });
         }
-        Ok(FileLock {
-            f: Some(f),
-            path,
-            state,
-        })
     }
 }
 
+/// Whether `f` is still the file at `path`. It is assumed to be when that
+/// can't be checked.
+fn is_file_at(f: &File, path: &Path) -> bool {
+    let (Ok(f), Ok(at_path)) = (
+        f.try_clone().and_then(same_file::Handle::from_file),
+        same_file::Handle::from_path(path),
+    ) else {
+        return true;
+    };
+    f == at_path
+}
+
 impl PartialEq<Path> for Filesystem {
     fn eq(&self, other: &Path) -> bool {
         self.root == other
@@ -270,14 +350,16 @@ impl PartialEq<Filesystem> for Path {
 /// to the console if we have to wait for it. It will first attempt to use `try`
 /// to acquire a lock on the crate, and in the case of contention it will emit a
 /// status message based on `msg` to `config`'s shell, and then use `block` to
-/// block waiting to acquire a lock.
+/// block waiting to acquire a lock. With a timeout in `wait`, `try` is polled
+/// until it expires instead.
 ///
-/// Returns an error if the lock could not be acquired or if any error other
-/// than a contention error happens.
+/// Returns an error if the lock could not be acquired, if the timeout expired
+/// or if any error other than a contention error happens.
 fn acquire(
     config: &Config,
     msg: &str,
     path: &Path,
+    wait: &LockWait<'_>,
     lock_try: &dyn Fn() -> io::Result<()>,
     lock_block: &dyn Fn() -> io::Result<()>,
 ) -> CargoResult<()> {
@@ -311,35 +393,60 @@ fn acquire(
             }
         }
     }
-    let msg = format!("waiting for file lock on {}", msg);
+    let mut msg = format!("waiting for file lock on {}", msg);
+    if let Some(holder) = wait.holder.and_then(|holder| holder()) {
+        msg.push_str(&format!(", held by {}", holder));
+    }
     config.shell().status_with_color("Blocking", &msg, Cyan)?;
 
-    lock_block().with_context(|| format!("failed to lock file: {}", path.display()))?;
-    return Ok(());
+    let start = Instant::now();
+    match wait.timeout {
+        None => lock_block().with_context(|| format!("failed to lock file: {}", path.display()))?,
+        Some(timeout) => loop {
+            match lock_try() {
+                Ok(()) => break,
+                Err(e) if error_contended(&e) => {}
+                Err(e) => {
+                    let cx = format!("failed to lock file: {}", path.display());
+                    return Err(anyhow::Error::from(e).context(cx));
+                }
+            }
+            let elapsed = start.elapsed();
+            if elapsed >= timeout {
+                bail!("timed out after {}s {}", timeout.as_secs(), msg);
+            }
+            thread::sleep((timeout - elapsed).min(Duration::from_millis(100)));
+        },
+    }
+    if config.cli_unstable().lock_wait_timeout {
+        let msg = format!("file lock after {:.1}s", start.elapsed().as_secs_f64());
+        config.shell().status_with_color("Acquired", &msg, Cyan)?;
+    }
+    Ok(())
+}
 
-    #[cfg(all(target_os = "linux", not(target_env = "musl")))]
-    fn is_on_nfs_mount(path: &Path) -> bool {
-        use std::ffi::CString;
-        use std::mem;
-        use std::os::unix::prelude::*;
+#[cfg(all(target_os = "linux", not(target_env = "musl")))]
+fn is_on_nfs_mount(path: &Path) -> bool {
+    use std::ffi::CString;
+    use std::mem;
+    use std::os::unix::prelude::*;
 
-        let path = match CString::new(path.as_os_str().as_bytes()) {
-            Ok(path) => path,
-            Err(_) => return false,
-        };
+    let path = match CString::new(path.as_os_str().as_bytes()) {
+        Ok(path) => path,
+        Err(_) => return false,
+    };
 
-        unsafe {
-            let mut buf: libc::statfs = mem::zeroed();
-            let r = libc::statfs(path.as_ptr(), &mut buf);
+    unsafe {
+        let mut buf: libc::statfs = mem::zeroed();
+        let r = libc::statfs(path.as_ptr(), &mut buf);
 
-            r == 0 && buf.f_type as u32 == libc::NFS_SUPER_MAGIC as u32
-        }
+        r == 0 && buf.f_type as u32 == libc::NFS_SUPER_MAGIC as u32
     }
+}
 
-    #[cfg(any(not(target_os = "linux"), target_env = "musl"))]
-    fn is_on_nfs_mount(_path: &Path) -> bool {
-        false
-    }
+#[cfg(any(not(target_os = "linux"), target_env = "musl"))]
+fn is_on_nfs_mount(_path: &Path) -> bool {
+    false
 }
 
 #[cfg(unix)]
@@ -0,0 +1,195 @@
+//! Adds the entries Cargo needs to the ignore files of a VCS, like `/target`
+//! in `.gitignore`.
+
+use std::io::ErrorKind;
+use std::path::Path;
+
+use anyhow::anyhow;
+use cargo_util::paths;
+
+use crate::util::errors::CargoResult;
+use crate::util::{FossilRepo, GitRepo, HgRepo, IgnoreSyntax, JjRepo, PijulRepo, Vcs};
+
+/// The entries to ignore, in the syntax of each VCS.
+#[derive(Debug, Default)]
+pub struct IgnoreList {
+    /// git like formatted entries
+    ignore: Vec<String>,
+    /// mercurial formatted entries
+    hg_ignore: Vec<String>,
+    /// Fossil-formatted entries.
+    fossil_ignore: Vec<String>,
+}
+
+impl IgnoreList {
+    /// constructor to build a new ignore file
+    pub fn new() -> IgnoreList {
+        IgnoreList::default()
+    }
+
+    /// Add a new entry to the ignore list. Requires three arguments with the
+    /// entry in possibly three different formats. One for "git style" entries,
+    /// one for "mercurial style" entries and one for "fossil style" entries.
+    pub fn push(&mut self, ignore: &str, hg_ignore: &str, fossil_ignore: &str) {
+        self.ignore.push(ignore.to_string());
+        self.hg_ignore.push(hg_ignore.to_string());
+        self.fossil_ignore.push(fossil_ignore.to_string());
+    }
+
+    /// The entries in the given syntax.
+    fn items(&self, syntax: IgnoreSyntax) -> &[String] {
+        match syntax {
+            IgnoreSyntax::Git => &self.ignore,
+            IgnoreSyntax::Hg => &self.hg_ignore,
+            IgnoreSyntax::Fossil => &self.fossil_ignore,
+        }
+    }
+
+    /// Returns the text to append to an ignore file with the `existing`
+    /// content, so that it has all the entries. The existing content is kept
+    /// as is, and nothing is added when it already has all the entries, so
+    /// merging into the merged content returns an empty string. Otherwise
+    /// the entries it already has are added commented out, or skipped with
+    /// Fossil.
+    pub fn merge(&self, existing: &str, syntax: IgnoreSyntax) -> String {
+        let ignore_items = self.items(syntax);
+        if existing.is_empty() {
+            return ignore_items.join("\n") + "\n";
+        }
+        let existing_items: Vec<&str> = existing.lines().map(str::trim).collect();
+        let is_existing = |item: &String| existing_items.contains(&item.trim());
+        if ignore_items.iter().all(is_existing) {
+            return String::new();
+        }
+
+        let mut out = String::new();
+        if !existing.ends_with('\n') {
+            out.push('\n');
+        }
+        // Fossil does not support `#` comments.
+        if syntax != IgnoreSyntax::Fossil {
+            out.push_str("\n# Added by cargo\n");
+            if ignore_items.iter().any(is_existing) {
+                out.push_str("#\n# already existing elements were commented out\n");
+            }
+            out.push('\n');
+        }
+        for item in ignore_items {
+            if is_existing(item) {
+                if syntax == IgnoreSyntax::Fossil {
+                    // Just merge for Fossil.
+                    continue;
+                }
+                out.push('#');
+            }
+            out.push_str(item);
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Writes the entries to the ignore files of `vcs` in the directory
+    /// `base_path`, creating them if they don't exist, or appending the
+    /// missing entries to them.
+    pub fn write(&self, base_path: &Path, vcs: &dyn Vcs) -> CargoResult<()> {
+        for fp_ignore in vcs.ignore_files() {
+            let fp_ignore = base_path.join(fp_ignore);
+            // Fossil only supports project-level settings in a dedicated subdirectory.
+            paths::create_dir_all(fp_ignore.parent().unwrap())?;
+            let existing = match paths::read_bytes(&fp_ignore) {
+                Ok(existing) => existing,
+                Err(err) => match err.downcast_ref::<std::io::Error>() {
+                    Some(io_err) if io_err.kind() == ErrorKind::NotFound => Vec::new(),
+                    _ => return Err(err),
+                },
+            };
+            let existing = String::from_utf8(existing).map_err(|err| {
+                let line = err.as_bytes()[..err.utf8_error().valid_up_to()]
+                    .iter()
+                    .filter(|b| **b == b'\n')
+                    .count();
+                anyhow!(
+                    "Character at line {} is invalid. Cargo only supports UTF-8.",
+                    line
+                )
+            })?;
+            let ignore = self.merge(&existing, vcs.ignore_syntax());
+            if !ignore.is_empty() {
+                paths::append(&fp_ignore, ignore.as_bytes())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether `path`, relative to the root of a package, is the ignore file of
+/// one of the VCSs Cargo supports, like `.gitignore` or `.hgignore`.
+pub fn is_ignore_file(path: &Path) -> bool {
+    let vcs: [&dyn Vcs; 5] = [&GitRepo, &HgRepo, &JjRepo, &PijulRepo, &FossilRepo];
+    vcs.iter()
+        .flat_map(|vcs| vcs.ignore_files())
+        .any(|ignore_file| path == Path::new(ignore_file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IgnoreList;
+    use crate::util::IgnoreSyntax;
+
+    fn list() -> IgnoreList {
+        let mut list = IgnoreList::new();
+        list.push("/target", "^target$", "target");
+        list.push("/Cargo.lock", "^Cargo.lock$", "Cargo.lock");
+        list
+    }
+
+    #[test]
+    fn merge_new() {
+        assert_eq!(
+            list().merge("", IgnoreSyntax::Git),
+            "/target\n/Cargo.lock\n"
+        );
+        assert_eq!(
+            list().merge("", IgnoreSyntax::Hg),
+            "^target$\n^Cargo.lock$\n"
+        );
+    }
+
+    #[test]
+    fn merge_keeps_existing() {
+        let existing = "# build output\n  /target  \n\n*.swp";
+        let added = list().merge(existing, IgnoreSyntax::Git);
+        assert_eq!(
+            added,
+            "\n\n# Added by cargo\n\
+             #\n\
+             # already existing elements were commented out\n\
+             \n\
+             #/target\n\
+             /Cargo.lock\n"
+        );
+    }
+
+    #[test]
+    fn merge_is_idempotent() {
+        for syntax in [IgnoreSyntax::Git, IgnoreSyntax::Hg, IgnoreSyntax::Fossil] {
+            for existing in ["", "*.swp\n", "*.swp"] {
+                let merged = format!("{existing}{}", list().merge(existing, syntax));
+                assert_eq!(list().merge(&merged, syntax), "", "{syntax:?} {existing:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn merge_fossil_without_comments() {
+        assert_eq!(
+            list().merge("*.swp\n", IgnoreSyntax::Fossil),
+            "target\nCargo.lock\n"
+        );
+        assert_eq!(
+            list().merge("target\n", IgnoreSyntax::Fossil),
+            "Cargo.lock\n"
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::util::errors::CargoResult;
+use crate::util::errors::{CargoErrorKind, CargoResult, KindError};
 use cargo_util::paths;
 use std::path::{Path, PathBuf};
 
@@ -18,19 +18,20 @@ pub fn find_root_manifest_for_wd(cwd: &Path) -> CargoResult<PathBuf> {
         }
     }
 
-    if invalid_cargo_toml_path_exists {
-        anyhow::bail!(
+    let error = if invalid_cargo_toml_path_exists {
+        anyhow::format_err!(
         "could not find `{}` in `{}` or any parent directory, but found cargo.toml please try to rename it to Cargo.toml",
         valid_cargo_toml_file_name,
         cwd.display()
     )
     } else {
-        anyhow::bail!(
+        anyhow::format_err!(
             "could not find `{}` in `{}` or any parent directory",
             valid_cargo_toml_file_name,
             cwd.display()
         )
-    }
+    };
+    Err(KindError::new(CargoErrorKind::ManifestNotFound, error).into())
 }
 
 /// Returns the path to the `file` in `pwd`, if it exists.
@@ -40,6 +41,7 @@ pub fn find_project_manifest_exact(pwd: &Path, file: &str) -> CargoResult<PathBu
     if manifest.exists() {
         Ok(manifest)
     } else {
-        anyhow::bail!("Could not find `{}` in `{}`", file, pwd.display())
+        let error = anyhow::format_err!("Could not find `{}` in `{}`", file, pwd.display());
+        Err(KindError::new(CargoErrorKind::ManifestNotFound, error).into())
     }
 }
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 use serde::ser;
@@ -39,6 +40,9 @@ pub struct Artifact<'a> {
     pub profile: ArtifactProfile,
     pub features: Vec<String>,
     pub filenames: Vec<PathBuf>,
+    /// Copies of `filenames` made for `--out-dir`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub out_dir_filenames: Vec<PathBuf>,
     pub executable: Option<PathBuf>,
     pub fresh: bool,
 }
@@ -101,6 +105,67 @@ impl<'a> Message for TimingInfo<'a> {
     }
 }
 
+/// An executable started by `cargo run` or `cargo test`.
+#[derive(Serialize)]
+pub struct Run<'a> {
+    pub package_id: PackageId,
+    pub manifest_path: &'a Path,
+    pub target: &'a Target,
+    pub executable: &'a Path,
+    pub cwd: &'a Path,
+}
+
+impl<'a> Message for Run<'a> {
+    fn reason(&self) -> &str {
+        "run"
+    }
+}
+
+/// A test executable built by `cargo test --no-run` or `cargo bench --no-run`.
+#[derive(Serialize)]
+pub struct TestBinary<'a> {
+    pub package_id: PackageId,
+    pub manifest_path: &'a Path,
+    pub target: &'a Target,
+    /// `"test"`, or `"bench"` when it is run with `--bench`.
+    pub kind: &'static str,
+    pub harness: bool,
+    pub executable: &'a Path,
+    /// The command Cargo runs, with the target runner if one is configured.
+    pub command: Vec<String>,
+    pub cwd: &'a Path,
+    /// The environment variables Cargo sets, without the ones from the
+    /// `[env]` config table and from build scripts.
+    pub env: BTreeMap<String, String>,
+}
+
+impl<'a> Message for TestBinary<'a> {
+    fn reason(&self) -> &str {
+        "test-binary"
+    }
+}
+
+/// The environment and command line of a process run for a unit, with
+/// `--emit-env`.
+#[derive(Serialize)]
+pub struct UnitEnv<'a> {
+    pub package_id: PackageId,
+    pub target: &'a Target,
+    pub mode: CompileMode,
+    pub program: String,
+    pub args: Vec<String>,
+    pub cwd: Option<&'a Path>,
+    /// All the environment variables of the process, inherited or set by
+    /// Cargo.
+    pub env: BTreeMap<String, String>,
+}
+
+impl<'a> Message for UnitEnv<'a> {
+    fn reason(&self) -> &str {
+        "unit-env"
+    }
+}
+
 #[derive(Serialize)]
 pub struct BuildFinished {
     pub success: bool,
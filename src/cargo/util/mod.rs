@@ -2,6 +2,7 @@ use std::fmt;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
+pub use self::cancellation::{CancellationToken, Cancelled};
 pub use self::canonical_url::CanonicalUrl;
 pub use self::config::{homedir, Config, ConfigValue};
 pub(crate) use self::counter::MetricsCounter;
@@ -10,10 +11,12 @@ pub use self::diagnostic_server::RustfixDiagnosticServer;
 pub use self::edit_distance::{closest, closest_msg, edit_distance};
 pub use self::errors::CliError;
 pub use self::errors::{internal, CargoResult, CliResult};
-pub use self::flock::{FileLock, Filesystem};
+pub use self::file_overlay::FileOverlay;
+pub use self::flock::{FileLock, Filesystem, LockWait};
 pub use self::graph::Graph;
 pub use self::hasher::StableHasher;
 pub use self::hex::{hash_u64, short_hash, to_hex};
+pub use self::ignore_file::{is_ignore_file, IgnoreList};
 pub use self::into_url::IntoUrl;
 pub use self::into_url_with_base::IntoUrlWithBase;
 pub(crate) use self::io::LimitErrorReader;
@@ -22,29 +25,37 @@ pub use self::progress::{Progress, ProgressStyle};
 pub use self::queue::Queue;
 pub use self::restricted_names::validate_package_name;
 pub use self::rustc::Rustc;
-pub use self::semver_ext::{OptVersionReq, VersionExt, VersionReqExt};
+pub use self::semver_ext::{OptVersionReq, VersionExt, VersionInterval, VersionReqExt};
 pub use self::to_semver::ToSemver;
-pub use self::vcs::{existing_vcs_repo, FossilRepo, GitRepo, HgRepo, PijulRepo};
+pub use self::vcs::{
+    existing_vcs_repo, git_dirty_files, FossilRepo, GitDirtyFile, GitFileStatus, GitRepo, HgRepo,
+    IgnoreSyntax, JjRepo, PijulRepo, Vcs,
+};
 pub use self::workspace::{
     add_path_args, path_args, print_available_benches, print_available_binaries,
     print_available_examples, print_available_packages, print_available_tests,
 };
 
 pub mod auth;
+pub mod browser;
+mod cancellation;
 mod canonical_url;
 pub mod command_prelude;
 pub mod config;
 mod counter;
 pub mod cpu;
 pub mod credential;
+pub mod dep_info;
 mod dependency_queue;
 pub mod diagnostic_server;
 pub mod edit_distance;
 pub mod errors;
+mod file_overlay;
 mod flock;
 pub mod graph;
 mod hasher;
 pub mod hex;
+mod ignore_file;
 pub mod important_paths;
 pub mod interning;
 pub mod into_url;
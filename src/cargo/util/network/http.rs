@@ -8,9 +8,11 @@ use curl::easy::Easy;
 use curl::easy::InfoType;
 use curl::easy::SslOpt;
 use curl::easy::SslVersion;
-use log::log;
-use log::Level;
+use tracing::{debug, trace, Level};
 
+use crate::core::SourceId;
+use crate::util::config::Definition;
+use crate::util::config::SslClientCertConfig;
 use crate::util::config::SslVersionConfig;
 use crate::util::config::SslVersionConfigRange;
 use crate::version;
@@ -59,16 +61,103 @@ pub fn needs_custom_http_transport(config: &Config) -> CargoResult<bool> {
     )
 }
 
+/// Configures `handle` to authenticate with the client certificate `cert`.
+///
+/// This is unstable and requires `-Zclient-certs`.
+fn configure_client_cert(
+    config: &Config,
+    handle: &mut Easy,
+    cert: &SslClientCertConfig,
+) -> CargoResult<()> {
+    let path = cert.cert.resolve_path(config);
+    if !path.exists() {
+        bail!("the client certificate `{}` doesn't exist", path.display());
+    }
+    handle.ssl_cert(&path)?;
+    if let Some(key) = &cert.key {
+        let key = key.resolve_path(config);
+        if !key.exists() {
+            bail!(
+                "the key of the client certificate `{}` doesn't exist",
+                key.display()
+            );
+        }
+        handle.ssl_key(&key)?;
+    }
+    if let Some(passphrase) = &cert.key_passphrase {
+        if !matches!(passphrase.definition, Definition::Environment(_)) {
+            bail!(
+                "the `key-passphrase` of a client certificate can only be set with an \
+                 environment variable, but it is set in {}",
+                passphrase.definition
+            );
+        }
+        handle.key_password(passphrase.val.as_deref().expose())?;
+    }
+    Ok(())
+}
+
+/// The HTTP settings of a registry from its `[registries.<name>]` table,
+/// which take precedence over the `[http]` table.
+#[derive(Clone, Default)]
+pub struct RegistryHttpConfig {
+    /// The proxy from `registries.<name>.proxy`, with `-Zproxy-config`.
+    proxy: Option<String>,
+    /// The client certificate from `registries.<name>.client-cert`, with
+    /// `-Zclient-certs`.
+    client_cert: Option<SslClientCertConfig>,
+}
+
+impl RegistryHttpConfig {
+    /// Loads the settings of the registry of `sid`.
+    pub fn new(config: &Config, sid: SourceId) -> CargoResult<RegistryHttpConfig> {
+        let unstable = config.cli_unstable();
+        if !(unstable.proxy_config || unstable.client_certs) || !sid.is_remote_registry() {
+            return Ok(RegistryHttpConfig::default());
+        }
+        let settings = config.registry_settings(&sid)?;
+        Ok(RegistryHttpConfig {
+            proxy: settings.proxy.filter(|_| unstable.proxy_config),
+            client_cert: settings.client_cert.filter(|_| unstable.client_certs),
+        })
+    }
+
+    /// Applies the settings to a handle set up by [`configure_http_handle`].
+    pub fn configure(&self, config: &Config, handle: &mut Easy) -> CargoResult<()> {
+        if let Some(proxy) = &self.proxy {
+            handle.proxy(proxy)?;
+        }
+        if let Some(cert) = &self.client_cert {
+            configure_client_cert(config, handle, cert)?;
+        }
+        Ok(())
+    }
+}
+
 /// Configure a libcurl http handle with the defaults options for Cargo
 pub fn configure_http_handle(config: &Config, handle: &mut Easy) -> CargoResult<HttpTimeout> {
     let http = config.http_config()?;
+    let proxy_config = config.cli_unstable().proxy_config;
     if let Some(proxy) = super::proxy::http_proxy(http) {
         handle.proxy(&proxy)?;
+    } else if proxy_config && !super::proxy::env_proxy_exists(config) {
+        if let Some(system) = super::proxy::system_proxy() {
+            handle.proxy(&system.proxy)?;
+            handle.noproxy(&system.no_proxy.join(","))?;
+        }
+    }
+    if let Some(no_proxy) = http.no_proxy.as_ref().filter(|_| proxy_config) {
+        handle.noproxy(&no_proxy.as_slice().join(","))?;
     }
     if let Some(cainfo) = &http.cainfo {
         let cainfo = cainfo.resolve_path(config);
         handle.cainfo(&cainfo)?;
     }
+    if let Some(cert) = http.ssl_client_cert.as_ref() {
+        if config.cli_unstable().client_certs {
+            configure_client_cert(config, handle, cert)?;
+        }
+    }
     if let Some(check) = http.check_revoke {
         handle.ssl_options(SslOpt::new().no_revoke(!check))?;
     }
@@ -135,14 +224,14 @@ pub fn configure_http_handle(config: &Config, handle: &mut Easy) -> CargoResult<
 
     if let Some(true) = http.debug {
         handle.verbose(true)?;
-        log::debug!("{:#?}", curl::Version::get());
+        tracing::debug!("{:#?}", curl::Version::get());
         handle.debug_function(|kind, data| {
             let (prefix, level) = match kind {
-                InfoType::Text => ("*", Level::Debug),
-                InfoType::HeaderIn => ("<", Level::Debug),
-                InfoType::HeaderOut => (">", Level::Debug),
-                InfoType::DataIn => ("{", Level::Trace),
-                InfoType::DataOut => ("}", Level::Trace),
+                InfoType::Text => ("*", Level::DEBUG),
+                InfoType::HeaderIn => ("<", Level::DEBUG),
+                InfoType::HeaderOut => (">", Level::DEBUG),
+                InfoType::DataIn => ("{", Level::TRACE),
+                InfoType::DataOut => ("}", Level::TRACE),
                 InfoType::SslDataIn | InfoType::SslDataOut => return,
                 _ => return,
             };
@@ -159,16 +248,18 @@ pub fn configure_http_handle(config: &Config, handle: &mut Easy) -> CargoResult<
                         } else if starts_with_ignore_case(line, "set-cookie") {
                             line = "set-cookie: [REDACTED]";
                         }
-                        log!(level, "http-debug: {} {}", prefix, line);
+                        match level {
+                            Level::DEBUG => debug!("http-debug: {prefix} {line}"),
+                            _ => trace!("http-debug: {prefix} {line}"),
+                        }
                     }
                 }
                 Err(_) => {
-                    log!(
-                        level,
-                        "http-debug: {} ({} bytes of data)",
-                        prefix,
-                        data.len()
-                    );
+                    let len = data.len();
+                    match level {
+                        Level::DEBUG => debug!("http-debug: {prefix} ({len} bytes of data)"),
+                        _ => trace!("http-debug: {prefix} ({len} bytes of data)"),
+                    }
                 }
             }
         })?;
@@ -4,6 +4,7 @@ use std::task::Poll;
 
 pub mod http;
 pub mod proxy;
+pub mod report;
 pub mod retry;
 pub mod sleep;
 
@@ -29,7 +30,7 @@ macro_rules! try_old_curl {
         let result = $e;
         if cfg!(target_os = "macos") {
             if let Err(e) = result {
-                ::log::warn!("ignoring libcurl {} error: {}", $msg, e);
+                ::tracing::warn!("ignoring libcurl {} error: {}", $msg, e);
             }
         } else {
             use ::anyhow::Context;
@@ -1,5 +1,8 @@
 //! Utilities for network proxies.
 
+use std::process::Command;
+use std::sync::OnceLock;
+
 use crate::util::config::CargoHttpConfig;
 use crate::util::config::Config;
 
@@ -34,9 +37,177 @@ pub fn http_proxy(http: &CargoHttpConfig) -> Option<String> {
 /// * `HTTP_PROXY` env var
 /// * `https_proxy` env var
 /// * `HTTPS_PROXY` env var
+/// * The proxy of the system, with `-Zproxy-config`
 pub fn http_proxy_exists(http: &CargoHttpConfig, config: &Config) -> bool {
     http_proxy(http).is_some()
-        || LIBCURL_HTTP_PROXY_ENVS
-            .iter()
-            .any(|v| config.get_env(v).is_ok())
+        || env_proxy_exists(config)
+        || (config.cli_unstable().proxy_config && system_proxy().is_some())
+}
+
+/// Determine if a proxy is set with one of [`LIBCURL_HTTP_PROXY_ENVS`].
+pub fn env_proxy_exists(config: &Config) -> bool {
+    LIBCURL_HTTP_PROXY_ENVS
+        .iter()
+        .any(|v| config.get_env(v).is_ok())
+}
+
+/// A proxy configured in the settings of the operating system.
+#[derive(Debug, PartialEq)]
+pub struct SystemProxy {
+    pub proxy: String,
+    /// Hosts to connect to directly, in the format of curl's `noproxy`.
+    pub no_proxy: Vec<String>,
+}
+
+/// Finds the proxy configured in the settings of the operating system, on
+/// Windows and macOS, where libcurl doesn't pick it up on its own.
+///
+/// Cargo only uses it with `-Zproxy-config` when neither an explicit proxy
+/// nor a proxy environment variable is set.
+pub fn system_proxy() -> Option<&'static SystemProxy> {
+    static PROXY: OnceLock<Option<SystemProxy>> = OnceLock::new();
+    PROXY
+        .get_or_init(|| {
+            let proxy = if cfg!(windows) {
+                let output = Command::new("reg")
+                    .args([
+                        "query",
+                        r"HKCU\Software\Microsoft\Windows\CurrentVersion\Internet Settings",
+                    ])
+                    .output()
+                    .ok()?;
+                parse_windows_proxy(&String::from_utf8_lossy(&output.stdout))
+            } else if cfg!(target_os = "macos") {
+                let output = Command::new("scutil").arg("--proxy").output().ok()?;
+                parse_macos_proxy(&String::from_utf8_lossy(&output.stdout))
+            } else {
+                None
+            };
+            tracing::debug!("system proxy: {proxy:?}");
+            proxy
+        })
+        .as_ref()
+}
+
+/// Parses the output of `reg query` for the `Internet Settings` key.
+fn parse_windows_proxy(output: &str) -> Option<SystemProxy> {
+    let value = |name: &str| {
+        output.lines().find_map(|line| {
+            let mut parts = line.split_whitespace();
+            if parts.next()? != name {
+                return None;
+            }
+            // Skip the type, like `REG_SZ`.
+            parts.next()?;
+            Some(parts.collect::<Vec<_>>().join(" "))
+        })
+    };
+    if value("ProxyEnable")? != "0x1" {
+        return None;
+    }
+    // Either a single proxy, or one per protocol like
+    // `http=proxy:8080;https=proxy:8443`.
+    let server = value("ProxyServer")?;
+    let proxy = if server.contains('=') {
+        let for_protocol = |protocol: &str| {
+            server.split(';').find_map(|entry| {
+                let (p, proxy) = entry.split_once('=')?;
+                (p.trim() == protocol).then(|| proxy.trim().to_string())
+            })
+        };
+        for_protocol("https").or_else(|| for_protocol("http"))?
+    } else {
+        server
+    };
+    // `<local>` (hosts without a dot) can't be expressed for curl.
+    let no_proxy = value("ProxyOverride")
+        .unwrap_or_default()
+        .split(';')
+        .map(str::trim)
+        .filter(|host| !host.is_empty() && *host != "<local>")
+        .map(|host| host.trim_start_matches('*').to_string())
+        .collect();
+    Some(SystemProxy { proxy, no_proxy })
+}
+
+/// Parses the output of `scutil --proxy`.
+fn parse_macos_proxy(output: &str) -> Option<SystemProxy> {
+    let value = |name: &str| {
+        output.lines().find_map(|line| {
+            let (key, value) = line.split_once(" : ")?;
+            (key.trim() == name).then(|| value.trim())
+        })
+    };
+    let proxy = ["HTTPS", "HTTP"].iter().find_map(|protocol| {
+        if value(&format!("{protocol}Enable"))? != "1" {
+            return None;
+        }
+        let host = value(&format!("{protocol}Proxy"))?;
+        Some(match value(&format!("{protocol}Port")) {
+            Some(port) => format!("{host}:{port}"),
+            None => host.to_string(),
+        })
+    })?;
+    let mut no_proxy = Vec::new();
+    let mut in_exceptions = false;
+    for line in output.lines() {
+        let line = line.trim();
+        if line.starts_with("ExceptionsList : ") {
+            in_exceptions = true;
+        } else if in_exceptions && line == "}" {
+            in_exceptions = false;
+        } else if let Some((_, host)) = line.split_once(" : ").filter(|_| in_exceptions) {
+            no_proxy.push(host.trim_start_matches('*').to_string());
+        }
+    }
+    Some(SystemProxy { proxy, no_proxy })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn windows_proxy() {
+        let output = r"
+HKEY_CURRENT_USER\Software\Microsoft\Windows\CurrentVersion\Internet Settings
+    ProxyEnable    REG_DWORD    0x1
+    ProxyServer    REG_SZ    http=proxy.example.com:8080;https=proxy.example.com:8443
+    ProxyOverride    REG_SZ    *.internal.example.com;10.0.0.1;<local>
+";
+        assert_eq!(
+            parse_windows_proxy(output),
+            Some(SystemProxy {
+                proxy: "proxy.example.com:8443".to_string(),
+                no_proxy: vec![".internal.example.com".to_string(), "10.0.0.1".to_string()],
+            })
+        );
+        let disabled = output.replace("0x1", "0x0");
+        assert_eq!(parse_windows_proxy(&disabled), None);
+    }
+
+    #[test]
+    fn macos_proxy() {
+        let output = "
+<dictionary> {
+  ExceptionsList : <array> {
+    0 : *.local
+    1 : 169.254/16
+  }
+  FTPPassive : 1
+  HTTPEnable : 1
+  HTTPPort : 3128
+  HTTPProxy : proxy.example.com
+  HTTPSEnable : 0
+}
+";
+        assert_eq!(
+            parse_macos_proxy(output),
+            Some(SystemProxy {
+                proxy: "proxy.example.com:3128".to_string(),
+                no_proxy: vec![".local".to_string(), "169.254/16".to_string()],
+            })
+        );
+        assert_eq!(parse_macos_proxy("<dictionary> {\n}\n"), None);
+    }
 }
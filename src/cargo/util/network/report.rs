@@ -0,0 +1,133 @@
+//! Statistics about network requests, printed with `cargo --network-report`.
+//!
+//! The download managers of [`PackageSet`] and of sparse registries record
+//! every finished attempt of a request into the [`NetworkReport`] of the
+//! [`Config`], which is printed once the command is done.
+//!
+//! [`PackageSet`]: crate::core::PackageSet
+//! [`Config`]: crate::util::Config
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use bytesize::ByteSize;
+use curl::easy::Easy;
+
+use crate::core::Shell;
+use crate::util::errors::CargoResult;
+
+/// A single finished attempt of a request.
+#[derive(Debug)]
+struct Request {
+    url: String,
+    /// The HTTP version of the response, like `HTTP/2`, or `None` if no
+    /// response was received.
+    http_version: Option<String>,
+    /// The response code, `0` if no response was received.
+    code: u32,
+    bytes: u64,
+    time: Duration,
+    /// Whether the attempt failed with a spurious error and was retried.
+    retried: bool,
+}
+
+/// Statistics about the network requests made during a command.
+#[derive(Debug, Default)]
+pub struct NetworkReport {
+    requests: Vec<Request>,
+    /// Number of packages that didn't need to be downloaded as they were
+    /// already in the download cache.
+    cache_hits: usize,
+}
+
+impl NetworkReport {
+    /// Records a finished attempt to download `url` with `handle`.
+    ///
+    /// `http_version` is the version of the status line of the response, see
+    /// [`http_version`].
+    pub fn record(
+        &mut self,
+        handle: &mut Easy,
+        url: &str,
+        http_version: Option<String>,
+        retried: bool,
+    ) {
+        self.requests.push(Request {
+            url: url.to_string(),
+            http_version,
+            code: handle.response_code().unwrap_or(0),
+            bytes: handle.download_size().unwrap_or(0.0) as u64,
+            time: handle.total_time().unwrap_or_default(),
+            retried,
+        });
+    }
+
+    /// Records a package that was found in the download cache.
+    pub fn record_cache_hit(&mut self) {
+        self.cache_hits += 1;
+    }
+
+    /// Prints a summary of the requests, and every request with `--verbose`.
+    pub fn print(&self, shell: &mut Shell) -> CargoResult<()> {
+        shell.verbose(|shell| {
+            for request in &self.requests {
+                let mut msg = format!(
+                    "{} {} {} ({}, {:.2}s)",
+                    request.http_version.as_deref().unwrap_or("-"),
+                    request.code,
+                    request.url,
+                    ByteSize(request.bytes),
+                    request.time.as_secs_f64()
+                );
+                if request.retried {
+                    msg.push_str(", retried");
+                }
+                shell.status("Request", msg)?;
+            }
+            Ok(())
+        })?;
+
+        let retried = self.requests.iter().filter(|r| r.retried).count();
+        let not_modified = self.requests.iter().filter(|r| r.code == 304).count();
+        let bytes: u64 = self.requests.iter().map(|r| r.bytes).sum();
+        shell.status(
+            "Network",
+            format!(
+                "{} requests ({retried} retried, {not_modified} not modified), \
+                 {} cache hits, {} downloaded",
+                self.requests.len(),
+                self.cache_hits,
+                ByteSize(bytes)
+            ),
+        )?;
+
+        let mut by_version: BTreeMap<&str, Vec<&Request>> = BTreeMap::new();
+        for request in &self.requests {
+            let version = request.http_version.as_deref().unwrap_or("no response");
+            by_version.entry(version).or_default().push(request);
+        }
+        for (version, requests) in by_version {
+            let total: Duration = requests.iter().map(|r| r.time).sum();
+            let slowest = requests.iter().max_by_key(|r| r.time).unwrap();
+            shell.status(
+                "Network",
+                format!(
+                    "{version}: {} requests, {:.2}s on average, slowest {:.2}s for {}",
+                    requests.len(),
+                    total.as_secs_f64() / requests.len() as f64,
+                    slowest.time.as_secs_f64(),
+                    slowest.url
+                ),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Gets the HTTP version of a response header line, if it is a status line
+/// like `HTTP/1.1 200 OK`.
+pub fn http_version(header: &[u8]) -> Option<String> {
+    let line = std::str::from_utf8(header).ok()?;
+    let version = line.split_whitespace().next()?;
+    version.starts_with("HTTP/").then(|| version.to_string())
+}
@@ -4,7 +4,6 @@ use crate::util::errors::HttpNotSuccessful;
 use crate::{CargoResult, Config};
 use anyhow::Error;
 use rand::Rng;
-use std::cmp::min;
 use std::time::Duration;
 
 pub struct Retry<'a> {
@@ -21,14 +20,15 @@ pub enum RetryResult<T> {
 
 /// Maximum amount of time a single retry can be delayed (milliseconds).
 const MAX_RETRY_SLEEP_MS: u64 = 10 * 1000;
-/// The minimum initial amount of time a retry will be delayed (milliseconds).
+/// The minimum initial amount of time a retry will be delayed (milliseconds),
+/// which doubles with every retry.
 ///
 /// The actual amount of time will be a random value above this.
 const INITIAL_RETRY_SLEEP_BASE_MS: u64 = 500;
-/// The maximum amount of additional time the initial retry will take (milliseconds).
+/// The maximum amount of additional time a retry will take (milliseconds).
 ///
-/// The initial delay will be [`INITIAL_RETRY_SLEEP_BASE_MS`] plus a random range
-/// from 0 to this value.
+/// Each delay will be the backoff based on [`INITIAL_RETRY_SLEEP_BASE_MS`] plus
+/// a random range from 0 to this value.
 const INITIAL_RETRY_JITTER_MS: u64 = 1000;
 
 impl<'a> Retry<'a> {
@@ -47,6 +47,13 @@ impl<'a> Retry<'a> {
                 let err_msg = e
                     .downcast_ref::<HttpNotSuccessful>()
                     .map(|http_err| http_err.display_short())
+                    .or_else(|| match e.downcast_ref::<crates_io::Error>() {
+                        // Like `display_short`, without the headers.
+                        Some(crates_io::Error::Code { code, body, .. }) => Some(format!(
+                            "failed to get a 200 OK response, got {code}\nbody:\n{body}"
+                        )),
+                        _ => None,
+                    })
                     .unwrap_or_else(|| e.root_cause().to_string());
                 let msg = format!(
                     "spurious network error ({} tries remaining): {err_msg}",
@@ -64,20 +71,20 @@ impl<'a> Retry<'a> {
     }
 
     /// Gets the next sleep duration in milliseconds.
+    ///
+    /// The delay doubles with every retry, up to [`MAX_RETRY_SLEEP_MS`], plus
+    /// a random jitter so that many clients failing at once don't retry in
+    /// lockstep.
     fn next_sleep_ms(&self) -> u64 {
         if let Ok(sleep) = self.config.get_env("__CARGO_TEST_FIXED_RETRY_SLEEP_MS") {
             return sleep.parse().expect("a u64");
         }
 
-        if self.retries == 1 {
-            let mut rng = rand::thread_rng();
-            INITIAL_RETRY_SLEEP_BASE_MS + rng.gen_range(0..INITIAL_RETRY_JITTER_MS)
-        } else {
-            min(
-                ((self.retries - 1) * 3) * 1000 + INITIAL_RETRY_SLEEP_BASE_MS,
-                MAX_RETRY_SLEEP_MS,
-            )
-        }
+        let backoff = INITIAL_RETRY_SLEEP_BASE_MS
+            .saturating_mul(1 << (self.retries - 1).min(16))
+            .min(MAX_RETRY_SLEEP_MS - INITIAL_RETRY_JITTER_MS);
+        let mut rng = rand::thread_rng();
+        backoff + rng.gen_range(0..INITIAL_RETRY_JITTER_MS)
     }
 }
 
@@ -92,24 +99,26 @@ fn maybe_spurious(err: &Error) -> bool {
         }
     }
     if let Some(curl_err) = err.downcast_ref::<curl::Error>() {
-        if curl_err.is_couldnt_connect()
-            || curl_err.is_couldnt_resolve_proxy()
-            || curl_err.is_couldnt_resolve_host()
-            || curl_err.is_operation_timedout()
-            || curl_err.is_recv_error()
-            || curl_err.is_send_error()
-            || curl_err.is_http2_error()
-            || curl_err.is_http2_stream_error()
-            || curl_err.is_ssl_connect_error()
-            || curl_err.is_partial_file()
-        {
+        if is_spurious_curl_error(curl_err) {
             return true;
         }
     }
     if let Some(not_200) = err.downcast_ref::<HttpNotSuccessful>() {
-        if 500 <= not_200.code && not_200.code < 600 {
+        if is_spurious_status(not_200.code) {
+            return true;
+        }
+    }
+    // Errors of the registry web API.
+    match err.downcast_ref::<crates_io::Error>() {
+        Some(crates_io::Error::Curl(curl_err)) if is_spurious_curl_error(curl_err) => {
             return true;
         }
+        Some(crates_io::Error::Api { code, .. } | crates_io::Error::Code { code, .. })
+            if is_spurious_status(*code) =>
+        {
+            return true;
+        }
+        _ => {}
     }
 
     use gix::protocol::transport::IsSpuriousError;
@@ -123,6 +132,25 @@ fn maybe_spurious(err: &Error) -> bool {
     false
 }
 
+fn is_spurious_curl_error(curl_err: &curl::Error) -> bool {
+    curl_err.is_couldnt_connect()
+        || curl_err.is_couldnt_resolve_proxy()
+        || curl_err.is_couldnt_resolve_host()
+        || curl_err.is_operation_timedout()
+        || curl_err.is_recv_error()
+        || curl_err.is_send_error()
+        || curl_err.is_http2_error()
+        || curl_err.is_http2_stream_error()
+        || curl_err.is_ssl_connect_error()
+        || curl_err.is_partial_file()
+        || curl_err.is_got_nothing()
+}
+
+/// Server errors, and `429 Too Many Requests` of rate limited requests.
+fn is_spurious_status(code: u32) -> bool {
+    code == 429 || (500..600).contains(&code)
+}
+
 /// Wrapper method for network call retry logic.
 ///
 /// Retry counts provided by Config object `net.retry`. Config shell outputs
@@ -226,22 +254,13 @@ fn default_retry_schedule() {
     let config = Config::default().unwrap();
     *config.shell() = Shell::from_write(Box::new(Vec::new()));
     let mut retry = Retry::new(&config).unwrap();
-    match retry.r#try(|| spurious()) {
-        RetryResult::Retry(sleep) => {
-            assert!(
-                sleep >= INITIAL_RETRY_SLEEP_BASE_MS
-                    && sleep < INITIAL_RETRY_SLEEP_BASE_MS + INITIAL_RETRY_JITTER_MS
-            );
+    for base in [500, 1000, 2000] {
+        match retry.r#try(|| spurious()) {
+            RetryResult::Retry(sleep) => {
+                assert!(sleep >= base && sleep < base + INITIAL_RETRY_JITTER_MS);
+            }
+            _ => panic!("unexpected non-retry"),
         }
-        _ => panic!("unexpected non-retry"),
-    }
-    match retry.r#try(|| spurious()) {
-        RetryResult::Retry(sleep) => assert_eq!(sleep, 3500),
-        _ => panic!("unexpected non-retry"),
-    }
-    match retry.r#try(|| spurious()) {
-        RetryResult::Retry(sleep) => assert_eq!(sleep, 6500),
-        _ => panic!("unexpected non-retry"),
     }
     match retry.r#try(|| spurious()) {
         RetryResult::Err(_) => {}
@@ -249,6 +268,40 @@ fn default_retry_schedule() {
     }
 }
 
+#[test]
+fn retry_schedule_is_capped() {
+    use crate::core::Shell;
+
+    let spurious =
+        || -> CargoResult<()> { Err(curl::Error::new(curl_sys::CURLE_RECV_ERROR).into()) };
+    let config = Config::default().unwrap();
+    *config.shell() = Shell::from_write(Box::new(Vec::new()));
+    let mut retry = Retry::new(&config).unwrap();
+    retry.max_retries = 100;
+    for _ in 0..100 {
+        match retry.r#try(|| spurious()) {
+            RetryResult::Retry(sleep) => assert!(sleep < MAX_RETRY_SLEEP_MS),
+            _ => panic!("unexpected non-retry"),
+        }
+    }
+}
+
+#[test]
+fn rate_limited_api_errors_are_spurious() {
+    let err = crates_io::Error::Code {
+        code: 429,
+        headers: Vec::new(),
+        body: String::new(),
+    };
+    assert!(maybe_spurious(&err.into()));
+    let err = crates_io::Error::Api {
+        code: 403,
+        headers: Vec::new(),
+        errors: vec!["forbidden".to_string()],
+    };
+    assert!(!maybe_spurious(&err.into()));
+}
+
 #[test]
 fn curle_http2_stream_is_spurious() {
     let code = curl_sys::CURLE_HTTP2_STREAM;
@@ -1,5 +1,14 @@
 //! An internal performance profiler for Cargo itself.
 //!
+//! With `CARGO_PROFILE`, the time spent in each [`start`] scope is printed,
+//! for contributors. With `-Zself-profile`, the scopes are recorded and saved
+//! with [`save_self_profile`] in the [Chrome trace format], which can be
+//! opened as a flame graph in tools like [Perfetto] or [speedscope].
+//!
+//! [Chrome trace format]: https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU
+//! [Perfetto]: https://ui.perfetto.dev
+//! [speedscope]: https://www.speedscope.app
+//!
 //! > **Note**: This might not be the module you are looking for.
 //! > For information about how Cargo handles compiler flags with profiles,
 //! > please see the module [`cargo::core::profiles`](crate::core::profiles).
@@ -10,15 +19,49 @@ use std::fmt;
 use std::io::{stdout, StdoutLock, Write};
 use std::iter::repeat;
 use std::mem;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
 use std::time;
 
+use cargo_util::paths;
+use serde::Serialize;
+
+use crate::util::CargoResult;
+
 thread_local!(static PROFILE_STACK: RefCell<Vec<time::Instant>> = RefCell::new(Vec::new()));
 thread_local!(static MESSAGES: RefCell<Vec<Message>> = RefCell::new(Vec::new()));
+thread_local!(static THREAD_ID: usize = NEXT_THREAD_ID.fetch_add(1, Ordering::Relaxed));
 
 type Message = (usize, u64, String);
 
+static NEXT_THREAD_ID: AtomicUsize = AtomicUsize::new(1);
+static SELF_PROFILING: AtomicBool = AtomicBool::new(false);
+static SELF_PROFILE: Mutex<Option<SelfProfile>> = Mutex::new(None);
+
 pub struct Profiler {
     desc: String,
+    /// When the scope started, if it is recorded for `-Zself-profile`.
+    start: Option<time::Instant>,
+}
+
+/// The scopes recorded for `-Zself-profile`.
+struct SelfProfile {
+    /// The time the events are relative to.
+    start: time::Instant,
+    events: Vec<TraceEvent>,
+}
+
+/// A complete event of the Chrome trace format, with times in microseconds.
+#[derive(Serialize)]
+struct TraceEvent {
+    name: String,
+    ph: &'static str,
+    ts: u128,
+    dur: u128,
+    pid: u32,
+    tid: usize,
 }
 
 fn enabled_level() -> Option<usize> {
@@ -28,21 +71,82 @@ fn enabled_level() -> Option<usize> {
 }
 
 pub fn start<T: fmt::Display>(desc: T) -> Profiler {
-    if enabled_level().is_none() {
+    let printed = enabled_level().is_some();
+    let recorded = SELF_PROFILING.load(Ordering::Relaxed);
+    if !printed && !recorded {
         return Profiler {
             desc: String::new(),
+            start: None,
         };
     }
 
-    PROFILE_STACK.with(|stack| stack.borrow_mut().push(time::Instant::now()));
+    let now = time::Instant::now();
+    if printed {
+        PROFILE_STACK.with(|stack| stack.borrow_mut().push(now));
+    }
 
     Profiler {
         desc: desc.to_string(),
+        start: recorded.then_some(now),
     }
 }
 
+/// Starts recording the scopes for `-Zself-profile`, with times relative to
+/// `start`, the time Cargo started.
+pub fn start_self_profile(start: time::Instant) {
+    *SELF_PROFILE.lock().unwrap() = Some(SelfProfile {
+        start,
+        events: Vec::new(),
+    });
+    SELF_PROFILING.store(true, Ordering::Relaxed);
+}
+
+/// Records a scope that started at `start` and ends now, for things that
+/// happen before they can be wrapped in a [`start`] scope.
+pub fn record(desc: impl fmt::Display, start: time::Instant) {
+    let mut profile = SELF_PROFILE.lock().unwrap();
+    let Some(profile) = profile.as_mut() else {
+        return;
+    };
+    let ts = start.saturating_duration_since(profile.start);
+    profile.events.push(TraceEvent {
+        name: desc.to_string(),
+        ph: "X",
+        ts: ts.as_micros(),
+        dur: start.elapsed().as_micros(),
+        pid: process::id(),
+        tid: THREAD_ID.with(|id| *id),
+    });
+}
+
+/// Writes the scopes recorded since the last call to
+/// `<dir>/cargo-self-profile-<timestamp>.json`, and links it to
+/// `<dir>/cargo-self-profile.json`. Returns the path of the profile.
+pub fn save_self_profile(dir: &Path) -> CargoResult<Option<PathBuf>> {
+    let events = match SELF_PROFILE.lock().unwrap().as_mut() {
+        Some(profile) => mem::take(&mut profile.events),
+        None => return Ok(None),
+    };
+    let timestamp = humantime::format_rfc3339_seconds(time::SystemTime::now())
+        .to_string()
+        .replace(&['-', ':'][..], "");
+    paths::create_dir_all(dir)?;
+    let path = dir.join(format!("cargo-self-profile-{timestamp}.json"));
+    let trace = serde_json::json!({
+        "traceEvents": events,
+        "displayTimeUnit": "ms",
+    });
+    paths::write(&path, serde_json::to_vec(&trace)?)?;
+    paths::link_or_copy(&path, dir.join("cargo-self-profile.json"))?;
+    Ok(Some(path))
+}
+
 impl Drop for Profiler {
     fn drop(&mut self) {
+        if let Some(start) = self.start {
+            record(&self.desc, start);
+        }
+
         let enabled = match enabled_level() {
             Some(i) => i,
             None => return,
@@ -26,6 +26,11 @@ use unicode_width::UnicodeWidthChar;
 /// too fast. It should usually be fine to call [`Progress::tick`] as often as
 /// needed, though be cautious if the tick rate is very high or it is
 /// expensive to compute the progress value.
+///
+/// When the shell has [plain output](crate::core::Shell::plain_output), no bar
+/// is drawn. The progress is instead printed as a status line, like
+/// `Building 35/222: foo`, at most once every few seconds and only when it
+/// changed.
 pub struct Progress<'cfg> {
     state: Option<State<'cfg>>,
 }
@@ -68,8 +73,14 @@ struct State<'cfg> {
     throttle: Throttle,
     last_line: Option<String>,
     fixed_width: Option<usize>,
+    /// When the last line of plain output was printed, if the shell has plain
+    /// output.
+    last_plain: Option<Instant>,
 }
 
+/// How often progress is printed with plain output.
+const PLAIN_INTERVAL: Duration = Duration::from_secs(5);
+
 struct Format {
     style: ProgressStyle,
     max_width: usize,
@@ -91,8 +102,9 @@ impl<'cfg> Progress<'cfg> {
         // report no progress when -q (for quiet) or TERM=dumb are set
         // or if running on Continuous Integration service like Travis where the
         // output logs get mangled.
+        // Plain output doesn't need the terminal to move the cursor.
         let dumb = match cfg.get_env("TERM") {
-            Ok(term) => term == "dumb",
+            Ok(term) => term == "dumb" && !cfg.shell().plain_output(),
             Err(_) => false,
         };
         let progress_config = cfg.progress_config();
@@ -109,9 +121,12 @@ impl<'cfg> Progress<'cfg> {
 
     fn new_priv(name: &str, style: ProgressStyle, cfg: &'cfg Config) -> Progress<'cfg> {
         let progress_config = cfg.progress_config();
+        let plain = cfg.shell().plain_output();
         let width = progress_config
             .width
-            .or_else(|| cfg.shell().err_width().progress_max_width());
+            .or_else(|| cfg.shell().err_width().progress_max_width())
+            // Lines of plain output aren't fitted to the terminal.
+            .or(plain.then_some(0));
 
         Progress {
             state: width.map(|n| State {
@@ -128,6 +143,7 @@ impl<'cfg> Progress<'cfg> {
                 throttle: Throttle::new(),
                 last_line: None,
                 fixed_width: progress_config.width,
+                last_plain: plain.then(Instant::now),
             }),
         }
     }
@@ -273,6 +289,11 @@ impl<'cfg> State<'cfg> {
             self.done = true;
         }
 
+        if self.last_plain.is_some() {
+            let line = self.format.plain(cur, max, msg);
+            return self.print_plain(line);
+        }
+
         // Write out a pretty header, then the progress bar itself, and then
         // return back to the beginning of the line for the next print.
         self.try_update_max_width();
@@ -283,6 +304,10 @@ impl<'cfg> State<'cfg> {
     }
 
     fn print(&mut self, prefix: &str, msg: &str) -> CargoResult<()> {
+        if self.last_plain.is_some() {
+            let line = format!("{prefix}{msg}");
+            return self.print_plain(line);
+        }
         self.throttle.update();
         self.try_update_max_width();
 
@@ -310,7 +335,32 @@ impl<'cfg> State<'cfg> {
         Ok(())
     }
 
+    /// Prints `line` as a status, if it changed and the last one was printed
+    /// long enough ago.
+    fn print_plain(&mut self, line: String) -> CargoResult<()> {
+        let Some(last_plain) = self.last_plain else {
+            return Ok(());
+        };
+        let interval = self
+            .config
+            .get_env("__CARGO_TEST_PLAIN_PROGRESS_INTERVAL_MS")
+            .ok()
+            .and_then(|ms| ms.parse().ok())
+            .map_or(PLAIN_INTERVAL, Duration::from_millis);
+        if last_plain.elapsed() < interval || self.last_line.as_ref() == Some(&line) {
+            return Ok(());
+        }
+        self.config.shell().status(&self.name, &line)?;
+        self.last_plain = Some(Instant::now());
+        self.last_line = Some(line);
+        Ok(())
+    }
+
     fn clear(&mut self) {
+        // Plain output is never cleared.
+        if self.last_plain.is_some() {
+            return;
+        }
         // No need to clear if the progress is not currently being displayed.
         if self.last_line.is_some() && !self.config.shell().is_cleared() {
             self.config.shell().err_erase_line();
@@ -334,11 +384,7 @@ impl Format {
         // progress bar is
         let pct = (cur as f64) / (max as f64);
         let pct = if !pct.is_finite() { 0.0 } else { pct };
-        let stats = match self.style {
-            ProgressStyle::Percentage => format!(" {:6.02}%", pct * 100.0),
-            ProgressStyle::Ratio => format!(" {}/{}", cur, max),
-            ProgressStyle::Indeterminate => String::new(),
-        };
+        let stats = self.stats(cur, max);
         let extra_len = stats.len() + 2 /* [ and ] */ + 15 /* status header */;
         let display_width = match self.width().checked_sub(extra_len) {
             Some(n) => n,
@@ -372,6 +418,29 @@ impl Format {
         Some(string)
     }
 
+    /// The amount of progress, displayed after the bar.
+    fn stats(&self, cur: usize, max: usize) -> String {
+        let pct = (cur as f64) / (max as f64);
+        let pct = if !pct.is_finite() { 0.0 } else { pct };
+        match self.style {
+            ProgressStyle::Percentage => format!(" {:6.02}%", pct * 100.0),
+            ProgressStyle::Ratio => format!(" {}/{}", cur, max),
+            ProgressStyle::Indeterminate => String::new(),
+        }
+    }
+
+    /// The line of plain output, the amount of progress followed by `msg`,
+    /// without the separator `msg` starts with when there's no amount.
+    fn plain(&self, cur: usize, max: usize, msg: &str) -> String {
+        let stats = self.stats(cur, max);
+        let stats = stats.trim_start();
+        if stats.is_empty() {
+            msg.trim_start_matches([',', ':', ' ']).to_string()
+        } else {
+            format!("{stats}{msg}")
+        }
+    }
+
     fn render(&self, string: &mut String, msg: &str) {
         let mut avail_msg_len = self.max_width - string.len() - 15;
         let mut ellipsis_pos = 0;
@@ -510,6 +579,32 @@ fn test_progress_status_percentage() {
     );
 }
 
+#[test]
+fn test_plain_progress() {
+    let format = Format {
+        style: ProgressStyle::Ratio,
+        max_print: 40,
+        max_width: 60,
+    };
+    assert_eq!(format.plain(3, 4, ": foo, bar"), "3/4: foo, bar");
+    let long = ": a message that is longer than the width of the progress bar";
+    assert_eq!(format.plain(3, 4, long), format!("3/4{long}"));
+
+    let format = Format {
+        style: ProgressStyle::Percentage,
+        max_print: 40,
+        max_width: 60,
+    };
+    assert_eq!(format.plain(1, 77, ""), "1.30%");
+
+    let format = Format {
+        style: ProgressStyle::Indeterminate,
+        max_print: 40,
+        max_width: 60,
+    };
+    assert_eq!(format.plain(1, 2, ", 3 objects"), "3 objects");
+}
+
 #[test]
 fn test_progress_status_too_short() {
     let format = Format {
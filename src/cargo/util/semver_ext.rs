@@ -1,5 +1,7 @@
-use semver::{Comparator, Op, Version, VersionReq};
+use semver::{Comparator, Op, Prerelease, Version, VersionReq};
+use std::cmp::Ordering;
 use std::fmt::{self, Display};
+use std::ops::Bound;
 
 #[derive(PartialEq, Eq, Hash, Clone, Debug)]
 pub enum OptVersionReq {
@@ -90,6 +92,179 @@ impl OptVersionReq {
             }
         }
     }
+
+    /// Like [`OptVersionReq::matches`], but matches against the original
+    /// requirement of a locked req instead of the version it is locked to.
+    pub fn matches_unlocked(&self, version: &Version) -> bool {
+        match self {
+            OptVersionReq::Locked(_, req) => req.matches(version),
+            _ => self.matches(version),
+        }
+    }
+
+    /// The range of versions the original requirement allows, see
+    /// [`VersionInterval`].
+    pub fn interval(&self) -> VersionInterval {
+        match self {
+            OptVersionReq::Any => VersionInterval::ANY,
+            OptVersionReq::Req(req) | OptVersionReq::Locked(_, req) => {
+                VersionInterval::from_req(req)
+            }
+        }
+    }
+}
+
+/// The range of versions between two bounds that a [`VersionReq`] allows.
+///
+/// This ignores the rule that pre-release versions only match requirements
+/// on the same version, so it is only meant to compare and display
+/// requirements.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct VersionInterval {
+    pub min: Bound<Version>,
+    pub max: Bound<Version>,
+}
+
+impl VersionInterval {
+    pub const ANY: VersionInterval = VersionInterval {
+        min: Bound::Unbounded,
+        max: Bound::Unbounded,
+    };
+
+    /// The intersection of the intervals of the comparators of `req`.
+    pub fn from_req(req: &VersionReq) -> VersionInterval {
+        req.comparators
+            .iter()
+            .map(VersionInterval::from_comparator)
+            .fold(VersionInterval::ANY, |a, b| a.intersect(&b))
+    }
+
+    fn from_comparator(cmp: &Comparator) -> VersionInterval {
+        let version = |major, minor, patch, pre: &Prerelease| Version {
+            major,
+            minor,
+            patch,
+            pre: pre.clone(),
+            build: Default::default(),
+        };
+        let none = &Prerelease::EMPTY;
+        let (major, minor, patch) = (cmp.major, cmp.minor, cmp.patch);
+        // The first version after the ones matching the given components.
+        let next = match (minor, patch) {
+            (Some(minor), Some(patch)) => version(major, minor, patch + 1, none),
+            (Some(minor), None) => version(major, minor + 1, 0, none),
+            (None, _) => version(major + 1, 0, 0, none),
+        };
+        let lowest = version(major, minor.unwrap_or(0), patch.unwrap_or(0), &cmp.pre);
+        let (min, max) = match cmp.op {
+            Op::Exact if patch.is_some() => {
+                (Bound::Included(lowest.clone()), Bound::Included(lowest))
+            }
+            Op::Exact | Op::Wildcard => (Bound::Included(lowest), Bound::Excluded(next)),
+            Op::Greater if patch.is_some() => (Bound::Excluded(lowest), Bound::Unbounded),
+            Op::Greater => (Bound::Included(next), Bound::Unbounded),
+            Op::GreaterEq => (Bound::Included(lowest), Bound::Unbounded),
+            Op::Less => (Bound::Unbounded, Bound::Excluded(lowest)),
+            Op::LessEq if patch.is_some() => (Bound::Unbounded, Bound::Included(lowest)),
+            Op::LessEq => (Bound::Unbounded, Bound::Excluded(next)),
+            Op::Tilde => {
+                let max = match minor {
+                    Some(minor) => version(major, minor + 1, 0, none),
+                    None => version(major + 1, 0, 0, none),
+                };
+                (Bound::Included(lowest), Bound::Excluded(max))
+            }
+            Op::Caret => {
+                let max = match (major, minor) {
+                    (0, Some(0)) => next,
+                    (0, Some(minor)) => version(0, minor + 1, 0, none),
+                    _ => version(major + 1, 0, 0, none),
+                };
+                (Bound::Included(lowest), Bound::Excluded(max))
+            }
+            _ => (Bound::Unbounded, Bound::Unbounded),
+        };
+        VersionInterval { min, max }
+    }
+
+    /// The versions in both `self` and `other`.
+    pub fn intersect(&self, other: &VersionInterval) -> VersionInterval {
+        let min = match cmp_bounds(&self.min, &other.min, Ordering::Less) {
+            Ordering::Less => other.min.clone(),
+            _ => self.min.clone(),
+        };
+        let max = match cmp_bounds(&self.max, &other.max, Ordering::Greater) {
+            Ordering::Greater => other.max.clone(),
+            _ => self.max.clone(),
+        };
+        VersionInterval { min, max }
+    }
+
+    /// Whether no version is in the interval.
+    pub fn is_empty(&self) -> bool {
+        let (Some((min, min_included)), Some((max, max_included))) =
+            (bound_version(&self.min), bound_version(&self.max))
+        else {
+            return false;
+        };
+        match min.cmp(max) {
+            Ordering::Less => false,
+            Ordering::Equal => !(min_included && max_included),
+            Ordering::Greater => true,
+        }
+    }
+}
+
+fn bound_version(bound: &Bound<Version>) -> Option<(&Version, bool)> {
+    match bound {
+        Bound::Included(v) => Some((v, true)),
+        Bound::Excluded(v) => Some((v, false)),
+        Bound::Unbounded => None,
+    }
+}
+
+/// Compares two bounds on the same side of an interval. `unbounded` is how
+/// an unbounded side compares to any other bound, and an excluded bound is
+/// the opposite of that, compared to an included bound on the same version.
+fn cmp_bounds(a: &Bound<Version>, b: &Bound<Version>, unbounded: Ordering) -> Ordering {
+    match (bound_version(a), bound_version(b)) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => unbounded,
+        (Some(_), None) => unbounded.reverse(),
+        (Some((a, a_included)), Some((b, b_included))) => {
+            a.cmp(b).then(match (a_included, b_included) {
+                (true, false) => unbounded,
+                (false, true) => unbounded.reverse(),
+                _ => Ordering::Equal,
+            })
+        }
+    }
+}
+
+impl Display for VersionInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return f.write_str("no version");
+        }
+        match (&self.min, &self.max) {
+            (Bound::Unbounded, Bound::Unbounded) => f.write_str("*"),
+            (Bound::Included(min), Bound::Included(max)) if min == max => write!(f, "={min}"),
+            (min, max) => {
+                let min = match min {
+                    Bound::Included(v) => Some(format!(">={v}")),
+                    Bound::Excluded(v) => Some(format!(">{v}")),
+                    Bound::Unbounded => None,
+                };
+                let max = match max {
+                    Bound::Included(v) => Some(format!("<={v}")),
+                    Bound::Excluded(v) => Some(format!("<{v}")),
+                    Bound::Unbounded => None,
+                };
+                let bounds: Vec<_> = min.into_iter().chain(max).collect();
+                f.write_str(&bounds.join(", "))
+            }
+        }
+    }
 }
 
 impl Display for OptVersionReq {
@@ -143,4 +318,35 @@ mod tests {
         );
         test_versions("0.1.0+meta", &["0.1.0", "0.1.0+meta", "0.1.0+any"]);
     }
+
+    #[test]
+    fn interval() {
+        fn interval(req: &str) -> String {
+            VersionInterval::from_req(&VersionReq::parse(req).unwrap()).to_string()
+        }
+
+        assert_eq!(interval("1.2.3"), ">=1.2.3, <2.0.0");
+        assert_eq!(interval("^0.2"), ">=0.2.0, <0.3.0");
+        assert_eq!(interval("^0.0.3"), ">=0.0.3, <0.0.4");
+        assert_eq!(interval("~1.2"), ">=1.2.0, <1.3.0");
+        assert_eq!(interval("~1"), ">=1.0.0, <2.0.0");
+        assert_eq!(interval("=1.2.3"), "=1.2.3");
+        assert_eq!(interval("=1.2"), ">=1.2.0, <1.3.0");
+        assert_eq!(interval("1.*"), ">=1.0.0, <2.0.0");
+        assert_eq!(interval("*"), "*");
+        assert_eq!(interval(">1.2"), ">=1.3.0");
+        assert_eq!(interval(">1.2.3"), ">1.2.3");
+        assert_eq!(interval("<=1.2"), "<1.3.0");
+        assert_eq!(interval("<=1.2.3"), "<=1.2.3");
+        assert_eq!(interval(">=1.0, <1.5"), ">=1.0.0, <1.5.0");
+        assert_eq!(interval("1.0.0-alpha"), ">=1.0.0-alpha, <2.0.0");
+        assert_eq!(interval(">=2, <1"), "no version");
+
+        let a = VersionInterval::from_req(&VersionReq::parse("1.2").unwrap());
+        let b = VersionInterval::from_req(&VersionReq::parse(">=1.0, <1.5").unwrap());
+        assert_eq!(a.intersect(&b).to_string(), ">=1.2.0, <1.5.0");
+        let c = VersionInterval::from_req(&VersionReq::parse("<=1.4.2").unwrap());
+        let d = VersionInterval::from_req(&VersionReq::parse(">1.4.2").unwrap());
+        assert!(c.intersect(&d).is_empty());
+    }
 }
@@ -11,12 +11,12 @@ use cargo_platform::Platform;
 use cargo_util::paths;
 use itertools::Itertools;
 use lazycell::LazyCell;
-use log::{debug, trace};
 use semver::{self, VersionReq};
 use serde::de::IntoDeserializer as _;
 use serde::de::{self, Unexpected};
 use serde::ser;
 use serde::{Deserialize, Serialize};
+use tracing::{debug, trace};
 use url::Url;
 
 use crate::core::compiler::{CompileKind, CompileTarget};
@@ -28,7 +28,7 @@ use crate::core::{Dependency, Manifest, PackageId, Summary, Target};
 use crate::core::{Edition, EitherManifest, Feature, Features, VirtualManifest, Workspace};
 use crate::core::{GitReference, PackageIdSpec, SourceId, WorkspaceConfig, WorkspaceRootConfig};
 use crate::sources::{CRATES_IO_INDEX, CRATES_IO_REGISTRY};
-use crate::util::errors::{CargoResult, ManifestError};
+use crate::util::errors::{CargoResult, LinkedError, ManifestError};
 use crate::util::interning::InternedString;
 use crate::util::{
     self, config::ConfigRelativePath, validate_package_name, Config, IntoUrl, VersionReqExt,
@@ -56,7 +56,10 @@ pub fn read_manifest(
         path.display(),
         source_id
     );
-    let mut contents = paths::read(path).map_err(|err| ManifestError::new(err, path.into()))?;
+    let mut contents = config
+        .file_overlay()
+        .read(path)
+        .map_err(|err| ManifestError::new(err, path.into()))?;
     let embedded = is_embedded(path);
     if embedded {
         if !config.cli_unstable().script {
@@ -70,8 +73,32 @@ pub fn read_manifest(
     }
 
     read_manifest_from_str(&contents, path, embedded, source_id, config)
-        .with_context(|| format!("failed to parse manifest at `{}`", path.display()))
-        .map_err(|err| ManifestError::new(err, path.into()))
+        .map_err(|err| ManifestError::new(parse_error(err, path), path.into()))
+}
+
+/// The context of an error parsing the manifest at `path`, which is displayed
+/// as a hyperlink to it with `term.hyperlinks`.
+pub fn parse_error(err: anyhow::Error, path: &Path) -> LinkedError {
+    LinkedError::new()
+        .push("failed to parse manifest at `")
+        .push_file_link(path)
+        .push("`")
+        .with_source(err)
+}
+
+/// Loads a manifest held in memory as if it was the `Cargo.toml` file at
+/// `path`, which doesn't need to exist.
+///
+/// Like [`read_manifest`], this could result in a real or virtual manifest
+/// being returned, along with the paths of its path dependencies.
+pub fn read_manifest_from_toml(
+    toml: toml::Table,
+    path: &Path,
+    source_id: SourceId,
+    config: &Config,
+) -> Result<(EitherManifest, Vec<PathBuf>), ManifestError> {
+    read_manifest_from_table(toml, path, false, source_id, config)
+        .map_err(|err| ManifestError::new(parse_error(err, path), path.into()))
 }
 
 /// See also `bin/cargo/commands/run.rs`s `is_manifest_command`
@@ -97,14 +124,25 @@ fn read_manifest_from_str(
     source_id: SourceId,
     config: &Config,
 ) -> CargoResult<(EitherManifest, Vec<PathBuf>)> {
-    let package_root = manifest_file.parent().unwrap();
-
     let toml = {
         let pretty_filename = manifest_file
             .strip_prefix(config.cwd())
             .unwrap_or(manifest_file);
         parse_document(contents, pretty_filename, config)?
     };
+    read_manifest_from_table(toml, manifest_file, embedded, source_id, config)
+}
+
+/// Interprets an already-parsed `Cargo.toml` as a Cargo manifest, see
+/// [`read_manifest_from_str`].
+fn read_manifest_from_table(
+    toml: toml::Table,
+    manifest_file: &Path,
+    embedded: bool,
+    source_id: SourceId,
+    config: &Config,
+) -> CargoResult<(EitherManifest, Vec<PathBuf>)> {
+    let package_root = manifest_file.parent().unwrap();
 
     // Provide a helpful error message for a common user error.
     if let Some(package) = toml.get("package").or_else(|| toml.get("project")) {
@@ -580,6 +618,10 @@ pub struct TomlProfile {
     pub strip: Option<StringOrBool>,
     // Note that `rustflags` is used for the cargo-feature `profile_rustflags`
     pub rustflags: Option<Vec<InternedString>>,
+    // Also part of the cargo-feature `profile_rustflags`
+    pub rustdocflags: Option<Vec<InternedString>>,
+    // unstable, `-Zprofile-auto-tune`
+    pub auto_tune: Option<bool>,
     // These two fields must be last because they are sub-tables, and TOML
     // requires all non-tables to be listed first.
     pub package: Option<BTreeMap<ProfilePackageSpec, TomlProfile>>,
@@ -815,7 +857,7 @@ impl TomlProfile {
                 );
             }
         }
-        if self.rustflags.is_some() {
+        if self.rustflags.is_some() || self.rustdocflags.is_some() {
             match (
                 features.require(Feature::profile_rustflags()),
                 cli_unstable.profile_rustflags,
@@ -824,6 +866,9 @@ impl TomlProfile {
                 _ => {}
             }
         }
+        if self.auto_tune.is_some() && !cli_unstable.profile_auto_tune {
+            bail!("`profile.{name}.auto-tune` requires `-Zprofile-auto-tune`");
+        }
         Ok(())
     }
 
@@ -844,6 +889,9 @@ impl TomlProfile {
         if self.rpath.is_some() {
             bail!("`rpath` may not be specified in a `{}` profile", which)
         }
+        if self.auto_tune.is_some() {
+            bail!("`auto-tune` may not be specified in a `{}` profile", which)
+        }
         Ok(())
     }
 
@@ -897,6 +945,14 @@ impl TomlProfile {
             self.rustflags = Some(v.clone());
         }
 
+        if let Some(v) = &profile.rustdocflags {
+            self.rustdocflags = Some(v.clone());
+        }
+
+        if let Some(v) = profile.auto_tune {
+            self.auto_tune = Some(v);
+        }
+
         if let Some(other_package) = &profile.package {
             match &mut self.package {
                 Some(self_package) => {
@@ -1548,6 +1604,12 @@ pub struct TomlWorkspace {
     #[serde(rename = "default-members")]
     default_members: Option<Vec<String>>,
     exclude: Option<Vec<String>>,
+    #[serde(rename = "members-from")]
+    members_from: Option<Vec<String>>,
+    #[serde(rename = "default-run")]
+    default_run: Option<String>,
+    #[serde(rename = "default-test")]
+    default_test: Option<Vec<String>>,
     resolver: Option<String>,
 
     // Properties that can be inherited by members.
@@ -1976,6 +2038,13 @@ impl TomlManifest {
 
         let workspace_config = match (me.workspace.as_ref(), package.workspace.as_ref()) {
             (Some(toml_config), None) => {
+                validate_member_globs(&toml_config.members, &features)?;
+                if toml_config.members_from.is_some() {
+                    features.require(Feature::nested_workspaces())?;
+                }
+                if toml_config.default_run.is_some() || toml_config.default_test.is_some() {
+                    features.require(Feature::workspace_default_targets())?;
+                }
                 let mut inheritable = toml_config.package.clone().unwrap_or_default();
                 inheritable.update_ws_path(package_root.to_path_buf());
                 inheritable.update_deps(toml_config.dependencies.clone());
@@ -1997,6 +2066,9 @@ impl TomlManifest {
                     &toml_config.members,
                     &toml_config.default_members,
                     &toml_config.exclude,
+                    &toml_config.members_from,
+                    &toml_config.default_run,
+                    &toml_config.default_test,
                     &Some(inheritable),
                     &toml_config.metadata,
                 );
@@ -2680,6 +2752,13 @@ impl TomlManifest {
             .transpose()?;
         let workspace_config = match me.workspace {
             Some(ref toml_config) => {
+                validate_member_globs(&toml_config.members, &features)?;
+                if toml_config.members_from.is_some() {
+                    features.require(Feature::nested_workspaces())?;
+                }
+                if toml_config.default_run.is_some() || toml_config.default_test.is_some() {
+                    features.require(Feature::workspace_default_targets())?;
+                }
                 let mut inheritable = toml_config.package.clone().unwrap_or_default();
                 inheritable.update_ws_path(root.to_path_buf());
                 inheritable.update_deps(toml_config.dependencies.clone());
@@ -2691,6 +2770,9 @@ impl TomlManifest {
                     &toml_config.members,
                     &toml_config.default_members,
                     &toml_config.exclude,
+                    &toml_config.members_from,
+                    &toml_config.default_run,
+                    &toml_config.default_test,
                     &Some(inheritable),
                     &toml_config.metadata,
                 );
@@ -2910,6 +2992,18 @@ fn verify_lints(lints: Option<TomlLints>) -> CargoResult<Option<TomlLints>> {
     Ok(Some(lints))
 }
 
+/// Negative patterns in `workspace.members`, like `!crates/experimental-*`,
+/// require `cargo-features = ["negative-member-globs"]`.
+fn validate_member_globs(members: &Option<Vec<String>>, features: &Features) -> CargoResult<()> {
+    let members = members.as_deref().unwrap_or_default();
+    if let Some(glob) = members.iter().find(|glob| glob.starts_with('!')) {
+        features
+            .require(Feature::negative_member_globs())
+            .with_context(|| format!("failed to parse `{glob}` in `workspace.members`"))?;
+    }
+    Ok(())
+}
+
 fn lints_to_rustflags(lints: &TomlLints) -> Vec<String> {
     let mut rustflags = lints
         .iter()
@@ -1,80 +1,498 @@
-use crate::util::CargoResult;
+use crate::util::{CargoResult, Config};
+use anyhow::Context as _;
 use cargo_util::paths;
 use cargo_util::ProcessBuilder;
-use std::path::Path;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
 // Check if we are in an existing repo. We define that to be true if either:
 //
 // 1. We are in a git repo and the path to the new package is not an ignored
 //    path in that repo.
 // 2. We are in an HG repo.
-pub fn existing_vcs_repo(path: &Path, cwd: &Path) -> bool {
-    fn in_git_repo(path: &Path, cwd: &Path) -> bool {
-        if let Ok(repo) = GitRepo::discover(path, cwd) {
-            // Don't check if the working directory itself is ignored.
-            if repo.workdir().map_or(false, |workdir| workdir == path) {
-                true
-            } else {
-                !repo.is_path_ignored(path).unwrap_or(false)
-            }
+// 3. With `-Zvcs-detection`, we are in a Jujutsu repo.
+pub fn existing_vcs_repo(path: &Path, config: &Config) -> bool {
+    let cwd = config.cwd();
+    GitRepo.is_in_repo(path, cwd)
+        || HgRepo.is_in_repo(path, cwd)
+        || (config.cli_unstable().vcs_detection && JjRepo.is_in_repo(path, cwd))
+}
+
+/// Whether `path` or one of its ancestors has the directory `marker`, like
+/// `.hg`, so that the program of a VCS is only run in its repositories.
+fn has_marker(path: &Path, marker: &str) -> bool {
+    path.ancestors().any(|dir| dir.join(marker).is_dir())
+}
+
+/// The syntax of the ignore files of a VCS.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IgnoreSyntax {
+    /// `.gitignore` patterns, like `/target`.
+    Git,
+    /// Mercurial regular expressions, like `^target$`.
+    Hg,
+    /// Fossil globs, like `target`, which don't support comments.
+    Fossil,
+}
+
+/// A version control system Cargo can create repositories for, and check for
+/// uncommitted changes.
+pub trait Vcs {
+    /// The name of the VCS, as accepted by `--vcs`.
+    fn name(&self) -> &'static str;
+
+    /// The directory marking the root of a repository, like `.git`.
+    fn marker(&self) -> &'static str;
+
+    /// The files listing the paths ignored by the VCS, relative to the root
+    /// of a repository.
+    fn ignore_files(&self) -> &'static [&'static str];
+
+    fn ignore_syntax(&self) -> IgnoreSyntax;
+
+    /// Creates a repository at `path`.
+    fn init(&self, path: &Path, cwd: &Path) -> CargoResult<()>;
+
+    /// Whether `path` is in a repository of this VCS, and not ignored by it.
+    fn is_in_repo(&self, _path: &Path, _cwd: &Path) -> bool {
+        false
+    }
+
+    /// Lists the files with uncommitted changes, including untracked files,
+    /// of the repository containing `path`, as absolute paths.
+    ///
+    /// Returns `None` if `path` isn't in a repository, or if Cargo can't query
+    /// this VCS.
+    fn dirty_files(&self, _path: &Path, _cwd: &Path) -> CargoResult<Option<Vec<PathBuf>>> {
+        Ok(None)
+    }
+}
+
+/// How a file with uncommitted changes differs from the `HEAD` commit of its
+/// git repository.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GitFileStatus {
+    /// The changes are all staged in the index.
+    Staged,
+    /// The file is changed in the working directory, or in conflict.
+    Dirty,
+    /// The file isn't tracked by git.
+    Untracked,
+    /// The file is ignored by git.
+    Ignored,
+}
+
+/// A file with uncommitted changes, found by [`git_dirty_files`].
+#[derive(Clone, Debug)]
+pub struct GitDirtyFile {
+    /// The absolute path of the file. Untracked directories aren't recursed
+    /// into, so this can be a directory.
+    pub path: PathBuf,
+    pub status: GitFileStatus,
+}
+
+/// Collects the files with uncommitted changes in the working directory of
+/// `repo`, including the files of its initialized submodules and the
+/// submodules checked out at another commit than the recorded one.
+///
+/// This also handles linked worktrees, whose submodules libgit2 may not find
+/// through the main repository, and sparse checkouts, whose files outside of
+/// the checkout aren't reported as deleted.
+pub fn git_dirty_files(
+    repo: &git2::Repository,
+    include_ignored: bool,
+) -> CargoResult<Vec<GitDirtyFile>> {
+    let mut dirty_files = Vec::new();
+    collect_git_statuses(repo, include_ignored, &mut dirty_files)?;
+    Ok(dirty_files)
+}
+
+fn collect_git_statuses(
+    repo: &git2::Repository,
+    include_ignored: bool,
+    dirty_files: &mut Vec<GitDirtyFile>,
+) -> CargoResult<()> {
+    let Some(workdir) = repo.workdir() else {
+        return Ok(());
+    };
+    // Files excluded from a sparse checkout are missing from the working
+    // directory, but aren't deleted.
+    let skip_worktree: HashSet<Vec<u8>> = repo
+        .index()?
+        .iter()
+        .filter(|entry| {
+            git2::IndexEntryExtendedFlag::from_bits_truncate(entry.flags_extended)
+                .contains(git2::IndexEntryExtendedFlag::SKIP_WORKTREE)
+        })
+        .map(|entry| entry.path)
+        .collect();
+
+    let mut status_opts = git2::StatusOptions::new();
+    // Exclude submodules, as they are handled by recursing into each one so
+    // that details about specific files can be retrieved.
+    status_opts
+        .exclude_submodules(true)
+        .include_ignored(include_ignored)
+        .include_untracked(true);
+    let statuses = repo.statuses(Some(&mut status_opts)).with_context(|| {
+        format!(
+            "failed to retrieve git status from repo {}",
+            repo.path().display()
+        )
+    })?;
+    for entry in statuses.iter() {
+        let status = entry.status();
+        if status == git2::Status::WT_DELETED && skip_worktree.contains(entry.path_bytes()) {
+            continue;
+        }
+        let status = if status.is_ignored() {
+            GitFileStatus::Ignored
+        } else if status.is_wt_new() {
+            GitFileStatus::Untracked
+        } else if status.intersects(
+            git2::Status::WT_MODIFIED
+                | git2::Status::WT_DELETED
+                | git2::Status::WT_RENAMED
+                | git2::Status::WT_TYPECHANGE
+                | git2::Status::CONFLICTED,
+        ) {
+            GitFileStatus::Dirty
         } else {
-            false
+            GitFileStatus::Staged
+        };
+        let path = entry.path().expect("valid utf-8 path");
+        // Use an absolute path, so that comparing paths is easier
+        // (particularly with submodules).
+        dirty_files.push(GitDirtyFile {
+            path: workdir.join(path),
+            status,
+        });
+    }
+
+    for submodule in repo.submodules()? {
+        let path = workdir.join(submodule.path());
+        if let Some(status) = submodule_commit_status(&submodule) {
+            dirty_files.push(GitDirtyFile {
+                path: path.clone(),
+                status,
+            });
+        }
+        // Ignore submodules that don't open, they are probably not
+        // initialized. In a linked worktree, libgit2 looks for them in the
+        // main repository, so fall back to their own `.git` file.
+        let sub_repo = submodule.open().or_else(|_| git2::Repository::open(&path));
+        if let Ok(sub_repo) = sub_repo {
+            collect_git_statuses(&sub_repo, include_ignored, dirty_files)?;
         }
     }
+    Ok(())
+}
 
-    in_git_repo(path, cwd) || HgRepo::discover(path, cwd).is_ok()
+/// Compares the commit `submodule` is checked out at with the one recorded in
+/// `HEAD`.
+fn submodule_commit_status(submodule: &git2::Submodule<'_>) -> Option<GitFileStatus> {
+    // A submodule added since `HEAD` is reported through `.gitmodules`.
+    let head_id = submodule.head_id()?;
+    let index_id = submodule.index_id();
+    if submodule
+        .workdir_id()
+        .map_or(false, |id| Some(id) != index_id)
+    {
+        Some(GitFileStatus::Dirty)
+    } else if index_id != Some(head_id) {
+        Some(GitFileStatus::Staged)
+    } else {
+        None
+    }
 }
 
 pub struct HgRepo;
+pub struct JjRepo;
 pub struct GitRepo;
 pub struct PijulRepo;
 pub struct FossilRepo;
 
 impl GitRepo {
-    pub fn init(path: &Path, _: &Path) -> CargoResult<GitRepo> {
-        git2::Repository::init(path)?;
-        Ok(GitRepo)
-    }
     pub fn discover(path: &Path, _: &Path) -> Result<git2::Repository, git2::Error> {
         git2::Repository::discover(path)
     }
 }
 
+impl Vcs for GitRepo {
+    fn name(&self) -> &'static str {
+        "git"
+    }
+
+    fn marker(&self) -> &'static str {
+        ".git"
+    }
+
+    fn ignore_files(&self) -> &'static [&'static str] {
+        &[".gitignore"]
+    }
+
+    fn ignore_syntax(&self) -> IgnoreSyntax {
+        IgnoreSyntax::Git
+    }
+
+    fn init(&self, path: &Path, _: &Path) -> CargoResult<()> {
+        git2::Repository::init(path)?;
+        Ok(())
+    }
+
+    fn is_in_repo(&self, path: &Path, cwd: &Path) -> bool {
+        if let Ok(repo) = GitRepo::discover(path, cwd) {
+            // Don't check if the working directory itself is ignored.
+            if repo.workdir().map_or(false, |workdir| workdir == path) {
+                true
+            } else {
+                !repo.is_path_ignored(path).unwrap_or(false)
+            }
+        } else {
+            false
+        }
+    }
+
+    fn dirty_files(&self, path: &Path, cwd: &Path) -> CargoResult<Option<Vec<PathBuf>>> {
+        let Ok(repo) = GitRepo::discover(path, cwd) else {
+            return Ok(None);
+        };
+        let files = git_dirty_files(&repo, false)?;
+        Ok(Some(files.into_iter().map(|file| file.path).collect()))
+    }
+}
+
 impl HgRepo {
-    pub fn init(path: &Path, cwd: &Path) -> CargoResult<HgRepo> {
+    /// Finds the root of the repository containing `path`.
+    fn root(path: &Path, cwd: &Path) -> CargoResult<PathBuf> {
+        let output = ProcessBuilder::new("hg")
+            .cwd(cwd)
+            .arg("--cwd")
+            .arg(path)
+            .arg("root")
+            .exec_with_output()?;
+        Ok(PathBuf::from(
+            String::from_utf8(output.stdout)
+                .context("`hg root` printed invalid UTF-8")?
+                .trim_end(),
+        ))
+    }
+}
+
+impl Vcs for HgRepo {
+    fn name(&self) -> &'static str {
+        "hg"
+    }
+
+    fn marker(&self) -> &'static str {
+        ".hg"
+    }
+
+    fn ignore_files(&self) -> &'static [&'static str] {
+        &[".hgignore"]
+    }
+
+    fn ignore_syntax(&self) -> IgnoreSyntax {
+        IgnoreSyntax::Hg
+    }
+
+    fn init(&self, path: &Path, cwd: &Path) -> CargoResult<()> {
         ProcessBuilder::new("hg")
             .cwd(cwd)
             .arg("init")
             .arg("--")
             .arg(path)
             .exec()?;
-        Ok(HgRepo)
+        Ok(())
     }
-    pub fn discover(path: &Path, cwd: &Path) -> CargoResult<HgRepo> {
-        ProcessBuilder::new("hg")
+
+    fn is_in_repo(&self, path: &Path, cwd: &Path) -> bool {
+        has_marker(path, self.marker()) && HgRepo::root(path, cwd).is_ok()
+    }
+
+    fn dirty_files(&self, path: &Path, cwd: &Path) -> CargoResult<Option<Vec<PathBuf>>> {
+        if !has_marker(path, self.marker()) {
+            return Ok(None);
+        }
+        let Ok(root) = HgRepo::root(path, cwd) else {
+            return Ok(None);
+        };
+        // `HGPLAIN` keeps the user configuration from changing the output,
+        // so that the paths are relative to the root.
+        let output = ProcessBuilder::new("hg")
+            .cwd(&root)
+            .env("HGPLAIN", "1")
+            .arg("status")
+            .arg("--print0")
+            .exec_with_output()?;
+        let files = output
+            .stdout
+            .split(|b| *b == 0)
+            .filter_map(|entry| {
+                // Each entry is a status letter, a space and the path.
+                let path = std::str::from_utf8(entry.get(2..)?).ok()?;
+                Some(root.join(path))
+            })
+            .collect();
+        Ok(Some(files))
+    }
+}
+
+impl JjRepo {
+    /// Finds the root of the workspace containing `path`.
+    fn root(path: &Path) -> CargoResult<PathBuf> {
+        let output = ProcessBuilder::new("jj")
+            .cwd(path)
+            .arg("root")
+            .exec_with_output()?;
+        Ok(PathBuf::from(
+            String::from_utf8(output.stdout)
+                .context("`jj root` printed invalid UTF-8")?
+                .trim_end(),
+        ))
+    }
+}
+
+impl Vcs for JjRepo {
+    fn name(&self) -> &'static str {
+        "jj"
+    }
+
+    fn marker(&self) -> &'static str {
+        ".jj"
+    }
+
+    fn ignore_files(&self) -> &'static [&'static str] {
+        &[".gitignore"]
+    }
+
+    fn ignore_syntax(&self) -> IgnoreSyntax {
+        IgnoreSyntax::Git
+    }
+
+    fn init(&self, path: &Path, cwd: &Path) -> CargoResult<()> {
+        paths::create_dir_all(path)?;
+        ProcessBuilder::new("jj")
             .cwd(cwd)
-            .arg("--cwd")
+            .arg("git")
+            .arg("init")
+            .arg("--")
             .arg(path)
-            .arg("root")
+            .exec()?;
+        Ok(())
+    }
+
+    fn is_in_repo(&self, path: &Path, _: &Path) -> bool {
+        has_marker(path, self.marker()) && JjRepo::root(path).is_ok()
+    }
+
+    fn dirty_files(&self, path: &Path, _: &Path) -> CargoResult<Option<Vec<PathBuf>>> {
+        if !has_marker(path, self.marker()) {
+            return Ok(None);
+        }
+        let Ok(root) = JjRepo::root(path) else {
+            return Ok(None);
+        };
+        // The working copy is a commit, so the changes not committed yet are
+        // the ones of this commit. Their paths are relative to the root.
+        let output = ProcessBuilder::new("jj")
+            .cwd(&root)
+            .args(&["--no-pager", "--color", "never", "diff", "--summary"])
             .exec_with_output()?;
-        Ok(HgRepo)
+        let files = String::from_utf8(output.stdout)
+            .context("`jj diff` printed invalid UTF-8")?
+            .lines()
+            .flat_map(jj_summary_paths)
+            .map(|path| root.join(path))
+            .collect();
+        Ok(Some(files))
+    }
+}
+
+/// Parses a line of `jj diff --summary`, a status letter, a space and a path,
+/// into the paths it changes.
+///
+/// The path of a rename, with the status `R`, or of a copy, with the status
+/// `C`, is like `src/{old.rs => new.rs}`. A rename changes both paths, a copy
+/// only the new one.
+fn jj_summary_paths(line: &str) -> Vec<String> {
+    let Some((status, path)) = line.split_once(' ') else {
+        return Vec::new();
+    };
+    if status != "R" && status != "C" {
+        return vec![path.to_string()];
+    }
+    let (Some(open), Some(close)) = (path.find('{'), path.rfind('}')) else {
+        return vec![path.to_string()];
+    };
+    let Some((old, new)) = path[open + 1..close].split_once(" => ") else {
+        return vec![path.to_string()];
+    };
+    let (prefix, suffix) = (&path[..open], &path[close + 1..]);
+    // Either side can be empty, like in `{ => src}/lib.rs`.
+    let join = |part: &str| {
+        format!("{prefix}{part}{suffix}")
+            .replace("//", "/")
+            .trim_start_matches('/')
+            .to_string()
+    };
+    if status == "R" {
+        vec![join(old), join(new)]
+    } else {
+        vec![join(new)]
     }
 }
 
-impl PijulRepo {
-    pub fn init(path: &Path, cwd: &Path) -> CargoResult<PijulRepo> {
+impl Vcs for PijulRepo {
+    fn name(&self) -> &'static str {
+        "pijul"
+    }
+
+    fn marker(&self) -> &'static str {
+        ".pijul"
+    }
+
+    fn ignore_files(&self) -> &'static [&'static str] {
+        &[".ignore"]
+    }
+
+    fn ignore_syntax(&self) -> IgnoreSyntax {
+        IgnoreSyntax::Git
+    }
+
+    fn init(&self, path: &Path, cwd: &Path) -> CargoResult<()> {
         ProcessBuilder::new("pijul")
             .cwd(cwd)
             .arg("init")
             .arg("--")
             .arg(path)
             .exec()?;
-        Ok(PijulRepo)
+        Ok(())
     }
 }
 
-impl FossilRepo {
-    pub fn init(path: &Path, cwd: &Path) -> CargoResult<FossilRepo> {
+impl Vcs for FossilRepo {
+    fn name(&self) -> &'static str {
+        "fossil"
+    }
+
+    fn marker(&self) -> &'static str {
+        ".fossil"
+    }
+
+    fn ignore_files(&self) -> &'static [&'static str] {
+        // Fossil has a cleaning functionality configured in a separate file.
+        &[
+            ".fossil-settings/ignore-glob",
+            ".fossil-settings/clean-glob",
+        ]
+    }
+
+    fn ignore_syntax(&self) -> IgnoreSyntax {
+        IgnoreSyntax::Fossil
+    }
+
+    fn init(&self, path: &Path, cwd: &Path) -> CargoResult<()> {
         // fossil doesn't create the directory so we'll do that first
         paths::create_dir_all(path)?;
 
@@ -99,6 +517,31 @@ impl FossilRepo {
             .arg(db_fname)
             .exec()?;
 
-        Ok(FossilRepo)
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::jj_summary_paths;
+
+    #[test]
+    fn parses_jj_summary() {
+        assert_eq!(jj_summary_paths("M src/lib.rs"), ["src/lib.rs"]);
+        assert_eq!(jj_summary_paths("A with space.rs"), ["with space.rs"]);
+        assert_eq!(
+            jj_summary_paths("R src/{old.rs => new.rs}"),
+            ["src/old.rs", "src/new.rs"]
+        );
+        assert_eq!(
+            jj_summary_paths("R {lib.rs => src/lib.rs}"),
+            ["lib.rs", "src/lib.rs"]
+        );
+        assert_eq!(
+            jj_summary_paths("R { => src}/lib.rs"),
+            ["lib.rs", "src/lib.rs"]
+        );
+        assert_eq!(jj_summary_paths("C src/{a.rs => b.rs}"), ["src/b.rs"]);
+        assert!(jj_summary_paths("").is_empty());
     }
 }
@@ -154,8 +154,14 @@ _cargo()
 					local opt_var=opt__${cmd//-/_}
 				fi
 				if [[ -z "${!opt_var}" ]]; then
-					# Fallback to filename completion.
-					_filedir
+					# External subcommands may register completions in
+					# `plugins.toml`, otherwise fallback to filename completion.
+					local hints="$(cargo -Zplugin-manifest --subcommand-completions "$cmd" 2>/dev/null)"
+					if [[ -n "$hints" ]]; then
+						COMPREPLY=( $( compgen -W "$hints" -- "$cur" ) )
+					else
+						_filedir
+					fi
 				else
 					COMPREPLY=( $( compgen -W "${!opt_var}" -- "$cur" ) )
 				fi
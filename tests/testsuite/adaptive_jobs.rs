@@ -0,0 +1,102 @@
+//! Tests for `-Zadaptive-jobs`.
+
+use std::thread;
+
+use cargo_test_support::{basic_manifest, project};
+
+#[cargo_test]
+fn auto_jobs_gated() {
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(".cargo/config.toml", "[build]\njobs = \"auto\"\n")
+        .build();
+
+    p.cargo("check")
+        .with_status(101)
+        .with_stderr("[ERROR] `jobs = \"auto\"` requires `-Zadaptive-jobs`")
+        .run();
+}
+
+#[cargo_test]
+fn auto_jobs_without_pressure() {
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(".cargo/config.toml", "[build]\njobs = \"auto\"\n")
+        .build();
+
+    p.cargo("check -v -Zadaptive-jobs")
+        .masquerade_as_nightly_cargo(&["adaptive-jobs"])
+        .env("__CARGO_TEST_SYSTEM_LOAD", "0,100000")
+        .with_stderr_does_not_contain("[NOTE] running at most [..]")
+        .run();
+}
+
+#[cargo_test]
+fn auto_jobs_memory_pressure() {
+    // Running fewer jobs needs several of them to begin with.
+    if thread::available_parallelism().map_or(true, |n| n.get() < 2) {
+        return;
+    }
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+
+                [dependencies]
+                a = { path = "a" }
+                b = { path = "b" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("a/Cargo.toml", &basic_manifest("a", "0.0.1"))
+        .file("a/src/lib.rs", "")
+        .file("b/Cargo.toml", &basic_manifest("b", "0.0.1"))
+        .file("b/src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            r#"
+                [build.adaptive-jobs]
+                min-free-memory = 512
+            "#,
+        )
+        .build();
+
+    p.cargo("check -v -j auto -Zadaptive-jobs")
+        .masquerade_as_nightly_cargo(&["adaptive-jobs"])
+        .env("__CARGO_TEST_SYSTEM_LOAD", "0,256")
+        .with_stderr_contains(
+            "[NOTE] running at most [..] at once, only 256.0 MiB of memory is available",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn auto_jobs_load_pressure() {
+    if thread::available_parallelism().map_or(true, |n| n.get() < 2) {
+        return;
+    }
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            r#"
+                [build]
+                jobs = "auto"
+
+                [build.adaptive-jobs]
+                max-load = 1.0
+            "#,
+        )
+        .build();
+
+    p.cargo("check -v -Zadaptive-jobs")
+        .masquerade_as_nightly_cargo(&["adaptive-jobs"])
+        .env("__CARGO_TEST_SYSTEM_LOAD", "10000,100000")
+        .with_stderr_contains(
+            "[NOTE] running at most [..] at once, the load average is 10000.0 on [..] CPUs",
+        )
+        .run();
+}
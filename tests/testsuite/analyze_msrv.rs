@@ -0,0 +1,191 @@
+//! Tests for `cargo analyze msrv`.
+
+use cargo_test_support::registry::Package;
+use cargo_test_support::{basic_manifest, project};
+
+fn make_project(rust_version: &str) -> cargo_test_support::Project {
+    project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                    [package]
+                    name = "foo"
+                    version = "0.1.0"
+                    rust-version = "{rust_version}"
+
+                    [dependencies]
+                    a = {{ path = "a" }}
+                    c = {{ path = "c" }}
+
+                    [build-dependencies]
+                    d = {{ path = "d" }}
+
+                    [target.'cfg(any())'.dependencies]
+                    never-built = {{ path = "never-built" }}
+                "#
+            ),
+        )
+        .file("src/lib.rs", "")
+        .file("build.rs", "fn main() {}")
+        .file(
+            "a/Cargo.toml",
+            r#"
+                [package]
+                name = "a"
+                version = "0.1.0"
+
+                [dependencies]
+                b = { path = "../b" }
+            "#,
+        )
+        .file("a/src/lib.rs", "")
+        .file(
+            "b/Cargo.toml",
+            r#"
+                [package]
+                name = "b"
+                version = "0.1.0"
+                rust-version = "1.70"
+            "#,
+        )
+        .file("b/src/lib.rs", "")
+        .file(
+            "c/Cargo.toml",
+            r#"
+                [package]
+                name = "c"
+                version = "0.1.0"
+                edition = "2021"
+            "#,
+        )
+        .file("c/src/lib.rs", "")
+        .file(
+            "d/Cargo.toml",
+            r#"
+                [package]
+                name = "d"
+                version = "0.1.0"
+                rust-version = "1.65.1"
+            "#,
+        )
+        .file("d/src/lib.rs", "")
+        .file(
+            "never-built/Cargo.toml",
+            r#"
+                [package]
+                name = "never-built"
+                version = "0.1.0"
+                rust-version = "1.9876"
+            "#,
+        )
+        .file("never-built/src/lib.rs", "")
+        .build()
+}
+
+#[cargo_test]
+fn gated() {
+    let p = make_project("1.50");
+    p.cargo("analyze msrv")
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] the `cargo analyze msrv` command is unstable, \
+             pass `-Z unstable-options` to enable it",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn compatible() {
+    let p = make_project("1.70");
+    p.cargo("analyze msrv -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr("[VERIFIED] dependencies of `foo v0.1.0 ([CWD])` with its `rust-version` 1.70")
+        .run();
+}
+
+#[cargo_test]
+fn incompatible() {
+    let p = make_project("1.50");
+    p.cargo("analyze msrv -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] the dependencies of `foo v0.1.0 ([CWD])` are not compatible with its `rust-version` 1.50
+
+package `b v0.1.0 ([CWD]/b)` requires rustc 1.70
+    ... which is a dependency of package `a v0.1.0 ([CWD]/a)`
+    ... which is a dependency of package `foo v0.1.0 ([CWD])`
+
+package `c v0.1.0 ([CWD]/c)` requires rustc 1.56 (edition 2021)
+    ... which is a dependency of package `foo v0.1.0 ([CWD])`
+
+package `d v0.1.0 ([CWD]/d)` requires rustc 1.65.1
+    ... which is a dependency of package `foo v0.1.0 ([CWD])`
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn registry_dependency() {
+    Package::new("bar", "1.0.0").rust_version("1.60").publish();
+    Package::new("bar", "1.1.0").rust_version("1.75").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["foo", "no-msrv"]
+
+                [workspace.package]
+                rust-version = "1.65"
+            "#,
+        )
+        .file(
+            "foo/Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                rust-version.workspace = true
+
+                [dependencies]
+                bar = "1.0"
+            "#,
+        )
+        .file("foo/src/lib.rs", "")
+        .file("no-msrv/Cargo.toml", &basic_manifest("no-msrv", "0.1.0"))
+        .file("no-msrv/src/lib.rs", "")
+        .build();
+
+    p.cargo("analyze msrv -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[UPDATING] `dummy-registry` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v1.1.0 (registry `dummy-registry`)
+[WARNING] package `no-msrv v0.1.0 ([CWD]/no-msrv)` has no `rust-version`, skipping it
+[ERROR] the dependencies of `foo v0.1.0 ([CWD]/foo)` are not compatible with its `rust-version` 1.65
+
+package `bar v1.1.0` requires rustc 1.75
+    ... which is a dependency of package `foo v0.1.0 ([CWD]/foo)`
+",
+        )
+        .run();
+
+    p.cargo("update -p bar --precise 1.0.0").run();
+    p.cargo("analyze msrv -p foo -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr(
+            "\
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v1.0.0 (registry `dummy-registry`)
+[VERIFIED] dependencies of `foo v0.1.0 ([CWD]/foo)` with its `rust-version` 1.65
+",
+        )
+        .run();
+}
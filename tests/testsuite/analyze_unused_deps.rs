@@ -0,0 +1,140 @@
+//! Tests for `cargo analyze unused-deps`.
+
+use cargo_test_support::{basic_manifest, project};
+
+fn make_project() -> cargo_test_support::Project {
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                used-by-lib = { path = "used-by-lib" }
+                used-by-bin = { path = "used-by-bin" }
+                renamed = { path = "unused", package = "unused" }
+
+                [dev-dependencies]
+                used-by-test = { path = "used-by-test" }
+                unused-dev = { path = "unused-dev" }
+
+                [build-dependencies]
+                unused-build = { path = "unused-build" }
+
+                [target.'cfg(all())'.dependencies]
+                unused-target = { path = "unused-target" }
+
+                [target.'cfg(any())'.dependencies]
+                never-built = { path = "never-built" }
+            "#,
+        )
+        .file("src/lib.rs", "pub fn foo() { used_by_lib::f(); }")
+        .file("src/main.rs", "fn main() { used_by_bin::f(); }")
+        .file("tests/t.rs", "#[test] fn t() { used_by_test::f(); }")
+        .file("build.rs", "fn main() {}")
+        .file(
+            "used-by-lib/Cargo.toml",
+            &basic_manifest("used-by-lib", "0.1.0"),
+        )
+        .file("used-by-lib/src/lib.rs", "pub fn f() {}")
+        .file(
+            "used-by-bin/Cargo.toml",
+            &basic_manifest("used-by-bin", "0.1.0"),
+        )
+        .file("used-by-bin/src/lib.rs", "pub fn f() {}")
+        .file(
+            "used-by-test/Cargo.toml",
+            &basic_manifest("used-by-test", "0.1.0"),
+        )
+        .file("used-by-test/src/lib.rs", "pub fn f() {}")
+        .file("unused/Cargo.toml", &basic_manifest("unused", "0.1.0"))
+        .file("unused/src/lib.rs", "")
+        .file(
+            "unused-dev/Cargo.toml",
+            &basic_manifest("unused-dev", "0.1.0"),
+        )
+        .file("unused-dev/src/lib.rs", "")
+        .file(
+            "unused-build/Cargo.toml",
+            &basic_manifest("unused-build", "0.1.0"),
+        )
+        .file("unused-build/src/lib.rs", "")
+        .file(
+            "unused-target/Cargo.toml",
+            &basic_manifest("unused-target", "0.1.0"),
+        )
+        .file("unused-target/src/lib.rs", "")
+        .file(
+            "never-built/Cargo.toml",
+            &basic_manifest("never-built", "0.1.0"),
+        )
+        .file("never-built/src/lib.rs", "")
+        .build()
+}
+
+#[cargo_test]
+fn gated() {
+    let p = make_project();
+    p.cargo("analyze unused-deps")
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] the `cargo analyze unused-deps` command is unstable, \
+             pass `-Z unstable-options` to enable it",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn reports_unused() {
+    let p = make_project();
+    p.cargo("analyze unused-deps -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout(
+            "\
+foo v0.1.0 ([CWD])
+  [dependencies] renamed
+  [target.'cfg(all())'.dependencies] unused-target
+  [dev-dependencies] unused-dev
+  [build-dependencies] unused-build
+",
+        )
+        .with_stderr_contains(
+            "[NOTE] crates only used by doctests or linked for their symbols are listed too, \
+             pass `--remove` to remove the dependencies",
+        )
+        .with_stderr_does_not_contain("[..]unused_crate_dependencies[..]")
+        .run();
+
+    // The lint isn't replayed by a later build.
+    p.cargo("check --all-targets")
+        .with_stderr("[FINISHED] [..]")
+        .run();
+}
+
+#[cargo_test]
+fn remove() {
+    let p = make_project();
+    p.cargo("analyze unused-deps -Zunstable-options --remove")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_contains("[REMOVING] renamed from dependencies")
+        .with_stderr_contains("[REMOVING] unused-target from dependencies for target `cfg(all())`")
+        .with_stderr_contains("[REMOVING] unused-dev from dev-dependencies")
+        .with_stderr_contains("[REMOVING] unused-build from build-dependencies")
+        .run();
+
+    let manifest = p.read_file("Cargo.toml");
+    for name in ["renamed", "unused-dev", "unused-build", "unused-target"] {
+        assert!(!manifest.contains(name), "{name} not removed:\n{manifest}");
+    }
+    for name in ["used-by-lib", "used-by-bin", "used-by-test", "never-built"] {
+        assert!(manifest.contains(name), "{name} removed:\n{manifest}");
+    }
+
+    p.cargo("analyze unused-deps -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout("")
+        .with_stderr_contains("[NOTE] no unused dependencies found")
+        .run();
+}
@@ -0,0 +1,166 @@
+//! Tests for the `-Zartifact-cache` feature.
+
+use std::fs;
+use std::path::PathBuf;
+
+use cargo_test_support::registry::Package;
+use cargo_test_support::{basic_lib_manifest, paths, project_in, Project};
+
+fn cache_dir() -> PathBuf {
+    paths::home().join(".cargo/artifact-cache")
+}
+
+fn cache_entries() -> Vec<PathBuf> {
+    let mut entries: Vec<_> = fs::read_dir(cache_dir())
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .filter(|p| p.is_dir())
+        .collect();
+    entries.sort();
+    entries
+}
+
+fn workspace(dir: &str) -> Project {
+    project_in(dir)
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                bar = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "pub fn f() { bar::bar(); }")
+        .build()
+}
+
+#[cargo_test]
+fn reused_across_workspaces() {
+    Package::new("bar", "1.0.0")
+        .file("src/lib.rs", "pub fn bar() {}")
+        .publish();
+    let p1 = workspace("one");
+    let p2 = workspace("two");
+
+    p1.cargo("build -v -Zartifact-cache")
+        .masquerade_as_nightly_cargo(&["artifact-cache"])
+        .with_stderr_contains("[RUNNING] `rustc --crate-name bar [..]")
+        .run();
+    let entries = cache_entries();
+    assert_eq!(entries.len(), 1);
+    let name = entries[0].file_name().unwrap().to_str().unwrap();
+    assert!(name.starts_with("bar-"), "{name}");
+    assert!(entries[0].join("manifest.json").is_file());
+
+    // The second workspace restores `bar` instead of compiling it, but still
+    // compiles its own package.
+    p2.cargo("build -v -Zartifact-cache")
+        .masquerade_as_nightly_cargo(&["artifact-cache"])
+        .with_stderr_does_not_contain("[RUNNING] `rustc --crate-name bar [..]")
+        .with_stderr_contains("[RUNNING] `rustc --crate-name foo [..]")
+        .run();
+    let restored = p2.target_debug_dir().join("deps").read_dir().unwrap();
+    assert!(restored
+        .map(|e| e.unwrap().file_name())
+        .any(|name| name.to_str().unwrap().starts_with("libbar-")));
+
+    // And considers it fresh afterwards.
+    p2.cargo("build -Zartifact-cache")
+        .masquerade_as_nightly_cargo(&["artifact-cache"])
+        .with_stderr("[FINISHED] [..]")
+        .run();
+}
+
+#[cargo_test]
+fn corrupt_entry_is_rebuilt() {
+    Package::new("bar", "1.0.0")
+        .file("src/lib.rs", "pub fn bar() {}")
+        .publish();
+    let p1 = workspace("one");
+    let p2 = workspace("two");
+
+    p1.cargo("build -Zartifact-cache")
+        .masquerade_as_nightly_cargo(&["artifact-cache"])
+        .run();
+    let entry = cache_entries().remove(0);
+    for file in entry.join("out").read_dir().unwrap() {
+        fs::write(file.unwrap().path(), "garbage").unwrap();
+    }
+
+    p2.cargo("build -v -Zartifact-cache")
+        .masquerade_as_nightly_cargo(&["artifact-cache"])
+        .with_stderr_contains("[RUNNING] `rustc --crate-name bar [..]")
+        .run();
+    // The corrupt entry was replaced by a good one.
+    for file in entry.join("out").read_dir().unwrap() {
+        assert_ne!(fs::read(file.unwrap().path()).unwrap(), b"garbage");
+    }
+}
+
+#[cargo_test]
+fn different_features_are_separate() {
+    Package::new("bar", "1.0.0")
+        .feature("f", &[])
+        .file("src/lib.rs", "pub fn bar() {}")
+        .publish();
+    let p = workspace("one");
+
+    p.cargo("build -Zartifact-cache")
+        .masquerade_as_nightly_cargo(&["artifact-cache"])
+        .run();
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            version = "0.1.0"
+
+            [dependencies]
+            bar = { version = "1.0", features = ["f"] }
+        "#,
+    );
+    p.cargo("build -v -Zartifact-cache")
+        .masquerade_as_nightly_cargo(&["artifact-cache"])
+        .with_stderr_contains("[RUNNING] `rustc --crate-name bar [..]")
+        .run();
+    assert_eq!(cache_entries().len(), 2);
+}
+
+#[cargo_test]
+fn path_dependencies_are_not_cached() {
+    let p = project_in("one")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                bar = { path = "bar" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("bar/Cargo.toml", &basic_lib_manifest("bar"))
+        .file("bar/src/lib.rs", "")
+        .build();
+
+    p.cargo("build -Zartifact-cache")
+        .masquerade_as_nightly_cargo(&["artifact-cache"])
+        .run();
+    assert!(!cache_dir().exists() || cache_entries().is_empty());
+}
+
+#[cargo_test]
+fn not_used_without_flag() {
+    Package::new("bar", "1.0.0")
+        .file("src/lib.rs", "pub fn bar() {}")
+        .publish();
+    let p = workspace("one");
+
+    p.cargo("build").run();
+    assert!(!cache_dir().exists());
+}
@@ -0,0 +1,112 @@
+//! Tests for `-Zartifact-link`.
+
+use cargo_test_support::{basic_bin_manifest, project, Project};
+use std::path::Path;
+
+fn make_project(artifact_link: &str) -> Project {
+    project()
+        .file("Cargo.toml", &basic_bin_manifest("foo"))
+        .file("src/main.rs", r#"fn main() { println!("hello"); }"#)
+        .file(
+            ".cargo/config.toml",
+            &format!("[build]\nartifact-link = \"{artifact_link}\"\n"),
+        )
+        .build()
+}
+
+fn is_symlink(path: &Path) -> bool {
+    path.symlink_metadata().unwrap().file_type().is_symlink()
+}
+
+#[cfg(unix)]
+fn is_hardlink(path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    path.symlink_metadata().unwrap().nlink() > 1
+}
+
+// Creating symlinks needs a privilege on Windows.
+#[cfg(unix)]
+#[cargo_test]
+fn symlink() {
+    let p = make_project("symlink");
+    p.cargo("build -Zartifact-link")
+        .masquerade_as_nightly_cargo(&["artifact-link"])
+        .run();
+
+    let bin = p.bin("foo");
+    assert!(is_symlink(&bin));
+    let target = std::fs::read_link(&bin).unwrap();
+    assert!(target.is_relative(), "{}", target.display());
+    assert!(target.starts_with("deps"), "{}", target.display());
+    p.process(&bin).with_stdout("hello").run();
+}
+
+#[cfg(unix)]
+#[cargo_test]
+fn copy() {
+    let p = make_project("copy");
+    p.cargo("build -Zartifact-link")
+        .masquerade_as_nightly_cargo(&["artifact-link"])
+        .run();
+
+    let bin = p.bin("foo");
+    assert!(!is_symlink(&bin));
+    assert!(!is_hardlink(&bin));
+    p.process(&bin).with_stdout("hello").run();
+}
+
+#[cfg(unix)]
+#[cargo_test]
+fn hardlink() {
+    let p = make_project("hardlink");
+    p.cargo("build -Zartifact-link")
+        .masquerade_as_nightly_cargo(&["artifact-link"])
+        .run();
+
+    let bin = p.bin("foo");
+    assert!(!is_symlink(&bin));
+    assert!(is_hardlink(&bin));
+}
+
+// Creating symlinks needs a privilege on Windows.
+#[cfg(unix)]
+#[cargo_test]
+fn changing_strategy_relinks_fresh_artifacts() {
+    let p = make_project("symlink");
+    p.cargo("build -Zartifact-link")
+        .masquerade_as_nightly_cargo(&["artifact-link"])
+        .run();
+    assert!(is_symlink(&p.bin("foo")));
+
+    p.change_file(".cargo/config.toml", "[build]\nartifact-link = \"copy\"\n");
+    p.cargo("build -Zartifact-link")
+        .masquerade_as_nightly_cargo(&["artifact-link"])
+        .with_stderr("[FINISHED] dev [..]")
+        .run();
+    assert!(!is_symlink(&p.bin("foo")));
+    p.process(&p.bin("foo")).with_stdout("hello").run();
+}
+
+#[cargo_test]
+fn gated() {
+    let p = make_project("symlink");
+    p.cargo("build").run();
+    assert!(!is_symlink(&p.bin("foo")));
+}
+
+#[cargo_test]
+fn invalid_value() {
+    let p = make_project("reflink");
+    p.cargo("build -Zartifact-link")
+        .masquerade_as_nightly_cargo(&["artifact-link"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] error in [..]/foo/.cargo/config.toml: could not load config key `build.artifact-link`
+
+Caused by:
+  unknown variant `reflink`, expected one of `hardlink`, `symlink`, `copy`
+",
+        )
+        .run();
+}
@@ -5602,7 +5602,7 @@ fn invalid_jobs() {
 
     p.cargo("build --jobs over9000")
         .with_status(101)
-        .with_stderr("error: could not parse `over9000`. Number of parallel jobs should be `default` or a number.")
+        .with_stderr("error: could not parse `over9000`. Number of parallel jobs should be `default`, `auto` or a number.")
         .run();
 }
 
@@ -6256,6 +6256,28 @@ fn target_directory_backup_exclusion() {
     assert!(!&cachedir_tag.is_file());
 }
 
+#[cargo_test]
+fn target_directory_case_sensitivity_is_cached() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("check").run();
+    let cache = p.build_dir().join(".case-sensitivity");
+    let expected = if cfg!(any(windows, target_os = "macos")) {
+        "insensitive"
+    } else {
+        "sensitive"
+    };
+    assert_eq!(fs::read_to_string(&cache).unwrap(), expected);
+    // An unknown value is probed again.
+    fs::write(&cache, "other").unwrap();
+    p.cargo("check").run();
+    assert_eq!(fs::read_to_string(&cache).unwrap(), expected);
+    // A known one is used as is.
+    fs::write(&cache, "insensitive").unwrap();
+    p.cargo("check").run();
+    assert_eq!(fs::read_to_string(&cache).unwrap(), "insensitive");
+}
+
 #[cargo_test]
 fn simple_terminal_width() {
     let p = project()
@@ -6409,3 +6431,47 @@ fn renamed_uplifted_artifact_remains_unmodified_after_rebuild() {
     let not_the_same = !same_file::is_same_file(bin, renamed_bin).unwrap();
     assert!(not_the_same, "renamed uplifted artifact must be unmodified");
 }
+
+#[cargo_test]
+fn equal_priority_units_build_in_order() {
+    // The units ready at the same time with the same priority are built in
+    // the order of the units, not in the order of a hash map.
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                c = { path = "c" }
+                a = { path = "a" }
+                d = { path = "d" }
+                b = { path = "b" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("a/Cargo.toml", &basic_manifest("a", "0.1.0"))
+        .file("a/src/lib.rs", "")
+        .file("b/Cargo.toml", &basic_manifest("b", "0.1.0"))
+        .file("b/src/lib.rs", "")
+        .file("c/Cargo.toml", &basic_manifest("c", "0.1.0"))
+        .file("c/src/lib.rs", "")
+        .file("d/Cargo.toml", &basic_manifest("d", "0.1.0"))
+        .file("d/src/lib.rs", "")
+        .build();
+
+    p.cargo("check -j1")
+        .with_stderr(
+            "\
+[CHECKING] a v0.1.0 ([..])
+[CHECKING] b v0.1.0 ([..])
+[CHECKING] c v0.1.0 ([..])
+[CHECKING] d v0.1.0 ([..])
+[CHECKING] foo v0.1.0 ([..])
+[FINISHED] [..]
+",
+        )
+        .run();
+}
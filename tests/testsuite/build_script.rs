@@ -992,6 +992,7 @@ versions that meet the requirements `*` are: 0.5.0
 the package `a-sys` links to the native library `a`, but it conflicts with a previous package which links to `a` as well:
 package `foo v0.5.0 ([..])`
 Only one package in the dependency graph may specify the same links value. This helps ensure that only one copy of a native library is linked in the final binary. Try to adjust your dependencies so that only one package uses the links ='a-sys' value. For more information, see https://doc.rust-lang.org/cargo/reference/resolver.html#links.
+help: to keep a single package linking to `a`, update the dependents so they agree on one package, using `[patch]` for dependencies outside the workspace, see https://doc.rust-lang.org/cargo/reference/overriding-dependencies.html#the-patch-section
 
 failed to select a version for `a-sys` which could resolve this conflict
 ").run();
@@ -1047,11 +1048,116 @@ links to native library `a`
 
 package `foo v0.1.0 ([..]foo)`
 also links to native library `a`
+help: to keep a single package linking to `a`, update the dependents so they agree on one package, using `[patch]` for dependencies outside the workspace, see https://doc.rust-lang.org/cargo/reference/overriding-dependencies.html#the-patch-section
 ",
         )
         .run();
 }
 
+#[cargo_test]
+fn links_duplicates_per_target() {
+    // `b` is never built, so with `-Zlinks-per-target` it doesn't conflict.
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.5.0"
+
+                [dependencies]
+                a = { path = "a" }
+
+                [target.'cfg(any())'.dependencies]
+                b = { path = "b" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            "a/Cargo.toml",
+            r#"
+                [package]
+                name = "a"
+                version = "0.5.0"
+                links = "x"
+            "#,
+        )
+        .file("a/src/lib.rs", "")
+        .file("a/build.rs", "fn main() {}")
+        .file(
+            "b/Cargo.toml",
+            r#"
+                [package]
+                name = "b"
+                version = "0.5.0"
+                links = "x"
+            "#,
+        )
+        .file("b/src/lib.rs", "")
+        .file("b/build.rs", "fn main() {}")
+        .build();
+
+    p.cargo("check")
+        .with_status(101)
+        .with_stderr_contains(
+            "\
+note: `b` is only a dependency of package `foo v0.5.0 ([..])` for `cfg(any())`, \
+`-Zlinks-per-target` only reports conflicts between packages built for the selected targets",
+        )
+        .run();
+
+    p.cargo("check -Zlinks-per-target")
+        .masquerade_as_nightly_cargo(&["links-per-target"])
+        .with_stderr(
+            "\
+[COMPILING] a v0.5.0 ([..])
+[CHECKING] foo v0.5.0 ([..])
+[FINISHED] [..]
+",
+        )
+        .run();
+
+    // Conflicts between packages that are built are still errors.
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            version = "0.5.0"
+
+            [dependencies]
+            a = { path = "a" }
+            b = { path = "b", optional = true }
+        "#,
+    );
+    p.cargo("check -Zlinks-per-target --features b")
+        .masquerade_as_nightly_cargo(&["links-per-target"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] multiple packages link to native library `x`, but a native library can be linked only once
+
+package `a v0.5.0 ([..]a)`
+    ... which satisfies path dependency `a` (locked to 0.5.0) of package `foo v0.5.0 ([..])`
+links to native library `x`
+
+package `b v0.5.0 ([..]b)`
+    ... which satisfies path dependency `b` (locked to 0.5.0) of package `foo v0.5.0 ([..])`
+also links to native library `x`
+help: `b` is an optional dependency of package `foo v0.5.0 ([..])`, \
+disabling the features that enable it removes this path
+help: to keep a single package linking to `x`, update the dependents so they agree on one package, \
+using `[patch]` for dependencies outside the workspace, \
+see https://doc.rust-lang.org/cargo/reference/overriding-dependencies.html#the-patch-section
+",
+        )
+        .run();
+    p.cargo("check -Zlinks-per-target")
+        .masquerade_as_nightly_cargo(&["links-per-target"])
+        .with_stderr("[FINISHED] [..]")
+        .run();
+}
+
 #[cargo_test]
 fn links_duplicates_deep_dependency() {
     // this tests that the links_duplicates are caught at resolver time
@@ -1112,6 +1218,7 @@ versions that meet the requirements `*` are: 0.5.0
 the package `a-sys` links to the native library `a`, but it conflicts with a previous package which links to `a` as well:
 package `foo v0.5.0 ([..])`
 Only one package in the dependency graph may specify the same links value. This helps ensure that only one copy of a native library is linked in the final binary. Try to adjust your dependencies so that only one package uses the links ='a-sys' value. For more information, see https://doc.rust-lang.org/cargo/reference/resolver.html#links.
+help: to keep a single package linking to `a`, update the dependents so they agree on one package, using `[patch]` for dependencies outside the workspace, see https://doc.rust-lang.org/cargo/reference/overriding-dependencies.html#the-patch-section
 
 failed to select a version for `a-sys` which could resolve this conflict
 ").run();
@@ -4345,6 +4452,7 @@ versions that meet the requirements `*` are: 0.5.0
 the package `a` links to the native library `a`, but it conflicts with a previous package which links to `a` as well:
 package `foo v0.5.0 ([..])`
 Only one package in the dependency graph may specify the same links value. This helps ensure that only one copy of a native library is linked in the final binary. Try to adjust your dependencies so that only one package uses the links ='a' value. For more information, see https://doc.rust-lang.org/cargo/reference/resolver.html#links.
+help: to keep a single package linking to `a`, update the dependents so they agree on one package, using `[patch]` for dependencies outside the workspace, see https://doc.rust-lang.org/cargo/reference/overriding-dependencies.html#the-patch-section
 
 failed to select a version for `a` which could resolve this conflict
 ").run();
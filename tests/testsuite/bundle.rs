@@ -0,0 +1,176 @@
+//! Tests for `cargo bundle export` and `cargo bundle import`.
+
+use std::io::Read;
+
+use cargo_test_support::git;
+use cargo_test_support::paths;
+use cargo_test_support::registry::Package;
+use cargo_test_support::{basic_lib_manifest, project, Project};
+
+fn project_with_deps() -> Project {
+    Package::new("bar", "0.1.0").publish();
+    Package::new("bar", "0.2.0").publish();
+    let git = git::new("baz", |p| {
+        p.file("Cargo.toml", &basic_lib_manifest("baz"))
+            .file("src/lib.rs", "")
+    });
+
+    project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                    [package]
+                    name = "foo"
+                    version = "0.1.0"
+
+                    [dependencies]
+                    bar = "0.1"
+                    baz = {{ git = '{}' }}
+                "#,
+                git.url()
+            ),
+        )
+        .file("src/lib.rs", "extern crate bar; extern crate baz;")
+        .build()
+}
+
+#[cargo_test]
+fn export_import_build_offline() {
+    let p = project_with_deps();
+
+    p.cargo("bundle export -Zunstable-options -o deps.tar")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_contains("[..]Exported 1 packages and 1 git repositories to `[CWD]/deps.tar`")
+        .run();
+
+    // A fresh `$CARGO_HOME`, without the test registry.
+    let home = paths::root().join("airgap");
+    p.cargo("bundle import -Zunstable-options deps.tar")
+        .env("CARGO_HOME", &home)
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr("[..]Imported 1 packages and 1 git repositories from `[CWD]/deps.tar`")
+        .run();
+    let config = std::fs::read_to_string(home.join("config.toml")).unwrap();
+    assert!(config.contains("[source.crates-io]\nreplace-with = \"bundle-"));
+
+    p.cargo("check --offline")
+        .env("CARGO_HOME", &home)
+        .with_stderr_contains("[CHECKING] bar v0.1.0")
+        .with_stderr_contains("[CHECKING] baz v0.5.0 ([..]/baz#[..])")
+        .run();
+
+    // Importing again is fine.
+    p.cargo("bundle import -Zunstable-options deps.tar")
+        .env("CARGO_HOME", &home)
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .run();
+}
+
+#[cargo_test]
+fn import_rejects_wrong_checksum() {
+    let p = project_with_deps();
+
+    p.cargo("bundle export -Zunstable-options -o deps.tar")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .run();
+
+    // Changes the `.crate` file of the bundle.
+    let file = std::fs::File::open(p.root().join("deps.tar")).unwrap();
+    let mut archive = tar::Archive::new(file);
+    let mut tampered = tar::Builder::new(Vec::new());
+    for entry in archive.entries().unwrap() {
+        let mut entry = entry.unwrap();
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).unwrap();
+        let path = entry.path().unwrap().into_owned();
+        if path.extension().map_or(false, |ext| ext == "crate") {
+            data.push(0);
+        }
+        let mut header = entry.header().clone();
+        header.set_size(data.len() as u64);
+        header.set_cksum();
+        tampered.append_data(&mut header, path, &data[..]).unwrap();
+    }
+    std::fs::write(p.root().join("deps.tar"), tampered.into_inner().unwrap()).unwrap();
+
+    let home = paths::root().join("airgap");
+    p.cargo("bundle import -Zunstable-options deps.tar")
+        .env("CARGO_HOME", &home)
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr("[ERROR] checksum of `bar v0.1.0` in the bundle is [..], expected [..]")
+        .run();
+    assert!(!home.join("bundle").exists());
+    assert!(!home.join("config.toml").exists());
+}
+
+#[cargo_test]
+fn import_rejects_path_traversal() {
+    let p = project_with_deps();
+
+    p.cargo("bundle export -Zunstable-options -o deps.tar")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .run();
+
+    // Renames `bar` in the manifest, so that its `.crate` file would be
+    // written outside of the bundle directory.
+    let file = std::fs::File::open(p.root().join("deps.tar")).unwrap();
+    let mut archive = tar::Archive::new(file);
+    let mut tampered = tar::Builder::new(Vec::new());
+    for entry in archive.entries().unwrap() {
+        let mut entry = entry.unwrap();
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).unwrap();
+        let path = entry.path().unwrap().into_owned();
+        if path.to_str() == Some("bundle.json") {
+            data = String::from_utf8(data)
+                .unwrap()
+                .replace(r#""name": "bar""#, r#""name": "../../evil""#)
+                .into_bytes();
+        }
+        let mut header = entry.header().clone();
+        header.set_size(data.len() as u64);
+        header.set_cksum();
+        tampered.append_data(&mut header, path, &data[..]).unwrap();
+    }
+    std::fs::write(p.root().join("deps.tar"), tampered.into_inner().unwrap()).unwrap();
+
+    let home = paths::root().join("airgap");
+    p.cargo("bundle import -Zunstable-options deps.tar")
+        .env("CARGO_HOME", &home)
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] the bundle is corrupt, invalid package `../../evil`
+
+Caused by:
+  invalid character `.` in package name: `../../evil`, [..]
+",
+        )
+        .run();
+    assert!(!home.join("bundle").exists());
+}
+
+#[cargo_test]
+fn bundle_requires_unstable_options() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("bundle export")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] the `cargo bundle export` command is unstable, pass `-Z unstable-options` to enable it
+",
+        )
+        .run();
+    p.cargo("bundle import deps.tar")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] the `cargo bundle import` command is unstable, pass `-Z unstable-options` to enable it
+",
+        )
+        .run();
+}
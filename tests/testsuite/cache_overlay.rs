@@ -0,0 +1,205 @@
+//! Tests for `-Zcache-overlay`.
+
+use std::fs;
+use std::path::Path;
+
+use cargo_test_support::install::cargo_home;
+use cargo_test_support::registry::Package;
+use cargo_test_support::{basic_manifest, git, project, t};
+
+/// Makes `$CARGO_HOME` read-only, or writable again. Returns `false` when it
+/// is still writable, since permissions don't apply to root.
+fn readonly_home(readonly: bool) -> bool {
+    chmod_readonly(&cargo_home(), readonly);
+    !readonly || fs::write(cargo_home().join("probe"), "").is_err()
+}
+
+fn chmod_readonly(path: &Path, readonly: bool) {
+    if !readonly {
+        set_readonly(path, readonly);
+    }
+    for entry in t!(path.read_dir()) {
+        let entry = t!(entry);
+        let path = entry.path();
+        if t!(entry.file_type()).is_dir() {
+            chmod_readonly(&path, readonly);
+        } else {
+            set_readonly(&path, readonly);
+        }
+    }
+    if readonly {
+        set_readonly(path, readonly);
+    }
+}
+
+fn set_readonly(path: &Path, readonly: bool) {
+    let mut perms = t!(path.metadata()).permissions();
+    perms.set_readonly(readonly);
+    t!(fs::set_permissions(path, perms));
+}
+
+#[cargo_test]
+fn registry_packages_from_readonly_layer() {
+    Package::new("bar", "0.1.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+
+                [dependencies]
+                bar = "0.1"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            r#"
+                [cache]
+                overlay-dir = "overlay"
+            "#,
+        )
+        .build();
+
+    p.cargo("fetch").run();
+    Package::new("baz", "0.1.0").publish();
+    if !readonly_home(true) {
+        readonly_home(false);
+        return;
+    }
+
+    p.cargo("check -Zcache-overlay")
+        .masquerade_as_nightly_cargo(&["cache-overlay"])
+        .with_stderr(
+            "\
+[CHECKING] bar v0.1.0
+[CHECKING] foo v0.0.1 ([CWD])
+[FINISHED] [..]
+",
+        )
+        .run();
+    let overlay = p.root().join("overlay");
+    assert!(overlay.join(".package-cache").exists());
+    assert_eq!(
+        overlay.join("registry/index").read_dir().unwrap().count(),
+        1
+    );
+    assert!(!overlay.join("registry/cache").exists());
+    assert!(!overlay.join("registry/src").exists());
+
+    // New packages go to the overlay.
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+
+            [dependencies]
+            bar = "0.1"
+            baz = "0.1"
+        "#,
+    );
+    p.cargo("check -Zcache-overlay")
+        .masquerade_as_nightly_cargo(&["cache-overlay"])
+        .with_stderr(
+            "\
+[UPDATING] `dummy-registry` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] baz v0.1.0 (registry `dummy-registry`)
+[CHECKING] baz v0.1.0
+[CHECKING] foo v0.0.1 ([CWD])
+[FINISHED] [..]
+",
+        )
+        .run();
+    assert_eq!(overlay.join("registry/src").read_dir().unwrap().count(), 1);
+
+    readonly_home(false);
+}
+
+#[cargo_test]
+fn git_checkouts_from_readonly_layer() {
+    let bar = git::new("bar", |p| {
+        p.file("Cargo.toml", &basic_manifest("bar", "0.1.0"))
+            .file("src/lib.rs", "")
+    });
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                    [package]
+                    name = "foo"
+                    version = "0.0.1"
+
+                    [dependencies]
+                    bar = {{ git = "{}" }}
+                "#,
+                bar.url()
+            ),
+        )
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            r#"
+                [cache]
+                overlay-dir = "overlay"
+            "#,
+        )
+        .build();
+
+    p.cargo("fetch").run();
+    if !readonly_home(true) {
+        readonly_home(false);
+        return;
+    }
+
+    p.cargo("check -Zcache-overlay")
+        .masquerade_as_nightly_cargo(&["cache-overlay"])
+        .with_stderr(
+            "\
+[CHECKING] bar v0.1.0 ([..]/bar#[..])
+[CHECKING] foo v0.0.1 ([CWD])
+[FINISHED] [..]
+",
+        )
+        .run();
+    assert!(!p.root().join("overlay/git/checkouts").exists());
+
+    readonly_home(false);
+}
+
+#[cargo_test]
+fn writable_home_ignores_overlay() {
+    Package::new("bar", "0.1.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+
+                [dependencies]
+                bar = "0.1"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            r#"
+                [cache]
+                overlay-dir = "overlay"
+            "#,
+        )
+        .build();
+
+    p.cargo("fetch -Zcache-overlay")
+        .masquerade_as_nightly_cargo(&["cache-overlay"])
+        .run();
+    assert!(!p.root().join("overlay").exists());
+    assert!(cargo_home().join("registry/cache").exists());
+}
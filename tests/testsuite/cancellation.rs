@@ -0,0 +1,48 @@
+//! Tests for `-Zcancellation`.
+
+use cargo_test_support::project;
+
+#[cargo_test]
+fn build_timeout() {
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "build.rs",
+            "fn main() { std::thread::sleep(std::time::Duration::from_secs(600)); }",
+        )
+        .file(".cargo/config.toml", "[build]\ntimeout = 1\n")
+        .build();
+
+    p.cargo("check -Zcancellation")
+        .masquerade_as_nightly_cargo(&["cancellation"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[COMPILING] foo v0.0.1 ([CWD])
+[ERROR] build timed out after 1.00s, as set by `build.timeout`
+[WARNING] build failed, waiting for other jobs to finish...
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn build_timeout_gated() {
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "build.rs",
+            "fn main() { std::thread::sleep(std::time::Duration::from_secs(2)); }",
+        )
+        .file(".cargo/config.toml", "[build]\ntimeout = 1\n")
+        .build();
+
+    p.cargo("check")
+        .with_stderr(
+            "\
+[COMPILING] foo v0.0.1 ([CWD])
+[FINISHED] [..]
+",
+        )
+        .run();
+}
@@ -0,0 +1,23 @@
+use cargo_test_support::compare::assert_ui;
+use cargo_test_support::prelude::*;
+use cargo_test_support::Project;
+
+use cargo_test_support::curr_dir;
+
+#[cargo_test]
+fn case() {
+    // A Jujutsu repository colocated with git has both `.jj` and `.git`.
+    let project = Project::from_template(curr_dir!().join("in"));
+    let project_root = &project.root();
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg_line("init --lib")
+        .current_dir(project_root)
+        .assert()
+        .success()
+        .stdout_matches_path(curr_dir!().join("stdout.log"))
+        .stderr_matches_path(curr_dir!().join("stderr.log"));
+
+    assert_ui().subset_matches(curr_dir!().join("out"), project_root);
+    assert!(!project_root.join(".hg").is_dir());
+}
@@ -24,6 +24,7 @@ mod inferred_bin_with_git;
 mod inferred_lib_with_git;
 mod inherit_workspace_package_table;
 mod invalid_dir_name;
+mod jujutsu_autodetect;
 mod lib_already_exists_nosrc;
 mod lib_already_exists_src;
 mod mercurial_autodetect;
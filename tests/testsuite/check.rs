@@ -1497,3 +1497,113 @@ fn check_unused_manifest_keys() {
         )
         .run();
 }
+
+#[cargo_test]
+fn all_features_matrix() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [features]
+                a = ["b"]
+                b = []
+                c = []
+            "#,
+        )
+        .file(
+            "src/lib.rs",
+            r#"
+                #[cfg(all(feature = "a", feature = "c"))]
+                compile_error!("a and c");
+            "#,
+        )
+        .build();
+
+    p.cargo("check --all-features-matrix -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_contains("    Features `foo` with [] (1/6)")
+        .with_stderr_contains("    Features `foo` with [a] (2/6)")
+        .with_stderr_contains("    Features `foo` with [b] (3/6)")
+        .with_stderr_contains("    Features `foo` with [c] (4/6)")
+        .with_stderr_contains("    Features `foo` with [a,c] (5/6)")
+        .with_stderr_contains("    Features `foo` with [b,c] (6/6)")
+        .with_stderr_contains("[ERROR] a and c")
+        .with_stderr_contains(
+            "\
+[ERROR] 1 of 6 feature combinations failed, rerun them with:
+  -p foo --no-default-features --features a,c
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn all_features_matrix_depth() {
+    let features: Vec<_> = (0..11).map(|i| format!("f{i} = []")).collect();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                    [package]
+                    name = "foo"
+                    version = "0.1.0"
+
+                    [features]
+                    {}
+                "#,
+                features.join("\n")
+            ),
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check --all-features-matrix -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to compute the feature matrix of `foo`
+
+Caused by:
+  there are more than 1024 combinations of features
+  help: pass `--matrix-depth` to only check combinations of fewer features
+",
+        )
+        .run();
+
+    // The empty combination and the 11 combinations of a single feature.
+    p.cargo("check --all-features-matrix --matrix-depth 1 -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_contains("    Features `foo` with [] (1/12)")
+        .with_stderr_contains("    Features `foo` with [f9] (12/12)")
+        .with_stderr_does_not_contain("[..]f0,f1[..]")
+        .run();
+}
+
+#[cargo_test]
+fn all_features_matrix_requires_unstable_options() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("check --all-features-matrix")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] the `--all-features-matrix` flag is unstable, \
+pass `-Z unstable-options` to enable it",
+        )
+        .run();
+
+    p.cargo("check --all-features-matrix --features a -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(1)
+        .with_stderr_contains(
+            "error: the argument '--all-features-matrix' cannot be used with '--features <FEATURES>'",
+        )
+        .run();
+}
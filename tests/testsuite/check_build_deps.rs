@@ -0,0 +1,117 @@
+//! Tests for `-Zcheck-build-deps`.
+
+use cargo_test_support::registry::Package;
+use cargo_test_support::{basic_manifest, project, Project};
+
+fn project_with_dep() -> Project {
+    Package::new("bar", "1.0.0")
+        .file("src/lib.rs", "pub fn bar() {}")
+        .publish();
+    Package::new("baz", "1.0.0")
+        .file("src/lib.rs", "pub fn baz() {}")
+        .publish();
+
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+
+                [dependencies]
+                bar = "1.0"
+
+                [dev-dependencies]
+                baz = "1.0"
+            "#,
+        )
+        .file(
+            "src/lib.rs",
+            r#"
+                pub fn foo() { bar::bar() }
+
+                #[test]
+                fn t() { baz::baz() }
+            "#,
+        )
+        .build()
+}
+
+#[cargo_test]
+fn check_build_deps_gated() {
+    let p = project_with_dep();
+
+    p.cargo("check -v")
+        .with_stderr_contains("[RUNNING] `rustc --crate-name bar [..]--emit=dep-info,metadata [..]")
+        .run();
+}
+
+#[cargo_test]
+fn test_reuses_checked_deps() {
+    let p = project_with_dep();
+
+    p.cargo("check -v --profile test -Zcheck-build-deps")
+        .masquerade_as_nightly_cargo(&["check-build-deps"])
+        .with_stderr_contains(
+            "[RUNNING] `rustc --crate-name bar [..]--emit=dep-info,metadata,link [..]",
+        )
+        .with_stderr_contains(
+            "[RUNNING] `rustc --crate-name baz [..]--emit=dep-info,metadata,link [..]",
+        )
+        .with_stderr_contains(
+            "[RUNNING] `rustc --crate-name foo [..]--emit=dep-info,metadata [..]--test [..]\
+             --extern bar=[..]libbar-[..].rmeta [..]",
+        )
+        .run();
+
+    p.cargo("test -v")
+        .with_stderr_contains("[FRESH] bar v1.0.0")
+        .with_stderr_contains("[FRESH] baz v1.0.0")
+        .with_stderr_does_not_contain("[COMPILING] ba[rz] [..]")
+        .run();
+
+    // Checking again after the tests reuses their dependencies as well.
+    p.change_file("src/lib.rs", "pub fn foo() { bar::bar() } pub fn f() {}");
+    p.cargo("check -Zcheck-build-deps")
+        .masquerade_as_nightly_cargo(&["check-build-deps"])
+        .with_stderr(
+            "\
+[CHECKING] foo v0.0.1 ([CWD])
+[FINISHED] dev [..]
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn workspace_members_are_checked() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["foo", "bar"]
+            "#,
+        )
+        .file(
+            "foo/Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+
+                [dependencies]
+                bar = { path = "../bar" }
+            "#,
+        )
+        .file("foo/src/lib.rs", "pub fn foo() { bar::bar() }")
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.0.1"))
+        .file("bar/src/lib.rs", "pub fn bar() {}")
+        .build();
+
+    p.cargo("check -v -Zcheck-build-deps")
+        .masquerade_as_nightly_cargo(&["check-build-deps"])
+        .with_stderr_contains("[RUNNING] `rustc --crate-name bar [..]--emit=dep-info,metadata [..]")
+        .run();
+}
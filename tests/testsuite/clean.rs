@@ -557,7 +557,7 @@ fn assert_all_clean(build_dir: &Path) {
     }) {
         let entry = entry.unwrap();
         let path = entry.path();
-        if let ".rustc_info.json" | ".cargo-lock" | "CACHEDIR.TAG" =
+        if let ".rustc_info.json" | ".cargo-lock" | "CACHEDIR.TAG" | ".case-sensitivity" =
             path.file_name().unwrap().to_str().unwrap()
         {
             continue;
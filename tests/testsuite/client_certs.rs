@@ -0,0 +1,97 @@
+//! Tests for `-Zclient-certs`.
+
+use cargo_test_support::registry::{Package, RegistryBuilder};
+use cargo_test_support::{paths, project, Project};
+
+fn make_project() -> Project {
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+
+                [dependencies]
+                bar = { version = "0.0.1", registry = "alternative" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build()
+}
+
+#[cargo_test]
+fn missing_cert() {
+    let _registry = RegistryBuilder::new().http_index().alternative().build();
+    Package::new("bar", "0.0.1").alternative(true).publish();
+    let cert = paths::root().join("missing.pem");
+
+    let p = make_project();
+    p.cargo("fetch -Zclient-certs")
+        .masquerade_as_nightly_cargo(&["client-certs"])
+        .env("CARGO_HTTP_SSL_CLIENT_CERT_CERT", &cert)
+        .with_status(101)
+        .with_stderr_contains(format!(
+            "[..]the client certificate `{}` doesn't exist",
+            cert.display()
+        ))
+        .run();
+
+    // Without the flag the certificate is ignored.
+    p.cargo("fetch")
+        .env("CARGO_HTTP_SSL_CLIENT_CERT_CERT", &cert)
+        .with_stderr(
+            "\
+[UPDATING] `alternative` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v0.0.1 (registry `alternative`)
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn registry_cert() {
+    let _registry = RegistryBuilder::new().http_index().alternative().build();
+    Package::new("bar", "0.0.1").alternative(true).publish();
+    let cert = paths::root().join("client.pem");
+    let key = paths::root().join("missing.key");
+    std::fs::write(&cert, "").unwrap();
+
+    let p = make_project();
+    p.cargo("fetch -Zclient-certs")
+        .masquerade_as_nightly_cargo(&["client-certs"])
+        .env("CARGO_REGISTRIES_ALTERNATIVE_CLIENT_CERT_CERT", &cert)
+        .env("CARGO_REGISTRIES_ALTERNATIVE_CLIENT_CERT_KEY", &key)
+        .with_status(101)
+        .with_stderr_contains(format!(
+            "[..]the key of the client certificate `{}` doesn't exist",
+            key.display()
+        ))
+        .run();
+}
+
+#[cargo_test]
+fn key_passphrase_only_from_env() {
+    let _registry = RegistryBuilder::new().http_index().alternative().build();
+    Package::new("bar", "0.0.1").alternative(true).publish();
+    std::fs::write(paths::root().join("client.pem"), "").unwrap();
+
+    let p = make_project();
+    p.change_file(
+        ".cargo/config.toml",
+        r#"
+            [http.ssl-client-cert]
+            cert = "../client.pem"
+            key-passphrase = "hunter2"
+        "#,
+    );
+    p.cargo("fetch -Zclient-certs")
+        .masquerade_as_nightly_cargo(&["client-certs"])
+        .with_status(101)
+        .with_stderr_contains(
+            "[..]the `key-passphrase` of a client certificate can only be set with an \
+             environment variable, but it is set in [CWD]/.cargo/config.toml",
+        )
+        .run();
+}
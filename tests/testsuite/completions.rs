@@ -0,0 +1,132 @@
+//! Tests for the `cargo completions` and `cargo complete` commands.
+
+use cargo_test_support::{basic_manifest, cargo_process, project};
+
+#[cargo_test]
+fn generates_scripts() {
+    cargo_process("completions bash -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_contains("_cargo() {")
+        .with_stdout_contains("complete -F _cargo_dynamic -o bashdefault -o default cargo")
+        .run();
+
+    cargo_process("completions zsh -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_contains("#compdef cargo")
+        .with_stdout_contains("compdef _cargo_dynamic cargo")
+        .run();
+
+    cargo_process("completions fish -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_contains(
+            "complete -c cargo -l bin -x -a \
+'(cargo -Zunstable-options complete --targets bin 2>/dev/null)'",
+        )
+        .run();
+
+    cargo_process("completions powershell -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_contains("Register-ArgumentCompleter -Native -CommandName 'cargo'[..]")
+        .run();
+}
+
+#[cargo_test]
+fn requires_unstable_options() {
+    cargo_process("completions bash")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] the `cargo completions` command is unstable, \
+pass `-Z unstable-options` to enable it",
+        )
+        .run();
+
+    cargo_process("complete --packages")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] the `cargo complete` command is unstable, \
+pass `-Z unstable-options` to enable it",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn complete_values() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["a", "b"]
+            "#,
+        )
+        .file(
+            "a/Cargo.toml",
+            r#"
+                [package]
+                name = "a"
+                version = "0.1.0"
+
+                [features]
+                default = ["std"]
+                std = []
+            "#,
+        )
+        .file("a/src/main.rs", "fn main() {}")
+        .file("a/src/bin/other.rs", "fn main() {}")
+        .file("a/examples/ex.rs", "fn main() {}")
+        .file(
+            "b/Cargo.toml",
+            r#"
+                [package]
+                name = "b"
+                version = "0.1.0"
+
+                [features]
+                extra = []
+            "#,
+        )
+        .file("b/src/lib.rs", "")
+        .file("b/tests/it.rs", "")
+        .build();
+
+    p.cargo("complete --packages -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout("a\nb\n")
+        .run();
+
+    // Outside of a member, the whole workspace is listed.
+    p.cargo("complete --targets bin -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout("a\nother\n")
+        .run();
+    p.cargo("complete --targets example -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout("ex\n")
+        .run();
+    p.cargo("complete --features -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout("default\nextra\nstd\n")
+        .run();
+
+    p.cargo("complete --features -p b -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout("extra\n")
+        .run();
+
+    p.cargo("complete --targets test -Zunstable-options")
+        .cwd(p.root().join("b"))
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout("it\n")
+        .run();
+
+    let p = project()
+        .file("Cargo.toml", &basic_manifest("foo", "0.1.0"))
+        .file("src/lib.rs", "")
+        .build();
+    p.cargo("complete --targets bench -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout("")
+        .run();
+}
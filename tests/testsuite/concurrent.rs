@@ -505,3 +505,100 @@ fn no_deadlock_with_git_dependencies() {
         execs().run_output(&result);
     }
 }
+
+#[cargo_test]
+fn package_cache_lock_wait_timeout() {
+    use cargo::core::Shell;
+    use cargo::util::Config;
+    use cargo_test_support::paths;
+
+    Package::new("bar", "0.1.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+
+                [dependencies]
+                bar = "0.1"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            r#"
+                [package-cache]
+                lock-wait-timeout = 1
+            "#,
+        )
+        .build();
+
+    let mut config = Config::new(
+        Shell::from_write(Box::new(Vec::new())),
+        paths::root(),
+        paths::home().join(".cargo"),
+    );
+    config.nightly_features_allowed = true;
+    config
+        .configure(
+            0,
+            false,
+            None,
+            false,
+            false,
+            false,
+            &None,
+            &["lock-wait-timeout".to_string()],
+            &[],
+        )
+        .unwrap();
+    config.set_subcommand("fetch");
+    let lock = config.acquire_package_cache_lock().unwrap();
+    let holder = cargo_home().join(".package-cache-holder");
+    let recorded: serde_json::Value = serde_json::from_slice(&fs::read(&holder).unwrap()).unwrap();
+    assert_eq!(recorded["pid"], std::process::id());
+    assert_eq!(recorded["command"], "fetch");
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(&holder).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    p.cargo("fetch -Zlock-wait-timeout")
+        .masquerade_as_nightly_cargo(&["lock-wait-timeout"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[..]Blocking waiting for file lock on package cache, held by `cargo fetch` (pid [..] on `[..]`) for [..]s
+[ERROR] failed to acquire package cache lock
+
+Caused by:
+  timed out after 1s waiting for file lock on package cache, held by `cargo fetch` (pid [..] on `[..]`) for [..]s
+",
+        )
+        .run();
+
+    drop(lock);
+    assert!(!holder.exists());
+    p.cargo("fetch -Zlock-wait-timeout")
+        .masquerade_as_nightly_cargo(&["lock-wait-timeout"])
+        .run();
+}
+
+#[cargo_test]
+fn package_cache_holder_requires_nightly() {
+    use cargo::core::Shell;
+    use cargo::util::Config;
+    use cargo_test_support::paths;
+
+    let config = Config::new(
+        Shell::from_write(Box::new(Vec::new())),
+        paths::root(),
+        paths::home().join(".cargo"),
+    );
+    let _lock = config.acquire_package_cache_lock().unwrap();
+    assert!(!cargo_home().join(".package-cache-holder").exists());
+}
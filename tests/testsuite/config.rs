@@ -1524,6 +1524,8 @@ fn all_profile_options() {
         package: None,
         build_override: None,
         rustflags: None,
+        rustdocflags: None,
+        auto_tune: Some(true),
     };
     let mut overrides = BTreeMap::new();
     let key = cargo_toml::ProfilePackageSpec::Spec(PackageIdSpec::parse("foo").unwrap());
@@ -1711,3 +1713,77 @@ jobs = 2
         JobsConfig::Integer(v) => assert_eq!(v, 2),
     }
 }
+
+#[cargo_test]
+fn verbose_config_resolution() {
+    write_config_toml(
+        "\
+[term]
+verbose-config-resolution = true
+
+[build]
+jobs = 2
+rustflags = ['-Cdebuginfo=1']
+
+[net]
+retry = 3
+offline = true
+git-fetch-with-cli = true
+",
+    );
+
+    let config = ConfigBuilder::new()
+        .unstable_flag("verbose-config-resolution")
+        .env("CARGO_BUILD_JOBS", "4")
+        .env("CARGO_BUILD_RUSTFLAGS", "-Copt-level=1")
+        .env("CARGO_NET_OFFLINE", "true")
+        .env("CARGO_NET_GIT_FETCH_WITH_CLI", "true")
+        .config_arg("net.retry=5")
+        .config_arg("net.git-fetch-with-cli=false")
+        .build();
+
+    assert_eq!(config.get::<i64>("build.jobs").unwrap(), 4);
+    // Each key is only reported once.
+    assert_eq!(config.get::<i64>("build.jobs").unwrap(), 4);
+    assert_eq!(config.get::<u32>("net.retry").unwrap(), 5);
+    // `--config` has a higher priority than the environment.
+    assert!(!config.get::<bool>("net.git-fetch-with-cli").unwrap());
+    // Values which don't change are still reported.
+    assert!(config.get::<bool>("net.offline").unwrap());
+    // Lists are merged.
+    assert_eq!(
+        config
+            .get::<StringList>("build.rustflags")
+            .unwrap()
+            .as_slice(),
+        ["-Cdebuginfo=1", "-Copt-level=1"]
+    );
+
+    let output = read_output(config);
+    let expected = "\
+warning: `build.jobs` is set to `4` in environment variable `CARGO_BUILD_JOBS`, overriding `2` in [..]/.cargo/config.toml
+warning: `net.retry` is set to `5` in --config cli option, overriding `3` in [..]/.cargo/config.toml
+warning: `net.git-fetch-with-cli` is set to `false` in --config cli option, overriding `true` in [..]/.cargo/config.toml
+warning: `net.offline` is set to `true` in environment variable `CARGO_NET_OFFLINE`, overriding `true` in [..]/.cargo/config.toml
+";
+    assert_match(expected, &output);
+}
+
+#[cargo_test]
+fn verbose_config_resolution_gated() {
+    write_config_toml(
+        "\
+[term]
+verbose-config-resolution = true
+
+[build]
+jobs = 2
+",
+    );
+
+    let config = ConfigBuilder::new().env("CARGO_BUILD_JOBS", "4").build();
+    assert_eq!(config.get::<i64>("build.jobs").unwrap(), 4);
+
+    let output = read_output(config);
+    assert_match("", &output);
+}
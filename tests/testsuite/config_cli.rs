@@ -476,6 +476,17 @@ c = \"d\"` was not a TOML dotted key expression (such as `build.jobs = 2`)",
     );
 }
 
+#[cargo_test]
+fn no_inline_array_of_tables_values() {
+    let config = ConfigBuilder::new()
+        .config_arg("a.b = [{c = \"d\"}]")
+        .build_err();
+    assert_error(
+        config.unwrap_err(),
+        "--config argument `a.b = [{c = \"d\"}]` sets a value to an array of tables, which is not accepted",
+    );
+}
+
 #[cargo_test]
 fn no_comments() {
     // Disallow comments in dotted form.
@@ -548,17 +559,122 @@ fn cli_path() {
     assert_eq!(config.get::<u32>("key").unwrap(), 123);
 
     let config = ConfigBuilder::new().config_arg("missing.toml").build_err();
+    assert_error(
+        config.unwrap_err(),
+        "--config argument `missing.toml` looks like a path to a config file, \
+         but `[..]/missing.toml` doesn't exist",
+    );
+
+    let config = ConfigBuilder::new().config_arg("missing").build_err();
     assert_error(
         config.unwrap_err(),
         "\
-failed to parse value from --config argument `missing.toml` as a dotted key expression
+failed to parse value from --config argument `missing` as a dotted key expression
 
 Caused by:
-  TOML parse error at line 1, column 13
+  TOML parse error at line 1, column 8
   |
-1 | missing.toml
-  |             ^
+1 | missing
+  |        ^
 expected `.`, `=`
 ",
     );
 }
+
+#[cargo_test]
+fn append_gated() {
+    let config = ConfigBuilder::new()
+        .config_arg("build.rustflags += ['--cli']")
+        .build_err();
+    assert_error(
+        config.unwrap_err(),
+        "--config argument `build.rustflags += ['--cli']` appends with `+=`, which is unstable, \
+         pass `-Z config-append` to enable it",
+    );
+}
+
+#[cargo_test]
+fn append_array() {
+    write_config(
+        "
+        [build]
+        rustflags = ['--file']
+        ",
+    );
+    let config = ConfigBuilder::new()
+        .unstable_flag("config-append")
+        .config_arg("build.rustflags += ['--cli']")
+        .build();
+    assert_eq!(
+        config.get::<Vec<String>>("build.rustflags").unwrap(),
+        ["--file", "--cli"]
+    );
+
+    // Values set as strings are split like string lists.
+    write_config(
+        "
+        [build]
+        rustflags = '--file1 --file2'
+        ",
+    );
+    let config = ConfigBuilder::new()
+        .unstable_flag("config-append")
+        .config_arg("build.rustflags+=['--cli']")
+        .build();
+    assert_eq!(
+        config
+            .get::<cargo::util::config::StringList>("build.rustflags")
+            .unwrap()
+            .as_slice(),
+        ["--file1", "--file2", "--cli"]
+    );
+
+    // Including strings from earlier arguments.
+    let config = ConfigBuilder::new()
+        .unstable_flag("config-append")
+        .config_arg("build.rustflags='--one'")
+        .config_arg("build.rustflags+=['--two']")
+        .build();
+    assert_eq!(
+        config.get::<Vec<String>>("build.rustflags").unwrap(),
+        ["--file1", "--file2", "--one", "--two"]
+    );
+}
+
+#[cargo_test]
+fn append_errors() {
+    write_config(
+        "
+        [build]
+        jobs = 2
+        ",
+    );
+    let config = ConfigBuilder::new()
+        .unstable_flag("config-append")
+        .config_arg("build.jobs += ['3']")
+        .build_err();
+    assert_error(
+        config.unwrap_err(),
+        "--config argument `build.jobs += ['3']` appends to `build.jobs`, \
+         but it is set to an integer in [..]/.cargo/config, not an array",
+    );
+
+    let config = ConfigBuilder::new()
+        .unstable_flag("config-append")
+        .config_arg("build.rustflags += '--cli'")
+        .build_err();
+    assert_error(
+        config.unwrap_err(),
+        "--config argument `build.rustflags += '--cli'` appends a string, \
+         only arrays can be appended with `+=`",
+    );
+
+    let config = ConfigBuilder::new()
+        .unstable_flag("config-append")
+        .config_arg("a.b += [{c = 'd'}]")
+        .build_err();
+    assert_error(
+        config.unwrap_err(),
+        "--config argument `a.b += [{c = 'd'}]` appends an array of tables, which is not accepted",
+    );
+}
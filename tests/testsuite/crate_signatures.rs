@@ -0,0 +1,201 @@
+//! Tests for `-Zcrate-signatures`.
+
+use cargo_test_support::registry::{signature_public_key, Package, RegistryBuilder};
+use cargo_test_support::{project, Project};
+
+const KEY: &str = "k3.secret.fNYVuMvBgOlljt9TDohnaYLblghqaHoQquVZwgR6X12cBFHZLFsaU3q7X3k1Zn36";
+const OTHER_KEY: &str =
+    "k3.secret.9Vxr5hVlI_g_orBZN54vPz20bmB4O76wB_MVqUSuJJJqHFLwP8kdn_RY5g6J6pQG";
+
+fn make_project() -> Project {
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+
+                [dependencies]
+                bar = "0.0.1"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build()
+}
+
+#[cargo_test]
+fn signed() {
+    let _registry = RegistryBuilder::new()
+        .signature_key(&signature_public_key(KEY))
+        .build();
+    Package::new("bar", "0.0.1").sign(KEY).publish();
+
+    let p = make_project();
+    p.cargo("fetch -Zcrate-signatures")
+        .masquerade_as_nightly_cargo(&["crate-signatures"])
+        .with_stderr(
+            "\
+[UPDATING] `dummy-registry` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v0.0.1 (registry `dummy-registry`)
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn unsigned() {
+    let _registry = RegistryBuilder::new()
+        .signature_key(&signature_public_key(KEY))
+        .build();
+    Package::new("bar", "0.0.1").publish();
+
+    let p = make_project();
+    p.cargo("fetch -Zcrate-signatures")
+        .masquerade_as_nightly_cargo(&["crate-signatures"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[UPDATING] `dummy-registry` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v0.0.1 (registry `dummy-registry`)
+[ERROR] failed to download replaced source registry `crates-io`
+
+Caused by:
+  `bar v0.0.1 (registry `dummy-registry`)` isn't signed, \
+but registry `dummy-registry` requires signed packages
+",
+        )
+        .run();
+
+    // Without the flag the signature key is ignored.
+    p.cargo("fetch")
+        .with_stderr(
+            "\
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v0.0.1 (registry `dummy-registry`)
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn cached_unsigned() {
+    // A `.crate` file downloaded without the flag is verified once it is on.
+    let _registry = RegistryBuilder::new()
+        .signature_key(&signature_public_key(KEY))
+        .build();
+    Package::new("bar", "0.0.1").publish();
+
+    let p = make_project();
+    p.cargo("fetch").run();
+
+    p.cargo("fetch -Zcrate-signatures")
+        .masquerade_as_nightly_cargo(&["crate-signatures"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to download `bar v0.0.1`
+
+Caused by:
+  unable to get packages from source
+
+Caused by:
+  failed to download replaced source registry `crates-io`
+
+Caused by:
+  `bar v0.0.1 (registry `dummy-registry`)` isn't signed, \
+but registry `dummy-registry` requires signed packages
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn signed_with_other_key() {
+    let _registry = RegistryBuilder::new()
+        .signature_key(&signature_public_key(KEY))
+        .build();
+    Package::new("bar", "0.0.1").sign(OTHER_KEY).publish();
+
+    let p = make_project();
+    p.cargo("fetch -Zcrate-signatures")
+        .masquerade_as_nightly_cargo(&["crate-signatures"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[UPDATING] `dummy-registry` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v0.0.1 (registry `dummy-registry`)
+[ERROR] failed to download replaced source registry `crates-io`
+
+Caused by:
+  failed to verify the signature of `bar v0.0.1 (registry `dummy-registry`)`
+
+Caused by:
+  [..]
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn pinned_key() {
+    // The key in the configuration is used even if the registry doesn't
+    // advertise one.
+    let _registry = RegistryBuilder::new().alternative().build();
+    Package::new("bar", "0.0.1")
+        .alternative(true)
+        .sign(OTHER_KEY)
+        .publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+
+                [dependencies]
+                bar = { version = "0.0.1", registry = "alternative" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("fetch -Zcrate-signatures")
+        .masquerade_as_nightly_cargo(&["crate-signatures"])
+        .env(
+            "CARGO_REGISTRIES_ALTERNATIVE_SIGNATURE_KEY",
+            signature_public_key(KEY),
+        )
+        .with_status(101)
+        .with_stderr(
+            "\
+[UPDATING] `alternative` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v0.0.1 (registry `alternative`)
+[ERROR] failed to verify the signature of `bar v0.0.1 (registry `alternative`)`
+
+Caused by:
+  [..]
+",
+        )
+        .run();
+
+    p.cargo("fetch -Zcrate-signatures")
+        .masquerade_as_nightly_cargo(&["crate-signatures"])
+        .env(
+            "CARGO_REGISTRIES_ALTERNATIVE_SIGNATURE_KEY",
+            signature_public_key(OTHER_KEY),
+        )
+        .with_stderr(
+            "\
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v0.0.1 (registry `alternative`)
+",
+        )
+        .run();
+}
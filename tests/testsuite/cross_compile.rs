@@ -633,7 +633,7 @@ test result: ok. 1 passed[..]
 [RUNNING] `rustc --crate-name foo [..]
 [FINISHED] test [unoptimized + debuginfo] target(s) in [..]
 [NOTE] skipping doctests for foo v0.0.1 ([ROOT]/foo) (lib), \
-cross-compilation doctests are not yet supported
+cross-compilation doctests are not yet supported without a `runner`
 See https://doc.rust-lang.org/nightly/cargo/reference/unstable.html#doctest-xcompile \
 for more information.
 ",
@@ -654,7 +654,7 @@ for more information.
 [FINISHED] test [unoptimized + debuginfo] target(s) in [..]
 [RUNNING] `[CWD]/target/{triple}/debug/deps/foo-[..][EXE]`
 [NOTE] skipping doctests for foo v0.0.1 ([ROOT]/foo) (lib), \
-cross-compilation doctests are not yet supported
+cross-compilation doctests are not yet supported without a `runner`
 See https://doc.rust-lang.org/nightly/cargo/reference/unstable.html#doctest-xcompile \
 for more information.
 ",
@@ -663,6 +663,66 @@ for more information.
         .run();
 }
 
+#[cargo_test]
+fn cross_doctests_with_runner() {
+    if !cross_compile::can_run_on_host() {
+        return;
+    }
+
+    // A runner leaving a mark in the directory doctests run in.
+    let runner = project()
+        .at("runner")
+        .file("Cargo.toml", &basic_bin_manifest("runner"))
+        .file(
+            "src/main.rs",
+            r#"
+                fn main() {
+                    std::fs::write("runner-was-used", "").unwrap();
+                    let args: Vec<String> = std::env::args().skip(1).collect();
+                    let status = std::process::Command::new(&args[0])
+                        .args(&args[1..])
+                        .status()
+                        .unwrap();
+                    std::process::exit(status.code().unwrap_or(1));
+                }
+            "#,
+        )
+        .build();
+    runner.cargo("build").run();
+
+    let target = cross_compile::alternate();
+    let p = project()
+        .file("Cargo.toml", &basic_manifest("foo", "0.0.1"))
+        .file(
+            "src/lib.rs",
+            &format!(
+                r#"
+                    //! ```
+                    //! assert!(cfg!(target_arch = "{}"));
+                    //! ```
+                "#,
+                cross_compile::alternate_arch()
+            ),
+        )
+        .file(
+            ".cargo/config.toml",
+            &format!(
+                r#"
+                    [target.{target}]
+                    runner = '{}'
+                "#,
+                runner.bin("runner").display()
+            ),
+        )
+        .build();
+
+    p.cargo("test --doc --target")
+        .arg(&target)
+        .with_stdout_contains("test result: ok. 1 passed[..]")
+        .run();
+    assert!(p.root().join("runner-was-used").exists());
+}
+
 #[cargo_test]
 fn simple_cargo_run() {
     if !cross_compile::can_run_on_host() {
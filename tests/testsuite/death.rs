@@ -99,3 +99,130 @@ pub fn ctrl_c(child: &mut Child) {
 pub fn ctrl_c(child: &mut Child) {
     child.kill().unwrap();
 }
+
+#[cfg(unix)]
+#[cargo_test]
+fn ctrl_c_cancels_build() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "build.rs",
+            &format!(
+                r#"
+                    use std::net::TcpStream;
+                    use std::io::Read;
+
+                    fn main() {{
+                        let mut socket = TcpStream::connect("{}").unwrap();
+                        let _ = socket.read(&mut [0; 10]);
+                        panic!("that read should never return");
+                    }}
+                "#,
+                addr
+            ),
+        )
+        .build();
+
+    let mut cargo = p
+        .cargo("check -Zcancellation")
+        .masquerade_as_nightly_cargo(&["cancellation"])
+        .build_command();
+    cargo
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut child = cargo.spawn().unwrap();
+
+    let mut sock = listener.accept().unwrap().0;
+    // Only Cargo gets the signal, it kills the build script itself.
+    let r = unsafe { libc::kill(child.id() as i32, libc::SIGINT) };
+    assert_eq!(r, 0, "failed to kill: {}", io::Error::last_os_error());
+
+    match sock.read(&mut [0; 10]) {
+        Ok(n) => assert_eq!(n, 0),
+        Err(e) => assert_eq!(e.kind(), io::ErrorKind::ConnectionReset),
+    }
+    let mut stderr = String::new();
+    child
+        .stderr
+        .take()
+        .unwrap()
+        .read_to_string(&mut stderr)
+        .unwrap();
+    assert!(!child.wait().unwrap().success());
+    assert!(
+        stderr.contains("error: operation cancelled"),
+        "unexpected stderr:\n{stderr}"
+    );
+}
+
+#[cfg(unix)]
+#[cargo_test]
+fn ctrl_c_kills_processes_of_build_scripts() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    // The build script runs itself again, and waits for that process, which
+    // connects to the test.
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "build.rs",
+            &format!(
+                r#"
+                    use std::net::TcpStream;
+                    use std::io::Read;
+                    use std::process::Command;
+
+                    fn main() {{
+                        if std::env::var("GRANDCHILD").is_ok() {{
+                            let mut socket = TcpStream::connect("{}").unwrap();
+                            let _ = socket.read(&mut [0; 10]);
+                            panic!("that read should never return");
+                        }}
+                        Command::new(std::env::current_exe().unwrap())
+                            .env("GRANDCHILD", "1")
+                            .status()
+                            .unwrap();
+                    }}
+                "#,
+                addr
+            ),
+        )
+        .build();
+
+    let mut cargo = p
+        .cargo("check -Zcancellation")
+        .masquerade_as_nightly_cargo(&["cancellation"])
+        .build_command();
+    cargo
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut child = cargo.spawn().unwrap();
+
+    let mut sock = listener.accept().unwrap().0;
+    let r = unsafe { libc::kill(child.id() as i32, libc::SIGINT) };
+    assert_eq!(r, 0, "failed to kill: {}", io::Error::last_os_error());
+
+    // The process spawned by the build script is killed too.
+    match sock.read(&mut [0; 10]) {
+        Ok(n) => assert_eq!(n, 0),
+        Err(e) => assert_eq!(e.kind(), io::ErrorKind::ConnectionReset),
+    }
+    let mut stderr = String::new();
+    child
+        .stderr
+        .take()
+        .unwrap()
+        .read_to_string(&mut stderr)
+        .unwrap();
+    assert!(!child.wait().unwrap().success());
+    assert!(
+        stderr.contains("error: operation cancelled"),
+        "unexpected stderr:\n{stderr}"
+    );
+}
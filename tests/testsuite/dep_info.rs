@@ -1,6 +1,9 @@
 //! Tests for dep-info files. This includes the dep-info file Cargo creates in
 //! the output directory, and the ones stored in the fingerprint.
 
+use cargo::core::{Shell, Workspace};
+use cargo::util::dep_info;
+use cargo::Config;
 use cargo_test_support::compare::assert_match_exact;
 use cargo_test_support::paths::{self, CargoPathExt};
 use cargo_test_support::registry::Package;
@@ -598,3 +601,112 @@ fn non_local_build_script() {
         &contents,
     );
 }
+
+#[cargo_test]
+fn read_with_dep_info_api() {
+    let p = project()
+        .file("src/main.rs", "mod a; fn main() {}")
+        .file("src/a.rs", "")
+        .file(".cargo/config.toml", "[build]\ndep-info-basedir = \".\"\n")
+        .build();
+
+    p.cargo("build").run();
+    let contents = p.read_file("target/debug/foo.d");
+    assert_match_exact("target/debug/foo[EXE]: src/a.rs src/main.rs", &contents);
+
+    let shell = Shell::from_write(Box::new(Vec::new()));
+    let config = Config::new(shell, p.root(), paths::home());
+    let ws = Workspace::new(&p.root().join("Cargo.toml"), &config).unwrap();
+    let basedir = dep_info::dep_info_basedir(&ws).unwrap().unwrap();
+    let mut info = dep_info::parse_rustc_dep_info(&p.root().join("target/debug/foo.d")).unwrap();
+    info.resolve(&basedir);
+    assert_eq!(
+        info.files,
+        [p.root().join("src/a.rs"), p.root().join("src/main.rs")]
+    );
+
+    // The files in the fingerprint directory are relative to the package
+    // and target roots.
+    let fingerprint = p
+        .glob("target/debug/.fingerprint/foo-*/dep-bin-foo")
+        .next()
+        .unwrap()
+        .unwrap();
+    let info = dep_info::parse_dep_info(&p.root(), &p.target_debug_dir(), &fingerprint)
+        .unwrap()
+        .unwrap();
+    let mut files = info.files;
+    files.sort();
+    assert_eq!(
+        files,
+        [p.root().join("src/a.rs"), p.root().join("src/main.rs")]
+    );
+}
+
+#[cargo_test]
+fn dep_info_basedir_gated() {
+    let p = project()
+        .file("src/main.rs", "fn main() {}")
+        .file(
+            ".cargo/config.toml",
+            "[build]\ndep-info-basedir = \"{workspace-root}\"\n",
+        )
+        .build();
+
+    p.cargo("build --dep-info-basedir .")
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] the `--dep-info-basedir` flag is unstable, \
+             pass `-Z dep-info-basedir` to enable it",
+        )
+        .run();
+
+    // The placeholder is a directory name on stable.
+    p.cargo("build").run();
+    let contents = p.read_file("target/debug/foo.d");
+    assert_match_exact(
+        "[ROOT]/foo/target/debug/foo[EXE]: [ROOT]/foo/src/main.rs",
+        &contents,
+    );
+}
+
+#[cargo_test]
+fn dep_info_basedir_placeholders() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["foo"]
+            "#,
+        )
+        .file("foo/Cargo.toml", &basic_bin_manifest("foo"))
+        .file("foo/src/main.rs", "fn main() {}")
+        .file(
+            "foo/.cargo/config.toml",
+            "[build]\ndep-info-basedir = \"{workspace-root}\"\n",
+        )
+        .build();
+
+    p.cargo("build -Zdep-info-basedir")
+        .cwd("foo")
+        .masquerade_as_nightly_cargo(&["dep-info-basedir"])
+        .run();
+    let contents = p.read_file("target/debug/foo.d");
+    assert_match_exact("target/debug/foo[EXE]: foo/src/main.rs", &contents);
+
+    // The flag overrides the config, and is relative to the current directory.
+    p.cargo("build -Zdep-info-basedir --dep-info-basedir {target-dir}/debug")
+        .cwd("foo")
+        .masquerade_as_nightly_cargo(&["dep-info-basedir"])
+        .run();
+    let contents = p.read_file("target/debug/foo.d");
+    assert_match_exact("foo[EXE]: [ROOT]/foo/foo/src/main.rs", &contents);
+
+    p.cargo("build -Zdep-info-basedir --dep-info-basedir src")
+        .cwd("foo")
+        .masquerade_as_nightly_cargo(&["dep-info-basedir"])
+        .run();
+    let contents = p.read_file("target/debug/foo.d");
+    assert_match_exact("[ROOT]/foo/target/debug/foo[EXE]: main.rs", &contents);
+}
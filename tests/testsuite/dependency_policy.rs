@@ -0,0 +1,133 @@
+//! Tests for `-Zdependency-policy`.
+
+use cargo_test_support::registry::RegistryBuilder;
+use cargo_test_support::{basic_manifest, git, project, Project};
+
+/// `foo` depends on the path dependency `bar`, which depends on the git
+/// dependency `gitdep`.
+fn git_dep_project(policy: &str) -> Project {
+    let git_project = git::new("gitdep", |p| {
+        p.file("Cargo.toml", &basic_manifest("gitdep", "0.5.0"))
+            .file("src/lib.rs", "")
+    });
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                bar = { path = "bar" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            "bar/Cargo.toml",
+            &format!(
+                r#"
+                    [package]
+                    name = "bar"
+                    version = "0.1.0"
+
+                    [dependencies]
+                    gitdep = {{ git = "{}" }}
+                "#,
+                git_project.url()
+            ),
+        )
+        .file("bar/src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            &format!("[dependency-policy]\n{policy}\n"),
+        )
+        .build()
+}
+
+#[cargo_test]
+fn forbid_git_deps() {
+    let p = git_dep_project("forbid-git-deps = true");
+
+    p.cargo("check -Zdependency-policy")
+        .masquerade_as_nightly_cargo(&["dependency-policy"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[UPDATING] git repository `[..]gitdep`
+[ERROR] git dependencies are forbidden in the `dev` profile by `dependency-policy.forbid-git-deps`
+
+package `gitdep v0.5.0 ([..]gitdep#[..])`
+    ... which satisfies git dependency `gitdep` (locked to 0.5.0) of package `bar v0.1.0 ([CWD]/bar)`
+    ... which satisfies path dependency `bar` (locked to 0.1.0) of package `foo v0.1.0 ([CWD])`
+",
+        )
+        .run();
+
+    // Without the flag the policy is ignored.
+    p.cargo("check").run();
+}
+
+#[cargo_test]
+fn forbid_git_deps_in_profiles() {
+    let p = git_dep_project(r#"forbid-git-deps = ["release"]"#);
+
+    p.cargo("check -Zdependency-policy")
+        .masquerade_as_nightly_cargo(&["dependency-policy"])
+        .run();
+
+    p.cargo("check --release -Zdependency-policy")
+        .masquerade_as_nightly_cargo(&["dependency-policy"])
+        .with_status(101)
+        .with_stderr_contains(
+            "[ERROR] git dependencies are forbidden in the `release` profile \
+             by `dependency-policy.forbid-git-deps`",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn forbid_path_deps_on_publish() {
+    let registry = RegistryBuilder::new().http_api().http_index().build();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                license = "MIT"
+                description = "foo"
+
+                [dependencies]
+                bar = { path = "bar", version = "0.1.0" }
+
+                [dev-dependencies]
+                baz = { path = "baz" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.1.0"))
+        .file("bar/src/lib.rs", "")
+        .file("baz/Cargo.toml", &basic_manifest("baz", "0.1.0"))
+        .file("baz/src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            "[dependency-policy]\nforbid-path-deps-on-publish = true\n",
+        )
+        .build();
+
+    p.cargo("publish --no-verify -Zdependency-policy")
+        .masquerade_as_nightly_cargo(&["dependency-policy"])
+        .replace_crates_io(registry.index_url())
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] path dependencies cannot be published with `dependency-policy.forbid-path-deps-on-publish`
+
+package `foo v0.1.0 ([CWD])`
+    ... has path dependency `bar` on `[CWD]/bar`
+",
+        )
+        .run();
+}
@@ -2,8 +2,11 @@
 
 use std::collections::HashMap;
 use std::fs;
+use std::path::Path;
 use std::str;
 
+use filetime::FileTime;
+
 use serde::Serialize;
 
 use cargo_test_support::cargo_process;
@@ -776,3 +779,113 @@ Caused by:
         .with_status(101)
         .run();
 }
+
+/// Makes the files of the directory source look like they were vendored a
+/// while ago, so that they are cached by the index.
+fn backdate_index() {
+    let mtime = FileTime::from_unix_time(FileTime::now().unix_seconds() - 3600, 0);
+    for entry in walkdir::WalkDir::new(paths::root().join("index")) {
+        let entry = entry.unwrap();
+        if entry.file_type().is_file() {
+            filetime::set_file_mtime(entry.path(), mtime).unwrap();
+        }
+    }
+}
+
+/// Replaces the contents of `path` without changing its size or mtime.
+fn replace_in_place(path: &Path, contents: &str) {
+    let meta = fs::metadata(path).unwrap();
+    assert_eq!(meta.len(), contents.len() as u64);
+    fs::write(path, contents).unwrap();
+    filetime::set_file_mtime(path, FileTime::from_last_modification_time(&meta)).unwrap();
+}
+
+#[cargo_test]
+fn unused_packages_are_not_loaded() {
+    setup();
+
+    VendorPackage::new("bar")
+        .file("Cargo.toml", &basic_manifest("bar", "0.1.0"))
+        .file("src/lib.rs", "")
+        .build();
+    VendorPackage::new("baz")
+        .file("Cargo.toml", &basic_manifest("baz", "0.1.0"))
+        .file("src/lib.rs", "")
+        .build();
+    backdate_index();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                bar = "0.1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check").run();
+    let index = paths::home().join(".cargo/directory-index");
+    assert_eq!(index.read_dir().unwrap().count(), 1);
+
+    // `baz` is known not to be needed, so its manifest isn't read again.
+    let manifest = paths::root().join("index/baz/Cargo.toml");
+    let len = fs::metadata(&manifest).unwrap().len() as usize;
+    replace_in_place(&manifest, &"x".repeat(len));
+    p.cargo("check").with_stderr("[FINISHED] [..]").run();
+
+    // Unless it changed.
+    filetime::set_file_mtime(&manifest, FileTime::now()).unwrap();
+    p.cargo("check")
+        .with_status(101)
+        .with_stderr_contains("  failed to parse manifest at `[..]baz/Cargo.toml`")
+        .run();
+}
+
+#[cargo_test]
+fn file_checksums_are_cached() {
+    setup();
+
+    VendorPackage::new("bar")
+        .file("Cargo.toml", &basic_manifest("bar", "0.1.0"))
+        .file("src/lib.rs", "pub fn bar() {}")
+        .build();
+    backdate_index();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                bar = "0.1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check").run();
+
+    // The file looks unchanged, so it is not hashed when `bar` is rebuilt.
+    let lib = paths::root().join("index/bar/src/lib.rs");
+    replace_in_place(&lib, "pub fn baz() {}");
+    p.cargo("check")
+        .env("RUSTFLAGS", "--cfg foo")
+        .with_stderr_contains("[CHECKING] bar v0.1.0")
+        .run();
+
+    // But it is once it looks modified.
+    filetime::set_file_mtime(&lib, FileTime::now()).unwrap();
+    p.cargo("check")
+        .with_status(101)
+        .with_stderr_contains("error: the listed checksum of `[..]lib.rs` has changed:")
+        .run();
+}
@@ -2590,3 +2590,226 @@ fn link_to_private_item() {
         )
         .run();
 }
+
+#[cargo_test]
+fn show_coverage_gated() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("doc --show-coverage")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] the `--show-coverage` flag is unstable, pass `-Z unstable-options` to enable it",
+        )
+        .run();
+
+    p.cargo("doc --deny-warnings")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] the `--deny-warnings` flag is unstable, pass `-Z unstable-options` to enable it",
+        )
+        .run();
+
+    p.cargo("doc --show-coverage --open -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr("[ERROR] `--open` can't be used with `--show-coverage`")
+        .run();
+}
+
+#[cargo_test]
+fn deny_warnings() {
+    Package::new("bar", "1.0.0")
+        .file("src/lib.rs", "/// [missing]\npub fn bar() {}")
+        .publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+
+                [dependencies]
+                bar = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "pub fn foo() {}")
+        .build();
+
+    // Only the warnings of the documented packages are denied.
+    p.cargo("doc --deny-warnings -Zunstable-options -v")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_contains("[RUNNING] `rustdoc [..]--crate-name foo [..]-D warnings [..]")
+        .with_stderr_line_without(
+            &["[RUNNING] `rustdoc [..]--crate-name bar "],
+            &["-D warnings"],
+        )
+        .run();
+
+    p.change_file("src/lib.rs", "/// [missing]\npub fn foo() {}");
+    p.cargo("doc --deny-warnings -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_contains("[ERROR] unresolved link to `missing`")
+        .with_stderr_contains("[ERROR] could not document `foo`")
+        .run();
+}
+
+#[cargo_test(nightly, reason = "rustdoc --show-coverage is unstable")]
+fn show_coverage() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["a", "b"]
+            "#,
+        )
+        .file("a/Cargo.toml", &basic_manifest("a", "0.1.0"))
+        .file(
+            "a/src/lib.rs",
+            r#"
+                //! Crate a.
+
+                /// Documented, with an example.
+                ///
+                /// ```
+                /// a::f();
+                /// ```
+                pub fn f() {}
+
+                pub fn g() {}
+            "#,
+        )
+        .file("b/Cargo.toml", &basic_manifest("b", "0.2.0"))
+        .file("b/src/lib.rs", "pub fn h() {}")
+        .build();
+
+    p.cargo("doc --show-coverage -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout(
+            "\
+Documented  Percentage    Examples  Percentage  Crate
+       2/3       66.7%         1/2       50.0%  a v0.1.0
+       0/2        0.0%         0/1        0.0%  b v0.2.0
+       2/5       40.0%         1/3       33.3%  Total
+",
+        )
+        .with_stderr_contains("   Generated [CWD]/target/doc/coverage.json")
+        .run();
+    assert!(!p.root().join("target/doc/a/index.html").exists());
+
+    let report = p.read_file("target/doc/coverage.json");
+    let report: serde_json::Value = serde_json::from_str(&report).unwrap();
+    assert_eq!(report["crates"][0]["crate_name"], "a");
+    assert_eq!(report["crates"][1]["with_docs"], 0);
+    assert_eq!(report["total"]["total"], 5);
+
+    p.cargo("doc --show-coverage -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .arg("--config=doc.min-coverage=50")
+        .with_status(101)
+        .with_stderr_contains(
+            "[ERROR] the documentation coverage of 40.0% is below the `doc.min-coverage` of 50%",
+        )
+        .run();
+
+    p.cargo("doc --show-coverage -Zunstable-options -p a")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .arg("--config=doc.min-coverage=50")
+        .run();
+}
+
+#[cargo_test(nightly, reason = "rustdoc --show-coverage is unstable")]
+fn show_coverage_same_crate_name() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["a", "b"]
+            "#,
+        )
+        .file("a/Cargo.toml", &basic_manifest("a", "0.1.0"))
+        .file("a/src/lib.rs", "/// Documented.\npub fn f() {}")
+        .file(
+            "b/Cargo.toml",
+            r#"
+                [package]
+                name = "b"
+                version = "0.2.0"
+
+                [lib]
+                name = "a"
+            "#,
+        )
+        .file("b/src/lib.rs", "pub fn g() {}")
+        .build();
+
+    p.cargo("doc --show-coverage -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout(
+            "\
+Documented  Percentage    Examples  Percentage  Crate
+       1/2       50.0%         0/1        0.0%  a v0.1.0
+       0/2        0.0%         0/1        0.0%  a v0.2.0
+       1/4       25.0%         0/2        0.0%  Total
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn doc_workspace_open_current_package() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["foo", "bar"]
+            "#,
+        )
+        .file("foo/Cargo.toml", &basic_manifest("foo", "0.1.0"))
+        .file("foo/src/lib.rs", "")
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.1.0"))
+        .file("bar/src/lib.rs", "")
+        .build();
+
+    // The documentation of the package in the current directory is opened,
+    // not the first one.
+    p.cargo("doc --workspace --open")
+        .cwd("foo")
+        .env("BROWSER", tools::echo())
+        .with_stderr_contains("[..] Opening [..]/foo/index.html")
+        .run();
+}
+
+#[cargo_test]
+fn doc_browser_templates() {
+    let p = project().file("src/lib.rs", "").build();
+    p.change_file(
+        ".cargo/config.toml",
+        &format!(
+            r#"
+                [doc]
+                browser = ["{}", "--new-window", "--page={{url}}", "{{path}}"]
+            "#,
+            tools::echo().display().to_string().replace('\\', "\\\\")
+        ),
+    );
+
+    // Without the flag, the placeholders are passed as is.
+    p.cargo("doc --open")
+        .with_stdout_contains("--new-window --page={url} {path} [CWD]/target/doc/foo/index.html")
+        .run();
+
+    p.cargo("doc --open -Zdoc-browser-templates")
+        .masquerade_as_nightly_cargo(&["doc-browser-templates"])
+        .with_stdout_contains(
+            "--new-window --page=file://[CWD]/target/doc/foo/index.html \
+             [CWD]/target/doc/foo/index.html",
+        )
+        .run();
+}
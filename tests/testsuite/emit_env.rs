@@ -0,0 +1,117 @@
+//! Tests for `--emit-env`.
+
+use cargo_test_support::{project, t, Project};
+use std::path::PathBuf;
+
+/// Returns the path of the file `name` in the fingerprint directory of the
+/// unit of `pkg`.
+fn env_file(p: &Project, pkg: &str, name: &str) -> PathBuf {
+    let pattern = p
+        .root()
+        .join(format!("target/debug/.fingerprint/{pkg}-*/{name}"));
+    let mut found: Vec<_> = t!(glob::glob(pattern.to_str().unwrap()))
+        .map(|path| path.unwrap())
+        .collect();
+    assert_eq!(found.len(), 1, "{found:?}");
+    found.pop().unwrap()
+}
+
+fn read_env_file(p: &Project, pkg: &str, name: &str) -> serde_json::Value {
+    let contents = std::fs::read_to_string(env_file(p, pkg, name)).unwrap();
+    serde_json::from_str(&contents).unwrap()
+}
+
+#[cargo_test]
+fn emit_env() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                build = "build.rs"
+            "#,
+        )
+        .file("build.rs", "fn main() {}")
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check -Zunstable-options --emit-env")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .env("EMIT_ENV_TEST", "inherited")
+        .run();
+
+    let lib = read_env_file(&p, "foo", "env-lib-foo");
+    assert_eq!(lib["reason"], "unit-env");
+    assert_eq!(lib["mode"], "check");
+    assert_eq!(lib["target"]["name"], "foo");
+    assert!(lib["program"].as_str().unwrap().contains("rustc"));
+    let args: Vec<_> = lib["args"].as_array().unwrap().iter().collect();
+    assert_eq!(args[..2], ["--crate-name", "foo"]);
+    assert_eq!(lib["cwd"], p.root().to_str().unwrap());
+    assert_eq!(lib["env"]["CARGO_PKG_NAME"], "foo");
+    assert_eq!(lib["env"]["EMIT_ENV_TEST"], "inherited");
+
+    let run = read_env_file(&p, "foo", "env-run-build-script-build-script-build");
+    assert_eq!(run["mode"], "run-custom-build");
+    assert!(run["program"]
+        .as_str()
+        .unwrap()
+        .contains("build-script-build"));
+    assert!(run["env"]["OUT_DIR"].as_str().unwrap().contains("out"));
+
+    // Fresh units keep the file of their last run.
+    p.cargo("check -Zunstable-options --emit-env")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .run();
+    let lib = read_env_file(&p, "foo", "env-lib-foo");
+    assert_eq!(lib["env"]["EMIT_ENV_TEST"], "inherited");
+}
+
+#[cargo_test]
+fn emit_env_json_messages() {
+    let p = project().file("src/lib.rs", "").build();
+
+    let output = p
+        .cargo("check -Zunstable-options --emit-env --message-format=json")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .exec_with_output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let messages: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .filter(|msg: &serde_json::Value| msg["reason"] == "unit-env")
+        .collect();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0], read_env_file(&p, "foo", "env-lib-foo"));
+}
+
+#[cfg(unix)]
+#[cargo_test]
+fn emit_env_file_is_private() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("check -Zunstable-options --emit-env")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .run();
+    let metadata = std::fs::metadata(env_file(&p, "foo", "env-lib-foo")).unwrap();
+    assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+}
+
+#[cargo_test]
+fn emit_env_requires_unstable_options() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("check --emit-env")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] the `--emit-env` flag is unstable, pass `-Z unstable-options` to enable it
+",
+        )
+        .run();
+}
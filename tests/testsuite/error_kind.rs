@@ -0,0 +1,106 @@
+//! Tests for the kinds of errors returned to library consumers, see
+//! `CargoErrorKind`.
+
+use cargo::core::{compiler::CompileMode, Shell, Workspace};
+use cargo::ops::{self, CompileOptions};
+use cargo::util::config::Config;
+use cargo::util::important_paths::find_project_manifest_exact;
+use cargo::CargoErrorKind;
+
+use cargo_test_support::install::cargo_home;
+use cargo_test_support::registry;
+use cargo_test_support::{basic_manifest, paths, project};
+
+fn config() -> Config {
+    Config::new(
+        Shell::from_write(Box::new(Vec::new())),
+        cargo_home(),
+        cargo_home(),
+    )
+}
+
+#[cargo_test]
+fn manifest_not_found() {
+    let error = find_project_manifest_exact(&paths::root(), "Cargo.toml").unwrap_err();
+    assert_eq!(CargoErrorKind::of(&error), CargoErrorKind::ManifestNotFound);
+}
+
+#[cargo_test]
+fn invalid_manifest() {
+    let p = project().file("Cargo.toml", "[package").build();
+
+    let error = Workspace::new(&p.root().join("Cargo.toml"), &config()).unwrap_err();
+    assert_eq!(CargoErrorKind::of(&error), CargoErrorKind::Manifest);
+}
+
+#[cargo_test]
+fn resolve_failure() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                i-dont-exist = "0.55"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    // Prevent this test from accessing the network by setting up .cargo/config.
+    registry::init();
+    let config = config();
+    let ws = Workspace::new(&p.root().join("Cargo.toml"), &config).unwrap();
+    let compile_options = CompileOptions::new(&config, CompileMode::Build).unwrap();
+    let error = ops::compile(&ws, &compile_options).map(drop).unwrap_err();
+    assert_eq!(CargoErrorKind::of(&error), CargoErrorKind::Resolve);
+}
+
+#[cargo_test]
+fn compile_failure() {
+    let p = project()
+        .file("Cargo.toml", &basic_manifest("foo", "0.1.0"))
+        .file("src/lib.rs", "invalid")
+        .build();
+
+    let config = config();
+    let ws = Workspace::new(&p.root().join("Cargo.toml"), &config).unwrap();
+    let compile_options = CompileOptions::new(&config, CompileMode::Build).unwrap();
+    let error = ops::compile(&ws, &compile_options).map(drop).unwrap_err();
+    assert_eq!(CargoErrorKind::of(&error), CargoErrorKind::Compile);
+    // The error was already displayed by the build.
+    assert!(error.is::<cargo::AlreadyPrintedError>());
+}
+
+#[cargo_test]
+fn build_script_failure() {
+    let p = project()
+        .file("Cargo.toml", &basic_manifest("foo", "0.1.0"))
+        .file("src/lib.rs", "")
+        .file("build.rs", "fn main() { std::process::exit(1); }")
+        .build();
+
+    let config = config();
+    let ws = Workspace::new(&p.root().join("Cargo.toml"), &config).unwrap();
+    let compile_options = CompileOptions::new(&config, CompileMode::Build).unwrap();
+    let error = ops::compile(&ws, &compile_options).map(drop).unwrap_err();
+    assert_eq!(CargoErrorKind::of(&error), CargoErrorKind::Compile);
+}
+
+#[cargo_test]
+fn cancelled() {
+    let p = project()
+        .file("Cargo.toml", &basic_manifest("foo", "0.1.0"))
+        .file("src/lib.rs", "")
+        .build();
+
+    let config = config();
+    let ws = Workspace::new(&p.root().join("Cargo.toml"), &config).unwrap();
+    let compile_options = CompileOptions::new(&config, CompileMode::Build).unwrap();
+    config.cancellation().clone().cancel();
+    let error = ops::compile(&ws, &compile_options).map(drop).unwrap_err();
+    assert_eq!(CargoErrorKind::of(&error), CargoErrorKind::Cancelled);
+}
@@ -0,0 +1,134 @@
+//! Tests for `cargo --explain-config` and `cargo --schema-json`.
+
+use cargo_test_support::cargo_process;
+
+#[cargo_test]
+fn gated() {
+    cargo_process("--explain-config build.jobs")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] the `--explain-config` flag is unstable, pass `-Z unstable-options` to enable it
+",
+        )
+        .run();
+    cargo_process("--schema-json")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] the `--schema-json` flag is unstable, pass `-Z unstable-options` to enable it
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn explain_key() {
+    cargo_process("-Zunstable-options --explain-config build.jobs")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout(
+            "\
+build.jobs
+type: integer or string
+default: number of logical CPUs
+environment: CARGO_BUILD_JOBS
+
+Sets the maximum number of compiler processes to run in parallel. [..]
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn explain_key_with_placeholder() {
+    cargo_process("-Zunstable-options --explain-config registries.my-registry.index")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout(
+            "\
+registries.<name>.index
+type: string (url)
+environment: CARGO_REGISTRIES_<name>_INDEX
+
+Specifies the URL of the index for the registry.
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn unknown_key() {
+    cargo_process("-Zunstable-options --explain-config build.jobz")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] unknown config key `build.jobz`
+
+See https://doc.rust-lang.org/nightly/cargo/reference/config.html for the known keys.
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn key_schema_json() {
+    cargo_process("-Zunstable-options --explain-config http.timeout --schema-json")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_json(
+            r#"
+            {
+              "description": "Sets the timeout for each HTTP request, in seconds.",
+              "type": "integer",
+              "x-default": "30"
+            }
+            "#,
+        )
+        .run();
+}
+
+#[cargo_test]
+fn schema_json() {
+    cargo_process("-Zunstable-options --schema-json")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_json(
+            r#"
+            {
+              "$schema": "http://json-schema.org/draft-07/schema#",
+              "title": "Cargo configuration",
+              "type": "object",
+              "properties": {
+                "build": {
+                  "type": "object",
+                  "description": "{...}",
+                  "properties": "{...}"
+                },
+                "registries": {
+                  "type": "object",
+                  "description": "{...}",
+                  "additionalProperties": {
+                    "type": "object",
+                    "properties": "{...}"
+                  },
+                  "properties": "{...}"
+                },
+                "target": "{...}",
+                "alias": "{...}",
+                "cargo-new": "{...}",
+                "doc": "{...}",
+                "env": "{...}",
+                "future-incompat-report": "{...}",
+                "http": "{...}",
+                "install": "{...}",
+                "net": "{...}",
+                "patch": "{...}",
+                "paths": "{...}",
+                "profile": "{...}",
+                "registry": "{...}",
+                "source": "{...}",
+                "term": "{...}"
+              }
+            }
+            "#,
+        )
+        .run();
+}
@@ -133,3 +133,159 @@ fn fetch_warning() {
         .with_stderr("[WARNING] unused manifest key: package.misspelled")
         .run();
 }
+
+#[cargo_test]
+fn fetch_multiple_targets() {
+    if cross_compile::disabled() {
+        return;
+    }
+
+    Package::new("d1", "1.2.3").publish();
+    Package::new("d2", "0.1.2").publish();
+    Package::new("d3", "0.3.0").publish();
+
+    let target = cross_compile::alternate();
+    let host = rustc_host();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                    [package]
+                    name = "foo"
+                    version = "0.0.1"
+
+                    [target.{host}.dependencies]
+                    d1 = "1.2.3"
+
+                    [target.{target}.dependencies]
+                    d2 = "0.1.2"
+
+                    [target.'cfg(any())'.dependencies]
+                    d3 = "0.3.0"
+                "#
+            ),
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("fetch --target")
+        .arg(&host)
+        .arg("--target")
+        .arg(&target)
+        .with_stderr_contains("[DOWNLOADED] d1 v1.2.3 [..]")
+        .with_stderr_contains("[DOWNLOADED] d2 v0.1.2 [..]")
+        .with_stderr_does_not_contain("[DOWNLOADED] d3 [..]")
+        .run();
+}
+
+#[cargo_test]
+fn dry_run() {
+    Package::new("bar", "1.0.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+
+                [dependencies]
+                bar = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("fetch --dry-run -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["fetch --dry-run"])
+        .with_stderr(
+            "\
+[UPDATING] `dummy-registry` index
+[DOWNLOADING] bar v1.0.0
+[WARNING] not downloading packages due to dry run
+",
+        )
+        .run();
+    assert!(p.root().join("Cargo.lock").is_file());
+
+    p.cargo("fetch")
+        .with_stderr(
+            "\
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v1.0.0 (registry `dummy-registry`)
+",
+        )
+        .run();
+
+    p.cargo("fetch --dry-run -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["fetch --dry-run"])
+        .with_stderr("[WARNING] not downloading packages due to dry run")
+        .run();
+}
+
+#[cargo_test]
+fn json() {
+    Package::new("bar", "1.0.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+
+                [dependencies]
+                bar = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("fetch --json --dry-run -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["fetch --json"])
+        .with_json(
+            r#"
+                {"id": "bar 1.0.0 (registry+https://github.com/rust-lang/crates.io-index)", "fresh": false}
+
+                {"id": "foo 0.0.1 (path+file://[..])", "fresh": true}
+            "#,
+        )
+        .run();
+
+    p.cargo("fetch --json -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["fetch --json"])
+        .with_json(
+            r#"
+                {"id": "bar 1.0.0 (registry+https://github.com/rust-lang/crates.io-index)", "fresh": false}
+
+                {"id": "foo 0.0.1 (path+file://[..])", "fresh": true}
+            "#,
+        )
+        .with_stderr_contains("[DOWNLOADED] bar v1.0.0 [..]")
+        .run();
+
+    p.cargo("fetch --json -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["fetch --json"])
+        .with_json(
+            r#"
+                {"id": "bar 1.0.0 (registry+https://github.com/rust-lang/crates.io-index)", "fresh": true}
+
+                {"id": "foo 0.0.1 (path+file://[..])", "fresh": true}
+            "#,
+        )
+        .run();
+}
+
+#[cargo_test]
+fn dry_run_requires_unstable_options() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("fetch --dry-run")
+        .masquerade_as_nightly_cargo(&["fetch --dry-run"])
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] the `--dry-run` flag is unstable, pass `-Z unstable-options` to enable it",
+        )
+        .run();
+}
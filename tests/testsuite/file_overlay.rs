@@ -0,0 +1,106 @@
+//! Tests for reading manifests and configuration files from memory with
+//! `Config::set_file_overlay`.
+
+use cargo::{
+    core::{Shell, Workspace},
+    Config,
+};
+use cargo_test_support::{basic_manifest, paths, project};
+
+#[cargo_test]
+fn overlay_manifests() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["foo"]
+            "#,
+        )
+        .file("foo/Cargo.toml", &basic_manifest("foo", "0.1.0"))
+        .file("foo/src/lib.rs", "")
+        .file("bar/src/lib.rs", "")
+        .build();
+    let shell = Shell::from_write(Box::new(Vec::new()));
+    let mut config = Config::new(shell, p.root(), paths::home());
+
+    let manifest = p.root().join("foo/Cargo.toml");
+    let ws = Workspace::new(&manifest, &config).unwrap();
+    assert_eq!(ws.current().unwrap().version().to_string(), "0.1.0");
+    drop(ws);
+
+    // The unsaved root manifest adds a member whose manifest only exists in
+    // memory, and the unsaved manifest of `foo` depends on it.
+    config.set_file_overlay(
+        &p.root().join("Cargo.toml"),
+        r#"
+            [workspace]
+            members = ["foo", "bar"]
+        "#
+        .to_string(),
+    );
+    config.set_file_overlay(
+        &p.root().join("bar/Cargo.toml"),
+        basic_manifest("bar", "0.1.0"),
+    );
+    config.set_file_overlay(
+        &manifest,
+        r#"
+            [package]
+            name = "foo"
+            version = "0.2.0"
+
+            [dependencies]
+            bar = { path = "../bar" }
+        "#
+        .to_string(),
+    );
+    let ws = Workspace::new(&manifest, &config).unwrap();
+    assert_eq!(ws.current().unwrap().version().to_string(), "0.2.0");
+    let mut members: Vec<_> = ws.members().map(|p| p.name().as_str()).collect();
+    members.sort();
+    assert_eq!(members, ["bar", "foo"]);
+    assert!(!p.root().join("bar/Cargo.toml").exists());
+    drop(ws);
+
+    // Removing the overlays reads the manifests on disk again.
+    config.remove_file_overlay(&p.root().join("Cargo.toml"));
+    config.remove_file_overlay(&manifest);
+    let ws = Workspace::new(&manifest, &config).unwrap();
+    assert_eq!(ws.current().unwrap().version().to_string(), "0.1.0");
+    assert_eq!(ws.members().count(), 1);
+}
+
+#[cargo_test]
+fn overlay_config() {
+    let p = project()
+        .file(".cargo/config.toml", "[build]\njobs = 1\n")
+        .file("src/lib.rs", "")
+        .build();
+    let shell = Shell::from_write(Box::new(Vec::new()));
+    let mut config = Config::new(shell, p.root(), paths::home());
+
+    config.set_file_overlay(
+        &p.root().join(".cargo/config.toml"),
+        "[build]\njobs = 2\n".to_string(),
+    );
+    config.set_file_overlay(
+        &paths::root().join(".cargo/config.toml"),
+        "[term]\nverbose = true\n".to_string(),
+    );
+    assert_eq!(config.get::<Option<i64>>("build.jobs").unwrap(), Some(2));
+    assert_eq!(
+        config.get::<Option<bool>>("term.verbose").unwrap(),
+        Some(true)
+    );
+
+    // Values already loaded are only read again when reloading.
+    config.remove_file_overlay(&p.root().join(".cargo/config.toml"));
+    assert_eq!(config.get::<Option<i64>>("build.jobs").unwrap(), Some(2));
+    config.reload_rooted_at(p.root()).unwrap();
+    assert_eq!(config.get::<Option<i64>>("build.jobs").unwrap(), Some(1));
+    assert_eq!(
+        config.get::<Option<bool>>("term.verbose").unwrap(),
+        Some(true)
+    );
+}
@@ -5,8 +5,9 @@ use cargo_test_support::compare::assert_match_exact;
 use cargo_test_support::git::{self, init};
 use cargo_test_support::paths::{self, CargoPathExt};
 use cargo_test_support::registry::{Dependency, Package};
-use cargo_test_support::{basic_manifest, is_nightly, project, Project};
+use cargo_test_support::{basic_manifest, is_nightly, path2url, project, Project};
 use cargo_test_support::{tools, wrapped_clippy_driver};
+use std::path::Path;
 
 #[cargo_test]
 fn do_not_fix_broken_builds() {
@@ -850,6 +851,50 @@ fn does_not_warn_about_clean_working_directory() {
     p.cargo("fix").run();
 }
 
+#[cargo_test]
+fn warns_about_dirty_submodule() {
+    let (p, repo) = git::new_repo("foo", |p| p.file("src/lib.rs", "pub fn foo() {}"));
+    let sub = git::new("bar", |p| p.no_manifest().file("lib.rs", "pub fn bar() {}"));
+    git::add_submodule(&repo, &path2url(sub.root()).to_string(), Path::new("bar"));
+    git::commit(&repo);
+
+    p.change_file("bar/lib.rs", "");
+
+    p.cargo("fix")
+        .with_status(101)
+        .with_stderr(
+            "\
+error: the working directory of this package has uncommitted changes, \
+and `cargo fix` can potentially perform destructive changes; if you'd \
+like to suppress this error pass `--allow-dirty`, `--allow-staged`, or \
+commit the changes to these files:
+
+  * bar/lib.rs (dirty)
+
+
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn does_not_warn_about_sparse_checkout() {
+    // Files outside of a sparse checkout are missing, but not deleted.
+    let (p, repo) = git::new_repo("foo", |p| {
+        p.file("src/lib.rs", "pub fn foo() {}")
+            .file("docs/guide.md", "")
+    });
+    let mut index = repo.index().unwrap();
+    let mut entry = index.get_path(Path::new("docs/guide.md"), 0).unwrap();
+    entry.flags |= git2::IndexEntryFlag::EXTENDED.bits();
+    entry.flags_extended |= git2::IndexEntryExtendedFlag::SKIP_WORKTREE.bits();
+    index.add(&entry).unwrap();
+    index.write().unwrap();
+    p.root().join("docs").rm_rf();
+
+    p.cargo("fix").run();
+}
+
 #[cargo_test]
 fn does_not_warn_about_dirty_ignored_files() {
     let p = git::new("foo", |p| {
@@ -1644,6 +1689,282 @@ fn fix_edition_2021() {
     assert!(p.read_file("src/lib.rs").contains(r#"0..=100 => true,"#));
 }
 
+#[cargo_test]
+fn to_edition_gated() {
+    let p = project().file("src/lib.rs", "").build();
+    p.cargo("fix --to-edition 2021 --allow-no-vcs")
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] the `--to-edition` flag is unstable, \
+             pass `-Z unstable-options` to enable it",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn to_edition_mixed_workspace() {
+    // Migrates each member from its own edition through the following ones.
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["a", "b", "c"]
+            "#,
+        )
+        .file(
+            "a/Cargo.toml",
+            r#"
+                [package]
+                name = "a"
+                version = "0.1.0"
+            "#,
+        )
+        .file(
+            "a/src/lib.rs",
+            r#"
+                pub fn async() {}
+
+                pub fn f(x: u32) -> bool {
+                    async();
+                    match x {
+                        0...100 => true,
+                        _ => false,
+                    }
+                }
+            "#,
+        )
+        .file(
+            "b/Cargo.toml",
+            r#"
+                [package]
+                name = "b"
+                version = "0.1.0"
+                edition = "2018"
+            "#,
+        )
+        .file(
+            "b/src/lib.rs",
+            r#"
+                pub fn f(x: u32) -> bool {
+                    match x {
+                        0...100 => true,
+                        _ => false,
+                    }
+                }
+            "#,
+        )
+        .file(
+            "c/Cargo.toml",
+            r#"
+                [package]
+                name = "c"
+                version = "0.1.0"
+            "#,
+        )
+        .file("c/src/lib.rs", "pub fn async() {}")
+        .build();
+
+    p.cargo("fix --to-edition 2021 --workspace --exclude c --allow-no-vcs -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_contains("[MIGRATING] a/src/lib.rs from 2015 edition to 2018")
+        .with_stderr_contains("[MIGRATING] b/src/lib.rs from 2018 edition to 2021")
+        .with_stderr_contains("[UPDATING] edition of `a` from 2015 to 2018")
+        .with_stderr_contains("[UPDATING] edition of `b` from 2018 to 2021")
+        .with_stderr_contains("[MIGRATING] a/src/lib.rs from 2018 edition to 2021")
+        .with_stderr_contains("[UPDATING] edition of `a` from 2018 to 2021")
+        .run();
+
+    let a = p.read_file("a/src/lib.rs");
+    assert!(a.contains("pub fn r#async() {}"));
+    assert!(a.contains("0..=100 => true,"));
+    assert!(p.read_file("a/Cargo.toml").contains(r#"edition = "2021""#));
+    assert!(p.read_file("b/src/lib.rs").contains("0..=100 => true,"));
+    assert!(p.read_file("b/Cargo.toml").contains(r#"edition = "2021""#));
+    assert_eq!(p.read_file("c/src/lib.rs"), "pub fn async() {}");
+    assert!(!p.read_file("c/Cargo.toml").contains("edition"));
+    p.cargo("check").run();
+}
+
+#[cargo_test]
+fn to_edition_inherited() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["a", "b"]
+
+                [workspace.package]
+                edition = "2018"
+            "#,
+        )
+        .file(
+            "a/Cargo.toml",
+            r#"
+                [package]
+                name = "a"
+                version = "0.1.0"
+                edition.workspace = true
+            "#,
+        )
+        .file("a/src/lib.rs", "")
+        .file(
+            "b/Cargo.toml",
+            r#"
+                [package]
+                name = "b"
+                version = "0.1.0"
+                edition.workspace = true
+            "#,
+        )
+        .file("b/src/lib.rs", "")
+        .build();
+
+    p.cargo("fix --to-edition 2021 -p a --allow-no-vcs -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] `a` inherits `workspace.package.edition` along with `b`, \
+             select both packages to migrate their edition",
+        )
+        .run();
+
+    p.cargo("fix --to-edition 2021 --workspace --allow-no-vcs -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_contains("[UPDATING] edition of the workspace from 2018 to 2021")
+        .run();
+    assert!(p.read_file("Cargo.toml").contains(r#"edition = "2021""#));
+    assert!(p
+        .read_file("a/Cargo.toml")
+        .contains("edition.workspace = true"));
+}
+
+#[cargo_test]
+fn replace_to_patch() {
+    Package::new("bar", "0.1.0").publish();
+    Package::new("baz", "0.2.0").publish();
+    let git_baz = git::new("baz", |p| {
+        p.file("Cargo.toml", &basic_manifest("baz", "0.2.0"))
+            .file("src/lib.rs", "")
+    });
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                bar = "0.1"
+                baz = "0.2"
+
+                # Local fork of bar.
+                [replace]
+                # Until the fix is released.
+                "bar:0.1.0" = {{ path = "bar" }}
+                "https://github.com/rust-lang/crates.io-index#baz:0.2.0" = {{ git = '{}' }}
+                "#,
+                git_baz.url()
+            ),
+        )
+        .file("src/lib.rs", "")
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.1.0"))
+        .file("bar/src/lib.rs", "")
+        .build();
+
+    p.cargo("fix --replace-to-patch --allow-no-vcs -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr(
+            "\
+[MIGRATING] `[replace]` entry `bar:0.1.0` to `[patch.crates-io]`
+[MIGRATING] `[replace]` entry `https://github.com/rust-lang/crates.io-index#baz:0.2.0` to `[patch.crates-io]`
+",
+        )
+        .run();
+    assert_eq!(
+        p.read_file("Cargo.toml"),
+        format!(
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                bar = "0.1"
+                baz = "0.2"
+
+                # Local fork of bar.
+                [patch.crates-io]
+                # Until the fix is released.
+                bar = {{ path = "bar" }}
+                baz = {{ git = '{}' }}
+                "#,
+            git_baz.url()
+        )
+    );
+
+    p.cargo("check")
+        .with_stderr_contains("[CHECKING] bar v0.1.0 ([CWD]/bar)")
+        .with_stderr_contains("[CHECKING] baz v0.2.0 ([..]/baz#[..])")
+        .run();
+}
+
+#[cargo_test]
+fn replace_to_patch_untranslatable() {
+    Package::new("bar", "0.1.0").publish();
+    Package::new("bar", "0.2.0").publish();
+    Package::new("baz", "0.1.0").publish();
+
+    let manifest = r#"
+        [workspace]
+        members = ["a", "b"]
+
+        [replace]
+        "bar:0.1.0" = { path = "bar1" }
+        "bar:0.2.0" = { path = "bar2" }
+        "baz:0.1.0" = { registry-index = "https://example.com/index" }
+    "#;
+    let p = project()
+        .file("Cargo.toml", manifest)
+        .file("a/Cargo.toml", &basic_manifest("a", "0.1.0"))
+        .file("a/src/lib.rs", "")
+        .file("b/Cargo.toml", &basic_manifest("b", "0.1.0"))
+        .file("b/src/lib.rs", "")
+        .build();
+
+    p.cargo("fix --replace-to-patch --allow-no-vcs -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[WARNING] cannot migrate `[replace]` entry `bar:0.2.0`: \
+another entry already replaces `bar` from the same source
+[WARNING] cannot migrate `[replace]` entry `baz:0.1.0`: \
+`[patch]` only supports `git` and `path` replacements here
+[ERROR] `[replace]` was not migrated, since a manifest can't have both `[replace]` and `[patch]`; \
+migrate the entries above by hand
+",
+        )
+        .run();
+    assert_eq!(p.read_file("Cargo.toml"), manifest);
+}
+
+#[cargo_test]
+fn replace_to_patch_gated() {
+    let p = project().file("src/lib.rs", "").build();
+    p.cargo("fix --replace-to-patch --allow-no-vcs")
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] the `--replace-to-patch` flag is unstable, \
+             pass `-Z unstable-options` to enable it",
+        )
+        .run();
+}
+
 #[cargo_test]
 fn fix_shared_cross_workspace() {
     // Fixing a file that is shared between multiple packages in the same workspace.
@@ -2874,3 +2874,60 @@ fn use_mtime_cache_in_cargo_home() {
         )
         .run_expect_error();
 }
+
+#[cargo_test]
+fn long_paths_stay_fresh() {
+    // Deep enough for the paths of the build artifacts to be longer than the
+    // 260 characters paths are limited to on Windows, unless they are
+    // extended-length paths.
+    let deep = (0..8)
+        .map(|i| format!("directory-nested-{i}-levels-down"))
+        .collect::<Vec<_>>()
+        .join("/");
+    let p = project()
+        .at(format!("{deep}/foo"))
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                bar = { path = "bar" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.1.0"))
+        .file("bar/src/lib.rs", "")
+        .build();
+    let target_dir = p.root().join(&deep).join("target");
+    assert!(target_dir.to_str().unwrap().len() > 260);
+
+    p.cargo("build")
+        .env("CARGO_TARGET_DIR", &target_dir)
+        .with_stderr(
+            "\
+[COMPILING] bar v0.1.0 ([CWD]/bar)
+[COMPILING] foo v0.1.0 ([CWD])
+[FINISHED] dev [..]
+",
+        )
+        .run();
+    p.cargo("build")
+        .env("CARGO_TARGET_DIR", &target_dir)
+        .with_stderr("[FINISHED] dev [..]")
+        .run();
+
+    p.change_file("bar/src/lib.rs", "pub fn bar() {}");
+    p.cargo("build")
+        .env("CARGO_TARGET_DIR", &target_dir)
+        .with_stderr(
+            "\
+[COMPILING] bar v0.1.0 ([CWD]/bar)
+[COMPILING] foo v0.1.0 ([CWD])
+[FINISHED] dev [..]
+",
+        )
+        .run();
+}
@@ -228,3 +228,77 @@ fn duplicate_entries_in_lockfile() {
         )
         .run();
 }
+
+#[cfg(unix)]
+#[cargo_test]
+fn rewrite_keeps_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let p = project()
+        .file("src/main.rs", "fn main() {}")
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.0.1"))
+        .file("bar/src/lib.rs", "")
+        .build();
+
+    p.cargo("generate-lockfile").run();
+    let lock = p.root().join("Cargo.lock");
+    fs::set_permissions(&lock, fs::Permissions::from_mode(0o640)).unwrap();
+
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            authors = []
+            version = "0.0.1"
+
+            [dependencies.bar]
+            path = "bar"
+        "#,
+    );
+    p.cargo("generate-lockfile").run();
+    assert!(p.read_lockfile().contains("name = \"bar\""));
+    let mode = fs::metadata(&lock).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o640);
+    // The temporary file was renamed.
+    let leftovers: Vec<_> = fs::read_dir(p.root())
+        .unwrap()
+        .map(|e| e.unwrap().file_name())
+        .filter(|name| name.to_string_lossy().starts_with(".Cargo.lock."))
+        .collect();
+    assert!(leftovers.is_empty(), "{leftovers:?}");
+}
+
+#[cfg(unix)]
+#[cargo_test]
+fn rewrite_keeps_symlink() {
+    let p = project()
+        .file("src/main.rs", "fn main() {}")
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.0.1"))
+        .file("bar/src/lib.rs", "")
+        .build();
+
+    p.cargo("generate-lockfile").run();
+    let lock = p.root().join("Cargo.lock");
+    let shared = p.root().join("shared");
+    fs::create_dir(&shared).unwrap();
+    fs::rename(&lock, shared.join("Cargo.lock")).unwrap();
+    std::os::unix::fs::symlink("shared/Cargo.lock", &lock).unwrap();
+
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            authors = []
+            version = "0.0.1"
+
+            [dependencies.bar]
+            path = "bar"
+        "#,
+    );
+    p.cargo("generate-lockfile").run();
+    assert!(fs::symlink_metadata(&lock).unwrap().file_type().is_symlink());
+    let contents = fs::read_to_string(shared.join("Cargo.lock")).unwrap();
+    assert!(contents.contains("name = \"bar\""));
+}
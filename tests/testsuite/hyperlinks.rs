@@ -0,0 +1,102 @@
+//! Tests for `term.hyperlinks`.
+
+use cargo_test_support::registry::Package;
+use cargo_test_support::{basic_manifest, project};
+
+/// Formats `text` as a hyperlink to `url`.
+fn link(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{url}\x1b\\{text}\x1b]8;;\x1b\\")
+}
+
+#[cargo_test]
+fn statuses() {
+    Package::new("bar", "0.1.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+
+                [dependencies]
+                bar = "0.1"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check -Zhyperlinks")
+        .masquerade_as_nightly_cargo(&["hyperlinks"])
+        .env("CARGO_TERM_HYPERLINKS", "true")
+        .with_stderr(&format!(
+            "\
+[UPDATING] `dummy-registry` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v0.1.0 (registry `dummy-registry`)
+[CHECKING] {}
+[CHECKING] {}
+[FINISHED] [..]
+",
+            link("https://docs.rs/bar/0.1.0", "bar v0.1.0"),
+            link("file://[ROOT]/foo", "foo v0.0.1 ([CWD])"),
+        ))
+        .run();
+
+    // Hyperlinks are disabled by default, and need `-Zhyperlinks`.
+    p.cargo("clean").run();
+    p.cargo("check")
+        .env("CARGO_TERM_HYPERLINKS", "true")
+        .with_stderr(
+            "\
+[CHECKING] bar v0.1.0
+[CHECKING] foo v0.0.1 ([CWD])
+[FINISHED] [..]
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn errors() {
+    Package::new("bar", "0.1.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+
+                [dependencies]
+                bar = "0.2"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("baz/Cargo.toml", "[package]\nname = ")
+        .build();
+
+    p.cargo("check -Zhyperlinks")
+        .masquerade_as_nightly_cargo(&["hyperlinks"])
+        .env("CARGO_TERM_HYPERLINKS", "true")
+        .with_status(101)
+        .with_stderr_contains(&format!(
+            "[ERROR] failed to select a version for the requirement `{} = \"^0.2\"`",
+            link("https://docs.rs/bar", "bar")
+        ))
+        .run();
+
+    p.change_file("Cargo.toml", &basic_manifest("foo", "0.0.1"));
+    p.cargo("check -Zhyperlinks --manifest-path baz/Cargo.toml")
+        .masquerade_as_nightly_cargo(&["hyperlinks"])
+        .env("CARGO_TERM_HYPERLINKS", "true")
+        .with_status(101)
+        .with_stderr_contains(&format!(
+            "[ERROR] failed to parse manifest at `{}`",
+            link(
+                "file://[ROOT]/foo/baz/Cargo.toml",
+                "[ROOT]/foo/baz/Cargo.toml"
+            )
+        ))
+        .run();
+}
@@ -0,0 +1,227 @@
+//! Tests for `-Zincremental-dir` and `cargo clean --incremental-only`.
+
+use cargo_test_support::{basic_manifest, project};
+
+#[cargo_test]
+fn build_incremental_dir() {
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            r#"
+                [build]
+                incremental-dir = "inc"
+            "#,
+        )
+        .build();
+
+    p.cargo("check -v -Zincremental-dir")
+        .env("CARGO_INCREMENTAL", "1")
+        .masquerade_as_nightly_cargo(&["incremental-dir"])
+        .with_stderr_contains("[RUNNING] `rustc [..] -C incremental=[CWD]/inc/debug [..]`")
+        .run();
+    assert!(p
+        .root()
+        .join("inc/debug")
+        .read_dir()
+        .unwrap()
+        .next()
+        .is_some());
+    assert!(!p.root().join("target/debug/incremental").exists());
+
+    // Ignored without `-Zincremental-dir`, without rebuilding.
+    p.cargo("check -v")
+        .env("CARGO_INCREMENTAL", "1")
+        .with_stderr(
+            "\
+[FRESH] foo v0.0.1 ([CWD])
+[FINISHED] [..]
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn relocating_is_fresh() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("check").env("CARGO_INCREMENTAL", "1").run();
+    p.cargo("check -v -Zincremental-dir")
+        .env("CARGO_INCREMENTAL", "1")
+        .masquerade_as_nightly_cargo(&["incremental-dir"])
+        .env("CARGO_BUILD_INCREMENTAL_DIR", p.root().join("inc"))
+        .with_stderr(
+            "\
+[FRESH] foo v0.0.1 ([CWD])
+[FINISHED] [..]
+",
+        )
+        .run();
+
+    // Dirty units are built with the new location.
+    p.change_file("src/lib.rs", "pub fn foo() {}");
+    p.cargo("check -v -Zincremental-dir")
+        .env("CARGO_INCREMENTAL", "1")
+        .masquerade_as_nightly_cargo(&["incremental-dir"])
+        .env("CARGO_BUILD_INCREMENTAL_DIR", p.root().join("inc"))
+        .with_stderr_contains("[RUNNING] `rustc [..] -C incremental=[CWD]/inc/debug [..]`")
+        .run();
+}
+
+#[cargo_test]
+fn profile_incremental_dir() {
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            r#"
+                [build]
+                incremental-dir = "inc"
+
+                [profile.dev]
+                incremental-dir = "dev-inc"
+
+                [profile.custom]
+                inherits = "dev"
+                incremental-dir = "custom-inc"
+            "#,
+        )
+        .build();
+
+    // `test` inherits the directory of `dev`.
+    p.cargo("test --no-run -v -Zincremental-dir")
+        .env("CARGO_INCREMENTAL", "1")
+        .masquerade_as_nightly_cargo(&["incremental-dir"])
+        .with_stderr_contains("[RUNNING] `rustc [..] -C incremental=[CWD]/dev-inc/debug [..]`")
+        .run();
+    p.cargo("check --release -v -Zincremental-dir")
+        .env("CARGO_INCREMENTAL", "1")
+        .masquerade_as_nightly_cargo(&["incremental-dir"])
+        .with_stderr_contains("[RUNNING] `rustc [..] -C incremental=[CWD]/inc/release [..]`")
+        .run();
+    p.cargo("check --profile custom -v -Zincremental-dir")
+        .env("CARGO_INCREMENTAL", "1")
+        .masquerade_as_nightly_cargo(&["incremental-dir"])
+        .with_stderr_contains("[RUNNING] `rustc [..] -C incremental=[CWD]/custom-inc/custom [..]`")
+        .run();
+}
+
+#[cargo_test]
+fn clean_incremental_only() {
+    let p = project()
+        .file("Cargo.toml", &basic_manifest("foo", "0.0.1"))
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build").env("CARGO_INCREMENTAL", "1").run();
+    assert!(p.root().join("target/debug/incremental").is_dir());
+
+    p.cargo("clean --incremental-only -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .run();
+    assert!(!p.root().join("target/debug/incremental").exists());
+    assert!(p.bin("foo").is_file());
+
+    // The artifacts are still fresh.
+    p.cargo("build -v")
+        .env("CARGO_INCREMENTAL", "1")
+        .with_stderr(
+            "\
+[FRESH] foo v0.0.1 ([CWD])
+[FINISHED] [..]
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn clean_incremental_only_relocated() {
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            r#"
+                [build]
+                incremental-dir = "inc"
+            "#,
+        )
+        .build();
+
+    p.cargo("check -Zincremental-dir")
+        .env("CARGO_INCREMENTAL", "1")
+        .masquerade_as_nightly_cargo(&["incremental-dir"])
+        .run();
+    p.cargo("check --release -Zincremental-dir")
+        .env("CARGO_INCREMENTAL", "1")
+        .masquerade_as_nightly_cargo(&["incremental-dir"])
+        .run();
+
+    p.cargo("clean --incremental-only --release -Zunstable-options -Zincremental-dir")
+        .masquerade_as_nightly_cargo(&["unstable-options", "incremental-dir"])
+        .run();
+    assert!(!p.root().join("inc/release").exists());
+    assert!(p.root().join("inc/debug").is_dir());
+    assert!(p.root().join("target/release").is_dir());
+}
+
+#[cargo_test]
+fn clean_incremental_only_requires_unstable_options() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("clean --incremental-only")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] the `--incremental-only` flag is unstable, pass `-Z unstable-options` to enable it
+",
+        )
+        .run();
+
+    p.cargo("clean --incremental-only --doc -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(1)
+        .with_stderr_contains(
+            "[ERROR] the argument '--incremental-only' cannot be used with '--doc'",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn clean_relocated() {
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            r#"
+                [build]
+                incremental-dir = "inc"
+
+                [profile.release]
+                incremental-dir = "inc-release"
+            "#,
+        )
+        .build();
+
+    p.cargo("check -Zincremental-dir")
+        .env("CARGO_INCREMENTAL", "1")
+        .masquerade_as_nightly_cargo(&["incremental-dir"])
+        .run();
+    p.cargo("check --release -Zincremental-dir")
+        .env("CARGO_INCREMENTAL", "1")
+        .masquerade_as_nightly_cargo(&["incremental-dir"])
+        .run();
+    assert!(p.root().join("inc/debug").is_dir());
+    assert!(p.root().join("inc-release/release").is_dir());
+
+    p.cargo("clean --release -Zincremental-dir")
+        .masquerade_as_nightly_cargo(&["incremental-dir"])
+        .run();
+    assert!(!p.root().join("inc-release/release").exists());
+    assert!(p.root().join("inc/debug").is_dir());
+
+    p.cargo("clean -Zincremental-dir")
+        .masquerade_as_nightly_cargo(&["incremental-dir"])
+        .run();
+    assert!(!p.root().join("inc").exists());
+    assert!(!p.target_debug_dir().exists());
+}
@@ -0,0 +1,126 @@
+//! Tests for `-Zindex-snapshots`.
+
+use cargo_test_support::registry::{self, Package, RegistryBuilder};
+use cargo_test_support::{project, Project};
+
+fn make_project() -> Project {
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+
+                [dependencies]
+                bar = { version = "0.1", registry = "alternative" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build()
+}
+
+#[cargo_test]
+fn pinned_to_commit() {
+    let registry = registry::alt_init();
+    Package::new("bar", "0.1.0").alternative(true).publish();
+    let index = git2::Repository::open(registry.index_url().to_file_path().unwrap()).unwrap();
+    let snapshot = index.head().unwrap().target().unwrap().to_string();
+    Package::new("bar", "0.1.1").alternative(true).publish();
+
+    let p = make_project();
+    p.cargo("generate-lockfile -Zindex-snapshots")
+        .masquerade_as_nightly_cargo(&["index-snapshots"])
+        .env("CARGO_REGISTRIES_ALTERNATIVE_SNAPSHOT", &snapshot)
+        .with_stderr("[UPDATING] `alternative` index")
+        .run();
+    p.cargo("tree")
+        .with_stdout(
+            "\
+foo v0.0.1 ([CWD])
+└── bar v0.1.0 (registry `alternative`)
+",
+        )
+        .run();
+
+    // Without the flag the snapshot is ignored.
+    p.cargo("update")
+        .env("CARGO_REGISTRIES_ALTERNATIVE_SNAPSHOT", &snapshot)
+        .with_stderr(
+            "\
+[UPDATING] `alternative` index
+[UPDATING] bar v0.1.0 (registry `alternative`) -> v0.1.1
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn unknown_commit() {
+    registry::alt_init();
+    Package::new("bar", "0.1.0").alternative(true).publish();
+
+    let p = make_project();
+    p.cargo("generate-lockfile -Zindex-snapshots")
+        .masquerade_as_nightly_cargo(&["index-snapshots"])
+        .env(
+            "CARGO_REGISTRIES_ALTERNATIVE_SNAPSHOT",
+            "0123456789abcdef0123456789abcdef01234567",
+        )
+        .with_status(101)
+        .with_stderr_contains(
+            "  failed to find the snapshot `0123456789abcdef0123456789abcdef01234567` \
+in the index of registry `alternative`",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn pinned_to_date() {
+    registry::alt_init();
+    Package::new("bar", "0.1.0").alternative(true).publish();
+
+    let p = make_project();
+    p.cargo("generate-lockfile -Zindex-snapshots")
+        .masquerade_as_nightly_cargo(&["index-snapshots"])
+        .env("CARGO_REGISTRIES_ALTERNATIVE_SNAPSHOT", "2000-01-01")
+        .with_status(101)
+        .with_stderr_contains(
+            "  the index of registry `alternative` has no commit made before \
+the snapshot `2000-01-01`",
+        )
+        .run();
+
+    p.cargo("generate-lockfile -Zindex-snapshots")
+        .masquerade_as_nightly_cargo(&["index-snapshots"])
+        .env("CARGO_REGISTRIES_ALTERNATIVE_SNAPSHOT", "9999-01-01")
+        .with_status(101)
+        .with_stderr_contains(
+            "  the snapshot `9999-01-01` of the index of registry `alternative` is in the future",
+        )
+        .run();
+
+    p.cargo("generate-lockfile -Zindex-snapshots")
+        .masquerade_as_nightly_cargo(&["index-snapshots"])
+        .env("CARGO_REGISTRIES_ALTERNATIVE_SNAPSHOT", "2000-13-01")
+        .with_status(101)
+        .with_stderr_contains("  invalid date in index snapshot `2000-13-01`")
+        .run();
+}
+
+#[cargo_test]
+fn sparse_unsupported() {
+    let _registry = RegistryBuilder::new().http_index().alternative().build();
+    Package::new("bar", "0.1.0").alternative(true).publish();
+
+    let p = make_project();
+    p.cargo("generate-lockfile -Zindex-snapshots")
+        .masquerade_as_nightly_cargo(&["index-snapshots"])
+        .env("CARGO_REGISTRIES_ALTERNATIVE_SNAPSHOT", "2000-01-01")
+        .with_status(101)
+        .with_stderr_contains(
+            "  snapshots of the index of registry `alternative` aren't supported, \
+as it uses the sparse protocol",
+        )
+        .run();
+}
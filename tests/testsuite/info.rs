@@ -0,0 +1,165 @@
+//! Tests for the `cargo info` command.
+
+use cargo_test_support::registry::{self, Package};
+use cargo_test_support::{cargo_process, project};
+
+fn publish_foo() {
+    Package::new("foo", "0.1.0").publish();
+    Package::new("foo", "0.2.0")
+        .feature("default", &["std"])
+        .feature("std", &[])
+        .feature("serde", &["dep:serde"])
+        .dep("bar", "1.0")
+        .add_dep(registry::Dependency::new("serde", "1.0").optional(true))
+        .dev_dep("baz", "1.0")
+        .rust_version("1.60")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.2.0"
+                description = "A foo"
+                keywords = ["foo", "bar"]
+                license = "MIT"
+                repository = "https://example.com/foo"
+                rust-version = "1.60"
+
+                [dependencies]
+                bar = "1.0"
+                serde = { version = "1.0", optional = true }
+
+                [dev-dependencies]
+                baz = "1.0"
+
+                [features]
+                default = ["std"]
+                # Use the standard library.
+                std = []
+                serde = ["dep:serde"] # Implement `Serialize`.
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .publish();
+}
+
+#[cargo_test]
+fn registry_package() {
+    registry::init();
+    publish_foo();
+
+    cargo_process("info foo -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout(
+            "\
+foo #foo #bar
+A foo
+version: 0.2.0
+versions: 0.2.0, 0.1.0
+license: MIT
+rust-version: 1.60
+repository: https://example.com/foo
+features:
+ +default = [std]
+  serde   = [dep:serde]  # Implement `Serialize`.
+ +std     = []  # Use the standard library.
+dependencies:
+  bar@^1.0
+  serde@^1.0 (optional)
+",
+        )
+        .with_stderr(
+            "\
+[UPDATING] `dummy-registry` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] foo v0.2.0 (registry `dummy-registry`)
+",
+        )
+        .run();
+
+    cargo_process("info foo@0.1 -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout(
+            "\
+foo
+version: 0.1.0 (latest 0.2.0)
+versions: 0.2.0, 0.1.0
+",
+        )
+        .run();
+
+    cargo_process("info foo@0.3 -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_contains(
+            "[ERROR] could not find `foo` in registry `crates-io` with version `0.3`",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn offline() {
+    registry::init();
+    publish_foo();
+
+    cargo_process("info foo@0.2 -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .run();
+
+    cargo_process("info foo@0.2 --offline -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_contains("A foo")
+        .with_stderr("")
+        .run();
+
+    // Only versions that were downloaded before are available.
+    cargo_process("info foo@0.1 --offline -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr("[ERROR] could not find `foo` in registry `crates-io` with version `0.1`")
+        .run();
+}
+
+#[cargo_test]
+fn workspace_member() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                description = "A local foo"
+
+                [features]
+                # Extra things.
+                extra = []
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("info foo -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout(
+            "\
+foo
+A local foo
+version: 0.1.0
+features:
+  extra = []  # Extra things.
+",
+        )
+        .with_stderr("")
+        .run();
+}
+
+#[cargo_test]
+fn requires_unstable_options() {
+    cargo_process("info foo")
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] the `cargo info` command is unstable, pass `-Z unstable-options` to enable it",
+        )
+        .run();
+}
@@ -1054,6 +1054,107 @@ foo v0.0.1:
         .run();
 }
 
+#[cargo_test]
+fn list_verbose_custom_root() {
+    let publish_foo = |vers: &str| {
+        Package::new("foo", vers)
+            .file(
+                "Cargo.toml",
+                &format!(
+                    r#"
+                        [package]
+                        name = "foo"
+                        version = "{vers}"
+
+                        [features]
+                        extra = []
+                    "#
+                ),
+            )
+            .file("src/main.rs", "fn main() {}")
+            .publish();
+    };
+    publish_foo("0.0.1");
+    pkg("bar", "0.2.1");
+    let root = paths::root().join("project-bin");
+
+    cargo_process("install foo --features extra --debug --root")
+        .arg(&root)
+        .run();
+    cargo_process("install bar").run();
+
+    cargo_process("install --list --verbose --root")
+        .arg(&root)
+        .with_stdout(
+            "\
+foo v0.0.1:
+    foo[EXE]
+",
+        )
+        .run();
+    cargo_process("install --list --verbose -Zinstall-settings --root")
+        .arg(&root)
+        .masquerade_as_nightly_cargo(&["install-settings"])
+        .with_stdout(format!(
+            "\
+foo v0.0.1:
+    foo[EXE]
+    source: registry `crates-io`
+    features: extra
+    profile: dev
+    target: {host}
+    rustc: rustc [..]
+",
+            host = cargo_test_support::rustc_host(),
+        ))
+        .run();
+    cargo_process("install --list")
+        .with_stdout(
+            "\
+bar v0.2.1:
+    bar[EXE]
+",
+        )
+        .run();
+
+    // Each root is upgraded on its own.
+    publish_foo("0.0.2");
+    cargo_process("install foo").run();
+    cargo_process("install foo --features extra --debug --root")
+        .arg(&root)
+        .with_stderr(
+            "\
+[UPDATING] `[..]` index
+[INSTALLING] foo v0.0.2
+[COMPILING] foo v0.0.2
+[FINISHED] dev [unoptimized + debuginfo] target(s) in [..]
+[REPLACING] [ROOT]/project-bin/bin/foo[EXE]
+[REPLACED] package `foo v0.0.1` with `foo v0.0.2` (executable `foo[EXE]`)
+[WARNING] be sure to add [..]
+",
+        )
+        .run();
+    cargo_process("install --list --root")
+        .arg(&root)
+        .with_stdout(
+            "\
+foo v0.0.2:
+    foo[EXE]
+",
+        )
+        .run();
+    cargo_process("install --list")
+        .with_stdout(
+            "\
+bar v0.2.1:
+    bar[EXE]
+foo v0.0.2:
+    foo[EXE]
+",
+        )
+        .run();
+}
+
 #[cargo_test]
 fn list_error() {
     pkg("foo", "0.0.1");
@@ -1685,6 +1786,106 @@ error: some packages failed to uninstall
     assert_has_not_installed_exe(cargo_home(), "bar");
 }
 
+#[cargo_test]
+fn uninstall_all() {
+    pkg("foo", "0.0.1");
+    pkg("bar", "0.0.1");
+    cargo_process("install foo bar").run();
+
+    cargo_process("uninstall --all")
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] the `--all` flag is unstable, pass `-Z unstable-options` to enable it",
+        )
+        .run();
+
+    cargo_process("uninstall --all --dry-run -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr(
+            "\
+Would uninstall [CWD]/home/.cargo/bin/bar[EXE]
+Would uninstall [CWD]/home/.cargo/bin/foo[EXE]
+[SUMMARY] Would uninstall bar, foo.
+[WARNING] aborting uninstall due to dry run
+",
+        )
+        .run();
+    assert_has_installed_exe(cargo_home(), "foo");
+    assert_has_installed_exe(cargo_home(), "bar");
+
+    cargo_process("uninstall --all -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr(
+            "\
+[REMOVING] [CWD]/home/.cargo/bin/bar[EXE]
+[REMOVING] [CWD]/home/.cargo/bin/foo[EXE]
+[SUMMARY] Successfully uninstalled bar, foo!
+",
+        )
+        .run();
+    assert_has_not_installed_exe(cargo_home(), "foo");
+    assert_has_not_installed_exe(cargo_home(), "bar");
+
+    cargo_process("uninstall --all -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr("[WARNING] no packages are installed")
+        .run();
+}
+
+#[cargo_test]
+fn uninstall_by_source() {
+    pkg("foo", "0.0.1");
+    cargo_process("install foo").run();
+    let git_project = git::new("gitbin", |p| {
+        p.file("Cargo.toml", &basic_manifest("gitbin", "0.1.0"))
+            .file("src/main.rs", "fn main() {}")
+    });
+    cargo_process("install --git")
+        .arg(git_project.url().to_string())
+        .run();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["a", "b"]
+            "#,
+        )
+        .file("a/Cargo.toml", &basic_manifest("a", "0.1.0"))
+        .file("a/src/main.rs", "fn main() {}")
+        .file("b/Cargo.toml", &basic_manifest("b", "0.1.0"))
+        .file("b/src/main.rs", "fn main() {}")
+        .build();
+    p.cargo("install --path a").run();
+    p.cargo("install --path b").run();
+
+    p.cargo("uninstall --path . -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr(
+            "\
+[REMOVING] [ROOT]/home/.cargo/bin/a[EXE]
+[REMOVING] [ROOT]/home/.cargo/bin/b[EXE]
+[SUMMARY] Successfully uninstalled a, b!
+",
+        )
+        .run();
+
+    cargo_process("uninstall -Zunstable-options --git")
+        .arg(format!("{}/", git_project.url()))
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr(
+            "\
+[REMOVING] [CWD]/home/.cargo/bin/gitbin[EXE]
+[SUMMARY] Successfully uninstalled gitbin!
+",
+        )
+        .run();
+    assert_has_not_installed_exe(cargo_home(), "a");
+    assert_has_not_installed_exe(cargo_home(), "b");
+    assert_has_not_installed_exe(cargo_home(), "gitbin");
+    assert_has_installed_exe(cargo_home(), "foo");
+}
+
 #[cargo_test]
 fn custom_target_dir_for_git_source() {
     let p = git::repo(&paths::root().join("foo"))
@@ -0,0 +1,208 @@
+//! Tests for `cargo install --from-binary-registry`.
+
+use std::fs;
+
+use cargo_test_support::install::{cargo_home, exe};
+use cargo_test_support::registry::{signature_public_key, Package, RegistryBuilder, TestRegistry};
+use cargo_test_support::{cargo_process, paths, rustc_host};
+
+const PREBUILT: &[u8] = b"prebuilt binary";
+const KEY: &str = "k3.secret.fNYVuMvBgOlljt9TDohnaYLblghqaHoQquVZwgR6X12cBFHZLFsaU3q7X3k1Zn36";
+const OTHER_KEY: &str =
+    "k3.secret.9Vxr5hVlI_g_orBZN54vPz20bmB4O76wB_MVqUSuJJJqHFLwP8kdn_RY5g6J6pQG";
+
+/// Publishes `foo`, with a prebuilt binary for `target` signed with `KEY`.
+fn publish_foo(target: &str) -> TestRegistry {
+    let registry = RegistryBuilder::new()
+        .bin_dl()
+        .signature_key(&signature_public_key(KEY))
+        .build();
+    Package::new("foo", "0.0.1")
+        .file("src/main.rs", "fn main() {}")
+        .prebuilt_bin(target, &exe("foo"), PREBUILT)
+        .sign(KEY)
+        .publish();
+    registry
+}
+
+fn installed_contents() -> Vec<u8> {
+    fs::read(cargo_home().join("bin").join(exe("foo"))).unwrap()
+}
+
+#[cargo_test]
+fn requires_unstable_options() {
+    let _registry = publish_foo(rustc_host());
+    cargo_process("install foo --from-binary-registry")
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] the `--from-binary-registry` flag is unstable, \
+             pass `-Z unstable-options` to enable it",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn installs_prebuilt() {
+    let _registry = publish_foo(rustc_host());
+    cargo_process("install foo --from-binary-registry -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr(&format!(
+            "\
+[UPDATING] `dummy-registry` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] foo v0.0.1 (registry `dummy-registry`)
+[INSTALLING] foo v0.0.1
+[DOWNLOADING] prebuilt binary `foo[EXE]` for `{}`
+[INSTALLING] [CWD]/home/.cargo/bin/foo[EXE]
+[INSTALLED] package `foo v0.0.1` (executable `foo[EXE]`)
+[WARNING] be sure to add `[..]` to your PATH to be able to run the installed binaries
+",
+            rustc_host()
+        ))
+        .run();
+    assert_eq!(installed_contents(), PREBUILT);
+
+    cargo_process("install foo --from-binary-registry -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr(
+            "\
+[UPDATING] `dummy-registry` index
+[IGNORED] package `foo v0.0.1` is already installed, use --force to override
+[WARNING] be sure to add `[..]` to your PATH to be able to run the installed binaries
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn ignored_without_flag() {
+    let _registry = publish_foo(rustc_host());
+    cargo_process("install foo")
+        .with_stderr_contains("[COMPILING] foo v0.0.1")
+        .run();
+    assert_ne!(installed_contents(), PREBUILT);
+}
+
+#[cargo_test]
+fn builds_without_prebuilt_for_target() {
+    let _registry = publish_foo("wasm32-unknown-unknown");
+    cargo_process("install foo --from-binary-registry -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_contains(&format!(
+            "[NOTE] building `foo v0.0.1` from source, the registry has no \
+             prebuilt binary `foo[EXE]` for `{}`",
+            rustc_host()
+        ))
+        .with_stderr_contains("[COMPILING] foo v0.0.1")
+        .run();
+    assert_ne!(installed_contents(), PREBUILT);
+}
+
+#[cargo_test]
+fn builds_with_other_features() {
+    let _registry = publish_foo(rustc_host());
+    cargo_process("install foo --from-binary-registry --no-default-features -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_contains(
+            "[NOTE] building `foo v0.0.1` from source, its prebuilt binaries are only \
+             available with the default features and the `release` profile",
+        )
+        .with_stderr_contains("[COMPILING] foo v0.0.1")
+        .run();
+    assert_ne!(installed_contents(), PREBUILT);
+}
+
+#[cargo_test]
+fn builds_with_other_profile() {
+    let _registry = publish_foo(rustc_host());
+    cargo_process("install foo --from-binary-registry --profile dev -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_contains(
+            "[NOTE] building `foo v0.0.1` from source, its prebuilt binaries are only \
+             available with the default features and the `release` profile",
+        )
+        .with_stderr_contains("[COMPILING] foo v0.0.1")
+        .run();
+    assert_ne!(installed_contents(), PREBUILT);
+}
+
+#[cargo_test]
+fn builds_without_signature_key() {
+    let _registry = RegistryBuilder::new().bin_dl().build();
+    Package::new("foo", "0.0.1")
+        .file("src/main.rs", "fn main() {}")
+        .prebuilt_bin(rustc_host(), &exe("foo"), PREBUILT)
+        .publish();
+    cargo_process("install foo --from-binary-registry -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_contains(
+            "[NOTE] not using the prebuilt binaries of `foo v0.0.1[..]`, \
+             registry `dummy-registry` has no signature key to verify them",
+        )
+        .with_stderr_contains("[COMPILING] foo v0.0.1")
+        .run();
+    assert_ne!(installed_contents(), PREBUILT);
+}
+
+#[cargo_test]
+fn unsigned() {
+    let _registry = RegistryBuilder::new()
+        .bin_dl()
+        .signature_key(&signature_public_key(KEY))
+        .build();
+    Package::new("foo", "0.0.1")
+        .file("src/main.rs", "fn main() {}")
+        .prebuilt_bin(rustc_host(), &exe("foo"), PREBUILT)
+        .publish();
+    cargo_process("install foo --from-binary-registry -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_contains(
+            "[ERROR] the prebuilt binaries of `foo v0.0.1[..]` aren't signed, \
+             but registry `dummy-registry` has a signature key",
+        )
+        .run();
+    assert!(!cargo_home().join("bin").join(exe("foo")).exists());
+}
+
+#[cargo_test]
+fn signed_with_other_key() {
+    let _registry = RegistryBuilder::new()
+        .bin_dl()
+        .signature_key(&signature_public_key(KEY))
+        .build();
+    Package::new("foo", "0.0.1")
+        .file("src/main.rs", "fn main() {}")
+        .prebuilt_bin(rustc_host(), &exe("foo"), PREBUILT)
+        .sign(OTHER_KEY)
+        .publish();
+    cargo_process("install foo --from-binary-registry -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_contains(
+            "[ERROR] failed to verify the signature of the prebuilt binaries of \
+             `foo v0.0.1 (registry `dummy-registry`)`",
+        )
+        .run();
+    assert!(!cargo_home().join("bin").join(exe("foo")).exists());
+}
+
+#[cargo_test]
+fn checksum_mismatch() {
+    let _registry = publish_foo(rustc_host());
+    let path = paths::root()
+        .join("dl/foo/0.0.1/bin")
+        .join(rustc_host())
+        .join(exe("foo"));
+    fs::write(path, "tampered").unwrap();
+
+    cargo_process("install foo --from-binary-registry -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_contains(
+            "[ERROR] failed to verify the checksum of the prebuilt binary `foo[EXE]` \
+             of `foo v0.0.1`",
+        )
+        .run();
+    assert!(!cargo_home().join("bin").join(exe("foo")).exists());
+}
@@ -860,3 +860,74 @@ fn partially_already_installed_does_one_update() {
         )
         .run();
 }
+
+#[cargo_test]
+fn repair_requires_unstable_options() {
+    cargo_process("install --repair")
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] the `--repair` flag is unstable, pass `-Z unstable-options` to enable it",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn repair_records_hashes() {
+    pkg("foo", "1.0.0");
+    cargo_process("install foo").run();
+    let data = load_crates2();
+    let info =
+        &data["installs"]["foo 1.0.0 (registry+https://github.com/rust-lang/crates.io-index)"];
+    let hash = &info["bin_hashes"][format!("foo{}", env::consts::EXE_SUFFIX)];
+    assert_eq!(
+        hash["size"].as_u64().unwrap(),
+        fs::metadata(installed_exe("foo")).unwrap().len()
+    );
+    assert_eq!(hash["sha256"].as_str().unwrap().len(), 64);
+
+    cargo_process("install --repair -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr("[CHECKED] 1 installed binaries, none were deleted or modified")
+        .run();
+}
+
+#[cargo_test]
+fn repair_deleted_and_modified() {
+    pkg("foo", "1.0.0");
+    pkg("bar", "1.0.0");
+    pkg("bar", "1.0.1");
+    cargo_process("install foo").run();
+    cargo_process("install bar --version=1.0.0").run();
+    fs::remove_file(installed_exe("foo")).unwrap();
+    fs::write(installed_exe("bar"), "not a binary").unwrap();
+
+    cargo_process("install --repair -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr(
+            "\
+[WARNING] binary `bar[EXE]` of package `bar v1.0.0` was modified
+[UPDATING] `[..]` index
+[INSTALLING] bar v1.0.0
+[COMPILING] bar v1.0.0
+[FINISHED] release [optimized] target(s) in [..]
+[REPLACING] [CWD]/home/.cargo/bin/bar[EXE]
+[REPLACED] package `bar v1.0.0` with `bar v1.0.0` (executable `bar[EXE]`)
+[WARNING] binary `foo[EXE]` of package `foo v1.0.0` was deleted
+[INSTALLING] foo v1.0.0
+[COMPILING] foo v1.0.0
+[FINISHED] release [optimized] target(s) in [..]
+[INSTALLING] [CWD]/home/.cargo/bin/foo[EXE]
+[INSTALLED] package `foo v1.0.0` (executable `foo[EXE]`)
+",
+        )
+        .run();
+    validate_trackers("foo", "1.0.0", &["foo"]);
+    validate_trackers("bar", "1.0.0", &["bar"]);
+
+    cargo_process("install --repair -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr("[CHECKED] 2 installed binaries, none were deleted or modified")
+        .run();
+    installed_process("foo").with_stdout("1.0.0").run();
+    installed_process("bar").with_stdout("1.0.0").run();
+}
@@ -6,7 +6,7 @@ use std::process::Command;
 use std::thread;
 
 use cargo_test_support::install::{assert_has_installed_exe, cargo_home};
-use cargo_test_support::{cargo_exe, project};
+use cargo_test_support::{basic_manifest, cargo_exe, project};
 
 const EXE_CONTENT: &str = r#"
 use std::env;
@@ -248,3 +248,104 @@ with an external jobserver in its environment, ignoring the `-j` parameter
         )
         .run();
 }
+
+#[cfg(unix)]
+#[cargo_test]
+fn jobserver_accounting_num_jobs() {
+    // The build script of `a` takes two tokens and never gives them back.
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+
+                [build-dependencies]
+                a = { path = "a" }
+            "#,
+        )
+        .file(
+            "build.rs",
+            r#"
+                fn main() {
+                    println!("cargo:warning=NUM_JOBS={}", std::env::var("NUM_JOBS").unwrap());
+                }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("a/Cargo.toml", &basic_manifest("a", "0.0.1"))
+        .file(
+            "a/build.rs",
+            r#"
+                use std::fs::File;
+                use std::io::Read;
+                use std::os::unix::prelude::*;
+
+                fn main() {
+                    let var = std::env::var("CARGO_MAKEFLAGS").unwrap();
+                    let arg = var.split(' ').find(|p| p.starts_with("--jobserver")).unwrap();
+                    let (r, _) = arg[arg.find('=').unwrap() + 1..].split_once(',').unwrap();
+                    let mut read = unsafe { File::from_raw_fd(r.parse().unwrap()) };
+                    read.read_exact(&mut [0; 2]).unwrap();
+                }
+            "#,
+        )
+        .file("a/src/lib.rs", "")
+        .build();
+
+    p.cargo("check -j4 -Zjobserver-accounting")
+        .masquerade_as_nightly_cargo(&["jobserver-accounting"])
+        .with_stderr_contains("[WARNING] NUM_JOBS=2")
+        .run();
+
+    // Without `-Zjobserver-accounting`, it is always `-j`.
+    p.cargo("clean").run();
+    p.cargo("check -j4")
+        .with_stderr_contains("[WARNING] NUM_JOBS=4")
+        .run();
+}
+
+#[cfg(unix)]
+#[cargo_test]
+fn jobserver_accounting_oversubscription() {
+    // Using more CPUs than `-j` needs several of them.
+    if thread::available_parallelism().map_or(true, |n| n.get() < 2) {
+        return;
+    }
+    let p = project()
+        .file(
+            "build.rs",
+            r#"
+                use std::time::{Duration, Instant};
+
+                fn main() {
+                    let threads: Vec<_> = (0..4)
+                        .map(|_| {
+                            std::thread::spawn(|| {
+                                let start = Instant::now();
+                                let mut n = 0u64;
+                                while start.elapsed() < Duration::from_secs(2) {
+                                    n = n.wrapping_add(1);
+                                }
+                                n
+                            })
+                        })
+                        .collect();
+                    for thread in threads {
+                        thread.join().unwrap();
+                    }
+                }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check -j1 -Zjobserver-accounting")
+        .masquerade_as_nightly_cargo(&["jobserver-accounting"])
+        .with_stderr_contains(
+            "[WARNING] the build script of `foo v0.0.1 ([CWD])` used [..] CPUs on average \
+             for [..]s, more than `-j1` allows",
+        )
+        .run();
+}
@@ -0,0 +1,148 @@
+//! Tests for the `-Zlayout-index` feature.
+
+use cargo_test_support::registry::Package;
+use cargo_test_support::{basic_manifest, project};
+use serde_json::Value;
+
+/// Returns the units in the index as `(pkg name, target kind, mode)` tuples.
+fn index_units(index: &Value) -> Vec<(String, String, String)> {
+    let mut units: Vec<_> = index["units"]
+        .as_object()
+        .unwrap()
+        .values()
+        .map(|unit| {
+            let pkg_id = unit["pkg_id"].as_str().unwrap();
+            (
+                pkg_id.split(' ').next().unwrap().to_string(),
+                unit["target_kind"].as_str().unwrap().to_string(),
+                unit["mode"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect();
+    units.sort();
+    units
+}
+
+#[cargo_test]
+fn not_written_by_default() {
+    let p = project().file("src/lib.rs", "").build();
+    p.cargo("build").run();
+    assert!(!p.target_debug_dir().join(".layout-index.json").exists());
+}
+
+#[cargo_test]
+fn simple() {
+    Package::new("bar", "1.0.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                bar = "1.0"
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build -Zlayout-index")
+        .masquerade_as_nightly_cargo(&["layout-index"])
+        .run();
+
+    let index: Value =
+        serde_json::from_str(&p.read_file("target/debug/.layout-index.json")).unwrap();
+    assert_eq!(index["version"], 1);
+    assert_eq!(
+        index_units(&index),
+        [
+            ("bar".to_string(), "lib".to_string(), "build".to_string()),
+            ("foo".to_string(), "bin".to_string(), "build".to_string()),
+        ]
+    );
+
+    for unit in index["units"].as_object().unwrap().values() {
+        assert_eq!(unit["platform"], Value::Null);
+        for output in unit["outputs"].as_array().unwrap() {
+            let path = output["path"].as_str().unwrap();
+            assert!(
+                path.starts_with(unit["out_dir"].as_str().unwrap()),
+                "{path} is outside of its out_dir"
+            );
+        }
+    }
+
+    let bin = index["units"]
+        .as_object()
+        .unwrap()
+        .values()
+        .find(|unit| unit["target_kind"] == "bin")
+        .unwrap();
+    let uplifted: Vec<_> = bin["outputs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|output| output["uplifted"].as_str())
+        .collect();
+    assert!(uplifted.contains(&p.bin("foo").to_str().unwrap()));
+}
+
+#[cargo_test]
+fn merges_with_previous_builds() {
+    let p = project()
+        .file("Cargo.toml", &basic_manifest("foo", "0.1.0"))
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("build -Zlayout-index")
+        .masquerade_as_nightly_cargo(&["layout-index"])
+        .run();
+    p.cargo("check -Zlayout-index")
+        .masquerade_as_nightly_cargo(&["layout-index"])
+        .run();
+
+    let index: Value =
+        serde_json::from_str(&p.read_file("target/debug/.layout-index.json")).unwrap();
+    assert_eq!(
+        index_units(&index),
+        [
+            ("foo".to_string(), "lib".to_string(), "build".to_string()),
+            ("foo".to_string(), "lib".to_string(), "check".to_string()),
+        ]
+    );
+}
+
+#[cargo_test]
+fn replaces_stale_entries() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [features]
+                f = []
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build -Zlayout-index")
+        .masquerade_as_nightly_cargo(&["layout-index"])
+        .run();
+    p.cargo("build -Zlayout-index --features f")
+        .masquerade_as_nightly_cargo(&["layout-index"])
+        .run();
+
+    // Both builds uplift to `target/debug/foo`, so only the latest one is kept.
+    let index: Value =
+        serde_json::from_str(&p.read_file("target/debug/.layout-index.json")).unwrap();
+    assert_eq!(
+        index_units(&index),
+        [("foo".to_string(), "bin".to_string(), "build".to_string())]
+    );
+}
@@ -0,0 +1,127 @@
+//! Tests for `cargo lockfile dedupe`.
+
+use cargo_test_support::registry::Package;
+use cargo_test_support::{project, Project};
+
+/// A package depending on `bar`, which accepts `baz` 0.1 or 0.2, with a lock
+/// file where `bar` still uses `baz 0.1.0` after `baz = "0.2"` was added.
+fn make_project(extra_deps: &str) -> Project {
+    Package::new("baz", "0.1.0").publish();
+    Package::new("bar", "0.1.0")
+        .dep("baz", ">=0.1, <0.3")
+        .publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                    [package]
+                    name = "foo"
+                    version = "0.1.0"
+
+                    [dependencies]
+                    bar = "0.1"
+                    {extra_deps}
+                "#
+            ),
+        )
+        .file("src/lib.rs", "")
+        .build();
+    p.cargo("generate-lockfile").run();
+
+    Package::new("baz", "0.2.0").publish();
+    p.change_file(
+        "Cargo.toml",
+        &format!(
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                bar = "0.1"
+                baz = "0.2"
+                {extra_deps}
+            "#
+        ),
+    );
+    p.cargo("metadata --format-version 1").run();
+    // A compatible update that dedupe must not pick up.
+    Package::new("baz", "0.1.1").publish();
+    p
+}
+
+#[cargo_test]
+fn gated() {
+    let p = make_project("");
+    p.cargo("lockfile dedupe")
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] the `cargo lockfile dedupe` command is unstable, \
+             pass `-Z unstable-options` to enable it",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn dedupe() {
+    let p = make_project("");
+    let lockfile = p.read_lockfile();
+    assert!(lockfile.contains("name = \"baz\"\nversion = \"0.1.0\""));
+    assert!(lockfile.contains("name = \"baz\"\nversion = \"0.2.0\""));
+
+    p.cargo("lockfile dedupe -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr(
+            "\
+[REMOVING] baz v0.1.0
+[WARNING] not updating lockfile, pass `--write` to apply the changes
+",
+        )
+        .run();
+    assert_eq!(p.read_lockfile(), lockfile);
+
+    p.cargo("lockfile dedupe --write -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr(
+            "\
+[REMOVING] baz v0.1.0
+",
+        )
+        .run();
+    let lockfile = p.read_lockfile();
+    assert!(!lockfile.contains("name = \"baz\"\nversion = \"0.1.0\""));
+    assert!(!lockfile.contains("name = \"baz\"\nversion = \"0.1.1\""));
+    assert!(lockfile.contains("name = \"baz\"\nversion = \"0.2.0\""));
+}
+
+#[cargo_test]
+fn keeps_required_versions() {
+    // `qux` only accepts `baz` 0.1, which stays at its locked version.
+    Package::new("qux", "0.1.0").dep("baz", "0.1").publish();
+    let p = make_project(r#"qux = "0.1""#);
+    let lockfile = p.read_lockfile();
+
+    p.cargo("lockfile dedupe --write -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr(
+            "\
+[NOTE] no duplicate packages can be removed from `Cargo.lock`
+",
+        )
+        .run();
+    assert_eq!(p.read_lockfile(), lockfile);
+}
+
+#[cargo_test]
+fn no_lockfile() {
+    let p = project().file("src/lib.rs", "").build();
+    p.cargo("lockfile dedupe -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] there is no `Cargo.lock` to deduplicate, \
+             run `cargo generate-lockfile` to create one",
+        )
+        .run();
+}
@@ -0,0 +1,139 @@
+//! Tests for the `-Zlockfile-overlay` feature.
+
+use cargo_test_support::project;
+use cargo_test_support::registry::Package;
+
+fn setup() -> (cargo_test_support::Project, String) {
+    Package::new("bar", "1.0.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                bar = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+    p.cargo("generate-lockfile").run();
+    let canonical = p.read_lockfile();
+
+    // Produce an overlay that pins the newer version, then restore the
+    // canonical lock file.
+    Package::new("bar", "1.0.1").publish();
+    p.cargo("update -p bar").run();
+    p.change_file("Cargo.lock.local", &p.read_lockfile());
+    p.change_file("Cargo.lock", &canonical);
+    (p, canonical)
+}
+
+#[cargo_test]
+fn overlay_overrides_locked_version() {
+    let (p, canonical) = setup();
+
+    p.cargo("check -Zlockfile-overlay")
+        .masquerade_as_nightly_cargo(&["lockfile-overlay"])
+        .with_stderr(
+            "\
+[WARNING] using lock file overlay `[CWD]/Cargo.lock.local`, `Cargo.lock` will not be updated
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v1.0.1 (registry `dummy-registry`)
+[CHECKING] bar v1.0.1
+[CHECKING] foo v0.1.0 ([CWD])
+[FINISHED] [..]
+",
+        )
+        .run();
+    assert_eq!(p.read_lockfile(), canonical);
+}
+
+#[cargo_test]
+fn overlay_ignored_without_flag() {
+    let (p, canonical) = setup();
+
+    p.cargo("check")
+        .with_stderr(
+            "\
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v1.0.0 (registry `dummy-registry`)
+[CHECKING] bar v1.0.0
+[CHECKING] foo v0.1.0 ([CWD])
+[FINISHED] [..]
+",
+        )
+        .run();
+    assert_eq!(p.read_lockfile(), canonical);
+}
+
+#[cargo_test]
+fn overlay_with_locked() {
+    let (p, canonical) = setup();
+
+    p.cargo("check -Zlockfile-overlay --locked")
+        .masquerade_as_nightly_cargo(&["lockfile-overlay"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[WARNING] using lock file overlay `[CWD]/Cargo.lock.local`, `Cargo.lock` will not be updated
+[ERROR] the lock file [CWD]/Cargo.lock needs to be updated but --locked was passed to prevent this
+If you want to try to generate the lock file without accessing the network, \
+remove the --locked flag and use --offline instead.
+",
+        )
+        .run();
+    assert_eq!(p.read_lockfile(), canonical);
+}
+
+#[cargo_test]
+fn overlay_only_replaces_named_packages() {
+    Package::new("baz", "1.0.0").publish();
+    let (p, canonical) = setup();
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            version = "0.1.0"
+
+            [dependencies]
+            bar = "1.0"
+            baz = "1.0"
+        "#,
+    );
+    p.cargo("generate-lockfile").run();
+    let canonical_with_baz = p.read_lockfile();
+    assert_ne!(canonical_with_baz, canonical);
+    Package::new("baz", "1.0.1").publish();
+
+    // Only `bar` is pinned by the overlay, `baz` keeps its locked version.
+    p.cargo("check -Zlockfile-overlay")
+        .masquerade_as_nightly_cargo(&["lockfile-overlay"])
+        .with_stderr_contains("[CHECKING] bar v1.0.1")
+        .with_stderr_contains("[CHECKING] baz v1.0.0")
+        .run();
+    assert_eq!(p.read_lockfile(), canonical_with_baz);
+}
+
+#[cargo_test]
+fn invalid_overlay() {
+    let (p, _canonical) = setup();
+    p.change_file("Cargo.lock.local", "[[package]]\nversion = \"1.0.1\"\n");
+
+    p.cargo("check -Zlockfile-overlay")
+        .masquerade_as_nightly_cargo(&["lockfile-overlay"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[WARNING] using lock file overlay `[CWD]/Cargo.lock.local`, `Cargo.lock` will not be updated
+[ERROR] failed to parse lock file at: [CWD]/Cargo.lock
+
+Caused by:
+  package in the lock file overlay is missing a `name`
+",
+        )
+        .run();
+}
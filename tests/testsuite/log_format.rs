@@ -0,0 +1,79 @@
+//! Tests for the traces displayed with `CARGO_LOG` and `CARGO_LOG_FORMAT`.
+
+use cargo_test_support::{basic_manifest, project};
+use serde_json::Value;
+
+/// The JSON traces printed on stderr.
+fn json_traces(stderr: &[u8]) -> Vec<Value> {
+    std::str::from_utf8(stderr)
+        .unwrap()
+        .lines()
+        .filter(|line| line.starts_with('{'))
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[cargo_test]
+fn json_spans() {
+    let p = project()
+        .file("Cargo.toml", &basic_manifest("foo", "0.1.0"))
+        .file("src/lib.rs", "")
+        .build();
+
+    let output = p
+        .cargo("build")
+        .env(
+            "CARGO_LOG",
+            "cargo::core::resolver=debug,cargo::core::compiler::job_queue=debug,cargo_util=debug",
+        )
+        .env("CARGO_LOG_FORMAT", "json")
+        .exec_with_output()
+        .unwrap();
+    let traces = json_traces(&output.stderr);
+
+    // Closing a span reports the time spent in it.
+    assert!(
+        traces.iter().any(|trace| trace["span"]["name"] == "resolve"
+            && trace["fields"]["message"] == "close"
+            && trace["fields"]["time.busy"].is_string()),
+        "{traces:#?}"
+    );
+    // Events of a job are attributed to its unit.
+    assert!(
+        traces.iter().any(|trace| {
+            let unit = &trace["span"];
+            unit["name"] == "unit"
+                && unit["package_id"]
+                    .as_str()
+                    .unwrap()
+                    .starts_with("foo v0.1.0")
+                && unit["target"] == "foo"
+                && unit["mode"] == "Build"
+        }),
+        "{traces:#?}"
+    );
+}
+
+#[cargo_test]
+fn text_by_default() {
+    let p = project()
+        .file("Cargo.toml", &basic_manifest("foo", "0.1.0"))
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("generate-lockfile")
+        .env("CARGO_LOG", "cargo::core::resolver=debug")
+        .with_stderr_contains(
+            "[..] DEBUG [..]resolve: cargo::core::resolver: initial activation: foo v0.1.0 ([CWD])",
+        )
+        .with_stderr_does_not_contain("{[..]")
+        .run();
+
+    p.cargo("generate-lockfile")
+        .env("CARGO_LOG", "warn")
+        .env("CARGO_LOG_FORMAT", "yaml")
+        .with_stderr_contains(
+            "[..] WARN cargo: unknown CARGO_LOG_FORMAT `yaml`, expected `text` or `json`",
+        )
+        .run();
+}
@@ -2,10 +2,11 @@
 
 use cargo_test_support::cargo_process;
 use cargo_test_support::paths::{self, CargoPathExt};
-use cargo_test_support::registry::{self, RegistryBuilder};
-use cargo_test_support::t;
+use cargo_test_support::registry::{self, RegistryBuilder, Response};
+use cargo_test_support::{t, tools};
 use std::fs;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 const TOKEN: &str = "test-token";
 const TOKEN2: &str = "test-token2";
@@ -304,3 +305,75 @@ fn default_registry_configured() {
     check_token(None, None);
     check_token(Some("a-new-token"), Some("alternative"));
 }
+
+fn device_code(_: &registry::Request, _: &registry::HttpServer) -> Response {
+    Response {
+        code: 200,
+        headers: vec![],
+        body: br#"{"device_code":"device-code","user_code":"ABCD-EFGH","verification_uri":"https://example.com/device","expires_in":60,"interval":0}"#.to_vec(),
+    }
+}
+
+#[cargo_test]
+fn device_login() {
+    static POLLS: AtomicUsize = AtomicUsize::new(0);
+    let _registry = RegistryBuilder::new()
+        .alternative()
+        .http_api()
+        .device_authorization()
+        .add_responder("/oauth/device/code", device_code)
+        .add_responder("/oauth/token", |req, _| {
+            let body = String::from_utf8(req.body.clone().unwrap()).unwrap();
+            assert!(body.contains("device_code=device-code"), "{body}");
+            if POLLS.fetch_add(1, Ordering::SeqCst) == 0 {
+                Response {
+                    code: 400,
+                    headers: vec![],
+                    body: br#"{"error":"authorization_pending"}"#.to_vec(),
+                }
+            } else {
+                Response {
+                    code: 200,
+                    headers: vec![],
+                    body: br#"{"access_token":"device-token","token_type":"bearer"}"#.to_vec(),
+                }
+            }
+        })
+        .build();
+
+    cargo_process("login -Zdevice-login --registry alternative")
+        .masquerade_as_nightly_cargo(&["device-login"])
+        .env("BROWSER", tools::echo())
+        .with_stdout("https://example.com/device")
+        .with_stderr(
+            "\
+[UPDATING] `alternative` index
+[NOTE] to log in to registry `alternative`, open https://example.com/device and enter the code `ABCD-EFGH`
+[LOGIN] token for `alternative` saved
+",
+        )
+        .run();
+    check_token(Some("device-token"), Some("alternative"));
+}
+
+#[cargo_test]
+fn device_login_denied() {
+    let _registry = RegistryBuilder::new()
+        .alternative()
+        .http_api()
+        .device_authorization()
+        .add_responder("/oauth/device/code", device_code)
+        .add_responder("/oauth/token", |_, _| Response {
+            code: 400,
+            headers: vec![],
+            body: br#"{"error":"access_denied"}"#.to_vec(),
+        })
+        .build();
+
+    cargo_process("login -Zdevice-login --registry alternative")
+        .masquerade_as_nightly_cargo(&["device-login"])
+        .env("BROWSER", tools::echo())
+        .with_status(101)
+        .with_stderr_contains("[ERROR] the login to registry `alternative` was denied")
+        .run();
+}
@@ -5,9 +5,14 @@
 #[macro_use]
 extern crate cargo_test_macro;
 
+mod adaptive_jobs;
 mod advanced_env;
 mod alt_registry;
+mod analyze_msrv;
+mod analyze_unused_deps;
+mod artifact_cache;
 mod artifact_dep;
+mod artifact_link;
 mod bad_config;
 mod bad_manifest_path;
 mod bench;
@@ -17,15 +22,21 @@ mod build_plan;
 mod build_script;
 mod build_script_env;
 mod build_script_extra_link_arg;
+mod bundle;
 mod cache_messages;
+mod cache_overlay;
+mod cancellation;
 mod cargo;
 mod cargo_add;
 mod cargo_alias_config;
+mod cargo_analyze;
 mod cargo_bench;
 mod cargo_build;
+mod cargo_bundle;
 mod cargo_check;
 mod cargo_clean;
 mod cargo_command;
+mod cargo_completions;
 mod cargo_config;
 mod cargo_doc;
 mod cargo_env_config;
@@ -35,9 +46,11 @@ mod cargo_fix;
 mod cargo_generate_lockfile;
 mod cargo_git_checkout;
 mod cargo_help;
+mod cargo_info;
 mod cargo_init;
 mod cargo_install;
 mod cargo_locate_project;
+mod cargo_lockfile;
 mod cargo_login;
 mod cargo_logout;
 mod cargo_metadata;
@@ -61,33 +74,43 @@ mod cargo_update;
 mod cargo_vendor;
 mod cargo_verify_project;
 mod cargo_version;
+mod cargo_why_version;
 mod cargo_yank;
 mod cfg;
 mod check;
+mod check_build_deps;
 mod check_cfg;
 mod clean;
+mod client_certs;
 mod collisions;
+mod completions;
 mod concurrent;
 mod config;
 mod config_cli;
 mod config_include;
 mod corrupt_git;
+mod crate_signatures;
 mod credential_process;
 mod cross_compile;
 mod cross_publish;
 mod custom_target;
 mod death;
 mod dep_info;
+mod dependency_policy;
 mod direct_minimal_versions;
 mod directory;
 mod doc;
 mod docscrape;
 mod edition;
+mod emit_env;
 mod error;
+mod error_kind;
+mod explain_config;
 mod features;
 mod features2;
 mod features_namespaced;
 mod fetch;
+mod file_overlay;
 mod fix;
 mod freshness;
 mod future_incompat_report;
@@ -99,20 +122,30 @@ mod git_shallow;
 mod glob_targets;
 mod help;
 mod https;
+mod hyperlinks;
+mod incremental_dir;
+mod index_snapshots;
+mod info;
 mod inheritable_workspace_fields;
 mod install;
+mod install_binary_registry;
 mod install_upgrade;
 mod jobserver;
+mod layout_index;
 mod lints;
 mod list_availables;
 mod local_registry;
 mod locate_project;
 mod lockfile_compat;
+mod lockfile_dedupe;
+mod lockfile_overlay;
+mod log_format;
 mod login;
 mod logout;
 mod lto;
 mod member_discovery;
 mod member_errors;
+mod message_catalogs;
 mod message_format;
 mod messages;
 mod metabuild;
@@ -120,10 +153,12 @@ mod metadata;
 mod minimal_versions;
 mod multitarget;
 mod net_config;
+mod network_report;
 mod new;
 mod offline;
 mod old_cargos;
 mod out_dir;
+mod output_width;
 mod owner;
 mod package;
 mod package_features;
@@ -131,24 +166,34 @@ mod patch;
 mod path;
 mod paths;
 mod pkgid;
+mod plugin_manifest;
 mod plugins;
+mod prefer_fast_linker;
 mod proc_macro;
+mod profile_auto_tune;
 mod profile_config;
 mod profile_custom;
 mod profile_overrides;
 mod profile_targets;
 mod profiles;
 mod progress;
+mod proxy_config;
 mod pub_priv;
 mod publish;
 mod publish_lockfile;
+mod publish_semver_check;
 mod read_manifest;
 mod registry;
 mod registry_auth;
 mod rename_deps;
 mod replace;
+mod report_native_deps;
+mod report_notices;
+mod report_sizes;
 mod required_features;
+mod resolver_explain;
 mod run;
+mod run_workdir;
 mod rust_version;
 mod rustc;
 mod rustc_info_cache;
@@ -159,6 +204,7 @@ mod rustflags;
 mod rustup;
 mod script;
 mod search;
+mod self_profile;
 mod shell_quoting;
 mod source_replacement;
 mod ssh;
@@ -168,13 +214,17 @@ mod timings;
 mod tool_paths;
 mod tree;
 mod tree_graph_features;
+mod trusted_publishing;
 mod unit_graph;
 mod update;
 mod vendor;
 mod verify_project;
 mod version;
+mod version_bump;
 mod warn_on_failure;
 mod weak_dep_features;
+mod why_version;
+mod workspace_builder;
 mod workspaces;
 mod yank;
 
@@ -0,0 +1,157 @@
+//! Tests for `-Zmessage-catalogs`.
+
+use cargo_test_support::paths::{self, CargoPathExt};
+use cargo_test_support::{basic_manifest, project, Project};
+
+fn write_catalog(name: &str, contents: &str) {
+    let dir = paths::home().join(".cargo/catalogs");
+    dir.mkdir_p();
+    std::fs::write(dir.join(name), contents).unwrap();
+}
+
+fn make_project() -> Project {
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                bar = { path = "bar" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.1.0"))
+        .file("bar/src/lib.rs", "")
+        .build()
+}
+
+const FRENCH: &str = r#"
+    [status]
+    Removing = "Suppression"
+    warning = "avertissement"
+
+    [messages]
+    "{dep} from {section}" = "{dep} de {section}"
+    "aborting remove due to dry run" = "suppression annulée, simulation uniquement"
+"#;
+
+#[cargo_test]
+fn translated_messages() {
+    write_catalog("fr.toml", FRENCH);
+    let p = make_project();
+
+    p.cargo("remove bar --dry-run -Zmessage-catalogs")
+        .masquerade_as_nightly_cargo(&["message-catalogs"])
+        .env("CARGO_TERM_LANGUAGE", "fr")
+        .with_stderr(
+            "\
+[..]Suppression bar de dependencies
+avertissement: suppression annulée, simulation uniquement
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn untranslated_messages_are_english() {
+    write_catalog("fr.toml", "[status]\nRemoving = \"Suppression\"\n");
+    let p = make_project();
+
+    p.cargo("remove bar --dry-run -Zmessage-catalogs")
+        .masquerade_as_nightly_cargo(&["message-catalogs"])
+        .env("CARGO_TERM_LANGUAGE", "fr")
+        .with_stderr(
+            "\
+[..]Suppression bar from dependencies
+[WARNING] aborting remove due to dry run
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn region_falls_back_to_language() {
+    write_catalog("fr.toml", FRENCH);
+    let p = make_project();
+
+    p.cargo("remove bar --dry-run -Zmessage-catalogs")
+        .masquerade_as_nightly_cargo(&["message-catalogs"])
+        .env("CARGO_TERM_LANGUAGE", "fr-CA")
+        .with_stderr_contains(" Suppression bar de dependencies")
+        .run();
+}
+
+#[cargo_test]
+fn english_and_gated() {
+    write_catalog("fr.toml", FRENCH);
+    let p = make_project();
+
+    p.cargo("remove bar --dry-run -Zmessage-catalogs")
+        .masquerade_as_nightly_cargo(&["message-catalogs"])
+        .env("CARGO_TERM_LANGUAGE", "en-US")
+        .with_stderr(
+            "\
+[REMOVING] bar from dependencies
+[WARNING] aborting remove due to dry run
+",
+        )
+        .run();
+
+    p.cargo("remove bar --dry-run")
+        .env("CARGO_TERM_LANGUAGE", "fr")
+        .with_stderr(
+            "\
+[REMOVING] bar from dependencies
+[WARNING] aborting remove due to dry run
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn missing_catalog() {
+    let p = make_project();
+
+    p.cargo("remove bar --dry-run -Zmessage-catalogs")
+        .masquerade_as_nightly_cargo(&["message-catalogs"])
+        .env("CARGO_TERM_LANGUAGE", "de")
+        .with_stderr(
+            "\
+[WARNING] messages are displayed in English
+
+no message catalog found for language `de` in `[ROOT]/home/.cargo/catalogs` or `[..]catalogs`
+[REMOVING] bar from dependencies
+[WARNING] aborting remove due to dry run
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn unknown_placeholder() {
+    write_catalog(
+        "fr.toml",
+        "[messages]\n\"{dep} from {section}\" = \"{dep} de {table}\"\n",
+    );
+    let p = make_project();
+
+    p.cargo("remove bar --dry-run -Zmessage-catalogs")
+        .masquerade_as_nightly_cargo(&["message-catalogs"])
+        .env("CARGO_TERM_LANGUAGE", "fr")
+        .with_stderr(
+            "\
+[WARNING] messages are displayed in English
+
+failed to load `[ROOT]/home/.cargo/catalogs/fr.toml`
+
+Caused by:
+  translation of message \"{dep} from {section}\" uses unknown placeholder `{table}`
+[REMOVING] bar from dependencies
+[WARNING] aborting remove due to dry run
+",
+        )
+        .run();
+}
@@ -3,7 +3,9 @@
 use cargo_test_support::install::cargo_home;
 use cargo_test_support::paths::CargoPathExt;
 use cargo_test_support::registry::Package;
-use cargo_test_support::{basic_bin_manifest, basic_lib_manifest, main_file, project, rustc_host};
+use cargo_test_support::{
+    basic_bin_manifest, basic_lib_manifest, basic_manifest, main_file, project, rustc_host,
+};
 use serde_json::json;
 
 #[cargo_test]
@@ -975,6 +977,49 @@ fn workspace_metadata_with_dependencies_no_deps() {
             r#"
     {
         "packages": [
+            {
+              "authors": [
+                "wycats@example.com"
+              ],
+              "categories": [],
+              "default_run": null,
+              "dependencies": [],
+              "description": null,
+              "documentation": null,
+              "edition": "2015",
+              "features": {},
+              "homepage": null,
+              "id": "artifact 0.5.0 (path+file:[..]/foo/artifact)",
+              "keywords": [],
+              "license": null,
+              "license_file": null,
+              "links": null,
+              "manifest_path": "[..]/foo/artifact/Cargo.toml",
+              "metadata": null,
+              "name": "artifact",
+              "publish": null,
+              "readme": null,
+              "repository": null,
+              "rust_version": null,
+              "source": null,
+              "targets": [
+                {
+                  "crate_types": [
+                    "bin"
+                  ],
+                  "doc": true,
+                  "doctest": false,
+                  "edition": "2015",
+                  "kind": [
+                    "bin"
+                  ],
+                  "name": "artifact",
+                  "src_path": "[..]/foo/artifact/src/main.rs",
+                  "test": true
+                }
+              ],
+              "version": "0.5.0"
+            },
             {
                 "authors": [
                     "wycats@example.com"
@@ -1048,49 +1093,6 @@ fn workspace_metadata_with_dependencies_no_deps() {
                 "metadata": null,
                 "publish": null
             },
-            {
-              "authors": [
-                "wycats@example.com"
-              ],
-              "categories": [],
-              "default_run": null,
-              "dependencies": [],
-              "description": null,
-              "documentation": null,
-              "edition": "2015",
-              "features": {},
-              "homepage": null,
-              "id": "artifact 0.5.0 (path+file:[..]/foo/artifact)",
-              "keywords": [],
-              "license": null,
-              "license_file": null,
-              "links": null,
-              "manifest_path": "[..]/foo/artifact/Cargo.toml",
-              "metadata": null,
-              "name": "artifact",
-              "publish": null,
-              "readme": null,
-              "repository": null,
-              "rust_version": null,
-              "source": null,
-              "targets": [
-                {
-                  "crate_types": [
-                    "bin"
-                  ],
-                  "doc": true,
-                  "doctest": false,
-                  "edition": "2015",
-                  "kind": [
-                    "bin"
-                  ],
-                  "name": "artifact",
-                  "src_path": "[..]/foo/artifact/src/main.rs",
-                  "test": true
-                }
-              ],
-              "version": "0.5.0"
-            },
             {
                 "authors": [
                     "wycats@example.com"
@@ -1132,13 +1134,13 @@ fn workspace_metadata_with_dependencies_no_deps() {
             }
         ],
         "workspace_members": [
-            "bar 0.5.0 (path+file:[..]bar)",
             "artifact 0.5.0 (path+file:[..]/foo/artifact)",
+            "bar 0.5.0 (path+file:[..]bar)",
             "baz 0.5.0 (path+file:[..]baz)"
         ],
         "workspace_default_members": [
-            "bar 0.5.0 (path+file:[..]bar)",
             "artifact 0.5.0 (path+file:[..]/foo/artifact)",
+            "bar 0.5.0 (path+file:[..]bar)",
             "baz 0.5.0 (path+file:[..]baz)"
         ],
         "resolve": null,
@@ -1768,14 +1770,14 @@ fn workspace_metadata_with_dependencies_and_resolve() {
               "target_directory": "[..]/foo/target",
               "version": 1,
               "workspace_members": [
-                "bar 0.5.0 (path+file://[..]/foo/bar)",
                 "artifact 0.5.0 (path+file://[..]/foo/artifact)",
+                "bar 0.5.0 (path+file://[..]/foo/bar)",
                 "bin-only-artifact 0.5.0 (path+file://[..]/foo/bin-only-artifact)",
                 "non-artifact 0.5.0 (path+file://[..]/foo/non-artifact)"
               ],
               "workspace_default_members": [
-                "bar 0.5.0 (path+file://[..]/foo/bar)",
                 "artifact 0.5.0 (path+file://[..]/foo/artifact)",
+                "bar 0.5.0 (path+file://[..]/foo/bar)",
                 "bin-only-artifact 0.5.0 (path+file://[..]/foo/bin-only-artifact)",
                 "non-artifact 0.5.0 (path+file://[..]/foo/non-artifact)"
               ],
@@ -4052,6 +4054,49 @@ fn workspace_metadata_with_dependencies_no_deps_artifact() {
             {
               "metadata": null,
               "packages": [
+                {
+                  "authors": [
+                    "wycats@example.com"
+                  ],
+                  "categories": [],
+                  "default_run": null,
+                  "dependencies": [],
+                  "description": null,
+                  "documentation": null,
+                  "edition": "2015",
+                  "features": {},
+                  "homepage": null,
+                  "id": "artifact 0.5.0 (path+file://[..]/foo/artifact)",
+                  "keywords": [],
+                  "license": null,
+                  "license_file": null,
+                  "links": null,
+                  "manifest_path": "[..]/foo/artifact/Cargo.toml",
+                  "metadata": null,
+                  "name": "artifact",
+                  "publish": null,
+                  "readme": null,
+                  "repository": null,
+                  "rust_version": null,
+                  "source": null,
+                  "targets": [
+                    {
+                      "crate_types": [
+                        "bin"
+                      ],
+                      "doc": true,
+                      "doctest": false,
+                      "edition": "2015",
+                      "kind": [
+                        "bin"
+                      ],
+                      "name": "artifact",
+                      "src_path": "[..]/foo/artifact/src/main.rs",
+                      "test": true
+                    }
+                  ],
+                  "version": "0.5.0"
+                },
                 {
                   "authors": [
                     "wycats@example.com"
@@ -4142,49 +4187,6 @@ fn workspace_metadata_with_dependencies_no_deps_artifact() {
                   ],
                   "version": "0.5.0"
                 },
-                {
-                  "authors": [
-                    "wycats@example.com"
-                  ],
-                  "categories": [],
-                  "default_run": null,
-                  "dependencies": [],
-                  "description": null,
-                  "documentation": null,
-                  "edition": "2015",
-                  "features": {},
-                  "homepage": null,
-                  "id": "artifact 0.5.0 (path+file://[..]/foo/artifact)",
-                  "keywords": [],
-                  "license": null,
-                  "license_file": null,
-                  "links": null,
-                  "manifest_path": "[..]/foo/artifact/Cargo.toml",
-                  "metadata": null,
-                  "name": "artifact",
-                  "publish": null,
-                  "readme": null,
-                  "repository": null,
-                  "rust_version": null,
-                  "source": null,
-                  "targets": [
-                    {
-                      "crate_types": [
-                        "bin"
-                      ],
-                      "doc": true,
-                      "doctest": false,
-                      "edition": "2015",
-                      "kind": [
-                        "bin"
-                      ],
-                      "name": "artifact",
-                      "src_path": "[..]/foo/artifact/src/main.rs",
-                      "test": true
-                    }
-                  ],
-                  "version": "0.5.0"
-                },
                 {
                   "authors": [
                     "wycats@example.com"
@@ -4233,13 +4235,13 @@ fn workspace_metadata_with_dependencies_no_deps_artifact() {
               "target_directory": "[..]/foo/target",
               "version": 1,
               "workspace_members": [
-                "bar 0.5.0 (path+file://[..]/foo/bar)",
                 "artifact 0.5.0 (path+file://[..]/foo/artifact)",
+                "bar 0.5.0 (path+file://[..]/foo/bar)",
                 "baz 0.5.0 (path+file://[..]/foo/baz)"
               ],
               "workspace_default_members": [
-                "bar 0.5.0 (path+file://[..]/foo/bar)",
                 "artifact 0.5.0 (path+file://[..]/foo/artifact)",
+                "bar 0.5.0 (path+file://[..]/foo/bar)",
                 "baz 0.5.0 (path+file://[..]/foo/baz)"
               ],
               "workspace_root": "[..]/foo"
@@ -4248,3 +4250,446 @@ fn workspace_metadata_with_dependencies_no_deps_artifact() {
         )
         .run();
 }
+
+#[cargo_test]
+fn vcs_info_of_registry_package() {
+    Package::new("bar", "0.1.0")
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo_vcs_info.json",
+            r#"{"git": {"sha1": "0123456789abcdef"}, "path_in_vcs": "crates/bar"}"#,
+        )
+        .publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                bar = "0.1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("metadata")
+        .with_stdout_does_not_contain("[..]vcs_info[..]")
+        .run();
+    p.cargo("metadata -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_contains(
+            r#"[..]"vcs_info":{"git":{"sha1":"0123456789abcdef"},"path_in_vcs":"crates/bar"}[..]"#,
+        )
+        .run();
+}
+
+#[cargo_test]
+fn resolve_for() {
+    Package::new("common", "0.1.0")
+        .feature("std", &[])
+        .publish();
+    Package::new("unix-dep", "0.1.0").publish();
+    Package::new("windows-dep", "0.1.0").publish();
+    Package::new("opt-dep", "0.1.0").publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2021"
+
+                [features]
+                extra = ["dep:opt-dep"]
+
+                [dependencies]
+                common = { version = "0.1", features = ["std"] }
+                opt-dep = { version = "0.1", optional = true }
+
+                [build-dependencies]
+                common = "0.1"
+
+                [target.'cfg(unix)'.dependencies]
+                unix-dep = "0.1"
+
+                [target.'cfg(windows)'.dependencies]
+                windows-dep = "0.1"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("build.rs", "fn main() {}")
+        .build();
+
+    let (platform_dep, cfg) = if cfg!(windows) {
+        ("windows-dep", "windows")
+    } else {
+        ("unix-dep", "unix")
+    };
+    let expected = r#"
+{
+  "packages": "{...}",
+  "workspace_members": "{...}",
+  "workspace_default_members": "{...}",
+  "resolve": "{...}",
+  "activated": {
+    "target": "$HOST",
+    "cfgs": "{...}",
+    "packages": [
+      {
+        "id": "common 0.1.0 (registry+https://github.com/rust-lang/crates.io-index)",
+        "host": false,
+        "features": ["std"],
+        "dependencies": []
+      },
+      {
+        "id": "common 0.1.0 (registry+https://github.com/rust-lang/crates.io-index)",
+        "host": true,
+        "features": [],
+        "dependencies": []
+      },
+      {
+        "id": "foo 0.1.0 (path+file://[..]/foo)",
+        "host": false,
+        "features": $FEATURES,
+        "dependencies": [
+          "common 0.1.0 (registry+https://github.com/rust-lang/crates.io-index)",
+          $OPT_DEP
+          "$PLATFORM_DEP 0.1.0 (registry+https://github.com/rust-lang/crates.io-index)"
+        ]
+      },
+      $OPT_DEP_PACKAGE
+      {
+        "id": "$PLATFORM_DEP 0.1.0 (registry+https://github.com/rust-lang/crates.io-index)",
+        "host": false,
+        "features": [],
+        "dependencies": []
+      }
+    ]
+  },
+  "target_directory": "[..]/foo/target",
+  "version": 1,
+  "workspace_root": "[..]/foo",
+  "metadata": null
+}
+"#
+    .replace("$HOST", &rustc_host())
+    .replace("$PLATFORM_DEP", platform_dep);
+
+    p.cargo("metadata --format-version 1 -Zunstable-options --resolve-for")
+        .arg(rustc_host())
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_json(
+            &expected
+                .replace("$FEATURES", "[]")
+                .replace("$OPT_DEP_PACKAGE", "")
+                .replace("$OPT_DEP", ""),
+        )
+        .run();
+
+    p.cargo("metadata --format-version 1 -Zunstable-options --features extra --resolve-for")
+        .arg(rustc_host())
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_json(
+            &expected
+                .replace("$FEATURES", r#"["extra"]"#)
+                .replace(
+                    "$OPT_DEP_PACKAGE",
+                    r#"{
+        "id": "opt-dep 0.1.0 (registry+https://github.com/rust-lang/crates.io-index)",
+        "host": false,
+        "features": [],
+        "dependencies": []
+      },"#,
+                )
+                .replace(
+                    "$OPT_DEP",
+                    r#""opt-dep 0.1.0 (registry+https://github.com/rust-lang/crates.io-index)","#,
+                ),
+        )
+        .run();
+
+    // The cfgs of the target are reported too.
+    p.cargo("metadata --format-version 1 -Zunstable-options --resolve-for")
+        .arg(rustc_host())
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_contains(format!("[..]\"cfgs\":[..]\"{cfg}\"[..]"))
+        .run();
+}
+
+#[cargo_test]
+fn resolve_for_host_and_cfgs() {
+    Package::new("unix-dep", "0.1.0").publish();
+    Package::new("windows-dep", "0.1.0").publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [target.'cfg(unix)'.dependencies]
+                unix-dep = "0.1"
+
+                [target.'cfg(windows)'.dependencies]
+                windows-dep = "0.1"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("metadata --format-version 1 -Zunstable-options --resolve-for host")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_contains(format!("[..]\"target\":\"{}\"[..]", rustc_host()))
+        .run();
+
+    // Only the given cfgs are set.
+    p.cargo("metadata --format-version 1 -Zunstable-options --resolve-for")
+        .arg(format!("{},windows,target_os=\"windows\"", rustc_host()))
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_contains("[..]\"cfgs\":[\"windows\",\"target_os = \\\"windows\\\"\"][..]")
+        .with_stdout_contains("[..]\"id\":\"windows-dep 0.1.0 [..]")
+        .with_stdout_does_not_contain("[..]\"id\":\"unix-dep 0.1.0 [..]")
+        .run();
+
+    p.cargo("metadata --format-version 1 -Zunstable-options --resolve-for host,unix,")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_contains("[ERROR] invalid `cfg` value `` in `--resolve-for host,unix,`")
+        .run();
+}
+
+#[cargo_test]
+fn resolve_for_requires_unstable_options() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("metadata --format-version 1 --resolve-for")
+        .arg(rustc_host())
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] the `--resolve-for` flag is unstable, \
+pass `-Z unstable-options` to enable it",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn filter_platform_cfg() {
+    Package::new("unix-dep", "0.1.0").publish();
+    Package::new("windows-dep", "0.1.0").publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [target.'cfg(unix)'.dependencies]
+                unix-dep = "0.1"
+
+                [target.'cfg(windows)'.dependencies]
+                windows-dep = "0.1"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    let deps = |args: &str| -> Vec<String> {
+        let output = p
+            .cargo(&format!(
+                "metadata --format-version 1 -Zunstable-options {args}"
+            ))
+            .masquerade_as_nightly_cargo(&["unstable-options"])
+            .exec_with_output()
+            .unwrap();
+        let metadata: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let node = metadata["resolve"]["nodes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|node| node["id"].as_str().unwrap().starts_with("foo "))
+            .unwrap();
+        node["deps"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|dep| dep["name"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    // The target is unknown to rustc, which is never asked about it.
+    assert_eq!(
+        deps("--filter-platform fake-unknown-target --cfg windows"),
+        ["windows_dep"]
+    );
+    assert_eq!(
+        deps("--filter-platform fake-unknown-target --cfg unix"),
+        ["unix_dep"]
+    );
+    assert!(deps("--filter-platform fake-unknown-target --cfg other").is_empty());
+
+    // `--resolve-for` reports the given cfgs.
+    p.cargo("metadata --format-version 1 -Zunstable-options --resolve-for fake-unknown-target")
+        .arg("--cfg=unix")
+        .arg(r#"--cfg=target_os="fake""#)
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_contains(r#"[..]"cfgs":["unix","target_os = \"fake\""][..]"#)
+        .run();
+}
+
+#[cargo_test]
+fn filter_platform_cfg_errors() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("metadata --format-version 1 --filter-platform fake-unknown-target --cfg unix")
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] the `--cfg` flag is unstable, pass `-Z unstable-options` to enable it",
+        )
+        .run();
+
+    p.cargo("metadata --format-version 1 -Zunstable-options --cfg unix")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] `--cfg` requires a target, pass `--filter-platform` or `--resolve-for`",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn members_sorted_by_package_id() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["zed", "alpha", "mid"]
+                default-members = ["zed", "alpha"]
+            "#,
+        )
+        .file("zed/Cargo.toml", &basic_lib_manifest("zed"))
+        .file("zed/src/lib.rs", "")
+        .file("alpha/Cargo.toml", &basic_lib_manifest("alpha"))
+        .file("alpha/src/lib.rs", "")
+        .file("mid/Cargo.toml", &basic_lib_manifest("mid"))
+        .file("mid/src/lib.rs", "")
+        .build();
+
+    for args in ["metadata --no-deps", "metadata"] {
+        let output = p.cargo(args).exec_with_output().unwrap();
+        let metadata: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let names = |key: &str| -> Vec<String> {
+            metadata[key]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|id| id.as_str().unwrap().split(' ').next().unwrap().to_string())
+                .collect()
+        };
+        assert_eq!(names("workspace_members"), ["alpha", "mid", "zed"]);
+        assert_eq!(names("workspace_default_members"), ["alpha", "zed"]);
+        let packages: Vec<_> = metadata["packages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|pkg| pkg["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(packages, ["alpha", "mid", "zed"]);
+    }
+}
+
+fn provenance_of(p: &cargo_test_support::Project, name: &str) -> serde_json::Value {
+    let output = p.cargo("metadata").exec_with_output().unwrap();
+    let metadata: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let node = metadata["resolve"]["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|node| {
+            node["id"]
+                .as_str()
+                .unwrap()
+                .starts_with(&format!("{name} "))
+        })
+        .unwrap();
+    node.get("provenance").cloned().unwrap_or_default()
+}
+
+#[cargo_test]
+fn patch_provenance() {
+    Package::new("bar", "0.1.0").publish();
+    Package::new("baz", "0.1.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                bar = "0.1.0"
+                baz = "0.1.0"
+
+                [patch.crates-io]
+                baz = { path = "baz" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("baz/Cargo.toml", &basic_manifest("baz", "0.1.0"))
+        .file("baz/src/lib.rs", "")
+        .build();
+
+    assert_eq!(
+        provenance_of(&p, "baz"),
+        json!({
+            "kind": "patch",
+            "source": "https://github.com/rust-lang/crates.io-index"
+        })
+    );
+    assert_eq!(provenance_of(&p, "bar"), serde_json::Value::Null);
+    assert_eq!(provenance_of(&p, "foo"), serde_json::Value::Null);
+}
+
+#[cargo_test]
+fn replace_provenance() {
+    Package::new("baz", "0.1.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                baz = "0.1.0"
+
+                [replace]
+                "baz:0.1.0" = { path = "baz" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("baz/Cargo.toml", &basic_manifest("baz", "0.1.0"))
+        .file("baz/src/lib.rs", "")
+        .build();
+
+    assert_eq!(
+        provenance_of(&p, "baz"),
+        json!({
+            "kind": "replace",
+            "source": "https://github.com/rust-lang/crates.io-index",
+            "replaces": "baz 0.1.0 (registry+https://github.com/rust-lang/crates.io-index)"
+        })
+    );
+}
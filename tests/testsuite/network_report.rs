@@ -0,0 +1,72 @@
+//! Tests for `cargo --network-report`.
+
+use cargo_test_support::project;
+use cargo_test_support::registry::{Package, RegistryBuilder};
+
+#[cargo_test]
+fn gated() {
+    let p = project().file("src/lib.rs", "").build();
+    p.cargo("--network-report fetch")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] the `--network-report` flag is unstable, pass `-Z unstable-options` to enable it
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn report() {
+    let _registry = RegistryBuilder::new().http_index().build();
+    Package::new("bar", "0.0.1").publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+
+                [dependencies]
+                bar = "0.0.1"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("--network-report fetch -Zunstable-options -v")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr(
+            "\
+[UPDATING] `dummy-registry` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v0.0.1 (registry `dummy-registry`)
+     Request HTTP/1.1 200 http://127.0.0.1:[..]/index/config.json ([..] B, [..]s)
+     Request HTTP/1.1 200 http://127.0.0.1:[..]/index/3/b/bar ([..] B, [..]s)
+     Request HTTP/1.1 200 http://127.0.0.1:[..]/dl/bar/0.0.1/download ([..] B, [..]s)
+     Network 3 requests (0 retried, 0 not modified), 0 cache hits, [..] B downloaded
+     Network HTTP/1.1: 3 requests, [..]s on average, slowest [..]s for http://127.0.0.1:[..]
+",
+        )
+        .run();
+
+    p.cargo("--network-report fetch -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr(
+            "     Network 0 requests (0 retried, 0 not modified), 1 cache hits, 0 B downloaded",
+        )
+        .run();
+
+    p.cargo("--network-report update -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr(
+            "\
+[UPDATING] `dummy-registry` index
+     Network 2 requests (0 retried, 0 not modified), 0 cache hits, [..] B downloaded
+     Network HTTP/1.1: 2 requests, [..]s on average, slowest [..]s for http://127.0.0.1:[..]
+",
+        )
+        .run();
+}
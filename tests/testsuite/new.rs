@@ -117,6 +117,21 @@ fn simple_hg() {
     cargo_process("build").cwd(&paths::root().join("foo")).run();
 }
 
+#[cargo_test(requires_jj)]
+fn simple_jj() {
+    cargo_process("new --lib foo --edition 2015 --vcs jj").run();
+
+    assert!(paths::root().join("foo/Cargo.toml").is_file());
+    assert!(paths::root().join("foo/src/lib.rs").is_file());
+    assert!(paths::root().join("foo/.jj").is_dir());
+
+    let fp = paths::root().join("foo/.gitignore");
+    let contents = fs::read_to_string(&fp).unwrap();
+    assert_eq!(contents, "/target\n/Cargo.lock\n",);
+
+    cargo_process("build").cwd(&paths::root().join("foo")).run();
+}
+
 #[cargo_test]
 fn no_argument() {
     cargo_process("new")
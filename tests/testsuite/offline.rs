@@ -572,6 +572,7 @@ candidate versions found which didn't match: 1.0.0
 location searched: `[..]` index (which is replacing registry `crates-io`)
 required by package `foo v0.1.0 ([..]/foo)`
 perhaps a crate was updated and forgotten to be re-vendored?
+versions of `dep` available offline: 1.0.0
 As a reminder, you're using offline mode (--offline) which can sometimes cause \
 surprising resolution failures, if this error is too confusing you may wish to \
 retry without the offline flag.
@@ -580,6 +581,90 @@ retry without the offline flag.
         .run();
 }
 
+#[cargo_test]
+fn use_cached_suggests_downloaded_version() {
+    Package::new("bar", "1.0.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "foo"
+            version = "0.1.0"
+
+            [dependencies]
+            bar = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+    p.cargo("fetch").run();
+
+    // Lock a version that never gets downloaded.
+    Package::new("bar", "1.0.1").publish();
+    p.cargo("update").run();
+
+    p.cargo("check --offline -Zuse-cached")
+        .masquerade_as_nightly_cargo(&["use-cached"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to select a version for the requirement `bar = \"^1.0\"` (locked to 1.0.1)
+candidate versions found which didn't match: 1.0.0
+location searched: `dummy-registry` index (which is replacing registry `crates-io`)
+required by package `foo v0.1.0 ([CWD])`
+perhaps a crate was updated and forgotten to be re-vendored?
+versions of `bar` available offline: 1.0.0
+consider running `cargo update -p bar --precise 1.0.0 --offline` to use a version that is available offline
+As a reminder, you're using offline mode (--offline) which can sometimes cause \
+surprising resolution failures, if this error is too confusing you may wish to \
+retry without the offline flag.
+",
+        )
+        .run();
+
+    p.cargo("update -p bar --precise 1.0.0 --offline").run();
+    p.cargo("check --offline -Zuse-cached")
+        .masquerade_as_nightly_cargo(&["use-cached"])
+        .with_stderr(
+            "\
+[CHECKING] bar v1.0.0
+[CHECKING] foo v0.1.0 ([CWD])
+[FINISHED] [..]
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn use_cached_without_offline() {
+    Package::new("bar", "1.0.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "foo"
+            version = "0.1.0"
+
+            [dependencies]
+            bar = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+    p.cargo("fetch").run();
+    p.cargo("generate-lockfile").run();
+
+    // The newer version is in the index, but isn't downloaded.
+    Package::new("bar", "1.0.1").publish();
+    p.cargo("update -Zuse-cached")
+        .masquerade_as_nightly_cargo(&["use-cached"])
+        .with_stderr("[UPDATING] `dummy-registry` index")
+        .run();
+    assert!(p.read_lockfile().contains("version = \"1.0.0\""));
+}
+
 #[cargo_test]
 fn offline_with_all_patched() {
     // Offline works if everything is patched.
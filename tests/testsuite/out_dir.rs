@@ -281,6 +281,146 @@ fn cargo_build_out_dir() {
     );
 }
 
+#[cargo_test]
+fn cargo_build_out_dir_per_kind() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [workspace]
+            members = ["a", "b", "c"]
+            "#,
+        )
+        .file("a/Cargo.toml", &basic_manifest("a", "0.0.1"))
+        .file("a/src/main.rs", "fn main() {}")
+        .file(
+            "b/Cargo.toml",
+            r#"
+                [package]
+                name = "b"
+                version = "0.0.1"
+
+                [lib]
+                crate-type = ["cdylib"]
+            "#,
+        )
+        .file("b/src/lib.rs", "")
+        .file("c/Cargo.toml", &basic_manifest("c", "0.0.1"))
+        .file("c/src/lib.rs", "")
+        .file(
+            ".cargo/config",
+            r#"
+            [build]
+            out-dir = { bins = "dist/bin", cdylibs = "dist/lib" }
+            "#,
+        )
+        .build();
+
+    p.cargo("build -Z unstable-options")
+        .masquerade_as_nightly_cargo(&["out-dir"])
+        .enable_mac_dsym()
+        .run();
+    check_dir_contents(
+        &p.root().join("dist/bin"),
+        &["a"],
+        &["a", "a.dSYM"],
+        &["a.exe", "a.pdb"],
+        &["a.exe"],
+    );
+    check_dir_contents(
+        &p.root().join("dist/lib"),
+        &["libb.so"],
+        &["libb.dylib", "libb.dylib.dSYM"],
+        &["b.dll", "b.dll.exp", "b.dll.lib", "b.pdb"],
+        &["b.dll", "libb.dll.a"],
+    );
+    // rlibs have no directory configured, so they are not copied anywhere.
+    check_dir_contents(
+        &p.root().join("dist"),
+        &["bin", "lib"],
+        &["bin", "lib"],
+        &["bin", "lib"],
+        &["bin", "lib"],
+    );
+}
+
+#[cargo_test]
+fn cargo_build_out_dir_per_kind_with_all() {
+    let p = project()
+        .file("src/main.rs", "fn main() {}")
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config",
+            r#"
+            [build]
+            out-dir = { all = "out", bins = "dist/bin" }
+            "#,
+        )
+        .build();
+
+    p.cargo("build -Z unstable-options")
+        .masquerade_as_nightly_cargo(&["out-dir"])
+        .enable_mac_dsym()
+        .run();
+    check_dir_contents(
+        &p.root().join("dist/bin"),
+        &["foo"],
+        &["foo", "foo.dSYM"],
+        &["foo.exe", "foo.pdb"],
+        &["foo.exe"],
+    );
+    // The rlib has no more specific directory, so it goes to `all`.
+    check_dir_contents(
+        &p.root().join("out"),
+        &["libfoo.rlib"],
+        &["libfoo.rlib"],
+        &["libfoo.rlib"],
+        &["libfoo.rlib"],
+    );
+}
+
+#[cargo_test]
+fn cargo_build_out_dir_unknown_kind() {
+    let p = project()
+        .file("src/main.rs", "fn main() {}")
+        .file(
+            ".cargo/config",
+            r#"
+            [build]
+            out-dir = { executables = "dist" }
+            "#,
+        )
+        .build();
+
+    p.cargo("build -Z unstable-options")
+        .masquerade_as_nightly_cargo(&["out-dir"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] unknown artifact kind `executables` in `build.out-dir` (defined in [..]config), \
+expected one of `all`, `bins`, `cdylibs`, `dylibs`, `staticlibs`, `rlibs` or `wasm`
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn out_dir_json_message() {
+    let p = project().file("src/main.rs", "fn main() {}").build();
+
+    p.cargo("build -Z unstable-options --out-dir out --message-format=json")
+        .masquerade_as_nightly_cargo(&["out-dir"])
+        .with_stdout_contains(
+            "[..]\"reason\":\"compiler-artifact\"[..]\"out_dir_filenames\":[\"[..]out[..]foo[EXE]\"[..]",
+        )
+        .run();
+
+    // Artifacts that are not copied do not mention the field at all.
+    p.cargo("build --message-format=json")
+        .with_stdout_does_not_contain("[..]out_dir_filenames[..]")
+        .run();
+}
+
 fn check_dir_contents(
     out_dir: &Path,
     expected_linux: &[&str],
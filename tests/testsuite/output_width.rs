@@ -0,0 +1,73 @@
+//! Tests for `term.width` and `--output-width`.
+
+use cargo_test_support::project;
+
+#[cargo_test]
+fn gated() {
+    let p = project().file("src/lib.rs", "").build();
+    p.cargo("--output-width 80 check")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] the `--output-width` flag is unstable, pass `-Z output-width` to enable it
+",
+        )
+        .run();
+
+    // The config is ignored without `-Zoutput-width`.
+    p.cargo("check -v")
+        .env("CARGO_TERM_WIDTH", "80")
+        .with_stderr_does_not_contain("[..]--diagnostic-width[..]")
+        .run();
+}
+
+#[cargo_test]
+fn diagnostic_width() {
+    let p = project().file("src/lib.rs", "").build();
+    p.cargo("--output-width 80 check -v -Zoutput-width")
+        .masquerade_as_nightly_cargo(&["output-width"])
+        .with_stderr_contains("[RUNNING] `rustc [..]--diagnostic-width=80 [..]`")
+        .run();
+
+    // The flag takes precedence over the config.
+    p.change_file(".cargo/config.toml", "[term]\nwidth = 60");
+    p.cargo("clean").run();
+    p.cargo("check -v -Zoutput-width")
+        .masquerade_as_nightly_cargo(&["output-width"])
+        .with_stderr_contains("[RUNNING] `rustc [..]--diagnostic-width=60[..]`")
+        .run();
+    p.cargo("clean").run();
+    p.cargo("--output-width 100 check -v -Zoutput-width")
+        .masquerade_as_nightly_cargo(&["output-width"])
+        .with_stderr_contains("[RUNNING] `rustc [..]--diagnostic-width=100[..]`")
+        .run();
+}
+
+#[cargo_test]
+fn wraps_messages() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                unused-key = "a value that is not read"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    // Statuses aren't wrapped, so commands printed with `-v` can be copied.
+    p.cargo("--output-width 24 check -Zoutput-width")
+        .masquerade_as_nightly_cargo(&["output-width"])
+        .with_stderr(
+            "\
+[WARNING] unused manifest
+key: package.unused-key
+[CHECKING] foo v0.0.1 ([CWD])
+[FINISHED] dev [unoptimized + debuginfo] target(s) in [..]
+",
+        )
+        .run();
+}
@@ -1,10 +1,11 @@
 //! Tests for the `cargo owner` command.
 
 use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use cargo_test_support::paths::CargoPathExt;
 use cargo_test_support::project;
-use cargo_test_support::registry::{self, api_path};
+use cargo_test_support::registry::{self, api_path, RegistryBuilder, Response};
 
 fn setup(name: &str, content: Option<&str>) {
     let dir = api_path().join(format!("api/v1/crates/{}", name));
@@ -58,6 +59,57 @@ octocat
         .run();
 }
 
+#[cargo_test]
+fn list_retries_server_errors() {
+    static ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
+    let registry = RegistryBuilder::new()
+        .http_api()
+        .add_responder("/api/v1/crates/foo/owners", |_, _| {
+            if ATTEMPTS.fetch_add(1, Ordering::SeqCst) == 0 {
+                Response {
+                    code: 503,
+                    headers: vec![],
+                    body: b"Please slow down".to_vec(),
+                }
+            } else {
+                Response {
+                    code: 200,
+                    headers: vec![],
+                    body: br#"{"users": [{"id": 123, "login": "octocat"}]}"#.to_vec(),
+                }
+            }
+        })
+        .build();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                authors = []
+                license = "MIT"
+                description = "foo"
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("owner -l")
+        .replace_crates_io(registry.index_url())
+        .with_stdout("octocat\n")
+        .with_stderr(
+            "\
+[UPDATING] [..]
+[WARNING] spurious network error (3 tries remaining): failed to get a 200 OK response, got 503
+body:
+Please slow down
+",
+        )
+        .run();
+}
+
 #[cargo_test]
 fn simple_add() {
     let registry = registry::init();
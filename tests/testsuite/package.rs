@@ -232,6 +232,98 @@ See https://doc.rust-lang.org/cargo/reference/manifest.html#package-metadata for
     );
 }
 
+#[cargo_test]
+fn package_dirty_vcs_info() {
+    let repo = git::repo(&paths::root().join("foo"))
+        .file("Cargo.toml", &basic_manifest("foo", "0.0.1"))
+        .file("src/lib.rs", "")
+        .build();
+    fs::write(repo.root().join("src/lib.rs"), "pub fn foo() {}").unwrap();
+
+    // Without `-Zunstable-options`, nothing is recorded for a dirty repo.
+    cargo_process("package --no-verify --no-metadata --allow-dirty")
+        .cwd(repo.root())
+        .run();
+    let f = File::open(&repo.root().join("target/package/foo-0.0.1.crate")).unwrap();
+    validate_crate_contents(
+        f,
+        "foo-0.0.1.crate",
+        &["Cargo.toml", "Cargo.toml.orig", "src/lib.rs"],
+        &[],
+    );
+
+    cargo_process("package --no-verify --no-metadata --allow-dirty -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .cwd(repo.root())
+        .run();
+
+    let f = File::open(&repo.root().join("target/package/foo-0.0.1.crate")).unwrap();
+    let vcs_contents = format!(
+        r#"{{
+  "git": {{
+    "sha1": "{}",
+    "dirty": true
+  }},
+  "path_in_vcs": ""
+}}
+"#,
+        repo.revparse_head()
+    );
+    validate_crate_contents(
+        f,
+        "foo-0.0.1.crate",
+        &[
+            "Cargo.toml",
+            "Cargo.toml.orig",
+            "src/lib.rs",
+            ".cargo_vcs_info.json",
+        ],
+        &[(".cargo_vcs_info.json", &vcs_contents)],
+    );
+}
+
+#[cargo_test]
+fn require_clean_vcs() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("package --require-clean-vcs")
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] the `--require-clean-vcs` flag is unstable, \
+             pass `-Z unstable-options` to enable it",
+        )
+        .run();
+
+    p.cargo("package --require-clean-vcs --no-metadata -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] `[CWD]/Cargo.toml` is not checked into a git repository
+
+`--require-clean-vcs` requires the package to be committed into git
+",
+        )
+        .run();
+
+    p.cargo("package --require-clean-vcs --allow-dirty -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(1)
+        .with_stderr_contains(
+            "[ERROR] the argument '--require-clean-vcs' cannot be used with '--allow-dirty'",
+        )
+        .run();
+
+    let repo = git::repo(&paths::root().join("bar"))
+        .file("Cargo.toml", &basic_manifest("bar", "0.0.1"))
+        .file("src/lib.rs", "")
+        .build();
+    cargo_process("package --require-clean-vcs --no-verify --no-metadata -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .cwd(repo.root())
+        .run();
+}
+
 #[cargo_test]
 fn package_verification() {
     let p = project().file("src/main.rs", "fn main() {}").build();
@@ -1077,6 +1169,92 @@ to proceed despite this and include the uncommitted changes, pass the `--allow-d
         .run();
 }
 
+#[cargo_test]
+fn dirty_linked_worktree() {
+    // Cargo checks the working directory of a linked worktree.
+    let (_p, repo) = git::new_repo("foo", |p| {
+        p.file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                description = "foo"
+                license = "foo"
+                documentation = "foo"
+            "#,
+        )
+        .file("src/lib.rs", "")
+    });
+    let worktree = paths::root().join("worktree");
+    repo.worktree("worktree", &worktree, None).unwrap();
+    fs::write(worktree.join("src/lib.rs"), "pub fn f() {}").unwrap();
+
+    cargo_process("package --list")
+        .cwd(&worktree)
+        .with_status(101)
+        .with_stderr(
+            "\
+error: 1 files in the working directory contain changes that were not yet committed into git:
+
+src/lib.rs
+
+to proceed despite this and include the uncommitted changes, pass the `--allow-dirty` flag
+",
+        )
+        .run();
+}
+
+#[cargo_test(requires_hg)]
+fn dirty_hg() {
+    // Cargo checks for uncommitted changes in a Mercurial repository.
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                description = "foo"
+                license = "foo"
+                documentation = "foo"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(".hgignore", "^target$\n")
+        .build();
+    p.process("hg").arg("init").run();
+    p.process("hg")
+        .arg("commit")
+        .arg("--addremove")
+        .arg("--message=init")
+        .arg("--user=foo")
+        .run();
+    p.cargo("package --list -Zvcs-detection")
+        .masquerade_as_nightly_cargo(&["vcs-detection"])
+        .run();
+
+    p.change_file("src/lib.rs", "pub fn f() {}");
+    // Mercurial is only checked with `-Zvcs-detection`.
+    p.cargo("package --list").run();
+    p.cargo("package --list -Zvcs-detection")
+        .masquerade_as_nightly_cargo(&["vcs-detection"])
+        .with_status(101)
+        .with_stderr(
+            "\
+error: 1 files in the working directory contain changes that were not yet committed into hg:
+
+src/lib.rs
+
+to proceed despite this and include the uncommitted changes, pass the `--allow-dirty` flag
+",
+        )
+        .run();
+    p.cargo("package --list --allow-dirty -Zvcs-detection")
+        .masquerade_as_nightly_cargo(&["vcs-detection"])
+        .run();
+}
+
 #[cargo_test]
 fn dirty_ignored() {
     // Cargo warns about an ignored file that will be published.
@@ -2421,6 +2599,134 @@ fn reproducible_output() {
     }
 }
 
+#[cargo_test]
+fn reproducible_across_file_metadata() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                license = "MIT"
+                description = "foo"
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .file("src/lib.rs", "")
+        .build();
+    let crate_path = p.root().join("target/package/foo-0.0.1.crate");
+
+    p.cargo("package --no-verify").run();
+    let first = fs::read(&crate_path).unwrap();
+
+    let mtime = filetime::FileTime::from_unix_time(1_000_000_000, 0);
+    filetime::set_file_mtime(p.root().join("src/lib.rs"), mtime).unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let lib = p.root().join("src/lib.rs");
+        fs::set_permissions(&lib, fs::Permissions::from_mode(0o600)).unwrap();
+    }
+    p.cargo("package --no-verify").run();
+    assert!(fs::read(&crate_path).unwrap() == first);
+
+    let mut archive = Archive::new(GzDecoder::new(File::open(&crate_path).unwrap()));
+    for ent in archive.entries().unwrap() {
+        let ent = ent.unwrap();
+        let header = ent.header();
+        assert_eq!(header.mode().unwrap(), 0o644);
+        assert_eq!(header.mtime().unwrap(), 1153704088);
+        assert_eq!(header.uid().unwrap(), 0);
+        assert_eq!(header.gid().unwrap(), 0);
+    }
+}
+
+#[cfg(unix)]
+#[cargo_test]
+fn executable_bit_is_kept() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                license = "MIT"
+                description = "foo"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("run.sh", "#!/bin/sh\n")
+        .build();
+    fs::set_permissions(p.root().join("run.sh"), fs::Permissions::from_mode(0o700)).unwrap();
+
+    p.cargo("package --no-verify").run();
+
+    let f = File::open(p.root().join("target/package/foo-0.0.1.crate")).unwrap();
+    let mut archive = Archive::new(GzDecoder::new(f));
+    for ent in archive.entries().unwrap() {
+        let ent = ent.unwrap();
+        let expected = if ent.path().unwrap().ends_with("run.sh") {
+            0o755
+        } else {
+            0o644
+        };
+        assert_eq!(ent.header().mode().unwrap(), expected);
+    }
+}
+
+#[cargo_test]
+fn compression_level() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                license = "MIT"
+                description = "foo"
+            "#,
+        )
+        .file("src/lib.rs", &"pub fn foo() {}\n".repeat(1000))
+        .build();
+    let crate_path = p.root().join("target/package/foo-0.0.1.crate");
+
+    p.cargo("package --no-verify").run();
+    let best = fs::metadata(&crate_path).unwrap().len();
+
+    // Ignored without `-Zcrate-compression`.
+    p.cargo("package --no-verify")
+        .env("CARGO_PACKAGE_COMPRESSION_LEVEL", "0")
+        .run();
+    assert_eq!(fs::metadata(&crate_path).unwrap().len(), best);
+
+    p.cargo("package --no-verify -Zcrate-compression")
+        .masquerade_as_nightly_cargo(&["crate-compression"])
+        .env("CARGO_PACKAGE_COMPRESSION_LEVEL", "0")
+        .run();
+    assert!(fs::metadata(&crate_path).unwrap().len() > best);
+    let f = File::open(&crate_path).unwrap();
+    let mut archive = Archive::new(GzDecoder::new(f));
+    assert!(archive.entries().unwrap().any(|ent| ent
+        .unwrap()
+        .path()
+        .unwrap()
+        .ends_with("src/lib.rs")));
+
+    p.cargo("package --no-verify -Zcrate-compression")
+        .masquerade_as_nightly_cargo(&["crate-compression"])
+        .env("CARGO_PACKAGE_COMPRESSION_LEVEL", "10")
+        .with_status(101)
+        .with_stderr_contains(
+            "  invalid `package.compression-level` `10`, expected a level between 0 and 9",
+        )
+        .run();
+}
+
 #[cargo_test]
 fn package_with_resolver_and_metadata() {
     let p = project()
@@ -2535,6 +2841,75 @@ See [..]
     assert!(p.root().join("target/package/bar-0.0.1.crate").is_file());
 }
 
+#[cargo_test]
+fn workspace_cross_verify() {
+    registry::init();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["app", "lib"]
+            "#,
+        )
+        .file(
+            "app/Cargo.toml",
+            r#"
+                [package]
+                name = "app"
+                version = "0.0.1"
+                license = "MIT"
+                description = "app"
+
+                [dependencies]
+                lib = { path = "../lib", version = "0.0.1" }
+            "#,
+        )
+        .file("app/src/lib.rs", "pub fn app() { lib::lib() }")
+        .file(
+            "lib/Cargo.toml",
+            r#"
+                [package]
+                name = "lib"
+                version = "0.0.1"
+                license = "MIT"
+                description = "lib"
+            "#,
+        )
+        .file("lib/src/lib.rs", "pub fn lib() {}")
+        .build();
+
+    // Without the flag `app` is verified against the unpublished `lib`.
+    p.cargo("package --workspace --no-metadata")
+        .with_status(101)
+        .with_stderr_contains("[ERROR] failed to verify package tarball")
+        .with_stderr_contains("  no matching package named `lib` found")
+        .run();
+
+    p.cargo("package --workspace --no-metadata -Zpackage-workspace")
+        .masquerade_as_nightly_cargo(&["package-workspace"])
+        .with_stderr(
+            "\
+[PACKAGING] lib v0.0.1 ([CWD]/lib)
+[VERIFYING] lib v0.0.1 ([CWD]/lib)
+[COMPILING] lib v0.0.1 ([CWD]/target/package/lib-0.0.1)
+[FINISHED] dev [unoptimized + debuginfo] target(s) in [..]
+[PACKAGED] [..] files, [..] ([..] compressed)
+[PACKAGING] app v0.0.1 ([CWD]/app)
+[VERIFYING] app v0.0.1 ([CWD]/app)
+[UPDATING] `dummy-registry` index
+[COMPILING] lib v0.0.1 ([CWD]/target/package/lib-0.0.1)
+[COMPILING] app v0.0.1 ([CWD]/target/package/app-0.0.1)
+[FINISHED] dev [unoptimized + debuginfo] target(s) in [..]
+[PACKAGED] [..] files, [..] ([..] compressed)
+",
+        )
+        .run();
+
+    assert!(p.root().join("target/package/app-0.0.1.crate").is_file());
+    assert!(p.root().join("target/package/lib-0.0.1.crate").is_file());
+}
+
 #[cargo_test]
 fn workspace_noconflict_readme() {
     let p = project()
@@ -0,0 +1,145 @@
+//! Tests for `-Zplugin-manifest`.
+
+use std::env;
+use std::path::Path;
+
+use cargo_test_support::install::cargo_home;
+use cargo_test_support::{cargo_process, project, Project};
+
+/// Makes the external subcommands `cargo-foo` and `cargo-bar`, and returns a
+/// `PATH` with them.
+fn make_subcommands() -> (Project, std::ffi::OsString) {
+    let p = project()
+        .executable(Path::new("path-test").join("cargo-foo"), "")
+        .executable(Path::new("path-test").join("cargo-bar"), "")
+        .build();
+    let mut path: Vec<_> = env::split_paths(&env::var_os("PATH").unwrap_or_default()).collect();
+    path.push(p.root().join("path-test"));
+    let path = env::join_paths(path.iter()).unwrap();
+    (p, path)
+}
+
+fn write_manifest(contents: &str) {
+    let home = cargo_home();
+    std::fs::create_dir_all(&home).unwrap();
+    std::fs::write(home.join("plugins.toml"), contents).unwrap();
+}
+
+#[cargo_test]
+fn list_descriptions() {
+    let (_p, path) = make_subcommands();
+    write_manifest(
+        r#"
+            [subcommands.foo]
+            description = "Does foo things"
+            min-cargo-version = "1.0"
+
+            [subcommands.bar]
+            description = "Needs a newer Cargo"
+            min-cargo-version = "99.0"
+        "#,
+    );
+
+    cargo_process("-Zplugin-manifest --list")
+        .masquerade_as_nightly_cargo(&["plugin-manifest"])
+        .env("PATH", &path)
+        .with_stdout_contains("    foo                  Does foo things")
+        .with_stdout_contains("    bar                  Needs a newer Cargo (requires cargo 99.0)")
+        .run();
+
+    // Without the flag the manifest is ignored.
+    cargo_process("--list")
+        .env("PATH", &path)
+        .with_stdout_does_not_contain("[..]Does foo things[..]")
+        .run();
+}
+
+#[cargo_test]
+fn min_cargo_version() {
+    let (_p, path) = make_subcommands();
+    write_manifest(
+        r#"
+            [subcommands.bar]
+            min-cargo-version = "99.0"
+        "#,
+    );
+
+    cargo_process("-Zplugin-manifest bar")
+        .masquerade_as_nightly_cargo(&["plugin-manifest"])
+        .env("PATH", &path)
+        .with_status(101)
+        .with_stderr("[ERROR] `cargo bar` requires cargo 99.0 or newer, but this is cargo [..]")
+        .run();
+}
+
+#[cargo_test]
+fn completions() {
+    write_manifest(
+        r#"
+            [subcommands.foo]
+            completions = ["--check", "--all"]
+        "#,
+    );
+
+    cargo_process("-Zplugin-manifest --subcommand-completions foo")
+        .masquerade_as_nightly_cargo(&["plugin-manifest"])
+        .with_stdout("--check\n--all\n")
+        .run();
+
+    cargo_process("-Zplugin-manifest --subcommand-completions unknown")
+        .masquerade_as_nightly_cargo(&["plugin-manifest"])
+        .with_stdout("")
+        .run();
+
+    cargo_process("--subcommand-completions foo")
+        .masquerade_as_nightly_cargo(&["plugin-manifest"])
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] the `--subcommand-completions` flag is unstable, \
+pass `-Z plugin-manifest` to enable it",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn invalid_manifest() {
+    let (_p, path) = make_subcommands();
+    write_manifest(
+        r#"
+            [subcommands.foo]
+            description = "Does foo things"
+            min-cargo-version = "^1.70"
+        "#,
+    );
+
+    cargo_process("-Zplugin-manifest --list")
+        .masquerade_as_nightly_cargo(&["plugin-manifest"])
+        .env("PATH", &path)
+        .with_stdout_contains("    foo")
+        .with_stderr(
+            "\
+[WARNING] failed to read the metadata of external subcommands
+
+failed to load `[..]plugins.toml`
+
+Caused by:
+  `min-cargo-version` of subcommand `foo` must be a value like \"1.70\", found `^1.70`
+",
+        )
+        .run();
+
+    write_manifest(
+        r#"
+            [subcommands.foo]
+            description = "Does foo things"
+            aliases = ["f"]
+        "#,
+    );
+
+    cargo_process("-Zplugin-manifest --list")
+        .masquerade_as_nightly_cargo(&["plugin-manifest"])
+        .env("PATH", &path)
+        .with_stdout_contains("    foo                  Does foo things")
+        .with_stderr("[WARNING] unused key `subcommands.foo.aliases` in `[..]plugins.toml`")
+        .run();
+}
@@ -0,0 +1,157 @@
+//! Tests for `-Zprefer-fast-linker`.
+
+use std::env;
+use std::ffi::OsString;
+
+use cargo_test_support::{project, Project};
+
+/// A project with a library, a binary, and an `ld.mold` in `bin` running
+/// `script` before linking with the system `ld`.
+fn fast_linker_project(script: &str) -> Project {
+    project()
+        .file("src/lib.rs", "pub fn f() {}")
+        .file("src/main.rs", "fn main() { foo::f() }")
+        .file(".cargo/config.toml", "[build]\nprefer-fast-linker = true\n")
+        .executable(
+            "bin/ld.mold",
+            &format!("#!/bin/sh\n{script}\nexec ld \"$@\"\n"),
+        )
+        .build()
+}
+
+/// `PATH`, with the `bin` directory of `p` first.
+fn path_with_bin(p: &Project) -> OsString {
+    let mut paths = vec![p.root().join("bin")];
+    paths.extend(env::split_paths(&env::var_os("PATH").unwrap_or_default()));
+    env::join_paths(paths).unwrap()
+}
+
+#[cargo_test]
+#[cfg(target_os = "linux")]
+fn prefer_fast_linker_gated() {
+    let p = fast_linker_project("");
+
+    p.cargo("build -v")
+        .env("PATH", path_with_bin(&p))
+        .with_stderr_does_not_contain("[..]-fuse-ld[..]")
+        .run();
+}
+
+#[cargo_test]
+#[cfg(target_os = "linux")]
+fn links_with_mold() {
+    let p = fast_linker_project("");
+
+    p.cargo("build -v -Zprefer-fast-linker")
+        .masquerade_as_nightly_cargo(&["prefer-fast-linker"])
+        .env("PATH", path_with_bin(&p))
+        .with_stderr_contains(
+            "[RUNNING] `[..]rustc --crate-name foo src/main.rs [..]-C link-arg=-fuse-ld=mold [..]",
+        )
+        .with_stderr_line_without(
+            &["[RUNNING] `[..]rustc --crate-name foo src/lib.rs"],
+            &["-fuse-ld"],
+        )
+        .run();
+    p.process(&p.bin("foo")).run();
+
+    // The linker is part of the fingerprint of the units it links.
+    p.change_file(
+        ".cargo/config.toml",
+        "[build]\nprefer-fast-linker = false\n",
+    );
+    p.cargo("build -v -Zprefer-fast-linker")
+        .masquerade_as_nightly_cargo(&["prefer-fast-linker"])
+        .env("PATH", path_with_bin(&p))
+        .with_stderr_contains("[DIRTY] foo v0.0.1 ([..]): the config settings changed")
+        .with_stderr_contains("[RUNNING] `[..]rustc --crate-name foo src/main.rs [..]")
+        .with_stderr_does_not_contain("[RUNNING] `[..]rustc --crate-name foo src/lib.rs [..]")
+        .with_stderr_does_not_contain("[..]-fuse-ld[..]")
+        .run();
+}
+
+#[cargo_test]
+#[cfg(target_os = "linux")]
+fn falls_back_when_probe_fails() {
+    let p = fast_linker_project("echo 'mold: unsupported' >&2\nexit 1");
+
+    p.cargo("build -v -Zprefer-fast-linker")
+        .masquerade_as_nightly_cargo(&["prefer-fast-linker"])
+        .env("PATH", path_with_bin(&p))
+        .with_stderr_contains(
+            "[WARNING] `build.prefer-fast-linker` could not link with `mold` for `[..]`, \
+             it will not be used",
+        )
+        .with_stderr_contains("[..]mold: unsupported[..]")
+        .with_stderr_line_without(
+            &["[RUNNING] `[..]rustc --crate-name foo src/main.rs"],
+            &["-fuse-ld=mold"],
+        )
+        .run();
+    p.process(&p.bin("foo")).run();
+}
+
+#[cargo_test]
+#[cfg(target_os = "linux")]
+fn note_when_linking_fails() {
+    // Only the program linked by the probe links successfully.
+    let p = fast_linker_project(
+        "case \"$*\" in *fast_linker_probe*) ;; *) echo 'mold: out of luck' >&2; exit 1 ;; esac",
+    );
+
+    p.cargo("build -Zprefer-fast-linker")
+        .masquerade_as_nightly_cargo(&["prefer-fast-linker"])
+        .env("PATH", path_with_bin(&p))
+        .with_status(101)
+        .with_stderr_contains("[ERROR] linking with `cc` failed[..]")
+        .with_stderr_contains(
+            "[WARNING] `foo` was linked with `mold` because of `build.prefer-fast-linker`, \
+             set it to `false` to link with the default linker",
+        )
+        .run();
+}
+
+#[cargo_test]
+#[cfg(target_os = "linux")]
+fn probe_is_cached() {
+    let p = fast_linker_project(
+        "case \"$*\" in *fast_linker_probe*) echo probed >> \"$(dirname \"$0\")/probes\" ;; esac",
+    );
+
+    p.cargo("build -Zprefer-fast-linker")
+        .masquerade_as_nightly_cargo(&["prefer-fast-linker"])
+        .env("PATH", path_with_bin(&p))
+        .run();
+    p.change_file("src/main.rs", "fn main() { foo::f(); foo::f() }");
+    p.cargo("build -v -Zprefer-fast-linker")
+        .masquerade_as_nightly_cargo(&["prefer-fast-linker"])
+        .env("PATH", path_with_bin(&p))
+        .with_stderr_contains(
+            "[RUNNING] `[..]rustc --crate-name foo src/main.rs [..]-fuse-ld=mold [..]",
+        )
+        .run();
+    assert_eq!(p.read_file("bin/probes"), "probed\n");
+}
+
+#[cargo_test]
+#[cfg(target_os = "linux")]
+fn rustflags_picking_linker() {
+    let p = fast_linker_project("");
+
+    // Flags merely mentioning a linker don't pick one.
+    p.cargo("build -v -Zprefer-fast-linker")
+        .masquerade_as_nightly_cargo(&["prefer-fast-linker"])
+        .env("PATH", path_with_bin(&p))
+        .env("RUSTFLAGS", "--cfg has_linker")
+        .with_stderr_contains(
+            "[RUNNING] `[..]rustc --crate-name foo src/main.rs [..]-fuse-ld=mold [..]",
+        )
+        .run();
+
+    p.cargo("build -v -Zprefer-fast-linker")
+        .masquerade_as_nightly_cargo(&["prefer-fast-linker"])
+        .env("PATH", path_with_bin(&p))
+        .env("RUSTFLAGS", "-Clink-arg=-fuse-ld=bfd")
+        .with_stderr_does_not_contain("[..]-fuse-ld=mold[..]")
+        .run();
+}
@@ -0,0 +1,207 @@
+//! Tests for `-Zprofile-auto-tune`.
+
+use cargo_test_support::registry::Package;
+use cargo_test_support::{project, Project};
+
+fn make_project() -> Project {
+    // `heavy` and `light` are leaves, `deep` depends on two levels of packages.
+    Package::new("heavy", "1.0.0").publish();
+    Package::new("light", "1.0.0").publish();
+    Package::new("mid", "1.0.0").dep("light", "1.0").publish();
+    Package::new("deep", "1.0.0").dep("mid", "1.0").publish();
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                heavy = "1.0"
+                deep = "1.0"
+
+                [profile.dev]
+                auto-tune = true
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build()
+}
+
+/// Makes `heavy` look like it took long to compile in the previous build.
+fn make_heavy(p: &Project) {
+    let path = p.root().join("target/debug/.auto-tune.json");
+    let times = std::fs::read_to_string(&path).unwrap();
+    let mut times: serde_json::Value = serde_json::from_str(&times).unwrap();
+    let packages = times["packages"].as_object_mut().unwrap();
+    let heavy = packages
+        .keys()
+        .find(|id| id.starts_with("heavy "))
+        .unwrap()
+        .clone();
+    packages.insert(heavy, serde_json::json!(30.0));
+    std::fs::write(&path, times.to_string()).unwrap();
+}
+
+#[cargo_test]
+fn gated() {
+    let p = make_project();
+    p.cargo("check")
+        .with_status(101)
+        .with_stderr_contains("  `profile.dev.auto-tune` requires `-Zprofile-auto-tune`")
+        .run();
+}
+
+#[cargo_test]
+fn optimizes_heavy_leaves() {
+    let p = make_project();
+
+    // Nothing is optimized before the compile times are known.
+    p.cargo("build -v -Zprofile-auto-tune")
+        .masquerade_as_nightly_cargo(&["profile-auto-tune"])
+        .with_stderr_does_not_contain("[..]opt-level=3[..]")
+        .run();
+    assert!(p.root().join("target/debug/.auto-tune.json").is_file());
+
+    make_heavy(&p);
+    p.cargo("build -v -Zprofile-auto-tune")
+        .masquerade_as_nightly_cargo(&["profile-auto-tune"])
+        .with_stderr_contains("[RUNNING] `rustc --crate-name heavy [..]-C opt-level=3[..]")
+        .with_stderr_contains("[FRESH] deep v1.0.0")
+        .with_stderr_contains("[FRESH] light v1.0.0")
+        .with_stderr_does_not_contain("[RUNNING] `rustc --crate-name foo [..]opt-level[..]")
+        .run();
+
+    // The build recorded the actual compile time of `heavy` again.
+    make_heavy(&p);
+    p.cargo("report auto-tune -Zunstable-options -Zprofile-auto-tune")
+        .masquerade_as_nightly_cargo(&["unstable-options", "profile-auto-tune"])
+        .with_stdout(
+            "\
+deep v1.0.0: `dev` profile (depends on 2 levels of packages)
+foo v0.1.0 ([CWD]): opt-level 0 (workspace member)
+heavy v1.0.0: opt-level 3 (compiled in 30.0s, has no dependencies)
+light v1.0.0: `dev` profile (compiled in [..]s)
+mid v1.0.0: `dev` profile (compiled in [..]s)
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn package_override_wins() {
+    let p = make_project();
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            version = "0.1.0"
+
+            [dependencies]
+            heavy = "1.0"
+            deep = "1.0"
+
+            [profile.dev]
+            auto-tune = true
+
+            [profile.dev.package.heavy]
+            opt-level = 1
+        "#,
+    );
+    p.cargo("build -Zprofile-auto-tune")
+        .masquerade_as_nightly_cargo(&["profile-auto-tune"])
+        .run();
+    make_heavy(&p);
+    p.cargo("build -v -Zprofile-auto-tune")
+        .masquerade_as_nightly_cargo(&["profile-auto-tune"])
+        .with_stderr_contains("[FRESH] heavy v1.0.0")
+        .run();
+}
+
+#[cargo_test]
+fn profile_settings_are_kept() {
+    let p = make_project();
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            version = "0.1.0"
+
+            [dependencies]
+            heavy = "1.0"
+            deep = "1.0"
+
+            [profile.dev]
+            auto-tune = true
+            opt-level = 1
+            codegen-units = 4
+        "#,
+    );
+    p.cargo("build -Zprofile-auto-tune")
+        .masquerade_as_nightly_cargo(&["profile-auto-tune"])
+        .run();
+    make_heavy(&p);
+    p.cargo("build -v -Zprofile-auto-tune")
+        .masquerade_as_nightly_cargo(&["profile-auto-tune"])
+        .with_stderr_contains("[FRESH] heavy v1.0.0")
+        .run();
+    p.cargo("report auto-tune -Zunstable-options -Zprofile-auto-tune")
+        .masquerade_as_nightly_cargo(&["unstable-options", "profile-auto-tune"])
+        .with_stdout_contains("foo v0.1.0 ([CWD]): `dev` profile (`opt-level` set in the profile)")
+        .with_stdout_contains("heavy v1.0.0: `dev` profile (`opt-level` set in the profile)")
+        .run();
+    p.change_file("src/lib.rs", "// touched");
+    p.cargo("build -v -Zprofile-auto-tune")
+        .masquerade_as_nightly_cargo(&["profile-auto-tune"])
+        .with_stderr_contains(
+            "[RUNNING] `rustc --crate-name foo [..]-C opt-level=1 [..]-C codegen-units=4 [..]",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn optimized_profile_is_kept() {
+    let p = make_project();
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            version = "0.1.0"
+
+            [dependencies]
+            heavy = "1.0"
+            deep = "1.0"
+
+            [profile.release]
+            auto-tune = true
+        "#,
+    );
+    p.cargo("build --release -v -Zprofile-auto-tune")
+        .masquerade_as_nightly_cargo(&["profile-auto-tune"])
+        .with_stderr_contains("[RUNNING] `rustc --crate-name foo [..]-C opt-level=3 [..]")
+        .with_stderr_does_not_contain("[..]opt-level=0[..]")
+        .run();
+    p.cargo("report auto-tune --release -Zunstable-options -Zprofile-auto-tune")
+        .masquerade_as_nightly_cargo(&["unstable-options", "profile-auto-tune"])
+        .with_stdout_contains(
+            "foo v0.1.0 ([CWD]): `release` profile (the profile has `opt-level` 3)",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn report_without_auto_tune() {
+    let p = project().file("src/lib.rs", "").build();
+    p.cargo("report auto-tune -Zunstable-options -Zprofile-auto-tune")
+        .masquerade_as_nightly_cargo(&["unstable-options", "profile-auto-tune"])
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] profile `dev` doesn't set `auto-tune = true`, \
+             or `-Zprofile-auto-tune` wasn't passed",
+        )
+        .run();
+}
@@ -66,6 +66,89 @@ fn rustflags_works_with_zflag() {
         .run();
 }
 
+#[cargo_test]
+fn rustflags_for_a_single_package() {
+    // Toggling the flags of a package rebuilds it and what depends on it,
+    // but not the other packages.
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["foo", "bar", "baz"]
+            "#,
+        )
+        .file(
+            "foo/Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                bar = { path = "../bar" }
+            "#,
+        )
+        .file("foo/src/lib.rs", "pub fn f() { bar::g() }")
+        .file("bar/Cargo.toml", &basic_lib_manifest("bar"))
+        .file(
+            "bar/src/lib.rs",
+            "#[cfg(internal)] pub fn g() {} #[cfg(not(internal))] compile_error!(\"no cfg\");",
+        )
+        .file("baz/Cargo.toml", &basic_lib_manifest("baz"))
+        .file("baz/src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            r#"
+                [profile.dev.package.bar]
+                rustflags = ["--cfg", "internal"]
+            "#,
+        )
+        .build();
+
+    p.cargo("check -v -Zprofile-rustflags")
+        .masquerade_as_nightly_cargo(&["profile-rustflags"])
+        .with_stderr_unordered(
+            "\
+[CHECKING] bar [..]
+[CHECKING] baz [..]
+[CHECKING] foo [..]
+[RUNNING] `rustc --crate-name bar [..] --cfg internal [..]
+[RUNNING] `rustc --crate-name baz [..]
+[RUNNING] `rustc --crate-name foo [..]
+[FINISHED] [..]
+",
+        )
+        .run();
+
+    p.change_file(
+        ".cargo/config.toml",
+        r#"
+            [profile.dev.package.bar]
+            rustflags = ["--cfg", "internal", "-A", "unused"]
+        "#,
+    );
+    p.cargo("check -v -Zprofile-rustflags")
+        .masquerade_as_nightly_cargo(&["profile-rustflags"])
+        .with_stderr_unordered(
+            "\
+[FRESH] baz [..]
+[CHECKING] bar [..]
+[CHECKING] foo [..]
+[RUNNING] `rustc --crate-name bar [..] --cfg internal -A unused [..]
+[RUNNING] `rustc --crate-name foo [..]
+[FINISHED] [..]
+",
+        )
+        .run();
+
+    p.cargo("check --print-flags -Zprofile-rustflags -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["profile-rustflags", "unstable-options"])
+        .with_stdout_contains("bar v0.5.0 ([..]) lib (host) rustc: --cfg internal -A unused")
+        .with_stdout_contains("baz v0.5.0 ([..]) lib (host) rustc:")
+        .run();
+}
+
 #[cargo_test]
 fn profile_config_validate_warnings() {
     let p = project()
@@ -30,6 +30,33 @@ Caused by:
         .run();
 }
 
+#[cargo_test]
+fn bad_progress_config_unknown_string() {
+    let p = project()
+        .file(
+            ".cargo/config",
+            r#"
+            [term]
+            progress = 'unknown'
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] error in [..].cargo/config: \
+could not load config key `term.progress`
+
+Caused by:
+  unknown variant `unknown`, expected one of `auto`, `never`, `plain`, `fancy`
+",
+        )
+        .run();
+}
+
 #[cargo_test]
 fn bad_progress_config_missing_width() {
     let p = project()
@@ -157,3 +184,103 @@ fn never_progress() {
         .with_stderr_does_not_contain("[BUILDING] [..] [..]/4: [..]")
         .run();
 }
+
+fn plain_progress_project() -> cargo_test_support::Project {
+    const N: usize = 3;
+    let mut deps = String::new();
+    for i in 1..=N {
+        Package::new(&format!("dep{}", i), "1.0.0").publish();
+        deps.push_str(&format!("dep{} = \"1.0\"\n", i));
+    }
+
+    project()
+        .file(
+            ".cargo/config",
+            r#"
+            [term]
+            progress = { when = 'always', style = 'plain' }
+            "#,
+        )
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                {}
+                "#,
+                deps
+            ),
+        )
+        .file("src/lib.rs", "")
+        .build()
+}
+
+#[cargo_test]
+fn plain_progress() {
+    let p = plain_progress_project();
+
+    let output = p
+        .cargo("check -Zplain-progress")
+        .masquerade_as_nightly_cargo(&["plain-progress"])
+        .env("__CARGO_TEST_PLAIN_PROGRESS_INTERVAL_MS", "0")
+        .env("TERM", "dumb")
+        .with_stderr_contains("[DOWNLOADED] 3 crates ([..]) in [..]")
+        .with_stderr_contains("[BUILDING] [..]/4: [..]")
+        .exec_with_output()
+        .unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    // Every line is a status, the cursor is never moved back.
+    assert!(!stderr.contains('\r'), "{stderr:?}");
+    assert!(!stderr.contains('\x1b'), "{stderr:?}");
+    for line in stderr.lines() {
+        assert!(!line.trim().is_empty(), "{stderr}");
+    }
+}
+
+#[cargo_test]
+fn plain_progress_is_gated() {
+    let p = plain_progress_project();
+
+    // Without `-Zplain-progress` the bar is drawn.
+    p.cargo("check")
+        .env("__CARGO_TEST_PLAIN_PROGRESS_INTERVAL_MS", "0")
+        .with_status(0)
+        .with_stderr_contains(
+            "[WARNING] the \"plain\" style of `term.progress` is unstable and ignored, \
+             pass `-Zplain-progress` to enable it",
+        )
+        .with_stderr_does_not_contain("[BUILDING] [..]/4: [..]")
+        .run();
+}
+
+#[cargo_test]
+fn progress_string_shorthands() {
+    for progress in ["plain", "fancy"] {
+        let p = project()
+            .file(
+                ".cargo/config",
+                &format!(
+                    r#"
+                    [term]
+                    progress = "{progress}"
+                    "#
+                ),
+            )
+            .file("src/lib.rs", "")
+            .build();
+
+        p.cargo("check -Zplain-progress")
+            .masquerade_as_nightly_cargo(&["plain-progress"])
+            .with_stderr(
+                "\
+[CHECKING] foo v0.0.1 ([CWD])
+[FINISHED] [..]
+",
+            )
+            .run();
+    }
+}
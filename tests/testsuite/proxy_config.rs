@@ -0,0 +1,84 @@
+//! Tests for `-Zproxy-config`.
+
+use cargo_test_support::project;
+use cargo_test_support::registry::{Package, RegistryBuilder};
+
+// Nothing listens on this port, so requests through the proxy fail.
+const DEAD_PROXY: &str = "127.0.0.1:1";
+
+#[cargo_test]
+fn registry_proxy() {
+    let _registry = RegistryBuilder::new().http_index().alternative().build();
+    Package::new("bar", "0.0.1").alternative(true).publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+
+                [dependencies]
+                bar = { version = "0.0.1", registry = "alternative" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("fetch -Zproxy-config")
+        .masquerade_as_nightly_cargo(&["proxy-config"])
+        .env("CARGO_REGISTRIES_ALTERNATIVE_PROXY", DEAD_PROXY)
+        .with_status(101)
+        .with_stderr_contains(
+            "[..]failed to download from `http://127.0.0.1:[..]/index/config.json`",
+        )
+        .with_stderr_contains("  [7] Couldn't connect to server[..]")
+        .run();
+
+    // Hosts listed in `http.no-proxy` are reached directly.
+    p.cargo("fetch -Zproxy-config")
+        .masquerade_as_nightly_cargo(&["proxy-config"])
+        .env("CARGO_REGISTRIES_ALTERNATIVE_PROXY", DEAD_PROXY)
+        .env("CARGO_HTTP_NO_PROXY", "localhost 127.0.0.1")
+        .with_stderr(
+            "\
+[UPDATING] `alternative` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v0.0.1 (registry `alternative`)
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn registry_proxy_requires_flag() {
+    let _registry = RegistryBuilder::new().http_index().alternative().build();
+    Package::new("bar", "0.0.1").alternative(true).publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+
+                [dependencies]
+                bar = { version = "0.0.1", registry = "alternative" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("fetch")
+        .env("CARGO_REGISTRIES_ALTERNATIVE_PROXY", DEAD_PROXY)
+        .with_stderr(
+            "\
+[UPDATING] `alternative` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v0.0.1 (registry `alternative`)
+",
+        )
+        .run();
+}
@@ -2804,6 +2804,50 @@ See [..]
         .run();
 }
 
+#[cargo_test]
+fn wait_timeout_overrides_timeout() {
+    // Intentionally using local registry so the crate never makes it to the index
+    let registry = registry::init();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                authors = []
+                license = "MIT"
+                description = "foo"
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .file(
+            ".cargo/config.toml",
+            "
+                [publish]
+                timeout = 60
+                wait-timeout = 0
+                ",
+        )
+        .build();
+
+    p.cargo("publish --no-verify -Zpublish-timeout")
+        .replace_crates_io(registry.index_url())
+        .masquerade_as_nightly_cargo(&["publish-timeout"])
+        .with_stderr(
+            "\
+[UPDATING] crates.io index
+[WARNING] manifest has no documentation, [..]
+See [..]
+[PACKAGING] foo v0.0.1 ([CWD])
+[PACKAGED] [..] files, [..] ([..] compressed)
+[UPLOADING] foo v0.0.1 ([CWD])
+",
+        )
+        .run();
+}
+
 #[cargo_test]
 fn timeout_waiting_for_publish() {
     // Publish doesn't happen within the timeout window.
@@ -2849,8 +2893,8 @@ See [..]
 [UPLOADED] delay v0.0.1 to registry `crates-io`
 note: Waiting for `delay v0.0.1` to be available at registry `crates-io`.
 You may press ctrl-c to skip waiting; the crate should be available shortly.
-warning: timed out waiting for `delay v0.0.1` to be available in registry `crates-io`
-note: The registry may have a backlog that is delaying making the crate available. The crate should be available soon.
+warning: timed out after 2s waiting for `delay v0.0.1` to be available in registry `crates-io`
+note: The registry may have a backlog that is delaying making the crate available. The crate should be available soon, set `publish.wait-timeout` to wait longer.
 ",
         )
         .run();
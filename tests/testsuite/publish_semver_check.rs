@@ -0,0 +1,158 @@
+//! Tests for `cargo publish --semver-check`.
+
+use cargo_test_support::registry::{Package, RegistryBuilder, TestRegistry};
+use cargo_test_support::{project, Project};
+
+const V0_1_0: &str = r#"
+    pub mod shapes {
+        pub struct Square {
+            pub side: u32,
+        }
+
+        impl Square {
+            pub fn area(&self) -> u32 {
+                self.side * self.side
+            }
+        }
+    }
+
+    pub use shapes::Square;
+
+    pub enum Color {
+        Red,
+        Green,
+    }
+
+    pub fn square(side: u32) -> shapes::Square {
+        shapes::Square { side }
+    }
+"#;
+
+/// Publishes `foo v0.1.0`, returning the registry.
+fn publish_previous() -> TestRegistry {
+    let registry = RegistryBuilder::new().http_api().http_index().build();
+    Package::new("foo", "0.1.0")
+        .file("src/lib.rs", V0_1_0)
+        .publish();
+    registry
+}
+
+fn make_project(version: &str, lib: &str) -> Project {
+    project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                    [package]
+                    name = "foo"
+                    version = "{version}"
+                    license = "MIT"
+                    description = "foo"
+                    documentation = "foo"
+                "#
+            ),
+        )
+        .file("src/lib.rs", lib)
+        .build()
+}
+
+#[cargo_test]
+fn gated() {
+    let registry = publish_previous();
+    let p = make_project("0.1.1", V0_1_0);
+    p.cargo("publish --semver-check --dry-run")
+        .replace_crates_io(registry.index_url())
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] the `--semver-check` flag is unstable, \
+             pass `-Z unstable-options` to enable it",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn no_previous_version() {
+    let registry = RegistryBuilder::new().http_api().http_index().build();
+    let p = make_project("0.1.0", V0_1_0);
+    p.cargo("publish --semver-check --dry-run --no-verify -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .replace_crates_io(registry.index_url())
+        .with_stderr(
+            "\
+[UPDATING] crates.io index
+[PACKAGING] foo v0.1.0 ([CWD])
+[PACKAGED] [..] files, [..] ([..] compressed)
+[UPDATING] crates.io index
+[NOTE] skipping the semver check of `foo`, no previous version is published to registry `crates-io`
+[UPLOADING] foo v0.1.0 ([CWD])
+[WARNING] aborting upload due to dry run
+",
+        )
+        .run();
+}
+
+#[cargo_test(nightly, reason = "rustdoc JSON output is unstable")]
+fn compatible() {
+    let registry = publish_previous();
+    let p = make_project(
+        "0.1.1",
+        &format!("{V0_1_0}\npub fn circle(_radius: u32) {{}}\n"),
+    );
+    p.cargo("publish --semver-check --dry-run --no-verify -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .replace_crates_io(registry.index_url())
+        .with_stderr_contains("[COMPARING] public API of `foo v0.1.1 ([CWD])` with v0.1.0")
+        .with_stderr_contains("[WARNING] aborting upload due to dry run")
+        .run();
+}
+
+#[cargo_test(nightly, reason = "rustdoc JSON output is unstable")]
+fn breaking() {
+    let registry = publish_previous();
+    let lib = V0_1_0
+        .replace("Green,", "Green,\n        Blue,")
+        .replace("pub fn area(&self) -> u32", "pub fn area(&self) -> u64")
+        .replace("\n    pub use shapes::Square;\n", "");
+    let p = make_project("0.1.1", &lib);
+    p.cargo("publish --semver-check --dry-run --no-verify -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .replace_crates_io(registry.index_url())
+        .with_status(101)
+        .with_stderr(
+            "\
+[UPDATING] crates.io index
+[PACKAGING] foo v0.1.1 ([CWD])
+[PACKAGED] [..] files, [..] ([..] compressed)
+[UPDATING] crates.io index
+[DOWNLOADING] crates ...
+[DOWNLOADED] foo v0.1.0
+[COMPARING] public API of `foo v0.1.1 ([CWD])` with v0.1.0
+[UPDATING] crates.io index
+[DOCUMENTING] foo v0.1.0
+[FINISHED] [..]
+[DOCUMENTING] foo v0.1.1 ([CWD])
+[FINISHED] [..]
+[ERROR] the public API of `foo` has breaking changes since v0.1.0, which need a new major version instead of v0.1.1:
+  struct `foo::Square` was removed
+  method `foo::shapes::Square::area` changed
+  variant `foo::Color::Blue` was added
+
+Bump the major version, or publish without `--semver-check` if these changes are expected to be compatible.
+",
+        )
+        .run();
+
+    let p = make_project("0.2.0", &lib);
+    p.cargo("publish --semver-check --dry-run --no-verify -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .replace_crates_io(registry.index_url())
+        .with_stderr_contains(
+            "\
+[NOTE] the public API of `foo` has breaking changes since v0.1.0:
+  struct `foo::Square` was removed
+[..]
+",
+        )
+        .with_stderr_contains("[WARNING] aborting upload due to dry run")
+        .run();
+}
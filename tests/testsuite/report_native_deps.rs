@@ -0,0 +1,125 @@
+//! Tests for `cargo report native-deps`.
+
+use cargo_test_support::project;
+
+fn make_project() -> cargo_test_support::Project {
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                a-sys = { path = "a-sys" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            "a-sys/Cargo.toml",
+            r#"
+                [package]
+                name = "a-sys"
+                version = "0.1.0"
+                links = "a"
+            "#,
+        )
+        .file("a-sys/src/lib.rs", "")
+        .file(
+            "a-sys/build.rs",
+            r#"
+                fn main() {
+                    println!("cargo:rerun-if-env-changed=A_DIR");
+                    println!("cargo:rustc-link-search=native=/opt/a/lib");
+                    println!("cargo:rustc-link-lib=a");
+                    println!("cargo:rustc-link-lib=static=a_extra");
+                    println!("cargo:rustc-cfg=a_v2");
+                }
+            "#,
+        )
+        .build()
+}
+
+#[cargo_test]
+fn gated() {
+    let p = make_project();
+    p.cargo("report native-deps")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] the `cargo report native-deps` command is unstable, pass `-Z unstable-options` to enable it
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn report() {
+    let p = make_project();
+
+    p.cargo("report native-deps -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout("")
+        .with_stderr(
+            "\
+[WARNING] the build scripts of these packages didn't run yet, build the packages to report what they link to:
+  a-sys v0.1.0 ([CWD]/a-sys)
+",
+        )
+        .run();
+
+    p.cargo("check").run();
+
+    p.cargo("report native-deps -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout(
+            "\
+a-sys v0.1.0 ([CWD]/a-sys) links = \"a\"
+  libraries: a, static=a_extra
+  search paths: native=/opt/a/lib
+  environment variables: A_DIR
+  cfgs: a_v2
+",
+        )
+        .with_stderr("")
+        .run();
+}
+
+#[cargo_test]
+fn overridden() {
+    let p = make_project();
+    let target = cargo_test_support::rustc_host();
+    p.change_file(
+        ".cargo/config.toml",
+        &format!(
+            r#"
+                [target.{target}.a]
+                rustc-link-lib = ["a_from_config"]
+                rustc-link-search = ["/usr/local/lib"]
+            "#
+        ),
+    );
+
+    p.cargo("report native-deps -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout(
+            "\
+a-sys v0.1.0 ([CWD]/a-sys) links = \"a\" (overridden in config)
+  libraries: a_from_config
+  search paths: /usr/local/lib
+",
+        )
+        .with_stderr("")
+        .run();
+}
+
+#[cargo_test]
+fn no_native_deps() {
+    let p = project().file("src/lib.rs", "").build();
+    p.cargo("report native-deps -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout("")
+        .with_stderr("[NOTE] no build script links to native libraries")
+        .run();
+}
@@ -0,0 +1,171 @@
+//! Tests for `cargo report notices`.
+
+use cargo_test_support::registry::Package;
+use cargo_test_support::{project, Project};
+
+fn make_project() -> Project {
+    Package::new("baz", "0.1.0").publish();
+    Package::new("bar", "0.1.0")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "bar"
+                version = "0.1.0"
+                license = "MIT OR Apache-2.0"
+
+                [dependencies]
+                baz = "0.1"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("LICENSE-MIT", "MIT text\n")
+        .file("LICENSE-APACHE", "Apache text\n")
+        .dep("baz", "0.1")
+        .publish();
+    Package::new("qux", "0.1.0")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "qux"
+                version = "0.1.0"
+                license-file = "legal/terms.txt"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("legal/terms.txt", "Terms\n")
+        .publish();
+    // Only used while building, so not part of the notices.
+    Package::new("gen", "0.1.0")
+        .file("src/lib.rs", "")
+        .file("LICENSE", "gen")
+        .publish();
+    Package::new("mac", "0.1.0")
+        .proc_macro(true)
+        .file("src/lib.rs", "")
+        .file("LICENSE", "mac")
+        .publish();
+    Package::new("tester", "0.1.0")
+        .file("src/lib.rs", "")
+        .file("LICENSE", "tester")
+        .publish();
+
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                bar = "0.1"
+                qux = "0.1"
+                mac = "0.1"
+
+                [build-dependencies]
+                gen = "0.1"
+
+                [dev-dependencies]
+                tester = "0.1"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("build.rs", "fn main() {}")
+        .file("LICENSE", "foo")
+        .build()
+}
+
+#[cargo_test]
+fn gated() {
+    let p = make_project();
+    p.cargo("report notices")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] the `cargo report notices` command is unstable, pass `-Z unstable-options` to enable it
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn notices() {
+    let p = make_project();
+    p.cargo("report notices -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout(
+            "\
+THIRD-PARTY SOFTWARE NOTICES
+
+================================================================================
+bar v0.1.0
+License: MIT OR Apache-2.0
+--------------------------------------------------------------------------------
+LICENSE-APACHE:
+
+Apache text
+--------------------------------------------------------------------------------
+LICENSE-MIT:
+
+MIT text
+
+================================================================================
+baz v0.1.0
+License: not specified
+
+================================================================================
+qux v0.1.0
+License: not specified
+--------------------------------------------------------------------------------
+legal/terms.txt:
+
+Terms
+",
+        )
+        .with_stderr_contains("[WARNING] no license file found in package `baz v0.1.0`")
+        .run();
+}
+
+#[cargo_test]
+fn json() {
+    let p = make_project();
+    p.cargo("report notices --json -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_json(
+            r#"
+{
+  "packages": [
+    {
+      "id": "bar 0.1.0 (registry+https://github.com/rust-lang/crates.io-index)",
+      "name": "bar",
+      "version": "0.1.0",
+      "license": "MIT OR Apache-2.0",
+      "license_files": [
+        { "path": "LICENSE-APACHE", "text": "Apache text" },
+        { "path": "LICENSE-MIT", "text": "MIT text" }
+      ]
+    },
+    {
+      "id": "baz 0.1.0 (registry+https://github.com/rust-lang/crates.io-index)",
+      "name": "baz",
+      "version": "0.1.0",
+      "license": null,
+      "license_files": []
+    },
+    {
+      "id": "qux 0.1.0 (registry+https://github.com/rust-lang/crates.io-index)",
+      "name": "qux",
+      "version": "0.1.0",
+      "license": null,
+      "license_files": [
+        { "path": "legal/terms.txt", "text": "Terms" }
+      ]
+    }
+  ]
+}
+"#,
+        )
+        .run();
+}
@@ -0,0 +1,93 @@
+//! Tests for `cargo build --report-sizes`.
+
+use cargo_test_support::project;
+
+#[cargo_test]
+fn gated() {
+    let p = project().file("src/main.rs", "fn main() {}").build();
+
+    p.cargo("build --report-sizes")
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] the `--report-sizes` flag is unstable, pass `-Z unstable-options` to enable it",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn binary_and_rlibs() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                bar = { path = "bar" }
+                pm = { path = "pm" }
+            "#,
+        )
+        .file("src/main.rs", "fn main() { bar::bar(); }")
+        .file(
+            "bar/Cargo.toml",
+            r#"
+                [package]
+                name = "bar"
+                version = "0.1.0"
+
+                [dependencies]
+                baz = { path = "../baz" }
+            "#,
+        )
+        .file("bar/src/lib.rs", "pub fn bar() { baz::baz(); }")
+        .file(
+            "baz/Cargo.toml",
+            r#"
+                [package]
+                name = "baz"
+                version = "0.1.0"
+            "#,
+        )
+        .file("baz/src/lib.rs", "pub fn baz() {}")
+        .file(
+            "pm/Cargo.toml",
+            r#"
+                [package]
+                name = "pm"
+                version = "0.1.0"
+
+                [lib]
+                proc-macro = true
+            "#,
+        )
+        .file("pm/src/lib.rs", "")
+        .build();
+
+    // Proc-macros aren't linked into the binary.
+    p.cargo("build --report-sizes -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_contains("[..]B  target/debug/foo[EXE]")
+        .with_stdout_contains("[..]rlibs: [..]bar v0.1.0 [..]")
+        .with_stdout_contains("[..]rlibs: [..]baz v0.1.0 [..]")
+        .with_stdout_does_not_contain("[..]pm v0.1.0[..]")
+        .run();
+
+    // Artifacts are reported when they're fresh too.
+    p.cargo("build --report-sizes -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_contains("[..]B  target/debug/foo[EXE]")
+        .with_stderr("[FINISHED] [..]")
+        .run();
+}
+
+#[cargo_test]
+fn library_only() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("build --report-sizes -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout("")
+        .run();
+}
@@ -0,0 +1,97 @@
+//! Tests for the `-Zresolver-explain` feature.
+
+use cargo_test_support::project;
+use cargo_test_support::registry::Package;
+
+#[cargo_test]
+fn explains_semver_conflict() {
+    Package::new("bar", "1.0.0").publish();
+    Package::new("bar", "1.1.0").publish();
+    Package::new("baz", "1.0.0").dep("bar", "=1.0.0").publish();
+    Package::new("qux", "1.0.0").dep("bar", "^1.1").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                baz = "1.0"
+                qux = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check -Zresolver-explain")
+        .masquerade_as_nightly_cargo(&["resolver-explain"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[UPDATING] [..]
+[ERROR] failed to select a version for `bar`.
+    ... required by package `qux v1.0.0`
+    ... which satisfies dependency `qux = \"^1.0\"` of package `foo v0.1.0 ([CWD])`
+versions that meet the requirements `^1.1` are: 1.1.0
+
+all possible versions conflict with previously selected packages.
+
+  previously selected package `bar v1.0.0`
+    ... which satisfies dependency `bar = \"=1.0.0\"` of package `baz v1.0.0`
+    ... which satisfies dependency `baz = \"^1.0\"` of package `foo v0.1.0 ([CWD])`
+
+explanation:
+  `qux v1.0.0` requires `bar ^1.1`
+  but `baz v1.0.0` requires `bar =1.0.0`, which selected `bar v1.0.0`
+  and both are needed because of `foo v0.1.0 ([CWD]) -> qux v1.0.0 -> bar` and `foo v0.1.0 ([CWD]) -> baz v1.0.0 -> bar`
+  and only one semver-compatible version of `bar` can be selected
+
+failed to select a version for `bar` which could resolve this conflict
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn explanation_requires_flag() {
+    Package::new("bar", "1.0.0").publish();
+    Package::new("bar", "1.1.0").publish();
+    Package::new("baz", "1.0.0").dep("bar", "=1.0.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                bar = "^1.1"
+                baz = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check")
+        .with_status(101)
+        .with_stderr_contains("all possible versions conflict with previously selected packages.")
+        .with_stderr_does_not_contain("explanation:")
+        .run();
+
+    p.cargo("check -Zresolver-explain")
+        .masquerade_as_nightly_cargo(&["resolver-explain"])
+        .with_status(101)
+        .with_stderr_contains(
+            "\
+explanation:
+  `baz v1.0.0` requires `bar =1.0.0`
+  but `foo v0.1.0 ([CWD])` requires `bar ^1.1`, which selected `bar v1.1.0`
+  and both are needed because of `foo v0.1.0 ([CWD]) -> baz v1.0.0 -> bar` and `foo v0.1.0 ([CWD]) -> bar`
+  and only one semver-compatible version of `bar` can be selected
+",
+        )
+        .run();
+}
@@ -1416,6 +1416,178 @@ fn default_run_workspace() {
     p.cargo("run").with_stdout("run-a").run();
 }
 
+#[cargo_test]
+fn workspace_default_run_gated() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["a", "b"]
+                default-run = "b"
+            "#,
+        )
+        .file("a/Cargo.toml", &basic_bin_manifest("a"))
+        .file("a/src/main.rs", "fn main() {}")
+        .file("b/Cargo.toml", &basic_bin_manifest("b"))
+        .file("b/src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("run")
+        .masquerade_as_nightly_cargo(&["workspace-default-targets"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to parse manifest at `[CWD]/Cargo.toml`
+
+Caused by:
+  feature `workspace-default-targets` is required
+[..]
+[..]
+[..]
+[..]
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn workspace_default_run() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["workspace-default-targets"]
+
+                [workspace]
+                members = ["a", "b", "c"]
+                default-members = ["a", "c"]
+                default-run = "b"
+            "#,
+        )
+        .file("a/Cargo.toml", &basic_bin_manifest("a"))
+        .file("a/src/main.rs", r#"fn main() {println!("run-a");}"#)
+        .file("b/Cargo.toml", &basic_bin_manifest("b"))
+        .file("b/src/main.rs", r#"fn main() {println!("run-b");}"#)
+        .file(
+            "c/Cargo.toml",
+            r#"
+                [package]
+                name = "c"
+                version = "0.0.1"
+
+                [[bin]]
+                name = "b"
+                path = "src/main.rs"
+            "#,
+        )
+        .file("c/src/main.rs", r#"fn main() {println!("run-c");}"#)
+        .build();
+
+    // `a` and `c` are the default members, only `c` has a binary named `b`.
+    p.cargo("run")
+        .masquerade_as_nightly_cargo(&["workspace-default-targets"])
+        .with_stdout("run-c")
+        .run();
+
+    // The workspace default doesn't apply with `-p`, or in a member.
+    p.cargo("run -p a")
+        .masquerade_as_nightly_cargo(&["workspace-default-targets"])
+        .with_stdout("run-a")
+        .run();
+    p.cargo("run")
+        .cwd("a")
+        .masquerade_as_nightly_cargo(&["workspace-default-targets"])
+        .with_stdout("run-a")
+        .run();
+
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            cargo-features = ["workspace-default-targets"]
+
+            [workspace]
+            members = ["a", "b", "c"]
+            default-run = "b"
+        "#,
+    );
+    p.cargo("run")
+        .masquerade_as_nightly_cargo(&["workspace-default-targets"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] `workspace.default-run` in `[CWD]/Cargo.toml` is `b`, but several of the selected \
+packages have a binary with that name: b, c
+Use the `-p` option to specify a package.
+",
+        )
+        .run();
+
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            cargo-features = ["workspace-default-targets"]
+
+            [workspace]
+            members = ["a", "b", "c"]
+            default-run = "d"
+        "#,
+    );
+    p.cargo("run")
+        .masquerade_as_nightly_cargo(&["workspace-default-targets"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] `workspace.default-run` in `[CWD]/Cargo.toml` is `d`, but none of the selected \
+packages has a binary with that name
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn conflicting_default_runs() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["a", "b"]
+            "#,
+        )
+        .file(
+            "a/Cargo.toml",
+            r#"
+                [package]
+                name = "a"
+                version = "0.0.1"
+                default-run = "a"
+            "#,
+        )
+        .file("a/src/main.rs", "fn main() {}")
+        .file(
+            "b/Cargo.toml",
+            r#"
+                [package]
+                name = "b"
+                version = "0.0.1"
+                default-run = "b"
+            "#,
+        )
+        .file("b/src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("run")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] `cargo run` could not determine which binary to run[..]
+conflicting `default-run` keys: `a` (package `a`), `b` (package `b`)
+available binaries: a, b",
+        )
+        .run();
+}
+
 #[cargo_test]
 #[cfg(target_os = "macos")]
 fn run_link_system_path_macos() {
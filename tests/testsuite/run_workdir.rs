@@ -0,0 +1,172 @@
+//! Tests for `-Zrun-workdir`.
+
+use cargo_test_support::{basic_manifest, project, Project};
+
+const PRINT_CWD: &str =
+    r#"fn main() { println!("{}", std::env::current_dir().unwrap().display()); }"#;
+
+fn make_project() -> Project {
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["bar"]
+            "#,
+        )
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.1.0"))
+        .file("bar/src/main.rs", PRINT_CWD)
+        .file(
+            "bar/tests/cwd.rs",
+            r#"
+                #[test]
+                fn cwd() {
+                    println!("cwd: {}", std::env::current_dir().unwrap().display());
+                }
+            "#,
+        )
+        .file("assets/.keep", "")
+        .build()
+}
+
+#[cargo_test]
+fn run_workdir() {
+    let p = make_project();
+
+    p.cargo("run")
+        .cwd(p.root().join("assets"))
+        .with_stdout("[ROOT]/foo/assets")
+        .run();
+
+    for (workdir, expected) in [
+        ("cwd", "[ROOT]/foo/assets"),
+        ("package-root", "[ROOT]/foo/bar"),
+        ("workspace-root", "[ROOT]/foo"),
+        (".", "[ROOT]/foo/assets"),
+        ("../bar/src", "[ROOT]/foo/bar/src"),
+    ] {
+        p.cargo("run -Zrun-workdir --workdir")
+            .arg(workdir)
+            .masquerade_as_nightly_cargo(&["run-workdir"])
+            .cwd(p.root().join("assets"))
+            .with_stdout(expected)
+            .run();
+    }
+}
+
+#[cargo_test]
+fn run_workdir_config() {
+    let p = make_project();
+    p.change_file(".cargo/config.toml", "[run]\nworkdir = \"assets\"\n");
+
+    p.cargo("run -Zrun-workdir")
+        .masquerade_as_nightly_cargo(&["run-workdir"])
+        .cwd(p.root().join("bar"))
+        .with_stdout("[ROOT]/foo/assets")
+        .run();
+
+    // The flag takes precedence.
+    p.cargo("run -Zrun-workdir --workdir package-root")
+        .masquerade_as_nightly_cargo(&["run-workdir"])
+        .with_stdout("[ROOT]/foo/bar")
+        .run();
+
+    // The config is ignored without `-Zrun-workdir`.
+    p.cargo("run").with_stdout("[ROOT]/foo").run();
+}
+
+#[cargo_test]
+fn test_workdir() {
+    let p = make_project();
+
+    p.cargo("test --test cwd -- --nocapture")
+        .with_stdout_contains("[..]cwd: [ROOT]/foo/bar")
+        .run();
+
+    p.cargo("test -Zrun-workdir --workdir workspace-root --test cwd -- --nocapture")
+        .masquerade_as_nightly_cargo(&["run-workdir"])
+        .with_stdout_contains("[..]cwd: [ROOT]/foo")
+        .run();
+
+    p.cargo("test -Zrun-workdir --workdir cwd --test cwd -- --nocapture")
+        .masquerade_as_nightly_cargo(&["run-workdir"])
+        .cwd(p.root().join("assets"))
+        .with_stdout_contains("[..]cwd: [ROOT]/foo/assets")
+        .run();
+
+    // The config applies to the tests like to `cargo run`.
+    p.change_file(".cargo/config.toml", "[run]\nworkdir = \"assets\"\n");
+    p.cargo("test -Zrun-workdir --test cwd -- --nocapture")
+        .masquerade_as_nightly_cargo(&["run-workdir"])
+        .with_stdout_contains("[..]cwd: [ROOT]/foo/assets")
+        .run();
+}
+
+#[cargo_test]
+fn bench_workdir() {
+    let p = make_project();
+    p.change_file(
+        "bar/Cargo.toml",
+        r#"
+            [package]
+            name = "bar"
+            version = "0.1.0"
+
+            [[bench]]
+            name = "cwd"
+            harness = false
+        "#,
+    );
+    p.change_file("bar/benches/cwd.rs", PRINT_CWD);
+
+    p.cargo("bench --bench cwd")
+        .with_stdout_contains("[ROOT]/foo/bar")
+        .run();
+
+    p.cargo("bench -Zrun-workdir --workdir workspace-root --bench cwd")
+        .masquerade_as_nightly_cargo(&["run-workdir"])
+        .with_stdout_contains("[ROOT]/foo")
+        .run();
+}
+
+#[cargo_test]
+fn json_message() {
+    let p = make_project();
+
+    p.cargo("run -Zrun-workdir --workdir workspace-root --message-format json")
+        .masquerade_as_nightly_cargo(&["run-workdir"])
+        .with_json_contains_unordered(
+            r#"
+                {
+                    "reason": "run",
+                    "package_id": "bar 0.1.0 [..]",
+                    "manifest_path": "[..]/foo/bar/Cargo.toml",
+                    "target": "{...}",
+                    "executable": "[..]/foo/target/debug/bar[EXE]",
+                    "cwd": "[..]/foo"
+                }
+            "#,
+        )
+        .run();
+}
+
+#[cargo_test]
+fn missing_workdir() {
+    let p = make_project();
+
+    p.cargo("run -Zrun-workdir --workdir missing")
+        .masquerade_as_nightly_cargo(&["run-workdir"])
+        .with_status(101)
+        .with_stderr_contains("[ERROR] the working directory `[ROOT]/foo/missing` does not exist")
+        .run();
+}
+
+#[cargo_test]
+fn gated() {
+    let p = make_project();
+
+    p.cargo("run --workdir package-root")
+        .with_status(101)
+        .with_stderr("[ERROR] the `--workdir` flag is unstable, pass `-Z run-workdir` to enable it")
+        .run();
+}
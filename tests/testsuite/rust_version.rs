@@ -1,6 +1,6 @@
 //! Tests for targets with `rust-version`.
 
-use cargo_test_support::{project, registry::Package};
+use cargo_test_support::{basic_manifest, project, registry::Package};
 
 #[cargo_test]
 fn rust_version_satisfied() {
@@ -130,6 +130,68 @@ fn rust_version_too_high() {
     p.cargo("check --ignore-rust-version").run();
 }
 
+#[cargo_test]
+fn rust_version_too_high_in_workspace() {
+    Package::new("bar", "1.0.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [workspace]
+            members = ["a", "b"]
+
+            [workspace.package]
+            rust-version = "1.9876.0"
+        "#,
+        )
+        .file("a/Cargo.toml", &basic_manifest("a", "0.1.0"))
+        .file("a/src/lib.rs", "")
+        .file(
+            "b/Cargo.toml",
+            r#"
+            [package]
+            name = "b"
+            version = "0.1.0"
+            rust-version.workspace = true
+
+            [dependencies]
+            bar = "2.0"
+        "#,
+        )
+        .file("b/src/lib.rs", "")
+        .file("rust-toolchain.toml", "[toolchain]\nchannel = \"1.0.0\"\n")
+        .build();
+
+    // The members are checked before resolving their dependencies.
+    p.cargo("build -Zmsrv-policy")
+        .masquerade_as_nightly_cargo(&["msrv-policy"])
+        .env_remove("RUSTUP_TOOLCHAIN")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] package `b v0.1.0 ([CWD]/b)` cannot be built because it requires \
+rustc 1.9876.0 or newer, while the currently active rustc version is [..]
+`rust-version` is set by workspace member `b` in `[CWD]/b/Cargo.toml`
+the active toolchain may be selected by `[CWD]/rust-toolchain.toml`
+Either upgrade to rustc 1.9876.0 or newer, or pass `--ignore-rust-version` to build anyway
+",
+        )
+        .run();
+
+    p.cargo("build --ignore-rust-version -Zmsrv-policy")
+        .masquerade_as_nightly_cargo(&["msrv-policy"])
+        .env_remove("RUSTUP_TOOLCHAIN")
+        .with_status(101)
+        .with_stderr_contains(
+            "[WARNING] ignoring `rust-version = \"1.9876.0\"` of package `b v0.1.0 ([CWD]/b)`, \
+             the currently active rustc version is [..]",
+        )
+        .with_stderr_contains(
+            "[ERROR] failed to select a version for the requirement `bar = \"^2.0\"`",
+        )
+        .run();
+}
+
 #[cargo_test]
 fn rust_version_dependency_fails() {
     Package::new("bar", "0.0.1")
@@ -1,5 +1,7 @@
 //! Tests for the `cargo rustdoc` command.
 
+use std::path::Path;
+
 use cargo_test_support::{basic_manifest, cross_compile, project};
 
 #[cargo_test]
@@ -250,3 +252,127 @@ fn fail_with_glob() {
         .with_stderr("[ERROR] Glob patterns on package selection are not supported.")
         .run();
 }
+
+#[cargo_test(nightly, reason = "rustdoc JSON output is unstable")]
+fn output_format_json_workspace() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["a", "b", "c"]
+            "#,
+        )
+        .file("a/Cargo.toml", &basic_manifest("a", "0.1.0"))
+        .file("a/src/lib.rs", "pub fn a() {}")
+        .file(
+            "b/Cargo.toml",
+            r#"
+                [package]
+                name = "b"
+                version = "0.2.0"
+
+                [lib]
+                name = "a"
+            "#,
+        )
+        .file("b/src/lib.rs", "pub fn b() {}")
+        .file("c/Cargo.toml", &basic_manifest("c", "0.3.0"))
+        .file("c/src/lib.rs", "pub fn c() {}")
+        .build();
+
+    p.cargo("rustdoc --workspace --exclude c --output-format json -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_unordered(
+            "\
+[DOCUMENTING] a v0.1.0 ([CWD]/a)
+[DOCUMENTING] b v0.2.0 ([CWD]/b)
+[FINISHED] [..]
+   Generated [CWD]/target/doc/json-index.json
+",
+        )
+        .run();
+    assert!(!p.root().join("target/doc/a/index.html").exists());
+
+    // Both crates are named `a`, and each has its own file.
+    let index = std::fs::read_to_string(p.root().join("target/doc/json-index.json")).unwrap();
+    let index: serde_json::Value = serde_json::from_str(&index).unwrap();
+    let crates = index["crates"].as_array().unwrap();
+    assert_eq!(crates.len(), 2);
+    assert_eq!(crates[0]["crate_name"], "a");
+    assert_eq!(crates[0]["version"], "0.1.0");
+    assert_eq!(crates[1]["crate_name"], "a");
+    assert_eq!(crates[1]["name"], "b");
+    let paths: Vec<_> = crates
+        .iter()
+        .map(|krate| Path::new(krate["path"].as_str().unwrap()))
+        .collect();
+    assert_ne!(paths[0], paths[1]);
+    for path in paths {
+        assert_eq!(path.parent().unwrap(), p.root().join("target/doc"));
+        let name = path.file_name().unwrap().to_str().unwrap();
+        assert!(name.starts_with("a-") && name.ends_with(".json"), "{name}");
+        let json = std::fs::read_to_string(path).unwrap();
+        serde_json::from_str::<serde_json::Value>(&json).unwrap();
+    }
+
+    p.cargo("rustdoc --workspace --exclude c --output-format json -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr(
+            "\
+[FINISHED] [..]
+   Generated [CWD]/target/doc/json-index.json
+",
+        )
+        .run();
+
+    // Switching back to HTML documents the crate again.
+    p.cargo("rustdoc -p a")
+        .with_stderr(
+            "\
+[DOCUMENTING] a v0.1.0 ([CWD]/a)
+[FINISHED] [..]
+",
+        )
+        .run();
+    assert!(p.root().join("target/doc/a/index.html").is_file());
+}
+
+#[cargo_test]
+fn output_format_requires_unstable_options() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("rustdoc --output-format json")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] the `--output-format` flag is unstable, pass `-Z unstable-options` to enable it
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn workspace_requires_output_format_json() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("rustdoc --workspace -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr("[ERROR] `--workspace` can only be used with `--output-format json`")
+        .run();
+
+    p.cargo("rustdoc --workspace --output-format json -Zunstable-options -- --cfg=foo")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] extra rustdoc flags can only be passed when documenting a single package",
+        )
+        .run();
+
+    p.cargo("rustdoc --open --output-format json -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr("[ERROR] `--open` can't be used with `--output-format json`")
+        .run();
+}
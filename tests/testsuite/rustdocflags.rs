@@ -153,3 +153,130 @@ fn not_affected_by_target_rustflags() {
         .with_stderr_contains("[RUNNING] `rustdoc [..] --cfg foo[..]`")
         .run();
 }
+
+#[cargo_test]
+fn target_rustdocflags() {
+    let host = cargo_test_support::rustc_host();
+    let cfg = if cfg!(windows) { "windows" } else { "unix" };
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            &format!(
+                r#"
+                    [target.{host}]
+                    rustdocflags = ["--cfg", "from_triple"]
+
+                    [target.'cfg({cfg})']
+                    rustdocflags = ["--cfg", "from_cfg"]
+
+                    [build]
+                    rustdocflags = ["--cfg", "from_build"]
+                "#,
+            ),
+        )
+        .build();
+
+    // `target.<cfg>.rustdocflags` is unstable.
+    p.cargo("doc -v --target")
+        .arg(host)
+        .with_stderr_contains("[RUNNING] `rustdoc [..] --cfg from_triple[..]`")
+        .with_stderr_does_not_contain("[..]from_cfg[..]")
+        .with_stderr_does_not_contain("[..]from_build[..]")
+        .run();
+
+    p.change_file("src/lib.rs", "// touched");
+    p.cargo("doc -v -Ztarget-cfg-rustdocflags --target")
+        .arg(host)
+        .masquerade_as_nightly_cargo(&["target-cfg-rustdocflags"])
+        .with_stderr_contains("[RUNNING] `rustdoc [..] --cfg from_triple --cfg from_cfg[..]`")
+        .with_stderr_does_not_contain("[..]from_build[..]")
+        .run();
+
+    // The environment still takes precedence.
+    p.cargo("doc -v --target")
+        .arg(host)
+        .env("RUSTDOCFLAGS", "--cfg=from_env")
+        .with_stderr_contains("[RUNNING] `rustdoc [..] --cfg=from_env[..]`")
+        .with_stderr_does_not_contain("[..]from_triple[..]")
+        .run();
+}
+
+#[cargo_test]
+fn profile_rustdocflags() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            r#"
+                //! ```
+                //! assert!(cfg!(from_profile));
+                //! ```
+            "#,
+        )
+        .file(
+            ".cargo/config.toml",
+            r#"
+                [profile.dev]
+                rustdocflags = ["--cfg", "from_profile"]
+            "#,
+        )
+        .build();
+
+    p.cargo("doc")
+        .masquerade_as_nightly_cargo(&["profile-rustflags"])
+        .with_status(101)
+        .with_stderr_contains("[..]feature `profile-rustflags` is required[..]")
+        .run();
+
+    p.cargo("doc -v -Zprofile-rustflags")
+        .masquerade_as_nightly_cargo(&["profile-rustflags"])
+        .env("RUSTDOCFLAGS", "--cfg=from_env")
+        .with_stderr_contains("[RUNNING] `rustdoc [..] --cfg from_profile [..]--cfg=from_env[..]`")
+        .run();
+
+    p.cargo("test --doc -Zprofile-rustflags")
+        .masquerade_as_nightly_cargo(&["profile-rustflags"])
+        .run();
+
+    // The flags aren't passed to rustc.
+    p.cargo("check -v -Zprofile-rustflags")
+        .masquerade_as_nightly_cargo(&["profile-rustflags"])
+        .with_stderr_does_not_contain("[..]from_profile[..]")
+        .run();
+}
+
+#[cargo_test]
+fn profile_rustdocflags_only_rebuild_docs() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("check -Zprofile-rustflags")
+        .masquerade_as_nightly_cargo(&["profile-rustflags"])
+        .run();
+    p.cargo("doc -Zprofile-rustflags")
+        .masquerade_as_nightly_cargo(&["profile-rustflags"])
+        .run();
+
+    p.change_file(
+        ".cargo/config.toml",
+        r#"
+            [profile.dev]
+            rustdocflags = ["--cfg", "from_profile"]
+        "#,
+    );
+    p.cargo("check -Zprofile-rustflags")
+        .masquerade_as_nightly_cargo(&["profile-rustflags"])
+        .with_stderr(
+            "\
+[FINISHED] [..]
+",
+        )
+        .run();
+    p.cargo("doc -v -Zprofile-rustflags")
+        .masquerade_as_nightly_cargo(&["profile-rustflags"])
+        .with_stderr_contains("[RUNNING] `rustdoc [..] --cfg from_profile [..]`")
+        .run();
+    p.cargo("doc -v -Zprofile-rustflags")
+        .masquerade_as_nightly_cargo(&["profile-rustflags"])
+        .with_stderr_contains("[FRESH] foo v0.0.1 ([CWD])")
+        .run();
+}
@@ -1671,3 +1671,193 @@ fn host_config_rustflags_with_target() {
         .arg("host.rustflags=[\"--cfg=foo\"]")
         .run();
 }
+
+#[cargo_test]
+fn rustflags_append_and_prepend() {
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            r#"
+            [build]
+            rustflags = ["--cfg", "from_build"]
+            rustflags-prepend = ["--cfg", "prepended"]
+            rustflags-append = ["--cfg", "appended"]
+            "#,
+        )
+        .build();
+
+    // Without the flag, the keys are ignored.
+    p.cargo("check -v")
+        .with_stderr_contains("[RUNNING] `rustc [..]--cfg from_build`")
+        .run();
+
+    // `RUSTFLAGS` replaces `build.rustflags`, but not the appended flags.
+    p.cargo("check -v -Zrustflags-append")
+        .masquerade_as_nightly_cargo(&["rustflags-append"])
+        .env("RUSTFLAGS", "--cfg from_env")
+        .with_stderr_contains("[RUNNING] `rustc [..]--cfg prepended --cfg from_env --cfg appended`")
+        .run();
+}
+
+#[cargo_test]
+fn rustflags_dedup() {
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            r#"
+            [build]
+            rustflags-append = ["--cfg", "a", "-C", "debug-assertions=off"]
+            "#,
+        )
+        .build();
+
+    p.cargo("check -v -Zrustflags-append")
+        .masquerade_as_nightly_cargo(&["rustflags-append"])
+        .env(
+            "RUSTFLAGS",
+            "-C debug-assertions=off --cfg a --cfg b -C debug-assertions=on",
+        )
+        .with_stderr_contains(
+            "[RUNNING] `rustc [..]--cfg b -C debug-assertions=on --cfg a -C debug-assertions=off`",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn print_flags() {
+    let p = project()
+        .file("src/lib.rs", "")
+        .file("build.rs", "fn main() {}")
+        .build();
+
+    p.cargo("check --print-flags")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] the `--print-flags` flag is unstable, pass `-Z unstable-options` to enable it
+",
+        )
+        .run();
+
+    p.cargo("check --print-flags -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .env("RUSTFLAGS", "--cfg foo")
+        .with_stdout(
+            "\
+foo v0.0.1 ([CWD]) lib (host) rustc: --cfg foo
+foo v0.0.1 ([CWD]) build script (host) rustc: --cfg foo
+",
+        )
+        .with_stderr("")
+        .run();
+}
+
+#[cargo_test]
+fn target_overrides() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["foo", "bar", "baz"]
+            "#,
+        )
+        .file(
+            "foo/Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                bar = { path = "../bar" }
+            "#,
+        )
+        .file("foo/src/lib.rs", "pub fn f() { bar::g() }")
+        .file("bar/Cargo.toml", &basic_lib_manifest("bar"))
+        .file(
+            "bar/src/lib.rs",
+            "#[cfg(internal)] pub fn g() {} #[cfg(not(internal))] compile_error!(\"no cfg\");",
+        )
+        .file("baz/Cargo.toml", &basic_lib_manifest("baz"))
+        .file("baz/src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            r#"
+                [target-overrides.bar]
+                cfgs = ["internal"]
+            "#,
+        )
+        .build();
+
+    // Ignored without the flag.
+    p.cargo("check -p bar")
+        .with_status(101)
+        .with_stderr_contains("[ERROR] no cfg")
+        .run();
+
+    p.cargo("check -v -Ztarget-overrides")
+        .masquerade_as_nightly_cargo(&["target-overrides"])
+        .with_stderr_unordered(
+            "\
+[CHECKING] bar [..]
+[CHECKING] baz [..]
+[CHECKING] foo [..]
+[RUNNING] `rustc --crate-name bar [..] --cfg internal [..]
+[RUNNING] `rustc --crate-name baz [..]
+[RUNNING] `rustc --crate-name foo [..]
+[FINISHED] [..]
+",
+        )
+        .with_stderr_does_not_contain("[RUNNING] `rustc --crate-name baz [..]--cfg internal[..]")
+        .with_stderr_does_not_contain("[RUNNING] `rustc --crate-name foo [..]--cfg internal[..]")
+        .run();
+
+    // Changing the flags only rebuilds `bar` and what depends on it.
+    p.change_file(
+        ".cargo/config.toml",
+        r#"
+            [target-overrides.bar]
+            rustflags = ["-A", "unused"]
+            cfgs = ["internal"]
+        "#,
+    );
+    p.cargo("check -v -Ztarget-overrides")
+        .masquerade_as_nightly_cargo(&["target-overrides"])
+        .with_stderr_unordered(
+            "\
+[FRESH] baz [..]
+[DIRTY] bar [..]
+[CHECKING] bar [..]
+[DIRTY] foo [..]: dependency info changed
+[CHECKING] foo [..]
+[RUNNING] `rustc --crate-name bar [..] -A unused --cfg internal [..]
+[RUNNING] `rustc --crate-name foo [..]
+[FINISHED] [..]
+",
+        )
+        .run();
+
+    p.cargo("check --print-flags -Ztarget-overrides -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["target-overrides", "unstable-options"])
+        .with_stdout_contains("bar v0.5.0 ([..]) lib (host) rustc: -A unused --cfg internal")
+        .with_stdout_contains("baz v0.5.0 ([..]) lib (host) rustc:")
+        .run();
+
+    p.change_file(
+        ".cargo/config.toml",
+        r#"
+            [target-overrides."bar@@1"]
+            cfgs = ["internal"]
+        "#,
+    );
+    p.cargo("check -Ztarget-overrides")
+        .masquerade_as_nightly_cargo(&["target-overrides"])
+        .with_status(101)
+        .with_stderr_contains(
+            "[ERROR] invalid package ID specification in `target-overrides.bar@@1`",
+        )
+        .run();
+}
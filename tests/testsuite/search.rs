@@ -4,6 +4,7 @@ use cargo_test_support::cargo_process;
 use cargo_test_support::paths;
 use cargo_test_support::registry::{RegistryBuilder, Response};
 use std::collections::HashSet;
+use std::fs;
 
 const SEARCH_API_RESPONSE: &[u8] = br#"
 {
@@ -168,6 +169,51 @@ fn colored_results() {
         .run();
 }
 
+#[cargo_test]
+fn stream_colors() {
+    let registry = setup().build();
+    let config = paths::home().join(".cargo/config");
+    let original = fs::read_to_string(&config).unwrap();
+    fs::write(
+        &config,
+        format!("{original}\n[term]\ncolor = {{ stdout = \"always\", stderr = \"never\" }}\n"),
+    )
+    .unwrap();
+
+    cargo_process("search postgres")
+        .replace_crates_io(registry.index_url())
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] setting `term.color` to a table is unstable, pass `-Zstream-color` to enable it
+",
+        )
+        .run();
+
+    cargo_process("search postgres -Zstream-color")
+        .masquerade_as_nightly_cargo(&["stream-color"])
+        .replace_crates_io(registry.index_url())
+        .with_stdout_contains("[..]\x1b[[..]")
+        .run();
+
+    // `--color` applies to both streams.
+    cargo_process("search postgres -Zstream-color --color never")
+        .masquerade_as_nightly_cargo(&["stream-color"])
+        .replace_crates_io(registry.index_url())
+        .with_stdout_does_not_contain("[..]\x1b[[..]")
+        .run();
+
+    // The keys can be set with environment variables.
+    fs::write(&config, original).unwrap();
+    cargo_process("search postgres -Zstream-color")
+        .masquerade_as_nightly_cargo(&["stream-color"])
+        .env("CARGO_TERM_COLOR_STDOUT", "always")
+        .env("CARGO_TERM_COLOR_STDERR", "never")
+        .replace_crates_io(registry.index_url())
+        .with_stdout_contains("[..]\x1b[[..]")
+        .run();
+}
+
 #[cargo_test]
 fn auth_required_failure() {
     let server = setup().auth_required().no_configure_token().build();
@@ -190,3 +236,98 @@ fn auth_required() {
         .with_stdout_contains(SEARCH_RESULTS)
         .run();
 }
+
+#[cargo_test]
+fn verbose_details() {
+    let registry = setup().build();
+
+    cargo_process("search postgres -v")
+        .replace_crates_io(registry.index_url())
+        .with_stdout_contains(
+            "\
+hoare = \"0.1.1\"        # Design by contract style assertions for Rust (2 downloads)
+postgres = \"0.17.3\"    # A native, synchronous PostgreSQL client (535491 downloads, 88321 recent downloads)
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn json() {
+    let registry = setup().build();
+
+    cargo_process("search postgres --json -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .replace_crates_io(registry.index_url())
+        .with_json(
+            r#"
+{
+  "crates": [
+    {
+      "name": "hoare",
+      "version": "0.1.1",
+      "description": "Design by contract style assertions for Rust",
+      "downloads": 2,
+      "recent_downloads": null,
+      "rust_version": null
+    },
+    {
+      "name": "postgres",
+      "version": "0.17.3",
+      "description": "A native, synchronous PostgreSQL client",
+      "downloads": 535491,
+      "recent_downloads": 88321,
+      "rust_version": null
+    }
+  ],
+  "total": 2
+}
+"#,
+        )
+        .run();
+
+    cargo_process("search postgres --json")
+        .replace_crates_io(registry.index_url())
+        .with_status(101)
+        .with_stderr_contains(
+            "[ERROR] the `--json` flag is unstable, pass `-Z unstable-options` to enable it",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn page() {
+    let registry = RegistryBuilder::new()
+        .http_api()
+        .add_responder("/api/v1/crates", |req, _| {
+            assert_eq!(req.url.query(), Some("q=postgres&per_page=1&page=2"));
+            Response {
+                code: 200,
+                headers: vec![],
+                body: br#"{
+                    "crates": [{"name": "postgres", "max_version": "0.17.3", "description": null}],
+                    "meta": {"total": 3}
+                }"#
+                .to_vec(),
+            }
+        })
+        .build();
+
+    cargo_process("search postgres --limit 1 --page 2 -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .replace_crates_io(registry.index_url())
+        .with_stdout(
+            "\
+postgres = \"0.17.3\"
+... and 1 crates more (use --limit N to see more)
+",
+        )
+        .run();
+
+    cargo_process("search postgres --page 0 -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .replace_crates_io(registry.index_url())
+        .with_status(101)
+        .with_stderr("[ERROR] `--page` starts at 1")
+        .run();
+}
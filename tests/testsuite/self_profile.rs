@@ -0,0 +1,88 @@
+//! Tests for `-Zself-profile`.
+
+use cargo_test_support::{basic_manifest, project};
+
+#[cargo_test]
+fn saves_profile() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                bar = { path = "bar" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.1.0"))
+        .file("bar/src/lib.rs", "")
+        .build();
+
+    p.cargo("check -Zself-profile")
+        .masquerade_as_nightly_cargo(&["self-profile"])
+        .with_stderr(
+            "\
+[CHECKING] bar v0.1.0 ([..])
+[CHECKING] foo v0.1.0 ([..])
+[FINISHED] [..]
+     Profile saved to [CWD]/target/cargo-self-profile/cargo-self-profile-[..].json
+",
+        )
+        .run();
+
+    let profile = p.read_file("target/cargo-self-profile/cargo-self-profile.json");
+    let profile: serde_json::Value = serde_json::from_str(&profile).unwrap();
+    let events = profile["traceEvents"].as_array().unwrap();
+    let names: Vec<_> = events
+        .iter()
+        .map(|event| event["name"].as_str().unwrap())
+        .collect();
+    for name in [
+        "loading config",
+        "loading workspace",
+        "resolving",
+        "building unit graph",
+        "executing the job graph",
+        "compiling",
+    ] {
+        assert!(names.contains(&name), "`{name}` not found in {names:?}");
+    }
+    assert!(
+        names
+            .iter()
+            .any(|name| name.starts_with("fingerprint: foo")),
+        "no fingerprint in {names:?}"
+    );
+    for event in events {
+        assert_eq!(event["ph"], "X");
+        assert!(event["ts"].is_u64() && event["dur"].is_u64() && event["tid"].is_u64());
+    }
+}
+
+#[cargo_test]
+fn gated() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("check")
+        .with_stderr_does_not_contain("[..]Profile[..]")
+        .run();
+    assert!(!p.root().join("target/cargo-self-profile").exists());
+}
+
+#[cargo_test]
+fn save_failure_keeps_build_error() {
+    let p = project()
+        .file("src/lib.rs", "invalid rust code")
+        .file("target/cargo-self-profile", "not a directory")
+        .build();
+
+    p.cargo("check -Zself-profile")
+        .masquerade_as_nightly_cargo(&["self-profile"])
+        .with_status(101)
+        .with_stderr_contains("[ERROR] could not compile `foo` [..]")
+        .with_stderr_contains("[WARNING] failed to save self-profile")
+        .run();
+}
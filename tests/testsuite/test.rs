@@ -3,7 +3,7 @@
 use cargo_test_support::paths::CargoPathExt;
 use cargo_test_support::registry::Package;
 use cargo_test_support::{
-    basic_bin_manifest, basic_lib_manifest, basic_manifest, cargo_exe, project,
+    basic_bin_manifest, basic_lib_manifest, basic_manifest, cargo_exe, project, Project,
 };
 use cargo_test_support::{cross_compile, paths};
 use cargo_test_support::{rustc_host, rustc_host_env, sleep_ms};
@@ -3989,6 +3989,188 @@ fn json_artifact_includes_executable_for_integration_tests() {
         .run();
 }
 
+#[cargo_test]
+fn json_test_binary_messages() {
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "tests/integration_test.rs",
+            r#"#[test] fn integration_test() {}"#,
+        )
+        .build();
+
+    p.cargo("test --no-run --message-format=json --test integration_test -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_json(
+            r#"
+                {
+                    "reason": "compiler-artifact",
+                    "package_id": "foo 0.0.1 ([..])",
+                    "manifest_path": "[..]",
+                    "target": "{...}",
+                    "profile": "{...}",
+                    "features": [],
+                    "filenames": "{...}",
+                    "executable": null,
+                    "fresh": false
+                }
+
+                {
+                    "reason": "compiler-artifact",
+                    "package_id": "foo 0.0.1 ([..])",
+                    "manifest_path": "[..]",
+                    "target": "{...}",
+                    "profile": "{...}",
+                    "features": [],
+                    "filenames": "{...}",
+                    "executable": "[..]/foo/target/debug/deps/integration_test-[..][EXE]",
+                    "fresh": false
+                }
+
+                {"reason": "build-finished", "success": true}
+
+                {
+                    "reason": "test-binary",
+                    "package_id": "foo 0.0.1 ([..])",
+                    "manifest_path": "[..]/foo/Cargo.toml",
+                    "target": {
+                        "crate_types": [ "bin" ],
+                        "kind": [ "test" ],
+                        "doc": false,
+                        "doctest": false,
+                        "edition": "2015",
+                        "name": "integration_test",
+                        "src_path": "[..]/foo/tests/integration_test.rs",
+                        "test": true
+                    },
+                    "kind": "test",
+                    "harness": true,
+                    "executable": "[..]/foo/target/debug/deps/integration_test-[..][EXE]",
+                    "command": ["[..]/foo/target/debug/deps/integration_test-[..][EXE]"],
+                    "cwd": "[..]/foo",
+                    "env": "{...}"
+                }
+            "#,
+        )
+        .run();
+}
+
+#[cargo_test]
+fn json_test_binary_env_only_has_cargo_vars() {
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "build.rs",
+            r#"fn main() { println!("cargo:rustc-env=BUILD_SECRET=from-build-script"); }"#,
+        )
+        .file(
+            ".cargo/config.toml",
+            r#"
+                [env]
+                CONFIG_SECRET = "from-config"
+            "#,
+        )
+        .build();
+
+    p.cargo("test --no-run --message-format=json --lib -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_contains(
+            "{\"reason\":\"test-binary\",[..]\"CARGO_MANIFEST_DIR\":\"[..]/foo\"[..]",
+        )
+        .with_stdout_does_not_contain("[..]from-config[..]")
+        .with_stdout_does_not_contain("{\"reason\":\"test-binary\",[..]from-build-script[..]")
+        .run();
+}
+
+#[cargo_test]
+fn list_binaries() {
+    let p = project()
+        .file("src/lib.rs", "")
+        .file("src/main.rs", "fn main() {}")
+        .file(
+            "tests/integration_test.rs",
+            r#"#[test] fn integration_test() {}"#,
+        )
+        .build();
+
+    p.cargo("test --list-binaries -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr(
+            "\
+[COMPILING] foo v0.0.1 ([CWD])
+[FINISHED] test [unoptimized + debuginfo] target(s) in [..]
+",
+        )
+        .with_json_contains_unordered(
+            r#"
+                {
+                    "reason": "test-binary",
+                    "package_id": "foo 0.0.1 ([..])",
+                    "manifest_path": "[..]/foo/Cargo.toml",
+                    "target": {
+                        "crate_types": [ "lib" ],
+                        "kind": [ "lib" ],
+                        "doc": true,
+                        "doctest": true,
+                        "edition": "2015",
+                        "name": "foo",
+                        "src_path": "[..]/foo/src/lib.rs",
+                        "test": true
+                    },
+                    "kind": "test",
+                    "harness": true,
+                    "executable": "[..]/foo/target/debug/deps/foo-[..][EXE]",
+                    "command": ["[..]/foo/target/debug/deps/foo-[..][EXE]"],
+                    "cwd": "[..]/foo",
+                    "env": "{...}"
+                }
+
+                {
+                    "reason": "test-binary",
+                    "package_id": "foo 0.0.1 ([..])",
+                    "manifest_path": "[..]/foo/Cargo.toml",
+                    "target": "{...}",
+                    "kind": "test",
+                    "harness": true,
+                    "executable": "[..]/foo/target/debug/deps/foo-[..][EXE]",
+                    "command": ["[..]/foo/target/debug/deps/foo-[..][EXE]"],
+                    "cwd": "[..]/foo",
+                    "env": "{...}"
+                }
+
+                {
+                    "reason": "test-binary",
+                    "package_id": "foo 0.0.1 ([..])",
+                    "manifest_path": "[..]/foo/Cargo.toml",
+                    "target": "{...}",
+                    "kind": "test",
+                    "harness": true,
+                    "executable": "[..]/foo/target/debug/deps/integration_test-[..][EXE]",
+                    "command": ["[..]/foo/target/debug/deps/integration_test-[..][EXE]"],
+                    "cwd": "[..]/foo",
+                    "env": "{...}"
+                }
+            "#,
+        )
+        .run();
+
+    p.cargo("test --list-binaries")
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] the `--list-binaries` flag is unstable, \
+             pass `-Z unstable-options` to enable it",
+        )
+        .run();
+    p.cargo("test --list-binaries --doc -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] Can't mix --doc with --list-binaries, \
+             doctests aren't compiled into test binaries",
+        )
+        .run();
+}
+
 #[cargo_test]
 fn test_build_script_links() {
     let p = project()
@@ -4154,7 +4336,189 @@ fn can_not_no_run_doc_tests() {
 
     p.cargo("test --doc --no-run")
         .with_status(101)
-        .with_stderr("[ERROR] Can't skip running doc tests with --no-run")
+        .with_stderr(
+            "[ERROR] the `--no-run` flag with `--doc` is unstable, \
+             pass `-Z unstable-options` to enable it",
+        )
+        .run();
+}
+
+#[cargo_test(nightly, reason = "rustdoc --no-run is unstable")]
+fn no_run_doc_tests() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            r#"
+            /// ```
+            /// panic!("doctests shouldn't run");
+            /// ```
+            pub fn foo() -> u8 { 1 }
+            "#,
+        )
+        .build();
+
+    p.cargo("test --doc --no-run -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr(
+            "\
+[COMPILING] foo v0.0.1 ([CWD])
+[FINISHED] test [unoptimized + debuginfo] target(s) in [..]
+[DOCTEST] foo",
+        )
+        .with_stdout_does_not_contain("[..]doctests shouldn't run[..]")
+        .run();
+
+    p.change_file(
+        "src/lib.rs",
+        r#"
+        /// ```
+        /// let _x: u8 = "foo";
+        /// ```
+        pub fn foo() -> u8 { 1 }
+        "#,
+    );
+    p.cargo("test --doc --no-run -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stdout_contains("[..]mismatched types[..]")
+        .with_stderr_contains("[ERROR] doctest failed, to rerun pass `--doc`")
+        .run();
+}
+
+#[cargo_test]
+fn doc_tests_with_doctest_false() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+
+                [lib]
+                doctest = false
+            "#,
+        )
+        .file(
+            "src/lib.rs",
+            r#"
+            /// ```
+            /// assert_eq!(1, 2);
+            /// ```
+            pub fn foo() -> u8 { 1 }
+            "#,
+        )
+        .build();
+
+    p.cargo("test --doc -Zparallel-doctests")
+        .masquerade_as_nightly_cargo(&["parallel-doctests"])
+        .with_stderr(
+            "\
+[NOTE] skipping the doctests of `foo`, its library sets `doctest = false`
+[FINISHED] test [unoptimized + debuginfo] target(s) in [..]",
+        )
+        .run();
+
+    p.cargo("test -vv -Zparallel-doctests")
+        .masquerade_as_nightly_cargo(&["parallel-doctests"])
+        .with_stderr_contains(
+            "[NOTE] skipping the doctests of `foo`, its library sets `doctest = false`",
+        )
+        .with_stderr_does_not_contain("[DOCTEST] foo")
+        .run();
+}
+
+/// A workspace with the members `a` and `b`, whose doctests print their
+/// name.
+fn doctest_workspace(a_doctest: &str) -> Project {
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["a", "b"]
+            "#,
+        )
+        .file("a/Cargo.toml", &basic_manifest("a", "0.1.0"))
+        .file(
+            "a/src/lib.rs",
+            &format!(
+                r#"
+                /// ```
+                /// {a_doctest}
+                /// ```
+                pub fn a() {{}}
+
+                /// ```
+                /// println!("doctest of a");
+                /// ```
+                pub fn a2() {{}}
+                "#
+            ),
+        )
+        .file("b/Cargo.toml", &basic_manifest("b", "0.1.0"))
+        .file(
+            "b/src/lib.rs",
+            r#"
+            /// ```
+            /// println!("doctest of b");
+            /// ```
+            pub fn b() {}
+            "#,
+        )
+        .build()
+}
+
+#[cargo_test]
+fn doc_tests_in_parallel() {
+    let p = doctest_workspace("println!(\"doctest of a\");");
+
+    p.cargo("test --doc --workspace -j2 -Zparallel-doctests")
+        .masquerade_as_nightly_cargo(&["parallel-doctests"])
+        .with_stderr_unordered(
+            "\
+[COMPILING] a v0.1.0 ([CWD]/a)
+[COMPILING] b v0.1.0 ([CWD]/b)
+[FINISHED] test [unoptimized + debuginfo] target(s) in [..]
+[DOCTEST] a
+[DOCTEST] b",
+        )
+        // The output is displayed in order.
+        .with_stderr_contains("[DOCTEST] a\n[DOCTEST] b")
+        .with_stdout_contains("running 2 tests")
+        .with_stdout_contains("running 1 test")
+        .run();
+
+    p.cargo("test --doc --workspace -j2 -vv -Zparallel-doctests")
+        .masquerade_as_nightly_cargo(&["parallel-doctests"])
+        .with_stderr_contains("[NOTE] found 2 doctests in `a`")
+        .with_stderr_contains("[NOTE] found 1 doctest in `b`")
+        .run();
+}
+
+#[cargo_test]
+fn doc_tests_in_parallel_fail_fast() {
+    let p = doctest_workspace("panic!(\"doctest of a failed\");");
+
+    p.cargo("test --doc --workspace -j2 -Zparallel-doctests")
+        .masquerade_as_nightly_cargo(&["parallel-doctests"])
+        .with_status(101)
+        .with_stderr_contains("[DOCTEST] a")
+        .with_stderr_contains("[ERROR] doctest failed, to rerun pass `-p a --doc`")
+        .with_stderr_does_not_contain("[DOCTEST] b")
+        .run();
+
+    p.cargo("test --doc --workspace -j2 --no-fail-fast -Zparallel-doctests")
+        .masquerade_as_nightly_cargo(&["parallel-doctests"])
+        .with_status(101)
+        .with_stderr_contains(
+            "[DOCTEST] a\n[ERROR] doctest failed, to rerun pass `-p a --doc`\n[DOCTEST] b",
+        )
+        .with_stderr_contains(
+            "\
+[ERROR] 1 target failed:
+    `-p a --doc`",
+        )
         .run();
 }
 
@@ -4818,3 +5182,69 @@ error: 2 targets failed:
         .with_status(101)
         .run();
 }
+
+#[cargo_test]
+fn workspace_default_test() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["workspace-default-targets"]
+
+                [workspace]
+                members = ["a", "b"]
+                default-test = ["fast"]
+            "#,
+        )
+        .file("a/Cargo.toml", &basic_manifest("a", "0.1.0"))
+        .file("a/src/lib.rs", "#[test] fn unit() {}")
+        .file("a/tests/fast.rs", "#[test] fn fast() {}")
+        .file("a/tests/slow.rs", "#[test] fn slow() {}")
+        .file("b/Cargo.toml", &basic_manifest("b", "0.1.0"))
+        .file("b/src/lib.rs", "")
+        .build();
+
+    p.cargo("test")
+        .masquerade_as_nightly_cargo(&["workspace-default-targets"])
+        .with_stderr(
+            "\
+[COMPILING] a v0.1.0 ([CWD]/a)
+[FINISHED] test [unoptimized + debuginfo] target(s) in [..]
+[RUNNING] tests/fast.rs (target/debug/deps/fast-[..][EXE])
+",
+        )
+        .run();
+
+    // Target selection flags, `-p` and members ignore the workspace default.
+    p.cargo("test --test slow")
+        .masquerade_as_nightly_cargo(&["workspace-default-targets"])
+        .with_stderr_contains("[RUNNING] tests/slow.rs [..]")
+        .with_stderr_does_not_contain("[RUNNING] tests/fast.rs [..]")
+        .run();
+    p.cargo("test -p a")
+        .masquerade_as_nightly_cargo(&["workspace-default-targets"])
+        .with_stderr_contains("[RUNNING] unittests src/lib.rs [..]")
+        .with_stderr_contains("[RUNNING] tests/slow.rs [..]")
+        .run();
+
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            cargo-features = ["workspace-default-targets"]
+
+            [workspace]
+            members = ["a", "b"]
+            default-test = ["fast", "missing"]
+        "#,
+    );
+    p.cargo("test")
+        .masquerade_as_nightly_cargo(&["workspace-default-targets"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] `workspace.default-test` in `[CWD]/Cargo.toml` lists `missing`, \
+which is not a test target of the selected packages
+",
+        )
+        .run();
+}
@@ -293,6 +293,99 @@ foo v0.1.0 ([..]/foo)
         .run();
 }
 
+#[cargo_test]
+fn patch_and_replace() {
+    // `{provenance}` shows the packages from `[patch]` and `[replace]`, with
+    // what they are used instead of.
+    Package::new("patched", "1.0.0").publish();
+    Package::new("replaced", "1.0.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                patched = "1.0"
+
+                [patch.crates-io]
+                patched = { path = "patched" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("patched/Cargo.toml", &basic_manifest("patched", "1.0.0"))
+        .file("patched/src/lib.rs", "")
+        .build();
+
+    p.cargo("tree --format")
+        .arg("{p} {provenance}")
+        .arg("-Zunstable-options")
+        .masquerade_as_nightly_cargo(&["{provenance} in tree --format"])
+        .with_stdout(
+            "\
+foo v0.1.0 ([..]/foo) \n\
+└── patched v1.0.0 ([..]/foo/patched) (patch for https://github.com/rust-lang/crates.io-index)
+",
+        )
+        .run();
+
+    // Plain `{p}` is unchanged, and `{provenance}` is unstable.
+    p.cargo("tree")
+        .with_stdout(
+            "\
+foo v0.1.0 ([..]/foo)
+└── patched v1.0.0 ([..]/foo/patched)
+",
+        )
+        .run();
+    p.cargo("tree --format")
+        .arg("{p} {provenance}")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] tree format `{p} {provenance}` not valid
+
+Caused by:
+  the `{provenance}` pattern is unstable, pass `-Z unstable-options` to enable it
+",
+        )
+        .run();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                replaced = "1.0"
+
+                [replace]
+                "replaced:1.0.0" = { path = "replaced" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("replaced/Cargo.toml", &basic_manifest("replaced", "1.0.0"))
+        .file("replaced/src/lib.rs", "")
+        .build();
+
+    p.cargo("tree --format")
+        .arg("{p} {provenance}")
+        .arg("-Zunstable-options")
+        .masquerade_as_nightly_cargo(&["{provenance} in tree --format"])
+        .with_stdout(
+            "\
+foo v0.1.0 ([..]/foo) \n\
+└── replaced v1.0.0 ([..]/foo/replaced) (replaces replaced v1.0.0)
+",
+        )
+        .run();
+}
+
 #[cargo_test]
 fn features() {
     // Exercises a variety of feature behaviors.
@@ -2199,3 +2292,92 @@ foo v1.0.0 ([ROOT]/foo)
         )
         .run();
 }
+
+#[cargo_test]
+fn target_cfg() {
+    Package::new("unixdep", "1.0.0").publish();
+    Package::new("windowsdep", "1.0.0").publish();
+    Package::new("featdep", "1.0.0").publish();
+    Package::new("tripledep", "1.0.0").publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [target.'cfg(unix)'.dependencies]
+                unixdep = "1.0"
+
+                [target.'cfg(windows)'.dependencies]
+                windowsdep = "1.0"
+
+                [target.'cfg(all(unix, feature = "foo"))'.dependencies]
+                featdep = "1.0"
+
+                [target.fake-unknown-target.dependencies]
+                tripledep = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    // The target is unknown to rustc, which is never asked about it.
+    p.cargo("tree -Zunstable-options --target fake-unknown-target --cfg windows")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout(
+            "\
+foo v0.1.0 ([..]/foo)
+├── tripledep v1.0.0
+└── windowsdep v1.0.0
+",
+        )
+        .run();
+
+    p.cargo("tree -Zunstable-options --target fake-unknown-target --cfg unix")
+        .arg("--cfg")
+        .arg("feature=\"foo\"")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout(
+            "\
+foo v0.1.0 ([..]/foo)
+├── featdep v1.0.0
+├── tripledep v1.0.0
+└── unixdep v1.0.0
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn target_cfg_errors() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("tree --target fake-unknown-target --cfg unix")
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] the `--cfg` flag is unstable, pass `-Z unstable-options` to enable it",
+        )
+        .run();
+
+    p.cargo("tree -Zunstable-options --cfg unix")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr("[ERROR] `--cfg` requires a target, pass `--target <TRIPLE>`")
+        .run();
+
+    p.cargo("tree -Zunstable-options --target fake-unknown-target --cfg foo=")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] invalid `--cfg` value `foo=`
+
+Caused by:
+  failed to parse `foo=` as a cfg expression: expected a string, but cfg expression ended
+",
+        )
+        .run();
+}
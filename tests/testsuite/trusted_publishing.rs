@@ -0,0 +1,161 @@
+//! Tests for `-Ztrusted-publishing`.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use cargo_test_support::registry::{RegistryBuilder, Request, Response, TestRegistry, Token};
+use cargo_test_support::{project, Project};
+
+const OIDC_TOKEN: &str = "oidc-token";
+const SHORT_LIVED_TOKEN: &str = "short-lived";
+
+/// Exchanges `OIDC_TOKEN` for `SHORT_LIVED_TOKEN`, and counts its revocations.
+fn tokens(req: &Request, revoked: &AtomicUsize) -> Response {
+    match req.method.as_str() {
+        "post" => {
+            let body = String::from_utf8(req.body.clone().unwrap()).unwrap();
+            assert_eq!(body, format!(r#"{{"jwt":"{OIDC_TOKEN}"}}"#));
+            Response {
+                code: 200,
+                headers: vec![],
+                body: format!(r#"{{"token":"{SHORT_LIVED_TOKEN}"}}"#).into_bytes(),
+            }
+        }
+        "delete" => {
+            assert_eq!(req.authorization.as_deref(), Some(SHORT_LIVED_TOKEN));
+            revoked.fetch_add(1, Ordering::SeqCst);
+            Response {
+                code: 200,
+                headers: vec![],
+                body: b"{}".to_vec(),
+            }
+        }
+        method => panic!("unexpected method {method}"),
+    }
+}
+
+fn registry(builder: RegistryBuilder) -> TestRegistry {
+    builder
+        .http_api()
+        .http_index()
+        .alternative()
+        .no_configure_token()
+        .token(Token::Plaintext(SHORT_LIVED_TOKEN.to_string()))
+        .build()
+}
+
+fn make_project() -> Project {
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                authors = []
+                license = "MIT"
+                description = "foo"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            r#"
+                [registries.alternative]
+                trusted-publishing = true
+            "#,
+        )
+        .build()
+}
+
+#[cargo_test]
+fn publish_with_oidc_token() {
+    static REVOKED: AtomicUsize = AtomicUsize::new(0);
+    let _registry = registry(
+        RegistryBuilder::new().add_responder("/api/v1/trusted_publishing/tokens", |req, _| {
+            tokens(req, &REVOKED)
+        }),
+    );
+
+    let p = make_project();
+    p.cargo("publish --no-verify --registry alternative -Ztrusted-publishing")
+        .masquerade_as_nightly_cargo(&["trusted-publishing"])
+        .env("CARGO_OIDC_TOKEN", OIDC_TOKEN)
+        .with_stderr(
+            "\
+[UPDATING] `alternative` index
+[WARNING] manifest has no documentation, [..]
+See [..]
+[PACKAGING] foo v0.0.1 ([CWD])
+[PACKAGED] [..] files, [..] ([..] compressed)
+[UPLOADING] foo v0.0.1 ([CWD])
+[UPLOADED] foo v0.0.1 to registry `alternative`
+note: Waiting for `foo v0.0.1` to be available at registry `alternative`.
+You may press ctrl-c to skip waiting; the crate should be available shortly.
+[PUBLISHED] foo v0.0.1 at registry `alternative`
+",
+        )
+        .run();
+    assert_eq!(REVOKED.load(Ordering::SeqCst), 1);
+}
+
+#[cargo_test]
+fn publish_with_github_actions() {
+    static REVOKED: AtomicUsize = AtomicUsize::new(0);
+    let registry = registry(
+        RegistryBuilder::new()
+            .add_responder("/api/v1/trusted_publishing/tokens", |req, _| {
+                tokens(req, &REVOKED)
+            })
+            .add_responder("/oidc", |req, _| {
+                assert_eq!(req.authorization.as_deref(), Some("bearer request-token"));
+                assert_eq!(req.url.query(), Some("api-version=2.0&audience=127.0.0.1"));
+                Response {
+                    code: 200,
+                    headers: vec![],
+                    body: format!(r#"{{"value":"{OIDC_TOKEN}"}}"#).into_bytes(),
+                }
+            }),
+    );
+
+    let p = make_project();
+    p.cargo("publish --no-verify --registry alternative -Ztrusted-publishing")
+        .masquerade_as_nightly_cargo(&["trusted-publishing"])
+        .env(
+            "ACTIONS_ID_TOKEN_REQUEST_URL",
+            format!("{}oidc?api-version=2.0", registry.api_url()),
+        )
+        .env("ACTIONS_ID_TOKEN_REQUEST_TOKEN", "request-token")
+        .with_stderr_contains("[PUBLISHED] foo v0.0.1 at registry `alternative`")
+        .run();
+    assert_eq!(REVOKED.load(Ordering::SeqCst), 1);
+}
+
+#[cargo_test]
+fn no_oidc_token() {
+    let _registry = registry(RegistryBuilder::new());
+
+    let p = make_project();
+    p.cargo("publish --no-verify --registry alternative -Ztrusted-publishing")
+        .masquerade_as_nightly_cargo(&["trusted-publishing"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[UPDATING] `alternative` index
+[WARNING] manifest has no documentation, [..]
+See [..]
+[PACKAGING] foo v0.0.1 ([CWD])
+[PACKAGED] [..] files, [..] ([..] compressed)
+[ERROR] trusted publishing to registry `alternative` requires an OIDC token, \
+but neither `CARGO_OIDC_TOKEN` is set nor running in GitHub Actions \
+with the `id-token: write` permission
+",
+        )
+        .run();
+
+    // Without the flag the registry is authenticated with as usual.
+    p.cargo("publish --no-verify --registry alternative")
+        .env("CARGO_OIDC_TOKEN", OIDC_TOKEN)
+        .with_status(101)
+        .with_stderr_contains("[ERROR] no token found for `alternative`, please run `cargo login --registry alternative`")
+        .run();
+}
@@ -833,3 +833,240 @@ required by package `foo v0.1.0 ([ROOT]/foo)`
         )
         .run();
 }
+
+fn summary_project() -> cargo_test_support::Project {
+    Package::new("log", "0.1.0").publish();
+    Package::new("old", "0.1.0").publish();
+    Package::new("serde", "0.1.0")
+        .dep("log", "0.1")
+        .dep("old", "0.1")
+        .publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+
+                [dependencies]
+                serde = "0.1"
+                log = "0.1"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+    p.cargo("generate-lockfile").run();
+
+    Package::new("log", "0.1.1").publish();
+    Package::new("new", "0.1.0").publish();
+    Package::new("serde", "0.1.1")
+        .dep("log", "0.1")
+        .dep("new", "0.1")
+        .publish();
+    p
+}
+
+#[cargo_test]
+fn summary() {
+    let p = summary_project();
+
+    p.cargo("update --summary -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout(
+            "\
+Upgraded:
+  log v0.1.0 -> v0.1.1 (foo requires ^0.1, serde requires ^0.1)
+  serde v0.1.0 -> v0.1.1 (foo requires ^0.1)
+Added:
+  new v0.1.0 (serde requires ^0.1)
+Removed:
+  old v0.1.0 (previously required by serde v0.1.0)
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn summary_json() {
+    let p = summary_project();
+
+    p.cargo("update -p serde --dry-run --json -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_json(
+            r#"
+            {
+              "upgraded": [
+                {
+                  "old": "serde 0.1.0 (registry+https://github.com/rust-lang/crates.io-index)",
+                  "new": "serde 0.1.1 (registry+https://github.com/rust-lang/crates.io-index)",
+                  "required_by": [
+                    {"package": "foo 0.0.1 (path+file://[..])", "req": "^0.1"}
+                  ]
+                }
+              ],
+              "downgraded": [],
+              "added": [
+                {
+                  "new": "new 0.1.0 (registry+https://github.com/rust-lang/crates.io-index)",
+                  "required_by": [
+                    {"package": "serde 0.1.1 (registry+https://github.com/rust-lang/crates.io-index)", "req": "^0.1"}
+                  ]
+                }
+              ],
+              "removed": [
+                {
+                  "old": "old 0.1.0 (registry+https://github.com/rust-lang/crates.io-index)",
+                  "required_by": [
+                    {"package": "serde 0.1.0 (registry+https://github.com/rust-lang/crates.io-index)"}
+                  ]
+                }
+              ]
+            }
+            "#,
+        )
+        .run();
+}
+
+#[cargo_test]
+fn summary_requires_unstable_options() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("update --summary")
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] the `--summary` flag is unstable, pass `-Z unstable-options` to enable it",
+        )
+        .run();
+}
+
+fn interactive_project() -> cargo_test_support::Project {
+    Package::new("bar", "1.0.0").publish();
+    Package::new("baz", "0.1.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                rust-version = "1.60"
+
+                [dependencies]
+                bar = "1.0"
+                baz = { version = "0.1" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+    p.cargo("generate-lockfile").run();
+
+    Package::new("bar", "1.0.1").publish();
+    Package::new("bar", "1.0.2").rust_version("1.99").publish();
+    Package::new("bar", "2.0.0").publish();
+    Package::new("baz", "0.2.0").publish();
+    p
+}
+
+#[cargo_test]
+fn interactive() {
+    let p = interactive_project();
+
+    p.cargo("update -i -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdin("1 3\n")
+        .with_stdout(
+            "\
+Available updates:
+  1) bar v1.0.0 -> v1.0.1
+  2) bar v1.0.0 -> v2.0.0 (changes `Cargo.toml`)
+  3) baz v0.1.0 -> v0.2.0 (changes `Cargo.toml`)
+Select the updates to apply (for example `1 3` or `all`), or press enter to skip:
+",
+        )
+        .with_stderr(
+            "\
+[UPDATING] `dummy-registry` index
+[UPDATING] bar v1.0.0 -> v1.0.1
+[UPDATING] baz v0.1.0 -> v0.2.0
+",
+        )
+        .run();
+
+    let lock = p.read_lockfile();
+    assert!(lock.contains("name = \"bar\"\nversion = \"1.0.1\""));
+    assert!(lock.contains("name = \"baz\"\nversion = \"0.2.0\""));
+    let manifest = p.read_file("Cargo.toml");
+    assert!(manifest.contains("bar = \"1.0\"\n"));
+    assert!(manifest.contains("baz = \"0.2.0\"\n"));
+}
+
+#[cargo_test]
+fn interactive_both_updates_of_a_package() {
+    let p = interactive_project();
+
+    p.cargo("update -i -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdin("2 1\n")
+        .with_stderr_contains("[UPDATING] bar v1.0.0 -> v2.0.0")
+        .run();
+
+    let lock = p.read_lockfile();
+    assert!(lock.contains("name = \"bar\"\nversion = \"2.0.0\""));
+    let manifest = p.read_file("Cargo.toml");
+    assert!(manifest.contains("bar = \"2.0.0\"\n"));
+}
+
+#[cargo_test]
+fn interactive_skip() {
+    let p = interactive_project();
+    let lock = p.read_lockfile();
+
+    p.cargo("update -i -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdin("\n")
+        .with_stdout_contains("  2) bar v1.0.0 -> v2.0.0 (changes `Cargo.toml`)")
+        .run();
+    assert_eq!(p.read_lockfile(), lock);
+
+    p.cargo("update -i -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdin("4\n")
+        .with_status(101)
+        .with_stderr_contains(
+            "[ERROR] invalid selection `4`, expected numbers from 1 to 3 or `all`",
+        )
+        .run();
+    assert_eq!(p.read_lockfile(), lock);
+}
+
+#[cargo_test]
+fn interactive_up_to_date() {
+    Package::new("bar", "1.0.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+
+                [dependencies]
+                bar = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("update -i -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout("")
+        .with_stderr(
+            "\
+[UPDATING] `dummy-registry` index
+[NOTE] all direct dependencies are up to date
+",
+        )
+        .run();
+}
@@ -516,6 +516,8 @@ fn ignore_files() {
         .file("foo.orig", "")
         .file(".gitignore", "")
         .file(".gitattributes", "")
+        .file(".hgignore", "")
+        .file(".fossil-settings/ignore-glob", "")
         .file("foo.rej", "")
         .publish();
 
@@ -524,6 +526,8 @@ fn ignore_files() {
     assert!(!csum.contains("foo.orig"));
     assert!(!csum.contains(".gitignore"));
     assert!(!csum.contains(".gitattributes"));
+    assert!(!csum.contains(".hgignore"));
+    assert!(!csum.contains("ignore-glob"));
     assert!(!csum.contains(".cargo-ok"));
     assert!(!csum.contains("foo.rej"));
 }
@@ -1150,3 +1154,200 @@ fn vendor_crate_with_ws_inherit() {
         .with_stderr_contains("[..]foo/vendor/bar/src/lib.rs[..]")
         .run();
 }
+
+#[cargo_test]
+fn verify() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                bar = "0.1.0"
+                log = "0.3.5"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    Package::new("bar", "0.1.0").publish();
+    Package::new("log", "0.3.5").publish();
+
+    p.cargo("vendor --respect-source-config").run();
+    p.cargo("vendor --respect-source-config --verify -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["vendor --verify"])
+        .with_stderr("[VERIFIED] 2 vendored packages in `vendor`")
+        .run();
+
+    // Drift in every possible way: a modified file, a missing package, an
+    // extra package and a package that doesn't match the lock file.
+    p.change_file("vendor/log/src/lib.rs", "// modified");
+    fs::remove_dir_all(p.root().join("vendor/bar")).unwrap();
+    Package::new("baz", "1.0.0").publish();
+    Package::new("log", "0.3.6").publish();
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            version = "0.1.0"
+
+            [dependencies]
+            bar = "0.1.0"
+            log = "0.3.6"
+        "#,
+    );
+    p.cargo("generate-lockfile").run();
+    fs::create_dir(p.root().join("vendor/baz")).unwrap();
+    p.change_file("vendor/baz/Cargo.toml", &basic_manifest("baz", "1.0.0"));
+    p.change_file("vendor/baz/src/lib.rs", "");
+    p.change_file(
+        "vendor/baz/.cargo-checksum.json",
+        r#"{"files":{},"package":null}"#,
+    );
+
+    p.cargo("vendor --respect-source-config --verify -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["vendor --verify"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[WARNING] `baz v1.0.0` is vendored in `[..]/vendor/baz` but is not in `Cargo.lock`
+[WARNING] `log v0.3.5` is vendored in `[..]/vendor/log` but is not in `Cargo.lock`
+[WARNING] `bar v0.1.0` is in `Cargo.lock` but is not vendored
+[WARNING] `log v0.3.6` is in `Cargo.lock` but is not vendored
+[ERROR] vendor directory `vendor` does not match `Cargo.lock`
+run `cargo vendor` to update it
+",
+        )
+        .run();
+
+    p.cargo("vendor --respect-source-config").run();
+    p.change_file("vendor/log/src/lib.rs", "// modified");
+    p.cargo("vendor --respect-source-config --verify -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["vendor --verify"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[WARNING] the vendored files of `log v0.3.6` were modified: the listed checksum of `[..]/vendor/log/src/lib.rs` has changed:
+expected: [..]
+actual:   [..]
+
+directory sources are not intended to be edited, [..]
+[ERROR] vendor directory `vendor` does not match `Cargo.lock`
+run `cargo vendor` to update it
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn verify_outdated_lockfile() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                bar = "0.1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    Package::new("bar", "0.1.0").publish();
+    Package::new("baz", "1.0.0").publish();
+
+    p.cargo("vendor --respect-source-config").run();
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            version = "0.1.0"
+
+            [dependencies]
+            bar = "0.1.0"
+            baz = "1.0.0"
+        "#,
+    );
+    let lock = p.read_file("Cargo.lock");
+
+    p.cargo("vendor --respect-source-config --verify -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["vendor --verify"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[UPDATING] `dummy-registry` index
+[WARNING] `Cargo.lock` of `[CWD]/Cargo.toml` is out of date with its dependencies
+[WARNING] `baz v1.0.0` is in `Cargo.lock` but is not vendored
+[ERROR] vendor directory `vendor` does not match `Cargo.lock`
+run `cargo vendor` to update it
+",
+        )
+        .run();
+    assert_eq!(p.read_file("Cargo.lock"), lock);
+}
+
+#[cargo_test]
+fn verify_package_checksum() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                bar = "0.1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    Package::new("bar", "0.1.0").publish();
+
+    p.cargo("vendor --respect-source-config").run();
+    let cksum = p.read_file("vendor/bar/.cargo-checksum.json");
+    let lock = p.read_file("Cargo.lock");
+    let package = lock
+        .lines()
+        .find_map(|line| line.strip_prefix("checksum = \""))
+        .unwrap()
+        .trim_end_matches('"');
+    p.change_file(
+        "vendor/bar/.cargo-checksum.json",
+        &cksum.replace(package, &"0".repeat(64)),
+    );
+
+    p.cargo("vendor --respect-source-config --verify -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["vendor --verify"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[WARNING] the package checksum of `bar v0.1.0` in `[..]/vendor/bar/.cargo-checksum.json` does not match `Cargo.lock`
+[ERROR] vendor directory `vendor` does not match `Cargo.lock`
+run `cargo vendor` to update it
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn verify_requires_unstable_options() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("vendor --verify")
+        .masquerade_as_nightly_cargo(&["vendor --verify"])
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] the `--verify` flag is unstable, pass `-Z unstable-options` to enable it",
+        )
+        .run();
+}
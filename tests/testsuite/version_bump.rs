@@ -0,0 +1,179 @@
+//! Tests for `cargo version bump`.
+
+use cargo_test_support::compare::assert_match_exact;
+use cargo_test_support::{basic_lib_manifest, project, Project};
+
+/// A workspace where `a` has its own version, `b` and `c` inherit the
+/// version of the workspace, and `b` depends on `a` and `c`.
+fn make_project() -> Project {
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["a", "b", "c"]
+
+                [workspace.package]
+                version = "0.3.1" # shared
+
+                [workspace.dependencies]
+                a = { path = "a", version = "0.1" }
+            "#,
+        )
+        .file(
+            "a/Cargo.toml",
+            r#"
+                [package]
+                name = "a"
+                version = "0.1.0"  # own version
+            "#,
+        )
+        .file("a/src/lib.rs", "")
+        .file(
+            "b/Cargo.toml",
+            r#"
+                [package]
+                name = "b"
+                version.workspace = true
+
+                [dependencies]
+                a = { path = "../a", version = "=0.1.0" }
+                c = { version = "0.3", path = "../c" }
+                other = { path = "../other", version = ">=0.5, <0.6" }
+            "#,
+        )
+        .file("b/src/lib.rs", "")
+        .file(
+            "c/Cargo.toml",
+            r#"
+                [package]
+                name = "c"
+                version.workspace = true
+
+                [dev-dependencies]
+                a.workspace = true
+            "#,
+        )
+        .file("c/src/lib.rs", "")
+        .file("other/Cargo.toml", &basic_lib_manifest("other"))
+        .file("other/src/lib.rs", "")
+        .build()
+}
+
+#[cargo_test]
+fn gated() {
+    let p = make_project();
+    p.cargo("version bump minor")
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] the `cargo version bump` command is unstable, \
+             pass `-Z unstable-options` to enable it",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn bump_package() {
+    let p = make_project();
+    p.cargo("generate-lockfile").run();
+
+    p.cargo("version bump minor -p a -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr(
+            "\
+[BUMPING] a from 0.1.0 to 0.2.0
+[UPDATING] dependency `a` in Cargo.toml from \"0.1\" to \"0.2\"
+[UPDATING] dependency `a` in b/Cargo.toml from \"=0.1.0\" to \"=0.2.0\"
+",
+        )
+        .run();
+
+    assert_match_exact(
+        r#"
+                [package]
+                name = "a"
+                version = "0.2.0"  # own version
+            "#,
+        &p.read_file("a/Cargo.toml"),
+    );
+    assert!(p
+        .read_file("Cargo.toml")
+        .contains(r#"a = { path = "a", version = "0.2" }"#));
+    assert!(p
+        .read_file("b/Cargo.toml")
+        .contains(r#"a = { path = "../a", version = "=0.2.0" }"#));
+    assert!(p
+        .read_lockfile()
+        .contains("name = \"a\"\nversion = \"0.2.0\""));
+    p.cargo("check").run();
+}
+
+#[cargo_test]
+fn bump_inherited() {
+    let p = make_project();
+
+    p.cargo("version bump major -p b -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr(
+            "\
+[BUMPING] workspace from 0.3.1 to 1.0.0
+[BUMPING] b from 0.3.1 to 1.0.0
+[BUMPING] c from 0.3.1 to 1.0.0
+[UPDATING] dependency `c` in b/Cargo.toml from \"0.3\" to \"1.0\"
+",
+        )
+        .run();
+
+    assert!(p
+        .read_file("Cargo.toml")
+        .contains(r#"version = "1.0.0" # shared"#));
+    assert!(p
+        .read_file("b/Cargo.toml")
+        .contains(r#"c = { version = "1.0", path = "../c" }"#));
+    // No lockfile is created.
+    assert!(!p.root().join("Cargo.lock").exists());
+    p.cargo("check").run();
+}
+
+#[cargo_test]
+fn unsupported_requirement() {
+    let p = make_project();
+
+    p.cargo("version bump minor -p other -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr(
+            "\
+[BUMPING] other from 0.5.0 to 0.6.0
+[WARNING] the requirement \">=0.5, <0.6\" of dependency `other` in b/Cargo.toml \
+doesn't match the new version 0.6.0, update it manually
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn dry_run() {
+    let p = make_project();
+    p.cargo("generate-lockfile").run();
+    let lockfile = p.read_lockfile();
+
+    p.cargo("version bump patch --workspace --exclude other --dry-run -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr(
+            "\
+[BUMPING] a from 0.1.0 to 0.1.1
+[BUMPING] workspace from 0.3.1 to 0.3.2
+[BUMPING] b from 0.3.1 to 0.3.2
+[BUMPING] c from 0.3.1 to 0.3.2
+[UPDATING] dependency `a` in b/Cargo.toml from \"=0.1.0\" to \"=0.1.1\"
+[WARNING] aborting bump due to dry run
+",
+        )
+        .run();
+
+    assert!(p.read_file("a/Cargo.toml").contains(r#"version = "0.1.0""#));
+    assert!(p
+        .read_file("Cargo.toml")
+        .contains(r#"version = "0.3.1" # shared"#));
+    assert_eq!(p.read_lockfile(), lockfile);
+}
@@ -0,0 +1,179 @@
+//! Tests for the `cargo why-version` command.
+
+use cargo_test_support::registry::Package;
+use cargo_test_support::{basic_manifest, project};
+
+#[cargo_test]
+fn binding_constraint() {
+    for version in ["1.0.0", "1.2.0", "1.4.0", "1.5.0", "2.0.0", "2.1.0-beta"] {
+        Package::new("bar", version).publish();
+    }
+    Package::new("baz", "0.3.0")
+        .dep("bar", ">=1.0, <1.5")
+        .publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                bar = "1.2"
+                baz = "0.3"
+
+                [build-dependencies]
+                bar = "1"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+    p.cargo("generate-lockfile").run();
+    p.cargo("update -p bar --precise 1.2.0").run();
+
+    p.cargo("why-version bar -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout(
+            "\
+bar v1.2.0
+requirements:
+  baz v0.3.0 requires >=1.0, <1.5 (>=1.0.0, <1.5.0)
+  foo v0.1.0 requires ^1.2 (>=1.2.0, <2.0.0)
+  foo v0.1.0 (build) requires ^1 (>=1.0.0, <2.0.0)
+allowed by all requirements: >=1.2.0, <1.5.0
+newest version: v2.0.0
+binding constraint: baz v0.3.0 requires >=1.0, <1.5, which allows up to v1.4.0
+v1.4.0 is allowed by all requirements, run `cargo update -p bar@1.2.0`
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn up_to_date() {
+    Package::new("bar", "1.0.0").publish();
+    Package::new("bar", "1.1.0").publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                bar = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("why-version bar -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout(
+            "\
+bar v1.1.0
+requirements:
+  foo v0.1.0 requires ^1.0 (>=1.0.0, <2.0.0)
+allowed by all requirements: >=1.0.0, <2.0.0
+newest version: v1.1.0
+v1.1.0 is the newest version
+",
+        )
+        .run();
+
+    p.cargo("update -p bar --precise 1.0.0").run();
+    p.cargo("why-version bar -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_contains(
+            "no requirement prevents an upgrade to v1.1.0, run `cargo update -p bar@1.0.0`",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn path_dependency() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [target.'cfg(unix)'.dependencies]
+                bar = { path = "bar", version = "0.5" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.5.1"))
+        .file("bar/src/lib.rs", "")
+        .build();
+
+    p.cargo("why-version bar -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout(
+            "\
+bar v0.5.1
+requirements:
+  foo v0.1.0 (cfg(unix)) requires ^0.5 (>=0.5.0, <0.6.0)
+allowed by all requirements: >=0.5.0, <0.6.0
+only packages from registries have other versions to upgrade to
+",
+        )
+        .run();
+
+    p.cargo("why-version foo -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout(
+            "\
+foo v0.1.0
+no package depends on it
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn why_version_errors() {
+    Package::new("bar", "1.0.0").publish();
+    Package::new("bar", "2.0.0").publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                bar = "1.0"
+                bar2 = { package = "bar", version = "2.0" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("why-version bar")
+        .with_status(101)
+        .with_stderr(
+            "[ERROR] the `cargo why-version` command is unstable, \
+             pass `-Z unstable-options` to enable it",
+        )
+        .run();
+
+    p.cargo("why-version bar -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_contains("[ERROR] There are multiple `bar` packages in your project[..]")
+        .run();
+
+    p.cargo("why-version bar@2.0.0 -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_contains("bar v2.0.0")
+        .run();
+}
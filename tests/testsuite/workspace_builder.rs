@@ -0,0 +1,137 @@
+//! Tests for building a workspace from manifests held in memory with
+//! `WorkspaceBuilder`.
+
+use std::env;
+
+use cargo::{
+    core::compiler::CompileMode,
+    core::{Shell, WorkspaceBuilder},
+    ops::{CompileOptions, Packages},
+    Config,
+};
+use cargo_test_support::{paths, project};
+
+fn config() -> Config {
+    let shell = Shell::from_write(Box::new(Vec::new()));
+    Config::new(shell, env::current_dir().unwrap(), paths::home())
+}
+
+#[cargo_test]
+fn compiles_generated_workspace() {
+    let p = project()
+        .no_manifest()
+        .file("gen/app.rs", "fn main() { lib::f() }")
+        .file("gen/lib.rs", "pub fn f() {}")
+        .build();
+    let app_rs = p.root().join("gen/app.rs").display().to_string();
+    let lib_rs = p.root().join("gen/lib.rs").display().to_string();
+    let config = config();
+
+    let ws = WorkspaceBuilder::new(&p.root(), &config)
+        .manifest(
+            "",
+            toml::toml! {
+                [workspace]
+                members = ["app"]
+
+                [workspace.package]
+                version = "0.1.0"
+            },
+        )
+        .manifest(
+            "app",
+            toml::toml! {
+                [package]
+                name = "app"
+                version.workspace = true
+
+                [[bin]]
+                name = "app"
+                path = (app_rs)
+
+                [dependencies]
+                lib = { path = "../lib" }
+            },
+        )
+        .manifest(
+            "lib",
+            toml::toml! {
+                [package]
+                name = "lib"
+                version.workspace = true
+
+                [lib]
+                path = (lib_rs)
+            },
+        )
+        .build()
+        .unwrap();
+
+    // Path dependencies are members, like with manifests on disk.
+    let mut members: Vec<_> = ws.members().map(|p| p.name().as_str()).collect();
+    members.sort();
+    assert_eq!(members, ["app", "lib"]);
+    assert!(ws.is_virtual());
+
+    let mut compile_options = CompileOptions::new(ws.config(), CompileMode::Build).unwrap();
+    compile_options.spec = Packages::Default;
+    let result = cargo::ops::compile(&ws, &compile_options).unwrap();
+
+    assert_eq!(1, result.binaries.len());
+    assert!(result.binaries[0].path.exists());
+    assert!(p.root().join("Cargo.lock").is_file());
+    assert!(!p.root().join("Cargo.toml").exists());
+    assert!(!p.root().join("app/Cargo.toml").exists());
+
+    // Nothing is rebuilt the second time.
+    let mtime = |path: &std::path::Path| path.metadata().unwrap().modified().unwrap();
+    let built = mtime(&result.binaries[0].path);
+    let result = cargo::ops::compile(&ws, &compile_options).unwrap();
+    assert_eq!(built, mtime(&result.binaries[0].path));
+}
+
+#[cargo_test]
+fn root_manifest_required() {
+    let p = project().no_manifest().build();
+    let config = config();
+
+    let err = WorkspaceBuilder::new(&p.root(), &config)
+        .manifest(
+            "foo",
+            toml::toml! {
+                [package]
+                name = "foo"
+                version = "0.1.0"
+            },
+        )
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!("no manifest was added for `{}`", p.root().display())
+    );
+}
+
+#[cargo_test]
+fn invalid_manifest() {
+    let p = project().no_manifest().build();
+    let config = config();
+
+    let err = WorkspaceBuilder::new(&p.root(), &config)
+        .manifest(
+            "",
+            toml::toml! {
+                [package]
+                name = "foo"
+            },
+        )
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        format!("{err:#}"),
+        format!(
+            "failed to parse manifest at `{}`: missing field `version`\nin `package`\n",
+            p.root().join("Cargo.toml").display()
+        )
+    );
+}
@@ -2529,3 +2529,253 @@ foo v0.1.0 ([..]/foo/sub/foo)
         )
         .run();
 }
+
+#[cargo_test]
+fn negative_member_globs() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["negative-member-globs"]
+
+                [workspace]
+                members = ["crates/*", "!crates/experimental-*"]
+            "#,
+        )
+        .file(
+            "crates/foo/Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                experimental-bar = { path = "../experimental-bar" }
+            "#,
+        )
+        .file("crates/foo/src/lib.rs", "")
+        .file(
+            "crates/experimental-bar/Cargo.toml",
+            &basic_manifest("experimental-bar", "0.1.0"),
+        )
+        .file("crates/experimental-bar/src/lib.rs", "")
+        .file("crates/experimental-baz/Cargo.toml", "invalid")
+        .build();
+
+    // Excluded crates are neither members nor loaded, even when they are path
+    // dependencies of a member.
+    let output = p
+        .cargo("metadata --format-version 1 --no-deps")
+        .masquerade_as_nightly_cargo(&["negative-member-globs"])
+        .exec_with_output()
+        .unwrap();
+    let metadata: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let members = metadata["workspace_members"].as_array().unwrap();
+    assert_eq!(members.len(), 1);
+    assert!(members[0].as_str().unwrap().starts_with("foo 0.1.0"));
+    let globs = metadata["workspace_member_globs"].as_object().unwrap();
+    assert_eq!(globs.len(), 1);
+    assert_eq!(globs[members[0].as_str().unwrap()], "crates/*");
+
+    p.cargo("check")
+        .masquerade_as_nightly_cargo(&["negative-member-globs"])
+        .with_stderr(
+            "\
+[CHECKING] experimental-bar v0.1.0 ([CWD]/crates/experimental-bar)
+[CHECKING] foo v0.1.0 ([CWD]/crates/foo)
+[FINISHED] [..]
+",
+        )
+        .run();
+    assert!(!p.root().join("crates/experimental-bar/target").exists());
+}
+
+#[cargo_test]
+fn negative_member_globs_requires_feature() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["crates/*", "!crates/experimental-*"]
+            "#,
+        )
+        .file("crates/foo/Cargo.toml", &basic_manifest("foo", "0.1.0"))
+        .file("crates/foo/src/lib.rs", "")
+        .build();
+
+    p.cargo("check")
+        .masquerade_as_nightly_cargo(&["negative-member-globs"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to parse manifest at `[CWD]/Cargo.toml`
+
+Caused by:
+  failed to parse `!crates/experimental-*` in `workspace.members`
+
+Caused by:
+  feature `negative-member-globs` is required
+[..]
+[..]
+[..]
+[..]
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn nested_workspaces() {
+    Package::new("bar", "0.1.0").publish();
+    Package::new("baz", "0.1.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["nested-workspaces"]
+
+                [workspace]
+                members = ["app"]
+                members-from = ["vendor/sub"]
+
+                [patch.crates-io]
+                bar = { path = "bar-outer" }
+            "#,
+        )
+        .file(
+            "app/Cargo.toml",
+            r#"
+                [package]
+                name = "app"
+                version = "0.1.0"
+
+                [dependencies]
+                sub-a = { path = "../vendor/sub/crates/sub-a" }
+            "#,
+        )
+        .file("app/src/lib.rs", "")
+        .file(
+            "vendor/sub/Cargo.toml",
+            r#"
+                [workspace]
+                members = ["crates/*"]
+
+                [patch.crates-io]
+                bar = { path = "bar-nested" }
+                baz = { path = "baz-nested" }
+
+                [profile.dev]
+                debug = false
+            "#,
+        )
+        .file(
+            "vendor/sub/crates/sub-a/Cargo.toml",
+            r#"
+                [package]
+                name = "sub-a"
+                version = "0.1.0"
+
+                [dependencies]
+                bar = "0.1"
+                baz = "0.1"
+            "#,
+        )
+        .file("vendor/sub/crates/sub-a/src/lib.rs", "")
+        .file(
+            "vendor/sub/crates/sub-b/Cargo.toml",
+            &basic_manifest("sub-b", "0.1.0"),
+        )
+        .file("vendor/sub/crates/sub-b/src/lib.rs", "")
+        .file("bar-outer/Cargo.toml", &basic_manifest("bar", "0.1.0"))
+        .file("bar-outer/src/lib.rs", "")
+        .file(
+            "vendor/sub/bar-nested/Cargo.toml",
+            &basic_manifest("bar", "0.1.0"),
+        )
+        .file("vendor/sub/bar-nested/src/lib.rs", "")
+        .file(
+            "vendor/sub/baz-nested/Cargo.toml",
+            &basic_manifest("baz", "0.1.0"),
+        )
+        .file("vendor/sub/baz-nested/src/lib.rs", "")
+        .build();
+
+    // The root's patches win over the ones of the nested workspace, but the
+    // nested workspace can still patch other packages.
+    p.cargo("check")
+        .masquerade_as_nightly_cargo(&["nested-workspaces"])
+        .with_stderr_unordered(
+            "\
+[WARNING] profiles for the nested workspace will be ignored, specify profiles at the workspace root:
+nested workspace: [CWD]/vendor/sub/Cargo.toml
+workspace:        [CWD]/Cargo.toml
+[UPDATING] `dummy-registry` index
+[CHECKING] bar v0.1.0 ([CWD]/bar-outer)
+[CHECKING] baz v0.1.0 ([CWD]/vendor/sub/baz-nested)
+[CHECKING] sub-a v0.1.0 ([CWD]/vendor/sub/crates/sub-a)
+[CHECKING] sub-b v0.1.0 ([CWD]/vendor/sub/crates/sub-b)
+[CHECKING] app v0.1.0 ([CWD]/app)
+[FINISHED] [..]
+",
+        )
+        .run();
+    assert!(p.root().join("Cargo.lock").is_file());
+    assert!(!p.root().join("vendor/sub/Cargo.lock").exists());
+
+    // The nested workspace can still be used on its own.
+    p.cargo("check")
+        .cwd("vendor/sub")
+        .masquerade_as_nightly_cargo(&["nested-workspaces"])
+        .with_stderr_contains("[CHECKING] bar v0.1.0 ([CWD]/bar-nested)")
+        .run();
+}
+
+#[cargo_test]
+fn nested_workspace_must_be_a_root() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["nested-workspaces"]
+
+                [workspace]
+                members-from = ["sub"]
+            "#,
+        )
+        .file("sub/Cargo.toml", &basic_manifest("sub", "0.1.0"))
+        .file("sub/src/lib.rs", "")
+        .build();
+
+    p.cargo("check")
+        .masquerade_as_nightly_cargo(&["nested-workspaces"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to load members of the workspace `[CWD]/sub/Cargo.toml`
+
+Caused by:
+  `[CWD]/sub/Cargo.toml` is not the root of a workspace
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn nested_workspaces_requires_feature() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members-from = ["sub"]
+            "#,
+        )
+        .build();
+
+    p.cargo("check")
+        .masquerade_as_nightly_cargo(&["nested-workspaces"])
+        .with_status(101)
+        .with_stderr_contains("  feature `nested-workspaces` is required")
+        .run();
+}